@@ -31,6 +31,8 @@ use fyrox::{
         Animation, AnimationContainer,
     },
     core::{
+        algebra::Vector3,
+        curve::Curve,
         futures::executor::block_on,
         parking_lot::Mutex,
         pool::{ErasedHandle, Handle},
@@ -48,28 +50,29 @@ use fyrox::{
     resource::{
         curve::{CurveResource, CurveResourceState},
         model::{MaterialSearchOptions, Model, ModelResource},
+        physics_material::{PhysicsMaterial, PhysicsMaterialResource},
         texture::{
             CompressionOptions, MipFilter, TextureMagnificationFilter, TextureMinificationFilter,
             TextureResource, TextureWrapMode,
         },
     },
     scene::{
-        base::{Base, LevelOfDetail, LodGroup, Mobility, Property, PropertyValue},
+        base::{Base, HlodCluster, LevelOfDetail, LodGroup, Mobility, Property, PropertyValue},
         camera::{
             ColorGradingLut, Exposure, OrthographicProjection, PerspectiveProjection, Projection,
             SkyBox,
         },
         collider::{
-            BallShape, BitMask, CapsuleShape, ColliderShape, ConeShape, ConvexPolyhedronShape,
-            CuboidShape, CylinderShape, GeometrySource, HeightfieldShape, InteractionGroups,
-            SegmentShape, TriangleShape, TrimeshShape,
+            BallShape, BitMask, CapsuleShape, ColliderShape, CompoundShape, CompoundShapeChild,
+            ConeShape, ConvexPolyhedronShape, CuboidShape, CylinderShape, GeometrySource,
+            HeightfieldShape, InteractionGroups, SegmentShape, TriangleShape, TrimeshShape,
         },
         dim2,
         graph::physics::CoefficientCombineRule,
         joint::*,
         light::{
             directional::{CsmOptions, FrustumSplitOptions},
-            BaseLight,
+            BaseLight, LightUnits,
         },
         mesh::{
             surface::{BlendShape, Surface, SurfaceSharedData},
@@ -83,6 +86,7 @@ use fyrox::{
             },
             ParticleSystemRng,
         },
+        post_process_volume::{PostProcessOverrides, VolumeShape},
         rigidbody::RigidBodyType,
         sound::{
             self,
@@ -91,9 +95,10 @@ use fyrox::{
                 HighShelfFilterEffect, LowPassFilterEffect, LowShelfFilterEffect,
             },
             reverb::Reverb,
-            Attenuate, AudioBus, Biquad, DistanceModel, Effect, SoundBuffer, SoundBufferResource,
-            Status,
+            Attenuate, AudioBus, Biquad, DistanceModel, Ducking, Effect, SoundBuffer,
+            SoundBufferResource, Status,
         },
+        sprite::{NineSliceMargins, SpriteTiling, SpriteUvMode},
         terrain::{Chunk, Layer},
         transform::Transform,
     },
@@ -165,6 +170,9 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_vec_collection::<GeometrySource>();
     container.register_inheritable_inspectable::<GeometrySource>();
 
+    container.register_inheritable_vec_collection::<CompoundShapeChild>();
+    container.register_inheritable_inspectable::<CompoundShapeChild>();
+
     container.insert(make_status_enum_editor_definition());
 
     container.insert(EnumPropertyEditorDefinition::<LodGroup>::new_optional());
@@ -174,6 +182,10 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
 
     container.register_inheritable_inspectable::<LodGroup>();
 
+    container.insert(EnumPropertyEditorDefinition::<HlodCluster>::new_optional());
+    container.insert(InheritablePropertyEditorDefinition::<Option<HlodCluster>>::new());
+    container.register_inheritable_inspectable::<HlodCluster>();
+
     container
         .register_inheritable_inspectable::<fyrox::animation::spritesheet::SpriteSheetAnimation>();
     container
@@ -213,6 +225,18 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.insert(InheritablePropertyEditorDefinition::<Option<CurveResource>>::new());
     container.register_inheritable_vec_collection::<Option<CurveResource>>();
 
+    container.insert(
+        ResourceFieldPropertyEditorDefinition::<PhysicsMaterial>::new(
+            Rc::new(|resource_manager, path| {
+                block_on(resource_manager.request::<PhysicsMaterial, _>(path))
+            }),
+            sender.clone(),
+        ),
+    );
+    container.insert(InheritablePropertyEditorDefinition::<
+        Option<PhysicsMaterialResource>,
+    >::new());
+
     container.insert(ResourceFieldPropertyEditorDefinition::<Shader>::new(
         Rc::new(|resource_manager, path| block_on(resource_manager.request::<Shader, _>(path))),
         sender,
@@ -231,8 +255,14 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_inspectable::<FixedJoint>();
     container.register_inheritable_inspectable::<dim2::joint::FixedJoint>();
     container.register_inheritable_inspectable::<RevoluteJoint>();
+    container.register_inheritable_inspectable::<JointMotor>();
     container.register_inheritable_inspectable::<PrismaticJoint>();
     container.register_inheritable_inspectable::<dim2::joint::PrismaticJoint>();
+    container.register_inheritable_inspectable::<SpringJoint>();
+    container.register_inheritable_inspectable::<RopeJoint>();
+    container.register_inheritable_inspectable::<GenericJoint>();
+    container.register_inheritable_inspectable::<JointAxisParams>();
+    container.register_inheritable_enum::<JointAxisMotion, _>();
 
     container.register_inheritable_inspectable::<Base>();
     container.register_inheritable_inspectable::<BaseLight>();
@@ -253,6 +283,8 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
 
     container.register_inheritable_inspectable::<Biquad>();
     container.register_inheritable_inspectable::<AudioBus>();
+    container.insert(EnumPropertyEditorDefinition::<Ducking>::new_optional());
+    container.register_inheritable_inspectable::<Ducking>();
     container.register_inheritable_inspectable::<BaseEmitter>();
     container.register_inheritable_inspectable::<SphereEmitter>();
     container.register_inheritable_inspectable::<CylinderEmitter>();
@@ -261,6 +293,7 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_inspectable::<OrthographicProjection>();
     container.register_inheritable_inspectable::<Transform>();
     container.register_inheritable_inspectable::<CsmOptions>();
+    container.register_inheritable_inspectable::<PostProcessOverrides>();
 
     container.register_inheritable_inspectable::<Chunk>();
     container.register_inheritable_vec_collection::<Chunk>();
@@ -271,6 +304,10 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_option::<ColorGradingLut>();
     container.register_inheritable_option::<Biquad>();
     container.register_inheritable_option::<SkyBox>();
+    container.register_inheritable_option::<Vector3<f32>>();
+    container.register_inheritable_option::<LowPassFilterEffect>();
+    container.register_inheritable_option::<HighPassFilterEffect>();
+    container.register_inheritable_option::<Curve>();
 
     container.register_inheritable_inspectable::<SkyBox>();
 
@@ -286,11 +323,15 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_enum::<Mobility, _>();
     container.register_inheritable_enum::<RigidBodyType, _>();
     container.register_inheritable_enum::<Exposure, _>();
+    container.insert(EnumPropertyEditorDefinition::<Exposure>::new_optional());
+    container.register_inheritable_enum::<VolumeShape, _>();
     container.register_inheritable_enum::<FrustumSplitOptions, _>();
+    container.register_inheritable_enum::<LightUnits, _>();
     container.register_inheritable_enum::<MaterialSearchOptions, _>();
     container.register_inheritable_enum::<DistanceModel, _>();
     container.register_inheritable_enum::<sound::Renderer, _>();
     container.register_inheritable_enum::<RenderPath, _>();
+    container.register_inheritable_enum::<SpriteUvMode, _>();
 
     container.insert(ScriptPropertyEditorDefinition {});
     container.insert(BitFieldPropertyEditorDefinition::<BitMask>::new());
@@ -312,6 +353,9 @@ pub fn make_property_editors_container(sender: MessageSender) -> PropertyEditorD
     container.register_inheritable_inspectable::<HeightfieldShape>();
     container.register_inheritable_inspectable::<dim2::collider::HeightfieldShape>();
     container.register_inheritable_inspectable::<ConvexPolyhedronShape>();
+    container.register_inheritable_inspectable::<CompoundShape>();
+    container.register_inheritable_inspectable::<SpriteTiling>();
+    container.register_inheritable_inspectable::<NineSliceMargins>();
     container.insert(SpriteSheetFramesContainerEditorDefinition);
 
     container.insert(SurfaceDataPropertyEditorDefinition);
@@ -37,6 +37,7 @@ use fyrox::{
             ParticleSystemBuilder,
         },
         pivot::PivotBuilder,
+        post_process_volume::PostProcessVolumeBuilder,
         sound::{listener::ListenerBuilder, SoundBuilder},
         sprite::SpriteBuilder,
         terrain::{Layer, TerrainBuilder},
@@ -86,6 +87,7 @@ pub struct CreateEntityMenu {
     create_cylinder: Handle<UiNode>,
     create_quad: Handle<UiNode>,
     create_decal: Handle<UiNode>,
+    create_post_process_volume: Handle<UiNode>,
     create_point_light: Handle<UiNode>,
     create_spot_light: Handle<UiNode>,
     create_directional_light: Handle<UiNode>,
@@ -115,6 +117,7 @@ impl CreateEntityMenu {
         let create_camera;
         let create_sprite;
         let create_decal;
+        let create_post_process_volume;
         let create_navmesh;
         let create_particle_system;
         let create_terrain;
@@ -218,6 +221,10 @@ impl CreateEntityMenu {
                 create_navmesh = create_menu_item("Navmesh", vec![], ctx);
                 create_navmesh
             },
+            {
+                create_post_process_volume = create_menu_item("Post-Process Volume", vec![], ctx);
+                create_post_process_volume
+            },
         ];
 
         (
@@ -239,6 +246,7 @@ impl CreateEntityMenu {
                 create_listener,
                 create_navmesh,
                 create_decal,
+                create_post_process_volume,
                 physics_menu,
                 physics2d_menu,
                 dim2_menu,
@@ -379,6 +387,13 @@ impl CreateEntityMenu {
                         )
                     } else if message.destination() == self.create_decal {
                         Some(DecalBuilder::new(BaseBuilder::new().with_name("Decal")).build_node())
+                    } else if message.destination() == self.create_post_process_volume {
+                        Some(
+                            PostProcessVolumeBuilder::new(
+                                BaseBuilder::new().with_name("PostProcessVolume"),
+                            )
+                            .build_node(),
+                        )
                     } else if message.destination() == self.create_listener {
                         Some(
                             ListenerBuilder::new(BaseBuilder::new().with_name("Listener"))
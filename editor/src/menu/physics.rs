@@ -2,7 +2,15 @@ use crate::menu::create_menu_item;
 use fyrox::{
     core::pool::Handle,
     gui::{menu::MenuItemMessage, message::UiMessage, BuildContext, UiNode},
-    scene::{base::BaseBuilder, collider::*, joint::*, node::Node, rigidbody::RigidBodyBuilder},
+    scene::{
+        base::BaseBuilder,
+        collider::*,
+        joint::*,
+        node::Node,
+        rigidbody::RigidBodyBuilder,
+        vehicle::{VehicleBuilder, WheelBuilder},
+        water_volume::WaterVolumeBuilder,
+    },
 };
 
 pub struct PhysicsMenu {
@@ -13,6 +21,9 @@ pub struct PhysicsMenu {
     create_prismatic_joint: Handle<UiNode>,
     create_fixed_joint: Handle<UiNode>,
     create_collider: Handle<UiNode>,
+    create_vehicle: Handle<UiNode>,
+    create_wheel: Handle<UiNode>,
+    create_water_volume: Handle<UiNode>,
 }
 
 impl PhysicsMenu {
@@ -23,6 +34,9 @@ impl PhysicsMenu {
         let create_ball_joint;
         let create_prismatic_joint;
         let create_fixed_joint;
+        let create_vehicle;
+        let create_wheel;
+        let create_water_volume;
         let menu = create_menu_item(
             "Physics",
             vec![
@@ -50,6 +64,18 @@ impl PhysicsMenu {
                     create_fixed_joint = create_menu_item("Fixed Joint", vec![], ctx);
                     create_fixed_joint
                 },
+                {
+                    create_vehicle = create_menu_item("Vehicle", vec![], ctx);
+                    create_vehicle
+                },
+                {
+                    create_wheel = create_menu_item("Wheel", vec![], ctx);
+                    create_wheel
+                },
+                {
+                    create_water_volume = create_menu_item("Water Volume", vec![], ctx);
+                    create_water_volume
+                },
             ],
             ctx,
         );
@@ -62,6 +88,9 @@ impl PhysicsMenu {
             create_prismatic_joint,
             create_fixed_joint,
             create_collider,
+            create_vehicle,
+            create_wheel,
+            create_water_volume,
         }
     }
 
@@ -99,6 +128,15 @@ impl PhysicsMenu {
                         .with_shape(ColliderShape::Cuboid(Default::default()))
                         .build_node(),
                 )
+            } else if message.destination() == self.create_vehicle {
+                Some(VehicleBuilder::new(BaseBuilder::new().with_name("Vehicle")).build_node())
+            } else if message.destination() == self.create_wheel {
+                Some(WheelBuilder::new(BaseBuilder::new().with_name("Wheel")).build_node())
+            } else if message.destination() == self.create_water_volume {
+                Some(
+                    WaterVolumeBuilder::new(BaseBuilder::new().with_name("Water Volume"))
+                        .build_node(),
+                )
             } else {
                 None
             }
@@ -93,6 +93,7 @@ impl EditorScene {
                 // Update only editor's camera.
                 node_overrides: Some(Default::default()),
                 paused: false,
+                node_type_statistics: false,
             },
         }
     }
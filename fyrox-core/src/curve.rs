@@ -482,6 +482,41 @@ mod test {
         assert_eq!(key3.interpolate(&key4, 0.0), 20.0);
     }
 
+    #[test]
+    fn test_curve_cubic_easing() {
+        let mut curve = Curve::default();
+        curve.add_key(CurveKey::new(0.0, 0.0, CurveKeyKind::new_cubic(0.0, 0.0)));
+        curve.add_key(CurveKey::new(1.0, 1.0, CurveKeyKind::new_cubic(0.0, 0.0)));
+
+        // Flat tangents must give ease-in-ease-out motion.
+        assert_eq!(curve.value_at(0.5), 0.5);
+        assert!(curve.value_at(0.25) < 0.25);
+        assert!(curve.value_at(0.75) > 0.75);
+
+        // Tangents that match slope of the span must give linear motion.
+        let slope = std::f32::consts::FRAC_PI_4;
+        let mut curve = Curve::default();
+        curve.add_key(CurveKey::new(
+            0.0,
+            0.0,
+            CurveKeyKind::new_cubic(slope, slope),
+        ));
+        curve.add_key(CurveKey::new(
+            1.0,
+            1.0,
+            CurveKeyKind::new_cubic(slope, slope),
+        ));
+        assert!((curve.value_at(0.25) - 0.25).abs() < 0.0001);
+        assert!((curve.value_at(0.75) - 0.75).abs() < 0.0001);
+
+        // Step interpolation holds the value until the next key.
+        let mut curve = Curve::default();
+        curve.add_key(CurveKey::new(0.0, 0.0, CurveKeyKind::Constant));
+        curve.add_key(CurveKey::new(1.0, 1.0, CurveKeyKind::new_cubic(0.0, 0.0)));
+        assert_eq!(curve.value_at(0.99), 0.0);
+        assert_eq!(curve.value_at(1.0), 1.0);
+    }
+
     #[test]
     fn test_curve_from_vec() {
         let key = CurveKey::new(-1.0, -1.0, CurveKeyKind::Constant);
@@ -19,6 +19,10 @@
 //! load portions of data into its cache piece by piece, it will be free from any
 //! indirections that might cause cache invalidation. This is the so called cache
 //! friendliness.
+//!
+//! Records are stored in pages of fixed size, every page is a contiguous block. It allows the
+//! pool to grow to hundreds of thousands of records without re-allocating and copying all the
+//! existing records, and to return unused pages back to the allocator (see [`Pool::shrink_to_fit`]).
 
 #![allow(clippy::unneeded_field_pattern)]
 
@@ -41,6 +45,9 @@ use std::{
 
 const INVALID_GENERATION: u32 = 0;
 
+/// Maximum number of records in a single page of a pool.
+const PAGE_SIZE: usize = 1024;
+
 pub trait PayloadContainer: Sized {
     type Element: Sized;
 
@@ -92,8 +99,8 @@ impl<T> PayloadContainer for Option<T> {
 }
 
 /// Pool allows to create as many objects as you want in contiguous memory
-/// block. It allows to create and delete objects much faster than if they'll
-/// be allocated on heap. Also since objects stored in contiguous memory block
+/// blocks (pages). It allows to create and delete objects much faster than if they'll
+/// be allocated on heap. Also since objects stored in contiguous memory blocks
 /// they can be effectively accessed because such memory layout is cache-friendly.
 #[derive(Debug)]
 pub struct Pool<T, P = Option<T>>
@@ -101,8 +108,227 @@ where
     T: Sized,
     P: PayloadContainer<Element = T>,
 {
-    records: Vec<PoolRecord<T, P>>,
+    records: PagedRecords<T, P>,
     free_stack: Vec<u32>,
+    // Maximum generation of the records that were released by `shrink_to_fit`. New records start
+    // from the next generation, so handles to released records never become valid again.
+    generation_floor: u32,
+}
+
+/// Paged storage of pool records. Every page except the last one is full, and pages are never
+/// re-allocated once they are full, so growing the storage does not move existing records.
+struct PagedRecords<T, P = Option<T>>
+where
+    P: PayloadContainer<Element = T>,
+{
+    pages: Vec<Vec<PoolRecord<T, P>>>,
+    len: usize,
+}
+
+impl<T, P> PagedRecords<T, P>
+where
+    P: PayloadContainer<Element = T>,
+{
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            len: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn capacity(&self) -> usize {
+        // Pages are filled sequentially, so capacity of a page after the first non-full page is
+        // available only when it is reserved in full.
+        let mut capacity = 0;
+        for page in self.pages.iter() {
+            capacity += page.capacity().min(PAGE_SIZE);
+            if page.capacity() < PAGE_SIZE {
+                break;
+            }
+        }
+        capacity
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        let target = self.len + additional;
+        if target == 0 {
+            return;
+        }
+
+        for page_index in self.len / PAGE_SIZE..=(target - 1) / PAGE_SIZE {
+            if page_index == self.pages.len() {
+                self.pages.push(Vec::new());
+            }
+            let page = &mut self.pages[page_index];
+            let desired_capacity = (target - page_index * PAGE_SIZE).min(PAGE_SIZE);
+            if page.capacity() < desired_capacity {
+                page.reserve_exact(desired_capacity - page.len());
+            }
+        }
+    }
+
+    fn get(&self, index: usize) -> Option<&PoolRecord<T, P>> {
+        if index < self.len {
+            Some(&self.pages[index / PAGE_SIZE][index % PAGE_SIZE])
+        } else {
+            None
+        }
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut PoolRecord<T, P>> {
+        if index < self.len {
+            Some(&mut self.pages[index / PAGE_SIZE][index % PAGE_SIZE])
+        } else {
+            None
+        }
+    }
+
+    fn push(&mut self, record: PoolRecord<T, P>) {
+        let page_index = self.len / PAGE_SIZE;
+        if page_index == self.pages.len() {
+            self.pages.push(Vec::new());
+        }
+        let page = &mut self.pages[page_index];
+        if page.len() == page.capacity() {
+            // Grow exactly up to the page size, so the page is never re-allocated when it is full.
+            let desired_capacity = (page.capacity() * 2).clamp(4, PAGE_SIZE);
+            page.reserve_exact(desired_capacity - page.len());
+        }
+        page.push(record);
+        self.len += 1;
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let page_count = (len + PAGE_SIZE - 1) / PAGE_SIZE;
+        self.pages.truncate(page_count);
+        if let Some(last) = self.pages.last_mut() {
+            last.truncate(len - (page_count - 1) * PAGE_SIZE);
+            last.shrink_to_fit();
+        }
+        self.pages.shrink_to_fit();
+        self.len = len;
+    }
+
+    fn clear(&mut self) {
+        self.pages.clear();
+        self.len = 0;
+    }
+
+    fn iter(&self) -> std::iter::Flatten<std::slice::Iter<'_, Vec<PoolRecord<T, P>>>> {
+        self.pages.iter().flatten()
+    }
+
+    fn iter_mut(&mut self) -> std::iter::Flatten<std::slice::IterMut<'_, Vec<PoolRecord<T, P>>>> {
+        self.pages.iter_mut().flatten()
+    }
+
+    fn take_all(&mut self) -> impl Iterator<Item = PoolRecord<T, P>> {
+        self.len = 0;
+        std::mem::take(&mut self.pages).into_iter().flatten()
+    }
+
+    // Returns an index of a record, which payload is located at the given address.
+    fn index_of_payload(&self, ptr: *const T) -> Option<usize> {
+        let val = ptr as usize;
+        let record_size = std::mem::size_of::<PoolRecord<T, P>>();
+        for (page_index, page) in self.pages.iter().enumerate() {
+            let begin = page.as_ptr() as usize;
+            let end = begin + page.len() * record_size;
+            if val >= begin && val < end {
+                // The element could be stored at any offset inside the payload container (for
+                // example after the discriminant of `Option`), so compare exact addresses.
+                let index = (val - begin) / record_size;
+                return page[index]
+                    .payload
+                    .as_ref()
+                    .filter(|payload| std::ptr::eq(*payload, ptr))
+                    .map(|_| page_index * PAGE_SIZE + index);
+            }
+        }
+        None
+    }
+}
+
+impl<T, P> Index<usize> for PagedRecords<T, P>
+where
+    P: PayloadContainer<Element = T>,
+{
+    type Output = PoolRecord<T, P>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.get(index).expect("Record index out of bounds!")
+    }
+}
+
+impl<T, P> Debug for PagedRecords<T, P>
+where
+    T: Debug,
+    P: PayloadContainer<Element = T> + Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, P> PartialEq for PagedRecords<T, P>
+where
+    T: PartialEq,
+    P: PayloadContainer<Element = T> + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Clone> Clone for PagedRecords<T> {
+    fn clone(&self) -> Self {
+        Self {
+            pages: self.pages.clone(),
+            len: self.len,
+        }
+    }
+}
+
+// The same layout as `Vec<PoolRecord>` has, so the pages are invisible in serialized data.
+impl<T, P> Visit for PagedRecords<T, P>
+where
+    T: Visit + 'static,
+    P: PayloadContainer<Element = T> + Visit + 'static,
+{
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        let mut len = self.len as u32;
+        len.visit("Length", &mut region)?;
+
+        if region.is_reading() {
+            self.clear();
+            self.reserve(len as usize);
+            for index in 0..len {
+                let region_name = format!("Item{}", index);
+                let mut region = region.enter_region(region_name.as_str())?;
+                let mut record = PoolRecord::default();
+                record.visit("ItemData", &mut region)?;
+                self.push(record);
+            }
+        } else {
+            for (index, record) in self.iter_mut().enumerate() {
+                let region_name = format!("Item{}", index);
+                let mut region = region.enter_region(region_name.as_str())?;
+                record.visit("ItemData", &mut region)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<T, P> Reflect for Pool<T, P>
@@ -380,6 +606,10 @@ where
         let mut region = visitor.enter_region(name)?;
         self.records.visit("Records", &mut region)?;
         self.free_stack.visit("FreeStack", &mut region)?;
+        if region.is_reading() {
+            self.generation_floor = INVALID_GENERATION;
+        }
+        let _ = self.generation_floor.visit("GenerationFloor", &mut region); // Backward compatibility.
         Ok(())
     }
 }
@@ -480,6 +710,7 @@ impl<T: Clone> Clone for Pool<T> {
         Self {
             records: self.records.clone(),
             free_stack: self.free_stack.clone(),
+            generation_floor: self.generation_floor,
         }
     }
 }
@@ -491,20 +722,39 @@ where
     #[inline]
     pub fn new() -> Self {
         Pool {
-            records: Vec::new(),
+            records: PagedRecords::new(),
             free_stack: Vec::new(),
+            generation_floor: INVALID_GENERATION,
         }
     }
 
     #[inline]
     pub fn with_capacity(capacity: u32) -> Self {
         let capacity = usize::try_from(capacity).expect("capacity overflowed usize");
+        let mut records = PagedRecords::new();
+        records.reserve(capacity);
         Pool {
-            records: Vec::with_capacity(capacity),
+            records,
             free_stack: Vec::new(),
+            generation_floor: INVALID_GENERATION,
         }
     }
 
+    /// Reserves capacity for at least `additional` more objects to be spawned without re-allocation
+    /// of the internal storage. Free records are not taken into account, because the objects will be
+    /// put in them first.
+    #[inline]
+    pub fn reserve(&mut self, additional: u32) {
+        let additional = usize::try_from(additional).expect("additional overflowed usize");
+        self.records.reserve(additional);
+    }
+
+    /// Returns the amount of records the pool can hold without re-allocation of the internal storage.
+    #[inline]
+    pub fn allocated_capacity(&self) -> u32 {
+        u32::try_from(self.records.capacity()).unwrap_or(u32::MAX)
+    }
+
     fn records_len(&self) -> u32 {
         u32::try_from(self.records.len()).expect("Number of records overflowed u32")
     }
@@ -597,14 +847,14 @@ where
                 // Spawn missing records to fill gaps.
                 for i in self.records_len()..index {
                     self.records.push(PoolRecord {
-                        generation: 1,
+                        generation: self.generation_floor + 1,
                         payload: P::new_empty(),
                     });
                     self.free_stack.push(i);
                 }
 
                 let generation = if desired_generation == INVALID_GENERATION {
-                    1
+                    self.generation_floor + 1
                 } else {
                     desired_generation
                 };
@@ -650,7 +900,7 @@ where
             handle
         } else {
             // No free records, create new one
-            let generation = 1;
+            let generation = self.generation_floor + 1;
 
             let handle = Handle {
                 index: self.records.len() as u32,
@@ -705,7 +955,7 @@ where
             handle
         } else {
             // No free records, create new one
-            let generation = 1;
+            let generation = self.generation_floor + 1;
 
             let handle = Handle {
                 index: self.records.len() as u32,
@@ -1181,6 +1431,33 @@ where
         u32::try_from(cnt).expect("alive_count overflowed u32")
     }
 
+    /// Returns the number of free records in the pool, that will be re-used by next spawned objects.
+    ///
+    /// This method is `O(1)`.
+    #[inline]
+    pub fn free_count(&self) -> u32 {
+        u32::try_from(self.free_stack.len()).expect("free stack length overflowed u32")
+    }
+
+    /// Returns the number of records that have been reserved (e.g. by [`take_reserve`]) and not put
+    /// back yet.
+    ///
+    /// It iterates through the entire pool so the complexity is `O(n)`.
+    ///
+    /// [`take_reserve`]: Pool::take_reserve
+    #[inline]
+    pub fn reserved_count(&self) -> u32 {
+        self.total_count() - self.alive_count()
+    }
+
+    /// Returns current generation of a record at the given index, or [`None`] if the index is out of
+    /// bounds. The generation is kept even if the record is free, the next object spawned in the record
+    /// will have an incremented generation.
+    #[inline]
+    pub fn generation_at(&self, index: u32) -> Option<u32> {
+        self.records_get(index).map(|record| record.generation)
+    }
+
     /// Returns the number of allocated objects in the pool.
     ///
     /// It also counts records that have been reserved (e.g. by [`take_reserve`]).
@@ -1246,12 +1523,8 @@ where
     /// ```
     #[must_use]
     pub fn iter(&self) -> PoolIterator<T, P> {
-        unsafe {
-            PoolIterator {
-                ptr: self.records.as_ptr(),
-                end: self.records.as_ptr().add(self.records.len()),
-                marker: PhantomData,
-            }
+        PoolIterator {
+            records: self.records.iter(),
         }
     }
 
@@ -1281,12 +1554,8 @@ where
     /// ```
     #[must_use]
     pub fn iter_mut(&mut self) -> PoolIteratorMut<T, P> {
-        unsafe {
-            PoolIteratorMut {
-                ptr: self.records.as_mut_ptr(),
-                end: self.records.as_mut_ptr().add(self.records.len()),
-                marker: PhantomData,
-            }
+        PoolIteratorMut {
+            records: self.records.iter_mut(),
         }
     }
 
@@ -1294,13 +1563,9 @@ where
     /// Can be useful when there is a need to iterate over pool records and know a handle of
     /// that record.
     pub fn pair_iter_mut(&mut self) -> PoolPairIteratorMut<T, P> {
-        unsafe {
-            PoolPairIteratorMut {
-                current: 0,
-                ptr: self.records.as_mut_ptr(),
-                end: self.records.as_mut_ptr().add(self.records.len()),
-                marker: PhantomData,
-            }
+        PoolPairIteratorMut {
+            current: 0,
+            records: self.records.iter_mut(),
         }
     }
 
@@ -1337,31 +1602,39 @@ where
     /// Removes all elements from the pool.
     pub fn drain(&mut self) -> impl Iterator<Item = T> + '_ {
         self.free_stack.clear();
-        self.records.drain(..).filter_map(|mut r| r.payload.take())
+        self.records.take_all().filter_map(|mut r| r.payload.take())
     }
 
-    fn end(&self) -> *const PoolRecord<T, P> {
-        unsafe { self.records.as_ptr().add(self.records.len()) }
+    pub fn handle_of(&self, ptr: &T) -> Handle<T> {
+        if let Some(index) = self.records.index_of_payload(ptr) {
+            let index = u32::try_from(index).expect("Index overflowed u32");
+            return self.handle_from_index(index);
+        }
+        Handle::NONE
     }
 
-    fn begin(&self) -> *const PoolRecord<T, P> {
-        self.records.as_ptr()
-    }
+    /// Releases trailing free records of the pool and returns memory of unused pages back to the
+    /// allocator. It could be useful after removing a lot of objects from the pool, for example
+    /// after unloading a huge part of a scene. Handles to the released records stay invalid, even
+    /// if new objects are spawned at the same indices later on.
+    pub fn shrink_to_fit(&mut self) {
+        let mut free = self.free_stack.clone();
+        free.sort_unstable();
 
-    pub fn handle_of(&self, ptr: &T) -> Handle<T> {
-        let begin = self.begin() as usize;
-        let end = self.end() as usize;
-        let val = ptr as *const T as usize;
-        if val >= begin && val < end {
-            let record_size = std::mem::size_of::<PoolRecord<T>>();
-            let record_location = (val - offset_of!(PoolRecord<T>, payload)) - begin;
-            if record_location % record_size == 0 {
-                let index = record_location / record_size;
-                let index = u32::try_from(index).expect("Index overflowed u32");
-                return self.handle_from_index(index);
-            }
+        // Reserved records are not in the free stack, so they're never released.
+        let mut new_len = self.records.len();
+        while new_len > 0 && free.last() == Some(&((new_len - 1) as u32)) {
+            free.pop();
+            new_len -= 1;
         }
-        Handle::NONE
+
+        for index in new_len..self.records.len() {
+            let generation = self.records[index].generation;
+            self.generation_floor = self.generation_floor.max(generation);
+        }
+
+        self.records.truncate(new_len);
+        self.free_stack.retain(|i| (*i as usize) < new_len);
     }
 }
 
@@ -1433,9 +1706,7 @@ pub struct PoolIterator<'a, T, P>
 where
     P: PayloadContainer<Element = T>,
 {
-    ptr: *const PoolRecord<T, P>,
-    end: *const PoolRecord<T, P>,
-    marker: PhantomData<&'a T>,
+    records: std::iter::Flatten<std::slice::Iter<'a, Vec<PoolRecord<T, P>>>>,
 }
 
 impl<'a, T, P> Iterator for PoolIterator<'a, T, P>
@@ -1445,18 +1716,7 @@ where
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        unsafe {
-            while self.ptr != self.end {
-                let current = &*self.ptr;
-                if let Some(payload) = current.payload.as_ref() {
-                    self.ptr = self.ptr.offset(1);
-                    return Some(payload);
-                }
-                self.ptr = self.ptr.offset(1);
-            }
-
-            None
-        }
+        self.records.find_map(|record| record.payload.as_ref())
     }
 }
 
@@ -1492,9 +1752,7 @@ pub struct PoolIteratorMut<'a, T, P>
 where
     P: PayloadContainer<Element = T>,
 {
-    ptr: *mut PoolRecord<T, P>,
-    end: *mut PoolRecord<T, P>,
-    marker: PhantomData<&'a mut T>,
+    records: std::iter::Flatten<std::slice::IterMut<'a, Vec<PoolRecord<T, P>>>>,
 }
 
 impl<'a, T, P> Iterator for PoolIteratorMut<'a, T, P>
@@ -1504,18 +1762,7 @@ where
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        unsafe {
-            while self.ptr != self.end {
-                let current = &mut *self.ptr;
-                if let Some(payload) = current.payload.as_mut() {
-                    self.ptr = self.ptr.offset(1);
-                    return Some(payload);
-                }
-                self.ptr = self.ptr.offset(1);
-            }
-
-            None
-        }
+        self.records.find_map(|record| record.payload.as_mut())
     }
 }
 
@@ -1523,9 +1770,7 @@ pub struct PoolPairIteratorMut<'a, T, P>
 where
     P: PayloadContainer<Element = T>,
 {
-    ptr: *mut PoolRecord<T, P>,
-    end: *mut PoolRecord<T, P>,
-    marker: PhantomData<&'a mut T>,
+    records: std::iter::Flatten<std::slice::IterMut<'a, Vec<PoolRecord<T, P>>>>,
     current: usize,
 }
 
@@ -1536,21 +1781,15 @@ where
     type Item = (Handle<T>, &'a mut T);
 
     fn next(&mut self) -> Option<Self::Item> {
-        unsafe {
-            while self.ptr != self.end {
-                let current = &mut *self.ptr;
-                if let Some(payload) = current.payload.as_mut() {
-                    let handle = Handle::new(self.current as u32, current.generation);
-                    self.ptr = self.ptr.offset(1);
-                    self.current += 1;
-                    return Some((handle, payload));
-                }
-                self.ptr = self.ptr.offset(1);
-                self.current += 1;
+        for record in self.records.by_ref() {
+            let index = self.current;
+            self.current += 1;
+            if let Some(payload) = record.payload.as_mut() {
+                return Some((Handle::new(index as u32, record.generation), payload));
             }
-
-            None
         }
+
+        None
     }
 }
 
@@ -1621,7 +1860,7 @@ where
 #[cfg(test)]
 mod test {
     use crate::{
-        pool::{ErasedHandle, Handle, Pool, PoolRecord, Ticket, INVALID_GENERATION},
+        pool::{ErasedHandle, Handle, Pool, PoolRecord, Ticket, INVALID_GENERATION, PAGE_SIZE},
         visitor::{Visit, Visitor},
     };
 
@@ -1732,6 +1971,46 @@ mod test {
         assert_eq!(pool.free_stack.len(), 1);
     }
 
+    #[test]
+    fn test_reserve() {
+        let mut pool = Pool::<Payload>::with_capacity(4);
+        assert!(pool.allocated_capacity() >= 4);
+        assert_eq!(pool.get_capacity(), 0);
+
+        pool.reserve(16);
+        assert!(pool.allocated_capacity() >= 16);
+
+        let handles = (0..16).map(|_| pool.spawn(Payload)).collect::<Vec<_>>();
+        for (i, handle) in handles.iter().enumerate() {
+            assert_eq!(handle.index(), i as u32);
+        }
+    }
+
+    #[test]
+    fn test_occupancy() {
+        let mut pool = Pool::<Payload>::new();
+        let a = pool.spawn(Payload);
+        let b = pool.spawn(Payload);
+        let c = pool.spawn(Payload);
+        assert_eq!(pool.free_count(), 0);
+        assert_eq!(pool.reserved_count(), 0);
+
+        pool.free(a);
+        assert_eq!(pool.free_count(), 1);
+        assert_eq!(pool.generation_at(a.index()), Some(a.generation()));
+
+        let (ticket, payload) = pool.take_reserve(b);
+        assert_eq!(pool.reserved_count(), 1);
+        assert_eq!(pool.alive_count(), 1);
+        pool.put_back(ticket, payload);
+        assert_eq!(pool.reserved_count(), 0);
+
+        let d = pool.spawn(Payload);
+        assert_eq!(d.index(), a.index());
+        assert_eq!(pool.generation_at(d.index()), Some(a.generation() + 1));
+        assert_eq!(pool.generation_at(c.index() + 1), None);
+    }
+
     #[test]
     fn test_multi_borrow_context() {
         let mut pool = Pool::<Payload>::new();
@@ -1926,7 +2205,8 @@ mod test {
     #[test]
     fn pool_with_capacity() {
         let p = Pool::<u32>::with_capacity(1);
-        assert_eq!(p.records, Vec::with_capacity(1));
+        assert_eq!(p.records.len(), 0);
+        assert!(p.allocated_capacity() >= 1);
         assert_eq!(p.free_stack, Vec::new())
     }
 
@@ -2071,11 +2351,11 @@ mod test {
         let mut pool = Pool::<u32>::new();
         let _ = pool.spawn(42);
 
-        assert!(!pool.records.is_empty());
+        assert_ne!(pool.records.len(), 0);
 
         pool.clear();
 
-        assert!(pool.records.is_empty());
+        assert_eq!(pool.records.len(), 0);
         assert!(pool.free_stack.is_empty());
     }
 
@@ -2151,10 +2431,11 @@ mod test {
         let mut pool = Pool::<u32>::new();
         let _ = pool.spawn(42);
 
-        let iter = pool.pair_iter_mut();
+        let mut iter = pool.pair_iter_mut();
 
         assert_eq!(iter.current, 0);
-        assert_eq!(iter.ptr, pool.records.as_mut_ptr());
+        assert_eq!(iter.next(), Some((Handle::new(0, 1), &mut 42)));
+        assert_eq!(iter.current, 1);
     }
 
     #[test]
@@ -2178,4 +2459,67 @@ mod test {
         assert_eq!(pool[a], 15);
         assert_eq!(pool[b], 5);
     }
+
+    #[test]
+    fn test_paged_growth_and_shrink() {
+        let mut pool = Pool::<u32>::new();
+        let first = pool.spawn(0);
+        let mut handles = (1..PAGE_SIZE as u32)
+            .map(|i| pool.spawn(i))
+            .collect::<Vec<_>>();
+
+        // The first page is full, growing the pool further must not move its records.
+        let first_address = pool.borrow(first) as *const u32;
+        handles.extend((PAGE_SIZE as u32..3 * PAGE_SIZE as u32).map(|i| pool.spawn(i)));
+
+        assert_eq!(pool.borrow(first) as *const u32, first_address);
+        assert_eq!(pool.handle_of(pool.borrow(first)), first);
+        let last = *handles.last().unwrap();
+        assert_eq!(pool.handle_of(pool.borrow(last)), last);
+        assert_eq!(pool.iter().count(), 3 * PAGE_SIZE);
+
+        for handle in handles.iter().skip(PAGE_SIZE / 2) {
+            pool.free(*handle);
+        }
+        pool.shrink_to_fit();
+
+        assert_eq!(pool.get_capacity() as usize, PAGE_SIZE / 2 + 1);
+        assert_eq!(pool.free_stack.len(), 0);
+        assert_eq!(pool.records.pages.len(), 1);
+
+        // Handles to the released records must not become valid again.
+        let new = pool.spawn(123);
+        assert_eq!(new.index() as usize, PAGE_SIZE / 2 + 1);
+        assert_eq!(new.generation(), 2);
+        for handle in handles.iter().skip(PAGE_SIZE / 2) {
+            assert!(!pool.is_valid_handle(*handle));
+        }
+        assert!(pool.is_valid_handle(new));
+        assert_eq!(pool.alive_count() as usize, PAGE_SIZE / 2 + 2);
+    }
+
+    #[test]
+    fn test_generation_floor_save_load() {
+        let mut pool = Pool::<u32>::new();
+        let a = pool.spawn(1);
+        let b = pool.spawn(2);
+        pool.free(b);
+        pool.shrink_to_fit();
+
+        let mut visitor = Visitor::new();
+        pool.visit("Pool", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut loaded = Pool::<u32>::new();
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        loaded.visit("Pool", &mut visitor).unwrap();
+
+        // A record at the released index must not revive the handle that was invalidated before
+        // saving.
+        let c = loaded.spawn(3);
+        assert_eq!(c.index(), b.index());
+        assert!(!loaded.is_valid_handle(b));
+        assert!(loaded.is_valid_handle(a));
+        assert!(loaded.is_valid_handle(c));
+    }
 }
@@ -633,3 +633,94 @@ pub fn collect_used_resources(
         }
     })
 }
+
+/// Replaces every reference to the `old` resource in a given entity with the `new` resource. Internally, it
+/// uses reflection to iterate over each field of every descendant sub-object of the entity (the same way as
+/// [`collect_used_resources`] does). Inheritable variables, that contain replaced resources, are marked as
+/// modified, so the new value won't be overwritten by property inheritance and will be synchronized with
+/// respective native objects (if any). Returns the number of replaced references.
+///
+/// ## Important notes
+///
+/// The function does not check whether the resources have the same type, it is up to the caller to ensure
+/// that the type UUIDs match. Otherwise, the entity will end up with a resource of a wrong type, which
+/// will lead to a panic on attempt to access its data.
+pub fn replace_used_resources(
+    entity: &mut dyn Reflect,
+    old: &UntypedResource,
+    new: &UntypedResource,
+) -> usize {
+    let mut count = 0;
+    let mut finished = false;
+
+    entity.downcast_mut::<UntypedResource>(&mut |v| {
+        if let Some(resource) = v {
+            if resource == old {
+                *resource = new.clone();
+                count += 1;
+            }
+            finished = true;
+        }
+    });
+
+    if finished {
+        return count;
+    }
+
+    entity.as_array_mut(&mut |array| {
+        if let Some(array) = array {
+            for i in 0..array.reflect_len() {
+                if let Some(item) = array.reflect_index_mut(i) {
+                    count += replace_used_resources(item, old, new);
+                }
+            }
+
+            finished = true;
+        }
+    });
+
+    if finished {
+        return count;
+    }
+
+    entity.as_inheritable_variable_mut(&mut |inheritable| {
+        if let Some(inheritable) = inheritable {
+            let replaced = replace_used_resources(inheritable.inner_value_mut(), old, new);
+            if replaced > 0 {
+                inheritable.mark_modified();
+            }
+            count += replaced;
+
+            finished = true;
+        }
+    });
+
+    if finished {
+        return count;
+    }
+
+    entity.as_hash_map_mut(&mut |hash_map| {
+        if let Some(hash_map) = hash_map {
+            // Keys are immutable, so only values could be replaced.
+            for i in 0..hash_map.reflect_len() {
+                if let Some(value) = hash_map.reflect_get_nth_value_mut(i) {
+                    count += replace_used_resources(value, old, new);
+                }
+            }
+
+            finished = true;
+        }
+    });
+
+    if finished {
+        return count;
+    }
+
+    entity.fields_mut(&mut |fields| {
+        for field in fields {
+            count += replace_used_resources(field, old, new);
+        }
+    });
+
+    count
+}
@@ -1,8 +1,12 @@
 //! Everything related to audio buses and audio bus graphs. See docs of [`AudioBus`] and [`AudioBusGraph`]
 //! for more info and examples
 
-use crate::effects::{Effect, EffectRenderTrait};
+use crate::{
+    context::SAMPLE_RATE,
+    effects::{Effect, EffectRenderTrait},
+};
 use fyrox_core::{
+    math::lerpf,
     pool::{Handle, Pool, Ticket},
     reflect::prelude::*,
     visitor::prelude::*,
@@ -76,6 +80,115 @@ impl PingPongBuffer {
     }
 }
 
+/// Side-chain ducking settings of an audio bus. Ducking automatically attenuates a bus (for example -
+/// music) while another bus (for example - voice) has signal. It is a common technique to make dialogues
+/// clear without manual volume scripting. See [`AudioBus::set_ducking`].
+#[derive(Debug, Reflect, Visit, Clone, PartialEq)]
+pub struct Ducking {
+    /// Name of an audio bus, whose signal triggers the ducking. The bus is found by its name, the same
+    /// way as sound sources find their output buses.
+    pub side_chain_bus: String,
+    /// Gain of the ducked bus while the side-chain bus has signal. For example, `0.25` means that the
+    /// bus will be four times quieter.
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub gain: f32,
+    /// Peak amplitude of the side-chain signal, above which the side-chain bus is considered active.
+    #[reflect(min_value = 0.0)]
+    pub threshold: f32,
+    /// Time (in seconds) that is needed to attenuate the bus when the side-chain signal appears.
+    #[reflect(min_value = 0.0)]
+    pub attack: f32,
+    /// Time (in seconds) that is needed to restore the gain of the bus when the side-chain signal
+    /// disappears.
+    #[reflect(min_value = 0.0)]
+    pub release: f32,
+}
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self {
+            side_chain_bus: Default::default(),
+            gain: 0.25,
+            threshold: 0.01,
+            attack: 0.05,
+            release: 0.5,
+        }
+    }
+}
+
+impl Ducking {
+    /// Creates new ducking settings with the given side-chain bus name and default parameters.
+    pub fn new<S: AsRef<str>>(side_chain_bus: S) -> Self {
+        Self {
+            side_chain_bus: side_chain_bus.as_ref().to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+// Calculates a per-sample coefficient of an exponential envelope with the given time constant.
+fn envelope_coefficient(time: f32) -> f32 {
+    if time <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-1.0 / (time * SAMPLE_RATE as f32)).exp()
+    }
+}
+
+/// A state of a single audio bus in an [`AudioSnapshot`].
+#[derive(Debug, Reflect, Visit, Clone, Default, PartialEq)]
+pub struct BusSnapshot {
+    /// Name of an audio bus.
+    pub bus: String,
+    /// Gain of the audio bus.
+    pub gain: f32,
+    /// Effects of the audio bus. Parameters of the effects are matched with the effects of the bus by
+    /// their indices, effects of different kinds are left untouched.
+    pub effects: Vec<Effect>,
+}
+
+/// Audio snapshot is a named state of the whole mix (gains and effect parameters of a set of audio buses).
+/// Snapshots allow gameplay code to reconfigure the mix atomically, for example to make everything
+/// muffled when the player is under water or to quiet down the world while a menu is open. See
+/// [`AudioBusGraph::transition_to_snapshot`].
+#[derive(Debug, Reflect, Visit, Clone, Default, PartialEq)]
+pub struct AudioSnapshot {
+    /// Name of the snapshot.
+    pub name: String,
+    /// States of audio buses. Buses that are not listed here are not affected by the snapshot.
+    pub buses: Vec<BusSnapshot>,
+}
+
+impl AudioSnapshot {
+    /// Creates new snapshot with current state of every audio bus of the given graph. It is the
+    /// easiest way of making snapshots - configure the mix as desired, capture it and then
+    /// restore the default mix.
+    pub fn capture<S: AsRef<str>>(name: S, graph: &AudioBusGraph) -> Self {
+        Self {
+            name: name.as_ref().to_owned(),
+            buses: graph
+                .buses
+                .iter()
+                .map(|bus| BusSnapshot {
+                    bus: bus.name.clone(),
+                    gain: bus.gain,
+                    effects: bus.effects.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SnapshotTransition {
+    // Index of the target snapshot.
+    snapshot: usize,
+    // States of the buses at the beginning of the transition.
+    initial_states: Vec<(Handle<AudioBus>, BusSnapshot)>,
+    elapsed: f32,
+    duration: f32,
+}
+
 /// Audio bus is a top-level audio processing unit. It takes data from multiple audio sources and passes their
 /// samples through a chain of effects. Output signal is then can be either sent to an audio playback device or
 /// to some other audio bus and be processed again, but with different sound effects (this can be done via
@@ -92,6 +205,13 @@ pub struct AudioBus {
     #[reflect(hidden)]
     parent_bus: Handle<AudioBus>,
 
+    #[visit(optional)]
+    ducking: Option<Ducking>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    ducking_gain: f32,
+
     #[reflect(hidden)]
     #[visit(skip)]
     ping_pong_buffer: PingPongBuffer,
@@ -104,6 +224,8 @@ impl Default for AudioBus {
             child_buses: Default::default(),
             effects: Default::default(),
             gain: 1.0,
+            ducking: None,
+            ducking_gain: 1.0,
             ping_pong_buffer: Default::default(),
             parent_bus: Default::default(),
         }
@@ -152,6 +274,61 @@ impl AudioBus {
         self.gain
     }
 
+    /// Sets new side-chain ducking settings of the audio bus. `None` disables the ducking.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fyrox_sound::bus::{AudioBus, AudioBusGraph, Ducking};
+    ///
+    /// let mut graph = AudioBusGraph::new();
+    /// let primary_bus = graph.primary_bus_handle();
+    ///
+    /// graph.add_bus(AudioBus::new("Voice".to_owned()), primary_bus);
+    ///
+    /// // Music will be attenuated to 30% of its volume while someone talks.
+    /// let mut music = AudioBus::new("Music".to_owned());
+    /// music.set_ducking(Some(Ducking {
+    ///     gain: 0.3,
+    ///     ..Ducking::new("Voice")
+    /// }));
+    /// graph.add_bus(music, primary_bus);
+    /// ```
+    pub fn set_ducking(&mut self, ducking: Option<Ducking>) -> Option<Ducking> {
+        std::mem::replace(&mut self.ducking, ducking)
+    }
+
+    /// Returns current side-chain ducking settings of the audio bus.
+    pub fn ducking(&self) -> Option<&Ducking> {
+        self.ducking.as_ref()
+    }
+
+    /// Returns current side-chain ducking settings of the audio bus.
+    pub fn ducking_mut(&mut self) -> Option<&mut Ducking> {
+        self.ducking.as_mut()
+    }
+
+    /// Returns current gain, that is applied to the audio bus by the ducking. It is `1.0` when the
+    /// bus is not attenuated.
+    pub fn ducking_gain(&self) -> f32 {
+        self.ducking_gain
+    }
+
+    fn apply_ducking(&mut self, side_chain_active: bool) {
+        let (target, time) = match self.ducking.as_ref() {
+            Some(ducking) if side_chain_active => (ducking.gain, ducking.attack),
+            Some(ducking) => (1.0, ducking.release),
+            None => (1.0, 0.0),
+        };
+        let coefficient = envelope_coefficient(time);
+
+        for (left, right) in self.ping_pong_buffer.input_mut() {
+            self.ducking_gain += (target - self.ducking_gain) * coefficient;
+            *left *= self.ducking_gain;
+            *right *= self.ducking_gain;
+        }
+    }
+
     pub(crate) fn input_buffer(&mut self) -> &mut [(f32, f32)] {
         self.ping_pong_buffer.input_mut()
     }
@@ -287,6 +464,14 @@ impl AudioBus {
 pub struct AudioBusGraph {
     buses: Pool<AudioBus>,
     root: Handle<AudioBus>,
+    #[visit(optional)]
+    snapshots: Vec<AudioSnapshot>,
+    #[visit(optional)]
+    #[reflect(hidden)]
+    active_snapshot: Option<String>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    transition: Option<SnapshotTransition>,
 }
 
 impl AudioBusGraph {
@@ -299,7 +484,13 @@ impl AudioBusGraph {
         let root = AudioBus::new(Self::PRIMARY_BUS.to_string());
         let mut buses = Pool::new();
         let root = buses.spawn(root);
-        Self { buses, root }
+        Self {
+            buses,
+            root,
+            snapshots: Default::default(),
+            active_snapshot: None,
+            transition: None,
+        }
     }
 
     /// Adds a new audio bus to the graph and attaches it to the given parent. `parent` handle must be
@@ -453,48 +644,238 @@ impl AudioBusGraph {
         self.buses.pair_iter_mut()
     }
 
+    /// Adds new snapshot to the graph. A snapshot with the same name will be replaced.
+    pub fn add_snapshot(&mut self, snapshot: AudioSnapshot) {
+        if let Some(existing) = self
+            .snapshots
+            .iter_mut()
+            .find(|existing| existing.name == snapshot.name)
+        {
+            *existing = snapshot;
+        } else {
+            self.snapshots.push(snapshot);
+        }
+    }
+
+    /// Removes a snapshot with the given name and returns it.
+    pub fn remove_snapshot<S: AsRef<str>>(&mut self, name: S) -> Option<AudioSnapshot> {
+        let index = self.snapshot_index(name.as_ref())?;
+        self.transition = None;
+        Some(self.snapshots.remove(index))
+    }
+
+    /// Returns a reference to a snapshot with the given name.
+    pub fn snapshot<S: AsRef<str>>(&self, name: S) -> Option<&AudioSnapshot> {
+        self.snapshot_index(name.as_ref())
+            .map(|index| &self.snapshots[index])
+    }
+
+    /// Returns a slice with every snapshot of the graph.
+    pub fn snapshots(&self) -> &[AudioSnapshot] {
+        &self.snapshots
+    }
+
+    fn snapshot_index(&self, name: &str) -> Option<usize> {
+        self.snapshots
+            .iter()
+            .position(|snapshot| snapshot.name == name)
+    }
+
+    /// Starts a smooth transition of the mix to a snapshot with the given name. Gains and effect
+    /// parameters of every bus of the snapshot are interpolated over the given duration (in seconds).
+    /// The transition of every bus is done in the same audio frame, so the mix changes atomically.
+    /// A transition that is in progress is interrupted and the new one starts from the current state
+    /// of the mix. Returns `false` if there is no such snapshot.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use fyrox_sound::{
+    ///     bus::{AudioBus, AudioBusGraph, AudioSnapshot},
+    ///     effects::{filter::LowPassFilterEffect, Effect},
+    /// };
+    ///
+    /// let mut graph = AudioBusGraph::new();
+    /// let primary_bus = graph.primary_bus_handle();
+    /// let mut sfx = AudioBus::new("SFX".to_owned());
+    /// sfx.add_effect(Effect::LowPassFilter(LowPassFilterEffect::default()));
+    /// let sfx = graph.add_bus(sfx, primary_bus);
+    ///
+    /// // Configure the mix for underwater, capture it and restore the default mix.
+    /// let default = AudioSnapshot::capture("default", &graph);
+    /// if let Some(Effect::LowPassFilter(filter)) =
+    ///     graph.try_get_bus_mut(sfx).unwrap().effect_mut(0)
+    /// {
+    ///     filter.set_cutoff_frequency_hz(500.0);
+    /// }
+    /// graph.add_snapshot(AudioSnapshot::capture("underwater", &graph));
+    /// graph.add_snapshot(default);
+    /// graph.transition_to_snapshot("default", 0.0);
+    ///
+    /// // Later, when the player dives.
+    /// graph.transition_to_snapshot("underwater", 0.5);
+    /// ```
+    pub fn transition_to_snapshot<S: AsRef<str>>(&mut self, name: S, duration: f32) -> bool {
+        let snapshot = match self.snapshot_index(name.as_ref()) {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+
+        let initial_states = self.snapshots[snapshot]
+            .buses
+            .iter()
+            .filter_map(|bus_snapshot| {
+                self.buses
+                    .pair_iter()
+                    .find(|(_, bus)| bus.name == bus_snapshot.bus)
+                    .map(|(handle, bus)| {
+                        (
+                            handle,
+                            BusSnapshot {
+                                bus: bus.name.clone(),
+                                gain: bus.gain,
+                                effects: bus.effects.clone(),
+                            },
+                        )
+                    })
+            })
+            .collect();
+
+        self.transition = Some(SnapshotTransition {
+            snapshot,
+            initial_states,
+            elapsed: 0.0,
+            duration: duration.max(0.0),
+        });
+
+        true
+    }
+
+    /// Returns the name of the snapshot, that was applied by the last finished transition.
+    pub fn active_snapshot(&self) -> Option<&str> {
+        self.active_snapshot.as_deref()
+    }
+
+    /// Returns `true` if there is a transition between snapshots in progress.
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    fn update_snapshot_transition(&mut self, dt: f32) {
+        let transition = match self.transition.as_mut() {
+            Some(transition) => transition,
+            None => return,
+        };
+
+        transition.elapsed += dt;
+        let t = if transition.duration > 0.0 {
+            (transition.elapsed / transition.duration).min(1.0)
+        } else {
+            1.0
+        };
+
+        let snapshot = &self.snapshots[transition.snapshot];
+        for (handle, initial_state) in transition.initial_states.iter() {
+            let target_state = match snapshot
+                .buses
+                .iter()
+                .find(|bus_snapshot| bus_snapshot.bus == initial_state.bus)
+            {
+                Some(target_state) => target_state,
+                None => continue,
+            };
+
+            let bus = match self.buses.try_borrow_mut(*handle) {
+                Some(bus) => bus,
+                None => continue,
+            };
+
+            bus.gain = lerpf(initial_state.gain, target_state.gain, t);
+            for ((effect, from), to) in bus
+                .effects
+                .iter_mut()
+                .zip(initial_state.effects.iter())
+                .zip(target_state.effects.iter())
+            {
+                effect.interpolate(from, to, t);
+            }
+        }
+
+        if t >= 1.0 {
+            self.active_snapshot = Some(snapshot.name.clone());
+            self.transition = None;
+        }
+    }
+
     pub(crate) fn begin_render(&mut self, output_device_buffer_size: usize) {
+        self.update_snapshot_transition(output_device_buffer_size as f32 / SAMPLE_RATE as f32);
+
         for bus in self.buses.iter_mut() {
             bus.begin_render(output_device_buffer_size);
         }
     }
 
     pub(crate) fn end_render(&mut self, output_device_buffer: &mut [(f32, f32)]) {
-        let mut leafs = Vec::new();
-        for (handle, bus) in self.buses.pair_iter_mut() {
+        for bus in self.buses.iter_mut() {
             bus.apply_effects();
+        }
 
-            if bus.child_buses.is_empty() {
-                leafs.push(handle);
+        // Side-chain ducking. Levels of side-chain buses must be collected first, because ducking
+        // modifies the signal.
+        let ducked_buses =
+            self.buses
+                .pair_iter()
+                .filter_map(|(handle, bus)| {
+                    let ducking = bus.ducking.as_ref()?;
+                    let side_chain_active =
+                        self.buses
+                            .iter()
+                            .find(|side_chain| side_chain.name == ducking.side_chain_bus)
+                            .map_or(false, |side_chain| {
+                                side_chain.ping_pong_buffer.input_ref().iter().any(
+                                    |(left, right)| left.abs().max(right.abs()) > ducking.threshold,
+                                )
+                            });
+                    Some((handle, side_chain_active))
+                })
+                .collect::<Vec<_>>();
+        for (handle, side_chain_active) in ducked_buses {
+            self.buses[handle].apply_ducking(side_chain_active);
+        }
+
+        // Every bus must be mixed into its parent exactly once and after all its descendants, so the
+        // buses are processed in reversed depth-first order.
+        let mut order = Vec::new();
+        let mut stack = vec![self.root];
+        while let Some(handle) = stack.pop() {
+            if let Some(bus) = self.buses.try_borrow(handle) {
+                stack.extend_from_slice(&bus.child_buses);
+                order.push(handle);
             }
         }
 
-        for mut leaf in leafs {
-            while leaf.is_some() {
-                let mut ctx = self.buses.begin_multi_borrow::<2>();
-
-                let leaf_ref = ctx.try_get(leaf).expect("Malformed bus graph!");
-
-                let input_buffer = leaf_ref.ping_pong_buffer.input_ref();
-                let leaf_gain = leaf_ref.gain;
-                let output_buffer = if leaf_ref.parent_bus.is_none() {
-                    // Special case for the root bus - it writes directly to the output device buffer.
-                    &mut *output_device_buffer
-                } else {
-                    ctx.try_get(leaf_ref.parent_bus)
-                        .expect("Malformed bus graph!")
-                        .ping_pong_buffer
-                        .input_mut()
-                };
-
-                for ((input_left, input_right), (output_left, output_right)) in
-                    input_buffer.iter().zip(output_buffer)
-                {
-                    *output_left += *input_left * leaf_gain;
-                    *output_right += *input_right * leaf_gain;
-                }
-
-                leaf = leaf_ref.parent_bus;
+        for handle in order.into_iter().rev() {
+            let mut ctx = self.buses.begin_multi_borrow::<2>();
+
+            let bus_ref = ctx.try_get(handle).expect("Malformed bus graph!");
+
+            let input_buffer = bus_ref.ping_pong_buffer.input_ref();
+            let bus_gain = bus_ref.gain;
+            let output_buffer = if bus_ref.parent_bus.is_none() {
+                // Special case for the root bus - it writes directly to the output device buffer.
+                &mut *output_device_buffer
+            } else {
+                ctx.try_get(bus_ref.parent_bus)
+                    .expect("Malformed bus graph!")
+                    .ping_pong_buffer
+                    .input_mut()
+            };
+
+            for ((input_left, input_right), (output_left, output_right)) in
+                input_buffer.iter().zip(output_buffer)
+            {
+                *output_left += *input_left * bus_gain;
+                *output_right += *input_right * bus_gain;
             }
         }
     }
@@ -503,7 +884,8 @@ impl AudioBusGraph {
 #[cfg(test)]
 mod test {
     use crate::{
-        bus::{AudioBus, AudioBusGraph},
+        bus::{AudioBus, AudioBusGraph, AudioSnapshot, BusSnapshot, Ducking},
+        context::SAMPLE_RATE,
         effects::{Attenuate, Effect},
     };
 
@@ -534,6 +916,31 @@ mod test {
         assert_eq!(output_buffer[0], (2.0, 2.0));
     }
 
+    #[test]
+    fn test_sibling_buses_data_flow() {
+        let mut output_buffer = [(0.0f32, 0.0f32)];
+
+        let mut graph = AudioBusGraph::new();
+
+        let group = graph.add_bus(AudioBus::new("Group".to_string()), graph.root);
+        let bus1 = graph.add_bus(AudioBus::new("Bus1".to_string()), group);
+        let bus2 = graph.add_bus(AudioBus::new("Bus2".to_string()), group);
+
+        graph.begin_render(output_buffer.len());
+
+        for bus in [bus1, bus2] {
+            for (left, right) in graph.buses[bus].input_buffer() {
+                *left = 1.0;
+                *right = 1.0;
+            }
+        }
+
+        graph.end_render(&mut output_buffer);
+
+        // Signal of each bus must be mixed in exactly once.
+        assert_eq!(output_buffer[0], (2.0, 2.0));
+    }
+
     #[test]
     fn test_primary_bus_data_flow() {
         let mut output_buffer = [(0.0f32, 0.0f32)];
@@ -586,4 +993,90 @@ mod test {
 
         assert_eq!(output_buffer[0], (0.75, 0.75));
     }
+
+    #[test]
+    fn test_side_chain_ducking() {
+        let mut output_buffer = [(0.0f32, 0.0f32)];
+
+        let mut graph = AudioBusGraph::new();
+
+        let voice = graph.add_bus(AudioBus::new("Voice".to_string()), graph.root);
+
+        let mut music = AudioBus::new("Music".to_string());
+        music.set_ducking(Some(Ducking {
+            gain: 0.25,
+            attack: 0.0,
+            release: 0.0,
+            ..Ducking::new("Voice")
+        }));
+        let music = graph.add_bus(music, graph.root);
+
+        let mut render = |graph: &mut AudioBusGraph, voice_level: f32| {
+            graph.begin_render(output_buffer.len());
+
+            for (left, right) in graph.buses[voice].input_buffer() {
+                *left = voice_level;
+                *right = voice_level;
+            }
+
+            for (left, right) in graph.buses[music].input_buffer() {
+                *left = 1.0;
+                *right = 1.0;
+            }
+
+            graph.end_render(&mut output_buffer);
+
+            output_buffer[0]
+        };
+
+        // Music must be attenuated while the voice bus has signal.
+        assert_eq!(render(&mut graph, 1.0), (1.25, 1.25));
+        assert_eq!(graph.buses[music].ducking_gain(), 0.25);
+
+        // And restored when the voice bus is silent.
+        assert_eq!(render(&mut graph, 0.0), (1.0, 1.0));
+        assert_eq!(graph.buses[music].ducking_gain(), 1.0);
+    }
+
+    #[test]
+    fn test_snapshot_transition() {
+        let mut graph = AudioBusGraph::new();
+
+        let mut music = AudioBus::new("Music".to_string());
+        music.add_effect(Effect::Attenuate(Attenuate::new(1.0)));
+        let music = graph.add_bus(music, graph.root);
+
+        graph.add_snapshot(AudioSnapshot {
+            name: "Menu".to_string(),
+            buses: vec![BusSnapshot {
+                bus: "Music".to_string(),
+                gain: 0.0,
+                effects: vec![Effect::Attenuate(Attenuate::new(0.5))],
+            }],
+        });
+
+        assert!(!graph.transition_to_snapshot("Unknown", 0.2));
+        assert!(graph.transition_to_snapshot("Menu", 0.2));
+
+        // Each render block is 0.1 seconds long.
+        let block = SAMPLE_RATE as usize / 10;
+
+        graph.begin_render(block);
+        assert!((graph.buses[music].gain() - 0.5).abs() < 0.001);
+        assert_eq!(
+            graph.buses[music].effect(0),
+            Some(&Effect::Attenuate(Attenuate::new(0.75)))
+        );
+        assert!(graph.is_transitioning());
+        assert_eq!(graph.active_snapshot(), None);
+
+        graph.begin_render(block);
+        assert_eq!(graph.buses[music].gain(), 0.0);
+        assert_eq!(
+            graph.buses[music].effect(0),
+            Some(&Effect::Attenuate(Attenuate::new(0.5)))
+        );
+        assert!(!graph.is_transitioning());
+        assert_eq!(graph.active_snapshot(), Some("Menu"));
+    }
 }
@@ -12,17 +12,22 @@
 
 use crate::bus::AudioBusGraph;
 use crate::{
+    error::SoundError,
     listener::Listener,
     pool::Ticket,
     renderer::{render_source_default, Renderer},
     source::{SoundSource, Status},
 };
 use fyrox_core::{
+    log::Log,
+    math::lerpf,
     pool::{Handle, Pool},
     reflect::prelude::*,
     visitor::prelude::*,
 };
 use std::{
+    cmp::Ordering,
+    collections::HashMap,
     sync::{Arc, Mutex, MutexGuard},
     time::Duration,
 };
@@ -107,6 +112,22 @@ pub struct State {
     bus_graph: AudioBusGraph,
     distance_model: DistanceModel,
     paused: bool,
+    #[reflect(min_value = 0.0, step = 0.05)]
+    doppler_scale: f32,
+    #[reflect(min_value = 0.0, step = 1.0)]
+    speed_of_sound: f32,
+    max_voices: Option<u32>,
+    // Amount of rendered time (in seconds).
+    #[reflect(hidden)]
+    time: f64,
+    // Last start times of sources, grouped by their buffers. It is used to prevent rapid retriggering
+    // of the same sound.
+    #[reflect(hidden)]
+    last_start_times: HashMap<usize, f64>,
+    // Temporary buffer for sources with a send (see `SoundSource::set_send_bus`). Such sources are
+    // rendered here first and then mixed into both of their buses.
+    #[reflect(hidden)]
+    send_buffer: Vec<(f32, f32)>,
 }
 
 impl State {
@@ -153,6 +174,44 @@ impl State {
         self.distance_model
     }
 
+    /// Sets new scale of Doppler effect. The effect changes pitch of spatial sound sources depending
+    /// on their velocity relative to the listener (see [`SoundSource::set_velocity`] and
+    /// [`Listener::set_velocity`]). Values greater than 1.0 exaggerate the effect, zero disables it.
+    /// Default is 1.0.
+    pub fn set_doppler_scale(&mut self, scale: f32) {
+        self.doppler_scale = scale.max(0.0);
+    }
+
+    /// Returns current scale of Doppler effect.
+    pub fn doppler_scale(&self) -> f32 {
+        self.doppler_scale
+    }
+
+    /// Sets speed of sound (in units per second) that is used to calculate Doppler effect. Default
+    /// is 343.3, which is speed of sound in the air in meters per second.
+    pub fn set_speed_of_sound(&mut self, speed: f32) {
+        self.speed_of_sound = speed.max(0.0);
+    }
+
+    /// Returns speed of sound (in units per second).
+    pub fn speed_of_sound(&self) -> f32 {
+        self.speed_of_sound
+    }
+
+    /// Sets maximum amount of simultaneously playing sound sources. When the limit is exceeded, the
+    /// least important sources are stopped: the sources with lower priority (see
+    /// [`SoundSource::set_priority`]) go first, sources with the same priority are stopped from the
+    /// quietest one. It is useful to limit CPU load, when a lot of sounds is played at once (rapid
+    /// fire weapons, etc.). `None` means no limit (default).
+    pub fn set_max_voices(&mut self, max_voices: Option<u32>) {
+        self.max_voices = max_voices;
+    }
+
+    /// Returns maximum amount of simultaneously playing sound sources.
+    pub fn max_voices(&self) -> Option<u32> {
+        self.max_voices
+    }
+
     /// Normalizes given frequency using context's sampling rate. Normalized frequency then can be used
     /// to create filters.
     pub fn normalize_frequency(&self, f: f32) -> f32 {
@@ -239,6 +298,119 @@ impl State {
         &mut self.bus_graph
     }
 
+    /// Stops every sound source with the given tag. Returns the last error, that has occurred while stopping
+    /// the sources (if any).
+    pub fn stop_all_with_tag(&mut self, tag: &str) -> Result<(), SoundError> {
+        let mut result = Ok(());
+        for source in self.sources.iter_mut().filter(|s| s.tag() == tag) {
+            if let Err(err) = source.stop() {
+                result = Err(err);
+            }
+        }
+        result
+    }
+
+    /// Pauses every playing sound source with the given tag.
+    pub fn pause_all_with_tag(&mut self, tag: &str) {
+        for source in self
+            .sources
+            .iter_mut()
+            .filter(|s| s.tag() == tag && s.status() == Status::Playing)
+        {
+            source.pause();
+        }
+    }
+
+    /// Resumes every paused sound source with the given tag.
+    pub fn resume_all_with_tag(&mut self, tag: &str) {
+        for source in self
+            .sources
+            .iter_mut()
+            .filter(|s| s.tag() == tag && s.status() == Status::Paused)
+        {
+            source.play();
+        }
+    }
+
+    /// Sets new gain for every sound source with the given tag.
+    pub fn set_gain_for_tag(&mut self, tag: &str, gain: f32) {
+        for source in self.sources.iter_mut().filter(|s| s.tag() == tag) {
+            source.set_gain(gain);
+        }
+    }
+
+    // Stops the least important playing sources, if there are more of them than voice limits allow
+    // (see `set_max_voices` and `SoundSource::set_max_buffer_voices`). Sources with lower priority
+    // are stopped first, sources with the same priority are stopped from the quietest one.
+    fn limit_voices(&mut self) {
+        struct Voice {
+            handle: Handle<SoundSource>,
+            buffer: Option<usize>,
+            priority: i32,
+            loudness: f32,
+        }
+
+        let mut buffer_limits = HashMap::<usize, u32>::new();
+        let mut voices = Vec::new();
+        for (handle, source) in self
+            .sources
+            .pair_iter()
+            .filter(|(_, s)| s.status() == Status::Playing)
+        {
+            let buffer = source.buffer().map(|b| b.key());
+            if let (Some(buffer), Some(limit)) = (buffer, source.max_buffer_voices()) {
+                let buffer_limit = buffer_limits.entry(buffer).or_insert(limit);
+                *buffer_limit = (*buffer_limit).min(limit);
+            }
+            let distance_gain = lerpf(
+                1.0,
+                source.calculate_distance_gain(&self.listener, self.distance_model),
+                source.spatial_blend(),
+            );
+            voices.push(Voice {
+                handle,
+                buffer,
+                priority: source.priority(),
+                loudness: source.gain() * distance_gain,
+            });
+        }
+
+        if self.max_voices.is_none() && buffer_limits.is_empty() {
+            return;
+        }
+
+        // The most important voices go first.
+        voices.sort_by(|a, b| {
+            b.priority.cmp(&a.priority).then_with(|| {
+                b.loudness
+                    .partial_cmp(&a.loudness)
+                    .unwrap_or(Ordering::Equal)
+            })
+        });
+
+        let mut buffer_voices = HashMap::<usize, u32>::new();
+        let mut voice_count = 0;
+        for voice in voices {
+            let within_buffer_limit = match voice
+                .buffer
+                .and_then(|buffer| buffer_limits.get(&buffer).map(|limit| (buffer, *limit)))
+            {
+                Some((buffer, limit)) => {
+                    let count = buffer_voices.entry(buffer).or_default();
+                    *count += 1;
+                    *count <= limit
+                }
+                None => true,
+            };
+
+            if within_buffer_limit && self.max_voices.map_or(true, |max| voice_count < max) {
+                voice_count += 1;
+            } else {
+                Log::verify(self.sources[voice.handle].stop());
+            }
+        }
+    }
+
     pub(crate) fn render(&mut self, output_device_buffer: &mut [(f32, f32)]) {
         let last_time = fyrox_core::instant::Instant::now();
 
@@ -248,6 +420,32 @@ impl State {
                 !done
             });
 
+            let time = self.time;
+
+            // Process sources, that were started since the last render pass.
+            for source in self
+                .sources
+                .iter_mut()
+                .filter(|s| s.status() == Status::Playing && s.pending_start)
+            {
+                source.pending_start = false;
+                if let Some(buffer) = source.buffer() {
+                    let key = buffer.key();
+                    let interval = source.min_retrigger_interval() as f64;
+                    let is_retrigger = self
+                        .last_start_times
+                        .get(&key)
+                        .map_or(false, |last| time - last < interval);
+                    if is_retrigger {
+                        Log::verify(source.stop());
+                        continue;
+                    }
+                    self.last_start_times.insert(key, time);
+                }
+            }
+
+            self.limit_voices();
+
             self.bus_graph.begin_render(output_device_buffer.len());
 
             // Render sounds to respective audio buses.
@@ -258,37 +456,80 @@ impl State {
             {
                 if let Some(bus_input_buffer) = self.bus_graph.try_get_bus_input_buffer(&source.bus)
                 {
+                    source.doppler_factor = source.calculate_doppler_factor(
+                        &self.listener,
+                        self.doppler_scale,
+                        self.speed_of_sound,
+                    );
+
                     source.render(output_device_buffer.len());
 
-                    match self.renderer {
-                        Renderer::Default => {
-                            // Simple rendering path. Much faster (4-5 times) than HRTF path.
-                            render_source_default(
-                                source,
-                                &self.listener,
-                                self.distance_model,
-                                bus_input_buffer,
-                            );
-                        }
-                        Renderer::HrtfRenderer(ref mut hrtf_renderer) => {
-                            hrtf_renderer.render_source(
-                                source,
-                                &self.listener,
-                                self.distance_model,
-                                bus_input_buffer,
-                            );
+                    if source.has_send() {
+                        self.send_buffer.clear();
+                        self.send_buffer
+                            .resize(output_device_buffer.len(), (0.0, 0.0));
+
+                        render_source(
+                            &mut self.renderer,
+                            source,
+                            &self.listener,
+                            self.distance_model,
+                            &mut self.send_buffer,
+                        );
+
+                        mix(bus_input_buffer, &self.send_buffer, 1.0);
+
+                        if let Some(send_input_buffer) =
+                            self.bus_graph.try_get_bus_input_buffer(source.send_bus())
+                        {
+                            mix(send_input_buffer, &self.send_buffer, source.send_level());
                         }
+                    } else {
+                        render_source(
+                            &mut self.renderer,
+                            source,
+                            &self.listener,
+                            self.distance_model,
+                            bus_input_buffer,
+                        );
                     }
                 }
             }
 
             self.bus_graph.end_render(output_device_buffer);
+
+            self.time += output_device_buffer.len() as f64 / SAMPLE_RATE as f64;
         }
 
         self.render_duration = fyrox_core::instant::Instant::now() - last_time;
     }
 }
 
+fn render_source(
+    renderer: &mut Renderer,
+    source: &mut SoundSource,
+    listener: &Listener,
+    distance_model: DistanceModel,
+    mix_buffer: &mut [(f32, f32)],
+) {
+    match renderer {
+        Renderer::Default => {
+            // Simple rendering path. Much faster (4-5 times) than HRTF path.
+            render_source_default(source, listener, distance_model, mix_buffer);
+        }
+        Renderer::HrtfRenderer(hrtf_renderer) => {
+            hrtf_renderer.render_source(source, listener, distance_model, mix_buffer);
+        }
+    }
+}
+
+fn mix(mix_buffer: &mut [(f32, f32)], samples: &[(f32, f32)], gain: f32) {
+    for ((out_left, out_right), (left, right)) in mix_buffer.iter_mut().zip(samples) {
+        *out_left += gain * left;
+        *out_right += gain * right;
+    }
+}
+
 impl SoundContext {
     /// TODO: This is magic constant that gives 1024 + 1 number when summed with
     ///       HRTF length for faster FFT calculations. Find a better way of selecting this.
@@ -312,6 +553,12 @@ impl SoundContext {
                 bus_graph: AudioBusGraph::new(),
                 distance_model: DistanceModel::InverseDistance,
                 paused: false,
+                doppler_scale: 1.0,
+                speed_of_sound: 343.3,
+                max_voices: None,
+                time: 0.0,
+                last_start_times: Default::default(),
+                send_buffer: Default::default(),
             }))),
         }
     }
@@ -365,7 +612,459 @@ impl Visit for State {
         self.renderer.visit("Renderer", &mut region)?;
         self.paused.visit("Paused", &mut region)?;
         self.distance_model.visit("DistanceModel", &mut region)?;
+        let _ = self.doppler_scale.visit("DopplerScale", &mut region); // Backward compatibility.
+        let _ = self.speed_of_sound.visit("SpeedOfSound", &mut region); // Backward compatibility.
+        let _ = self.max_voices.visit("MaxVoices", &mut region); // Backward compatibility.
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        buffer::{
+            DataSource, RawStreamingDataSource, SoundBufferResource, SoundBufferResourceExtension,
+        },
+        bus::AudioBusGraph,
+        context::{DistanceModel, SoundContext, SAMPLE_RATE},
+        effects::filter::{HighPassFilterEffect, LowPassFilterEffect},
+        source::{SoundSource, SoundSourceBuilder, Status},
+    };
+    use fyrox_core::{
+        algebra::Vector3,
+        curve::{Curve, CurveKey, CurveKeyKind},
+    };
+    use std::time::Duration;
+
+    fn make_buffer() -> SoundBufferResource {
+        SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples: vec![1.0; SAMPLE_RATE as usize],
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_start_delay() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let source = state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(make_buffer())
+                .with_spatial_blend_factor(0.0)
+                .with_start_delay(0.5)
+                .with_status(Status::Playing)
+                .build()
+                .unwrap(),
+        );
+
+        let mut output = vec![(0.0, 0.0); 512];
+        state.render(&mut output);
+        assert!(state
+            .source(source)
+            .frame_samples
+            .iter()
+            .all(|s| *s == (0.0, 0.0)));
+        assert_eq!(state.source(source).playback_time().as_secs_f32(), 0.0);
+
+        // Half of a second later the source must become audible.
+        for _ in 0..(SAMPLE_RATE as usize / 1024) {
+            state.render(&mut output);
+        }
+        assert!(state.source(source).playback_time().as_secs_f32() > 0.0);
+        assert!(state
+            .source(source)
+            .frame_samples
+            .iter()
+            .any(|s| *s != (0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_min_retrigger_interval() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let buffer = make_buffer();
+        let mut add_source = || {
+            state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(buffer.clone())
+                    .with_min_retrigger_interval(0.1)
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let first = add_source();
+        let duplicate = add_source();
+
+        let mut output = vec![(0.0, 0.0); 512];
+        state.render(&mut output);
+
+        assert_eq!(state.source(first).status(), Status::Playing);
+        assert_eq!(state.source(duplicate).status(), Status::Stopped);
+
+        // The interval has passed, the sound could be started again.
+        for _ in 0..(SAMPLE_RATE as usize / 5120) {
+            state.render(&mut output);
+        }
+        state.source_mut(duplicate).play();
+        state.render(&mut output);
+        assert_eq!(state.source(duplicate).status(), Status::Playing);
+    }
+
+    #[test]
+    fn test_doppler_effect() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let buffer = make_buffer();
+        let mut add_source = |velocity| {
+            state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(buffer.clone())
+                    .with_position(Vector3::new(0.0, 0.0, 10.0))
+                    .with_velocity(velocity)
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let fixed = add_source(Vector3::default());
+        let approaching = add_source(Vector3::new(0.0, 0.0, -34.33));
+        let receding = add_source(Vector3::new(0.0, 0.0, 34.33));
+
+        let mut output = vec![(0.0, 0.0); 512];
+        state.render(&mut output);
+
+        let time = |state: &crate::context::State, source| {
+            state.source(source).playback_time().as_secs_f32()
+        };
+        assert!(time(&state, approaching) > time(&state, fixed));
+        assert!(time(&state, receding) < time(&state, fixed));
+
+        // Zero scale disables the effect.
+        state.set_doppler_scale(0.0);
+        let fixed_time = time(&state, fixed);
+        let approaching_time = time(&state, approaching);
+        state.render(&mut output);
+        assert!(
+            ((time(&state, approaching) - approaching_time) - (time(&state, fixed) - fixed_time))
+                .abs()
+                < 1.0e-5
+        );
+    }
+
+    #[test]
+    fn test_send() {
+        let render = |send_level| {
+            let context = SoundContext::new();
+            let mut state = context.state();
+            state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(make_buffer())
+                    .with_spatial_blend_factor(0.0)
+                    .with_send_bus(AudioBusGraph::PRIMARY_BUS)
+                    .with_send_level(send_level)
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            );
+            let mut output = vec![(0.0, 0.0); 512];
+            state.render(&mut output);
+            output
+        };
+
+        let dry = render(0.0);
+        let with_send = render(0.5);
+        assert!(dry.iter().any(|s| *s != (0.0, 0.0)));
+        for (dry, with_send) in dry.iter().zip(with_send.iter()) {
+            assert!((dry.0 * 1.5 - with_send.0).abs() < 1.0e-5);
+            assert!((dry.1 * 1.5 - with_send.1).abs() < 1.0e-5);
+        }
+    }
+
+    #[test]
+    fn test_source_filters() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let mut add_source = |low_pass, high_pass| {
+            state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(make_buffer())
+                    .with_spatial_blend_factor(0.0)
+                    .with_low_pass_filter(low_pass)
+                    .with_high_pass_filter(high_pass)
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let mut filter = LowPassFilterEffect::default();
+        filter.set_cutoff_frequency_hz(1000.0);
+        let low_pass = add_source(Some(filter), None);
+        let mut filter = HighPassFilterEffect::default();
+        filter.set_cutoff_frequency_hz(1000.0);
+        let high_pass = add_source(None, Some(filter));
+
+        let mut output = vec![(0.0, 0.0); 512];
+        state.render(&mut output);
+
+        // The buffer contains constant signal, which is passed through low-pass filter and removed
+        // by high-pass filter.
+        let last = |source| *state.source(source).frame_samples.last().unwrap();
+        let (left, right) = last(low_pass);
+        assert!((left - 1.0).abs() < 0.01 && (right - 1.0).abs() < 0.01);
+        let (left, right) = last(high_pass);
+        assert!(left.abs() < 0.01 && right.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_set_playback_time() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let source = state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(make_buffer())
+                .build()
+                .unwrap(),
+        );
+
+        let source = state.source_mut(source);
+        source.set_playback_time(Duration::from_secs_f32(0.5));
+        assert!((source.playback_time().as_secs_f32() - 0.5).abs() < 1.0e-5);
+
+        // The time is clamped to the duration of the buffer.
+        source.set_playback_time(Duration::from_secs(2));
+        assert!(source.playback_time() < Duration::from_secs(1));
+    }
+
+    // Produces sequence of sample indices, so the samples could be checked easily.
+    #[derive(Debug)]
+    struct IndexSource {
+        index: usize,
+    }
+
+    impl Iterator for IndexSource {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let sample = self.index as f32;
+            self.index += 1;
+            Some(sample)
+        }
+    }
+
+    impl RawStreamingDataSource for IndexSource {
+        fn sample_rate(&self) -> usize {
+            SAMPLE_RATE as usize
+        }
+
+        fn channel_count(&self) -> usize {
+            1
+        }
+
+        fn time_seek(&mut self, duration: Duration) {
+            self.index = (duration.as_secs_f64() * SAMPLE_RATE as f64) as usize;
+        }
+
+        fn channel_duration_in_samples(&self) -> usize {
+            10 * SAMPLE_RATE as usize
+        }
+    }
+
+    #[test]
+    fn test_set_playback_time_streaming() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let buffer =
+            SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(IndexSource {
+                index: 0,
+            })))
+            .unwrap();
+        let source = state.add_source(
+            SoundSourceBuilder::new()
+                .with_buffer(buffer)
+                .with_spatial_blend_factor(0.0)
+                .with_status(Status::Playing)
+                .build()
+                .unwrap(),
+        );
+
+        state
+            .source_mut(source)
+            .set_playback_time(Duration::from_secs_f32(1.5));
+
+        let mut output = vec![(0.0, 0.0); 512];
+        state.render(&mut output);
+
+        let expected = 1.5 * SAMPLE_RATE as f32;
+        assert_eq!(state.source(source).frame_samples[0], (expected, expected));
+        assert_eq!(
+            state.source(source).frame_samples[511],
+            (expected + 511.0, expected + 511.0)
+        );
+    }
+
+    #[test]
+    fn test_loop_region() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let generic = SoundBufferResource::new_generic(DataSource::Raw {
+            sample_rate: SAMPLE_RATE as usize,
+            channel_count: 1,
+            samples: (0..SAMPLE_RATE).map(|i| i as f32).collect(),
+        })
+        .unwrap();
+        let streaming =
+            SoundBufferResource::new_streaming(DataSource::RawStreaming(Box::new(IndexSource {
+                index: 0,
+            })))
+            .unwrap();
+
+        let mut sources = Vec::new();
+        for buffer in [generic, streaming] {
+            sources.push(
+                state.add_source(
+                    SoundSourceBuilder::new()
+                        .with_buffer(buffer)
+                        .with_spatial_blend_factor(0.0)
+                        .with_looping(true)
+                        .with_loop_start(100)
+                        .with_loop_end(Some(200))
+                        .with_status(Status::Playing)
+                        .build()
+                        .unwrap(),
+                ),
+            );
+        }
+
+        let mut output = vec![(0.0, 0.0); 512];
+        state.render(&mut output);
+
+        for source in sources {
+            let samples = &state.source(source).frame_samples;
+            // The intro is played once, then the loop region is repeated.
+            assert_eq!(samples[199], (199.0, 199.0));
+            assert_eq!(samples[200], (100.0, 100.0));
+            assert_eq!(samples[299], (199.0, 199.0));
+            assert_eq!(samples[300], (100.0, 100.0));
+            assert_eq!(samples[511], (111.0, 111.0));
+        }
+    }
+
+    #[test]
+    fn test_distance_attenuation() {
+        let context = SoundContext::new();
+        let state = context.state();
+
+        let mut source = SoundSourceBuilder::new()
+            .with_position(Vector3::new(5.0, 0.0, 0.0))
+            .with_radius(1.0)
+            .with_max_distance(10.0)
+            .build()
+            .unwrap();
+        let gain = |source: &SoundSource| {
+            source.calculate_distance_gain(state.listener(), DistanceModel::InverseDistance)
+        };
+        assert!((gain(&source) - 0.2).abs() < 1.0e-5);
+
+        // Custom curve overrides distance model.
+        source.set_distance_curve(Some(Curve::from(vec![
+            CurveKey::new(0.0, 1.0, CurveKeyKind::Linear),
+            CurveKey::new(10.0, 0.0, CurveKeyKind::Linear),
+        ])));
+        assert!((gain(&source) - 0.5).abs() < 1.0e-5);
+
+        // Without cutoff the gain stays at its value at max distance.
+        source.set_distance_curve(None);
+        source.set_max_distance(3.0);
+        assert!((gain(&source) - 1.0 / 3.0).abs() < 1.0e-5);
+        source.set_max_distance_cutoff(true);
+        assert_eq!(gain(&source), 0.0);
+    }
+
+    #[test]
+    fn test_voice_limits() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let buffer = make_buffer();
+        let mut add_source = |priority: i32, gain: f32| {
+            state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(buffer.clone())
+                    .with_spatial_blend_factor(0.0)
+                    .with_priority(priority)
+                    .with_gain(gain)
+                    .with_max_buffer_voices(Some(2))
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let quiet = add_source(0, 0.5);
+        let loud = add_source(0, 1.0);
+        let important = add_source(1, 0.1);
+
+        let mut output = vec![(0.0, 0.0); 512];
+        state.render(&mut output);
+
+        // The quietest voice of the lowest priority is stolen.
+        assert_eq!(state.source(quiet).status(), Status::Stopped);
+        assert_eq!(state.source(loud).status(), Status::Playing);
+        assert_eq!(state.source(important).status(), Status::Playing);
+
+        state.set_max_voices(Some(1));
+        state.render(&mut output);
+
+        assert_eq!(state.source(loud).status(), Status::Stopped);
+        assert_eq!(state.source(important).status(), Status::Playing);
+    }
+
+    #[test]
+    fn test_tag_control() {
+        let context = SoundContext::new();
+        let mut state = context.state();
+
+        let buffer = make_buffer();
+        let mut add_source = |tag: &str| {
+            state.add_source(
+                SoundSourceBuilder::new()
+                    .with_buffer(buffer.clone())
+                    .with_tag(tag)
+                    .with_status(Status::Playing)
+                    .build()
+                    .unwrap(),
+            )
+        };
+        let music = add_source("Music");
+        let other_music = add_source("Music");
+        let footsteps = add_source("Sfx");
+
+        state.pause_all_with_tag("Music");
+        assert_eq!(state.source(music).status(), Status::Paused);
+        assert_eq!(state.source(other_music).status(), Status::Paused);
+        assert_eq!(state.source(footsteps).status(), Status::Playing);
+
+        state.resume_all_with_tag("Music");
+        assert_eq!(state.source(music).status(), Status::Playing);
+        assert_eq!(state.source(other_music).status(), Status::Playing);
+
+        state.set_gain_for_tag("Sfx", 0.25);
+        assert_eq!(state.source(music).gain(), 1.0);
+        assert_eq!(state.source(footsteps).gain(), 0.25);
+
+        state.stop_all_with_tag("Music").unwrap();
+        assert_eq!(state.source(music).status(), Status::Stopped);
+        assert_eq!(state.source(other_music).status(), Status::Stopped);
+        assert_eq!(state.source(footsteps).status(), Status::Playing);
+    }
+}
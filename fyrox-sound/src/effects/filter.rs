@@ -49,9 +49,8 @@ macro_rules! define_filter_effect {
 
         impl EffectRenderTrait for $name {
             fn render(&mut self, input: &[(f32, f32)], output: &mut [(f32, f32)]) {
-                for ((input_left, input_right), (output_left, output_right)) in input.iter().zip(output) {
-                    *output_left = self.left.feed(*input_left);
-                    *output_right = self.right.feed(*input_right);
+                for (input, output) in input.iter().zip(output) {
+                    *output = self.feed(*input);
                 }
             }
         }
@@ -98,6 +97,26 @@ macro_rules! define_filter_effect {
                 self.quality
             }
 
+            /// Sets all parameters of the filter at once, but keeps the internal state of the filter,
+            /// so the change won't produce audible clicks. The filter is retuned only if any of the
+            /// parameters has changed.
+            pub(crate) fn set_params(&mut self, cutoff_frequency_hz: f32, gain: f32, quality: f32) {
+                if self.cutoff_frequency_hz != cutoff_frequency_hz
+                    || self.gain != gain
+                    || self.quality != quality
+                {
+                    self.cutoff_frequency_hz = cutoff_frequency_hz;
+                    self.gain = gain;
+                    self.quality = quality;
+                    self.update();
+                }
+            }
+
+            #[inline]
+            pub(crate) fn feed(&mut self, (left, right): (f32, f32)) -> (f32, f32) {
+                (self.left.feed(left), self.right.feed(right))
+            }
+
             fn update(&mut self) {
                 self.left.tune(
                     $kind,
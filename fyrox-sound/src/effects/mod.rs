@@ -7,7 +7,7 @@ use crate::{
     },
     effects::reverb::Reverb,
 };
-use fyrox_core::{reflect::prelude::*, visitor::prelude::*};
+use fyrox_core::{math::lerpf, reflect::prelude::*, visitor::prelude::*};
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 pub mod filter;
@@ -98,3 +98,63 @@ impl EffectRenderTrait for Effect {
         static_dispatch!(self, render, input, output)
     }
 }
+
+macro_rules! interpolate_filter {
+    ($filter:ident, $from:ident, $to:ident, $t:ident) => {{
+        $filter.set_params(
+            lerpf($from.cutoff_frequency_hz(), $to.cutoff_frequency_hz(), $t),
+            lerpf($from.gain(), $to.gain(), $t),
+            lerpf($from.quality(), $to.quality(), $t),
+        );
+    }};
+}
+
+impl Effect {
+    /// Sets parameters of the effect to an interpolation between parameters of two other effects. Internal
+    /// state of the effect (delay lines, etc.) is preserved, so the parameters could be changed smoothly
+    /// while the effect is playing. Does nothing if the effects are of different kinds.
+    pub fn interpolate(&mut self, from: &Effect, to: &Effect, t: f32) {
+        match (self, from, to) {
+            (Effect::Attenuate(effect), Effect::Attenuate(from), Effect::Attenuate(to)) => {
+                effect.gain = lerpf(from.gain, to.gain, t);
+            }
+            (Effect::Reverb(effect), Effect::Reverb(from), Effect::Reverb(to)) => {
+                effect.set_dry(lerpf(from.get_dry(), to.get_dry(), t));
+                effect.set_wet(lerpf(from.get_wet(), to.get_wet(), t));
+                effect.set_decay_time(lerpf(from.decay_time(), to.decay_time(), t));
+                effect.set_fc(lerpf(from.fc(), to.fc(), t));
+            }
+            (
+                Effect::LowPassFilter(effect),
+                Effect::LowPassFilter(from),
+                Effect::LowPassFilter(to),
+            ) => interpolate_filter!(effect, from, to, t),
+            (
+                Effect::HighPassFilter(effect),
+                Effect::HighPassFilter(from),
+                Effect::HighPassFilter(to),
+            ) => interpolate_filter!(effect, from, to, t),
+            (
+                Effect::BandPassFilter(effect),
+                Effect::BandPassFilter(from),
+                Effect::BandPassFilter(to),
+            ) => interpolate_filter!(effect, from, to, t),
+            (
+                Effect::AllPassFilter(effect),
+                Effect::AllPassFilter(from),
+                Effect::AllPassFilter(to),
+            ) => interpolate_filter!(effect, from, to, t),
+            (
+                Effect::LowShelfFilter(effect),
+                Effect::LowShelfFilter(from),
+                Effect::LowShelfFilter(to),
+            ) => interpolate_filter!(effect, from, to, t),
+            (
+                Effect::HighShelfFilter(effect),
+                Effect::HighShelfFilter(from),
+                Effect::HighShelfFilter(to),
+            ) => interpolate_filter!(effect, from, to, t),
+            _ => (),
+        }
+    }
+}
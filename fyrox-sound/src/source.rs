@@ -31,12 +31,14 @@
 use crate::{
     buffer::{streaming::StreamingBuffer, SoundBuffer, SoundBufferResource},
     bus::AudioBusGraph,
-    context::DistanceModel,
+    context::{DistanceModel, SAMPLE_RATE},
+    effects::filter::{HighPassFilterEffect, LowPassFilterEffect},
     error::SoundError,
     listener::Listener,
 };
 use fyrox_core::{
     algebra::Vector3,
+    curve::Curve,
     reflect::prelude::*,
     visitor::{Visit, VisitResult, Visitor},
 };
@@ -81,6 +83,10 @@ pub struct SoundSource {
     #[reflect(min_value = 0.0, step = 0.05)]
     gain: f32,
     looping: bool,
+    #[visit(optional)]
+    loop_start: u32,
+    #[visit(optional)]
+    loop_end: Option<u32>,
     #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
     spatial_blend: f32,
     // Important coefficient for runtime resampling. It is used to modify playback speed
@@ -96,6 +102,17 @@ pub struct SoundSource {
     status: Status,
     #[visit(optional)]
     pub(crate) bus: String,
+    #[visit(optional)]
+    tag: String,
+    #[visit(optional)]
+    send_bus: String,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    send_level: f32,
+    #[visit(optional)]
+    low_pass_filter: Option<LowPassFilterEffect>,
+    #[visit(optional)]
+    high_pass_filter: Option<HighPassFilterEffect>,
     play_once: bool,
     // Here we use Option because when source is just created it has no info about it
     // previous left and right channel gains. We can't set it to 1.0 for example
@@ -119,10 +136,16 @@ pub struct SoundSource {
     #[reflect(min_value = 0.0, step = 0.05)]
     radius: f32,
     position: Vector3<f32>,
+    #[visit(optional)]
+    velocity: Vector3<f32>,
     #[reflect(min_value = 0.0, step = 0.05)]
     max_distance: f32,
     #[reflect(min_value = 0.0, step = 0.05)]
     rolloff_factor: f32,
+    #[visit(optional)]
+    distance_curve: Option<Curve>,
+    #[visit(optional)]
+    max_distance_cutoff: bool,
     // Some data that needed for iterative overlap-save convolution.
     #[reflect(hidden)]
     #[visit(skip)]
@@ -136,6 +159,30 @@ pub struct SoundSource {
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) prev_distance_gain: Option<f32>,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.01)]
+    start_delay: f32,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.01)]
+    min_retrigger_interval: f32,
+    #[visit(optional)]
+    priority: i32,
+    #[visit(optional)]
+    max_buffer_voices: Option<u32>,
+    // Time (in seconds) left until the source starts producing samples after it was started.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    remaining_delay: f32,
+    // A flag that is set when the source was started from stopped state and the context did not
+    // process the start yet (see `min_retrigger_interval`).
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) pending_start: bool,
+    // Pitch multiplier caused by the Doppler effect, it is calculated by the context on each
+    // render pass from relative velocity of the source and the listener.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) doppler_factor: f64,
 }
 
 impl Default for SoundSource {
@@ -150,9 +197,16 @@ impl Default for SoundSource {
             gain: 1.0,
             spatial_blend: 1.0,
             looping: false,
+            loop_start: 0,
+            loop_end: None,
             resampling_multiplier: 1.0,
             status: Status::Stopped,
             bus: "Master".to_string(),
+            tag: Default::default(),
+            send_bus: Default::default(),
+            send_level: 0.0,
+            low_pass_filter: None,
+            high_pass_filter: None,
             play_once: false,
             last_left_gain: None,
             last_right_gain: None,
@@ -160,12 +214,22 @@ impl Default for SoundSource {
             prev_buffer_sample: (0.0, 0.0),
             radius: 1.0,
             position: Vector3::new(0.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            distance_curve: None,
+            max_distance_cutoff: false,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
             prev_sampling_vector: Vector3::new(0.0, 0.0, 1.0),
             prev_distance_gain: None,
+            start_delay: 0.0,
+            min_retrigger_interval: 0.0,
+            priority: 0,
+            max_buffer_voices: None,
+            remaining_delay: 0.0,
+            pending_start: false,
+            doppler_factor: 1.0,
         }
     }
 }
@@ -296,8 +360,13 @@ impl SoundSource {
         self.status
     }
 
-    /// Changes status to `Playing`.
+    /// Changes status to `Playing`. If the source was stopped, it will start producing samples after
+    /// its start delay (see [`Self::set_start_delay`]).
     pub fn play(&mut self) -> &mut Self {
+        if self.status == Status::Stopped {
+            self.remaining_delay = self.start_delay;
+            self.pending_start = true;
+        }
         self.status = Status::Playing;
         self
     }
@@ -320,6 +389,33 @@ impl SoundSource {
         self.looping
     }
 
+    /// Sets start of the loop region (in samples per channel from the beginning of the buffer). When
+    /// a looping sound reaches the end of the loop region, it continues playing from its start
+    /// instead of the beginning of the buffer. It is useful for music with an intro, that should be
+    /// played only once. Default is 0.
+    pub fn set_loop_start(&mut self, start: u32) -> &mut Self {
+        self.loop_start = start;
+        self
+    }
+
+    /// Returns start of the loop region (in samples per channel).
+    pub fn loop_start(&self) -> u32 {
+        self.loop_start
+    }
+
+    /// Sets end of the loop region (in samples per channel from the beginning of the buffer). See
+    /// [`Self::set_loop_start`] for more info. `None` (default) means the end of the buffer. The end
+    /// is ignored if it is not greater than the start of the region.
+    pub fn set_loop_end(&mut self, end: Option<u32>) -> &mut Self {
+        self.loop_end = end;
+        self
+    }
+
+    /// Returns end of the loop region (in samples per channel).
+    pub fn loop_end(&self) -> Option<u32> {
+        self.loop_end
+    }
+
     /// Sets sound pitch. Defines "tone" of sounds. Default value is 1.0
     pub fn set_pitch(&mut self, pitch: f64) -> &mut Self {
         self.pitch = pitch.abs();
@@ -334,6 +430,8 @@ impl SoundSource {
     /// Stops sound source. Automatically rewinds streaming buffers.
     pub fn stop(&mut self) -> Result<(), SoundError> {
         self.status = Status::Stopped;
+        self.remaining_delay = 0.0;
+        self.pending_start = false;
 
         self.buf_read_pos = 0.0;
         self.playback_pos = 0.0;
@@ -347,6 +445,62 @@ impl SoundSource {
 
         Ok(())
     }
+    /// Sets a delay (in seconds) between the moment when the source is started (from stopped state)
+    /// and the moment when it starts producing samples. Small random delays could be used to
+    /// "humanize" layered one-shot sounds, that are started at the same time. The delay is not
+    /// applied when the source is resumed after pause or when it loops. Default is 0.0.
+    pub fn set_start_delay(&mut self, delay: f32) -> &mut Self {
+        self.start_delay = delay.max(0.0);
+        self
+    }
+
+    /// Returns start delay (in seconds) of the source.
+    pub fn start_delay(&self) -> f32 {
+        self.start_delay
+    }
+
+    /// Sets minimum interval (in seconds) between starts of sources with the same sound buffer. If
+    /// the source is started earlier than the interval has passed since the last start of any source
+    /// with the same buffer, it will be stopped right away. This prevents phasing and doubled volume
+    /// of rapidly repeated one-shot sounds (UI clicks, overlapping pickups, etc.). Zero disables the
+    /// protection. Default is 0.0.
+    pub fn set_min_retrigger_interval(&mut self, interval: f32) -> &mut Self {
+        self.min_retrigger_interval = interval.max(0.0);
+        self
+    }
+
+    /// Returns minimum retrigger interval (in seconds) of the source.
+    pub fn min_retrigger_interval(&self) -> f32 {
+        self.min_retrigger_interval
+    }
+
+    /// Sets priority of the source. When the amount of playing sources exceeds voice limits (see
+    /// [`crate::context::State::set_max_voices`] and [`Self::set_max_buffer_voices`]), sources with
+    /// lower priority are stopped first. Default is 0.
+    pub fn set_priority(&mut self, priority: i32) -> &mut Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Returns priority of the source.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Sets maximum amount of simultaneously playing sources with the same sound buffer. When the
+    /// limit is exceeded, the least important sources (with lower priority or quieter) are stopped.
+    /// If sources with the same buffer have different limits, the smallest one is used. `None` means
+    /// no limit (default).
+    pub fn set_max_buffer_voices(&mut self, max_voices: Option<u32>) -> &mut Self {
+        self.max_buffer_voices = max_voices;
+        self
+    }
+
+    /// Returns maximum amount of simultaneously playing sources with the same sound buffer.
+    pub fn max_buffer_voices(&self) -> Option<u32> {
+        self.max_buffer_voices
+    }
+
     /// Sets position of source in world space.
     pub fn set_position(&mut self, position: Vector3<f32>) -> &mut Self {
         self.position = position;
@@ -358,6 +512,19 @@ impl SoundSource {
         self.position
     }
 
+    /// Sets velocity (in units per second) of source in world space. The velocity does not move the
+    /// source, it is used only to calculate Doppler effect. See [`crate::context::State::set_doppler_scale`]
+    /// for more info.
+    pub fn set_velocity(&mut self, velocity: Vector3<f32>) -> &mut Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Returns velocity of source.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
     /// Sets radius of imaginable sphere around source in which no distance attenuation is applied.
     pub fn set_radius(&mut self, radius: f32) -> &mut Self {
         self.radius = radius;
@@ -396,6 +563,34 @@ impl SoundSource {
         self.max_distance
     }
 
+    /// Sets custom distance attenuation curve, that maps distance between the source and the
+    /// listener to distance gain. The curve overrides distance model of the context (see
+    /// [`crate::context::State::set_distance_model`]) for this source. Radius and max distance of
+    /// the source do not clamp the distance passed to the curve. `None` disables the curve
+    /// (default).
+    pub fn set_distance_curve(&mut self, curve: Option<Curve>) -> &mut Self {
+        self.distance_curve = curve;
+        self
+    }
+
+    /// Returns custom distance attenuation curve, if any.
+    pub fn distance_curve(&self) -> Option<&Curve> {
+        self.distance_curve.as_ref()
+    }
+
+    /// Enables or disables max distance cutoff. When enabled, the source becomes silent if the
+    /// listener is further than max distance (see [`Self::set_max_distance`]), instead of keeping
+    /// its volume at max distance. Default is `false`.
+    pub fn set_max_distance_cutoff(&mut self, cutoff: bool) -> &mut Self {
+        self.max_distance_cutoff = cutoff;
+        self
+    }
+
+    /// Returns `true` if max distance cutoff is enabled, `false` - otherwise.
+    pub fn is_max_distance_cutoff(&self) -> bool {
+        self.max_distance_cutoff
+    }
+
     /// Sets new name of the target audio bus. The name must be valid, otherwise the sound won't play!
     /// Default is [`AudioBusGraph::PRIMARY_BUS`].
     pub fn set_bus<S: AsRef<str>>(&mut self, bus: S) {
@@ -407,6 +602,98 @@ impl SoundSource {
         &self.bus
     }
 
+    /// Sets new tag of the sound source. Tags allow you to control a category of sound sources at once
+    /// (for example stop every sound source with "Music" tag), see [`crate::context::State::stop_all_with_tag`]
+    /// and similar methods.
+    pub fn set_tag<S: AsRef<str>>(&mut self, tag: S) {
+        self.tag = tag.as_ref().to_owned();
+    }
+
+    /// Returns the tag of the sound source.
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Sets new name of the send bus. Besides its own output bus (see [`Self::set_bus`]), the sound
+    /// source also feeds a part of its signal (defined by [`Self::set_send_level`]) to the send bus.
+    /// Usually the send bus has a reverb effect, this way distant or indoor sounds could have more
+    /// reverberation than the others. Empty name disables the send (default).
+    pub fn set_send_bus<S: AsRef<str>>(&mut self, bus: S) {
+        self.send_bus = bus.as_ref().to_owned();
+    }
+
+    /// Returns the name of the send bus.
+    pub fn send_bus(&self) -> &str {
+        &self.send_bus
+    }
+
+    /// Sets amount of the signal (in `[0; 1]` range) that will be sent to the send bus (see
+    /// [`Self::set_send_bus`]). The send is applied after distance attenuation and panning. Default
+    /// is 0.0.
+    pub fn set_send_level(&mut self, level: f32) -> &mut Self {
+        self.send_level = level.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns send level of the sound source.
+    pub fn send_level(&self) -> f32 {
+        self.send_level
+    }
+
+    /// Sets new low-pass filter of the sound source, the filter muffles high frequencies of the
+    /// sound. It could be used to simulate occlusion, sounds heard underwater, etc. If the source
+    /// already has a filter, only its parameters are changed, so the filter could be changed on the
+    /// fly without audible clicks. `None` disables the filter (default).
+    pub fn set_low_pass_filter(&mut self, filter: Option<LowPassFilterEffect>) {
+        match (self.low_pass_filter.as_mut(), filter) {
+            (Some(current), Some(filter)) => current.set_params(
+                filter.cutoff_frequency_hz(),
+                filter.gain(),
+                filter.quality(),
+            ),
+            (_, filter) => self.low_pass_filter = filter,
+        }
+    }
+
+    /// Returns a reference to the low-pass filter of the sound source, if any.
+    pub fn low_pass_filter(&self) -> Option<&LowPassFilterEffect> {
+        self.low_pass_filter.as_ref()
+    }
+
+    /// Returns a mutable reference to the low-pass filter of the sound source, if any.
+    pub fn low_pass_filter_mut(&mut self) -> Option<&mut LowPassFilterEffect> {
+        self.low_pass_filter.as_mut()
+    }
+
+    /// Sets new high-pass filter of the sound source, the filter removes low frequencies of the
+    /// sound. It could be used to simulate radio voices, small speakers, etc. If the source already
+    /// has a filter, only its parameters are changed, so the filter could be changed on the fly
+    /// without audible clicks. `None` disables the filter (default).
+    pub fn set_high_pass_filter(&mut self, filter: Option<HighPassFilterEffect>) {
+        match (self.high_pass_filter.as_mut(), filter) {
+            (Some(current), Some(filter)) => current.set_params(
+                filter.cutoff_frequency_hz(),
+                filter.gain(),
+                filter.quality(),
+            ),
+            (_, filter) => self.high_pass_filter = filter,
+        }
+    }
+
+    /// Returns a reference to the high-pass filter of the sound source, if any.
+    pub fn high_pass_filter(&self) -> Option<&HighPassFilterEffect> {
+        self.high_pass_filter.as_ref()
+    }
+
+    /// Returns a mutable reference to the high-pass filter of the sound source, if any.
+    pub fn high_pass_filter_mut(&mut self) -> Option<&mut HighPassFilterEffect> {
+        self.high_pass_filter.as_mut()
+    }
+
+    pub(crate) fn has_send(&self) -> bool {
+        self.send_level > 0.0 && !self.send_bus.is_empty()
+    }
+
     // Distance models were taken from OpenAL Specification because it looks like they're
     // standard in industry and there is no need to reinvent it.
     // https://www.openal.org/documentation/openal-1.1-specification.pdf
@@ -415,10 +702,14 @@ impl SoundSource {
         listener: &Listener,
         distance_model: DistanceModel,
     ) -> f32 {
-        let distance = self
-            .position
-            .metric_distance(&listener.position())
-            .clamp(self.radius, self.max_distance);
+        let distance = self.position.metric_distance(&listener.position());
+        if self.max_distance_cutoff && distance > self.max_distance {
+            return 0.0;
+        }
+        if let Some(curve) = self.distance_curve.as_ref() {
+            return curve.value_at(distance);
+        }
+        let distance = distance.clamp(self.radius, self.max_distance);
         match distance_model {
             DistanceModel::None => 1.0,
             DistanceModel::InverseDistance => {
@@ -431,6 +722,39 @@ impl SoundSource {
         }
     }
 
+    // Doppler shift formula was taken from OpenAL Specification as well. The result is blended
+    // with 1.0 using spatial blend factor, so 2D sounds are not affected.
+    pub(crate) fn calculate_doppler_factor(
+        &self,
+        listener: &Listener,
+        doppler_scale: f32,
+        speed_of_sound: f32,
+    ) -> f64 {
+        if doppler_scale <= 0.0 || speed_of_sound <= 0.0 {
+            return 1.0;
+        }
+
+        let direction = match (listener.position() - self.position).try_normalize(f32::EPSILON) {
+            Some(direction) => direction,
+            // Source and listener at the same point, there is no meaningful direction.
+            None => return 1.0,
+        };
+
+        // The formula breaks when velocities reach the speed of sound. Unlike OpenAL, the speeds
+        // are limited to a half of it, so sudden jumps (teleportation) won't produce extreme pitch.
+        let max_speed = 0.5 * speed_of_sound / doppler_scale;
+        let listener_speed = listener
+            .velocity()
+            .dot(&direction)
+            .clamp(-max_speed, max_speed);
+        let source_speed = self.velocity.dot(&direction).clamp(-max_speed, max_speed);
+
+        let factor = (speed_of_sound - doppler_scale * listener_speed)
+            / (speed_of_sound - doppler_scale * source_speed);
+
+        (1.0 + (factor - 1.0) * self.spatial_blend) as f64
+    }
+
     pub(crate) fn calculate_panning(&self, listener: &Listener) -> f32 {
         (listener.position() - self.position)
             .try_normalize(f32::EPSILON)
@@ -450,7 +774,7 @@ impl SoundSource {
             .unwrap_or_else(|| Vector3::new(0.0, 0.0, 1.0))
     }
 
-    /// Returns playback duration.
+    /// Returns current playback position (as time from the beginning of the buffer).
     pub fn playback_time(&self) -> Duration {
         if let Some(buffer) = self.buffer.as_ref() {
             let buffer = buffer.data_ref();
@@ -460,34 +784,60 @@ impl SoundSource {
         }
     }
 
-    /// Sets playback duration.
+    /// Sets current playback position (as time from the beginning of the buffer). The time is
+    /// clamped to the duration of the buffer. Streaming buffers are re-filled with the data from
+    /// the respective position of their decoders.
     pub fn set_playback_time(&mut self, time: Duration) {
-        if let Some(buffer) = self.buffer.as_ref() {
+        if let Some(buffer) = self.buffer.clone() {
             let mut buffer = buffer.data_ref();
-            if let SoundBuffer::Streaming(ref mut streaming) = *buffer {
-                // Make sure decoder is at right position.
-                streaming.time_seek(time.clamp(Duration::from_secs(0), streaming.duration()));
+            let mut position = time.as_secs_f64() * buffer.sample_rate() as f64;
+            // Duration of procedural streaming sources could be unknown.
+            let duration = buffer.channel_duration_in_samples();
+            if duration > 0 {
+                position = position.min((duration - 1) as f64);
             }
-            // Set absolute position first.
-            self.playback_pos = (time.as_secs_f64() * buffer.sample_rate as f64)
-                .clamp(0.0, buffer.duration().as_secs_f64());
-            // Then adjust buffer read position.
-            self.buf_read_pos = match *buffer {
-                SoundBuffer::Streaming(ref mut streaming) => {
-                    // Make sure to load correct data into buffer from decoder.
-                    streaming.read_next_block();
-                    // Streaming sources has different buffer read position because
-                    // buffer contains only small portion of data.
-                    self.playback_pos % (StreamingBuffer::STREAM_SAMPLE_COUNT as f64)
-                }
-                SoundBuffer::Generic(_) => self.playback_pos,
-            };
-            assert!(
-                self.buf_read_pos * (buffer.channel_count() as f64) < buffer.samples().len() as f64
-            );
+            self.seek(&mut buffer, position);
         }
     }
 
+    // Moves playback to the given position (in samples per channel).
+    fn seek(&mut self, buffer: &mut SoundBuffer, position: f64) {
+        // Set absolute position first.
+        self.playback_pos = position;
+        // Then adjust buffer read position.
+        self.buf_read_pos = match buffer {
+            SoundBuffer::Streaming(streaming) => {
+                // Make sure the decoder is at right position and load correct data into the
+                // buffer. Half of a sample is added to prevent rounding to the previous sample.
+                let sample_rate = streaming.sample_rate() as f64;
+                streaming.time_seek(Duration::from_secs_f64(
+                    (position.floor() + 0.5) / sample_rate,
+                ));
+                streaming.read_next_block();
+                // Streaming buffer contains only small portion of data, that starts at the
+                // new position.
+                position.fract()
+            }
+            SoundBuffer::Generic(_) => position,
+        };
+    }
+
+    // Returns loop region (in samples per channel) clamped to the duration of the buffer. The end
+    // of the region is `None` if the region ends at the end of the buffer.
+    fn loop_region(&self, buffer: &SoundBuffer) -> (f64, Option<f64>) {
+        let duration = buffer.channel_duration_in_samples();
+        let mut start = self.loop_start as usize;
+        let mut end = self.loop_end.map(|end| end as usize);
+        if duration > 0 {
+            start = start.min(duration - 1);
+            end = end.filter(|end| *end < duration);
+        }
+        (
+            start as f64,
+            end.filter(|end| *end > start).map(|end| end as f64),
+        )
+    }
+
     pub(crate) fn render(&mut self, amount: usize) {
         if self.frame_samples.capacity() < amount {
             self.frame_samples = Vec::with_capacity(amount);
@@ -499,18 +849,73 @@ impl SoundSource {
             let mut state = buffer.state();
             if let ResourceStateRefMut::Ok(buffer) = state.get_mut() {
                 if self.status == Status::Playing && !buffer.is_empty() {
-                    self.render_playing(buffer, amount);
+                    // Produce silence until the start delay is over.
+                    let mut delayed = 0;
+                    if self.remaining_delay > 0.0 {
+                        delayed =
+                            ((self.remaining_delay * SAMPLE_RATE as f32) as usize).min(amount);
+                        self.frame_samples.resize(delayed, (0.0, 0.0));
+                        self.remaining_delay = if delayed < amount {
+                            0.0
+                        } else {
+                            self.remaining_delay - delayed as f32 / SAMPLE_RATE as f32
+                        };
+                    }
+                    if delayed < amount {
+                        self.render_playing(buffer, amount - delayed);
+                    }
                 }
             }
         }
         // Fill the remaining part of frame_samples.
         self.frame_samples.resize(amount, (0.0, 0.0));
+
+        if let Some(filter) = self.low_pass_filter.as_mut() {
+            for sample in self.frame_samples.iter_mut() {
+                *sample = filter.feed(*sample);
+            }
+        }
+        if let Some(filter) = self.high_pass_filter.as_mut() {
+            for sample in self.frame_samples.iter_mut() {
+                *sample = filter.feed(*sample);
+            }
+        }
     }
 
     fn render_playing(&mut self, buffer: &mut SoundBuffer, amount: usize) {
+        let (loop_start, loop_end) = self.loop_region(buffer);
+        let loop_end = loop_end.filter(|_| self.looping);
         let mut count = 0;
         loop {
-            count += self.render_until_block_end(buffer, amount - count);
+            let mut block_amount = amount - count;
+            let mut reached_loop_end = None;
+            if let Some(loop_end) = loop_end {
+                // Do not render past the end of the loop region.
+                let remaining = ((loop_end - self.playback_pos) / self.sampling_step())
+                    .ceil()
+                    .max(0.0) as usize;
+                if remaining <= block_amount {
+                    block_amount = remaining;
+                    reached_loop_end = Some(loop_end);
+                }
+            }
+
+            let rendered = if block_amount > 0 {
+                self.render_until_block_end(buffer, block_amount)
+            } else {
+                0
+            };
+            count += rendered;
+
+            if let Some(loop_end) = reached_loop_end.filter(|_| rendered == block_amount) {
+                let overshoot = (self.playback_pos - loop_end).max(0.0) % (loop_end - loop_start);
+                self.seek(buffer, loop_start + overshoot);
+                if count == amount {
+                    break;
+                }
+                continue;
+            }
+
             if count == amount {
                 break;
             }
@@ -533,18 +938,27 @@ impl SoundSource {
                     self.status = Status::Stopped;
                     return;
                 }
-                self.buf_read_pos = 0.0;
-                self.playback_pos = 0.0;
+                if loop_start > 0.0 {
+                    self.seek(buffer, loop_start);
+                } else {
+                    self.buf_read_pos = 0.0;
+                    self.playback_pos = 0.0;
+                }
             } else {
                 self.buf_read_pos -= len as f64 / channel_count as f64;
             }
         }
     }
 
+    // Amount of samples of the buffer per one output sample.
+    fn sampling_step(&self) -> f64 {
+        self.pitch * self.doppler_factor * self.resampling_multiplier
+    }
+
     // Renders until the end of the block or until amount samples is written and returns
     // the number of written samples.
     fn render_until_block_end(&mut self, buffer: &mut SoundBuffer, mut amount: usize) -> usize {
-        let step = self.pitch * self.resampling_multiplier;
+        let step = self.sampling_step();
         if step == 1.0 {
             if self.buf_read_pos < 0.0 {
                 // This can theoretically happen if we change pitch on the fly.
@@ -707,15 +1121,29 @@ pub struct SoundSourceBuilder {
     name: String,
     panning: f32,
     looping: bool,
+    loop_start: u32,
+    loop_end: Option<u32>,
     status: Status,
     play_once: bool,
     playback_time: Duration,
     radius: f32,
     position: Vector3<f32>,
+    velocity: Vector3<f32>,
     max_distance: f32,
     rolloff_factor: f32,
+    distance_curve: Option<Curve>,
+    max_distance_cutoff: bool,
     spatial_blend: f32,
     bus: String,
+    tag: String,
+    send_bus: String,
+    send_level: f32,
+    low_pass_filter: Option<LowPassFilterEffect>,
+    high_pass_filter: Option<HighPassFilterEffect>,
+    start_delay: f32,
+    min_retrigger_interval: f32,
+    priority: i32,
+    max_buffer_voices: Option<u32>,
 }
 
 impl Default for SoundSourceBuilder {
@@ -734,15 +1162,29 @@ impl SoundSourceBuilder {
             name: Default::default(),
             panning: 0.0,
             looping: false,
+            loop_start: 0,
+            loop_end: None,
             status: Status::Stopped,
             play_once: false,
             playback_time: Default::default(),
             radius: 1.0,
             position: Vector3::new(0.0, 0.0, 0.0),
+            velocity: Vector3::new(0.0, 0.0, 0.0),
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            distance_curve: None,
+            max_distance_cutoff: false,
             spatial_blend: 1.0,
             bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            tag: Default::default(),
+            send_bus: Default::default(),
+            send_level: 0.0,
+            low_pass_filter: None,
+            high_pass_filter: None,
+            start_delay: 0.0,
+            min_retrigger_interval: 0.0,
+            priority: 0,
+            max_buffer_voices: None,
         }
     }
 
@@ -788,6 +1230,18 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_loop_start`]
+    pub fn with_loop_start(mut self, start: u32) -> Self {
+        self.loop_start = start;
+        self
+    }
+
+    /// See [`SoundSource::set_loop_end`]
+    pub fn with_loop_end(mut self, end: Option<u32>) -> Self {
+        self.loop_end = end;
+        self
+    }
+
     /// Sets desired status of source.
     pub fn with_status(mut self, status: Status) -> Self {
         self.status = status;
@@ -818,6 +1272,12 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_velocity`]
+    pub fn with_velocity(mut self, velocity: Vector3<f32>) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
     /// See `set_radius` of SpatialSource.
     pub fn with_radius(mut self, radius: f32) -> Self {
         self.radius = radius;
@@ -836,12 +1296,78 @@ impl SoundSourceBuilder {
         self
     }
 
+    /// See [`SoundSource::set_distance_curve`]
+    pub fn with_distance_curve(mut self, curve: Option<Curve>) -> Self {
+        self.distance_curve = curve;
+        self
+    }
+
+    /// See [`SoundSource::set_max_distance_cutoff`]
+    pub fn with_max_distance_cutoff(mut self, cutoff: bool) -> Self {
+        self.max_distance_cutoff = cutoff;
+        self
+    }
+
     /// Sets desired output bus for the sound source.
     pub fn with_bus<S: AsRef<str>>(mut self, bus: S) -> Self {
         self.bus = bus.as_ref().to_string();
         self
     }
 
+    /// See [`SoundSource::set_tag`]
+    pub fn with_tag<S: AsRef<str>>(mut self, tag: S) -> Self {
+        self.tag = tag.as_ref().to_string();
+        self
+    }
+
+    /// See [`SoundSource::set_send_bus`]
+    pub fn with_send_bus<S: AsRef<str>>(mut self, bus: S) -> Self {
+        self.send_bus = bus.as_ref().to_string();
+        self
+    }
+
+    /// See [`SoundSource::set_send_level`]
+    pub fn with_send_level(mut self, level: f32) -> Self {
+        self.send_level = level;
+        self
+    }
+
+    /// See [`SoundSource::set_low_pass_filter`]
+    pub fn with_low_pass_filter(mut self, filter: Option<LowPassFilterEffect>) -> Self {
+        self.low_pass_filter = filter;
+        self
+    }
+
+    /// See [`SoundSource::set_high_pass_filter`]
+    pub fn with_high_pass_filter(mut self, filter: Option<HighPassFilterEffect>) -> Self {
+        self.high_pass_filter = filter;
+        self
+    }
+
+    /// See [`SoundSource::set_start_delay`]
+    pub fn with_start_delay(mut self, delay: f32) -> Self {
+        self.start_delay = delay;
+        self
+    }
+
+    /// See [`SoundSource::set_min_retrigger_interval`]
+    pub fn with_min_retrigger_interval(mut self, interval: f32) -> Self {
+        self.min_retrigger_interval = interval;
+        self
+    }
+
+    /// See [`SoundSource::set_priority`]
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// See [`SoundSource::set_max_buffer_voices`]
+    pub fn with_max_buffer_voices(mut self, max_voices: Option<u32>) -> Self {
+        self.max_buffer_voices = max_voices;
+        self
+    }
+
     /// Creates new instance of generic sound source. May fail if buffer is invalid.
     pub fn build(self) -> Result<SoundSource, SoundError> {
         let mut source = SoundSource {
@@ -852,22 +1378,41 @@ impl SoundSourceBuilder {
             panning: self.panning,
             status: self.status,
             looping: self.looping,
+            loop_start: self.loop_start,
+            loop_end: self.loop_end,
             name: self.name,
             frame_samples: Default::default(),
             radius: self.radius,
             position: self.position,
+            velocity: self.velocity,
             max_distance: self.max_distance,
             rolloff_factor: self.rolloff_factor,
+            distance_curve: self.distance_curve,
+            max_distance_cutoff: self.max_distance_cutoff,
             spatial_blend: self.spatial_blend,
             prev_left_samples: Default::default(),
             prev_right_samples: Default::default(),
             bus: self.bus,
+            tag: self.tag,
+            send_bus: self.send_bus,
+            send_level: self.send_level.clamp(0.0, 1.0),
+            low_pass_filter: self.low_pass_filter,
+            high_pass_filter: self.high_pass_filter,
+            start_delay: self.start_delay.max(0.0),
+            min_retrigger_interval: self.min_retrigger_interval.max(0.0),
+            priority: self.priority,
+            max_buffer_voices: self.max_buffer_voices,
             ..Default::default()
         };
 
         source.set_buffer(self.buffer)?;
         source.set_playback_time(self.playback_time);
 
+        if source.status == Status::Playing {
+            source.remaining_delay = source.start_delay;
+            source.pending_start = true;
+        }
+
         Ok(source)
     }
 }
@@ -159,6 +159,7 @@ impl Executor {
             control_flow.set_wait();
 
             engine.handle_os_event_by_plugins(&event, fixed_time_step, control_flow, &mut lag);
+            engine.handle_os_event_by_debug_camera(&event);
 
             let scenes = engine
                 .scenes
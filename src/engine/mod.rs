@@ -5,6 +5,7 @@
 
 pub mod error;
 pub mod executor;
+pub mod scaling;
 
 use crate::scene::camera::SkyBoxKind;
 use crate::{
@@ -14,7 +15,10 @@ use crate::{
         ResourceStateRef,
     },
     core::{algebra::Vector2, futures::executor::block_on, instant, log::Log, pool::Handle},
-    engine::error::EngineError,
+    engine::{
+        error::EngineError,
+        scaling::{PerformanceScaler, QualityKnobContext},
+    },
     event::Event,
     event_loop::ControlFlow,
     gui::UserInterface,
@@ -24,13 +28,15 @@ use crate::{
     resource::{
         curve::{loader::CurveLoader, CurveResourceState},
         model::{loader::ModelLoader, Model, ModelResource},
+        physics_material::{loader::PhysicsMaterialLoader, PhysicsMaterial},
         texture::{loader::TextureLoader, Texture, TextureKind},
     },
     scene::{
         base::NodeScriptMessage,
-        graph::GraphUpdateSwitches,
+        graph::{migration::GraphMigrationContainer, GraphUpdateSwitches},
         node::{constructor::NodeConstructorContainer, Node},
         sound::SoundEngine,
+        user_data::UserDataConstructorContainer,
         Scene, SceneContainer,
     },
     script::{
@@ -38,6 +44,7 @@ use crate::{
         ScriptDeinitContext, ScriptMessage, ScriptMessageContext, ScriptMessageKind,
         ScriptMessageSender,
     },
+    utils::debug_camera::DebugCamera,
     window::{Window, WindowBuilder},
 };
 use fxhash::{FxHashMap, FxHashSet};
@@ -83,6 +90,10 @@ pub struct SerializationContext {
     pub node_constructors: NodeConstructorContainer,
     /// A script constructor container.
     pub script_constructors: ScriptConstructorContainer,
+    /// A container of migrations, that are used to upgrade graphs stored in older formats.
+    pub graph_migrations: GraphMigrationContainer,
+    /// A container of constructors of serializable user data of scene nodes.
+    pub user_data_constructors: UserDataConstructorContainer,
 }
 
 impl Default for SerializationContext {
@@ -97,6 +108,8 @@ impl SerializationContext {
         Self {
             node_constructors: NodeConstructorContainer::new(),
             script_constructors: ScriptConstructorContainer::new(),
+            graph_migrations: GraphMigrationContainer::new(),
+            user_data_constructors: UserDataConstructorContainer::new(),
         }
     }
 }
@@ -227,6 +240,18 @@ pub struct Engine {
 
     /// Script processor is used to run script methods in a strict order.
     pub script_processor: ScriptProcessor,
+
+    /// Performance scaler automatically adjusts quality of the game according to its frame time.
+    /// It is disabled by default, see [`PerformanceScaler`] docs for more info.
+    pub performance_scaler: PerformanceScaler,
+
+    /// Free-fly debug camera, see [`DebugCamera`] docs for more info. It controls the scene from
+    /// [`Self::debug_camera_scene`].
+    pub debug_camera: DebugCamera,
+
+    /// A scene that is controlled by [`Self::debug_camera`]. It is [`Handle::NONE`] by default,
+    /// which means that the debug camera is unavailable.
+    pub debug_camera_scene: Handle<Scene>,
 }
 
 /// Performs dispatch of script messages.
@@ -821,6 +846,7 @@ pub(crate) fn initialize_resource_manager_loaders(
     state.constructors_container.add::<Shader>();
     state.constructors_container.add::<Model>();
     state.constructors_container.add::<CurveResourceState>();
+    state.constructors_container.add::<PhysicsMaterial>();
     state.constructors_container.add::<SoundBuffer>();
     state.constructors_container.add::<HrirSphereResourceData>();
 
@@ -834,6 +860,7 @@ pub(crate) fn initialize_resource_manager_loaders(
     });
     loaders.set(ShaderLoader);
     loaders.set(CurveLoader);
+    loaders.set(PhysicsMaterialLoader);
     loaders.set(HrirSphereLoader);
 }
 
@@ -900,6 +927,9 @@ impl Engine {
             plugins: Default::default(),
             serialization_context,
             script_processor: Default::default(),
+            performance_scaler: Default::default(),
+            debug_camera: Default::default(),
+            debug_camera_scene: Default::default(),
             plugins_enabled: false,
             plugin_constructors: Default::default(),
             elapsed_time: 0.0,
@@ -1240,6 +1270,10 @@ impl Engine {
             ctx.renderer.update_caches(dt);
             self.handle_model_events();
 
+            if let Some(scene) = self.scenes.try_get_mut(self.debug_camera_scene) {
+                self.debug_camera.update(scene, dt);
+            }
+
             for (handle, scene) in self.scenes.pair_iter_mut().filter(|(_, s)| s.enabled) {
                 let frame_size = scene.render_target.as_ref().map_or(window_size, |rt| {
                     if let TextureKind::Rectangle { width, height } = rt.data_ref().kind() {
@@ -1376,6 +1410,12 @@ impl Engine {
         }
     }
 
+    pub(crate) fn handle_os_event_by_debug_camera(&mut self, event: &Event<()>) {
+        if self.scenes.is_valid_handle(self.debug_camera_scene) {
+            self.debug_camera.process_event(event);
+        }
+    }
+
     pub(crate) fn handle_graphics_context_created_by_plugins(
         &mut self,
         dt: f32,
@@ -1548,6 +1588,12 @@ impl Engine {
                     &self.user_interface.get_drawing_context(),
                 )?;
             }
+
+            self.performance_scaler
+                .on_frame_rendered(&mut QualityKnobContext {
+                    renderer: Some(&mut ctx.renderer),
+                    scenes: &mut self.scenes,
+                });
         }
 
         Ok(())
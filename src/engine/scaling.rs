@@ -0,0 +1,547 @@
+//! Adaptive performance scaling allows you to automatically adjust quality of the game according to
+//! its actual performance. See [`PerformanceScaler`] docs for more info.
+
+use crate::{
+    core::{instant, log::Log},
+    renderer::{QualitySettings, Renderer},
+    scene::{particle_system::ParticleSystem, SceneContainer},
+};
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Formatter},
+    sync::mpsc::Sender,
+};
+
+/// A context that is passed to quality knobs when they're applying a new quality tier.
+pub struct QualityKnobContext<'a> {
+    /// Current renderer. It could be [`None`] if the graphics context is not initialized.
+    pub renderer: Option<&'a mut Renderer>,
+    /// All available scenes in the engine.
+    pub scenes: &'a mut SceneContainer,
+}
+
+/// A quality knob is something that can change quality of the game (and its performance as well).
+/// Quality is defined by a tier index, where `0` is the highest quality.
+pub trait QualityKnob {
+    /// Applies the given quality tier. `tier_count` is the total amount of tiers of the scaler.
+    fn apply(&mut self, tier: usize, tier_count: usize, context: &mut QualityKnobContext);
+}
+
+impl<F> QualityKnob for F
+where
+    F: FnMut(usize, usize, &mut QualityKnobContext),
+{
+    fn apply(&mut self, tier: usize, tier_count: usize, context: &mut QualityKnobContext) {
+        (self)(tier, tier_count, context)
+    }
+}
+
+/// A quality knob that changes resolution of shadow maps of every light source type. The knob holds
+/// shadow map sizes for each tier, the last size is used for tiers that have no respective size.
+pub struct ShadowResolutionKnob {
+    /// Shadow map sizes for each tier.
+    pub sizes: Vec<usize>,
+}
+
+impl QualityKnob for ShadowResolutionKnob {
+    fn apply(&mut self, tier: usize, _tier_count: usize, context: &mut QualityKnobContext) {
+        if let (Some(renderer), Some(&size)) = (
+            context.renderer.as_mut(),
+            self.sizes.get(tier).or_else(|| self.sizes.last()),
+        ) {
+            let mut settings = QualitySettings {
+                point_shadow_map_size: size,
+                spot_shadow_map_size: size,
+                ..renderer.get_quality_settings()
+            };
+            settings.csm_settings.size = size;
+            Log::verify(renderer.set_quality_settings(&settings));
+        }
+    }
+}
+
+/// A quality knob that changes resolution of scenes that are rendered into the back buffer (see
+/// [`Renderer::set_render_scale`]). The knob holds render scales for each tier, the last scale is
+/// used for tiers that have no respective scale.
+pub struct RenderScaleKnob {
+    /// Render scales for each tier.
+    pub scales: Vec<f32>,
+}
+
+impl QualityKnob for RenderScaleKnob {
+    fn apply(&mut self, tier: usize, _tier_count: usize, context: &mut QualityKnobContext) {
+        if let (Some(renderer), Some(&scale)) = (
+            context.renderer.as_mut(),
+            self.scales.get(tier).or_else(|| self.scales.last()),
+        ) {
+            renderer.set_render_scale(scale);
+        }
+    }
+}
+
+/// A quality knob that changes density of every particle system in every scene (see
+/// [`ParticleSystem::set_density`]). The knob holds densities for each tier, the last density is
+/// used for tiers that have no respective density. Particle systems that are created after the tier
+/// change keep their own density.
+pub struct ParticleDensityKnob {
+    /// Particle densities for each tier.
+    pub densities: Vec<f32>,
+}
+
+impl QualityKnob for ParticleDensityKnob {
+    fn apply(&mut self, tier: usize, _tier_count: usize, context: &mut QualityKnobContext) {
+        if let Some(&density) = self.densities.get(tier).or_else(|| self.densities.last()) {
+            for scene in context.scenes.iter_mut() {
+                for node in scene.graph.linear_iter_mut() {
+                    if let Some(particle_system) = node.cast_mut::<ParticleSystem>() {
+                        particle_system.set_density(density);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A decision made by a [`ScalingPolicy`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TierChange {
+    /// Keep the current tier.
+    Keep,
+    /// Switch to a lower quality (higher tier index).
+    Downgrade,
+    /// Switch to a higher quality (lower tier index).
+    Upgrade,
+}
+
+/// A policy decides when the quality should be changed.
+pub trait ScalingPolicy {
+    /// Evaluates frame times of last frames (in seconds, the most recent is the last) and makes a
+    /// decision. The scaler resets the history after every tier change.
+    fn evaluate(
+        &mut self,
+        frame_times: &VecDeque<f32>,
+        tier: usize,
+        tier_count: usize,
+    ) -> TierChange;
+
+    /// Returns the amount of frames that should be collected before evaluation.
+    fn window(&self) -> usize;
+}
+
+/// Default scaling policy that compares average frame time with a target frame time. The quality
+/// will be lowered if the average frame time is higher than `target_frame_time * downgrade_ratio`,
+/// and raised if it is lower than `target_frame_time * upgrade_ratio`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FrameTimePolicy {
+    /// Desired frame time in seconds.
+    pub target_frame_time: f32,
+    /// A ratio of the target frame time, above which the quality will be lowered.
+    pub downgrade_ratio: f32,
+    /// A ratio of the target frame time, below which the quality will be raised.
+    pub upgrade_ratio: f32,
+    /// Amount of frames to average.
+    pub window: usize,
+}
+
+impl Default for FrameTimePolicy {
+    fn default() -> Self {
+        Self {
+            target_frame_time: 1.0 / 60.0,
+            downgrade_ratio: 1.15,
+            upgrade_ratio: 0.7,
+            window: 60,
+        }
+    }
+}
+
+impl ScalingPolicy for FrameTimePolicy {
+    fn evaluate(
+        &mut self,
+        frame_times: &VecDeque<f32>,
+        _tier: usize,
+        _tier_count: usize,
+    ) -> TierChange {
+        if frame_times.is_empty() {
+            return TierChange::Keep;
+        }
+
+        let average = frame_times.iter().sum::<f32>() / frame_times.len() as f32;
+        if average > self.target_frame_time * self.downgrade_ratio {
+            TierChange::Downgrade
+        } else if average < self.target_frame_time * self.upgrade_ratio {
+            TierChange::Upgrade
+        } else {
+            TierChange::Keep
+        }
+    }
+
+    fn window(&self) -> usize {
+        self.window
+    }
+}
+
+/// An event that is sent when a quality tier is changed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TierChangedEvent {
+    /// Previous tier.
+    pub old_tier: usize,
+    /// Current tier.
+    pub new_tier: usize,
+}
+
+/// Performance scaler monitors frame time and automatically adjusts registered quality knobs
+/// according to a [`ScalingPolicy`]. Quality is defined by a tier index in `0..tier_count` range,
+/// where `0` is the highest quality. Every registered knob decides what each tier means for it.
+///
+/// The scaler is disabled by default, the engine updates it after each rendered frame.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::engine::{
+///     scaling::{
+///         ParticleDensityKnob, QualityKnobContext, RenderScaleKnob, ShadowResolutionKnob,
+///     },
+///     Engine,
+/// };
+///
+/// fn setup_scaling(engine: &mut Engine) {
+///     let scaler = &mut engine.performance_scaler;
+///     scaler.set_tier_count(3);
+///     scaler.add_knob(RenderScaleKnob {
+///         scales: vec![1.0, 0.85, 0.7],
+///     });
+///     scaler.add_knob(ShadowResolutionKnob {
+///         sizes: vec![2048, 1024, 512],
+///     });
+///     scaler.add_knob(ParticleDensityKnob {
+///         densities: vec![1.0, 0.5, 0.25],
+///     });
+///     scaler.add_knob(
+///         |tier: usize, _tier_count: usize, context: &mut QualityKnobContext| {
+///             // Adjust draw distances, LOD biases, etc. here.
+///         },
+///     );
+///     scaler.set_enabled(true);
+/// }
+/// ```
+pub struct PerformanceScaler {
+    enabled: bool,
+    tier: usize,
+    tier_count: usize,
+    cooldown: f32,
+    cooldown_timer: f32,
+    policy: Box<dyn ScalingPolicy>,
+    knobs: Vec<Box<dyn QualityKnob>>,
+    frame_times: VecDeque<f32>,
+    last_frame: Option<instant::Instant>,
+    senders: Vec<Sender<TierChangedEvent>>,
+}
+
+impl Debug for PerformanceScaler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PerformanceScaler: tier {} of {}, {} knobs.",
+            self.tier,
+            self.tier_count,
+            self.knobs.len()
+        )
+    }
+}
+
+impl Default for PerformanceScaler {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tier: 0,
+            tier_count: 3,
+            cooldown: 2.0,
+            cooldown_timer: 0.0,
+            policy: Box::new(FrameTimePolicy::default()),
+            knobs: Default::default(),
+            frame_times: Default::default(),
+            last_frame: None,
+            senders: Default::default(),
+        }
+    }
+}
+
+impl PerformanceScaler {
+    /// Enables or disables the scaler. Disabled scaler does not change the current tier.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.reset_history();
+    }
+
+    /// Returns `true` if the scaler is enabled, `false` - otherwise.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets the total amount of quality tiers. The current tier will be clamped to the new range,
+    /// but the knobs won't be re-applied until the next tier change.
+    pub fn set_tier_count(&mut self, tier_count: usize) {
+        self.tier_count = tier_count.max(1);
+        self.tier = self.tier.min(self.tier_count - 1);
+    }
+
+    /// Returns the total amount of quality tiers.
+    pub fn tier_count(&self) -> usize {
+        self.tier_count
+    }
+
+    /// Returns the current quality tier.
+    pub fn tier(&self) -> usize {
+        self.tier
+    }
+
+    /// Sets the minimum amount of time (in seconds) between two tier changes. It prevents the scaler
+    /// from oscillating between tiers. Default is 2 seconds.
+    pub fn set_cooldown(&mut self, cooldown: f32) {
+        self.cooldown = cooldown.max(0.0);
+    }
+
+    /// Returns the minimum amount of time (in seconds) between two tier changes.
+    pub fn cooldown(&self) -> f32 {
+        self.cooldown
+    }
+
+    /// Sets a new scaling policy. Default is [`FrameTimePolicy`] with default parameters.
+    pub fn set_policy<P: ScalingPolicy + 'static>(&mut self, policy: P) {
+        self.policy = Box::new(policy);
+        self.reset_history();
+    }
+
+    /// Registers a new quality knob.
+    pub fn add_knob<K: QualityKnob + 'static>(&mut self, knob: K) {
+        self.knobs.push(Box::new(knob));
+    }
+
+    /// Removes every registered quality knob.
+    pub fn clear_knobs(&mut self) {
+        self.knobs.clear();
+    }
+
+    /// Adds new subscriber, that will receive an event every time when the tier changes.
+    pub fn subscribe(&mut self, sender: Sender<TierChangedEvent>) {
+        self.senders.push(sender);
+    }
+
+    /// Forces the given quality tier (clamped to the valid range) and applies it to every registered
+    /// knob. It works even if the scaler is disabled.
+    pub fn set_tier(&mut self, tier: usize, context: &mut QualityKnobContext) {
+        let new_tier = tier.min(self.tier_count - 1);
+        let old_tier = std::mem::replace(&mut self.tier, new_tier);
+
+        for knob in self.knobs.iter_mut() {
+            knob.apply(new_tier, self.tier_count, context);
+        }
+
+        self.reset_history();
+        self.cooldown_timer = self.cooldown;
+
+        if old_tier != new_tier {
+            let event = TierChangedEvent { old_tier, new_tier };
+            self.senders.retain(|sender| sender.send(event).is_ok());
+        }
+    }
+
+    /// Registers a frame with the given frame time (in seconds) and changes the current tier if the
+    /// policy decides so. The engine calls this method automatically, you should call it manually
+    /// only if you're using the scaler on your own.
+    pub fn update(&mut self, frame_time: f32, context: &mut QualityKnobContext) {
+        if !self.enabled {
+            return;
+        }
+
+        self.cooldown_timer -= frame_time;
+
+        self.frame_times.push_back(frame_time);
+        let window = self.policy.window().max(1);
+        while self.frame_times.len() > window {
+            self.frame_times.pop_front();
+        }
+
+        if self.frame_times.len() < window || self.cooldown_timer > 0.0 {
+            return;
+        }
+
+        match self
+            .policy
+            .evaluate(&self.frame_times, self.tier, self.tier_count)
+        {
+            TierChange::Downgrade if self.tier + 1 < self.tier_count => {
+                self.set_tier(self.tier + 1, context)
+            }
+            TierChange::Upgrade if self.tier > 0 => self.set_tier(self.tier - 1, context),
+            _ => (),
+        }
+    }
+
+    pub(crate) fn on_frame_rendered(&mut self, context: &mut QualityKnobContext) {
+        let now = instant::Instant::now();
+        if let Some(last_frame) = self.last_frame.replace(now) {
+            self.update((now - last_frame).as_secs_f32(), context);
+        }
+    }
+
+    fn reset_history(&mut self) {
+        self.frame_times.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        engine::scaling::{
+            FrameTimePolicy, ParticleDensityKnob, PerformanceScaler, QualityKnobContext,
+            ScalingPolicy, TierChange,
+        },
+        scene::{
+            base::BaseBuilder,
+            particle_system::{ParticleSystem, ParticleSystemBuilder},
+            Scene, SceneContainer,
+        },
+    };
+    use std::{cell::RefCell, rc::Rc, sync::mpsc};
+
+    const TARGET: f32 = 1.0 / 60.0;
+    const SLOW: f32 = 1.0 / 20.0;
+    const FAST: f32 = 1.0 / 200.0;
+
+    fn policy() -> FrameTimePolicy {
+        FrameTimePolicy {
+            target_frame_time: TARGET,
+            window: 4,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_frame_time_policy() {
+        let mut policy = policy();
+        let mut evaluate =
+            |frame_times: &[f32]| policy.evaluate(&frame_times.iter().cloned().collect(), 1, 3);
+
+        assert_eq!(evaluate(&[]), TierChange::Keep);
+        assert_eq!(evaluate(&[TARGET; 4]), TierChange::Keep);
+        assert_eq!(evaluate(&[SLOW; 4]), TierChange::Downgrade);
+        assert_eq!(evaluate(&[FAST; 4]), TierChange::Upgrade);
+        // Average is what matters, not a single slow frame.
+        assert_eq!(
+            evaluate(&[FAST, FAST, FAST, TARGET * 1.5]),
+            TierChange::Upgrade
+        );
+    }
+
+    fn feed(
+        scaler: &mut PerformanceScaler,
+        scenes: &mut SceneContainer,
+        frame_time: f32,
+        count: usize,
+    ) {
+        for _ in 0..count {
+            scaler.update(
+                frame_time,
+                &mut QualityKnobContext {
+                    renderer: None,
+                    scenes,
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_scaler_steps_down_and_up() {
+        let mut scenes = SceneContainer::new(Default::default());
+        let mut scene = Scene::new();
+        let particle_system =
+            ParticleSystemBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        let scene = scenes.add(scene);
+        let density = |scenes: &SceneContainer| {
+            scenes.try_get(scene).unwrap().graph[particle_system]
+                .cast::<ParticleSystem>()
+                .unwrap()
+                .density()
+        };
+
+        let applied = Rc::new(RefCell::new(Vec::new()));
+        let (sender, receiver) = mpsc::channel();
+
+        let mut scaler = PerformanceScaler::default();
+        scaler.set_tier_count(3);
+        scaler.set_cooldown(0.0);
+        scaler.set_policy(policy());
+        scaler.add_knob(ParticleDensityKnob {
+            densities: vec![1.0, 0.5],
+        });
+        scaler.add_knob({
+            let applied = applied.clone();
+            move |tier: usize, _tier_count: usize, _context: &mut QualityKnobContext| {
+                applied.borrow_mut().push(tier)
+            }
+        });
+        scaler.subscribe(sender);
+
+        // Disabled scaler ignores frame times.
+        feed(&mut scaler, &mut scenes, SLOW, 8);
+        assert_eq!(scaler.tier(), 0);
+
+        scaler.set_enabled(true);
+
+        // Not enough frames to make a decision.
+        feed(&mut scaler, &mut scenes, SLOW, 3);
+        assert_eq!(scaler.tier(), 0);
+        assert_eq!(density(&scenes), 1.0);
+
+        feed(&mut scaler, &mut scenes, SLOW, 1);
+        assert_eq!(scaler.tier(), 1);
+        assert_eq!(density(&scenes), 0.5);
+        feed(&mut scaler, &mut scenes, SLOW, 4);
+        assert_eq!(scaler.tier(), 2);
+        // There is no density for the tier, so the last one is used.
+        assert_eq!(density(&scenes), 0.5);
+        // The lowest quality is reached.
+        feed(&mut scaler, &mut scenes, SLOW, 4);
+        assert_eq!(scaler.tier(), 2);
+
+        // Stable frame time keeps the tier.
+        feed(&mut scaler, &mut scenes, TARGET, 8);
+        assert_eq!(scaler.tier(), 2);
+
+        feed(&mut scaler, &mut scenes, FAST, 4);
+        assert_eq!(scaler.tier(), 1);
+        feed(&mut scaler, &mut scenes, FAST, 4);
+        assert_eq!(scaler.tier(), 0);
+        assert_eq!(density(&scenes), 1.0);
+        // The highest quality is reached.
+        feed(&mut scaler, &mut scenes, FAST, 4);
+        assert_eq!(scaler.tier(), 0);
+
+        assert_eq!(*applied.borrow(), vec![1, 2, 1, 0]);
+        let events = receiver
+            .try_iter()
+            .map(|e| (e.old_tier, e.new_tier))
+            .collect::<Vec<_>>();
+        assert_eq!(events, vec![(0, 1), (1, 2), (2, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_scaler_cooldown() {
+        let mut scenes = SceneContainer::new(Default::default());
+        let mut scaler = PerformanceScaler::default();
+        scaler.set_cooldown(0.5);
+        scaler.set_policy(policy());
+        scaler.set_enabled(true);
+
+        feed(&mut scaler, &mut scenes, SLOW, 4);
+        assert_eq!(scaler.tier(), 1);
+        // The window is full, but the cooldown is not passed yet.
+        feed(&mut scaler, &mut scenes, SLOW, 4);
+        assert_eq!(scaler.tier(), 1);
+        // 11 frames of 50 ms each are enough to pass the cooldown.
+        feed(&mut scaler, &mut scenes, SLOW, 7);
+        assert_eq!(scaler.tier(), 2);
+    }
+}
@@ -159,6 +159,7 @@
 //! | fyrox_cameraPosition       | `Vector3`       | Position of the camera.
 //! | fyrox_usePOM               | `bool`          | Whether to use parallax mapping or not.
 //! | fyrox_lightPosition        | `Vector3`       | Light position.
+//! | fyrox_prevViewProjectionMatrix | `Matrix4`   | World-to-clip-space transform of the previous frame (light's view-projection in shadow passes).
 //!
 //! To use any of the variables, just define a uniform with appropriate name:
 //!
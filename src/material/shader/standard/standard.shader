@@ -47,6 +47,10 @@
             name: "emissionStrength",
             kind: Vector3((2.0, 2.0, 2.0)),
         ),
+        (
+            name: "emissionIntensity",
+            kind: Float(1.0),
+        ),
         (
             name: "diffuseColor",
             kind: Color(r: 255, g: 255, b: 255, a: 255),
@@ -185,6 +189,7 @@
                 uniform vec2 texCoordScale;
                 uniform uint layerIndex;
                 uniform vec3 emissionStrength;
+                uniform float emissionIntensity;
                 uniform vec4 diffuseColor;
 
                 // Define uniforms with reserved names. Fyrox will automatically provide
@@ -228,7 +233,7 @@
                     outMaterial.z = texture(aoTexture, tc).r;
                     outMaterial.a = 1.0;
 
-                    outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
+                    outAmbient.xyz = emissionIntensity * emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
                     outAmbient.a = 1.0;
 
                     outDecalMask = layerIndex;
@@ -327,7 +332,10 @@
            fragment_shader:
                r#"
                 uniform sampler2D diffuseTexture;
+                uniform sampler2D emissionTexture;
                 uniform vec4 diffuseColor;
+                uniform vec3 emissionStrength;
+                uniform float emissionIntensity;
 
                 out vec4 FragColor;
 
@@ -336,6 +344,7 @@
                 void main()
                 {
                     FragColor = diffuseColor * texture(diffuseTexture, texCoord);
+                    FragColor.rgb += emissionIntensity * emissionStrength * texture(emissionTexture, texCoord).rgb;
                 }
                "#,
         ),
@@ -59,6 +59,10 @@
             name: "emissionStrength",
             kind: Vector3((2.0, 2.0, 2.0)),
         ),
+        (
+            name: "emissionIntensity",
+            kind: Float(1.0),
+        ),
         (
             name: "diffuseColor",
             kind: Color(r: 255, g: 255, b: 255, a: 255),
@@ -160,6 +164,7 @@
                 uniform vec2 texCoordScale;
                 uniform uint layerIndex;
                 uniform vec3 emissionStrength;
+                uniform float emissionIntensity;
                 uniform sampler2D maskTexture;
                 uniform vec4 diffuseColor;
 
@@ -198,7 +203,7 @@
                     outMaterial.z = texture(aoTexture, tc).r;
                     outMaterial.a = 1.0;
 
-                    outAmbient.xyz = emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
+                    outAmbient.xyz = emissionIntensity * emissionStrength * texture(emissionTexture, tc).rgb + texture(lightmapTexture, secondTexCoord).rgb;
                     outAmbient.a = 1.0;
 
                     outDecalMask = layerIndex;
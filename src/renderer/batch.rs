@@ -6,6 +6,7 @@ use crate::{
         algebra::{Matrix4, Vector3},
         math::frustum::Frustum,
         sstorage::ImmutableString,
+        uuid::Uuid,
     },
     material::SharedMaterial,
     renderer::framework::geometry_buffer::ElementRange,
@@ -132,6 +133,48 @@ impl Debug for RenderDataBatch {
     }
 }
 
+/// A stage of the frame, at which a [`CustomDrawRequest`] is processed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CustomRenderStage {
+    /// High dynamic range stage. Requests are processed in
+    /// [`crate::renderer::SceneRenderPass::on_hdr_render`], after the scene was lit, but before
+    /// bloom and tone mapping. Suitable for custom geometry that should be affected by post effects
+    /// (custom sky, holograms, etc.).
+    Hdr,
+    /// Low dynamic range stage. Requests are processed in
+    /// [`crate::renderer::SceneRenderPass::on_ldr_render`], after every post effect was applied.
+    /// Suitable for overlays.
+    Ldr,
+}
+
+/// A request of a scene node to be drawn by a custom render pass (see
+/// [`crate::renderer::SceneRenderPass`]). It allows scene nodes to be drawn by bespoke rendering code
+/// (with its own vertex buffers, shaders, etc.) without forking the renderer. A node pushes the
+/// request in [`crate::scene::node::NodeTrait::collect_render_data`] using
+/// [`RenderDataBatchStorage::push_custom_draw_request`] and the render pass with matching id fetches
+/// the requests using [`RenderDataBatchStorage::custom_draw_requests`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CustomDrawRequest {
+    /// Unique identifier of a render pass that should process the request. It is up to the render
+    /// pass which id it uses, a type UUID of the render pass is a good candidate.
+    pub pass_id: Uuid,
+    /// A stage of the frame, at which the request should be processed.
+    pub stage: CustomRenderStage,
+    /// Handle of the node that should be drawn. The render pass has access to the scene, so it could
+    /// fetch any data it needs from the node.
+    pub node: Handle<Node>,
+}
+
+fn hide_hierarchy(graph: &Graph, root: Handle<Node>, filter: &mut [bool]) {
+    let mut stack = vec![root];
+    while let Some(handle) = stack.pop() {
+        if let Some(node) = graph.try_get(handle) {
+            filter[handle.index() as usize] = false;
+            stack.extend_from_slice(node.children());
+        }
+    }
+}
+
 /// Batch storage handles batch generation for a scene before rendering. It is used to optimize
 /// rendering by reducing amount of state changes of OpenGL context.
 #[derive(Default)]
@@ -139,6 +182,7 @@ pub struct RenderDataBatchStorage {
     batch_map: FxHashMap<u64, usize>,
     /// A sorted list of batches.
     pub batches: Vec<RenderDataBatch>,
+    custom_draw_requests: Vec<CustomDrawRequest>,
 }
 
 impl RenderDataBatchStorage {
@@ -155,6 +199,7 @@ impl RenderDataBatchStorage {
         let mut storage = Self {
             batch_map: FxHashMap::with_capacity_and_hasher(capacity, FxBuildHasher::default()),
             batches: Vec::with_capacity(capacity),
+            custom_draw_requests: Default::default(),
         };
 
         let mut lod_filter = vec![true; graph.capacity() as usize];
@@ -177,6 +222,18 @@ impl RenderDataBatchStorage {
             }
         }
 
+        for node in graph.linear_iter() {
+            if let Some(cluster) = node.hlod_cluster() {
+                if cluster.use_proxy(graph, &observer_info.observer_position) {
+                    for &member in cluster.members.iter() {
+                        hide_hierarchy(graph, member, &mut lod_filter);
+                    }
+                } else {
+                    hide_hierarchy(graph, cluster.proxy, &mut lod_filter);
+                }
+            }
+        }
+
         let frustum = Frustum::from_view_projection_matrix(
             observer_info.projection_matrix * observer_info.view_matrix,
         )
@@ -249,8 +306,147 @@ impl RenderDataBatchStorage {
         batch.instances.push(instance_data)
     }
 
+    /// Adds a new custom draw request to the storage. See [`CustomDrawRequest`] docs for more info.
+    pub fn push_custom_draw_request(&mut self, request: CustomDrawRequest) {
+        self.custom_draw_requests.push(request)
+    }
+
+    /// Returns an iterator over custom draw requests for the given render pass and the stage of the
+    /// frame. See [`CustomDrawRequest`] docs for more info.
+    pub fn custom_draw_requests(
+        &self,
+        pass_id: Uuid,
+        stage: CustomRenderStage,
+    ) -> impl Iterator<Item = &CustomDrawRequest> {
+        self.custom_draw_requests
+            .iter()
+            .filter(move |request| request.pass_id == pass_id && request.stage == stage)
+    }
+
     /// Sorts the batches by their respective sort index.
     pub fn sort(&mut self) {
         self.batches.sort_unstable_by_key(|b| b.sort_index);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector3},
+            math::aabb::AxisAlignedBoundingBox,
+            reflect::prelude::*,
+            sstorage::ImmutableString,
+            uuid::{uuid, Uuid},
+            visitor::prelude::*,
+            TypeUuidProvider,
+        },
+        renderer::batch::{
+            CustomDrawRequest, CustomRenderStage, ObserverInfo, RenderContext,
+            RenderDataBatchStorage,
+        },
+        scene::{
+            base::{Base, BaseBuilder},
+            graph::Graph,
+            node::{Node, NodeTrait},
+        },
+    };
+    use std::ops::{Deref, DerefMut};
+
+    const HOLOGRAM_PASS: Uuid = uuid!("4d1b0e5e-7a0e-4bb4-a0e8-1a8ec65b9c37");
+    const OVERLAY_PASS: Uuid = uuid!("9b8e3f4c-2d6a-4f1e-8c7b-5a0d3e2f1c48");
+
+    // A node that is drawn by two custom render passes at different stages.
+    #[derive(Clone, Reflect, Default, Debug)]
+    struct Hologram {
+        base: Base,
+    }
+
+    impl Visit for Hologram {
+        fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+            self.base.visit(name, visitor)
+        }
+    }
+
+    impl Deref for Hologram {
+        type Target = Base;
+
+        fn deref(&self) -> &Self::Target {
+            &self.base
+        }
+    }
+
+    impl DerefMut for Hologram {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.base
+        }
+    }
+
+    impl TypeUuidProvider for Hologram {
+        fn type_uuid() -> Uuid {
+            uuid!("0f6a2c1e-5b3d-4e8f-9a7c-6d2e1b0c3f54")
+        }
+    }
+
+    impl NodeTrait for Hologram {
+        crate::impl_query_component!();
+
+        fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+            self.base.local_bounding_box()
+        }
+
+        fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+            self.base.world_bounding_box()
+        }
+
+        fn id(&self) -> Uuid {
+            Self::type_uuid()
+        }
+
+        fn collect_render_data(&self, ctx: &mut RenderContext) {
+            ctx.storage.push_custom_draw_request(CustomDrawRequest {
+                pass_id: HOLOGRAM_PASS,
+                stage: CustomRenderStage::Hdr,
+                node: ctx.node_handle,
+            });
+            ctx.storage.push_custom_draw_request(CustomDrawRequest {
+                pass_id: OVERLAY_PASS,
+                stage: CustomRenderStage::Ldr,
+                node: ctx.node_handle,
+            });
+        }
+    }
+
+    #[test]
+    fn test_custom_draw_requests() {
+        let mut graph = Graph::new();
+        let hologram = graph.add_node(Node::new(Hologram {
+            base: BaseBuilder::new().build_base(),
+        }));
+
+        let storage = RenderDataBatchStorage::from_graph(
+            &graph,
+            ObserverInfo {
+                observer_position: Vector3::default(),
+                z_near: 0.025,
+                z_far: 2048.0,
+                view_matrix: Matrix4::identity(),
+                projection_matrix: Matrix4::identity(),
+            },
+            ImmutableString::new("Forward"),
+        );
+
+        let requests = |pass_id, stage| {
+            storage
+                .custom_draw_requests(pass_id, stage)
+                .map(|request| request.node)
+                .collect::<Vec<_>>()
+        };
+
+        // Each pass must get only the requests that were addressed to it at the given stage.
+        assert_eq!(requests(HOLOGRAM_PASS, CustomRenderStage::Hdr), [hologram]);
+        assert!(requests(HOLOGRAM_PASS, CustomRenderStage::Ldr).is_empty());
+        assert_eq!(requests(OVERLAY_PASS, CustomRenderStage::Ldr), [hologram]);
+        assert!(requests(OVERLAY_PASS, CustomRenderStage::Hdr).is_empty());
+    }
+}
@@ -5,8 +5,8 @@ use crate::{
         state::PipelineState,
     },
     scene::mesh::surface::{SurfaceData, SurfaceSharedData},
+    utils::array_as_u8_slice,
 };
-use fyrox_core::sparse::AtomicIndex;
 use fyrox_resource::entry::DEFAULT_RESOURCE_LIFETIME;
 
 struct CacheEntry {
@@ -14,71 +14,220 @@ struct CacheEntry {
     data_hash: u64,
     layout_hash: u64,
     time_to_live: f32,
+    // Amount of bytes (vertices first, then triangles) that were uploaded to GPU. `None` if the data
+    // was uploaded at once.
+    uploaded_bytes: Option<usize>,
 }
 
+// A portion of surface data, that should be uploaded to GPU next. Offset and size are in bytes.
+#[derive(Debug, PartialEq, Eq)]
+enum Portion {
+    Vertices { offset: usize, size: usize },
+    Triangles { offset: usize, size: usize },
+}
+
+// Calculates next portion of the data (vertices first, then triangles) that fits in the budget.
+// Returns `None` if the data is fully uploaded or the budget is exhausted.
+fn next_portion(
+    uploaded_bytes: usize,
+    vertices_size: usize,
+    triangles_size: usize,
+    budget: Option<usize>,
+) -> Option<Portion> {
+    let (offset, remaining) = if uploaded_bytes < vertices_size {
+        (uploaded_bytes, vertices_size - uploaded_bytes)
+    } else if uploaded_bytes < vertices_size + triangles_size {
+        let offset = uploaded_bytes - vertices_size;
+        (offset, triangles_size - offset)
+    } else {
+        return None;
+    };
+
+    let size = match budget {
+        Some(0) => return None,
+        Some(budget) => remaining.min(budget),
+        None => remaining,
+    };
+
+    if uploaded_bytes < vertices_size {
+        Some(Portion::Vertices { offset, size })
+    } else {
+        Some(Portion::Triangles { offset, size })
+    }
+}
+
+impl CacheEntry {
+    // Uploads next portion of the data, limited by the budget. Returns `true` if the data is fully
+    // uploaded.
+    fn upload(
+        &mut self,
+        state: &mut PipelineState,
+        data: &SurfaceData,
+        budget: &mut Option<usize>,
+    ) -> bool {
+        let uploaded_bytes = match self.uploaded_bytes.as_mut() {
+            Some(uploaded_bytes) => uploaded_bytes,
+            None => return true,
+        };
+
+        let vertices = data.vertex_buffer.raw_data();
+        let triangles = array_as_u8_slice(data.geometry_buffer.triangles_ref());
+
+        while let Some(portion) =
+            next_portion(*uploaded_bytes, vertices.len(), triangles.len(), *budget)
+        {
+            let size = match portion {
+                Portion::Vertices { offset, size } => {
+                    self.buffer.write_buffer_data(
+                        state,
+                        0,
+                        offset,
+                        &vertices[offset..(offset + size)],
+                    );
+                    size
+                }
+                Portion::Triangles { offset, size } => {
+                    self.buffer
+                        .bind(state)
+                        .write_elements(offset, &triangles[offset..(offset + size)]);
+                    size
+                }
+            };
+
+            *uploaded_bytes += size;
+            if let Some(budget) = budget {
+                *budget -= size;
+            }
+        }
+
+        *uploaded_bytes >= vertices.len() + triangles.len()
+    }
+}
+
+/// Geometry cache uploads surface data to GPU. Large surfaces could be uploaded in portions over
+/// multiple frames, if upload budget is set. Such surfaces are not available for rendering until the
+/// upload is finished.
 #[derive(Default)]
 pub struct GeometryCache {
     buffer: SparseBuffer<CacheEntry>,
+    upload_budget: Option<usize>,
+    budget_left: Option<usize>,
 }
 
 fn create_geometry_buffer(
     data: &SurfaceData,
     state: &mut PipelineState,
     buffer: &mut SparseBuffer<CacheEntry>,
-) -> AtomicIndex {
-    let geometry_buffer =
-        GeometryBuffer::from_surface_data(data, GeometryBufferKind::StaticDraw, state);
+    in_portions: bool,
+) {
+    let (geometry_buffer, uploaded_bytes) = if in_portions {
+        (
+            GeometryBuffer::with_surface_layout(data, GeometryBufferKind::StaticDraw, state),
+            Some(0),
+        )
+    } else {
+        (
+            GeometryBuffer::from_surface_data(data, GeometryBufferKind::StaticDraw, state),
+            None,
+        )
+    };
 
     let index = buffer.spawn(CacheEntry {
         buffer: geometry_buffer,
         time_to_live: DEFAULT_RESOURCE_LIFETIME,
         data_hash: data.content_hash(),
         layout_hash: data.vertex_buffer.layout_hash(),
+        uploaded_bytes,
     });
 
     data.cache_entry.set(index.get());
-
-    index
 }
 
 impl GeometryCache {
+    /// Returns total amount of cached geometry buffers.
+    pub fn count(&self) -> usize {
+        self.buffer.iter().count()
+    }
+
+    /// Returns amount of video memory (in bytes) occupied by the cached geometry buffers.
+    pub fn size_bytes(&self) -> usize {
+        self.buffer
+            .iter()
+            .map(|entry| entry.buffer.size_bytes())
+            .sum()
+    }
+
+    /// Sets the maximum amount of bytes, that could be uploaded to GPU per frame. `None` means that
+    /// there is no limit and every surface is uploaded at once.
+    pub fn set_upload_budget(&mut self, budget: Option<usize>) {
+        self.upload_budget = budget;
+        self.budget_left = budget;
+    }
+
+    /// Returns current upload budget. See [`Self::set_upload_budget`].
+    pub fn upload_budget(&self) -> Option<usize> {
+        self.upload_budget
+    }
+
+    /// Restores upload budget, must be called once per frame.
+    pub fn begin_frame(&mut self) {
+        self.budget_left = self.upload_budget;
+    }
+
+    /// Returns a geometry buffer for the given surface data. Returns `None` if the data is still being
+    /// uploaded to GPU.
     pub fn get<'a>(
         &'a mut self,
         state: &mut PipelineState,
         data: &SurfaceSharedData,
-    ) -> &'a mut GeometryBuffer {
+    ) -> Option<&'a mut GeometryBuffer> {
         scope_profile!();
 
-        let data = data.lock();
+        let mut data = data.lock();
 
+        let mut is_valid = false;
         if let Some(entry) = self.buffer.get_mut(&data.cache_entry) {
             // We also must check if buffer's layout changed, and if so - recreate the entire
             // buffer.
             if entry.layout_hash == data.vertex_buffer.layout_hash() {
                 let data_hash = data.content_hash();
                 if data_hash != entry.data_hash {
-                    // Content has changed, upload new content.
-                    entry
-                        .buffer
-                        .set_buffer_data(state, 0, data.vertex_buffer.raw_data());
-                    entry
-                        .buffer
-                        .bind(state)
-                        .set_triangles(data.geometry_buffer.triangles_ref());
+                    if entry.uploaded_bytes.is_none() {
+                        // Content has changed, upload new content.
+                        entry
+                            .buffer
+                            .set_buffer_data(state, 0, data.vertex_buffer.raw_data());
+                        entry
+                            .buffer
+                            .bind(state)
+                            .set_triangles(data.geometry_buffer.triangles_ref());
 
-                    entry.data_hash = data_hash;
+                        entry.data_hash = data_hash;
+                        is_valid = true;
+                    }
+                    // Otherwise the size of the data could be changed, the buffer will be re-created
+                    // and uploaded in portions again.
+                } else {
+                    is_valid = true;
                 }
 
                 entry.time_to_live = DEFAULT_RESOURCE_LIFETIME;
-
-                &mut self.buffer.get_mut(&data.cache_entry).unwrap().buffer
-            } else {
-                let index = create_geometry_buffer(&data, state, &mut self.buffer);
-                &mut self.buffer.get_mut(&index).unwrap().buffer
             }
+        }
+
+        if !is_valid {
+            create_geometry_buffer(&data, state, &mut self.buffer, self.upload_budget.is_some());
+        }
+
+        let entry = self.buffer.get_mut(&data.cache_entry).unwrap();
+
+        let is_ready = entry.upload(state, &data, &mut self.budget_left);
+        data.is_ready = is_ready;
+
+        if is_ready {
+            Some(&mut entry.buffer)
         } else {
-            let index = create_geometry_buffer(&data, state, &mut self.buffer);
-            &mut self.buffer.get_mut(&index).unwrap().buffer
+            None
         }
     }
 
@@ -102,3 +251,75 @@ impl GeometryCache {
         self.buffer.clear();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::renderer::cache::geometry::{next_portion, Portion};
+
+    #[test]
+    fn test_upload_portions() {
+        // Without a budget everything is uploaded in two portions.
+        assert_eq!(
+            next_portion(0, 100, 30, None),
+            Some(Portion::Vertices {
+                offset: 0,
+                size: 100
+            })
+        );
+        assert_eq!(
+            next_portion(100, 100, 30, None),
+            Some(Portion::Triangles {
+                offset: 0,
+                size: 30
+            })
+        );
+        assert_eq!(next_portion(130, 100, 30, None), None);
+
+        // Simulate budgeted upload over multiple frames.
+        let mut uploaded_bytes = 0;
+        let mut portions = Vec::new();
+        let mut frames = 0;
+        while uploaded_bytes < 130 {
+            frames += 1;
+            let mut budget = Some(40);
+            while let Some(portion) = next_portion(uploaded_bytes, 100, 30, budget) {
+                let size = match portion {
+                    Portion::Vertices { size, .. } | Portion::Triangles { size, .. } => size,
+                };
+                uploaded_bytes += size;
+                budget = budget.map(|budget| budget - size);
+                portions.push(portion);
+            }
+        }
+        assert_eq!(frames, 4);
+        assert_eq!(
+            portions,
+            vec![
+                Portion::Vertices {
+                    offset: 0,
+                    size: 40
+                },
+                Portion::Vertices {
+                    offset: 40,
+                    size: 40
+                },
+                Portion::Vertices {
+                    offset: 80,
+                    size: 20
+                },
+                // The rest of the budget of the third frame is spent on triangles.
+                Portion::Triangles {
+                    offset: 0,
+                    size: 20
+                },
+                Portion::Triangles {
+                    offset: 20,
+                    size: 10
+                },
+            ]
+        );
+
+        // Exhausted budget stops the upload.
+        assert_eq!(next_portion(0, 100, 30, Some(0)), None);
+    }
+}
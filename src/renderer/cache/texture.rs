@@ -23,6 +23,14 @@ pub struct TextureCache {
 }
 
 impl TextureCache {
+    /// Returns approximate amount of video memory (in bytes) occupied by the cached textures.
+    pub fn size_bytes(&self) -> usize {
+        self.map
+            .values()
+            .map(|entry| entry.value.borrow().size_bytes())
+            .sum()
+    }
+
     /// Unconditionally uploads requested texture into GPU memory, previous GPU texture will be automatically
     /// destroyed.
     pub fn upload(
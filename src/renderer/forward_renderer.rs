@@ -84,7 +84,11 @@ impl ForwardRenderer {
             .filter(|b| b.render_path == RenderPath::Forward)
         {
             let material = batch.material.lock();
-            let geometry = geom_cache.get(state, &batch.data);
+            let geometry = match geom_cache.get(state, &batch.data) {
+                Some(geometry) => geometry,
+                // The geometry is still being uploaded to GPU.
+                None => continue,
+            };
             let blend_shapes_storage = batch
                 .data
                 .lock()
@@ -126,6 +130,7 @@ impl ForwardRenderer {
                                 light_position: &Default::default(),
                                 blend_shapes_storage: blend_shapes_storage.as_ref(),
                                 blend_shapes_weights: &instance.blend_shapes_weights,
+                                prev_view_projection_matrix: &camera.prev_view_projection_matrix(),
                                 normal_dummy: normal_dummy.clone(),
                                 white_dummy: white_dummy.clone(),
                                 black_dummy: black_dummy.clone(),
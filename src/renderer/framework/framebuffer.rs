@@ -219,6 +219,33 @@ impl FrameBuffer {
         self.fbo
     }
 
+    /// Reads pixels of the first color attachment (or the back buffer) in the given rectangle. Pixels
+    /// are returned in RGBA8 format, row by row starting from the bottom one.
+    pub fn read_pixels(&self, state: &mut PipelineState, rect: Rect<i32>) -> Vec<u8> {
+        scope_profile!();
+
+        state.set_framebuffer(self.id());
+
+        let mut pixels = vec![0; rect.w().max(0) as usize * rect.h().max(0) as usize * 4];
+
+        unsafe {
+            if self.fbo.is_some() {
+                state.gl.read_buffer(glow::COLOR_ATTACHMENT0);
+            }
+            state.gl.read_pixels(
+                rect.x(),
+                rect.y(),
+                rect.w(),
+                rect.h(),
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(&mut pixels),
+            );
+        }
+
+        pixels
+    }
+
     pub fn clear(
         &mut self,
         state: &mut PipelineState,
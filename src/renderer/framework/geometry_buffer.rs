@@ -211,6 +211,37 @@ impl<'a> GeometryBufferBinding<'a> {
         self
     }
 
+    /// Allocates (but does not fill) video memory for the given amount of triangles. Use
+    /// [`Self::write_elements`] to fill it.
+    pub fn reserve_triangles(self, count: usize) -> Self {
+        scope_profile!();
+
+        assert_eq!(self.buffer.element_kind, ElementKind::Triangle);
+        self.buffer.element_count.set(count);
+
+        unsafe {
+            self.state.gl.buffer_data_size(
+                glow::ELEMENT_ARRAY_BUFFER,
+                (count * size_of::<TriangleDefinition>()) as i32,
+                glow::DYNAMIC_DRAW,
+            );
+        }
+
+        self
+    }
+
+    /// Writes a portion of raw element data at the given offset (in bytes). Element buffer must have
+    /// enough space for the data.
+    pub fn write_elements(&self, offset: usize, data: &[u8]) {
+        scope_profile!();
+
+        unsafe {
+            self.state
+                .gl
+                .buffer_sub_data_u8_slice(glow::ELEMENT_ARRAY_BUFFER, offset as i32, data);
+        }
+    }
+
     unsafe fn set_elements(&self, data: &[u8]) {
         scope_profile!();
 
@@ -307,6 +338,52 @@ impl GeometryBuffer {
         geometry_buffer
     }
 
+    /// Creates a geometry buffer with the layout of the given surface data and allocates (but does not
+    /// fill) video memory for its vertices and triangles. Use [`Self::write_buffer_data`] and
+    /// [`GeometryBufferBinding::write_elements`] to fill the buffer in portions.
+    pub fn with_surface_layout(
+        data: &SurfaceData,
+        kind: GeometryBufferKind,
+        state: &mut PipelineState,
+    ) -> Self {
+        let geometry_buffer = GeometryBufferBuilder::new(ElementKind::Triangle)
+            .with_buffer_builder(
+                BufferBuilder::from_vertex_buffer(&data.vertex_buffer, kind).reserve_only(),
+            )
+            .build(state)
+            .unwrap();
+
+        geometry_buffer
+            .bind(state)
+            .reserve_triangles(data.geometry_buffer.triangles_ref().len());
+
+        geometry_buffer
+    }
+
+    /// Writes a portion of raw data at the given offset (in bytes) of a buffer. The buffer must have
+    /// enough space for the data.
+    pub fn write_buffer_data(
+        &mut self,
+        state: &mut PipelineState,
+        buffer: usize,
+        offset: usize,
+        data: &[u8],
+    ) {
+        scope_profile!();
+
+        let buffer = &mut self.buffers[buffer];
+
+        assert!(offset + data.len() <= buffer.size_bytes);
+
+        state.set_vertex_buffer_object(Some(buffer.id));
+
+        unsafe {
+            state
+                .gl
+                .buffer_sub_data_u8_slice(glow::ARRAY_BUFFER, offset as i32, data);
+        }
+    }
+
     pub fn set_buffer_data<T>(&mut self, state: &mut PipelineState, buffer: usize, data: &[T]) {
         scope_profile!();
 
@@ -356,6 +433,12 @@ impl GeometryBuffer {
     pub fn element_count(&self) -> usize {
         self.element_count.get()
     }
+
+    /// Returns amount of video memory (in bytes) occupied by vertex and element buffers.
+    pub fn size_bytes(&self) -> usize {
+        self.buffers.iter().map(|b| b.size_bytes).sum::<usize>()
+            + self.element_count.get() * self.element_kind.index_per_element() * size_of::<u32>()
+    }
 }
 
 impl Drop for GeometryBuffer {
@@ -433,6 +516,12 @@ impl BufferBuilder {
         }
     }
 
+    /// Forces the builder to only allocate video memory for the data, without uploading the data.
+    pub fn reserve_only(mut self) -> Self {
+        self.data = std::ptr::null();
+        self
+    }
+
     pub fn with_attribute(mut self, attribute: AttributeDefinition) -> Self {
         self.attributes.push(attribute);
         self
@@ -445,11 +534,19 @@ impl BufferBuilder {
 
         if self.data_size > 0 {
             unsafe {
-                state.gl.buffer_data_u8_slice(
-                    glow::ARRAY_BUFFER,
-                    std::slice::from_raw_parts(self.data, self.data_size),
-                    self.kind as u32,
-                );
+                if self.data.is_null() {
+                    state.gl.buffer_data_size(
+                        glow::ARRAY_BUFFER,
+                        self.data_size as i32,
+                        self.kind as u32,
+                    );
+                } else {
+                    state.gl.buffer_data_u8_slice(
+                        glow::ARRAY_BUFFER,
+                        std::slice::from_raw_parts(self.data, self.data_size),
+                        self.kind as u32,
+                    );
+                }
             }
         }
 
@@ -34,6 +34,7 @@ pub enum BuiltInUniform {
     BlendShapesStorage,
     BlendShapesWeights,
     BlendShapesCount,
+    PrevViewProjectionMatrix,
     // Must be last.
     Count,
 }
@@ -438,6 +439,8 @@ fn fetch_built_in_uniform_locations(
         fetch_uniform_location(state, program, "fyrox_blendShapesWeights");
     locations[BuiltInUniform::BlendShapesCount as usize] =
         fetch_uniform_location(state, program, "fyrox_blendShapesCount");
+    locations[BuiltInUniform::PrevViewProjectionMatrix as usize] =
+        fetch_uniform_location(state, program, "fyrox_prevViewProjectionMatrix");
 
     locations
 }
@@ -1114,6 +1114,34 @@ impl GpuTexture {
     pub fn pixel_kind(&self) -> PixelKind {
         self.pixel_kind
     }
+
+    /// Returns approximate amount of video memory (in bytes) occupied by the texture. Mip levels are
+    /// taken into account approximately, using the minification filter of the texture.
+    pub fn size_bytes(&self) -> usize {
+        let base_level_size = match self.kind {
+            GpuTextureKind::Line { length } => image_1d_size_bytes(self.pixel_kind, length),
+            GpuTextureKind::Rectangle { width, height } => {
+                image_2d_size_bytes(self.pixel_kind, width, height)
+            }
+            GpuTextureKind::Cube { width, height } => {
+                6 * image_2d_size_bytes(self.pixel_kind, width, height)
+            }
+            GpuTextureKind::Volume {
+                width,
+                height,
+                depth,
+            } => image_3d_size_bytes(self.pixel_kind, width, height, depth),
+        };
+
+        match self.min_filter {
+            // Full mip chain takes one third of the base level size.
+            MinificationFilter::NearestMipMapNearest
+            | MinificationFilter::NearestMipMapLinear
+            | MinificationFilter::LinearMipMapNearest
+            | MinificationFilter::LinearMipMapLinear => base_level_size * 4 / 3,
+            MinificationFilter::Nearest | MinificationFilter::Linear => base_level_size,
+        }
+    }
 }
 
 impl Drop for GpuTexture {
@@ -35,6 +35,15 @@ float S_LightDistanceAttenuation(float distance, float radius)
     return attenuation;
 }
 
+// Returns physically-based inverse square attenuation, smoothly windowed so it falls to zero at given radius.
+float S_PhysicalLightDistanceAttenuation(float distance, float radius)
+{
+    float ratio = distance / radius;
+    float ratio2 = ratio * ratio;
+    float window = clamp(1.0 - ratio2 * ratio2, 0.0, 1.0);
+    return window * window / max(distance * distance, 0.0001);
+}
+
 // Projects world space position (typical use case) by given matrix.
 vec3 S_Project(vec3 worldPosition, mat4 matrix)
 {
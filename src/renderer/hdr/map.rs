@@ -11,8 +11,12 @@ pub struct MapShader {
     pub hdr_sampler: UniformLocation,
     pub lum_sampler: UniformLocation,
     pub bloom_sampler: UniformLocation,
+    pub bloom_intensity: UniformLocation,
     pub color_map_sampler: UniformLocation,
     pub use_color_grading: UniformLocation,
+    pub blend_color_map_sampler: UniformLocation,
+    pub use_blend_color_grading: UniformLocation,
+    pub color_grading_blend: UniformLocation,
     pub key_value: UniformLocation,
     pub min_luminance: UniformLocation,
     pub max_luminance: UniformLocation,
@@ -35,10 +39,18 @@ impl MapShader {
             lum_sampler: program.uniform_location(state, &ImmutableString::new("lumSampler"))?,
             bloom_sampler: program
                 .uniform_location(state, &ImmutableString::new("bloomSampler"))?,
+            bloom_intensity: program
+                .uniform_location(state, &ImmutableString::new("bloomIntensity"))?,
             color_map_sampler: program
                 .uniform_location(state, &ImmutableString::new("colorMapSampler"))?,
             use_color_grading: program
                 .uniform_location(state, &ImmutableString::new("useColorGrading"))?,
+            blend_color_map_sampler: program
+                .uniform_location(state, &ImmutableString::new("blendColorMapSampler"))?,
+            use_blend_color_grading: program
+                .uniform_location(state, &ImmutableString::new("useBlendColorGrading"))?,
+            color_grading_blend: program
+                .uniform_location(state, &ImmutableString::new("colorGradingBlend"))?,
             key_value: program.uniform_location(state, &ImmutableString::new("keyValue"))?,
             min_luminance: program
                 .uniform_location(state, &ImmutableString::new("minLuminance"))?,
@@ -24,7 +24,7 @@ use crate::{
         },
         make_viewport_matrix, RenderPassStatistics,
     },
-    scene::camera::{ColorGradingLut, Exposure},
+    scene::{camera::Exposure, post_process_volume::PostProcessSettings},
 };
 use std::{cell::RefCell, rc::Rc};
 
@@ -258,18 +258,26 @@ impl HighDynamicRangeRenderer {
         ldr_framebuffer: &mut FrameBuffer,
         viewport: Rect<i32>,
         quad: &GeometryBuffer,
-        exposure: Exposure,
-        color_grading_lut: Option<&ColorGradingLut>,
-        use_color_grading: bool,
+        settings: &PostProcessSettings,
         texture_cache: &mut TextureCache,
     ) -> Result<DrawCallStatistics, FrameworkError> {
         let shader = &self.map_shader;
         let frame_matrix = make_viewport_matrix(viewport);
         let avg_lum = self.adaptation_chain.avg_lum_texture();
 
-        let color_grading_lut_tex = color_grading_lut
-            .and_then(|l| texture_cache.get(state, l.lut_ref()))
-            .unwrap_or_else(|| self.stub_lut.clone());
+        let color_grading_lut_tex = settings
+            .color_grading_lut
+            .as_ref()
+            .and_then(|l| texture_cache.get(state, l.lut_ref()));
+        let blend_color_grading_lut_tex = settings
+            .blend_color_grading_lut
+            .as_ref()
+            .and_then(|l| texture_cache.get(state, l.lut_ref()));
+        let use_color_grading = color_grading_lut_tex.is_some();
+        let use_blend_color_grading = blend_color_grading_lut_tex.is_some();
+        let color_grading_lut_tex = color_grading_lut_tex.unwrap_or_else(|| self.stub_lut.clone());
+        let blend_color_grading_lut_tex =
+            blend_color_grading_lut_tex.unwrap_or_else(|| self.stub_lut.clone());
 
         ldr_framebuffer.draw(
             quad,
@@ -292,13 +300,17 @@ impl HighDynamicRangeRenderer {
                     .set_texture(&shader.lum_sampler, &avg_lum)
                     .set_texture(&shader.bloom_sampler, &bloom_texture)
                     .set_texture(&shader.hdr_sampler, &hdr_scene_frame)
-                    .set_bool(
-                        &shader.use_color_grading,
-                        use_color_grading && color_grading_lut.is_some(),
+                    .set_f32(&shader.bloom_intensity, settings.bloom_intensity)
+                    .set_bool(&shader.use_color_grading, use_color_grading)
+                    .set_texture(&shader.color_map_sampler, &color_grading_lut_tex)
+                    .set_bool(&shader.use_blend_color_grading, use_blend_color_grading)
+                    .set_texture(
+                        &shader.blend_color_map_sampler,
+                        &blend_color_grading_lut_tex,
                     )
-                    .set_texture(&shader.color_map_sampler, &color_grading_lut_tex);
+                    .set_f32(&shader.color_grading_blend, settings.color_grading_blend);
 
-                match exposure {
+                match settings.exposure {
                     Exposure::Auto {
                         key_value,
                         min_luminance,
@@ -329,9 +341,7 @@ impl HighDynamicRangeRenderer {
         viewport: Rect<i32>,
         quad: &GeometryBuffer,
         dt: f32,
-        exposure: Exposure,
-        color_grading_lut: Option<&ColorGradingLut>,
-        use_color_grading: bool,
+        settings: &PostProcessSettings,
         texture_cache: &mut TextureCache,
     ) -> Result<RenderPassStatistics, FrameworkError> {
         let mut stats = RenderPassStatistics::default();
@@ -345,9 +355,7 @@ impl HighDynamicRangeRenderer {
             ldr_framebuffer,
             viewport,
             quad,
-            exposure,
-            color_grading_lut,
-            use_color_grading,
+            settings,
             texture_cache,
         )?;
         Ok(stats)
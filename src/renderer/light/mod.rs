@@ -445,7 +445,7 @@ impl DeferredLightRenderer {
                 light.cast::<SpotLight>()
             {
                 (
-                    spot_light.distance(),
+                    spot_light.effective_distance(),
                     settings.spot_shadows_distance,
                     spot_light.base_light_ref().is_cast_shadows()
                         && distance_to_camera <= settings.spot_shadows_distance
@@ -453,7 +453,7 @@ impl DeferredLightRenderer {
                 )
             } else if let Some(point_light) = light.cast::<PointLight>() {
                 (
-                    point_light.radius(),
+                    point_light.effective_radius(),
                     settings.point_shadows_distance,
                     point_light.base_light_ref().is_cast_shadows()
                         && distance_to_camera <= settings.point_shadows_distance
@@ -729,9 +729,10 @@ impl DeferredLightRenderer {
                             .set_texture(&shader.cookie_texture, &cookie_texture)
                             .set_bool(&shader.cookie_enabled, cookie_enabled)
                             .set_f32(&shader.shadow_bias, spot_light.shadow_bias())
-                            .set_f32(
-                                &shader.light_intensity,
-                                spot_light.base_light_ref().intensity(),
+                            .set_f32(&shader.light_intensity, spot_light.luminous_intensity())
+                            .set_bool(
+                                &shader.physical_attenuation,
+                                spot_light.base_light_ref().is_physically_based(),
                             );
                     },
                 )?
@@ -761,9 +762,10 @@ impl DeferredLightRenderer {
                             .set_matrix4(&shader.wvp_matrix, &frame_matrix)
                             .set_vector3(&shader.camera_position, &camera_global_position)
                             .set_f32(&shader.shadow_bias, point_light.shadow_bias())
-                            .set_f32(
-                                &shader.light_intensity,
-                                point_light.base_light_ref().intensity(),
+                            .set_f32(&shader.light_intensity, point_light.luminous_intensity())
+                            .set_bool(
+                                &shader.physical_attenuation,
+                                point_light.base_light_ref().is_physically_based(),
                             )
                             .set_texture(&shader.depth_sampler, &gbuffer_depth_map)
                             .set_texture(&shader.color_sampler, &gbuffer_diffuse_map)
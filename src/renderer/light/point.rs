@@ -22,6 +22,7 @@ pub struct PointLightShader {
     pub camera_position: UniformLocation,
     pub shadow_bias: UniformLocation,
     pub light_intensity: UniformLocation,
+    pub physical_attenuation: UniformLocation,
 }
 
 impl PointLightShader {
@@ -56,6 +57,8 @@ impl PointLightShader {
             shadow_bias: program.uniform_location(state, &ImmutableString::new("shadowBias"))?,
             light_intensity: program
                 .uniform_location(state, &ImmutableString::new("lightIntensity"))?,
+            physical_attenuation: program
+                .uniform_location(state, &ImmutableString::new("physicalAttenuation"))?,
             program,
         })
     }
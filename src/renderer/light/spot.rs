@@ -29,6 +29,7 @@ pub struct SpotLightShader {
     pub camera_position: UniformLocation,
     pub shadow_bias: UniformLocation,
     pub light_intensity: UniformLocation,
+    pub physical_attenuation: UniformLocation,
 }
 
 impl SpotLightShader {
@@ -77,6 +78,8 @@ impl SpotLightShader {
             shadow_bias: program.uniform_location(state, &ImmutableString::new("shadowBias"))?,
             light_intensity: program
                 .uniform_location(state, &ImmutableString::new("lightIntensity"))?,
+            physical_attenuation: program
+                .uniform_location(state, &ImmutableString::new("physicalAttenuation"))?,
             program,
         })
     }
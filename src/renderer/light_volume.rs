@@ -193,7 +193,8 @@ impl LightVolumeRenderer {
             // calculations, it will significantly reduce amount of pixels for far lights thus
             // significantly improve performance.
 
-            let k = (spot.full_cone_angle() * 0.5 + 1.0f32.to_radians()).tan() * spot.distance();
+            let k = (spot.full_cone_angle() * 0.5 + 1.0f32.to_radians()).tan()
+                * spot.effective_distance();
             let light_shape_matrix = Isometry3 {
                 rotation: graph.global_rotation(light_handle),
                 translation: Translation {
@@ -201,7 +202,7 @@ impl LightVolumeRenderer {
                 },
             }
             .to_homogeneous()
-                * Matrix4::new_nonuniform_scaling(&Vector3::new(k, spot.distance(), k));
+                * Matrix4::new_nonuniform_scaling(&Vector3::new(k, spot.effective_distance(), k));
             let mvp = view_proj * light_shape_matrix;
 
             // Clear stencil only.
@@ -293,7 +294,7 @@ impl LightVolumeRenderer {
             // Radius bias is used to to slightly increase sphere radius to add small margin
             // for fadeout effect. It is set to 5%.
             let bias = 1.05;
-            let k = bias * point.radius();
+            let k = bias * point.effective_radius();
             let light_shape_matrix = Matrix4::new_translation(&light.global_position())
                 * Matrix4::new_nonuniform_scaling(&Vector3::new(k, k, k));
             let mvp = view_proj * light_shape_matrix;
@@ -364,7 +365,7 @@ impl LightVolumeRenderer {
                         .set_matrix4(&shader.inv_proj, &inv_proj)
                         .set_vector3(&shader.light_position, &position)
                         .set_texture(&shader.depth_sampler, &depth_map)
-                        .set_f32(&shader.light_radius, point.radius())
+                        .set_f32(&shader.light_radius, point.effective_radius())
                         .set_vector3(
                             &shader.light_color,
                             &point.base_light_ref().color().srgb_to_linear_f32().xyz(),
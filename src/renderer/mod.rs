@@ -19,8 +19,10 @@ pub mod debug_renderer;
 pub mod renderer2d;
 pub mod storage;
 pub mod ui_renderer;
+pub mod warmup;
 
 mod bloom;
+mod dithering;
 mod flat_shader;
 mod forward_renderer;
 mod fxaa;
@@ -57,6 +59,7 @@ use crate::{
         bloom::BloomRenderer,
         cache::{geometry::GeometryCache, shader::ShaderCache, texture::TextureCache, CacheEntry},
         debug_renderer::DebugRenderer,
+        dithering::DitheringRenderer,
         flat_shader::FlatShader,
         forward_renderer::{ForwardRenderContext, ForwardRenderer},
         framework::{
@@ -81,9 +84,13 @@ use crate::{
         sprite_renderer::{SpriteRenderContext, SpriteRenderer},
         storage::MatrixStorageCache,
         ui_renderer::{UiRenderContext, UiRenderer},
+        warmup::{ItemStatus, WarmUpItem, WarmUpProgress, WarmUpQueue},
     },
     resource::texture::{Texture, TextureKind, TextureResource},
-    scene::{camera::Camera, mesh::surface::SurfaceData, Scene, SceneContainer},
+    scene::{
+        camera::Camera, mesh::surface::SurfaceData, post_process_volume::PostProcessSettings,
+        Scene, SceneContainer,
+    },
 };
 use fxhash::FxHashMap;
 use glow::HasContext;
@@ -162,6 +169,40 @@ impl Display for Statistics {
     }
 }
 
+/// Approximate amount of video memory held by the renderer caches. See [`Renderer::memory_usage`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct RendererMemoryUsage {
+    /// Amount of textures in the texture cache.
+    pub texture_count: usize,
+    /// Approximate amount of video memory (in bytes) occupied by the texture cache.
+    pub texture_bytes: usize,
+    /// Amount of geometry buffers (uploaded mesh surfaces) in the geometry cache.
+    pub geometry_buffer_count: usize,
+    /// Amount of video memory (in bytes) occupied by the geometry cache.
+    pub geometry_buffer_bytes: usize,
+}
+
+impl RendererMemoryUsage {
+    /// Returns total amount of video memory (in bytes) occupied by the renderer caches.
+    pub fn total_bytes(&self) -> usize {
+        self.texture_bytes + self.geometry_buffer_bytes
+    }
+}
+
+impl Display for RendererMemoryUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Textures: {} ({:.2} Mb)\n\
+            Geometry Buffers: {} ({:.2} Mb)",
+            self.texture_count,
+            self.texture_bytes as f32 / (1024.0 * 1024.0),
+            self.geometry_buffer_count,
+            self.geometry_buffer_bytes as f32 / (1024.0 * 1024.0),
+        )
+    }
+}
+
 /// GPU statistics for single frame.
 #[derive(Debug, Copy, Clone, Default)]
 pub struct RenderPassStatistics {
@@ -714,6 +755,7 @@ pub struct Renderer {
     statistics: Statistics,
     quad: GeometryBuffer,
     frame_size: (u32, u32),
+    render_scale: f32,
     quality_settings: QualitySettings,
     /// Debug renderer instance can be used for debugging purposes
     pub debug_renderer: DebugRenderer,
@@ -726,6 +768,7 @@ pub struct Renderer {
     geometry_cache: GeometryCache,
     forward_renderer: ForwardRenderer,
     fxaa_renderer: FxaaRenderer,
+    dithering_renderer: DitheringRenderer,
     renderer2d: Renderer2d,
     texture_event_receiver: Receiver<ResourceEvent>,
     shader_event_receiver: Receiver<ResourceEvent>,
@@ -865,6 +908,45 @@ pub struct SceneRenderPassContext<'a, 'b> {
 }
 
 /// A trait for custom scene rendering pass. It could be used to add your own rendering techniques.
+///
+/// ## Per-node drawing
+///
+/// Scene nodes could ask a render pass to draw them by pushing a [`batch::CustomDrawRequest`] in
+/// [`crate::scene::node::NodeTrait::collect_render_data`]. This allows a plugin to implement bespoke
+/// effects (custom sky, holograms, etc.) with its own geometry and shaders, while the nodes decide
+/// whether they should be drawn and at which stage of the frame:
+///
+/// ```rust
+/// use fyrox::{
+///     core::uuid::{uuid, Uuid},
+///     renderer::{
+///         batch::CustomRenderStage, framework::error::FrameworkError, RenderPassStatistics,
+///         SceneRenderPass, SceneRenderPassContext,
+///     },
+/// };
+///
+/// const HOLOGRAM_PASS: Uuid = uuid!("4d1b0e5e-7a0e-4bb4-a0e8-1a8ec65b9c37");
+///
+/// struct HologramPass;
+///
+/// impl SceneRenderPass for HologramPass {
+///     fn on_hdr_render(
+///         &mut self,
+///         ctx: SceneRenderPassContext,
+///     ) -> Result<RenderPassStatistics, FrameworkError> {
+///         let view_projection = ctx.camera.view_projection_matrix();
+///         for request in ctx
+///             .batch_storage
+///             .custom_draw_requests(HOLOGRAM_PASS, CustomRenderStage::Hdr)
+///         {
+///             let _world_view_projection =
+///                 view_projection * ctx.scene.graph[request.node].global_transform();
+///             // Draw the node using own geometry and shader here.
+///         }
+///         Ok(Default::default())
+///     }
+/// }
+/// ```
 pub trait SceneRenderPass {
     /// Renders scene into high dynamic range target. It will be called for **each** scene
     /// registered in the engine, but you are able to filter out scene by its handle.
@@ -946,6 +1028,7 @@ pub(crate) struct MaterialContext<'a, 'b, 'c> {
     pub light_position: &'a Vector3<f32>,
     pub blend_shapes_storage: Option<&'a TextureResource>,
     pub blend_shapes_weights: &'a [f32],
+    pub prev_view_projection_matrix: &'a Matrix4<f32>,
 
     // Fallback samplers.
     pub normal_dummy: Rc<RefCell<GpuTexture>>,
@@ -1017,6 +1100,10 @@ pub(crate) fn apply_material(ctx: MaterialContext) {
         ctx.program_binding
             .set_i32(location, ctx.blend_shapes_weights.len() as i32);
     }
+    if let Some(location) = &built_in_uniforms[BuiltInUniform::PrevViewProjectionMatrix as usize] {
+        ctx.program_binding
+            .set_matrix4(location, ctx.prev_view_projection_matrix);
+    }
 
     // Apply material properties.
     for (name, value) in ctx.material.properties() {
@@ -1231,6 +1318,7 @@ impl Renderer {
             ),
             ui_renderer: UiRenderer::new(&mut state)?,
             particle_system_renderer: ParticleSystemRenderer::new(&mut state)?,
+            render_scale: 1.0,
             quality_settings: settings,
             debug_renderer: DebugRenderer::new(&mut state)?,
             scene_data_map: Default::default(),
@@ -1240,6 +1328,7 @@ impl Renderer {
             forward_renderer: ForwardRenderer::new(),
             ui_frame_buffers: Default::default(),
             fxaa_renderer: FxaaRenderer::new(&mut state)?,
+            dithering_renderer: DitheringRenderer::new(&mut state)?,
             statistics: Statistics::default(),
             renderer2d: Renderer2d::new(&mut state)?,
             shader_event_receiver,
@@ -1282,6 +1371,97 @@ impl Renderer {
         self.statistics
     }
 
+    /// Returns approximate amount of video memory held by the texture and geometry caches of the
+    /// renderer. Keep in mind that the caches are filled lazily during rendering, and their entries
+    /// are removed after some time of inactivity.
+    pub fn memory_usage(&self) -> RendererMemoryUsage {
+        RendererMemoryUsage {
+            texture_count: self.texture_cache.map.len(),
+            texture_bytes: self.texture_cache.size_bytes(),
+            geometry_buffer_count: self.geometry_cache.count(),
+            geometry_buffer_bytes: self.geometry_cache.size_bytes(),
+        }
+    }
+
+    /// Processes the warm-up queue: compiles shaders and uploads textures and geometry to GPU, until the
+    /// queue is empty or the given time budget is exceeded (at least one item is processed per call).
+    /// Call this method every frame while a loading screen is shown, until returned progress says that
+    /// the warm-up is finished. See [`WarmUpQueue`] docs for more info.
+    ///
+    /// Keep in mind, that uploaded resources are still subject to the usual cache lifetime, so the scene
+    /// should be shown shortly after the warm-up.
+    pub fn warm_up(
+        &mut self,
+        queue: &mut WarmUpQueue,
+        time_budget: std::time::Duration,
+    ) -> WarmUpProgress {
+        scope_profile!();
+
+        let start = instant::Instant::now();
+
+        // Every item is checked at most once per call, pending items are moved to the back of the queue.
+        let mut remaining = queue.items.len();
+        while remaining > 0 {
+            remaining -= 1;
+
+            let item = match queue.items.pop_front() {
+                Some(item) => item,
+                None => break,
+            };
+
+            let status = match item {
+                WarmUpItem::Shader(ref shader) => {
+                    let status = warmup::resource_status(shader);
+                    if let ItemStatus::Ready = status {
+                        let _ = self.shader_cache.get(&mut self.state, shader);
+                    }
+                    status
+                }
+                WarmUpItem::Texture(ref texture) => {
+                    let status = warmup::resource_status(texture);
+                    if let ItemStatus::Ready = status {
+                        let _ = self.texture_cache.get(&mut self.state, texture);
+                    }
+                    status
+                }
+                WarmUpItem::Geometry(ref data) => {
+                    // Large geometry could be uploaded in portions over multiple calls.
+                    if self.geometry_cache.get(&mut self.state, data).is_some() {
+                        ItemStatus::Ready
+                    } else {
+                        ItemStatus::Pending
+                    }
+                }
+            };
+
+            match status {
+                ItemStatus::Ready | ItemStatus::Failed => queue.processed += 1,
+                ItemStatus::Pending => queue.items.push_back(item),
+            }
+
+            if instant::Instant::now() - start >= time_budget {
+                break;
+            }
+        }
+
+        queue.progress()
+    }
+
+    /// Sets the maximum amount of bytes of vertex and index data, that could be uploaded to GPU per
+    /// frame. Large meshes will be uploaded in portions over multiple frames and won't be rendered until
+    /// they're fully uploaded (see [`crate::scene::mesh::surface::SurfaceData::is_ready`]). This
+    /// prevents long stalls of the render thread, when huge meshes are streamed in. `None` (default)
+    /// means that there is no limit and every mesh is uploaded at once, when it is rendered for the
+    /// first time.
+    pub fn set_geometry_upload_budget(&mut self, budget: Option<usize>) {
+        self.geometry_cache.set_upload_budget(budget);
+    }
+
+    /// Returns current geometry upload budget. See [`Self::set_geometry_upload_budget`].
+    pub fn geometry_upload_budget(&self) -> Option<usize> {
+        self.geometry_cache.upload_budget()
+    }
+
     /// Unloads texture from GPU memory.
     pub fn unload_texture(&mut self, texture: TextureResource) {
         self.texture_cache.unload(texture)
@@ -1325,6 +1505,20 @@ impl Renderer {
         Vector2::new(self.frame_size.0 as f32, self.frame_size.1 as f32)
     }
 
+    /// Sets a scale of the resolution of scenes that are rendered into the back buffer, relative to
+    /// the size of the back buffer. Such scenes are rendered in a lower resolution and then stretched
+    /// to the back buffer, which significantly reduces fill rate at cost of image sharpness. Scenes
+    /// with render targets are not affected. The value is clamped to `[0.1; 1.0]` range, default
+    /// is `1.0`.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.render_scale = scale.clamp(0.1, 1.0);
+    }
+
+    /// Returns current scale of the resolution of scenes that are rendered into the back buffer.
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
     /// Sets new quality settings for renderer. Never call this method in a loop, otherwise
     /// you may get **significant** lags. Always check if current quality setting differs
     /// from new!
@@ -1421,6 +1615,54 @@ impl Renderer {
         Ok(())
     }
 
+    /// Renders the given scene into its render target right away and reads the resulting image back
+    /// from GPU memory. The scene does not need to be added to the scene container, so the method could
+    /// be used to generate previews of scenes and prefabs (see [`crate::utils::thumbnail`]). The scene must
+    /// have a rectangle render target, its size defines the size of the image. Returns pixels of the
+    /// image in RGBA8 format, row by row starting from the bottom one (the way OpenGL stores them).
+    /// GPU resources of the scene are released right after the image is read back.
+    ///
+    /// This method is slow, because it waits until the GPU finishes rendering, so it should not be used
+    /// every frame.
+    pub fn render_scene_to_pixels(&mut self, scene: &Scene) -> Result<Vec<u8>, FrameworkError> {
+        scope_profile!();
+
+        let (width, height) = match scene.render_target.as_ref().map(|rt| rt.data_ref().kind()) {
+            Some(TextureKind::Rectangle { width, height }) => (width as i32, height as i32),
+            _ => {
+                return Err(FrameworkError::Custom(
+                    "Only a scene with rectangle render target can be rendered to pixels!"
+                        .to_string(),
+                ))
+            }
+        };
+
+        self.matrix_storage.begin_frame();
+        self.geometry_cache.begin_frame();
+        self.state.invalidate_resource_bindings_cache();
+
+        // The scene is not in the scene container, so its associated data is stored using an empty
+        // handle.
+        let result = self.render_scene(Handle::NONE, scene, 0.0).and_then(|_| {
+            let scene_associated_data =
+                self.scene_data_map.get(&Handle::NONE).ok_or_else(|| {
+                    FrameworkError::Custom("Associated scene data must exist!".to_string())
+                })?;
+            Ok(scene_associated_data
+                .ldr_scene_framebuffer
+                .read_pixels(&mut self.state, Rect::new(0, 0, width, height)))
+        });
+
+        // The scene is rendered only once, so its associated data and the render target texture
+        // must not stay in memory.
+        self.scene_data_map.remove(&Handle::NONE);
+        if let Some(render_target) = scene.render_target.clone() {
+            self.texture_cache.unload(render_target);
+        }
+
+        result
+    }
+
     fn update_texture_cache(&mut self, dt: f32) {
         // Maximum amount of textures uploaded to GPU per frame. This defines throughput **only** for
         // requests from resource manager. This is needed to prevent huge lag when there are tons of
@@ -1489,6 +1731,7 @@ impl Renderer {
         }
 
         self.matrix_storage.begin_frame();
+        self.geometry_cache.begin_frame();
 
         // Make sure to drop associated data for destroyed scenes.
         self.scene_data_map
@@ -1515,342 +1758,386 @@ impl Renderer {
         let backbuffer_height = self.frame_size.1 as f32;
 
         for (scene_handle, scene) in scenes.pair_iter().filter(|(_, s)| s.enabled) {
-            let graph = &scene.graph;
-
-            let frame_size = scene
-                .render_target
-                .as_ref()
-                .map_or_else(
-                    // Use either backbuffer size
-                    || Vector2::new(backbuffer_width, backbuffer_height),
-                    // Or framebuffer size
-                    |rt| {
-                        if let TextureKind::Rectangle { width, height } = rt.data_ref().kind() {
-                            Vector2::new(width as f32, height as f32)
-                        } else {
-                            panic!("only rectangle textures can be used as render target!")
-                        }
-                    },
-                )
-                // Clamp to [1.0; infinity] range.
-                .sup(&Vector2::new(1.0, 1.0));
-
-            let state = &mut self.state;
-
-            let scene_associated_data = self
-                .scene_data_map
-                .entry(scene_handle)
-                .and_modify(|data| {
-                    if data.gbuffer.width != frame_size.x as i32
-                        || data.gbuffer.height != frame_size.y as i32
-                    {
-                        let width = frame_size.x as usize;
-                        let height = frame_size.y as usize;
-
-                        Log::info(format!(
-                            "Associated scene rendering data was re-created for scene {}, because render frame size was changed. Old is {}x{}, new {}x{}!",
-                            scene_handle,
-                            data.gbuffer.width,data.gbuffer.height,width,height
-                        ));
+            self.render_scene(scene_handle, scene, dt)?;
+        }
+
+        self.pipeline_state()
+            .set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
 
-                        *data = AssociatedSceneData::new(state, width, height).unwrap();
+        // Render UI on top of everything without gamma correction.
+        self.statistics += self.ui_renderer.render(UiRenderContext {
+            state: &mut self.state,
+            viewport: window_viewport,
+            frame_buffer: &mut self.backbuffer,
+            frame_width: backbuffer_width,
+            frame_height: backbuffer_height,
+            drawing_context,
+            white_dummy: self.white_dummy.clone(),
+            texture_cache: &mut self.texture_cache,
+        })?;
+
+        Ok(())
+    }
+
+    fn render_scene(
+        &mut self,
+        scene_handle: Handle<Scene>,
+        scene: &Scene,
+        dt: f32,
+    ) -> Result<(), FrameworkError> {
+        let window_viewport = Rect::new(0, 0, self.frame_size.0 as i32, self.frame_size.1 as i32);
+        let backbuffer_width = self.frame_size.0 as f32;
+        let backbuffer_height = self.frame_size.1 as f32;
+        let render_scale = self.render_scale;
+
+        let graph = &scene.graph;
+
+        let frame_size = scene
+            .render_target
+            .as_ref()
+            .map_or_else(
+                // Use either scaled backbuffer size
+                || {
+                    Vector2::new(
+                        (backbuffer_width * render_scale).round(),
+                        (backbuffer_height * render_scale).round(),
+                    )
+                },
+                // Or framebuffer size
+                |rt| {
+                    if let TextureKind::Rectangle { width, height } = rt.data_ref().kind() {
+                        Vector2::new(width as f32, height as f32)
+                    } else {
+                        panic!("only rectangle textures can be used as render target!")
                     }
-                })
-                .or_insert_with(|| {
+                },
+            )
+            // Clamp to [1.0; infinity] range.
+            .sup(&Vector2::new(1.0, 1.0));
+
+        let state = &mut self.state;
+
+        let scene_associated_data = self
+            .scene_data_map
+            .entry(scene_handle)
+            .and_modify(|data| {
+                if data.gbuffer.width != frame_size.x as i32
+                    || data.gbuffer.height != frame_size.y as i32
+                {
                     let width = frame_size.x as usize;
                     let height = frame_size.y as usize;
 
                     Log::info(format!(
-                        "A new associated scene rendering data was created for scene {}!",
-                        scene_handle
+                        "Associated scene rendering data was re-created for scene {}, because render frame size was changed. Old is {}x{}, new {}x{}!",
+                        scene_handle,
+                        data.gbuffer.width,data.gbuffer.height,width,height
                     ));
 
-                    AssociatedSceneData::new(state, width, height).unwrap()
-                });
-
-            // If we specified a texture to draw to, we have to register it in texture cache
-            // so it can be used in later on as texture. This is useful in case if you need
-            // to draw something on offscreen and then draw it on some mesh.
-            // TODO: However it can be dangerous to use frame texture as it may be bound to
-            //  pipeline.
-            if let Some(rt) = scene.render_target.clone() {
-                self.texture_cache.map.insert(
-                    rt.key(),
-                    CacheEntry {
-                        value: scene_associated_data.ldr_scene_frame_texture(),
-                        time_to_live: f32::INFINITY,
-                        value_hash: 0, // TODO
-                    },
-                );
-            }
-
-            for camera in graph
-                .linear_iter()
-                .filter_map(|node| node.cast::<Camera>().filter(|&camera| camera.is_enabled()))
-            {
-                let viewport = camera.viewport_pixels(frame_size);
-
-                let batch_storage = RenderDataBatchStorage::from_graph(
-                    graph,
-                    ObserverInfo {
-                        observer_position: camera.global_position(),
-                        z_near: camera.projection().z_near(),
-                        z_far: camera.projection().z_far(),
-                        view_matrix: camera.view_matrix(),
-                        projection_matrix: camera.projection_matrix(),
-                    },
-                    GBUFFER_PASS_NAME.clone(),
-                );
-
-                state.set_polygon_fill_mode(
-                    PolygonFace::FrontAndBack,
-                    scene.polygon_rasterization_mode,
-                );
-
-                self.statistics += scene_associated_data.gbuffer.fill(GBufferRenderContext {
-                    state,
-                    camera,
-                    geom_cache: &mut self.geometry_cache,
-                    batch_storage: &batch_storage,
-                    texture_cache: &mut self.texture_cache,
-                    shader_cache: &mut self.shader_cache,
-                    environment_dummy: self.environment_dummy.clone(),
-                    use_parallax_mapping: self.quality_settings.use_parallax_mapping,
-                    normal_dummy: self.normal_dummy.clone(),
-                    white_dummy: self.white_dummy.clone(),
-                    black_dummy: self.black_dummy.clone(),
-                    volume_dummy: self.volume_dummy.clone(),
-                    graph,
-                    matrix_storage: &mut self.matrix_storage,
-                })?;
-
-                state.set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
-
-                scene_associated_data.copy_depth_stencil_to_scene_framebuffer(state);
+                    *data = AssociatedSceneData::new(state, width, height).unwrap();
+                }
+            })
+            .or_insert_with(|| {
+                let width = frame_size.x as usize;
+                let height = frame_size.y as usize;
+
+                Log::info(format!(
+                    "A new associated scene rendering data was created for scene {}!",
+                    scene_handle
+                ));
+
+                AssociatedSceneData::new(state, width, height).unwrap()
+            });
+
+        // If we specified a texture to draw to, we have to register it in texture cache
+        // so it can be used in later on as texture. This is useful in case if you need
+        // to draw something on offscreen and then draw it on some mesh.
+        // TODO: However it can be dangerous to use frame texture as it may be bound to
+        //  pipeline.
+        if let Some(rt) = scene.render_target.clone() {
+            self.texture_cache.map.insert(
+                rt.key(),
+                CacheEntry {
+                    value: scene_associated_data.ldr_scene_frame_texture(),
+                    time_to_live: f32::INFINITY,
+                    value_hash: 0, // TODO
+                },
+            );
+        }
 
-                scene_associated_data.hdr_scene_framebuffer.clear(
-                    state,
-                    viewport,
-                    Some(self.backbuffer_clear_color),
-                    None, // Keep depth, we've just copied valid data in it.
-                    Some(0),
-                );
-
-                let (pass_stats, light_stats) =
-                    self.deferred_light_renderer
-                        .render(DeferredRendererContext {
-                            state,
+        for camera in graph
+            .linear_iter()
+            .filter_map(|node| node.cast::<Camera>().filter(|&camera| camera.is_enabled()))
+        {
+            let viewport = camera.viewport_pixels(frame_size);
+
+            let batch_storage = RenderDataBatchStorage::from_graph(
+                graph,
+                ObserverInfo {
+                    observer_position: camera.global_position(),
+                    z_near: camera.projection().z_near(),
+                    z_far: camera.projection().z_far(),
+                    view_matrix: camera.view_matrix(),
+                    projection_matrix: camera.projection_matrix(),
+                },
+                GBUFFER_PASS_NAME.clone(),
+            );
+
+            state
+                .set_polygon_fill_mode(PolygonFace::FrontAndBack, scene.polygon_rasterization_mode);
+
+            self.statistics += scene_associated_data.gbuffer.fill(GBufferRenderContext {
+                state,
+                camera,
+                geom_cache: &mut self.geometry_cache,
+                batch_storage: &batch_storage,
+                texture_cache: &mut self.texture_cache,
+                shader_cache: &mut self.shader_cache,
+                environment_dummy: self.environment_dummy.clone(),
+                use_parallax_mapping: self.quality_settings.use_parallax_mapping,
+                normal_dummy: self.normal_dummy.clone(),
+                white_dummy: self.white_dummy.clone(),
+                black_dummy: self.black_dummy.clone(),
+                volume_dummy: self.volume_dummy.clone(),
+                graph,
+                matrix_storage: &mut self.matrix_storage,
+            })?;
+
+            state.set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
+
+            scene_associated_data.copy_depth_stencil_to_scene_framebuffer(state);
+
+            scene_associated_data.hdr_scene_framebuffer.clear(
+                state,
+                viewport,
+                Some(self.backbuffer_clear_color),
+                None, // Keep depth, we've just copied valid data in it.
+                Some(0),
+            );
+
+            let (pass_stats, light_stats) =
+                self.deferred_light_renderer
+                    .render(DeferredRendererContext {
+                        state,
+                        scene,
+                        camera,
+                        gbuffer: &mut scene_associated_data.gbuffer,
+                        white_dummy: self.white_dummy.clone(),
+                        ambient_color: scene.ambient_lighting_color,
+                        settings: &self.quality_settings,
+                        textures: &mut self.texture_cache,
+                        geometry_cache: &mut self.geometry_cache,
+                        frame_buffer: &mut scene_associated_data.hdr_scene_framebuffer,
+                        shader_cache: &mut self.shader_cache,
+                        normal_dummy: self.normal_dummy.clone(),
+                        black_dummy: self.black_dummy.clone(),
+                        volume_dummy: self.volume_dummy.clone(),
+                        matrix_storage: &mut self.matrix_storage,
+                    })?;
+
+            self.statistics.lighting += light_stats;
+            self.statistics.geometry += pass_stats;
+
+            let depth = scene_associated_data.gbuffer.depth();
+
+            self.statistics +=
+                self.particle_system_renderer
+                    .render(ParticleSystemRenderContext {
+                        state,
+                        framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
+                        graph,
+                        camera,
+                        white_dummy: self.white_dummy.clone(),
+                        depth,
+                        frame_width: frame_size.x,
+                        frame_height: frame_size.y,
+                        viewport,
+                        texture_cache: &mut self.texture_cache,
+                    })?;
+
+            self.statistics += self.sprite_renderer.render(SpriteRenderContext {
+                state,
+                framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
+                graph,
+                camera,
+                white_dummy: self.white_dummy.clone(),
+                viewport,
+                textures: &mut self.texture_cache,
+            })?;
+
+            self.statistics += self.renderer2d.render(
+                state,
+                camera,
+                &mut scene_associated_data.hdr_scene_framebuffer,
+                viewport,
+                graph,
+                &mut self.texture_cache,
+                self.white_dummy.clone(),
+                scene.ambient_lighting_color,
+            )?;
+
+            self.statistics += self.forward_renderer.render(ForwardRenderContext {
+                state,
+                camera,
+                geom_cache: &mut self.geometry_cache,
+                texture_cache: &mut self.texture_cache,
+                shader_cache: &mut self.shader_cache,
+                batch_storage: &batch_storage,
+                framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
+                viewport,
+                quality_settings: &self.quality_settings,
+                white_dummy: self.white_dummy.clone(),
+                normal_dummy: self.normal_dummy.clone(),
+                black_dummy: self.black_dummy.clone(),
+                volume_dummy: self.volume_dummy.clone(),
+                matrix_storage: &mut self.matrix_storage,
+            })?;
+
+            for render_pass in self.scene_render_passes.iter() {
+                self.statistics +=
+                    render_pass
+                        .borrow_mut()
+                        .on_hdr_render(SceneRenderPassContext {
+                            pipeline_state: state,
+                            texture_cache: &mut self.texture_cache,
+                            geometry_cache: &mut self.geometry_cache,
+                            quality_settings: &self.quality_settings,
+                            batch_storage: &batch_storage,
+                            viewport,
                             scene,
                             camera,
-                            gbuffer: &mut scene_associated_data.gbuffer,
+                            scene_handle,
                             white_dummy: self.white_dummy.clone(),
-                            ambient_color: scene.ambient_lighting_color,
-                            settings: &self.quality_settings,
-                            textures: &mut self.texture_cache,
-                            geometry_cache: &mut self.geometry_cache,
-                            frame_buffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                            shader_cache: &mut self.shader_cache,
                             normal_dummy: self.normal_dummy.clone(),
+                            metallic_dummy: self.metallic_dummy.clone(),
+                            environment_dummy: self.environment_dummy.clone(),
                             black_dummy: self.black_dummy.clone(),
-                            volume_dummy: self.volume_dummy.clone(),
-                            matrix_storage: &mut self.matrix_storage,
-                        })?;
-
-                self.statistics.lighting += light_stats;
-                self.statistics.geometry += pass_stats;
-
-                let depth = scene_associated_data.gbuffer.depth();
-
-                self.statistics +=
-                    self.particle_system_renderer
-                        .render(ParticleSystemRenderContext {
-                            state,
+                            depth_texture: scene_associated_data.gbuffer.depth(),
+                            normal_texture: scene_associated_data.gbuffer.normal_texture(),
+                            ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
                             framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                            graph,
-                            camera,
-                            white_dummy: self.white_dummy.clone(),
-                            depth,
-                            frame_width: frame_size.x,
-                            frame_height: frame_size.y,
-                            viewport,
-                            texture_cache: &mut self.texture_cache,
+                            ui_renderer: &mut self.ui_renderer,
                         })?;
+            }
 
-                self.statistics += self.sprite_renderer.render(SpriteRenderContext {
-                    state,
-                    framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                    graph,
-                    camera,
-                    white_dummy: self.white_dummy.clone(),
-                    viewport,
-                    textures: &mut self.texture_cache,
-                })?;
-
-                self.statistics += self.renderer2d.render(
-                    state,
-                    camera,
-                    &mut scene_associated_data.hdr_scene_framebuffer,
-                    viewport,
-                    graph,
-                    &mut self.texture_cache,
-                    self.white_dummy.clone(),
-                    scene.ambient_lighting_color,
-                )?;
-
-                self.statistics += self.forward_renderer.render(ForwardRenderContext {
-                    state,
-                    camera,
-                    geom_cache: &mut self.geometry_cache,
-                    texture_cache: &mut self.texture_cache,
-                    shader_cache: &mut self.shader_cache,
-                    batch_storage: &batch_storage,
-                    framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                    viewport,
-                    quality_settings: &self.quality_settings,
-                    white_dummy: self.white_dummy.clone(),
-                    normal_dummy: self.normal_dummy.clone(),
-                    black_dummy: self.black_dummy.clone(),
-                    volume_dummy: self.volume_dummy.clone(),
-                    matrix_storage: &mut self.matrix_storage,
-                })?;
-
-                for render_pass in self.scene_render_passes.iter() {
-                    self.statistics +=
-                        render_pass
-                            .borrow_mut()
-                            .on_hdr_render(SceneRenderPassContext {
-                                pipeline_state: state,
-                                texture_cache: &mut self.texture_cache,
-                                geometry_cache: &mut self.geometry_cache,
-                                quality_settings: &self.quality_settings,
-                                batch_storage: &batch_storage,
-                                viewport,
-                                scene,
-                                camera,
-                                scene_handle,
-                                white_dummy: self.white_dummy.clone(),
-                                normal_dummy: self.normal_dummy.clone(),
-                                metallic_dummy: self.metallic_dummy.clone(),
-                                environment_dummy: self.environment_dummy.clone(),
-                                black_dummy: self.black_dummy.clone(),
-                                depth_texture: scene_associated_data.gbuffer.depth(),
-                                normal_texture: scene_associated_data.gbuffer.normal_texture(),
-                                ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
-                                framebuffer: &mut scene_associated_data.hdr_scene_framebuffer,
-                                ui_renderer: &mut self.ui_renderer,
-                            })?;
-                }
-
-                let quad = &self.quad;
+            let quad = &self.quad;
 
-                // Prepare glow map.
+            // Prepare glow map. Bright pixels (including emissive surfaces with HDR intensity
+            // above 1.0) will glow.
+            let bloom_texture = if self.quality_settings.use_bloom {
                 self.statistics.geometry += scene_associated_data.bloom_renderer.render(
                     state,
                     quad,
                     scene_associated_data.hdr_scene_frame_texture(),
                 )?;
+                scene_associated_data.bloom_renderer.result()
+            } else {
+                self.black_dummy.clone()
+            };
+
+            // Convert high dynamic range frame to low dynamic range (sRGB) with tone mapping and gamma correction.
+            self.statistics.geometry += scene_associated_data.hdr_renderer.render(
+                state,
+                scene_associated_data.hdr_scene_frame_texture(),
+                bloom_texture,
+                &mut scene_associated_data.ldr_scene_framebuffer,
+                viewport,
+                quad,
+                dt,
+                &PostProcessSettings::evaluate(graph, camera),
+                &mut self.texture_cache,
+            )?;
+
+            // Apply FXAA if needed.
+            if self.quality_settings.fxaa {
+                self.statistics.geometry += self.fxaa_renderer.render(
+                    state,
+                    viewport,
+                    scene_associated_data.ldr_scene_frame_texture(),
+                    &mut scene_associated_data.ldr_temp_framebuffer,
+                )?;
 
-                // Convert high dynamic range frame to low dynamic range (sRGB) with tone mapping and gamma correction.
-                self.statistics.geometry += scene_associated_data.hdr_renderer.render(
+                let quad = &self.quad;
+                let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
+                self.statistics.geometry += blit_pixels(
                     state,
-                    scene_associated_data.hdr_scene_frame_texture(),
-                    scene_associated_data.bloom_renderer.result(),
                     &mut scene_associated_data.ldr_scene_framebuffer,
+                    temp_frame_texture,
+                    &self.flat_shader,
                     viewport,
                     quad,
-                    dt,
-                    camera.exposure(),
-                    camera.color_grading_lut_ref(),
-                    camera.color_grading_enabled(),
-                    &mut self.texture_cache,
                 )?;
+            }
 
-                // Apply FXAA if needed.
-                if self.quality_settings.fxaa {
-                    self.statistics.geometry += self.fxaa_renderer.render(
-                        state,
-                        viewport,
-                        scene_associated_data.ldr_scene_frame_texture(),
-                        &mut scene_associated_data.ldr_temp_framebuffer,
-                    )?;
-
-                    let quad = &self.quad;
-                    let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
-                    self.statistics.geometry += blit_pixels(
-                        state,
-                        &mut scene_associated_data.ldr_scene_framebuffer,
-                        temp_frame_texture,
-                        &self.flat_shader,
-                        viewport,
-                        quad,
-                    )?;
-                }
-
-                // Render debug geometry in the LDR frame buffer.
-                self.statistics += self.debug_renderer.render(
+            // Apply dithering as the last step to hide gradient banding, if needed.
+            if camera.dithering_enabled() {
+                self.statistics.geometry += self.dithering_renderer.render(
                     state,
                     viewport,
-                    &mut scene_associated_data.ldr_scene_framebuffer,
-                    &scene.drawing_context,
-                    camera,
+                    scene_associated_data.ldr_scene_frame_texture(),
+                    &mut scene_associated_data.ldr_temp_framebuffer,
                 )?;
 
-                for render_pass in self.scene_render_passes.iter() {
-                    self.statistics +=
-                        render_pass
-                            .borrow_mut()
-                            .on_ldr_render(SceneRenderPassContext {
-                                pipeline_state: state,
-                                texture_cache: &mut self.texture_cache,
-                                geometry_cache: &mut self.geometry_cache,
-                                quality_settings: &self.quality_settings,
-                                batch_storage: &batch_storage,
-                                viewport,
-                                scene,
-                                camera,
-                                scene_handle,
-                                white_dummy: self.white_dummy.clone(),
-                                normal_dummy: self.normal_dummy.clone(),
-                                metallic_dummy: self.metallic_dummy.clone(),
-                                environment_dummy: self.environment_dummy.clone(),
-                                black_dummy: self.black_dummy.clone(),
-                                depth_texture: scene_associated_data.gbuffer.depth(),
-                                normal_texture: scene_associated_data.gbuffer.normal_texture(),
-                                ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
-                                framebuffer: &mut scene_associated_data.ldr_scene_framebuffer,
-                                ui_renderer: &mut self.ui_renderer,
-                            })?;
-                }
-            }
-
-            // Optionally render everything into back buffer.
-            if scene.render_target.is_none() {
                 let quad = &self.quad;
+                let temp_frame_texture = scene_associated_data.ldr_temp_frame_texture();
                 self.statistics.geometry += blit_pixels(
                     state,
-                    &mut self.backbuffer,
-                    scene_associated_data.ldr_scene_frame_texture(),
+                    &mut scene_associated_data.ldr_scene_framebuffer,
+                    temp_frame_texture,
                     &self.flat_shader,
-                    window_viewport,
+                    viewport,
                     quad,
                 )?;
             }
-        }
 
-        self.pipeline_state()
-            .set_polygon_fill_mode(PolygonFace::FrontAndBack, PolygonFillMode::Fill);
+            // Render debug geometry in the LDR frame buffer.
+            self.statistics += self.debug_renderer.render(
+                state,
+                viewport,
+                &mut scene_associated_data.ldr_scene_framebuffer,
+                &scene.drawing_context,
+                camera,
+            )?;
 
-        // Render UI on top of everything without gamma correction.
-        self.statistics += self.ui_renderer.render(UiRenderContext {
-            state: &mut self.state,
-            viewport: window_viewport,
-            frame_buffer: &mut self.backbuffer,
-            frame_width: backbuffer_width,
-            frame_height: backbuffer_height,
-            drawing_context,
-            white_dummy: self.white_dummy.clone(),
-            texture_cache: &mut self.texture_cache,
-        })?;
+            for render_pass in self.scene_render_passes.iter() {
+                self.statistics +=
+                    render_pass
+                        .borrow_mut()
+                        .on_ldr_render(SceneRenderPassContext {
+                            pipeline_state: state,
+                            texture_cache: &mut self.texture_cache,
+                            geometry_cache: &mut self.geometry_cache,
+                            quality_settings: &self.quality_settings,
+                            batch_storage: &batch_storage,
+                            viewport,
+                            scene,
+                            camera,
+                            scene_handle,
+                            white_dummy: self.white_dummy.clone(),
+                            normal_dummy: self.normal_dummy.clone(),
+                            metallic_dummy: self.metallic_dummy.clone(),
+                            environment_dummy: self.environment_dummy.clone(),
+                            black_dummy: self.black_dummy.clone(),
+                            depth_texture: scene_associated_data.gbuffer.depth(),
+                            normal_texture: scene_associated_data.gbuffer.normal_texture(),
+                            ambient_texture: scene_associated_data.gbuffer.ambient_texture(),
+                            framebuffer: &mut scene_associated_data.ldr_scene_framebuffer,
+                            ui_renderer: &mut self.ui_renderer,
+                        })?;
+            }
+        }
+
+        // Optionally render everything into back buffer.
+        if scene.render_target.is_none() {
+            let quad = &self.quad;
+            self.statistics.geometry += blit_pixels(
+                state,
+                &mut self.backbuffer,
+                scene_associated_data.ldr_scene_frame_texture(),
+                &self.flat_shader,
+                window_viewport,
+                quad,
+            )?;
+        }
 
         Ok(())
     }
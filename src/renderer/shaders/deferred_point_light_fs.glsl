@@ -13,6 +13,7 @@ uniform bool softShadows;
 uniform bool shadowsEnabled;
 uniform float shadowBias;
 uniform float lightIntensity;
+uniform bool physicalAttenuation;
 
 in vec2 texCoord;
 out vec4 FragColor;
@@ -36,7 +37,9 @@ void main()
 
     vec3 lighting = S_PBR_CalculateLight(ctx);
 
-    float distanceAttenuation = S_LightDistanceAttenuation(distance, lightRadius);
+    float distanceAttenuation = physicalAttenuation
+        ? S_PhysicalLightDistanceAttenuation(distance, lightRadius)
+        : S_LightDistanceAttenuation(distance, lightRadius);
 
     float shadow = S_PointShadow(
         shadowsEnabled, softShadows, distance, shadowBias, ctx.fragmentToLight, pointShadowTexture);
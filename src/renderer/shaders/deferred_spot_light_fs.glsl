@@ -20,6 +20,7 @@ uniform float shadowMapInvSize;
 uniform float shadowBias;
 uniform bool cookieEnabled;
 uniform float lightIntensity;
+uniform bool physicalAttenuation;
 
 in vec2 texCoord;
 out vec4 FragColor;
@@ -43,7 +44,9 @@ void main()
 
     vec3 lighting = S_PBR_CalculateLight(ctx);
 
-    float distanceAttenuation = S_LightDistanceAttenuation(distance, lightRadius);
+    float distanceAttenuation = physicalAttenuation
+        ? S_PhysicalLightDistanceAttenuation(distance, lightRadius)
+        : S_LightDistanceAttenuation(distance, lightRadius);
 
     float spotAngleCos = dot(lightDirection, ctx.fragmentToLight);
     float coneFactor = smoothstep(halfConeAngleCos, halfHotspotConeAngleCos, spotAngleCos);
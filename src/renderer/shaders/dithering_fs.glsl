@@ -0,0 +1,25 @@
+// Adds tiny amount of noise to the final frame to hide gradient banding that is caused by quantization
+// to 8 bits per channel. Interleaved gradient noise (Jorge Jimenez, "Next Generation Post Processing
+// in Call of Duty: Advanced Warfare") is used as a cheap source of noise with blue-noise-like
+// properties - it has no low-frequency components, so the pattern is barely visible.
+
+uniform sampler2D screenTexture;
+
+in vec2 texCoord;
+out vec4 fragColor;
+
+float InterleavedGradientNoise(vec2 position) {
+    return fract(52.9829189 * fract(dot(position, vec2(0.06711056, 0.00583715))));
+}
+
+void main() {
+    vec4 color = texture(screenTexture, texCoord);
+
+    // Two noise samples are combined to get triangular distribution in [-1; 1] range, it makes
+    // noise intensity independent of the signal.
+    float a = InterleavedGradientNoise(gl_FragCoord.xy);
+    float b = InterleavedGradientNoise(gl_FragCoord.xy + vec2(113.0, 47.0));
+    float noise = a + b - 1.0;
+
+    fragColor = vec4(color.rgb + vec3(noise / 255.0), color.a);
+}
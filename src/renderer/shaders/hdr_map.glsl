@@ -1,8 +1,12 @@
 uniform sampler2D hdrSampler;
 uniform sampler2D lumSampler;
 uniform sampler2D bloomSampler;
+uniform float bloomIntensity;
 uniform sampler3D colorMapSampler;
 uniform bool useColorGrading;
+uniform sampler3D blendColorMapSampler;
+uniform bool useBlendColorGrading;
+uniform float colorGradingBlend;
 uniform float keyValue;
 uniform float minLuminance;
 uniform float maxLuminance;
@@ -13,19 +17,19 @@ in vec2 texCoord;
 
 out vec4 outLdrColor;
 
-vec3 ColorGrading(vec3 color) {
+vec3 ColorGrading(sampler3D lut, vec3 color) {
     const float lutSize = 16.0;
     const float a = (lutSize - 1.0) / lutSize;
     const float b = 1.0 / (2.0 * lutSize);
     vec3 scale = vec3(a);
     vec3 offset = vec3(b);
-    return texture(colorMapSampler, scale * color + offset).rgb;
+    return texture(lut, scale * color + offset).rgb;
 }
 
 void main() {
     vec4 hdrColor = texture(hdrSampler, texCoord);
 
-    hdrColor += texture(bloomSampler, texCoord);
+    hdrColor += bloomIntensity * texture(bloomSampler, texCoord);
 
     float luminance = texture(lumSampler, vec2(0.5, 0.5)).r;
 
@@ -38,9 +42,14 @@ void main() {
 
     vec4 ldrColor = vec4(vec3(1.0) - exp(-hdrColor.rgb * exposure), hdrColor.a);
 
-    if (useColorGrading) {
-        outLdrColor = vec4(ColorGrading(S_LinearToSRGB(ldrColor).rgb), ldrColor.a);
-    } else {
-        outLdrColor = S_LinearToSRGB(ldrColor);
+    vec4 srgbColor = S_LinearToSRGB(ldrColor);
+
+    vec3 gradedColor = useColorGrading ? ColorGrading(colorMapSampler, srgbColor.rgb) : srgbColor.rgb;
+
+    if (colorGradingBlend > 0.0) {
+        vec3 blendGradedColor = useBlendColorGrading ? ColorGrading(blendColorMapSampler, srgbColor.rgb) : srgbColor.rgb;
+        gradedColor = mix(gradedColor, blendGradedColor, colorGradingBlend);
     }
+
+    outLdrColor = vec4(gradedColor, srgbColor.a);
 }
@@ -1,11 +1,49 @@
 uniform sampler2D diffuseTexture;
 uniform vec4 color;
+uniform vec2 sizeScale;
+// 0 - stretch, 1 - tile, 2 - nine slice.
+uniform int uvMode;
+// Tile: xy - repeat counts. Nine slice: left, right, top, bottom margins.
+uniform vec4 uvModeParams;
 
 out vec4 FragColor;
 
 in vec2 texCoord;
 
+// Maps sprite coordinate to texture coordinate along one axis, so the borders keep their size.
+float NineSliceAxis(float t, float extent, float marginMin, float marginMax)
+{
+    // Borders are squeezed if the sprite is too small to fit them.
+    float e = max(extent, marginMin + marginMax);
+    float borderMin = marginMin / e;
+    float borderMax = marginMax / e;
+    if (t < borderMin) {
+        return t * e;
+    } else if (t > 1.0 - borderMax) {
+        return 1.0 - (1.0 - t) * e;
+    } else {
+        float middle = max(1.0 - borderMin - borderMax, 0.00001);
+        return marginMin + (t - borderMin) / middle * (1.0 - marginMin - marginMax);
+    }
+}
+
 void main()
 {
-    FragColor = color * S_SRGBToLinear(texture(diffuseTexture, texCoord));
-}
\ No newline at end of file
+    vec4 diffuse;
+    if (uvMode == 1) {
+        vec2 tc = texCoord * uvModeParams.xy;
+        // Use gradients of the continuous coordinates to prevent seams on tile borders.
+        diffuse = textureGrad(diffuseTexture, fract(tc), dFdx(tc), dFdy(tc));
+    } else if (uvMode == 2) {
+        vec2 scale = abs(sizeScale);
+        vec2 tc = vec2(
+            NineSliceAxis(texCoord.x, scale.x, uvModeParams.x, uvModeParams.y),
+            NineSliceAxis(texCoord.y, scale.y, uvModeParams.w, uvModeParams.z)
+        );
+        diffuse = textureGrad(diffuseTexture, tc, dFdx(texCoord), dFdy(texCoord));
+    } else {
+        diffuse = texture(diffuseTexture, texCoord);
+    }
+
+    FragColor = color * S_SRGBToLinear(diffuse);
+}
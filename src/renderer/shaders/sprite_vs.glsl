@@ -6,6 +6,7 @@ uniform mat4 worldMatrix;
 uniform vec3 cameraUpVector;
 uniform vec3 cameraSideVector;
 uniform float size;
+uniform vec2 sizeScale;
 uniform float rotation;
 
 out vec2 texCoord;
@@ -21,7 +22,7 @@ vec2 rotateVec2(vec2 v, float angle)
 void main()
 {
     texCoord = vertexTexCoord;
-    vec2 vertexOffset = rotateVec2(vertexTexCoord * 2.0 - 1.0, rotation);
+    vec2 vertexOffset = rotateVec2((vertexTexCoord * 2.0 - 1.0) * sizeScale, rotation);
     vec4 worldPosition = worldMatrix * vec4(vertexPosition, 1.0);
     vec3 offset = (vertexOffset.x * cameraSideVector + vertexOffset.y * cameraUpVector) * size;
     gl_Position = viewProjectionMatrix * (worldPosition + vec4(offset.x, offset.y, offset.z, 0.0));
@@ -256,7 +256,11 @@ impl CsmRenderer {
 
             for batch in batches.batches.iter() {
                 let material = batch.material.lock();
-                let geometry = geom_cache.get(state, &batch.data);
+                let geometry = match geom_cache.get(state, &batch.data) {
+                    Some(geometry) => geometry,
+                    // The geometry is still being uploaded to GPU.
+                    None => continue,
+                };
                 let blend_shapes_storage = batch
                     .data
                     .lock()
@@ -302,6 +306,7 @@ impl CsmRenderer {
                                     light_position: &Default::default(),
                                     blend_shapes_storage: blend_shapes_storage.as_ref(),
                                     blend_shapes_weights: &instance.blend_shapes_weights,
+                                    prev_view_projection_matrix: &light_view_projection,
                                     normal_dummy: normal_dummy.clone(),
                                     white_dummy: white_dummy.clone(),
                                     black_dummy: black_dummy.clone(),
@@ -250,7 +250,11 @@ impl PointShadowMapRenderer {
 
             for batch in batches.batches.iter() {
                 let material = batch.material.lock();
-                let geometry = geom_cache.get(state, &batch.data);
+                let geometry = match geom_cache.get(state, &batch.data) {
+                    Some(geometry) => geometry,
+                    // The geometry is still being uploaded to GPU.
+                    None => continue,
+                };
                 let blend_shapes_storage = batch
                     .data
                     .lock()
@@ -286,6 +290,7 @@ impl PointShadowMapRenderer {
                                     light_position: &light_pos,
                                     blend_shapes_storage: blend_shapes_storage.as_ref(),
                                     blend_shapes_weights: &instance.blend_shapes_weights,
+                                    prev_view_projection_matrix: &light_view_projection_matrix,
                                     normal_dummy: normal_dummy.clone(),
                                     white_dummy: white_dummy.clone(),
                                     black_dummy: black_dummy.clone(),
@@ -28,7 +28,10 @@ struct SpriteShader {
     color: UniformLocation,
     diffuse_texture: UniformLocation,
     size: UniformLocation,
+    size_scale: UniformLocation,
     rotation: UniformLocation,
+    uv_mode: UniformLocation,
+    uv_mode_params: UniformLocation,
 }
 
 impl SpriteShader {
@@ -46,6 +49,10 @@ impl SpriteShader {
             camera_up_vector: program
                 .uniform_location(state, &ImmutableString::new("cameraUpVector"))?,
             size: program.uniform_location(state, &ImmutableString::new("size"))?,
+            size_scale: program.uniform_location(state, &ImmutableString::new("sizeScale"))?,
+            uv_mode: program.uniform_location(state, &ImmutableString::new("uvMode"))?,
+            uv_mode_params: program
+                .uniform_location(state, &ImmutableString::new("uvModeParams"))?,
             diffuse_texture: program
                 .uniform_location(state, &ImmutableString::new("diffuseTexture"))?,
             color: program.uniform_location(state, &ImmutableString::new("color"))?,
@@ -160,6 +167,12 @@ impl SpriteRenderer {
                         .set_vector3(&self.shader.camera_up_vector, &camera_up)
                         .set_vector3(&self.shader.camera_side_vector, &camera_side)
                         .set_f32(&self.shader.size, sprite.size())
+                        .set_vector2(&self.shader.size_scale, &sprite.size_scale())
+                        .set_i32(&self.shader.uv_mode, sprite.uv_mode().shader_index())
+                        .set_vector4(
+                            &self.shader.uv_mode_params,
+                            &sprite.uv_mode().shader_params(),
+                        )
                         .set_linear_color(&self.shader.color, &sprite.color())
                         .set_f32(&self.shader.rotation, sprite.rotation());
                 },
@@ -0,0 +1,276 @@
+//! Warm-up allows you to upload GPU resources (shaders, textures, geometry) used by a scene before
+//! the scene is shown, to eliminate hitches when new materials appear on screen for the first time.
+//! See [`WarmUpQueue`] docs for more info.
+
+use crate::{
+    asset::{Resource, ResourceData, ResourceStateRef},
+    core::TypeUuidProvider,
+    material::{shader::ShaderResource, Material, PropertyValue, SharedMaterial},
+    resource::texture::TextureResource,
+    scene::{
+        decal::Decal, dim2::rectangle::Rectangle, mesh::surface::SurfaceSharedData, mesh::Mesh,
+        particle_system::ParticleSystem, sprite::Sprite, terrain::Terrain, Scene,
+    },
+};
+use fxhash::FxHashSet;
+use std::collections::VecDeque;
+
+pub(crate) enum WarmUpItem {
+    Shader(ShaderResource),
+    Texture(TextureResource),
+    Geometry(SurfaceSharedData),
+}
+
+pub(crate) enum ItemStatus {
+    Ready,
+    Pending,
+    Failed,
+}
+
+pub(crate) fn resource_status<T>(resource: &Resource<T>) -> ItemStatus
+where
+    T: ResourceData + TypeUuidProvider,
+{
+    match resource.state().get() {
+        ResourceStateRef::Ok(_) => ItemStatus::Ready,
+        ResourceStateRef::Pending { .. } => ItemStatus::Pending,
+        ResourceStateRef::LoadError { .. } => ItemStatus::Failed,
+    }
+}
+
+/// Progress of a warm-up.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct WarmUpProgress {
+    /// Amount of items (shaders, textures, geometry buffers) that were processed.
+    pub processed: usize,
+    /// Total amount of items in the queue.
+    pub total: usize,
+}
+
+impl WarmUpProgress {
+    /// Returns progress percentage in [0; 100] range.
+    pub fn percent(&self) -> u32 {
+        (self.processed * 100)
+            .checked_div(self.total)
+            .map_or(100, |percent| percent as u32)
+    }
+
+    /// Returns `true` if every item was processed, `false` - otherwise.
+    pub fn is_finished(&self) -> bool {
+        self.processed >= self.total
+    }
+}
+
+/// A queue of GPU resources that should be uploaded to GPU before they're used for rendering. Shaders
+/// are compiled and linked, textures and geometry are uploaded to video memory. Resources that are
+/// still loading are kept in the queue until they're loaded, resources that failed to load are
+/// skipped.
+///
+/// The queue is processed by [`crate::renderer::Renderer::warm_up`] in small portions limited by a
+/// time budget, so it could be done while a loading screen is shown without freezing the application.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     renderer::{warmup::WarmUpQueue, Renderer},
+///     scene::Scene,
+/// };
+/// use std::time::Duration;
+///
+/// // Call this once, when the scene is loaded.
+/// fn begin_warm_up(scene: &Scene) -> WarmUpQueue {
+///     WarmUpQueue::from_scene(scene)
+/// }
+///
+/// // Call this every frame, while loading screen is shown. Returns `true` when the scene is ready.
+/// fn warm_up(renderer: &mut Renderer, queue: &mut WarmUpQueue) -> bool {
+///     let progress = renderer.warm_up(queue, Duration::from_millis(8));
+///     println!("Warming up: {}%", progress.percent());
+///     progress.is_finished()
+/// }
+/// ```
+#[derive(Default)]
+pub struct WarmUpQueue {
+    pub(crate) items: VecDeque<WarmUpItem>,
+    keys: FxHashSet<usize>,
+    pub(crate) processed: usize,
+}
+
+impl WarmUpQueue {
+    /// Creates new empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates new queue with every resource used by the given scene. See [`Self::add_scene`].
+    pub fn from_scene(scene: &Scene) -> Self {
+        let mut queue = Self::default();
+        queue.add_scene(scene);
+        queue
+    }
+
+    /// Adds every resource used by the given scene: materials (shaders and textures) and geometry of
+    /// meshes and terrains, height maps of terrains, textures of decals, sprites, particle systems and
+    /// 2D rectangles.
+    pub fn add_scene(&mut self, scene: &Scene) {
+        for node in scene.graph.linear_iter() {
+            if let Some(mesh) = node.cast::<Mesh>() {
+                for surface in mesh.surfaces() {
+                    self.add_material(surface.material());
+                    self.add_geometry(&surface.data());
+                }
+            } else if let Some(terrain) = node.cast::<Terrain>() {
+                for layer in terrain.layers() {
+                    self.add_material(&layer.material);
+                }
+                for chunk in terrain.chunks_ref() {
+                    self.add_texture(chunk.heightmap());
+                }
+                self.add_geometry(&terrain.geometry().data);
+            } else if let Some(decal) = node.cast::<Decal>() {
+                for texture in [decal.diffuse_texture(), decal.normal_texture()]
+                    .into_iter()
+                    .flatten()
+                {
+                    self.add_texture(texture);
+                }
+            } else if let Some(sprite) = node.cast::<Sprite>() {
+                if let Some(texture) = sprite.texture() {
+                    self.add_texture(&texture);
+                }
+            } else if let Some(particle_system) = node.cast::<ParticleSystem>() {
+                if let Some(texture) = particle_system.texture() {
+                    self.add_texture(&texture);
+                }
+            } else if let Some(rectangle) = node.cast::<Rectangle>() {
+                if let Some(texture) = rectangle.texture() {
+                    self.add_texture(texture);
+                }
+            }
+        }
+    }
+
+    /// Adds shader and textures of the given material.
+    pub fn add_material(&mut self, material: &SharedMaterial) {
+        let material = material.lock();
+        self.add_material_ref(&material);
+    }
+
+    fn add_material_ref(&mut self, material: &Material) {
+        self.add_shader(material.shader());
+        for property in material.properties().values() {
+            if let PropertyValue::Sampler {
+                value: Some(texture),
+                ..
+            } = property
+            {
+                self.add_texture(texture);
+            }
+        }
+    }
+
+    /// Adds the shader. Every render pass of the shader will be compiled.
+    pub fn add_shader(&mut self, shader: &ShaderResource) {
+        if self.keys.insert(shader.key()) {
+            self.items.push_back(WarmUpItem::Shader(shader.clone()));
+        }
+    }
+
+    /// Adds the texture.
+    pub fn add_texture(&mut self, texture: &TextureResource) {
+        if self.keys.insert(texture.key()) {
+            self.items.push_back(WarmUpItem::Texture(texture.clone()));
+        }
+    }
+
+    /// Adds vertex and index buffers of a surface.
+    pub fn add_geometry(&mut self, data: &SurfaceSharedData) {
+        let key = &*data.lock() as *const _ as usize;
+        if self.keys.insert(key) {
+            self.items.push_back(WarmUpItem::Geometry(data.clone()));
+        }
+    }
+
+    /// Returns current progress of the warm-up.
+    pub fn progress(&self) -> WarmUpProgress {
+        WarmUpProgress {
+            processed: self.processed,
+            total: self.processed + self.items.len(),
+        }
+    }
+
+    /// Returns `true` if every item was processed, `false` - otherwise.
+    pub fn is_finished(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Matrix4,
+        renderer::warmup::{WarmUpItem, WarmUpProgress, WarmUpQueue},
+        scene::{
+            base::BaseBuilder,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            Scene,
+        },
+    };
+
+    #[test]
+    fn test_warm_up_queue() {
+        let mut scene = Scene::new();
+
+        let cube = SurfaceSharedData::new(SurfaceData::make_cube(Matrix4::identity()));
+        let sphere =
+            SurfaceSharedData::new(SurfaceData::make_sphere(8, 8, 1.0, &Matrix4::identity()));
+        for data in [cube.clone(), cube, sphere] {
+            MeshBuilder::new(BaseBuilder::new())
+                .with_surfaces(vec![SurfaceBuilder::new(data).build()])
+                .build(&mut scene.graph);
+        }
+
+        // Shared resources are added only once.
+        let mut queue = WarmUpQueue::from_scene(&scene);
+        let count = |queue: &WarmUpQueue, filter: fn(&WarmUpItem) -> bool| {
+            queue.items.iter().filter(|item| filter(item)).count()
+        };
+        assert_eq!(
+            count(&queue, |item| matches!(item, WarmUpItem::Geometry(_))),
+            2
+        );
+        assert_eq!(
+            count(&queue, |item| matches!(item, WarmUpItem::Shader(_))),
+            1
+        );
+        queue.add_scene(&scene);
+        let total = queue.items.len();
+        assert_eq!(queue.progress().total, total);
+        assert_eq!(queue.progress().percent(), 0);
+        assert!(!queue.is_finished());
+
+        queue.items.pop_front();
+        queue.processed += 1;
+        assert_eq!(
+            queue.progress(),
+            WarmUpProgress {
+                processed: 1,
+                total
+            }
+        );
+
+        queue.items.clear();
+        queue.processed = total;
+        assert!(queue.is_finished());
+        assert!(queue.progress().is_finished());
+        assert_eq!(queue.progress().percent(), 100);
+
+        // Empty queue is finished right away.
+        assert_eq!(WarmUpQueue::new().progress().percent(), 100);
+        assert!(WarmUpQueue::new().progress().is_finished());
+    }
+}
@@ -30,7 +30,10 @@ use crate::{
             document::FbxDocument,
             error::FbxError,
             scene::{
-                animation::{FbxAnimationCurveNode, FbxAnimationCurveNodeType},
+                animation::{
+                    FbxAnimationCurveNode, FbxAnimationCurveNodeType, FbxKeyInterpolation,
+                    FbxTimeValuePair,
+                },
                 geometry::FbxMeshGeometry,
                 model::FbxModel,
                 FbxComponent, FbxMapping, FbxScene,
@@ -57,7 +60,7 @@ use crate::{
         transform::TransformBuilder,
         Scene,
     },
-    utils::{self, raw_mesh::RawMeshBuilder},
+    utils::{self, raw_mesh::RawMeshBuilder, uvgen},
 };
 use fxhash::{FxHashMap, FxHashSet};
 use std::{
@@ -583,6 +586,20 @@ async fn convert_mesh(
             }
         }
 
+        if model_import_options.generate_lightmap_uvs {
+            for surface in surfaces.iter_mut() {
+                if let Err(e) = uvgen::generate_uvs_if_missing(
+                    &mut surface.data().lock(),
+                    uvgen::DEFAULT_UV_SPACING,
+                ) {
+                    Log::err(format!(
+                        "Unable to generate lightmap UVs for {}. Reason: {:?}",
+                        model.name, e
+                    ));
+                }
+            }
+        }
+
         for surface in surfaces {
             mesh_surfaces.push(surface);
         }
@@ -709,11 +726,17 @@ async fn convert_model(
                                 CurveKeyKind::Constant,
                             ));
                         } else {
-                            for pair in fbx_curve.keys.iter() {
+                            for (i, pair) in fbx_curve.keys.iter().enumerate() {
+                                let kind = key_kind(
+                                    fbx_curve.keys.get(i.wrapping_sub(1)),
+                                    pair,
+                                    fbx_curve.keys.get(i + 1),
+                                    &transform_value,
+                                );
                                 curves[index].add_key(CurveKey::new(
                                     pair.time,
                                     transform_value(pair.value),
-                                    CurveKeyKind::Linear,
+                                    kind,
                                 ))
                             }
                         }
@@ -722,6 +745,53 @@ async fn convert_model(
             }
         }
 
+        // Converts a slope (value change per second) of a span to a tangent of a curve key. Curve
+        // tangents are normalized by the span duration and the absolute value difference of the
+        // span.
+        fn slope_to_tangent(slope: f32, left: &FbxTimeValuePair, right: &FbxTimeValuePair) -> f32 {
+            let value_delta = (right.value - left.value).abs();
+            if value_delta > f32::EPSILON {
+                slope * (right.time - left.time) / value_delta
+            } else {
+                0.0
+            }
+        }
+
+        // Converts FBX interpolation mode of a key to native key kind. Transform function must be
+        // linear, because it is also applied to slopes.
+        fn key_kind<F: Fn(f32) -> f32>(
+            prev: Option<&FbxTimeValuePair>,
+            current: &FbxTimeValuePair,
+            next: Option<&FbxTimeValuePair>,
+            transform_value: &F,
+        ) -> CurveKeyKind {
+            let transform = |pair: &FbxTimeValuePair| FbxTimeValuePair {
+                value: transform_value(pair.value),
+                right_slope: transform_value(pair.right_slope),
+                next_left_slope: transform_value(pair.next_left_slope),
+                ..*pair
+            };
+
+            match current.interpolation {
+                FbxKeyInterpolation::Constant => CurveKeyKind::Constant,
+                FbxKeyInterpolation::Linear => CurveKeyKind::Linear,
+                FbxKeyInterpolation::Cubic => {
+                    let current = transform(current);
+                    CurveKeyKind::Cubic {
+                        left_tangent: prev
+                            .map(transform)
+                            .filter(|prev| prev.interpolation == FbxKeyInterpolation::Cubic)
+                            .map_or(0.0, |prev| {
+                                slope_to_tangent(prev.next_left_slope, &prev, &current)
+                            }),
+                        right_tangent: next.map(transform).map_or(0.0, |next| {
+                            slope_to_tangent(current.right_slope, &current, &next)
+                        }),
+                    }
+                }
+            }
+        }
+
         fn add_vec3_key(track: &mut Track, value: Vector3<f32>) {
             let curves = track.data_container_mut().curves_mut();
             curves[0].add_key(CurveKey::new(0.0, value.x, CurveKeyKind::Constant));
@@ -7,9 +7,105 @@ use crate::{
 };
 use fxhash::FxHashMap;
 
+/// Interpolation mode of a span between a key and the next one.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FbxKeyInterpolation {
+    Constant,
+    Linear,
+    Cubic,
+}
+
+impl FbxKeyInterpolation {
+    // See `FbxAnimCurveDef::EInterpolationType` in FBX SDK.
+    const CONSTANT: i32 = 0x00000002;
+    const CUBIC: i32 = 0x00000008;
+
+    fn from_flags(flags: i32) -> Self {
+        if flags & Self::CUBIC != 0 {
+            Self::Cubic
+        } else if flags & Self::CONSTANT != 0 {
+            Self::Constant
+        } else {
+            Self::Linear
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
 pub struct FbxTimeValuePair {
     pub time: f32,
     pub value: f32,
+    /// Interpolation mode of the span that starts at this key.
+    pub interpolation: FbxKeyInterpolation,
+    /// Slope (value change per second) at the right side of this key.
+    pub right_slope: f32,
+    /// Slope (value change per second) at the left side of the next key.
+    pub next_left_slope: f32,
+}
+
+/// Per-key interpolation attributes, FBX stores them in a compressed form: every attribute is
+/// shared by `ref_count` sequential keys.
+struct FbxKeyAttributes {
+    flags: Vec<i32>,
+    data: Vec<f32>,
+    ref_counts: Vec<i32>,
+}
+
+impl FbxKeyAttributes {
+    // Every attribute has 4 floats: right slope, next left slope, packed weights, packed velocity.
+    const DATA_STRIDE: usize = 4;
+
+    fn read(curve_handle: Handle<FbxNode>, nodes: &FbxNodeContainer) -> Result<Self, String> {
+        let read_array = |name: &str| -> Result<&FbxNode, String> {
+            let handle = nodes.find(curve_handle, name)?;
+            nodes.get_by_name(handle, "a")
+        };
+
+        let flags_array = read_array("KeyAttrFlags")?;
+        let data_array = read_array("KeyAttrDataFloat")?;
+        let ref_count_array = read_array("KeyAttrRefCount")?;
+
+        let mut attributes = Self {
+            flags: Vec::with_capacity(flags_array.attrib_count()),
+            data: Vec::with_capacity(data_array.attrib_count()),
+            ref_counts: Vec::with_capacity(ref_count_array.attrib_count()),
+        };
+        for i in 0..flags_array.attrib_count() {
+            attributes.flags.push(flags_array.get_attrib(i)?.as_i32()?);
+        }
+        for i in 0..data_array.attrib_count() {
+            attributes.data.push(data_array.get_attrib(i)?.as_f32()?);
+        }
+        for i in 0..ref_count_array.attrib_count() {
+            attributes
+                .ref_counts
+                .push(ref_count_array.get_attrib(i)?.as_i32()?);
+        }
+
+        if attributes.flags.len() != attributes.ref_counts.len()
+            || attributes.data.len() != attributes.flags.len() * Self::DATA_STRIDE
+        {
+            return Err(String::from(
+                "FBX: Animation curve contains wrong key attributes!",
+            ));
+        }
+
+        Ok(attributes)
+    }
+
+    /// Expands compressed attributes to a list of `(interpolation, right slope, next left slope)`
+    /// for every key.
+    fn expand(&self, key_count: usize) -> Vec<(FbxKeyInterpolation, f32, f32)> {
+        let mut expanded = Vec::with_capacity(key_count);
+        for (i, (flags, ref_count)) in self.flags.iter().zip(self.ref_counts.iter()).enumerate() {
+            let data = &self.data[i * Self::DATA_STRIDE..(i + 1) * Self::DATA_STRIDE];
+            for _ in 0..(*ref_count).max(0) {
+                expanded.push((FbxKeyInterpolation::from_flags(*flags), data[0], data[1]));
+            }
+        }
+        expanded.resize(key_count, (FbxKeyInterpolation::Linear, 0.0, 0.0));
+        expanded
+    }
 }
 
 pub struct FbxAnimationCurve {
@@ -33,12 +129,23 @@ impl FbxAnimationCurve {
             ));
         }
 
+        let key_count = key_value_array.attrib_count();
+
+        // Key attributes are optional, keys are interpolated linearly if there are none.
+        let attributes = FbxKeyAttributes::read(curve_handle, nodes)
+            .map(|attributes| attributes.expand(key_count))
+            .unwrap_or_else(|_| vec![(FbxKeyInterpolation::Linear, 0.0, 0.0); key_count]);
+
         let mut curve = FbxAnimationCurve { keys: Vec::new() };
 
-        for i in 0..key_value_array.attrib_count() {
+        for (i, (interpolation, right_slope, next_left_slope)) in attributes.into_iter().enumerate()
+        {
             curve.keys.push(FbxTimeValuePair {
                 time: ((key_time_array.get_attrib(i)?.as_i64()? as f64) * FBX_TIME_UNIT) as f32,
                 value: key_value_array.get_attrib(i)?.as_f32()?,
+                interpolation,
+                right_slope,
+                next_left_slope,
             });
         }
 
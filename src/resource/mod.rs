@@ -5,4 +5,5 @@
 pub mod curve;
 pub mod fbx;
 pub mod model;
+pub mod physics_material;
 pub mod texture;
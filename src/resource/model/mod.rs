@@ -40,6 +40,7 @@ use crate::{
         animation::AnimationPlayer,
         graph::{map::NodeHandleMap, Graph},
         node::Node,
+        particle_system::ParticleSystem,
         Scene, SceneLoader,
     },
 };
@@ -178,6 +179,13 @@ impl ModelResourceExtension for ModelResource {
             // from parent objects on resolve stage.
             node.as_reflect_mut(&mut |node| mark_inheritable_properties_non_modified(node));
 
+            // Make sure that every copy of a particle effect looks different.
+            if let Some(particle_system) = node.cast_mut::<ParticleSystem>() {
+                if !particle_system.is_seed_fixed() {
+                    particle_system.randomize_seed();
+                }
+            }
+
             // Continue on children.
             stack.extend_from_slice(node.children());
         }
@@ -433,6 +441,12 @@ pub struct ModelImportOptions {
     /// See [`MaterialSearchOptions`] docs for more info.
     #[serde(default)]
     pub material_search_options: MaterialSearchOptions,
+
+    /// If set, secondary texture coordinates (the ones used for lightmaps) will be generated for every
+    /// surface that does not have them. It allows you to lightmap arbitrary models without doing
+    /// UV unwrapping in a DCC tool. Keep in mind that the generation is quite slow for large models.
+    #[serde(default)]
+    pub generate_lightmap_uvs: bool,
 }
 
 impl ImportOptions for ModelImportOptions {}
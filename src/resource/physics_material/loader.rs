@@ -0,0 +1,62 @@
+//! Physics material loader.
+
+use crate::{
+    asset::{
+        event::ResourceEventBroadcaster,
+        loader::{BoxedLoaderFuture, ResourceLoader},
+        untyped::UntypedResource,
+    },
+    core::log::Log,
+    resource::physics_material::PhysicsMaterial,
+};
+use std::any::Any;
+
+/// Default implementation for physics material loading.
+pub struct PhysicsMaterialLoader;
+
+impl ResourceLoader for PhysicsMaterialLoader {
+    fn extensions(&self) -> &[&str] {
+        &["pmat"]
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn load(
+        &self,
+        resource: UntypedResource,
+        event_broadcaster: ResourceEventBroadcaster,
+        reload: bool,
+    ) -> BoxedLoaderFuture {
+        Box::pin(async move {
+            let path = resource.0.lock().path().to_path_buf();
+
+            match PhysicsMaterial::from_file(&path).await {
+                Ok(material) => {
+                    Log::info(format!("Physics material {:?} is loaded!", path));
+
+                    resource.commit_ok(material);
+
+                    event_broadcaster.broadcast_loaded_or_reloaded(resource, reload);
+                }
+                Err(error) => {
+                    Log::err(format!(
+                        "Unable to load physics material from {:?}! Reason {:?}",
+                        path, error
+                    ));
+
+                    resource.commit_error(path, error);
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,259 @@
+//! Physics material is a set of surface properties (friction, restitution and their combine rules),
+//! that can be shared across any number of colliders. See [`PhysicsMaterial`] docs for more info.
+
+use crate::{
+    asset::{options::ImportOptions, Resource, ResourceData},
+    core::{
+        io::FileLoadError,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    scene::graph::physics::CoefficientCombineRule,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    borrow::Cow,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+pub mod loader;
+
+/// An error that may occur during physics material resource loading.
+#[derive(Debug)]
+pub enum PhysicsMaterialError {
+    /// An i/o error has occurred.
+    Io(FileLoadError),
+
+    /// An error that may occur due to version incompatibilities.
+    Visit(VisitError),
+}
+
+impl Display for PhysicsMaterialError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PhysicsMaterialError::Io(v) => {
+                write!(f, "A file load error has occurred {v:?}")
+            }
+            PhysicsMaterialError::Visit(v) => {
+                write!(
+                    f,
+                    "An error that may occur due to version incompatibilities. {v:?}"
+                )
+            }
+        }
+    }
+}
+
+impl From<FileLoadError> for PhysicsMaterialError {
+    fn from(e: FileLoadError) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<VisitError> for PhysicsMaterialError {
+    fn from(e: VisitError) -> Self {
+        Self::Visit(e)
+    }
+}
+
+/// Physics material defines surface properties of colliders. Unlike per-collider properties, a
+/// material is a resource, which means that it could be shared across any number of colliders (see
+/// [`crate::scene::collider::Collider::set_physics_material`]). Any change of the material affects
+/// every collider that uses it, so it is possible to tweak the "ice" material once, instead of
+/// searching for every icy collider in a scene.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     asset::Resource,
+///     resource::physics_material::PhysicsMaterial,
+///     scene::{base::BaseBuilder, collider::ColliderBuilder, graph::Graph},
+/// };
+///
+/// fn create_icy_colliders(graph: &mut Graph) {
+///     let ice = Resource::new_ok(PhysicsMaterial {
+///         friction: 0.02,
+///         ..Default::default()
+///     });
+///
+///     for _ in 0..10 {
+///         ColliderBuilder::new(BaseBuilder::new())
+///             .with_physics_material(Some(ice.clone()))
+///             .build(graph);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Visit, Reflect)]
+pub struct PhysicsMaterial {
+    #[reflect(hidden)]
+    pub(crate) path: PathBuf,
+
+    /// Friction coefficient of the surface. See
+    /// [`crate::scene::collider::Collider::set_friction`] for more info.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub friction: f32,
+
+    /// Restitution coefficient of the surface. See
+    /// [`crate::scene::collider::Collider::set_restitution`] for more info.
+    #[reflect(min_value = 0.0, step = 0.05)]
+    pub restitution: f32,
+
+    /// A rule that defines how friction coefficients of two touching colliders are combined.
+    pub friction_combine_rule: CoefficientCombineRule,
+
+    /// A rule that defines how restitution coefficients of two touching colliders are combined.
+    pub restitution_combine_rule: CoefficientCombineRule,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self {
+            path: Default::default(),
+            friction: 0.0,
+            restitution: 0.0,
+            friction_combine_rule: Default::default(),
+            restitution_combine_rule: Default::default(),
+        }
+    }
+}
+
+impl ResourceData for PhysicsMaterial {
+    fn path(&self) -> Cow<Path> {
+        Cow::Borrowed(&self.path)
+    }
+
+    fn set_path(&mut self, path: PathBuf) {
+        self.path = path;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn type_uuid(&self) -> Uuid {
+        <Self as TypeUuidProvider>::type_uuid()
+    }
+
+    fn is_procedural(&self) -> bool {
+        // Materials created from code have no path and must be saved together with their users.
+        self.path.as_os_str().is_empty()
+    }
+}
+
+impl TypeUuidProvider for PhysicsMaterial {
+    fn type_uuid() -> Uuid {
+        uuid!("bd9d2e9c-2a5c-4a05-a1b1-1f9d45c8a3e7")
+    }
+}
+
+impl PhysicsMaterial {
+    /// Loads a physics material from the specific file path.
+    pub async fn from_file(path: &Path) -> Result<Self, PhysicsMaterialError> {
+        let mut visitor = Visitor::load_binary(path).await?;
+        let mut material = PhysicsMaterial::default();
+        material.visit("PhysicsMaterial", &mut visitor)?;
+        material.path = path.to_path_buf();
+        Ok(material)
+    }
+
+    /// Saves the physics material to the specific file path. The path will be remembered, so the
+    /// material will be loaded from the file when deserializing its users.
+    pub fn save(&mut self, path: &Path) -> Result<(), VisitError> {
+        self.path = path.to_path_buf();
+        let mut visitor = Visitor::new();
+        self.visit("PhysicsMaterial", &mut visitor)?;
+        visitor.save_binary(path)
+    }
+}
+
+/// Type alias for physics material resources.
+pub type PhysicsMaterialResource = Resource<PhysicsMaterial>;
+
+/// Import options for physics material resource.
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct PhysicsMaterialImportOptions {}
+
+impl ImportOptions for PhysicsMaterialImportOptions {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        asset::Resource,
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+        },
+        resource::physics_material::PhysicsMaterial,
+        scene::{
+            base::BaseBuilder,
+            collider::ColliderBuilder,
+            graph::Graph,
+            node::Node,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_shared_physics_material() {
+        let mut graph = Graph::new();
+
+        let material = Resource::new_ok(PhysicsMaterial {
+            friction: 0.5,
+            ..Default::default()
+        });
+
+        let colliders = [0.0, 2.0].map(|x| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_friction(1.0)
+                .with_physics_material(Some(material.clone()))
+                .build(&mut graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(x, 0.0, 0.0))
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+            collider
+        });
+
+        let native_friction = |graph: &Graph, collider: Handle<Node>| {
+            let native = graph[collider].as_collider().native.get();
+            graph.physics.native_collider(native).unwrap().friction()
+        };
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        for collider in colliders {
+            assert_eq!(native_friction(&graph, collider), 0.5);
+        }
+
+        // Editing the material must affect every collider that uses it.
+        material.data_ref().friction = 0.25;
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        for collider in colliders {
+            assert_eq!(native_friction(&graph, collider), 0.25);
+        }
+
+        // Own properties are used when there is no material.
+        graph[colliders[0]]
+            .as_collider_mut()
+            .set_physics_material(None);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        assert_eq!(native_friction(&graph, colliders[0]), 1.0);
+        assert_eq!(native_friction(&graph, colliders[1]), 0.25);
+    }
+}
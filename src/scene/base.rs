@@ -16,7 +16,13 @@ use crate::{
     },
     engine::SerializationContext,
     resource::model::ModelResource,
-    scene::{node::Node, transform::Transform},
+    scene::{
+        component::NodeComponent,
+        graph::Graph,
+        node::Node,
+        transform::Transform,
+        user_data::{UserData, UserDataStorage},
+    },
     script::{Script, ScriptTrait},
 };
 use std::{any::Any, cell::Cell, sync::mpsc::Sender};
@@ -97,6 +103,63 @@ pub struct LodGroup {
     pub levels: Vec<LevelOfDetail>,
 }
 
+/// Hierarchical level of detail (HLOD) cluster is a group of static scene nodes (for example, buildings
+/// of a city block) that is replaced by a single proxy node beyond some distance from an observer. The
+/// proxy is usually a mesh that was merged and simplified offline from the members of the cluster, so
+/// instead of hundreds of separate objects the renderer draws just one. Unlike [`LodGroup`], which
+/// switches individual nodes, the cluster switches whole hierarchies - members and the proxy are hidden
+/// together with all their descendants.
+///
+/// The distance is measured in world units from an observer to the world-space bounding box of the
+/// proxy. If the observer is closer than the switch distance, the members are rendered and the proxy
+/// is hidden, otherwise the members are hidden and the proxy is rendered. The cluster only hides nodes,
+/// so LOD groups of the members or the proxy still apply.
+#[derive(Debug, Default, Clone, Visit, Reflect, PartialEq)]
+pub struct HlodCluster {
+    #[reflect(min_value = 0.0)]
+    switch_distance: f32,
+    /// Root nodes of the hierarchies that form the cluster.
+    pub members: Vec<Handle<Node>>,
+    /// A node that replaces the members of the cluster beyond the switch distance.
+    pub proxy: Handle<Node>,
+}
+
+impl HlodCluster {
+    /// Creates new HLOD cluster.
+    pub fn new(switch_distance: f32, members: Vec<Handle<Node>>, proxy: Handle<Node>) -> Self {
+        Self {
+            switch_distance: switch_distance.max(0.0),
+            members,
+            proxy,
+        }
+    }
+
+    /// Sets new switch distance (in world units). Negative values will be clamped to zero.
+    pub fn set_switch_distance(&mut self, distance: f32) {
+        self.switch_distance = distance.max(0.0);
+    }
+
+    /// Returns current switch distance (in world units).
+    pub fn switch_distance(&self) -> f32 {
+        self.switch_distance
+    }
+
+    /// Returns `true` if the proxy should be rendered instead of the members for an observer at the
+    /// given position. It is always `false` if the proxy handle is invalid.
+    pub fn use_proxy(&self, graph: &Graph, observer_position: &Vector3<f32>) -> bool {
+        graph.try_get(self.proxy).map_or(false, |proxy| {
+            let aabb = proxy.local_bounding_box();
+            let closest_point = if aabb.is_valid() {
+                let aabb = aabb.transform(&proxy.global_transform());
+                observer_position.sup(&aabb.min).inf(&aabb.max)
+            } else {
+                proxy.global_position()
+            };
+            observer_position.metric_distance(&closest_point) >= self.switch_distance
+        })
+    }
+}
+
 /// Mobility defines a group for scene node which has direct impact on performance
 /// and capabilities of nodes.
 #[derive(
@@ -299,6 +362,9 @@ pub struct Base {
     #[reflect(setter = "set_visibility")]
     visibility: InheritableVariable<bool>,
 
+    #[reflect(setter = "set_ignore_parent_visibility")]
+    ignore_parent_visibility: InheritableVariable<bool>,
+
     #[reflect(
         description = "Maximum amount of Some(time) that node will \"live\" or None if the node has unlimited lifetime."
     )]
@@ -311,6 +377,9 @@ pub struct Base {
     #[reflect(setter = "set_lod_group")]
     lod_group: InheritableVariable<Option<LodGroup>>,
 
+    #[reflect(setter = "set_hlod_cluster")]
+    hlod_cluster: InheritableVariable<Option<HlodCluster>>,
+
     #[reflect(setter = "set_mobility")]
     mobility: InheritableVariable<Mobility>,
 
@@ -341,6 +410,9 @@ pub struct Base {
     #[reflect(hidden)]
     pub(crate) global_visibility: Cell<bool>,
 
+    #[reflect(hidden)]
+    user_data: UserDataStorage,
+
     #[reflect(hidden)]
     pub(crate) parent: Handle<Node>,
 
@@ -350,6 +422,10 @@ pub struct Base {
     #[reflect(hidden)]
     pub(crate) global_transform: Cell<Matrix4<f32>>,
 
+    // Set to `true` when global transform of the node has changed during current frame.
+    #[reflect(hidden)]
+    pub(crate) global_transform_changed: Cell<bool>,
+
     // Bone-specific matrix. Non-serializable.
     #[reflect(hidden)]
     pub(crate) inv_bind_pose_transform: Matrix4<f32>,
@@ -531,6 +607,22 @@ impl Base {
         *self.visibility
     }
 
+    /// Sets whether the node should ignore visibility of its parent nodes or not. When set, global
+    /// visibility of the node (and thus its descendants) is defined by its local visibility only. It
+    /// could be used to keep some child node visible when its parent is hidden (for example a muzzle
+    /// flash attached to a hidden weapon mesh), or to force debug gizmos to be visible.
+    #[inline]
+    pub fn set_ignore_parent_visibility(&mut self, ignore: bool) -> bool {
+        self.ignore_parent_visibility
+            .set_value_and_mark_modified(ignore)
+    }
+
+    /// Returns `true` if the node ignores visibility of its parent nodes, `false` - otherwise.
+    #[inline]
+    pub fn ignore_parent_visibility(&self) -> bool {
+        *self.ignore_parent_visibility
+    }
+
     /// Returns current **local-space** bounding box. Keep in mind that this value is just
     /// a placeholder, because there is not information to calculate actual bounding box.
     #[inline]
@@ -563,7 +655,8 @@ impl Base {
     /// Returns combined visibility of an node. This is the final visibility of a node. Global visibility calculated
     /// using visibility of all parent nodes until root one, so if some parent node upper on tree is invisible then
     /// all its children will be invisible. It defines if object will be rendered. It is *not* the same as real
-    /// visibility from point of view of a camera. Use frustum-box intersection test instead.
+    /// visibility from point of view of a camera. Use frustum-box intersection test instead. Nodes that ignore
+    /// parent visibility (see [`Self::set_ignore_parent_visibility`]) are the exception from this rule.
     #[inline]
     pub fn global_visibility(&self) -> bool {
         self.global_visibility.get()
@@ -648,6 +741,33 @@ impl Base {
         self.lod_group.get_value_mut_and_mark_modified().as_mut()
     }
 
+    /// Sets new HLOD cluster.
+    #[inline]
+    pub fn set_hlod_cluster(&mut self, hlod_cluster: Option<HlodCluster>) -> Option<HlodCluster> {
+        std::mem::replace(
+            self.hlod_cluster.get_value_mut_and_mark_modified(),
+            hlod_cluster,
+        )
+    }
+
+    /// Extracts HLOD cluster, leaving None in the node.
+    #[inline]
+    pub fn take_hlod_cluster(&mut self) -> Option<HlodCluster> {
+        std::mem::take(self.hlod_cluster.get_value_mut_and_mark_modified())
+    }
+
+    /// Returns shared reference to current HLOD cluster.
+    #[inline]
+    pub fn hlod_cluster(&self) -> Option<&HlodCluster> {
+        self.hlod_cluster.as_ref()
+    }
+
+    /// Returns mutable reference to current HLOD cluster.
+    #[inline]
+    pub fn hlod_cluster_mut(&mut self) -> Option<&mut HlodCluster> {
+        self.hlod_cluster.get_value_mut_and_mark_modified().as_mut()
+    }
+
     /// Returns node tag.
     #[inline]
     pub fn tag(&self) -> &str {
@@ -843,6 +963,59 @@ impl Base {
         self.global_enabled.get()
     }
 
+    /// Returns a reference to the user data of the given type attached to the node, if any. See
+    /// [`UserDataStorage`] docs for more info.
+    #[inline]
+    pub fn user_data<T: UserData>(&self) -> Option<&T> {
+        self.user_data.get()
+    }
+
+    /// Returns a reference to the user data of the given type attached to the node, if any. See
+    /// [`UserDataStorage`] docs for more info.
+    #[inline]
+    pub fn user_data_mut<T: UserData>(&mut self) -> Option<&mut T> {
+        self.user_data.get_mut()
+    }
+
+    /// Returns a reference to the user data storage of the node.
+    #[inline]
+    pub fn user_data_storage(&self) -> &UserDataStorage {
+        &self.user_data
+    }
+
+    /// Returns a reference to the user data storage of the node. Use it to add or remove user data.
+    #[inline]
+    pub fn user_data_storage_mut(&mut self) -> &mut UserDataStorage {
+        &mut self.user_data
+    }
+
+    /// Attaches a new component to the node, replacing previous component of the same type (if any).
+    /// See [`NodeComponent`] docs for more info.
+    #[inline]
+    pub fn add_component<T: NodeComponent>(&mut self, component: T) -> Option<T> {
+        self.user_data.insert_serializable(component)
+    }
+
+    /// Returns a reference to the component of the given type attached to the node, if any.
+    /// See [`NodeComponent`] docs for more info.
+    #[inline]
+    pub fn component<T: NodeComponent>(&self) -> Option<&T> {
+        self.user_data.get()
+    }
+
+    /// Returns a reference to the component of the given type attached to the node, if any.
+    /// See [`NodeComponent`] docs for more info.
+    #[inline]
+    pub fn component_mut<T: NodeComponent>(&mut self) -> Option<&mut T> {
+        self.user_data.get_mut()
+    }
+
+    /// Detaches the component of the given type from the node and returns it, if any.
+    #[inline]
+    pub fn remove_component<T: NodeComponent>(&mut self) -> Option<T> {
+        self.user_data.remove()
+    }
+
     /// Returns a root resource of the scene node. This method crawls up on dependency tree until it finds that
     /// the ancestor node does not have any dependencies and returns this resource as the root resource. For
     /// example, in case of simple scene node instance, this method will return the resource from which the node
@@ -944,6 +1117,16 @@ impl Visit for Base {
         let _ = self.cast_shadows.visit("CastShadows", &mut region);
         let _ = self.instance_id.visit("InstanceId", &mut region);
         let _ = self.enabled.visit("Enabled", &mut region);
+        let _ = self.hlod_cluster.visit("HlodCluster", &mut region);
+        let _ = self
+            .ignore_parent_visibility
+            .visit("IgnoreParentVisibility", &mut region);
+        if let Err(e) = self.user_data.visit("UserData", &mut region) {
+            // It is ok for old scenes not to have user data at all.
+            if !matches!(e, VisitError::RegionDoesNotExist(_)) {
+                Log::err(format!("Unable to visit user data. Reason: {:?}", e))
+            }
+        }
 
         // Script visiting may fail for various reasons:
         //
@@ -969,17 +1152,20 @@ impl Visit for Base {
 pub struct BaseBuilder {
     name: String,
     visibility: bool,
+    ignore_parent_visibility: bool,
     local_transform: Transform,
     children: Vec<Handle<Node>>,
     lifetime: Option<f32>,
     depth_offset: f32,
     lod_group: Option<LodGroup>,
+    hlod_cluster: Option<HlodCluster>,
     mobility: Mobility,
     inv_bind_pose_transform: Matrix4<f32>,
     tag: String,
     frustum_culling: bool,
     cast_shadows: bool,
     script: Option<Script>,
+    user_data: UserDataStorage,
     instance_id: InstanceId,
     enabled: bool,
 }
@@ -997,17 +1183,20 @@ impl BaseBuilder {
         Self {
             name: Default::default(),
             visibility: true,
+            ignore_parent_visibility: false,
             local_transform: Default::default(),
             children: Default::default(),
             lifetime: None,
             depth_offset: 0.0,
             lod_group: None,
+            hlod_cluster: None,
             mobility: Mobility::Dynamic,
             inv_bind_pose_transform: Matrix4::identity(),
             tag: Default::default(),
             frustum_culling: true,
             cast_shadows: true,
             script: None,
+            user_data: Default::default(),
             instance_id: InstanceId(Uuid::new_v4()),
             enabled: true,
         }
@@ -1034,6 +1223,14 @@ impl BaseBuilder {
         self
     }
 
+    /// Sets whether the node should ignore visibility of its parent nodes or not. See
+    /// [`Base::set_ignore_parent_visibility`] for more info.
+    #[inline]
+    pub fn with_ignore_parent_visibility(mut self, ignore: bool) -> Self {
+        self.ignore_parent_visibility = ignore;
+        self
+    }
+
     /// Sets desired local transform.
     #[inline]
     pub fn with_local_transform(mut self, transform: Transform) -> Self {
@@ -1089,6 +1286,13 @@ impl BaseBuilder {
         self
     }
 
+    /// Sets desired HLOD cluster.
+    #[inline]
+    pub fn with_hlod_cluster(mut self, hlod_cluster: HlodCluster) -> Self {
+        self.hlod_cluster = Some(hlod_cluster);
+        self
+    }
+
     /// Sets desired tag.
     #[inline]
     pub fn with_tag(mut self, tag: String) -> Self {
@@ -1117,6 +1321,13 @@ impl BaseBuilder {
         self
     }
 
+    /// Adds a component to the node. See [`NodeComponent`] docs for more info.
+    #[inline]
+    pub fn with_component<T: NodeComponent>(mut self, component: T) -> Self {
+        self.user_data.insert_serializable(component);
+        self
+    }
+
     /// Sets new instance id.
     pub fn with_instance_id(mut self, id: InstanceId) -> Self {
         self.instance_id = id;
@@ -1134,7 +1345,9 @@ impl BaseBuilder {
             local_transform: self.local_transform,
             lifetime: self.lifetime.into(),
             visibility: self.visibility.into(),
+            ignore_parent_visibility: self.ignore_parent_visibility.into(),
             global_visibility: Cell::new(true),
+            user_data: self.user_data,
             parent: Handle::NONE,
             global_transform: Cell::new(Matrix4::identity()),
             inv_bind_pose_transform: self.inv_bind_pose_transform,
@@ -1143,10 +1356,12 @@ impl BaseBuilder {
             is_resource_instance_root: false,
             depth_offset: self.depth_offset.into(),
             lod_group: self.lod_group.into(),
+            hlod_cluster: self.hlod_cluster.into(),
             mobility: self.mobility.into(),
             tag: self.tag.into(),
             properties: Default::default(),
             transform_modified: Cell::new(false),
+            global_transform_changed: Cell::new(false),
             frustum_culling: self.frustum_culling.into(),
             cast_shadows: self.cast_shadows.into(),
             script: self.script,
@@ -1156,3 +1371,62 @@ impl BaseBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::{BaseBuilder, HlodCluster},
+            graph::Graph,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    fn base_at(position: Vector3<f32>) -> BaseBuilder {
+        BaseBuilder::new().with_local_transform(
+            TransformBuilder::new()
+                .with_local_position(position)
+                .build(),
+        )
+    }
+
+    #[test]
+    fn test_hlod_cluster_switching() {
+        let mut graph = Graph::new();
+        let member = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        // Unit cube, its closest face to the origin is at x = 9.5.
+        let proxy = MeshBuilder::new(base_at(Vector3::new(10.0, 0.0, 0.0)))
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut graph);
+        // Proxy without bounds, the distance is measured to its position.
+        let pivot_proxy =
+            PivotBuilder::new(base_at(Vector3::new(10.0, 0.0, 0.0))).build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let cluster = HlodCluster::new(5.0, vec![member], proxy);
+        assert!(cluster.use_proxy(&graph, &Vector3::default()));
+        assert!(cluster.use_proxy(&graph, &Vector3::new(4.5, 0.0, 0.0)));
+        assert!(!cluster.use_proxy(&graph, &Vector3::new(6.0, 0.0, 0.0)));
+        assert!(!cluster.use_proxy(&graph, &Vector3::new(10.0, 0.0, 0.0)));
+
+        let cluster = HlodCluster::new(5.0, vec![member], pivot_proxy);
+        assert!(cluster.use_proxy(&graph, &Vector3::new(4.5, 0.0, 0.0)));
+        assert!(!cluster.use_proxy(&graph, &Vector3::new(6.0, 0.0, 0.0)));
+
+        // Members must stay visible if there is no proxy.
+        let cluster = HlodCluster::new(5.0, vec![member], Handle::NONE);
+        assert!(!cluster.use_proxy(&graph, &Vector3::default()));
+    }
+}
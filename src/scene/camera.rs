@@ -329,6 +329,10 @@ pub struct Camera {
     #[reflect(setter = "set_color_grading_enabled")]
     color_grading_enabled: InheritableVariable<bool>,
 
+    #[reflect(setter = "set_dithering_enabled")]
+    #[visit(optional)]
+    dithering_enabled: InheritableVariable<bool>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     view_matrix: Matrix4<f32>,
@@ -336,6 +340,19 @@ pub struct Camera {
     #[visit(skip)]
     #[reflect(hidden)]
     projection_matrix: Matrix4<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    prev_view_matrix: Matrix4<f32>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    prev_projection_matrix: Matrix4<f32>,
+
+    // Whether the matrices were calculated at least once, previous matrices are not valid otherwise.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    matrices_calculated: bool,
 }
 
 impl Deref for Camera {
@@ -460,6 +477,29 @@ impl Camera {
         self.view_matrix.try_inverse()
     }
 
+    /// Returns view-projection matrix of the previous frame. It could be used for temporal effects,
+    /// such as reprojection or motion vectors calculation. The matrix is also available in shaders
+    /// as `fyrox_prevViewProjectionMatrix` built-in uniform. At the first frame, it is equal to the
+    /// current view-projection matrix.
+    #[inline]
+    pub fn prev_view_projection_matrix(&self) -> Matrix4<f32> {
+        self.prev_projection_matrix * self.prev_view_matrix
+    }
+
+    /// Returns projection matrix of the previous frame. See [`Self::prev_view_projection_matrix`]
+    /// for more info.
+    #[inline]
+    pub fn prev_projection_matrix(&self) -> Matrix4<f32> {
+        self.prev_projection_matrix
+    }
+
+    /// Returns view matrix of the previous frame. See [`Self::prev_view_projection_matrix`] for
+    /// more info.
+    #[inline]
+    pub fn prev_view_matrix(&self) -> Matrix4<f32> {
+        self.prev_view_matrix
+    }
+
     /// Returns current projection mode.
     #[inline]
     pub fn projection(&self) -> &Projection {
@@ -669,6 +709,18 @@ impl Camera {
         *self.color_grading_enabled
     }
 
+    /// Enables or disables dithering of the final frame. Dithering adds tiny amount of noise with
+    /// blue-noise-like properties to the final frame, which effectively hides gradient banding that
+    /// is especially noticeable in dark scenes. Dithering is disabled by default.
+    pub fn set_dithering_enabled(&mut self, enable: bool) -> bool {
+        self.dithering_enabled.set_value_and_mark_modified(enable)
+    }
+
+    /// Whether dithering enabled or not.
+    pub fn dithering_enabled(&self) -> bool {
+        *self.dithering_enabled
+    }
+
     /// Sets new exposure. See `Exposure` struct docs for more info.
     pub fn set_exposure(&mut self, exposure: Exposure) -> Exposure {
         self.exposure.set_value_and_mark_modified(exposure)
@@ -700,7 +752,16 @@ impl NodeTrait for Camera {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
+        self.prev_view_matrix = self.view_matrix;
+        self.prev_projection_matrix = self.projection_matrix;
+
         self.calculate_matrices(context.frame_size);
+
+        if !self.matrices_calculated {
+            self.prev_view_matrix = self.view_matrix;
+            self.prev_projection_matrix = self.projection_matrix;
+            self.matrices_calculated = true;
+        }
     }
 
     fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
@@ -1000,6 +1061,7 @@ pub struct CameraBuilder {
     exposure: Exposure,
     color_grading_lut: Option<ColorGradingLut>,
     color_grading_enabled: bool,
+    dithering_enabled: bool,
     projection: Projection,
 }
 
@@ -1018,6 +1080,7 @@ impl CameraBuilder {
             exposure: Exposure::Manual(std::f32::consts::E),
             color_grading_lut: None,
             color_grading_enabled: false,
+            dithering_enabled: false,
             projection: Projection::default(),
         }
     }
@@ -1082,6 +1145,12 @@ impl CameraBuilder {
         self
     }
 
+    /// Sets whether dithering of the final frame should be enabled or not.
+    pub fn with_dithering_enabled(mut self, enabled: bool) -> Self {
+        self.dithering_enabled = enabled;
+        self
+    }
+
     /// Sets desired exposure options.
     pub fn with_exposure(mut self, exposure: Exposure) -> Self {
         self.exposure = exposure;
@@ -1105,6 +1174,9 @@ impl CameraBuilder {
             // recalculated before rendering.
             view_matrix: Matrix4::identity(),
             projection_matrix: Matrix4::identity(),
+            prev_view_matrix: Matrix4::identity(),
+            prev_projection_matrix: Matrix4::identity(),
+            matrices_calculated: false,
             sky_box: InheritableVariable::new_modified(match self.skybox {
                 SkyBoxKind::Builtin => Some(SkyBoxKind::built_in_skybox().clone()),
                 SkyBoxKind::None => None,
@@ -1114,6 +1186,7 @@ impl CameraBuilder {
             exposure: self.exposure.into(),
             color_grading_lut: self.color_grading_lut.into(),
             color_grading_enabled: self.color_grading_enabled.into(),
+            dithering_enabled: self.dithering_enabled.into(),
         }
     }
 
@@ -1513,3 +1586,37 @@ impl SkyBox {
         self.back.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{base::BaseBuilder, camera::CameraBuilder, graph::Graph},
+    };
+
+    #[test]
+    fn test_prev_frame_matrices() {
+        let mut graph = Graph::new();
+        let camera = CameraBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        // At the first frame, the previous matrices must be equal to the current ones.
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        let first = graph[camera].as_camera();
+        let first_view = first.view_matrix();
+        let first_view_projection = first.view_projection_matrix();
+        assert_eq!(first.prev_view_matrix(), first_view);
+        assert_eq!(first.prev_projection_matrix(), first.projection_matrix());
+        assert_eq!(first.prev_view_projection_matrix(), first_view_projection);
+
+        graph[camera]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 2.0, 3.0));
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        // Then they must lag one frame behind.
+        let second = graph[camera].as_camera();
+        assert_ne!(second.view_matrix(), first_view);
+        assert_eq!(second.prev_view_matrix(), first_view);
+        assert_eq!(second.prev_view_projection_matrix(), first_view_projection);
+    }
+}
@@ -2,8 +2,9 @@
 //! participate in contact generation, collision response and proximity queries.
 
 use crate::{
+    asset::ResourceStateRef,
     core::{
-        algebra::Vector3,
+        algebra::{UnitQuaternion, Vector3},
         log::Log,
         math::aabb::AxisAlignedBoundingBox,
         num_traits::{NumCast, One, ToPrimitive, Zero},
@@ -14,6 +15,7 @@ use crate::{
         visitor::prelude::*,
         TypeUuidProvider,
     },
+    resource::physics_material::PhysicsMaterialResource,
     scene::{
         base::{Base, BaseBuilder},
         graph::{
@@ -25,9 +27,10 @@ use crate::{
         Scene,
     },
 };
+use fxhash::FxHashSet;
 use rapier3d::geometry::{self, ColliderHandle};
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     ops::{Add, BitAnd, BitOr, Deref, DerefMut, Mul, Not, Shl},
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
@@ -173,11 +176,22 @@ impl Default for TriangleShape {
 #[derive(Default, Clone, Copy, PartialEq, Hash, Debug, Visit, Reflect, Eq)]
 pub struct GeometrySource(pub Handle<Node>);
 
-/// Arbitrary triangle mesh shape.
-#[derive(Default, Clone, Debug, Visit, Reflect, PartialEq, Eq)]
+/// Arbitrary triangle mesh shape. High-poly visual meshes make construction of the shape and physics
+/// queries very slow, use [`Self::weld_distance`] and [`Self::triangle_budget`] to simplify the
+/// geometry of the shape.
+#[derive(Default, Clone, Debug, Visit, Reflect, PartialEq)]
 pub struct TrimeshShape {
     /// Geometry sources for the shape.
     pub sources: Vec<GeometrySource>,
+    /// Vertices of the source geometry that are closer to each other than this distance are welded
+    /// together. Zero means that only exactly matching vertices are welded.
+    #[visit(optional)]
+    #[reflect(min_value = 0.0)]
+    pub weld_distance: f32,
+    /// Maximum amount of triangles in the shape. The source geometry is decimated until it fits the
+    /// budget. `None` means that the source geometry is used as is.
+    #[visit(optional)]
+    pub triangle_budget: Option<u32>,
 }
 
 /// Arbitrary height field shape.
@@ -194,6 +208,48 @@ pub struct ConvexPolyhedronShape {
     pub geometry_source: GeometrySource,
 }
 
+/// A part of [`CompoundShape`] - a primitive shape with an offset relative to the collider.
+#[derive(Default, Clone, Debug, Visit, Reflect, PartialEq)]
+pub struct CompoundShapeChild {
+    /// Position of the shape relative to the collider.
+    pub position: Vector3<f32>,
+    /// Rotation of the shape relative to the collider.
+    pub rotation: UnitQuaternion<f32>,
+    /// The shape itself.
+    pub shape: ColliderShape,
+}
+
+/// A set of shapes with local offsets, that act as a single shape. It allows a single collider to
+/// describe a complex object (a table, a chair, etc.) without attaching a separate collider node for
+/// every part of the object.
+///
+/// # Notes
+///
+/// Composite shapes (triangle meshes and height fields) cannot be a part of compound shape, such
+/// children are ignored. Nested compound shapes are flattened.
+#[derive(Default, Clone, Debug, Visit, Reflect, PartialEq)]
+pub struct CompoundShape {
+    /// Parts of the shape.
+    pub children: Vec<CompoundShapeChild>,
+}
+
+impl CompoundShape {
+    /// Adds a new shape with the given offset to the compound shape.
+    pub fn with_child(
+        mut self,
+        position: Vector3<f32>,
+        rotation: UnitQuaternion<f32>,
+        shape: ColliderShape,
+    ) -> Self {
+        self.children.push(CompoundShapeChild {
+            position,
+            rotation,
+            shape,
+        });
+        self
+    }
+}
+
 /// A set of bits used for pairwise collision filtering.
 #[derive(Clone, Copy, Default, PartialEq, Debug, Reflect, Eq)]
 pub struct BitMask(pub u32);
@@ -358,6 +414,8 @@ pub enum ColliderShape {
     Heightfield(HeightfieldShape),
     /// See [`ConvexPolyhedronShape`] docs.
     Polyhedron(ConvexPolyhedronShape),
+    /// See [`CompoundShape`] docs.
+    Compound(CompoundShape),
 }
 
 impl Default for ColliderShape {
@@ -435,6 +493,7 @@ impl ColliderShape {
     pub fn trimesh(geometry_sources: Vec<GeometrySource>) -> Self {
         Self::Trimesh(TrimeshShape {
             sources: geometry_sources,
+            ..Default::default()
         })
     }
 
@@ -442,6 +501,24 @@ impl ColliderShape {
     pub fn heightfield(geometry_source: GeometrySource) -> Self {
         Self::Heightfield(HeightfieldShape { geometry_source })
     }
+
+    /// Initializes a compound shape defined by a set of shapes with their offsets (position and
+    /// rotation) relative to the collider.
+    pub fn compound<I>(children: I) -> Self
+    where
+        I: IntoIterator<Item = (Vector3<f32>, UnitQuaternion<f32>, ColliderShape)>,
+    {
+        Self::Compound(CompoundShape {
+            children: children
+                .into_iter()
+                .map(|(position, rotation, shape)| CompoundShapeChild {
+                    position,
+                    rotation,
+                    shape,
+                })
+                .collect(),
+        })
+    }
 }
 
 /// Collider is a geometric entity that can be attached to a rigid body to allow participate it
@@ -477,9 +554,53 @@ pub struct Collider {
     #[reflect(setter = "set_restitution_combine_rule")]
     pub(crate) restitution_combine_rule: InheritableVariable<CoefficientCombineRule>,
 
+    #[visit(optional)]
+    #[reflect(setter = "set_surface_type")]
+    pub(crate) surface_type: InheritableVariable<String>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_physics_material")]
+    pub(crate) physics_material: InheritableVariable<Option<PhysicsMaterialResource>>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ColliderHandle>,
+
+    // Combined global scale of geometry sources (meshes, terrains) at the moment when the native
+    // shape was created.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) sources_scale: Cell<Vector3<f32>>,
+
+    // Global scale of the collider at the moment when the native shape was created.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) shape_scale: Cell<Vector3<f32>>,
+
+    // Hashes of height maps of a terrain, that is used as a geometry source of a height field, at the
+    // moment when the native shape was created or updated.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) height_map_hashes: RefCell<Vec<u64>>,
+
+    // Trigger state of sensor colliders, updated by the graph after every physics update.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) entered: FxHashSet<Handle<Node>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) exited: FxHashSet<Handle<Node>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) inside: FxHashSet<Handle<Node>>,
+}
+
+// Effective surface properties of a collider.
+pub(crate) struct SurfaceProperties {
+    pub(crate) friction: f32,
+    pub(crate) restitution: f32,
+    pub(crate) friction_combine_rule: CoefficientCombineRule,
+    pub(crate) restitution_combine_rule: CoefficientCombineRule,
 }
 
 impl Default for Collider {
@@ -495,7 +616,15 @@ impl Default for Collider {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            surface_type: Default::default(),
+            physics_material: Default::default(),
             native: Cell::new(ColliderHandle::invalid()),
+            sources_scale: Cell::new(Vector3::repeat(1.0)),
+            shape_scale: Cell::new(Vector3::repeat(1.0)),
+            height_map_hashes: Default::default(),
+            entered: Default::default(),
+            exited: Default::default(),
+            inside: Default::default(),
         }
     }
 }
@@ -527,8 +656,16 @@ impl Clone for Collider {
             solver_groups: self.solver_groups.clone(),
             friction_combine_rule: self.friction_combine_rule.clone(),
             restitution_combine_rule: self.restitution_combine_rule.clone(),
+            surface_type: self.surface_type.clone(),
+            physics_material: self.physics_material.clone(),
             // Do not copy. The copy will have its own native representation (for example - Rapier's collider)
             native: Cell::new(ColliderHandle::invalid()),
+            sources_scale: Cell::new(Vector3::repeat(1.0)),
+            shape_scale: Cell::new(Vector3::repeat(1.0)),
+            height_map_hashes: Default::default(),
+            entered: Default::default(),
+            exited: Default::default(),
+            inside: Default::default(),
         }
     }
 }
@@ -680,6 +817,73 @@ impl Collider {
         *self.is_sensor
     }
 
+    /// Returns a set of colliders that started intersecting with the sensor collider during the last
+    /// physics update. It is always empty for non-sensor colliders.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::scene::{collider::Collider, graph::Graph, node::Node};
+    /// use fyrox::core::pool::Handle;
+    ///
+    /// fn check_point_reached(graph: &Graph, checkpoint: Handle<Node>, player: Handle<Node>) -> bool {
+    ///     graph
+    ///         .try_get_of_type::<Collider>(checkpoint)
+    ///         .map_or(false, |trigger| trigger.entered().contains(&player))
+    /// }
+    /// ```
+    pub fn entered(&self) -> &FxHashSet<Handle<Node>> {
+        &self.entered
+    }
+
+    /// Returns a set of colliders that stopped intersecting with the sensor collider during the last
+    /// physics update. Handles of removed colliders could be invalid. It is always empty for non-sensor
+    /// colliders.
+    pub fn exited(&self) -> &FxHashSet<Handle<Node>> {
+        &self.exited
+    }
+
+    /// Returns a set of colliders that are intersecting with the sensor collider at the moment. It is
+    /// always empty for non-sensor colliders.
+    pub fn inside(&self) -> &FxHashSet<Handle<Node>> {
+        &self.inside
+    }
+
+    /// Returns a set of nodes (colliders) that overlap the sensor collider after the last physics update.
+    /// The set is maintained by the engine, so there is no need to track intersection events manually.
+    /// It is always empty for non-sensor colliders.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::scene::{collider::Collider, graph::Graph, node::Node};
+    /// use fyrox::core::pool::Handle;
+    ///
+    /// fn damage_zone(graph: &Graph, zone: Handle<Node>, damage: f32) {
+    ///     if let Some(zone) = graph.try_get_of_type::<Collider>(zone) {
+    ///         for victim in zone.overlapping_nodes() {
+    ///             println!("{} takes {} damage", graph[*victim].name(), damage);
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn overlapping_nodes(&self) -> &FxHashSet<Handle<Node>> {
+        &self.inside
+    }
+
+    /// Returns a set of nodes (colliders) that started to overlap the sensor collider during the last
+    /// physics update. It is always empty for non-sensor colliders.
+    pub fn just_entered(&self) -> &FxHashSet<Handle<Node>> {
+        &self.entered
+    }
+
+    /// Returns a set of nodes (colliders) that stopped to overlap the sensor collider during the last
+    /// physics update. Handles of removed colliders could be invalid. It is always empty for non-sensor
+    /// colliders.
+    pub fn just_left(&self) -> &FxHashSet<Handle<Node>> {
+        &self.exited
+    }
+
     /// Sets the new friction combine rule. See [`CoefficientCombineRule`] docs for more info.
     ///
     /// # Performance
@@ -719,6 +923,55 @@ impl Collider {
         *self.restitution_combine_rule
     }
 
+    /// Sets new surface type of the collider. Surface type is an arbitrary name (for example -
+    /// `"Wood"`, `"Metal"`, `"Grass"`) that can be used to select footstep sounds, impact effects,
+    /// etc. See [`Graph::surface_type_at`] for more info.
+    pub fn set_surface_type(&mut self, surface_type: String) -> String {
+        self.surface_type.set_value_and_mark_modified(surface_type)
+    }
+
+    /// Returns current surface type of the collider.
+    pub fn surface_type(&self) -> &str {
+        &self.surface_type
+    }
+
+    /// Sets a shared physics material of the collider. If set, friction, restitution and their
+    /// combine rules of the material are used instead of the properties of the collider itself. Any
+    /// change of the material affects every collider that uses it. Returns previous material.
+    pub fn set_physics_material(
+        &mut self,
+        material: Option<PhysicsMaterialResource>,
+    ) -> Option<PhysicsMaterialResource> {
+        self.physics_material.set_value_and_mark_modified(material)
+    }
+
+    /// Returns current physics material of the collider.
+    pub fn physics_material(&self) -> Option<&PhysicsMaterialResource> {
+        self.physics_material.as_ref()
+    }
+
+    // Returns surface properties that are used by the physics engine - either the properties of the
+    // physics material (if it is set and loaded) or the properties of the collider itself.
+    pub(crate) fn surface_properties(&self) -> SurfaceProperties {
+        if let Some(material) = self.physics_material.as_ref() {
+            if let ResourceStateRef::Ok(material) = material.state().get() {
+                return SurfaceProperties {
+                    friction: material.friction,
+                    restitution: material.restitution,
+                    friction_combine_rule: material.friction_combine_rule,
+                    restitution_combine_rule: material.restitution_combine_rule,
+                };
+            }
+        }
+
+        SurfaceProperties {
+            friction: *self.friction,
+            restitution: *self.restitution,
+            friction_combine_rule: *self.friction_combine_rule,
+            restitution_combine_rule: *self.restitution_combine_rule,
+        }
+    }
+
     /// Returns an iterator that yields contact information for the collider.
     /// Contacts checks between two regular colliders
     pub fn contacts<'a>(
@@ -747,6 +1000,7 @@ impl Collider {
             || self.solver_groups.need_sync()
             || self.friction_combine_rule.need_sync()
             || self.restitution_combine_rule.need_sync()
+            || self.physics_material.need_sync()
     }
 }
 
@@ -784,15 +1038,14 @@ impl NodeTrait for Collider {
     fn validate(&self, scene: &Scene) -> Result<(), String> {
         if scene
             .graph
-            .try_get(self.parent())
-            .and_then(|p| p.query_component_ref::<RigidBody>())
+            .find_up(self.parent(), &mut |p| {
+                p.query_component_ref::<RigidBody>().is_some()
+            })
             .is_none()
         {
-            Err(
-                "3D Collider must be a direct child of a 3D Rigid Body node, \
+            Err("3D Collider must be a descendant of a 3D Rigid Body node, \
             otherwise it will not have any effect!"
-                    .to_string(),
-            )
+                .to_string())
         } else {
             Ok(())
         }
@@ -811,6 +1064,8 @@ pub struct ColliderBuilder {
     solver_groups: InteractionGroups,
     friction_combine_rule: CoefficientCombineRule,
     restitution_combine_rule: CoefficientCombineRule,
+    surface_type: String,
+    physics_material: Option<PhysicsMaterialResource>,
 }
 
 impl ColliderBuilder {
@@ -827,6 +1082,8 @@ impl ColliderBuilder {
             solver_groups: Default::default(),
             friction_combine_rule: Default::default(),
             restitution_combine_rule: Default::default(),
+            surface_type: Default::default(),
+            physics_material: None,
         }
     }
 
@@ -884,6 +1141,18 @@ impl ColliderBuilder {
         self
     }
 
+    /// Sets desired surface type.
+    pub fn with_surface_type(mut self, surface_type: String) -> Self {
+        self.surface_type = surface_type;
+        self
+    }
+
+    /// Sets desired physics material.
+    pub fn with_physics_material(mut self, material: Option<PhysicsMaterialResource>) -> Self {
+        self.physics_material = material;
+        self
+    }
+
     /// Creates collider node, but does not add it to a graph.
     pub fn build_collider(self) -> Collider {
         Collider {
@@ -897,7 +1166,15 @@ impl ColliderBuilder {
             solver_groups: self.solver_groups.into(),
             friction_combine_rule: self.friction_combine_rule.into(),
             restitution_combine_rule: self.restitution_combine_rule.into(),
+            surface_type: self.surface_type.into(),
+            physics_material: self.physics_material.into(),
             native: Cell::new(ColliderHandle::invalid()),
+            sources_scale: Cell::new(Vector3::repeat(1.0)),
+            shape_scale: Cell::new(Vector3::repeat(1.0)),
+            height_map_hashes: Default::default(),
+            entered: Default::default(),
+            exited: Default::default(),
+            inside: Default::default(),
         }
     }
 
@@ -913,15 +1190,96 @@ impl ColliderBuilder {
 }
 
 #[cfg(test)]
-mod test {
-    use crate::core::algebra::Vector2;
+pub(crate) mod test {
+    use crate::core::algebra::{Isometry3, Matrix4, Point3, UnitQuaternion, Vector2, Vector3};
+    use crate::core::math::aabb::AxisAlignedBoundingBox;
+    use crate::core::pool::Handle;
     use crate::scene::{
         base::BaseBuilder,
-        collider::{ColliderBuilder, ColliderShape},
-        graph::Graph,
-        rigidbody::{RigidBodyBuilder, RigidBodyType},
+        collider::{BallShape, ColliderBuilder, ColliderShape, GeometrySource, TrimeshShape},
+        graph::{
+            physics::{
+                ColliderPair, CollisionEventKind, ImpulseFalloff, NativePhysicsEvent, PhysicsHooks,
+                RayCastOptions, ShapeCastOptions,
+            },
+            Graph, GraphUpdateSwitches,
+        },
+        joint::JointBuilder,
+        mesh::{
+            surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+            MeshBuilder,
+        },
+        node::Node,
+        pivot::PivotBuilder,
+        rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+        terrain::{Brush, BrushMode, BrushShape, Layer, TerrainBuilder},
+        transform::TransformBuilder,
     };
 
+    // Creates a static (or dynamic) body with a box collider of the given half extents and returns
+    // the collider.
+    pub(crate) fn create_cuboid(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        half_extents: Vector3<f32>,
+        body_type: RigidBodyType,
+        sensor: bool,
+    ) -> Handle<Node> {
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+            ))
+            .with_sensor(sensor)
+            .build(graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_body_type(body_type)
+        .build(graph);
+        collider
+    }
+
+    // Creates a static (or dynamic) body with a unit box collider and returns the collider.
+    pub(crate) fn create_box(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        body_type: RigidBodyType,
+        sensor: bool,
+    ) -> Handle<Node> {
+        create_cuboid(graph, position, Vector3::repeat(0.5), body_type, sensor)
+    }
+
+    // Casts a ray from (0, 10, 0) straight down and returns the hit colliders, closest first.
+    fn cast_down(
+        graph: &Graph,
+        cached: bool,
+        filter: Option<&dyn Fn(Handle<Node>) -> bool>,
+    ) -> Vec<Handle<Node>> {
+        let options = RayCastOptions {
+            ray_origin: Point3::new(0.0, 10.0, 0.0),
+            ray_direction: -Vector3::y(),
+            max_len: 20.0,
+            groups: Default::default(),
+            sort_results: true,
+            filter,
+        };
+        let mut hits = Vec::new();
+        if cached {
+            graph.physics.cast_ray_cached(options, &mut hits);
+        } else {
+            graph.physics.cast_ray(options, &mut hits);
+        }
+        hits.iter().map(|hit| hit.collider).collect()
+    }
+
     #[test]
     fn test_collider_intersect() {
         let mut graph = Graph::new();
@@ -983,4 +1341,1279 @@ mod test {
                 .count()
         );
     }
+
+    #[test]
+    fn test_cast_shape() {
+        let mut graph = Graph::new();
+
+        let mut create_wall = |x| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+                .build(&mut graph);
+
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(x, 0.0, 0.0))
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+            collider
+        };
+
+        let far = create_wall(6.0);
+        let near = create_wall(3.0);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        let shape = ColliderShape::Ball(BallShape { radius: 0.5 });
+        let mut hits = Vec::new();
+        assert!(graph.cast_shape(
+            &shape,
+            &Isometry3::identity(),
+            &Vector3::x(),
+            ShapeCastOptions {
+                max_toi: 10.0,
+                ..Default::default()
+            },
+            &mut hits,
+        ));
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].collider, near);
+        assert!((hits[0].toi - 2.0).abs() < 0.01);
+        assert!((hits[0].position.x - 2.5).abs() < 0.01);
+        assert!((hits[0].normal - -Vector3::x()).norm() < 0.01);
+        assert_eq!(hits[1].collider, far);
+        assert!((hits[1].toi - 5.0).abs() < 0.01);
+
+        // Too short cast.
+        graph.cast_shape(
+            &shape,
+            &Isometry3::identity(),
+            &Vector3::x(),
+            ShapeCastOptions {
+                max_toi: 1.0,
+                ..Default::default()
+            },
+            &mut hits,
+        );
+        assert!(hits.is_empty());
+    }
+
+    #[test]
+    fn test_collision_events() {
+        let mut graph = Graph::new();
+
+        let mut create_body = |shape, position: Vector3<f32>, body_type, is_sensor| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(shape)
+                .with_sensor(is_sensor)
+                .build(&mut graph);
+
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(position)
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_body_type(body_type)
+            .build(&mut graph);
+
+            collider
+        };
+
+        let ground = create_body(
+            ColliderShape::cuboid(5.0, 0.5, 5.0),
+            Vector3::default(),
+            RigidBodyType::Static,
+            false,
+        );
+        let ball = create_body(
+            ColliderShape::ball(0.5),
+            Vector3::new(0.0, 1.5, 0.0),
+            RigidBodyType::Dynamic,
+            false,
+        );
+        let sensor = create_body(
+            ColliderShape::cuboid(0.5, 0.5, 0.5),
+            Vector3::new(0.0, 1.5, 0.0),
+            RigidBodyType::Static,
+            true,
+        );
+
+        let mut contact_started = false;
+        let mut intersection_started = false;
+        for _ in 0..120 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+            for event in graph.physics.contact_events() {
+                assert_eq!(event.kind, CollisionEventKind::Started);
+                assert!(!event.removed);
+                let pair = [event.collider1, event.collider2];
+                assert!(pair.contains(&ground) && pair.contains(&ball));
+                contact_started = true;
+            }
+
+            for event in graph.physics.intersection_events() {
+                if event.kind == CollisionEventKind::Started {
+                    let pair = [event.collider1, event.collider2];
+                    assert!(pair.contains(&sensor) && pair.contains(&ball));
+                    intersection_started = true;
+                }
+            }
+        }
+
+        assert!(contact_started);
+        assert!(intersection_started);
+
+        // Removal of a collider must produce a "stopped" event with a valid handle of the removed
+        // collider.
+        graph.remove_node(ball);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        let event = graph.physics.contact_events()[0];
+        assert_eq!(event.kind, CollisionEventKind::Stopped);
+        assert!(event.removed);
+        assert!([event.collider1, event.collider2].contains(&ball));
+    }
+
+    #[test]
+    fn test_contacts_of() {
+        let mut graph = Graph::new();
+
+        let ground_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(5.0, 0.5, 5.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[ground_collider])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -0.5, 0.0))
+                        .build(),
+                ),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let ball_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .build(&mut graph);
+        let ball = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[ball_collider])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.6, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+
+        for _ in 0..30 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let contacts = graph.contacts_of(ball_collider);
+        assert_eq!(contacts.len(), 1);
+        let contact = &contacts[0];
+        assert_eq!(contact.other, ground_collider);
+        assert!(!contact.points.is_empty());
+        for point in contact.points.iter() {
+            // The normal points from the ball towards the ground.
+            assert!(point.normal.y < -0.99);
+            assert!(point.position.y.abs() < 0.05);
+            assert!(point.impulse > 0.0);
+        }
+
+        // The same contact from the point of view of the ground.
+        let contacts = graph.contacts_of(ground_collider);
+        assert_eq!(contacts.len(), 1);
+        assert_eq!(contacts[0].other, ball_collider);
+        assert_eq!(contacts[0].other_body, ball);
+        assert!(contacts[0].points.iter().all(|p| p.normal.y > 0.99));
+
+        assert!(graph.contacts_of(Handle::NONE).is_empty());
+    }
+
+    #[test]
+    fn test_set_gravity() {
+        let mut graph = Graph::new();
+        graph.physics.set_gravity(Vector3::default());
+        assert_eq!(graph.physics.gravity(), Vector3::default());
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .build(&mut graph);
+        let body =
+            RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider])).build(&mut graph);
+
+        // Zero-G, the body must stay in place and eventually fall asleep.
+        for _ in 0..200 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        assert_eq!(graph[body].global_position().y, 0.0);
+        assert!(graph[body].cast::<RigidBody>().unwrap().is_sleeping());
+
+        // Changing gravity must wake the body up.
+        graph.physics.set_gravity(Vector3::new(0.0, -9.81, 0.0));
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        assert!(graph[body].global_position().y < 0.0);
+    }
+
+    #[test]
+    fn test_surface_type_at() {
+        let mut graph = Graph::new();
+
+        let terrain = TerrainBuilder::new(BaseBuilder::new())
+            .with_layers(vec![
+                Layer {
+                    surface_type: "Grass".to_string(),
+                    ..Default::default()
+                },
+                Layer {
+                    surface_type: "Rock".to_string(),
+                    ..Default::default()
+                },
+            ])
+            .with_mask_size(Vector2::new(32, 32))
+            .with_height_map_size(Vector2::new(32, 32))
+            .build(&mut graph);
+        graph[terrain].as_terrain_mut().draw(&Brush {
+            center: Vector3::new(8.0, 0.0, 8.0),
+            shape: BrushShape::Circle { radius: 2.0 },
+            mode: BrushMode::DrawOnMask {
+                layer: 1,
+                alpha: 1.0,
+            },
+        });
+
+        let ground = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::heightfield(GeometrySource(terrain)))
+            .with_surface_type("Dirt".to_string())
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[ground]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let crate_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .with_surface_type("Wood".to_string())
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(24.0, 0.5, 8.0))
+                        .build(),
+                )
+                .with_children(&[crate_collider]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        let surface_type_below = |graph: &Graph, x: f32, z: f32| {
+            let mut hits = Vec::new();
+            graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::new(x, 10.0, z),
+                    ray_direction: -Vector3::y(),
+                    max_len: 20.0,
+                    groups: Default::default(),
+                    sort_results: true,
+                    filter: None,
+                },
+                &mut hits,
+            );
+            graph.surface_type_at(&hits[0]).map(|s| s.to_string())
+        };
+
+        assert_eq!(
+            surface_type_below(&graph, 8.0, 8.0).as_deref(),
+            Some("Rock")
+        );
+        assert_eq!(
+            surface_type_below(&graph, 16.0, 16.0).as_deref(),
+            Some("Grass")
+        );
+        assert_eq!(
+            surface_type_below(&graph, 24.0, 8.0).as_deref(),
+            Some("Wood")
+        );
+    }
+
+    #[test]
+    fn test_heightfield_follows_terrain_edits() {
+        let mut graph = Graph::new();
+
+        let terrain = TerrainBuilder::new(BaseBuilder::new())
+            .with_height_map_size(Vector2::new(32, 32))
+            .build(&mut graph);
+        let ground = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::heightfield(GeometrySource(terrain)))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[ground]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let height_at = |graph: &Graph, x: f32, z: f32| {
+            let mut hits = Vec::new();
+            graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::new(x, 10.0, z),
+                    ray_direction: -Vector3::y(),
+                    max_len: 20.0,
+                    groups: Default::default(),
+                    sort_results: true,
+                    filter: None,
+                },
+                &mut hits,
+            );
+            hits[0].position.y
+        };
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        assert!(height_at(&graph, 8.0, 8.0).abs() < 0.001);
+
+        // Raise the terrain, the collider must follow without re-creation.
+        graph[terrain]
+            .as_terrain_mut()
+            .for_each_height_map_pixel(|pixel, _| *pixel = 2.0);
+        let native = graph[ground].as_collider().native.get();
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        assert_eq!(graph[ground].as_collider().native.get(), native);
+        assert!((height_at(&graph, 8.0, 8.0) - 2.0).abs() < 0.001);
+        assert!((height_at(&graph, 24.0, 24.0) - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_trimesh_simplification() {
+        let mut graph = Graph::new();
+
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_sphere(64, 64, 1.0, &Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut graph);
+
+        let mut make_collider = |graph: &mut Graph, triangle_budget| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::Trimesh(TrimeshShape {
+                    sources: vec![GeometrySource(mesh)],
+                    weld_distance: 0.001,
+                    triangle_budget,
+                }))
+                .build(graph);
+            RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+                .with_body_type(RigidBodyType::Static)
+                .build(graph);
+            collider
+        };
+        let full = make_collider(&mut graph, None);
+        let simplified = make_collider(&mut graph, Some(500));
+        let simplified_copy = make_collider(&mut graph, Some(500));
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        fn native_trimesh(graph: &Graph, collider: Handle<Node>) -> &rapier3d::geometry::TriMesh {
+            graph
+                .physics
+                .native_collider(graph[collider].as_collider().native.get())
+                .unwrap()
+                .shape()
+                .as_trimesh()
+                .unwrap()
+        }
+
+        assert!(native_trimesh(&graph, full).indices().len() > 500);
+        let count = native_trimesh(&graph, simplified).indices().len();
+        assert!(count > 0 && count <= 500);
+
+        // Colliders with the same geometry share the native shape.
+        assert!(std::ptr::eq(
+            native_trimesh(&graph, simplified),
+            native_trimesh(&graph, simplified_copy)
+        ));
+        assert!(!std::ptr::eq(
+            native_trimesh(&graph, full),
+            native_trimesh(&graph, simplified)
+        ));
+    }
+
+    #[test]
+    fn test_trimesh_ray_hit_uv() {
+        let mut graph = Graph::new();
+
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![
+                SurfaceBuilder::new(SurfaceSharedData::new(SurfaceData::make_quad(
+                    &Matrix4::new_translation(&Vector3::new(10.0, 0.0, 0.0)),
+                )))
+                .build(),
+                SurfaceBuilder::new(SurfaceSharedData::new(SurfaceData::make_quad(
+                    &Matrix4::identity(),
+                )))
+                .build(),
+            ])
+            .build(&mut graph);
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Trimesh(TrimeshShape {
+                sources: vec![GeometrySource(mesh)],
+                ..Default::default()
+            }))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let check_hit = |graph: &Graph| {
+            let mut hits = Vec::new();
+            graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::new(0.25, 0.25, 5.0),
+                    ray_direction: -Vector3::z(),
+                    max_len: 10.0,
+                    groups: Default::default(),
+                    sort_results: true,
+                    filter: None,
+                },
+                &mut hits,
+            );
+            assert!(hits[0].triangle.is_some());
+
+            let hit = graph.mesh_hit(&hits[0]).unwrap();
+            assert_eq!(hit.mesh, mesh);
+            assert_eq!(hit.surface, 1);
+            assert_eq!(hit.triangle, 0);
+            assert!((hit.barycentric.sum() - 1.0).abs() < 0.001);
+            let uv = hit.uv.unwrap();
+            assert!((uv - Vector2::new(0.25, 0.75)).norm() < 0.001);
+        };
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        check_hit(&graph);
+
+        // Triangles of a simplified mesh do not match the source ones, but the hit must be the same.
+        graph[collider]
+            .as_collider_mut()
+            .set_shape(ColliderShape::Trimesh(TrimeshShape {
+                sources: vec![GeometrySource(mesh)],
+                weld_distance: 0.001,
+                triangle_budget: None,
+            }));
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+        check_hit(&graph);
+    }
+
+    #[test]
+    fn test_native_physics_events() {
+        let mut graph = Graph::new();
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        graph.physics.subscribe_native_events(tx);
+
+        let collider = ColliderBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let body1 =
+            RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider])).build(&mut graph);
+        let body2 = RigidBodyBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let joint = JointBuilder::new(BaseBuilder::new())
+            .with_body1(body1)
+            .with_body2(body2)
+            .build(&mut graph);
+
+        for _ in 0..2 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let created = rx.try_iter().collect::<Vec<_>>();
+        let created_node = |node: Handle<Node>| {
+            created.iter().any(|event| match *event {
+                NativePhysicsEvent::RigidBodyCreated { node: n, .. }
+                | NativePhysicsEvent::ColliderCreated { node: n, .. }
+                | NativePhysicsEvent::JointCreated { node: n, .. } => n == node,
+                _ => false,
+            })
+        };
+        assert_eq!(created.len(), 4);
+        assert!(created_node(body1) && created_node(body2));
+        assert!(created_node(collider) && created_node(joint));
+
+        // Removal of a body removes its colliders and joints as well.
+        graph.remove_node(body1);
+        let removed = rx.try_iter().collect::<Vec<_>>();
+        assert!(removed.iter().any(|e| matches!(
+            e,
+            NativePhysicsEvent::ColliderRemoved { node, .. } if *node == collider
+        )));
+        assert!(removed.iter().any(|e| matches!(
+            e,
+            NativePhysicsEvent::JointRemoved { node, .. } if *node == joint
+        )));
+        assert!(removed.iter().any(|e| matches!(
+            e,
+            NativePhysicsEvent::RigidBodyRemoved { node, .. } if *node == body1
+        )));
+    }
+
+    #[test]
+    fn test_trigger_enter_exit() {
+        let mut graph = Graph::new();
+
+        let trigger = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(1.0, 1.0, 1.0))
+            .with_sensor(true)
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[trigger]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let ball = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.25))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[ball])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 2.0, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+
+        let mut entered_frame = None;
+        let mut exited_frame = None;
+        for frame in 0..120 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+            let trigger = graph[trigger].as_collider();
+            if trigger.entered().contains(&ball) {
+                assert!(entered_frame.is_none());
+                entered_frame = Some(frame);
+            }
+            if trigger.exited().contains(&ball) {
+                assert!(exited_frame.is_none());
+                exited_frame = Some(frame);
+            }
+            let expected_inside = entered_frame.is_some() && exited_frame.is_none();
+            assert_eq!(trigger.inside().contains(&ball), expected_inside);
+        }
+
+        assert!(entered_frame.unwrap() < exited_frame.unwrap());
+        // The ball was never inside of itself.
+        assert!(graph[ball].as_collider().entered().is_empty());
+    }
+
+    #[test]
+    fn test_sensor_overlap_lists() {
+        let mut graph = Graph::new();
+        graph.physics.gravity = Vector3::default();
+
+        let sensor = create_box(&mut graph, Vector3::default(), RigidBodyType::Static, true);
+        let far = create_box(
+            &mut graph,
+            Vector3::new(0.0, 0.0, 10.0),
+            RigidBodyType::Static,
+            true,
+        );
+        let mover = create_box(
+            &mut graph,
+            Vector3::new(-3.0, 0.0, 0.0),
+            RigidBodyType::Dynamic,
+            false,
+        );
+        let body = graph[mover].parent();
+        graph[body]
+            .as_rigid_body_mut()
+            .set_lin_vel(Vector3::new(6.0, 0.0, 0.0));
+
+        let mut entered_frame = None;
+        let mut left_frame = None;
+        for frame in 0..120 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+            let sensor = graph[sensor].as_collider();
+            if sensor.just_entered().contains(&mover) {
+                assert!(entered_frame.is_none());
+                entered_frame = Some(frame);
+            }
+            if sensor.just_left().contains(&mover) {
+                assert!(left_frame.is_none());
+                left_frame = Some(frame);
+            }
+            let expected_overlap = entered_frame.is_some() && left_frame.is_none();
+            assert_eq!(
+                sensor.overlapping_nodes().contains(&mover),
+                expected_overlap
+            );
+            assert_eq!(sensor.overlapping_nodes().len(), expected_overlap as usize);
+
+            // The sensor that was never touched must have empty lists.
+            let far = graph[far].as_collider();
+            assert!(far.overlapping_nodes().is_empty());
+            assert!(far.just_entered().is_empty());
+            assert!(far.just_left().is_empty());
+        }
+
+        assert!(entered_frame.unwrap() < left_frame.unwrap());
+        // Non-sensor colliders do not track overlaps.
+        assert!(graph[mover].as_collider().just_entered().is_empty());
+        assert!(graph[mover].as_collider().overlapping_nodes().is_empty());
+    }
+
+    #[test]
+    fn test_ray_cast_filter() {
+        let mut graph = Graph::new();
+
+        let lower = create_box(&mut graph, Vector3::default(), RigidBodyType::Static, false);
+        let upper = create_box(
+            &mut graph,
+            Vector3::new(0.0, 2.0, 0.0),
+            RigidBodyType::Static,
+            false,
+        );
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        assert_eq!(cast_down(&graph, false, None), vec![upper, lower]);
+        assert_eq!(
+            cast_down(
+                &graph,
+                false,
+                Some(&|collider: Handle<Node>| collider != upper)
+            ),
+            vec![lower]
+        );
+        assert_eq!(
+            cast_down(&graph, false, Some(&|_: Handle<Node>| false)),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_cached_ray_cast() {
+        let mut graph = Graph::new();
+        graph.physics.gravity = Vector3::default();
+
+        let lower = create_box(&mut graph, Vector3::default(), RigidBodyType::Static, false);
+        create_box(
+            &mut graph,
+            Vector3::new(0.0, 2.0, 0.0),
+            RigidBodyType::Dynamic,
+            false,
+        );
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        // Dynamic bodies are ignored.
+        assert_eq!(cast_down(&graph, true, None), vec![lower]);
+        // Served from the cache.
+        assert_eq!(cast_down(&graph, true, None), vec![lower]);
+
+        // Removal of static geometry must invalidate the cache.
+        let lower_body = graph[lower].parent();
+        graph.remove_node(lower_body);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        assert!(cast_down(&graph, true, None).is_empty());
+    }
+
+    #[test]
+    fn test_query_pipeline_scheduling() {
+        let mut graph = Graph::new();
+
+        let lower = create_box(&mut graph, Vector3::default(), RigidBodyType::Static, false);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        assert_eq!(cast_down(&graph, false, None), vec![lower]);
+
+        // Removed colliders must be ignored immediately, even though native ones are still alive.
+        let lower_body = graph[lower].parent();
+        graph.remove_node(lower_body);
+        assert!(cast_down(&graph, false, None).is_empty());
+
+        // New colliders become visible after the update, even if the simulation is disabled.
+        create_box(&mut graph, Vector3::default(), RigidBodyType::Static, false);
+        create_box(
+            &mut graph,
+            Vector3::new(0.0, 2.0, 0.0),
+            RigidBodyType::Static,
+            false,
+        );
+        graph.update(
+            Vector2::new(800.0, 600.0),
+            1.0 / 60.0,
+            GraphUpdateSwitches {
+                physics: false,
+                ..Default::default()
+            },
+        );
+        assert_eq!(cast_down(&graph, false, None).len(), 2);
+    }
+
+    #[test]
+    fn test_shape_overlap() {
+        let mut graph = Graph::new();
+
+        let obstacle = create_box(
+            &mut graph,
+            Vector3::new(0.0, 0.0, 0.0),
+            RigidBodyType::Static,
+            false,
+        );
+        create_box(
+            &mut graph,
+            Vector3::new(10.0, 0.0, 0.0),
+            RigidBodyType::Static,
+            true,
+        );
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let ball = ColliderShape::Ball(BallShape { radius: 0.5 });
+        let at = |x: f32| Isometry3::translation(x, 0.0, 0.0);
+
+        assert!(!graph.is_shape_free(&ball, &at(0.5), Default::default(), None));
+        assert_eq!(
+            graph.overlapping_colliders(&ball, &at(0.5), Default::default(), None),
+            vec![obstacle]
+        );
+        assert!(graph.is_shape_free(&ball, &at(5.0), Default::default(), None));
+        assert!(graph.is_shape_free(
+            &ball,
+            &at(0.5),
+            Default::default(),
+            Some(&|collider: Handle<Node>| collider != obstacle)
+        ));
+
+        // Sensors do not obstruct anything.
+        assert!(graph.is_shape_free(&ball, &at(10.0), Default::default(), None));
+        assert!(graph
+            .overlapping_colliders(&ball, &at(10.0), Default::default(), None)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_colliders_in_region() {
+        let mut graph = Graph::new();
+
+        let a = create_box(
+            &mut graph,
+            Vector3::new(0.0, 0.0, 0.0),
+            RigidBodyType::Static,
+            false,
+        );
+        let b = create_box(
+            &mut graph,
+            Vector3::new(3.0, 0.0, 0.0),
+            RigidBodyType::Static,
+            true,
+        );
+        let far = create_box(
+            &mut graph,
+            Vector3::new(20.0, 0.0, 0.0),
+            RigidBodyType::Static,
+            false,
+        );
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let region = AxisAlignedBoundingBox::from_min_max(
+            Vector3::new(-1.0, -1.0, -1.0),
+            Vector3::new(4.0, 1.0, 1.0),
+        );
+
+        let found = graph.colliders_in_region(&region);
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&a));
+        assert!(found.contains(&b));
+        assert!(!found.contains(&far));
+
+        // Removed colliders must not be reported even before the next update.
+        graph.remove_node(a);
+        assert_eq!(graph.colliders_in_region(&region), vec![b]);
+    }
+
+    #[test]
+    fn test_time_of_impact() {
+        let mut graph = Graph::new();
+
+        let create_ball = |graph: &mut Graph, x: f32| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::ball(0.5))
+                .build(graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(x, 0.0, 0.0))
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_body_type(RigidBodyType::Static)
+            .build(graph);
+            collider
+        };
+
+        let a = create_ball(&mut graph, 0.0);
+        let b = create_ball(&mut graph, 10.0);
+        let c = create_ball(&mut graph, 10.5);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let toi = graph
+            .time_of_impact(a, Vector3::x(), b, -Vector3::x(), 10.0)
+            .unwrap();
+        assert_eq!(toi.collider1, a);
+        assert_eq!(toi.collider2, b);
+        assert!(!toi.penetrating);
+        assert!((toi.toi - 4.5).abs() < 1.0e-3);
+        assert!((toi.position1 - Vector3::new(5.0, 0.0, 0.0)).norm() < 1.0e-3);
+        assert!((toi.position2 - Vector3::new(5.0, 0.0, 0.0)).norm() < 1.0e-3);
+        assert!((toi.normal - Vector3::x()).norm() < 1.0e-3);
+
+        // Too far to touch in the given time.
+        assert!(graph
+            .time_of_impact(a, Vector3::x(), b, -Vector3::x(), 1.0)
+            .is_none());
+
+        // Moving apart.
+        assert!(graph
+            .time_of_impact(a, -Vector3::x(), b, Vector3::x(), 10.0)
+            .is_none());
+
+        let toi = graph
+            .time_of_impact(b, Default::default(), c, Default::default(), 10.0)
+            .unwrap();
+        assert!(toi.penetrating);
+        assert_eq!(toi.toi, 0.0);
+    }
+
+    #[test]
+    fn test_radial_impulse() {
+        let mut graph = Graph::new();
+        graph.physics.gravity = Vector3::default();
+
+        let create_ball = |graph: &mut Graph, x: f32, body_type: RigidBodyType| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::ball(0.5))
+                .build(graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(x, 0.0, 0.0))
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_body_type(body_type)
+            .build(graph)
+        };
+
+        let near = create_ball(&mut graph, 2.0, RigidBodyType::Dynamic);
+        let far = create_ball(&mut graph, -4.0, RigidBodyType::Dynamic);
+        let outside = create_ball(&mut graph, 10.0, RigidBodyType::Dynamic);
+        let fixed = create_ball(&mut graph, 0.0, RigidBodyType::Static);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        graph.apply_radial_impulse(
+            Vector3::default(),
+            6.0,
+            10.0,
+            ImpulseFalloff::Linear,
+            Default::default(),
+        );
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let velocity = |handle: Handle<Node>| graph[handle].as_rigid_body().lin_vel();
+
+        // Bodies are pushed away from the center, closer bodies are pushed harder.
+        assert!(velocity(near).x > 0.0);
+        assert!(velocity(far).x < 0.0);
+        assert!(velocity(near).x > velocity(far).x.abs());
+        assert_eq!(velocity(outside), Vector3::default());
+        assert_eq!(velocity(fixed), Vector3::default());
+    }
+
+    #[test]
+    fn test_collider_shape_respects_scale() {
+        let mut graph = Graph::new();
+
+        let ball = ColliderBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(1.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_shape(ColliderShape::ball(0.5))
+        .build(&mut graph);
+        let cuboid = ColliderBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_scale(Vector3::new(1.0, 3.0, 1.0))
+                    .build(),
+            ),
+        )
+        .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+        .build(&mut graph);
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_scale(Vector3::repeat(2.0))
+                        .build(),
+                )
+                .with_children(&[ball, cuboid]),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let native_collider = |graph: &Graph, collider: Handle<Node>| {
+            graph
+                .physics
+                .native_collider(graph[collider].as_collider().native.get())
+                .unwrap()
+                .clone()
+        };
+
+        let native_ball = native_collider(&graph, ball);
+        assert_eq!(native_ball.shape().as_ball().unwrap().radius, 1.0);
+        // Offset of the collider is scaled too.
+        assert_eq!(
+            native_ball
+                .position_wrt_parent()
+                .unwrap()
+                .translation
+                .vector,
+            Vector3::new(2.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            native_collider(&graph, cuboid)
+                .shape()
+                .as_cuboid()
+                .unwrap()
+                .half_extents,
+            Vector3::new(1.0, 3.0, 1.0)
+        );
+
+        // Changing the scale must re-create the shapes.
+        graph[body]
+            .local_transform_mut()
+            .set_scale(Vector3::repeat(3.0));
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let native_ball = native_collider(&graph, ball);
+        assert_eq!(native_ball.shape().as_ball().unwrap().radius, 1.5);
+        assert_eq!(
+            native_ball
+                .position_wrt_parent()
+                .unwrap()
+                .translation
+                .vector,
+            Vector3::new(3.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_geometry_sources_scale_is_baked() {
+        let mut graph = Graph::new();
+
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(Matrix4::identity()),
+            ))
+            .build()])
+            .build(&mut graph);
+        let mesh_pivot = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(-10.0, 0.0, -10.0))
+                        .with_local_scale(Vector3::repeat(2.0))
+                        .build(),
+                )
+                .with_children(&[mesh]),
+        )
+        .build(&mut graph);
+        let cube = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::trimesh(vec![GeometrySource(mesh)]))
+            .build(&mut graph);
+
+        let terrain = TerrainBuilder::new(BaseBuilder::new())
+            .with_height_map_size(Vector2::new(32, 32))
+            .build(&mut graph);
+        let terrain_pivot = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_scale(Vector3::new(1.0, 2.0, 1.0))
+                        .build(),
+                )
+                .with_children(&[terrain]),
+        )
+        .build(&mut graph);
+        let ground = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::heightfield(GeometrySource(terrain)))
+            .build(&mut graph);
+
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[cube, ground]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        graph[terrain]
+            .as_terrain_mut()
+            .for_each_height_map_pixel(|pixel, _| *pixel = 1.0);
+
+        let hit_height = |graph: &Graph, x: f32, z: f32| {
+            let mut hits = Vec::new();
+            graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::new(x, 10.0, z),
+                    ray_direction: -Vector3::y(),
+                    max_len: 20.0,
+                    groups: Default::default(),
+                    sort_results: true,
+                    filter: None,
+                },
+                &mut hits,
+            );
+            hits[0].position.y
+        };
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        assert!((hit_height(&graph, -10.0, -10.0) - 1.0).abs() < 0.001);
+        assert!((hit_height(&graph, 8.0, 8.0) - 2.0).abs() < 0.001);
+
+        // Scale of the geometry sources' ancestors changes, the shapes must be re-created.
+        graph[mesh_pivot]
+            .local_transform_mut()
+            .set_scale(Vector3::repeat(3.0));
+        graph[terrain_pivot]
+            .local_transform_mut()
+            .set_scale(Vector3::new(1.0, 3.0, 1.0));
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        assert!((hit_height(&graph, -10.0, -10.0) - 1.5).abs() < 0.001);
+        assert!((hit_height(&graph, 8.0, 8.0) - 3.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_collider_nested_in_rigid_body() {
+        let mut graph = Graph::new();
+
+        let collider = ColliderBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 1.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_shape(ColliderShape::ball(0.5))
+        .build(&mut graph);
+        let pivot = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(1.0, 0.0, 0.0))
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .build(&mut graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[pivot]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+        let other_body = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let native_collider = |graph: &Graph| {
+            graph
+                .physics
+                .native_collider(graph[collider].as_collider().native.get())
+                .unwrap()
+                .clone()
+        };
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let native = native_collider(&graph);
+        assert_eq!(
+            native.parent(),
+            Some(graph[body].as_rigid_body().native.get())
+        );
+        assert_eq!(
+            native.position_wrt_parent().unwrap().translation.vector,
+            Vector3::new(1.0, 1.0, 0.0)
+        );
+
+        // Moving an intermediate node moves the collider relative to the body.
+        graph[pivot]
+            .local_transform_mut()
+            .set_position(Vector3::new(2.0, 0.0, 0.0));
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        assert_eq!(
+            native_collider(&graph)
+                .position_wrt_parent()
+                .unwrap()
+                .translation
+                .vector,
+            Vector3::new(2.0, 1.0, 0.0)
+        );
+
+        // Re-linking an intermediate node attaches the collider to another body.
+        graph.link_nodes(pivot, other_body);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        assert_eq!(
+            native_collider(&graph).parent(),
+            Some(graph[other_body].as_rigid_body().native.get())
+        );
+    }
+
+    #[test]
+    fn test_compound_collider() {
+        let mut graph = Graph::new();
+
+        let table = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::compound([
+                (
+                    Vector3::new(0.0, 1.0, 0.0),
+                    UnitQuaternion::identity(),
+                    ColliderShape::cuboid(1.0, 0.05, 1.0),
+                ),
+                (
+                    Vector3::new(-0.9, 0.5, 0.0),
+                    UnitQuaternion::identity(),
+                    ColliderShape::cuboid(0.05, 0.5, 0.05),
+                ),
+                (
+                    Vector3::new(0.9, 0.5, 0.0),
+                    UnitQuaternion::identity(),
+                    ColliderShape::cuboid(0.05, 0.5, 0.05),
+                ),
+                // Composite shapes must be ignored.
+                (
+                    Vector3::default(),
+                    UnitQuaternion::identity(),
+                    ColliderShape::trimesh(vec![]),
+                ),
+            ]))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[table]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let native = graph
+            .physics
+            .native_collider(graph[table].as_collider().native.get())
+            .unwrap();
+        assert_eq!(native.shape().as_compound().unwrap().shapes().len(), 3);
+
+        let hit_height = |graph: &Graph, x: f32| {
+            let mut hits = Vec::new();
+            graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::new(x, 10.0, 0.5),
+                    ray_direction: -Vector3::y(),
+                    max_len: 20.0,
+                    groups: Default::default(),
+                    sort_results: true,
+                    filter: None,
+                },
+                &mut hits,
+            );
+            hits.first().map(|hit| hit.position.y)
+        };
+
+        // The table top is hit from above, the space below it is free.
+        assert!((hit_height(&graph, 0.0).unwrap() - 1.05).abs() < 0.001);
+        assert!(graph.is_shape_free(
+            &ColliderShape::ball(0.2),
+            &Isometry3::translation(0.0, 0.5, 0.0),
+            Default::default(),
+            None,
+        ));
+        assert!(!graph.is_shape_free(
+            &ColliderShape::ball(0.2),
+            &Isometry3::translation(0.9, 0.5, 0.0),
+            Default::default(),
+            None,
+        ));
+    }
+
+    #[test]
+    fn test_physics_hooks() {
+        struct Hooks {
+            ghost: Handle<Node>,
+        }
+
+        impl PhysicsHooks for Hooks {
+            fn filter_contact_pair(&self, pair: &ColliderPair) -> bool {
+                pair.rigid_body1 != self.ghost && pair.rigid_body2 != self.ghost
+            }
+        }
+
+        let mut graph = Graph::new();
+
+        let ground = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(10.0, 0.5, 10.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[ground]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let add_ball = |graph: &mut Graph, x: f32| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::ball(0.5))
+                .build(graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_children(&[collider])
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(x, 2.0, 0.0))
+                            .build(),
+                    ),
+            )
+            .build(graph)
+        };
+
+        let ball = add_ball(&mut graph, -2.0);
+        let ghost = add_ball(&mut graph, 2.0);
+
+        assert!(graph
+            .physics
+            .set_hooks(Some(Box::new(Hooks { ghost })))
+            .is_none());
+
+        for _ in 0..120 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        assert!(graph[ball].global_position().y > 0.5);
+        assert!(graph[ghost].global_position().y < -1.0);
+        assert!(graph.physics.set_hooks(None).is_some());
+    }
 }
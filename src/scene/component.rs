@@ -0,0 +1,131 @@
+//! Node components allow you to attach capabilities to any scene node, instead of creating dedicated
+//! child nodes for them. See [`NodeComponent`] docs for more info.
+
+use crate::{
+    core::{visitor::Visit, TypeUuidProvider},
+    scene::user_data::UserData,
+};
+
+/// A component that can be attached to a scene node. It is implemented automatically for every type
+/// that is `Clone + Send + Debug + Visit + TypeUuidProvider`.
+///
+/// Components are serializable user data (see [`crate::scene::user_data::UserDataStorage`]), so a
+/// node may have at most one component of each type. The components are cloned and serialized
+/// together with the node.
+///
+/// Components are also available via [`crate::scene::node::Node::query_component_ref`] (and thus
+/// via [`crate::scene::graph::Graph::try_get_of_type`]), which means that a capability could be
+/// fetched in the same way regardless of whether it is provided by the node itself or attached to
+/// it as a component. Components of the node itself take precedence.
+///
+/// # Serialization
+///
+/// Type of every component must be registered in
+/// [`crate::engine::SerializationContext::user_data_constructors`], so it could be created on
+/// loading. Components of unknown types are skipped on loading.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{uuid::uuid, uuid::Uuid, visitor::prelude::*, TypeUuidProvider},
+///     scene::node::Node,
+/// };
+///
+/// #[derive(Clone, Debug, Default, Visit)]
+/// struct Interactable {
+///     prompt: String,
+/// }
+///
+/// impl TypeUuidProvider for Interactable {
+///     fn type_uuid() -> Uuid {
+///         uuid!("3c1f0e5a-8d2b-4a6e-9f71-5b4c2d8e0a13")
+///     }
+/// }
+///
+/// fn make_interactable(node: &mut Node) {
+///     node.add_component(Interactable {
+///         prompt: "Open".to_string(),
+///     });
+/// }
+///
+/// fn prompt(node: &Node) -> Option<&str> {
+///     node.query_component_ref::<Interactable>()
+///         .map(|i| i.prompt.as_str())
+/// }
+/// ```
+pub trait NodeComponent: UserData + Visit + TypeUuidProvider {}
+
+impl<T> NodeComponent for T where T: UserData + Visit + TypeUuidProvider {}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            uuid::{uuid, Uuid},
+            visitor::prelude::*,
+            TypeUuidProvider,
+        },
+        engine::SerializationContext,
+        scene::{
+            base::{Base, BaseBuilder},
+            graph::Graph,
+            pivot::{Pivot, PivotBuilder},
+        },
+    };
+    use std::sync::Arc;
+
+    #[derive(Clone, Debug, Default, PartialEq, Visit)]
+    struct Emitter {
+        rate: f32,
+    }
+
+    impl TypeUuidProvider for Emitter {
+        fn type_uuid() -> Uuid {
+            uuid!("5d0b7e2c-1a9f-4c6d-8e3b-2f7a4c9d1e60")
+        }
+    }
+
+    #[test]
+    fn test_node_components() {
+        let mut graph = Graph::new();
+        let node = PivotBuilder::new(BaseBuilder::new().with_component(Emitter { rate: 1.0 }))
+            .build(&mut graph);
+
+        // Components must be accessible in the same way as components of the node itself.
+        assert!(graph.try_get_of_type::<Pivot>(node).is_some());
+        graph.try_get_mut_of_type::<Emitter>(node).unwrap().rate = 3.0;
+        assert_eq!(graph[node].component(), Some(&Emitter { rate: 3.0 }));
+        assert_eq!(
+            graph[node].add_component(Emitter { rate: 2.0 }),
+            Some(Emitter { rate: 3.0 })
+        );
+
+        let (copy, map) = graph.clone(graph.get_root(), &mut |_, _| true);
+        let copy_node = &copy[map.inner()[&node]];
+        assert_eq!(copy_node.component(), Some(&Emitter { rate: 2.0 }));
+
+        let serialization_context = Arc::new(SerializationContext::new());
+        serialization_context
+            .user_data_constructors
+            .add::<Emitter>();
+
+        let mut base = (**copy_node).clone();
+        let mut visitor = Visitor::new();
+        visitor.blackboard.register(serialization_context.clone());
+        base.visit("Base", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        visitor.blackboard.register(serialization_context);
+        let mut loaded = Base::default();
+        loaded.visit("Base", &mut visitor).unwrap();
+        assert_eq!(loaded.component(), Some(&Emitter { rate: 2.0 }));
+
+        assert_eq!(
+            loaded.remove_component::<Emitter>(),
+            Some(Emitter { rate: 2.0 })
+        );
+        assert_eq!(loaded.component::<Emitter>(), None);
+    }
+}
@@ -697,15 +697,17 @@ impl ColliderBuilder {
 #[cfg(test)]
 mod test {
 
-    use crate::core::algebra::Vector2;
+    use crate::core::algebra::{Point2, Vector2, Vector3};
     use crate::scene::{
         base::BaseBuilder,
         dim2::{
             collider::{ColliderBuilder, ColliderShape},
+            physics::RayCastOptions,
             rigidbody::RigidBodyBuilder,
         },
-        graph::Graph,
+        graph::{Graph, GraphUpdateSwitches},
         rigidbody::RigidBodyType,
+        transform::TransformBuilder,
     };
 
     #[test]
@@ -765,4 +767,63 @@ mod test {
                 .count()
         );
     }
+
+    #[test]
+    fn test_query_pipeline_scheduling_2d() {
+        let mut graph = Graph::new();
+
+        let create_box = |graph: &mut Graph, y: f32| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::cuboid(0.5, 0.5))
+                .build(graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(0.0, y, 0.0))
+                            .build(),
+                    )
+                    .with_children(&[collider]),
+            )
+            .with_body_type(RigidBodyType::Static)
+            .build(graph)
+        };
+
+        let cast = |graph: &Graph| {
+            let mut hits = Vec::new();
+            graph.physics2d.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point2::new(0.0, 10.0),
+                    ray_direction: -Vector2::y(),
+                    max_len: 20.0,
+                    groups: Default::default(),
+                    sort_results: true,
+                    filter: None,
+                },
+                &mut hits,
+            );
+            hits.len()
+        };
+
+        let lower = create_box(&mut graph, 0.0);
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        assert_eq!(cast(&graph), 1);
+
+        // Removed colliders must be ignored immediately, even though native ones are still alive.
+        graph.remove_node(lower);
+        assert_eq!(cast(&graph), 0);
+
+        // New colliders become visible after the update, even if the simulation is disabled.
+        create_box(&mut graph, 0.0);
+        create_box(&mut graph, 2.0);
+        graph.update(
+            Vector2::new(800.0, 600.0),
+            1.0 / 60.0,
+            GraphUpdateSwitches {
+                physics2d: false,
+                ..Default::default()
+            },
+        );
+        assert_eq!(cast(&graph), 2);
+    }
 }
@@ -1,5 +1,21 @@
 //! The module contains 2D scene nodes and physics. Despite the naming, scene nodes are still 3D
 //! but physics simulation is in true 2D.
+//!
+//! Every graph has both 3D and 2D physics worlds ([`crate::scene::graph::Graph::physics`] and
+//! [`crate::scene::graph::Graph::physics2d`]), 2D rigid bodies, colliders and joints from this module
+//! are simulated by the latter. The worlds are independent, a scene can pick the one it needs by
+//! disabling the other one (the flag is saved with the scene):
+//!
+//! ```rust
+//! use fyrox::scene::Scene;
+//!
+//! fn make_2d_scene() -> Scene {
+//!     let mut scene = Scene::new();
+//!     // The scene does not pay for 3D simulation.
+//!     scene.graph.physics.enabled = false;
+//!     scene
+//! }
+//! ```
 
 pub mod collider;
 pub mod joint;
@@ -29,12 +29,13 @@ use crate::{
         node::{Node, NodeTrait},
     },
 };
+use fxhash::FxHashSet;
 use rapier2d::{
     dynamics::{
-        CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet,
-        IslandManager, JointAxesMask, JointAxis, MultibodyJointHandle, MultibodyJointSet,
-        RigidBody, RigidBodyActivation, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
-        RigidBodyType,
+        CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJoint, ImpulseJointHandle,
+        ImpulseJointSet, IslandManager, JointAxesMask, JointAxis, MultibodyJointHandle,
+        MultibodyJointSet, RigidBody, RigidBodyActivation, RigidBodyBuilder, RigidBodyHandle,
+        RigidBodySet, RigidBodyType,
     },
     geometry::{
         BroadPhase, Collider, ColliderBuilder, ColliderHandle, ColliderSet, Cuboid,
@@ -47,7 +48,7 @@ use std::{
     cmp::Ordering,
     fmt::{Debug, Formatter},
     hash::Hash,
-    sync::Arc,
+    sync::{mpsc::Sender, Arc},
 };
 
 /// A trait for ray cast results storage. It has two implementations: Vec and ArrayVec.
@@ -128,7 +129,7 @@ pub struct Intersection {
 }
 
 /// A set of options for the ray cast.
-pub struct RayCastOptions {
+pub struct RayCastOptions<'a> {
     /// A ray origin.
     pub ray_origin: Point2<f32>,
 
@@ -143,6 +144,11 @@ pub struct RayCastOptions {
 
     /// Whether to sort intersections from closest to farthest.
     pub sort_results: bool,
+
+    /// An optional filter, that is called for every collider node hit by the ray. If it returns `false`,
+    /// the collider is excluded from the results. It could be used, for example, to exclude colliders of
+    /// the shooter itself.
+    pub filter: Option<&'a dyn Fn(Handle<Node>) -> bool>,
 }
 
 /// Data of the contact.
@@ -328,6 +334,57 @@ fn isometry2_to_mat4(isometry: &Isometry2<f32>) -> Matrix4<f32> {
     .to_homogeneous()
 }
 
+/// An event that is produced when a native physics entity is created or removed for a scene node. It
+/// could be used to attach (and detach) custom per-body data at the right moment. See
+/// [`PhysicsWorld::subscribe_native_events`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NativePhysicsEvent {
+    /// A native rigid body was created for a rigid body node.
+    RigidBodyCreated {
+        /// A handle of the rigid body node.
+        node: Handle<Node>,
+        /// A handle of the native rigid body.
+        native: RigidBodyHandle,
+    },
+    /// A native rigid body was removed. The handle of the node could be invalid at this moment.
+    RigidBodyRemoved {
+        /// A handle of the rigid body node.
+        node: Handle<Node>,
+        /// A handle of the native rigid body.
+        native: RigidBodyHandle,
+    },
+    /// A native collider was created for a collider node.
+    ColliderCreated {
+        /// A handle of the collider node.
+        node: Handle<Node>,
+        /// A handle of the native collider.
+        native: ColliderHandle,
+    },
+    /// A native collider was removed (explicitly or together with its rigid body). The handle of the
+    /// node could be invalid at this moment.
+    ColliderRemoved {
+        /// A handle of the collider node.
+        node: Handle<Node>,
+        /// A handle of the native collider.
+        native: ColliderHandle,
+    },
+    /// A native joint was created for a joint node.
+    JointCreated {
+        /// A handle of the joint node.
+        node: Handle<Node>,
+        /// A handle of the native joint.
+        native: ImpulseJointHandle,
+    },
+    /// A native joint was removed (explicitly or together with one of its rigid bodies). The handle
+    /// of the node could be invalid at this moment.
+    JointRemoved {
+        /// A handle of the joint node.
+        node: Handle<Node>,
+        /// A handle of the native joint.
+        native: ImpulseJointHandle,
+    },
+}
+
 /// Physics world is responsible for physics simulation in the engine. There is a very few public
 /// methods, mostly for ray casting. You should add physical entities using scene graph nodes, such
 /// as RigidBody, Collider, Joint.
@@ -340,6 +397,7 @@ pub struct PhysicsWorld {
     pub integration_parameters: IntegrationParameters,
 
     /// Current gravity vector. Default is (0.0, -9.81)
+    /// Use [`Self::set_gravity`] to change gravity at runtime, it wakes up sleeping rigid bodies.
     pub gravity: Vector2<f32>,
 
     /// Performance statistics of a single simulation step.
@@ -388,14 +446,31 @@ pub struct PhysicsWorld {
     #[visit(skip)]
     #[reflect(hidden)]
     event_handler: Box<dyn EventHandler>,
+    // Subscribers of the events about creation and removal of native entities.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    native_event_senders: Vec<Sender<NativePhysicsEvent>>,
     #[visit(skip)]
     #[reflect(hidden)]
     query: RefCell<QueryPipeline>,
     #[visit(skip)]
     #[reflect(hidden)]
+    pending_removals: PendingRemovals,
+    #[visit(skip)]
+    #[reflect(hidden)]
     debug_render_pipeline: Mutex<DebugRenderPipeline>,
 }
 
+// Native rigid bodies and colliders, which removal is deferred until the next simulation step. See
+// the 3D counterpart for more info.
+#[derive(Default)]
+struct PendingRemovals {
+    bodies: FxHashSet<RigidBodyHandle>,
+    colliders: Vec<ColliderHandle>,
+    // Contains the colliders of the removed bodies too.
+    removed_colliders: FxHashSet<ColliderHandle>,
+}
+
 fn isometry_from_global_transform(transform: &Matrix4<f32>) -> Isometry2<f32> {
     Isometry2 {
         translation: Translation2::new(transform[12], transform[13]),
@@ -447,7 +522,9 @@ impl PhysicsWorld {
                 map: Default::default(),
             },
             event_handler: Box::new(()),
+            native_event_senders: Default::default(),
             query: RefCell::new(Default::default()),
+            pending_removals: Default::default(),
             performance_statistics: Default::default(),
             debug_render_pipeline: Default::default(),
         }
@@ -506,20 +583,73 @@ impl PhysicsWorld {
         self.performance_statistics.step_time += instant::Instant::now() - time;
     }
 
+    /// Adds new subscriber of the events about creation and removal of native physics entities.
+    pub fn subscribe_native_events(&mut self, sender: Sender<NativePhysicsEvent>) {
+        self.native_event_senders.push(sender);
+    }
+
+    fn broadcast_native_event(&mut self, event: NativePhysicsEvent) {
+        self.native_event_senders
+            .retain(|sender| sender.send(event).is_ok());
+    }
+
     pub(crate) fn add_body(&mut self, owner: Handle<Node>, mut body: RigidBody) -> RigidBodyHandle {
         body.user_data = owner.encode_to_u128();
-        self.bodies.insert(body)
+        let handle = self.bodies.insert(body);
+        self.broadcast_native_event(NativePhysicsEvent::RigidBodyCreated {
+            node: owner,
+            native: handle,
+        });
+        handle
     }
 
     pub(crate) fn remove_body(&mut self, handle: RigidBodyHandle) {
-        self.bodies.remove(
-            handle,
-            &mut self.islands,
-            &mut self.colliders,
-            &mut self.joints.set,
-            &mut self.multibody_joints.set,
-            true,
-        );
+        if self.pending_removals.bodies.contains(&handle) {
+            return;
+        }
+
+        let mut events = Vec::new();
+
+        // Attached colliders and joints will be removed together with the body.
+        if let Some(body) = self.bodies.get(handle) {
+            self.pending_removals.bodies.insert(handle);
+
+            for collider in body.colliders() {
+                if !self.pending_removals.removed_colliders.insert(*collider) {
+                    // Already removed, the event was sent.
+                    continue;
+                }
+                if let Some(native) = self.colliders.get(*collider) {
+                    events.push(NativePhysicsEvent::ColliderRemoved {
+                        node: Handle::decode_from_u128(native.user_data),
+                        native: *collider,
+                    });
+                }
+            }
+
+            for (_, _, joint, _) in self.joints.set.attached_joints(handle) {
+                if let Some(owner) = self.joints.map.value_of(&joint) {
+                    events.push(NativePhysicsEvent::JointRemoved {
+                        node: *owner,
+                        native: joint,
+                    });
+                }
+            }
+
+            events.push(NativePhysicsEvent::RigidBodyRemoved {
+                node: Handle::decode_from_u128(body.user_data),
+                native: handle,
+            });
+        }
+
+        for event in events {
+            if let NativePhysicsEvent::JointRemoved { native, .. } = event {
+                // Joints do not participate in queries, so there is no need to defer their removal.
+                self.joints.set.remove(native, false);
+                self.joints.map.remove_by_key(&native);
+            }
+            self.broadcast_native_event(event);
+        }
     }
 
     pub(crate) fn add_collider(
@@ -529,14 +659,74 @@ impl PhysicsWorld {
         mut collider: Collider,
     ) -> ColliderHandle {
         collider.user_data = owner.encode_to_u128();
-        self.colliders
-            .insert_with_parent(collider, parent_body, &mut self.bodies)
+        let handle = self
+            .colliders
+            .insert_with_parent(collider, parent_body, &mut self.bodies);
+        self.broadcast_native_event(NativePhysicsEvent::ColliderCreated {
+            node: owner,
+            native: handle,
+        });
+        handle
     }
 
     pub(crate) fn remove_collider(&mut self, handle: ColliderHandle) -> bool {
-        self.colliders
-            .remove(handle, &mut self.islands, &mut self.bodies, false)
-            .is_some()
+        if self.is_removed_collider(handle) {
+            return false;
+        }
+
+        if let Some(collider) = self.colliders.get(handle) {
+            let node = Handle::decode_from_u128(collider.user_data);
+            self.pending_removals.colliders.push(handle);
+            self.pending_removals.removed_colliders.insert(handle);
+            self.broadcast_native_event(NativePhysicsEvent::ColliderRemoved {
+                node,
+                native: handle,
+            });
+            true
+        } else {
+            false
+        }
+    }
+
+    // Returns `true` if the collider was removed, but its native representation is still alive until
+    // the next simulation step.
+    fn is_removed_collider(&self, handle: ColliderHandle) -> bool {
+        !self.pending_removals.removed_colliders.is_empty()
+            && self.pending_removals.removed_colliders.contains(&handle)
+    }
+
+    // Removes native rigid bodies and colliders, that were removed since the last simulation step.
+    pub(crate) fn flush_removals(&mut self) {
+        for handle in self.pending_removals.colliders.drain(..) {
+            self.colliders
+                .remove(handle, &mut self.islands, &mut self.bodies, false);
+        }
+
+        for handle in self.pending_removals.bodies.drain() {
+            self.bodies.remove(
+                handle,
+                &mut self.islands,
+                &mut self.colliders,
+                &mut self.joints.set,
+                &mut self.multibody_joints.set,
+                true,
+            );
+        }
+
+        self.pending_removals.removed_colliders.clear();
+    }
+
+    /// Rebuilds acceleration structure of scene queries (ray casts, etc.). The structure is rebuilt
+    /// automatically once per frame after the physics step, so colliders that were created or moved
+    /// after that are not visible for queries until the next frame. Call this method after such
+    /// changes if the queries must take them into account in the same frame. Colliders of removed
+    /// nodes are ignored by queries immediately, there is no need to call this method after removal.
+    ///
+    /// Keep in mind that the method is expensive and must not be called before every query.
+    pub fn refresh_queries(&self) {
+        self.query
+            .borrow_mut()
+            .update(&self.bodies, &self.colliders);
     }
 
     pub(crate) fn add_joint(
@@ -548,13 +738,72 @@ impl PhysicsWorld {
     ) -> ImpulseJointHandle {
         let handle = self.joints.set.insert(body1, body2, params, false);
         self.joints.map.insert(handle, owner);
+        self.broadcast_native_event(NativePhysicsEvent::JointCreated {
+            node: owner,
+            native: handle,
+        });
         handle
     }
 
     pub(crate) fn remove_joint(&mut self, handle: ImpulseJointHandle) {
         if self.joints.set.remove(handle, false).is_some() {
-            assert!(self.joints.map.remove_by_key(&handle).is_some());
+            let owner = self.joints.map.remove_by_key(&handle);
+            assert!(owner.is_some());
+            self.broadcast_native_event(NativePhysicsEvent::JointRemoved {
+                node: owner.unwrap(),
+                native: handle,
+            });
+        }
+    }
+
+    pub(crate) fn native_body(&self, handle: RigidBodyHandle) -> Option<&RigidBody> {
+        self.bodies
+            .get(handle)
+            .filter(|_| !self.pending_removals.bodies.contains(&handle))
+    }
+
+    pub(crate) fn native_joint(&self, handle: ImpulseJointHandle) -> Option<&ImpulseJoint> {
+        self.joints.set.get(handle)
+    }
+
+    // Copies runtime state (velocities, accumulated forces, activation state) of the given native
+    // rigid body (possibly from other physics world) to the rigid body with the given handle.
+    pub(crate) fn copy_rigid_body_state(&mut self, source: &RigidBody, dest: RigidBodyHandle) {
+        if let Some(dest) = self.bodies.get_mut(dest) {
+            dest.set_linvel(*source.linvel(), false);
+            dest.set_angvel(source.angvel(), false);
+            dest.reset_forces(false);
+            dest.reset_torques(false);
+            dest.add_force(source.user_force(), false);
+            dest.add_torque(source.user_torque(), false);
+            *dest.activation_mut() = *source.activation();
+        }
+    }
+
+    // Copies accumulated impulses of the given native joint (possibly from other physics world) to
+    // the joint with the given handle.
+    pub(crate) fn copy_joint_state(&mut self, source: &ImpulseJoint, dest: ImpulseJointHandle) {
+        if let Some(dest) = self.joints.set.get_mut(dest) {
+            dest.impulses = source.impulses;
+        }
+    }
+
+    /// Sets new gravity vector of the physics world and wakes up every dynamic rigid body, so the
+    /// new gravity is applied to sleeping bodies as well. Returns previous gravity vector. Zero
+    /// vector could be used to create zero-G scenes, per-body gravity could be tweaked using
+    /// gravity scale of rigid bodies.
+    pub fn set_gravity(&mut self, gravity: Vector2<f32>) -> Vector2<f32> {
+        for (_, body) in self.bodies.iter_mut() {
+            if body.is_dynamic() {
+                body.wake_up(true);
+            }
         }
+        std::mem::replace(&mut self.gravity, gravity)
+    }
+
+    /// Returns current gravity vector of the physics world.
+    pub fn gravity(&self) -> Vector2<f32> {
+        self.gravity
     }
 
     /// Draws physics world. Very useful for debugging, it allows you to see where are
@@ -574,14 +823,7 @@ impl PhysicsWorld {
     pub fn cast_ray<S: QueryResultsStorage>(&self, opts: RayCastOptions, query_buffer: &mut S) {
         let time = instant::Instant::now();
 
-        let mut query = self.query.borrow_mut();
-
-        // TODO: Ideally this must be called once per frame, but it seems to be impossible because
-        // a body can be deleted during the consecutive calls of this method which will most
-        // likely end up in panic because of invalid handle stored in internal acceleration
-        // structure. This could be fixed by delaying deleting of bodies/collider to the end
-        // of the frame.
-        query.update(&self.bodies, &self.colliders);
+        let query = self.query.borrow();
 
         query_buffer.clear();
         let ray = Ray::new(
@@ -590,16 +832,24 @@ impl PhysicsWorld {
                 .try_normalize(f32::EPSILON)
                 .unwrap_or_default(),
         );
+        let predicate = |handle: ColliderHandle, collider: &Collider| {
+            !self.is_removed_collider(handle)
+                && opts.filter.map_or(true, |filter| {
+                    filter(Handle::decode_from_u128(collider.user_data))
+                })
+        };
         query.intersections_with_ray(
             &self.bodies,
             &self.colliders,
             &ray,
             opts.max_len,
             true,
-            QueryFilter::new().groups(InteractionGroups::new(
-                u32_to_group(opts.groups.memberships.0),
-                u32_to_group(opts.groups.filter.0),
-            )),
+            QueryFilter::new()
+                .groups(InteractionGroups::new(
+                    u32_to_group(opts.groups.memberships.0),
+                    u32_to_group(opts.groups.filter.0),
+                ))
+                .predicate(&predicate),
             |handle, intersection| {
                 query_buffer.push(Intersection {
                     collider: Handle::decode_from_u128(
@@ -636,12 +886,20 @@ impl PhysicsWorld {
         new_global_transform: &Matrix4<f32>,
     ) {
         if let Some(native) = self.bodies.get_mut(rigid_body.native.get()) {
-            native.set_position(
-                isometry_from_global_transform(new_global_transform),
-                // Do not wake up body, it is too expensive and must be done **only** by explicit
-                // `wake_up` call!
-                false,
-            );
+            let position = isometry_from_global_transform(new_global_transform);
+            if native.body_type() == RigidBodyType::KinematicPositionBased {
+                // Kinematic bodies must be moved smoothly, so the physics engine could calculate
+                // their velocities and push dynamic bodies correctly instead of teleporting
+                // through them.
+                native.set_next_kinematic_position(position);
+            } else {
+                native.set_position(
+                    position,
+                    // Do not wake up body, it is too expensive and must be done **only** by explicit
+                    // `wake_up` call!
+                    false,
+                );
+            }
         }
     }
 
@@ -749,7 +1007,7 @@ impl PhysicsWorld {
                         .try_sync_model(|v| native.set_dominance_group(v));
                     rigid_body_node
                         .gravity_scale
-                        .try_sync_model(|v| native.set_gravity_scale(v, false));
+                        .try_sync_model(|v| native.set_gravity_scale(v, true));
 
                     // We must reset any forces applied at previous update step, otherwise physics engine
                     // will keep pushing the rigid body infinitely.
@@ -758,26 +1016,28 @@ impl PhysicsWorld {
                         native.reset_torques(false);
                     }
 
+                    // Forces and impulses must wake up the body, otherwise they're ignored by
+                    // sleeping bodies.
                     while let Some(action) = actions.pop_front() {
                         match action {
                             ApplyAction::Force(force) => {
-                                native.add_force(force, false);
+                                native.add_force(force, true);
                                 rigid_body_node.reset_forces.set(true);
                             }
                             ApplyAction::Torque(torque) => {
-                                native.add_torque(torque, false);
+                                native.add_torque(torque, true);
                                 rigid_body_node.reset_forces.set(true);
                             }
                             ApplyAction::ForceAtPoint { force, point } => {
-                                native.add_force_at_point(force, Point2::from(point), false);
+                                native.add_force_at_point(force, Point2::from(point), true);
                                 rigid_body_node.reset_forces.set(true);
                             }
-                            ApplyAction::Impulse(impulse) => native.apply_impulse(impulse, false),
+                            ApplyAction::Impulse(impulse) => native.apply_impulse(impulse, true),
                             ApplyAction::TorqueImpulse(impulse) => {
-                                native.apply_torque_impulse(impulse, false)
+                                native.apply_torque_impulse(impulse, true)
                             }
                             ApplyAction::ImpulseAtPoint { impulse, point } => {
-                                native.apply_impulse_at_point(impulse, Point2::from(point), false)
+                                native.apply_impulse_at_point(impulse, Point2::from(point), true)
                             }
                             ApplyAction::WakeUp => native.wake_up(true),
                         }
@@ -1069,6 +1329,39 @@ impl PhysicsWorld {
             .contact_pairs()
             .filter_map(|c| ContactPair::from_native(c, self))
     }
+
+    /// Returns approximate amount of memory (in bytes) held by native physics entities - rigid bodies,
+    /// colliders (including geometry of triangle meshes, height fields and convex polygons), joints
+    /// and contact pairs. Internal acceleration structures are not taken into account.
+    pub fn memory_usage(&self) -> usize {
+        self.bodies.len() * std::mem::size_of::<RigidBody>()
+            + self
+                .colliders
+                .iter()
+                .map(|(_, c)| std::mem::size_of::<Collider>() + shape_size_bytes(c.shape()))
+                .sum::<usize>()
+            + self.joints.set.len() * std::mem::size_of::<ImpulseJoint>()
+            + self.narrow_phase.contact_pairs().count()
+                * std::mem::size_of::<rapier2d::geometry::ContactPair>()
+    }
+}
+
+fn shape_size_bytes(shape: &dyn rapier2d::geometry::Shape) -> usize {
+    if let Some(trimesh) = shape.as_trimesh() {
+        std::mem::size_of_val(trimesh.vertices()) + std::mem::size_of_val(trimesh.indices())
+    } else if let Some(heightfield) = shape.as_heightfield() {
+        heightfield.heights().len() * std::mem::size_of::<f32>()
+    } else if let Some(polygon) = shape.as_convex_polygon() {
+        std::mem::size_of_val(polygon.points())
+    } else if let Some(compound) = shape.as_compound() {
+        compound
+            .shapes()
+            .iter()
+            .map(|(_, shape)| shape_size_bytes(&**shape))
+            .sum()
+    } else {
+        0
+    }
 }
 
 impl Default for PhysicsWorld {
@@ -62,6 +62,7 @@ pub(crate) enum ApplyAction {
 ///
 /// Rigid body that does not move for some time will go asleep. This means that the body will not
 /// move unless it is woken up by some other moving body. This feature allows to save CPU resources.
+/// Forces, torques and impulses applied via `apply_*` methods wake up the body automatically.
 #[derive(Visit, Reflect)]
 pub struct RigidBody {
     base: Base,
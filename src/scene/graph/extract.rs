@@ -0,0 +1,226 @@
+//! Render extraction allows you to decouple simulation from rendering by copying only the data that
+//! is needed for rendering into a separate snapshot. See [`RenderSnapshot`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::Matrix4, math::aabb::AxisAlignedBoundingBox, parking_lot::Mutex, pool::Handle,
+    },
+    material::SharedMaterial,
+    scene::{
+        graph::Graph,
+        mesh::{surface::SurfaceSharedData, Mesh, RenderPath},
+        node::Node,
+    },
+};
+use std::sync::Arc;
+
+/// Render data of a single surface of a mesh.
+#[derive(Clone, Debug)]
+pub struct RenderSurface {
+    /// Shared vertex and index buffers of the surface.
+    pub data: SurfaceSharedData,
+    /// Material of the surface.
+    pub material: SharedMaterial,
+    /// Final bone matrices (global transform of a bone multiplied by its inverse bind pose). Empty
+    /// if the surface is not skinned.
+    pub bone_matrices: Vec<Matrix4<f32>>,
+}
+
+/// Render data of a single mesh node.
+#[derive(Clone, Debug)]
+pub struct RenderInstance {
+    /// Handle of the node from which the data was extracted.
+    pub handle: Handle<Node>,
+    /// Global transform of the node.
+    pub global_transform: Matrix4<f32>,
+    /// World-space bounding box of the node.
+    pub world_bounding_box: AxisAlignedBoundingBox,
+    /// Render path of the mesh.
+    pub render_path: RenderPath,
+    /// Decal layer index of the mesh.
+    pub decal_layer_index: u8,
+    /// Depth offset factor of the node.
+    pub depth_offset: f32,
+    /// Whether the node casts shadows or not.
+    pub cast_shadows: bool,
+    /// Whether the node should be frustum culled or not.
+    pub frustum_culling: bool,
+    /// Weights of blend shapes of the mesh in `[0; 1]` range.
+    pub blend_shape_weights: Vec<f32>,
+    /// Surfaces of the mesh.
+    pub surfaces: Vec<RenderSurface>,
+}
+
+/// A snapshot of the render-relevant state of a graph - transforms, visibility and material
+/// references of meshes. Unlike the graph itself, the snapshot is cheap to produce and could be sent
+/// to another thread, so simulation of the next frame can run in parallel with rendering of the
+/// previous one. Vertex buffers and materials are shared (not copied) with the graph.
+///
+/// Only visible and enabled meshes are extracted. Use [`SnapshotExchange`] to pass snapshots between
+/// threads.
+#[derive(Clone, Debug, Default)]
+pub struct RenderSnapshot {
+    /// Index of a frame at which the snapshot was extracted. It is incremented by
+    /// [`SnapshotExchange::publish`], and could be used to detect dropped or repeated frames.
+    pub frame: u64,
+    /// Render data of every visible mesh.
+    pub instances: Vec<RenderInstance>,
+}
+
+impl RenderSnapshot {
+    /// Extracts a new snapshot from the given graph.
+    pub fn extract(graph: &Graph) -> Self {
+        let mut snapshot = Self::default();
+        snapshot.extract_into(graph);
+        snapshot
+    }
+
+    /// Overwrites the snapshot with the data from the given graph. Unlike [`Self::extract`], it
+    /// re-uses previously allocated memory of the snapshot.
+    pub fn extract_into(&mut self, graph: &Graph) {
+        let mut instance_count = 0;
+
+        for (handle, node) in graph.pair_iter() {
+            if !node.global_visibility() || !node.is_globally_enabled() {
+                continue;
+            }
+
+            let mesh = match node.cast::<Mesh>() {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+
+            if instance_count == self.instances.len() {
+                self.instances.push(RenderInstance {
+                    handle,
+                    global_transform: Matrix4::identity(),
+                    world_bounding_box: Default::default(),
+                    render_path: RenderPath::Deferred,
+                    decal_layer_index: 0,
+                    depth_offset: 0.0,
+                    cast_shadows: true,
+                    frustum_culling: true,
+                    blend_shape_weights: Default::default(),
+                    surfaces: Default::default(),
+                });
+            }
+
+            let instance = &mut self.instances[instance_count];
+            instance_count += 1;
+
+            instance.handle = handle;
+            instance.global_transform = mesh.global_transform();
+            instance.world_bounding_box = mesh.world_bounding_box();
+            instance.render_path = mesh.render_path();
+            instance.decal_layer_index = mesh.decal_layer_index();
+            instance.depth_offset = mesh.depth_offset_factor();
+            instance.cast_shadows = mesh.cast_shadows();
+            instance.frustum_culling = mesh.frustum_culling();
+
+            instance.blend_shape_weights.clear();
+            instance
+                .blend_shape_weights
+                .extend(mesh.blend_shapes().iter().map(|bs| bs.weight / 100.0));
+
+            instance.surfaces.truncate(mesh.surfaces().len());
+            for (index, surface) in mesh.surfaces().iter().enumerate() {
+                let bones = surface.bones().iter().map(|bone_handle| {
+                    if let Some(bone_node) = graph.try_get(*bone_handle) {
+                        bone_node.global_transform() * bone_node.inv_bind_pose_transform()
+                    } else {
+                        Matrix4::identity()
+                    }
+                });
+
+                if let Some(render_surface) = instance.surfaces.get_mut(index) {
+                    render_surface.data = surface.data();
+                    render_surface.material = surface.material().clone();
+                    render_surface.bone_matrices.clear();
+                    render_surface.bone_matrices.extend(bones);
+                } else {
+                    instance.surfaces.push(RenderSurface {
+                        data: surface.data(),
+                        material: surface.material().clone(),
+                        bone_matrices: bones.collect(),
+                    });
+                }
+            }
+        }
+
+        self.instances.truncate(instance_count);
+    }
+}
+
+#[derive(Default)]
+struct ExchangeState {
+    frame: u64,
+    latest: Option<RenderSnapshot>,
+    spare: Vec<RenderSnapshot>,
+}
+
+/// A thread-safe exchange point for render snapshots. Simulation thread publishes a new snapshot
+/// after each update, render thread takes the latest one, renders it and gives it back, so its
+/// memory could be re-used by next snapshots. If the render thread is slower than the simulation,
+/// intermediate snapshots are dropped (re-used) and only the latest one is rendered.
+///
+/// The exchange can be cloned, every clone refers to the same shared state.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::graph::{extract::SnapshotExchange, Graph};
+///
+/// fn simulation_thread(graph: &Graph, exchange: &SnapshotExchange) {
+///     // Call this after the graph was updated.
+///     exchange.publish(graph);
+/// }
+///
+/// fn render_thread(exchange: &SnapshotExchange) {
+///     if let Some(snapshot) = exchange.take_latest() {
+///         for instance in snapshot.instances.iter() {
+///             // Render the instance here.
+///         }
+///         exchange.recycle(snapshot);
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct SnapshotExchange {
+    state: Arc<Mutex<ExchangeState>>,
+}
+
+impl SnapshotExchange {
+    /// Extracts a new snapshot from the given graph and makes it the latest one. Extraction is done
+    /// without holding the lock, so the render thread is not blocked while the graph is being
+    /// extracted.
+    pub fn publish(&self, graph: &Graph) {
+        let (mut snapshot, frame) = {
+            let mut state = self.state.lock();
+            state.frame += 1;
+            (state.spare.pop().unwrap_or_default(), state.frame)
+        };
+
+        snapshot.extract_into(graph);
+        snapshot.frame = frame;
+
+        let mut state = self.state.lock();
+        if let Some(previous) = state.latest.replace(snapshot) {
+            state.spare.push(previous);
+        }
+    }
+
+    /// Takes the latest published snapshot, if any. Next call will return [`None`] until a new
+    /// snapshot is published.
+    pub fn take_latest(&self) -> Option<RenderSnapshot> {
+        self.state.lock().latest.take()
+    }
+
+    /// Gives the snapshot back to the exchange, so its memory could be re-used.
+    pub fn recycle(&self, snapshot: RenderSnapshot) {
+        let mut state = self.state.lock();
+        // Two spare snapshots are enough for double buffering, drop the rest.
+        if state.spare.len() < 2 {
+            state.spare.push(snapshot);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Named groups of scene nodes. See [`NodeGroups`] docs for more info.
+
+use crate::{
+    core::{
+        pool::Handle,
+        visitor::{Visit, VisitResult, Visitor},
+    },
+    scene::{graph::map::NodeHandleMap, node::Node},
+};
+use fxhash::{FxHashMap, FxHashSet};
+
+/// A container that maps group names to sets of scene nodes. Every graph has its own instance of the
+/// container (see [`crate::scene::graph::Graph::groups`]), which is saved together with the scene.
+///
+/// Groups are useful to replace ad-hoc `Vec<Handle<Node>>` lists of gameplay entities (enemies, pickups,
+/// spawn points, etc.) - such lists tend to contain stale handles when the nodes are destroyed. Unlike
+/// them, the graph automatically removes destroyed nodes from every group they belong to. A node can
+/// belong to any number of groups at once.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::{base::BaseBuilder, graph::Graph, pivot::PivotBuilder};
+///
+/// fn make_enemies(graph: &mut Graph) {
+///     for _ in 0..10 {
+///         let enemy = PivotBuilder::new(BaseBuilder::new()).build(graph);
+///         graph.add_to_group(enemy, "Enemies");
+///     }
+/// }
+///
+/// fn enemy_count(graph: &Graph) -> usize {
+///     graph.groups().members("Enemies").count()
+/// }
+/// ```
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct NodeGroups {
+    groups: FxHashMap<String, FxHashSet<Handle<Node>>>,
+}
+
+impl NodeGroups {
+    /// Adds the node to a group with the given name, the group will be created if it does not exist.
+    /// Returns `true` if the node was added, `false` - if it was already in the group.
+    ///
+    /// # Important notes
+    ///
+    /// This method does not check whether the handle is valid or not, use
+    /// [`crate::scene::graph::Graph::add_to_group`] if you're not sure.
+    pub fn add(&mut self, group: &str, handle: Handle<Node>) -> bool {
+        if let Some(members) = self.groups.get_mut(group) {
+            members.insert(handle)
+        } else {
+            let mut members = FxHashSet::default();
+            members.insert(handle);
+            self.groups.insert(group.to_owned(), members);
+            true
+        }
+    }
+
+    /// Removes the node from a group with the given name. Returns `true` if the node was in the group,
+    /// `false` - otherwise. The group itself is kept even if it becomes empty.
+    pub fn remove(&mut self, group: &str, handle: Handle<Node>) -> bool {
+        self.groups
+            .get_mut(group)
+            .map_or(false, |members| members.remove(&handle))
+    }
+
+    /// Removes a group with the given name and returns its members, if any. The nodes are not removed
+    /// from the graph.
+    pub fn remove_group(&mut self, group: &str) -> Option<FxHashSet<Handle<Node>>> {
+        self.groups.remove(group)
+    }
+
+    /// Returns `true` if the node is in a group with the given name, `false` - otherwise.
+    pub fn contains(&self, group: &str, handle: Handle<Node>) -> bool {
+        self.groups
+            .get(group)
+            .map_or(false, |members| members.contains(&handle))
+    }
+
+    /// Returns `true` if a group with the given name exists, `false` - otherwise.
+    pub fn has_group(&self, group: &str) -> bool {
+        self.groups.contains_key(group)
+    }
+
+    /// Returns an iterator over the members of a group with the given name. The iterator will be empty
+    /// if there is no such group. The order of the members is unspecified.
+    pub fn members<'a>(&'a self, group: &str) -> impl Iterator<Item = Handle<Node>> + 'a {
+        self.groups
+            .get(group)
+            .into_iter()
+            .flat_map(|members| members.iter().cloned())
+    }
+
+    /// Returns an iterator over the names of every group the given node belongs to.
+    pub fn groups_of(&self, handle: Handle<Node>) -> impl Iterator<Item = &str> {
+        self.groups
+            .iter()
+            .filter(move |(_, members)| members.contains(&handle))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Returns an iterator over the names of every group in the container.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.groups.keys().map(|name| name.as_str())
+    }
+
+    /// Returns total amount of groups in the container.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns `true` if the container has no groups, `false` - otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Removes every group.
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+
+    /// Removes the node from every group and returns names of the groups the node was in.
+    pub(crate) fn remove_node(&mut self, handle: Handle<Node>) -> Vec<String> {
+        let mut names = Vec::new();
+        for (name, members) in self.groups.iter_mut() {
+            if members.remove(&handle) {
+                names.push(name.clone());
+            }
+        }
+        names
+    }
+
+    /// Creates a copy of the container with every handle mapped using the given map. Handles that
+    /// have no mapping are dropped, groups are kept even if they become empty.
+    pub(crate) fn remap(&self, map: &NodeHandleMap) -> Self {
+        Self {
+            groups: self
+                .groups
+                .iter()
+                .map(|(name, members)| {
+                    let members = members
+                        .iter()
+                        .filter_map(|handle| map.inner().get(handle).cloned())
+                        .collect();
+                    (name.clone(), members)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Visit for NodeGroups {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        self.groups.visit("Groups", &mut region)
+    }
+}
@@ -0,0 +1,205 @@
+//! A special container that holds a set of migrations, that are used to upgrade graphs stored in
+//! older formats to the current one. See [`GraphMigrationContainer`] docs for more info.
+
+use crate::{core::parking_lot::Mutex, scene::graph::Graph};
+use std::collections::BTreeMap;
+
+/// A simple type alias for boxed graph migration.
+pub type GraphMigration = Box<dyn FnMut(&mut Graph) + Send>;
+
+/// A special container that holds a set of migrations, that are used to upgrade graphs stored in
+/// older formats to the current one. Every migration is bound to a format version it upgrades from,
+/// the migration must upgrade the graph to the next version (`version + 1`). When a graph with an
+/// older version is loaded, every migration starting from the version of the graph up to the current
+/// [`Graph::VERSION`] will be applied to the graph in ascending order.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::graph::migration::GraphMigrationContainer;
+///
+/// let migrations = GraphMigrationContainer::new();
+///
+/// migrations.add(
+///     0,
+///     Box::new(|graph| {
+///         for node in graph.linear_iter_mut() {
+///             // Fix up the data of the nodes here.
+///             let name = node.name().trim().to_owned();
+///             node.set_name(name);
+///         }
+///     }),
+/// );
+/// ```
+#[derive(Default)]
+pub struct GraphMigrationContainer {
+    map: Mutex<BTreeMap<u32, Vec<GraphMigration>>>,
+}
+
+impl GraphMigrationContainer {
+    /// Creates new empty migration container.
+    pub fn new() -> Self {
+        GraphMigrationContainer::default()
+    }
+
+    /// Adds new migration that upgrades a graph from the given version to the next one. There could
+    /// be multiple migrations for the same version, they will be applied in order of addition.
+    pub fn add(&self, version: u32, migration: GraphMigration) {
+        self.map.lock().entry(version).or_default().push(migration);
+    }
+
+    /// Removes every migration for the given version.
+    pub fn remove(&self, version: u32) {
+        self.map.lock().remove(&version);
+    }
+
+    /// Applies every migration starting from the given version up to the current [`Graph::VERSION`]
+    /// to the given graph. Returns total amount of applied migrations.
+    pub fn migrate(&self, graph: &mut Graph, version: u32) -> usize {
+        if version >= Graph::VERSION {
+            return 0;
+        }
+
+        // Migrations are taken out of the container while running, so they are free to use the
+        // container (for example - to load other graphs).
+        let mut taken = self
+            .map
+            .lock()
+            .range_mut(version..Graph::VERSION)
+            .map(|(version, migrations)| (*version, std::mem::take(migrations)))
+            .collect::<Vec<_>>();
+
+        let mut count = 0;
+        for (_, migrations) in taken.iter_mut() {
+            for migration in migrations.iter_mut() {
+                (migration)(graph);
+                count += 1;
+            }
+        }
+
+        let mut map = self.map.lock();
+        for (version, mut migrations) in taken {
+            // Keep the migrations that were added while running after the existing ones.
+            let added = map.entry(version).or_default();
+            migrations.append(added);
+            *added = migrations;
+        }
+
+        count
+    }
+
+    /// Returns total amount of migrations.
+    pub fn len(&self) -> usize {
+        self.map.lock().values().map(|m| m.len()).sum()
+    }
+
+    /// Returns true if the container is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::visitor::prelude::*,
+        engine::SerializationContext,
+        scene::{base::BaseBuilder, graph::Graph, pivot::PivotBuilder},
+    };
+    use std::sync::Arc;
+
+    fn trim_names(serialization_context: &SerializationContext) {
+        serialization_context.graph_migrations.add(
+            0,
+            Box::new(|graph| {
+                for node in graph.linear_iter_mut() {
+                    let name = node.name().trim().to_owned();
+                    node.set_name(name);
+                }
+            }),
+        );
+    }
+
+    fn load(data: Vec<u8>, serialization_context: Arc<SerializationContext>) -> Graph {
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        visitor.blackboard.register(serialization_context);
+        let mut graph = Graph::default();
+        graph.visit("Graph", &mut visitor).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_migrate_old_graph() {
+        let mut graph = Graph::new();
+        let node = PivotBuilder::new(BaseBuilder::new().with_name("  Pivot  ")).build(&mut graph);
+
+        // Graphs saved prior to versioning do not have the version field.
+        let mut visitor = Visitor::new();
+        {
+            let mut region = visitor.enter_region("Graph").unwrap();
+            graph.root.visit("Root", &mut region).unwrap();
+            graph.pool.visit("Pool", &mut region).unwrap();
+            graph
+                .sound_context
+                .visit("SoundContext", &mut region)
+                .unwrap();
+            graph.physics.visit("PhysicsWorld", &mut region).unwrap();
+            graph
+                .physics2d
+                .visit("PhysicsWorld2D", &mut region)
+                .unwrap();
+        }
+        let old_data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::new();
+        graph.visit("Graph", &mut visitor).unwrap();
+        let current_data = visitor.save_binary_to_vec().unwrap();
+
+        let serialization_context = Arc::new(SerializationContext::new());
+        trim_names(&serialization_context);
+
+        let loaded = load(old_data, serialization_context.clone());
+        assert_eq!(loaded[node].name(), "Pivot");
+
+        // Graphs of the current version must be left untouched.
+        let loaded = load(current_data, serialization_context);
+        assert_eq!(loaded[node].name(), "  Pivot  ");
+    }
+
+    #[test]
+    fn test_migrate_version_range() {
+        let serialization_context = SerializationContext::new();
+        let migrations = &serialization_context.graph_migrations;
+        trim_names(&serialization_context);
+        migrations.add(Graph::VERSION, Box::new(|_| panic!("must not be applied")));
+        assert_eq!(migrations.len(), 2);
+
+        let mut graph = Graph::new();
+        assert_eq!(migrations.migrate(&mut graph, 0), 1);
+        assert_eq!(migrations.migrate(&mut graph, Graph::VERSION), 0);
+
+        migrations.remove(0);
+        assert_eq!(migrations.len(), 1);
+        assert_eq!(migrations.migrate(&mut graph, 0), 0);
+    }
+
+    #[test]
+    fn test_migration_uses_container() {
+        let serialization_context = Arc::new(SerializationContext::new());
+        let context = serialization_context.clone();
+        serialization_context.graph_migrations.add(
+            0,
+            Box::new(move |_| {
+                // Must not deadlock.
+                context.graph_migrations.add(0, Box::new(|_| ()));
+            }),
+        );
+
+        let migrations = &serialization_context.graph_migrations;
+        let mut graph = Graph::new();
+        assert_eq!(migrations.migrate(&mut graph, 0), 1);
+        assert_eq!(migrations.len(), 2);
+        assert_eq!(migrations.migrate(&mut graph, 0), 2);
+        assert_eq!(migrations.len(), 3);
+    }
+}
@@ -25,49 +25,72 @@
 use crate::{
     asset::ResourceStateRef,
     core::{
-        algebra::{Matrix4, Rotation3, UnitQuaternion, Vector2, Vector3},
+        algebra::{
+            Isometry3, Matrix3, Matrix4, Point3, Rotation3, UnitQuaternion, Vector2, Vector3,
+        },
         instant,
         log::{Log, MessageKind},
-        math::Matrix4Ext,
+        math::{get_barycentric_coords, Matrix4Ext},
         pool::{Handle, MultiBorrowContext, Pool, Ticket},
         reflect::prelude::*,
         variable::try_inherit_properties,
-        visitor::{Visit, VisitResult, Visitor},
+        visitor::{Visit, VisitError, VisitResult, Visitor},
     },
+    engine::SerializationContext,
     material::SharedMaterial,
     resource::model::{ModelResource, ModelResourceExtension, NodeMapping},
     scene::{
         self,
         base::NodeScriptMessage,
         camera::Camera,
+        collider::{Collider, ColliderShape, InteractionGroups},
         dim2::{self},
         graph::{
             event::{GraphEvent, GraphEventBroadcaster},
+            group::NodeGroups,
             map::NodeHandleMap,
-            physics::{PhysicsPerformanceStatistics, PhysicsWorld},
+            physics::{
+                CollisionEventKind, Contact, ImpulseFalloff, Intersection, MeshHit,
+                PhysicsPerformanceStatistics, PhysicsWorld, QueryResultsStorage, ShapeCastOptions,
+                TimeOfImpact,
+            },
+            snapshot::{GraphSnapshot, SnapshotFlags},
+            stage::{UpdateStage, UpdateSystemContext, UpdateSystemId, UpdateSystems},
+        },
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            surface::SurfaceData,
+            Mesh,
         },
-        mesh::Mesh,
         node::{container::NodeContainer, Node, NodeTrait, SyncContext, UpdateContext},
         pivot::Pivot,
-        sound::context::SoundContext,
+        sound::{context::SoundContext, Sound},
+        terrain::Terrain,
         transform::TransformBuilder,
+        Scene,
     },
     script::ScriptTrait,
 };
-use fxhash::FxHashSet;
+use fxhash::{FxHashMap, FxHashSet};
 use fyrox_core::math::aabb::AxisAlignedBoundingBox;
 use rapier3d::geometry::ColliderHandle;
 use std::{
     any::Any,
-    fmt::Debug,
+    fmt::{Debug, Display, Formatter},
     ops::{Index, IndexMut},
     sync::mpsc::{channel, Receiver, Sender},
     time::Duration,
 };
 
 pub mod event;
+pub mod extract;
+pub mod group;
 pub mod map;
+pub mod migration;
 pub mod physics;
+pub mod snapshot;
+pub mod stage;
+pub mod sweep;
 
 /// Graph performance statistics. Allows you to find out "hot" parts of the scene graph, which
 /// parts takes the most time to update.
@@ -89,6 +112,19 @@ pub struct GraphPerformanceStatistics {
 
     /// A time which was required to render sounds.
     pub sound_update_time: Duration,
+
+    /// Amount of time that was needed to update every node (including their scripts, animations,
+    /// particle systems, cameras, etc.).
+    pub nodes_update_time: Duration,
+
+    /// Amount of time that was needed to run every custom update system. See
+    /// [`Graph::add_update_system`] for more info.
+    pub update_systems_time: Duration,
+
+    /// Update statistics of each node type, the key is a type name of a node. It is collected only if
+    /// [`GraphUpdateSwitches::node_type_statistics`] is set, because measuring time of each node update
+    /// has noticeable overhead.
+    pub node_types: FxHashMap<&'static str, NodeTypeStatistics>,
 }
 
 impl GraphPerformanceStatistics {
@@ -99,6 +135,91 @@ impl GraphPerformanceStatistics {
             + self.physics.total()
             + self.physics2d.total()
             + self.sound_update_time
+            + self.nodes_update_time
+            + self.update_systems_time
+    }
+}
+
+/// Update statistics of a single node type. See [`GraphPerformanceStatistics::node_types`].
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct NodeTypeStatistics {
+    /// Amount of updated nodes of the type.
+    pub count: usize,
+    /// Total amount of time that was needed to update the nodes of the type.
+    pub update_time: Duration,
+}
+
+/// Approximate amount of memory held by a graph. See [`Graph::memory_usage`] for more info.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct GraphMemoryUsage {
+    /// Amount of records in the node pool (including free ones).
+    pub node_pool_capacity: usize,
+    /// Approximate amount of memory (in bytes) occupied by the node pool and the nodes.
+    pub node_bytes: usize,
+    /// Amount of unique surface data instances used by meshes.
+    pub mesh_buffer_count: usize,
+    /// Amount of memory (in bytes) occupied by vertex and index buffers of unique surfaces of meshes.
+    pub mesh_buffer_bytes: usize,
+    /// Amount of unique sound buffers used by sound sources.
+    pub sound_buffer_count: usize,
+    /// Amount of memory (in bytes) occupied by samples of unique sound buffers.
+    pub sound_buffer_bytes: usize,
+    /// Approximate amount of memory (in bytes) occupied by 3D and 2D physics entities.
+    pub physics_bytes: usize,
+}
+
+impl GraphMemoryUsage {
+    /// Returns total amount of memory (in bytes).
+    pub fn total_bytes(&self) -> usize {
+        self.node_bytes + self.mesh_buffer_bytes + self.sound_buffer_bytes + self.physics_bytes
+    }
+}
+
+impl Display for GraphMemoryUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mb = |bytes: usize| bytes as f32 / (1024.0 * 1024.0);
+        write!(
+            f,
+            "Nodes: {} records ({:.2} Mb)\n\
+            Mesh Buffers: {} ({:.2} Mb)\n\
+            Sound Buffers: {} ({:.2} Mb)\n\
+            Physics: {:.2} Mb",
+            self.node_pool_capacity,
+            mb(self.node_bytes),
+            self.mesh_buffer_count,
+            mb(self.mesh_buffer_bytes),
+            self.sound_buffer_count,
+            mb(self.sound_buffer_bytes),
+            mb(self.physics_bytes)
+        )
+    }
+}
+
+/// Occupancy statistics of the node pool of a graph. See [`Graph::pool_occupancy`] for more info.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct PoolOccupancy {
+    /// Total amount of records in the pool (alive, free and reserved).
+    pub records: u32,
+    /// Amount of records occupied by alive nodes.
+    pub alive: u32,
+    /// Amount of free records, that will be re-used by new nodes.
+    pub free: u32,
+    /// Amount of records reserved by sub-graphs that were taken out of the graph.
+    pub reserved: u32,
+    /// The highest generation among all records. Generation is incremented every time when a record
+    /// is re-used, so it shows how intensively the records are recycled.
+    pub max_generation: u32,
+}
+
+impl PoolOccupancy {
+    /// Returns a ratio of free records to the total amount of records, in `[0; 1]` range. Zero means
+    /// that the pool is dense.
+    pub fn fragmentation(&self) -> f32 {
+        if self.records == 0 {
+            0.0
+        } else {
+            self.free as f32 / self.records as f32
+        }
     }
 }
 
@@ -138,8 +259,60 @@ pub struct Graph {
     pub(crate) script_message_sender: Sender<NodeScriptMessage>,
     #[reflect(hidden)]
     pub(crate) script_message_receiver: Receiver<NodeScriptMessage>,
+
+    #[reflect(hidden)]
+    instance_patch_mode: bool,
+
+    #[reflect(hidden)]
+    locked: FxHashSet<Handle<Node>>,
+
+    #[reflect(hidden)]
+    groups: NodeGroups,
+
+    #[reflect(hidden)]
+    update_systems: UpdateSystems,
+
+    #[reflect(hidden)]
+    orphan_sweep_cursor: u32,
+
+    // Sensor colliders, which sets of entered/exited colliders must be cleared on the next physics update.
+    #[reflect(hidden)]
+    active_triggers: Vec<Handle<Node>>,
+}
+
+/// An error that may occur during modification of a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphError {
+    /// An attempt to modify a locked node. See [`Graph::set_subtree_locked`] for more info.
+    NodeLocked(Handle<Node>),
+    /// An attempt to access a node using an invalid handle.
+    InvalidHandle(Handle<Node>),
+    /// An attempt to compact a graph, that has reserved nodes. See [`Graph::compact`] for more info.
+    HasReservedNodes(u32),
+}
+
+impl Display for GraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::NodeLocked(handle) => {
+                write!(f, "Node {} is locked and cannot be modified!", handle)
+            }
+            GraphError::InvalidHandle(handle) => {
+                write!(f, "Node handle {} is invalid!", handle)
+            }
+            GraphError::HasReservedNodes(count) => {
+                write!(
+                    f,
+                    "Graph has {} reserved nodes and cannot be compacted!",
+                    count
+                )
+            }
+        }
+    }
 }
 
+impl std::error::Error for GraphError {}
+
 impl Default for Graph {
     fn default() -> Self {
         let (tx, rx) = channel();
@@ -155,6 +328,12 @@ impl Default for Graph {
             event_broadcaster: Default::default(),
             script_message_receiver: rx,
             script_message_sender: tx,
+            instance_patch_mode: false,
+            locked: Default::default(),
+            groups: Default::default(),
+            update_systems: Default::default(),
+            orphan_sweep_cursor: 0,
+            active_triggers: Default::default(),
         }
     }
 }
@@ -176,6 +355,79 @@ pub struct SubGraph {
     /// A handle to the parent node from which the sub-graph was extracted (it it parent node of
     /// the root of this sub-graph).
     pub parent: Handle<Node>,
+
+    // Runtime state of native physics entities of the removed nodes. It is filled only by
+    // Graph::remove_sub_graph and used to restore the state when the sub-graph is put back.
+    physics_state: Vec<(Handle<Node>, NativePhysicsState)>,
+
+    // Names of the groups the extracted nodes were in. Nodes are excluded from their groups while
+    // they're extracted and will be added back when the sub-graph is put back.
+    groups: Vec<(Handle<Node>, Vec<String>)>,
+
+    // Locked nodes of the sub-graph. The nodes are unlocked while they're extracted and will be
+    // locked again when the sub-graph is put back.
+    locked: Vec<Handle<Node>>,
+}
+
+// A set of nodes that were temporarily removed from a graph, because they can be fully restored from
+// their resources.
+#[derive(Default)]
+struct RestorableInstanceNodes {
+    nodes: Vec<(Handle<Node>, Node)>,
+    // Parent, position in the list of children of the parent, child.
+    links: Vec<(Handle<Node>, usize, Handle<Node>)>,
+}
+
+// Checks whether the given instance node can be fully restored from its resource - it must have no
+// modified properties, no script and the same name as its original node in the resource.
+fn is_restorable_from_resource(node: &Node) -> bool {
+    if node.script.is_some() {
+        return false;
+    }
+
+    let resource = match node.resource.as_ref() {
+        Some(resource) => resource,
+        None => return false,
+    };
+
+    let state = resource.state();
+    let name_matches = match state.get() {
+        ResourceStateRef::Ok(data) => data
+            .get_scene()
+            .graph
+            .pool
+            .try_borrow(node.original_handle_in_resource)
+            .map_or(false, |original| original.name() == node.name()),
+        _ => false,
+    };
+
+    if !name_matches {
+        return false;
+    }
+
+    let mut modified = false;
+    node.as_reflect(&mut |node_reflect| {
+        node_reflect.apply_recursively(&mut |object| {
+            object.as_inheritable_variable(&mut |variable| {
+                if let Some(variable) = variable {
+                    if variable.is_modified() {
+                        modified = true;
+                    }
+                }
+            })
+        })
+    });
+
+    !modified
+}
+
+// Runtime state of native physics entities of a node. It is used to transfer the state between graphs.
+#[derive(Debug)]
+enum NativePhysicsState {
+    RigidBody(rapier3d::dynamics::RigidBody),
+    Joint(rapier3d::dynamics::ImpulseJoint),
+    RigidBody2D(rapier2d::dynamics::RigidBody),
+    Joint2D(rapier2d::dynamics::ImpulseJoint),
 }
 
 fn remap_handles(old_new_mapping: &NodeHandleMap, dest_graph: &mut Graph) {
@@ -205,6 +457,62 @@ fn isometric_global_transform(nodes: &NodePool, node: Handle<Node>) -> Matrix4<f
     }
 }
 
+// Calculates global scale of a node by combining local scales of every node up to the root, rotations
+// of the nodes are ignored.
+fn global_scale(nodes: &NodePool, node: Handle<Node>) -> Vector3<f32> {
+    let mut scale = Vector3::repeat(1.0);
+    let mut handle = node;
+    while let Some(node) = nodes.try_borrow(handle) {
+        scale.component_mul_assign(node.local_transform().scale());
+        handle = node.parent();
+    }
+    scale
+}
+
+// Interpolates texture coordinates (first channel) of the vertices of the given triangle of a surface.
+fn interpolate_uv(
+    data: &SurfaceData,
+    triangle: usize,
+    barycentric: Vector3<f32>,
+) -> Option<Vector2<f32>> {
+    let triangle = data.geometry_buffer.triangles_ref().get(triangle)?;
+    let mut uv = Vector2::default();
+    for (&vertex, weight) in triangle.0.iter().zip(barycentric.iter()) {
+        let vertex_uv = data
+            .vertex_buffer
+            .get(vertex as usize)?
+            .read_2_f32(VertexAttributeUsage::TexCoord0)
+            .ok()?;
+        uv += vertex_uv.scale(*weight);
+    }
+    Some(uv)
+}
+
+// Checks whether the given string matches the pattern with `*` (any sequence of characters) and `?` (any
+// single character) wildcards.
+fn wildcard_match(pattern: &[char], string: &[char]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    // Position of the last `*` in the pattern and position in the string where it started to match.
+    let mut backtrack = None;
+    while s < string.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == string[s]) {
+            p += 1;
+            s += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, s));
+            p += 1;
+        } else if let Some((star_p, star_s)) = backtrack {
+            // Let the last `*` consume one more character.
+            p = star_p + 1;
+            s = star_s + 1;
+            backtrack = Some((star_p, star_s + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
 // Clears all information about parent-child relations of a given node. This is needed in some
 // cases (mostly when copying a node), because `Graph::add_node` uses children list to attach
 // children to the given node, and when copying a node it is important that this step is skipped.
@@ -229,6 +537,9 @@ pub struct GraphUpdateSwitches {
     /// Whether the graph update is paused or not. Paused graphs won't be updated and their sound content will be also paused
     /// so it won't emit any sounds.
     pub paused: bool,
+    /// Enables or disables collection of update statistics of each node type (see
+    /// [`GraphPerformanceStatistics::node_types`]). Disabled by default.
+    pub node_type_statistics: bool,
 }
 
 impl Default for GraphUpdateSwitches {
@@ -239,14 +550,28 @@ impl Default for GraphUpdateSwitches {
             node_overrides: Default::default(),
             delete_dead_nodes: true,
             paused: false,
+            node_type_statistics: false,
         }
     }
 }
 
 impl Graph {
+    /// Current version of the serialization format of the graph. Graphs stored in older formats
+    /// will be upgraded on load using migrations from
+    /// [`crate::engine::SerializationContext::graph_migrations`].
+    pub const VERSION: u32 = 1;
+
     /// Creates new graph instance with single root node.
     #[inline]
     pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates new graph instance with a single root node and pre-allocated storage for at least
+    /// `capacity` nodes (including the root). It allows you to avoid repeated re-allocations of the
+    /// node pool when you know the amount of nodes in advance (for example when loading large scenes),
+    /// and keeps handle indices of the nodes contiguous.
+    pub fn with_capacity(capacity: u32) -> Self {
         let (tx, rx) = channel();
 
         // Create root node.
@@ -255,7 +580,7 @@ impl Graph {
         root_node.set_name("__ROOT__");
 
         // Add it to the pool.
-        let mut pool = Pool::new();
+        let mut pool = Pool::with_capacity(capacity);
         let root = pool.spawn(Node::new(root_node));
         pool[root].self_handle = root;
 
@@ -270,7 +595,215 @@ impl Graph {
             event_broadcaster: Default::default(),
             script_message_receiver: rx,
             script_message_sender: tx,
+            instance_patch_mode: false,
+            locked: Default::default(),
+            groups: Default::default(),
+            update_systems: Default::default(),
+            orphan_sweep_cursor: 0,
+            active_triggers: Default::default(),
+        }
+    }
+
+    /// Returns approximate amount of memory held by the graph - its nodes, vertex and index buffers of
+    /// meshes, sound buffers and native physics entities. Shared resources (surfaces and sound buffers)
+    /// are counted once, even if they're used by multiple nodes. It could be used to track leaks and
+    /// to budget content per level. Keep in mind that it is not cheap, and should not be called every
+    /// frame.
+    pub fn memory_usage(&self) -> GraphMemoryUsage {
+        let mut usage = GraphMemoryUsage {
+            node_pool_capacity: self.pool.allocated_capacity() as usize,
+            node_bytes: self.pool.allocated_capacity() as usize * std::mem::size_of::<Node>(),
+            physics_bytes: self.physics.memory_usage() + self.physics2d.memory_usage(),
+            ..Default::default()
+        };
+
+        let mut surfaces = FxHashSet::default();
+        let mut sound_buffers = FxHashSet::default();
+        for node in self.pool.iter() {
+            usage.node_bytes += std::mem::size_of_val(&**node);
+
+            if let Some(mesh) = node.cast::<Mesh>() {
+                for surface in mesh.surfaces() {
+                    if surfaces.insert(surface.data_ref().key()) {
+                        let data = surface.data_ref().lock();
+                        usage.mesh_buffer_count += 1;
+                        usage.mesh_buffer_bytes += data.vertex_buffer.raw_data().len()
+                            + std::mem::size_of_val(data.geometry_buffer.triangles_ref());
+                    }
+                }
+            } else if let Some(buffer) = node.cast::<Sound>().and_then(|s| s.buffer()) {
+                if sound_buffers.insert(buffer.key()) {
+                    if let ResourceStateRef::Ok(buffer) = buffer.state().get() {
+                        usage.sound_buffer_count += 1;
+                        usage.sound_buffer_bytes += std::mem::size_of_val(buffer.samples());
+                    }
+                }
+            }
+        }
+
+        usage
+    }
+
+    /// Reserves storage for at least `additional` more nodes, so they could be added without
+    /// re-allocation of the node pool. See [`Self::with_capacity`] for more info.
+    #[inline]
+    pub fn reserve(&mut self, additional: u32) {
+        self.pool.reserve(additional);
+    }
+
+    /// Returns the amount of nodes the graph can hold without re-allocation of the node pool. Unlike
+    /// [`Self::capacity`], it includes records that are not used yet.
+    #[inline]
+    pub fn allocated_capacity(&self) -> u32 {
+        self.pool.allocated_capacity()
+    }
+
+    /// Releases unused records at the end of the node pool and returns memory of its unused pages
+    /// back to the allocator. Unlike [`Self::compact`], it does not move any nodes, so every handle
+    /// stays the same. It could be useful after removing a large part of the graph.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        self.pool.shrink_to_fit();
+    }
+
+    /// Returns occupancy statistics of the node pool. It could be used to decide whether the graph
+    /// should be compacted or not, see [`Self::compact`]. The method iterates over the entire pool,
+    /// so it should not be called every frame.
+    pub fn pool_occupancy(&self) -> PoolOccupancy {
+        let records = self.pool.get_capacity();
+        PoolOccupancy {
+            records,
+            alive: self.pool.alive_count(),
+            free: self.pool.free_count(),
+            reserved: self.pool.reserved_count(),
+            max_generation: (0..records)
+                .filter_map(|i| self.pool.generation_at(i))
+                .max()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns current generation of a record of the node pool at the given index, or [`None`] if
+    /// the index is out of bounds.
+    pub fn generation_at(&self, index: u32) -> Option<u32> {
+        self.pool.generation_at(index)
+    }
+
+    /// Moves every node into a new, dense node pool without free records, nodes are placed in
+    /// hierarchical order (parents go before their children). It is useful for long-lived procedural
+    /// worlds, where lots of nodes are created and destroyed over time, so the pool becomes sparse.
+    ///
+    /// Returns old-to-new node mapping. Cross-references between nodes (parent-child links, bodies
+    /// of joints, bones of skinned meshes, handles in scripts, etc.), group membership and locked
+    /// nodes are remapped automatically. Native physics and sound entities are re-created, but the
+    /// runtime state of rigid bodies and joints is preserved.
+    ///
+    /// # Important notes
+    ///
+    /// This is an "offline" operation - every handle to the nodes of the graph stored outside of it
+    /// becomes invalid and must be remapped using the returned map. It is also quite slow, so it
+    /// should be done at some appropriate moment, for example during a loading screen.
+    ///
+    /// Returns [`GraphError::HasReservedNodes`] if the graph has reserved nodes (see
+    /// [`Self::take_reserve`]), because their handles cannot be remapped.
+    pub fn compact(&mut self) -> Result<NodeHandleMap, GraphError> {
+        let reserved = self.pool.reserved_count();
+        if reserved > 0 {
+            return Err(GraphError::HasReservedNodes(reserved));
+        }
+
+        let mut order = self.topological_sort();
+        if order.len() != self.pool.alive_count() as usize {
+            // Nodes that aren't reachable from the root must be kept as well.
+            let reachable = order.iter().cloned().collect::<FxHashSet<_>>();
+            let unreachable = self
+                .pool
+                .pair_iter()
+                .map(|(handle, _)| handle)
+                .filter(|handle| !reachable.contains(handle))
+                .collect::<Vec<_>>();
+            order.extend(unreachable);
+        }
+
+        let mut old_new_map = NodeHandleMap::default();
+        let mut physics_states = Vec::new();
+        let mut nodes = Vec::with_capacity(order.len());
+        for handle in order {
+            let mut node = self.pool.free(handle);
+
+            // Native entities will be re-created, remember state of physics entities first.
+            if let Some(state) = self.native_physics_state(&node) {
+                physics_states.push((handle, state));
+            }
+            node.on_removed_from_graph(self);
+
+            nodes.push((handle, node));
         }
+
+        self.pool.clear();
+        self.pool.reserve(nodes.len() as u32);
+        for (old_handle, node) in nodes {
+            let new_handle = self.pool.spawn(node);
+            old_new_map.map.insert(old_handle, new_handle);
+        }
+
+        for (&old_handle, &new_handle) in old_new_map.inner().iter() {
+            let node = &mut self.pool[new_handle];
+            node.self_handle = new_handle;
+            node.parent = old_new_map
+                .map
+                .get(&node.parent)
+                .cloned()
+                .unwrap_or_default();
+            for child in node.children.iter_mut() {
+                *child = old_new_map.map.get(child).cloned().unwrap_or_default();
+            }
+            if self.root == old_handle {
+                self.root = new_handle;
+            }
+        }
+
+        remap_handles(&old_new_map, self);
+
+        self.locked = self
+            .locked
+            .iter()
+            .filter_map(|handle| old_new_map.map.get(handle).cloned())
+            .collect();
+        self.groups = self.groups.remap(&old_new_map);
+
+        self.update_hierarchical_data();
+        // Sync twice, because native joints can be created only when native rigid bodies of both
+        // ends of the joint are exist.
+        for _ in 0..2 {
+            self.sync_native(&Default::default());
+        }
+
+        for (handle, state) in physics_states {
+            if let Some(&new_handle) = old_new_map.map.get(&handle) {
+                self.apply_native_physics_state(new_handle, &state);
+            }
+        }
+
+        Ok(old_new_map)
+    }
+
+    /// Checks up to `budget` records of the node pool for nodes with broken hierarchy links and
+    /// handles them using the given action. A node is considered orphaned, if its parent handle is
+    /// invalid or its parent does not list it as a child. Such nodes could appear after failed
+    /// operations or bad merges, they're not reachable from the root and thus won't be updated nor
+    /// rendered. Invalid handles in children lists are detected (and removed) as well.
+    ///
+    /// The sweep is incremental - every call continues from the record where the previous call
+    /// stopped, so it could be spread across multiple frames with a small budget to avoid frame
+    /// spikes. Use [`sweep::OrphanSweepReport::pass_finished`] to find out when the entire pool was
+    /// checked. Locked nodes are handled as well, since this is a repair operation.
+    pub fn sweep_orphans(
+        &mut self,
+        action: sweep::OrphanAction,
+        budget: u32,
+    ) -> sweep::OrphanSweepReport {
+        sweep::sweep(self, action, budget)
     }
 
     /// Creates a new graph using a hierarchy of nodes specified by the `root`.
@@ -321,7 +854,8 @@ impl Graph {
         if self.root.is_none() {
             self.root = handle;
         } else {
-            self.link_nodes(handle, self.root);
+            // New nodes must be attached to the root even if it is locked.
+            self.link_nodes_internal(handle, self.root);
         }
 
         for child in children {
@@ -336,13 +870,488 @@ impl Graph {
         }
 
         let sender = self.script_message_sender.clone();
-        let node = &mut self[handle];
+        let node = &mut self.pool[handle];
         node.self_handle = handle;
         node.script_message_sender = Some(sender);
 
         handle
     }
 
+    /// Casts (sweeps) the given shape from the given initial position along the given velocity and collects
+    /// every collider it hits into the storage, sorted from closest to farthest. It could be used for character
+    /// movement and for fast projectiles, where a ray is too thin to detect all the hits.
+    ///
+    /// Trimesh and heightfield shapes are built using world-space geometry of their sources, `shape_position`
+    /// is applied on top of that. Returns `false` if the shape cannot be converted to a physical shape (for
+    /// example - a trimesh without sources), `true` - otherwise.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::{
+    ///     core::algebra::{Isometry3, Vector3},
+    ///     scene::{
+    ///         collider::{BallShape, ColliderShape},
+    ///         graph::{physics::ShapeCastOptions, Graph},
+    ///     },
+    /// };
+    ///
+    /// fn sweep_ball(graph: &Graph, position: Vector3<f32>, direction: Vector3<f32>) -> bool {
+    ///     let mut hits = Vec::new();
+    ///     graph.cast_shape(
+    ///         &ColliderShape::Ball(BallShape { radius: 0.5 }),
+    ///         &Isometry3::translation(position.x, position.y, position.z),
+    ///         &direction,
+    ///         ShapeCastOptions {
+    ///             max_toi: 10.0,
+    ///             ..Default::default()
+    ///         },
+    ///         &mut hits,
+    ///     );
+    ///     !hits.is_empty()
+    /// }
+    /// ```
+    pub fn cast_shape<S: QueryResultsStorage>(
+        &self,
+        shape: &ColliderShape,
+        shape_position: &Isometry3<f32>,
+        velocity: &Vector3<f32>,
+        opts: ShapeCastOptions,
+        query_buffer: &mut S,
+    ) -> bool {
+        match physics::collider_shape_into_native_shape(
+            shape,
+            Vector3::repeat(1.0),
+            Matrix4::identity(),
+            Handle::NONE,
+            &self.pool,
+            &self.physics.trimesh_cache,
+        ) {
+            Some(native_shape) => {
+                self.physics.cast_shape(
+                    &native_shape,
+                    shape_position,
+                    velocity,
+                    opts,
+                    query_buffer,
+                );
+                true
+            }
+            None => {
+                query_buffer.clear();
+                false
+            }
+        }
+    }
+
+    /// Checks whether the given shape at the given position does not overlap any collider. It could be
+    /// used by spawn systems to verify that a location is unobstructed before creating a character or a
+    /// prop there. Colliders are filtered using the given interaction groups and the optional filter (if
+    /// it returns `false` for a collider node, the collider is ignored). Sensor colliders are ignored,
+    /// because they do not obstruct anything. See [`Self::overlapping_colliders`] if you need to know
+    /// which colliders overlap the shape.
+    ///
+    /// Returns `true` if the shape cannot be converted to a physical shape (for example, a trimesh without
+    /// geometry sources).
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::{
+    ///     core::algebra::{Isometry3, Vector3},
+    ///     scene::{
+    ///         collider::{CapsuleShape, ColliderShape},
+    ///         graph::Graph,
+    ///     },
+    /// };
+    ///
+    /// fn can_spawn_character(graph: &Graph, position: Vector3<f32>) -> bool {
+    ///     graph.is_shape_free(
+    ///         &ColliderShape::Capsule(CapsuleShape {
+    ///             begin: Vector3::new(0.0, 0.5, 0.0),
+    ///             end: Vector3::new(0.0, 1.5, 0.0),
+    ///             radius: 0.5,
+    ///         }),
+    ///         &Isometry3::translation(position.x, position.y, position.z),
+    ///         Default::default(),
+    ///         None,
+    ///     )
+    /// }
+    /// ```
+    pub fn is_shape_free(
+        &self,
+        shape: &ColliderShape,
+        isometry: &Isometry3<f32>,
+        groups: InteractionGroups,
+        filter: Option<&dyn Fn(Handle<Node>) -> bool>,
+    ) -> bool {
+        let mut colliders = Vec::new();
+        self.collect_overlapping_colliders(shape, isometry, groups, filter, true, &mut colliders);
+        colliders.is_empty()
+    }
+
+    /// Returns handles of every collider node that overlaps the given shape at the given position. It
+    /// has the same rules as [`Self::is_shape_free`], but does not stop at the first overlap.
+    pub fn overlapping_colliders(
+        &self,
+        shape: &ColliderShape,
+        isometry: &Isometry3<f32>,
+        groups: InteractionGroups,
+        filter: Option<&dyn Fn(Handle<Node>) -> bool>,
+    ) -> Vec<Handle<Node>> {
+        let mut colliders = Vec::new();
+        self.collect_overlapping_colliders(shape, isometry, groups, filter, false, &mut colliders);
+        colliders
+    }
+
+    /// Returns handles of every collider node, which bounding box intersects the given region (in world
+    /// coordinates). Unlike [`Self::overlapping_colliders`], only bounding boxes of the colliders are
+    /// checked, so the method is very cheap, but the result could contain colliders that do not actually
+    /// touch the region. It could be used for "what is roughly nearby" queries - for example, to gather
+    /// potential targets for AI or occluders for sound. Sensors are included in the result.
+    ///
+    /// The method uses the same acceleration structure as other scene queries, so colliders that were
+    /// created or moved after the last physics update are taken into account only after
+    /// [`PhysicsWorld::refresh_queries`] call.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::{
+    ///     core::{algebra::Vector3, math::aabb::AxisAlignedBoundingBox, pool::Handle},
+    ///     scene::{graph::Graph, node::Node},
+    /// };
+    ///
+    /// fn nearby_colliders(graph: &Graph, position: Vector3<f32>, radius: f32) -> Vec<Handle<Node>> {
+    ///     graph.colliders_in_region(&AxisAlignedBoundingBox::from_min_max(
+    ///         position - Vector3::repeat(radius),
+    ///         position + Vector3::repeat(radius),
+    ///     ))
+    /// }
+    /// ```
+    pub fn colliders_in_region(&self, region: &AxisAlignedBoundingBox) -> Vec<Handle<Node>> {
+        let mut colliders = Vec::new();
+        self.physics.colliders_in_region(region, &mut colliders);
+        colliders
+    }
+
+    fn collect_overlapping_colliders(
+        &self,
+        shape: &ColliderShape,
+        isometry: &Isometry3<f32>,
+        groups: InteractionGroups,
+        filter: Option<&dyn Fn(Handle<Node>) -> bool>,
+        first_only: bool,
+        colliders: &mut Vec<Handle<Node>>,
+    ) {
+        if let Some(native_shape) = physics::collider_shape_into_native_shape(
+            shape,
+            Vector3::repeat(1.0),
+            Matrix4::identity(),
+            Handle::NONE,
+            &self.pool,
+            &self.physics.trimesh_cache,
+        ) {
+            self.physics.overlapping_colliders(
+                &native_shape,
+                isometry,
+                groups,
+                filter,
+                first_only,
+                colliders,
+            );
+        }
+    }
+
+    /// Returns surface type at the given ray or shape cast intersection. If the intersected collider
+    /// is a heightfield of a terrain, the surface type of the most visible terrain layer at the
+    /// intersection point is used (if it is set). Otherwise, the surface type of the collider itself
+    /// is used. Returns `None` if the intersected collider does not exist or there is no surface type
+    /// set.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::scene::graph::{physics::Intersection, Graph};
+    ///
+    /// fn footstep_sound(graph: &Graph, ground: &Intersection) -> &'static str {
+    ///     match graph.surface_type_at(ground) {
+    ///         Some("Grass") => "data/sounds/footstep_grass.ogg",
+    ///         Some("Metal") => "data/sounds/footstep_metal.ogg",
+    ///         _ => "data/sounds/footstep_default.ogg",
+    ///     }
+    /// }
+    /// ```
+    pub fn surface_type_at(&self, intersection: &Intersection) -> Option<&str> {
+        let collider = self
+            .try_get(intersection.collider)
+            .and_then(|node| node.cast::<Collider>())?;
+
+        if let ColliderShape::Heightfield(heightfield) = collider.shape() {
+            let terrain_surface_type = self
+                .try_get(heightfield.geometry_source.0)
+                .and_then(|node| node.cast::<Terrain>())
+                .and_then(|terrain| terrain.surface_type_at(intersection.position.coords))
+                .filter(|surface_type| !surface_type.is_empty());
+            if terrain_surface_type.is_some() {
+                return terrain_surface_type;
+            }
+        }
+
+        Some(collider.surface_type()).filter(|surface_type| !surface_type.is_empty())
+    }
+
+    /// Finds a triangle on a surface of a source mesh of a triangle mesh collider at the given ray cast
+    /// intersection, together with barycentric and texture coordinates of the intersection position in
+    /// the triangle. It could be used to place decals or to fetch a texel of the hit surface. Returns
+    /// `None` if the intersected collider is not a triangle mesh or the source mesh does not exist.
+    ///
+    /// ## Important notes
+    ///
+    /// If the triangle mesh was simplified (see [`crate::scene::collider::TrimeshShape::weld_distance`]
+    /// and [`crate::scene::collider::TrimeshShape::triangle_budget`]), its triangles do not match the
+    /// triangles of the source meshes. In this case the source triangle closest to the intersection
+    /// position is used, which is slower and could be imprecise.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::{
+    ///     core::algebra::Vector2,
+    ///     scene::graph::{physics::Intersection, Graph},
+    /// };
+    ///
+    /// fn hit_uv(graph: &Graph, intersection: &Intersection) -> Option<Vector2<f32>> {
+    ///     graph.mesh_hit(intersection).and_then(|hit| hit.uv)
+    /// }
+    /// ```
+    pub fn mesh_hit(&self, intersection: &Intersection) -> Option<MeshHit> {
+        let triangle_hit = intersection.triangle?;
+        let collider = self
+            .try_get(intersection.collider)
+            .and_then(|node| node.cast::<Collider>())?;
+        let trimesh = match collider.shape() {
+            ColliderShape::Trimesh(trimesh) => trimesh,
+            _ => return None,
+        };
+
+        let meshes = trimesh
+            .sources
+            .iter()
+            .filter_map(|source| {
+                self.try_get(source.0)
+                    .and_then(|node| node.cast::<Mesh>())
+                    .map(|mesh| (source.0, mesh))
+            })
+            .collect::<Vec<_>>();
+
+        let triangle_count = meshes
+            .iter()
+            .flat_map(|(_, mesh)| mesh.surfaces())
+            .map(|surface| surface.data().lock().geometry_buffer.len())
+            .sum::<usize>();
+        let simplified = trimesh.weld_distance > 0.0
+            || trimesh
+                .triangle_budget
+                .map_or(false, |budget| triangle_count > budget as usize);
+
+        if !simplified {
+            // Triangles of the native mesh go in the same order as the triangles of the sources.
+            let mut index = triangle_hit.index as usize;
+            for (handle, mesh) in meshes {
+                for (surface_index, surface) in mesh.surfaces().iter().enumerate() {
+                    let data = surface.data();
+                    let data = data.lock();
+                    let count = data.geometry_buffer.len();
+                    if index < count {
+                        return Some(MeshHit {
+                            mesh: handle,
+                            surface: surface_index,
+                            triangle: index,
+                            barycentric: triangle_hit.barycentric,
+                            uv: interpolate_uv(&data, index, triangle_hit.barycentric),
+                        });
+                    }
+                    index -= count;
+                }
+            }
+            None
+        } else {
+            let position = intersection.position.coords;
+            let mut closest: Option<(f32, MeshHit)> = None;
+            for (handle, mesh) in meshes {
+                let transform = mesh.global_transform();
+                for (surface_index, surface) in mesh.surfaces().iter().enumerate() {
+                    let data = surface.data();
+                    let data = data.lock();
+                    for (index, triangle) in data.geometry_buffer.iter().enumerate() {
+                        let mut points = [Vector3::default(); 3];
+                        for (point, &vertex) in points.iter_mut().zip(triangle.0.iter()) {
+                            if let Some(local) = data
+                                .vertex_buffer
+                                .get(vertex as usize)
+                                .and_then(|v| v.read_3_f32(VertexAttributeUsage::Position).ok())
+                            {
+                                *point = transform.transform_point(&Point3::from(local)).coords;
+                            }
+                        }
+                        let (u, v, w) =
+                            get_barycentric_coords(&position, &points[0], &points[1], &points[2]);
+                        // Clamp the projection to the triangle.
+                        let barycentric = Vector3::new(u, v, w).sup(&Vector3::default());
+                        let sum = barycentric.sum();
+                        if !sum.is_finite() || sum <= 0.0 {
+                            // Degenerate triangle.
+                            continue;
+                        }
+                        let barycentric = barycentric.scale(1.0 / sum);
+                        let distance = (points[0].scale(barycentric.x)
+                            + points[1].scale(barycentric.y)
+                            + points[2].scale(barycentric.z)
+                            - position)
+                            .norm();
+                        if closest.as_ref().map_or(true, |(d, _)| distance < *d) {
+                            closest = Some((
+                                distance,
+                                MeshHit {
+                                    mesh: handle,
+                                    surface: surface_index,
+                                    triangle: index,
+                                    barycentric,
+                                    uv: interpolate_uv(&data, index, barycentric),
+                                },
+                            ));
+                        }
+                    }
+                }
+            }
+            closest.map(|(_, hit)| hit)
+        }
+    }
+
+    /// Applies an impulse to every dynamic rigid body, that has at least one collider within the given
+    /// radius from the center. The impulse pushes the bodies away from the center, its strength
+    /// decreases with the distance from the center to the closest collider of a body according to
+    /// the given falloff. Colliders are filtered using the given interaction groups. The method uses
+    /// the acceleration structure of the physics world, so it is cheap even for large scenes. The
+    /// impulses are applied at the next physics update.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::{
+    ///     core::algebra::Vector3,
+    ///     scene::graph::{physics::ImpulseFalloff, Graph},
+    /// };
+    ///
+    /// fn explode(graph: &mut Graph, position: Vector3<f32>) {
+    ///     graph.apply_radial_impulse(
+    ///         position,
+    ///         5.0,
+    ///         100.0,
+    ///         ImpulseFalloff::Quadratic,
+    ///         Default::default(),
+    ///     );
+    /// }
+    /// ```
+    pub fn apply_radial_impulse(
+        &mut self,
+        center: Vector3<f32>,
+        radius: f32,
+        strength: f32,
+        falloff: ImpulseFalloff,
+        groups: InteractionGroups,
+    ) {
+        if radius <= 0.0 {
+            return;
+        }
+
+        for (body, distance, center_of_mass) in
+            self.physics
+                .dynamic_bodies_in_sphere(Point3::from(center), radius, groups)
+        {
+            if let Some(rigid_body) = self.try_get_mut_of_type::<scene::rigidbody::RigidBody>(body)
+            {
+                // Bodies at the very center are pushed up.
+                let direction = (center_of_mass - center)
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(Vector3::y);
+                let factor = falloff.factor(distance / radius);
+                rigid_body.apply_impulse(direction.scale(strength * factor));
+            }
+        }
+    }
+
+    /// Returns current contacts of the given collider node with other colliders. Every contact
+    /// contains a handle of the other collider and a set of contact points with world-space
+    /// positions, normals (pointing from the given collider towards the other one) and impulses
+    /// that were applied at the last physics step. Sensor colliders do not produce contacts, use
+    /// intersections for them. The data is taken directly from the narrow phase of the physics
+    /// world, so it reflects the state after the last physics update.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::{
+    ///     core::pool::Handle,
+    ///     scene::{graph::Graph, node::Node},
+    /// };
+    ///
+    /// fn is_grounded(graph: &Graph, collider: Handle<Node>) -> bool {
+    ///     graph
+    ///         .contacts_of(collider)
+    ///         .iter()
+    ///         .flat_map(|contact| contact.points.iter())
+    ///         .any(|point| point.normal.y < -0.7)
+    /// }
+    /// ```
+    pub fn contacts_of(&self, collider: Handle<Node>) -> Vec<Contact> {
+        self.physics.contacts_of(collider)
+    }
+
+    /// Calculates the time after which two collider nodes, moving with the given constant linear
+    /// velocities (in world coordinates), will touch each other. Rotation of the colliders is not taken
+    /// into account. Returns [`None`] if the colliders won't touch within `max_toi`, if any of the handles
+    /// does not have a backing native collider, or if the pair of shapes is not supported (for example -
+    /// two triangle meshes). If the colliders are already intersecting, the result will have zero time
+    /// of impact and [`TimeOfImpact::penetrating`] flag set.
+    ///
+    /// It could be used to predict where a projectile will hit a moving target, or to avoid collisions
+    /// before they actually happen. Positions of the colliders are taken from the physics world, so they
+    /// reflect the state after the last physics update.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::{
+    ///     core::{algebra::Vector3, pool::Handle},
+    ///     scene::{graph::Graph, node::Node},
+    /// };
+    ///
+    /// fn will_collide_soon(
+    ///     graph: &Graph,
+    ///     ship: Handle<Node>,
+    ///     ship_velocity: Vector3<f32>,
+    ///     asteroid: Handle<Node>,
+    ///     asteroid_velocity: Vector3<f32>,
+    /// ) -> bool {
+    ///     graph
+    ///         .time_of_impact(ship, ship_velocity, asteroid, asteroid_velocity, 2.0)
+    ///         .is_some()
+    /// }
+    /// ```
+    pub fn time_of_impact(
+        &self,
+        collider1: Handle<Node>,
+        velocity1: Vector3<f32>,
+        collider2: Handle<Node>,
+        velocity2: Vector3<f32>,
+        max_toi: f32,
+    ) -> Option<TimeOfImpact> {
+        self.physics
+            .time_of_impact(collider1, velocity1, collider2, velocity2, max_toi)
+    }
+
     /// Tries to find references of the given node in other scene nodes. It could be used to check if the node is
     /// used by some other scene node or not. Returns an array of nodes, that references the given node. This method
     /// is reflection-based, so it is quite slow and should not be used every frame.
@@ -411,12 +1420,24 @@ impl Graph {
             .and_then(|n| n.query_component_ref::<T>())
     }
 
-    /// Tries to mutably borrow a node, returns Some(node) if the handle is valid, None - otherwise.
+    /// Tries to mutably borrow a node, returns Some(node) if the handle is valid, None - otherwise. Node
+    /// locks are ignored, use [`Self::try_get_mut_checked`] to respect them.
     #[inline]
     pub fn try_get_mut(&mut self, handle: Handle<Node>) -> Option<&mut Node> {
         self.pool.try_borrow_mut(handle)
     }
 
+    /// Tries to mutably borrow a node. Returns an error if the handle is invalid or the node is locked. Use it
+    /// instead of [`Self::try_get_mut`] or mutable indexing in the code that must not modify locked nodes. See
+    /// [`Self::set_subtree_locked`] for more info.
+    #[inline]
+    pub fn try_get_mut_checked(&mut self, handle: Handle<Node>) -> Result<&mut Node, GraphError> {
+        self.check_unlocked(handle)?;
+        self.pool
+            .try_borrow_mut(handle)
+            .ok_or(GraphError::InvalidHandle(handle))
+    }
+
     /// Tries to mutably borrow a node and fetch its component of specified type.
     #[inline]
     pub fn try_get_mut_of_type<T>(&mut self, handle: Handle<Node>) -> Option<&mut T>
@@ -471,9 +1492,30 @@ impl Graph {
     }
 
     /// Destroys the node and its children recursively. Scripts of the destroyed nodes will be removed in the next
-    /// update tick.
+    /// update tick. The node won't be removed if it, any of its descendants or its parent is locked, an error
+    /// message will be written to the log instead. See [`Self::try_remove_node`] for a fallible version.
     #[inline]
     pub fn remove_node(&mut self, node_handle: Handle<Node>) {
+        Log::verify(self.try_remove_node(node_handle));
+    }
+
+    /// Destroys the node and its children recursively. Returns an error if the node, any of its descendants or its
+    /// parent is locked, the graph is left unchanged in this case. See [`Self::set_subtree_locked`] for more info.
+    #[inline]
+    pub fn try_remove_node(&mut self, node_handle: Handle<Node>) -> Result<(), GraphError> {
+        if !self.locked.is_empty() {
+            self.check_unlocked(self.pool[node_handle].parent)?;
+            for handle in self.traverse_handle_iter(node_handle) {
+                self.check_unlocked(handle)?;
+            }
+        }
+
+        self.remove_node_internal(node_handle);
+
+        Ok(())
+    }
+
+    fn remove_node_internal(&mut self, node_handle: Handle<Node>) {
         self.unlink_internal(node_handle);
 
         self.stack.clear();
@@ -485,6 +1527,8 @@ impl Graph {
 
             // Remove associated entities.
             let mut node = self.pool.free(handle);
+            self.locked.remove(&handle);
+            self.groups.remove_node(handle);
             node.on_removed_from_graph(self);
 
             self.event_broadcaster
@@ -519,14 +1563,100 @@ impl Graph {
         }
     }
 
-    /// Links specified child with specified parent.
+    /// Links specified child with specified parent. The nodes won't be linked if the child, its current parent or
+    /// the new parent is locked, an error message will be written to the log instead. See [`Self::try_link_nodes`]
+    /// for a fallible version.
     #[inline]
     pub fn link_nodes(&mut self, child: Handle<Node>, parent: Handle<Node>) {
+        Log::verify(self.try_link_nodes(child, parent));
+    }
+
+    /// Links specified child with specified parent. Returns an error if the child, its current parent or the new
+    /// parent is locked, the graph is left unchanged in this case. See [`Self::set_subtree_locked`] for more info.
+    #[inline]
+    pub fn try_link_nodes(
+        &mut self,
+        child: Handle<Node>,
+        parent: Handle<Node>,
+    ) -> Result<(), GraphError> {
+        if !self.locked.is_empty() {
+            self.check_unlocked(child)?;
+            self.check_unlocked(self.pool[child].parent)?;
+            self.check_unlocked(parent)?;
+        }
+
+        self.link_nodes_internal(child, parent);
+
+        Ok(())
+    }
+
+    fn link_nodes_internal(&mut self, child: Handle<Node>, parent: Handle<Node>) {
         self.unlink_internal(child);
         self.pool[child].parent = parent;
         self.pool[parent].children.push(child);
     }
 
+    /// Locks or unlocks the given node and all its descendants. Locked nodes cannot be linked to other nodes,
+    /// removed or moved to another scene, other nodes cannot be linked to locked nodes as well. It is useful to
+    /// protect some parts of the graph from accidental modification, for example the editor could use it to
+    /// protect internals of prefab instances.
+    ///
+    /// # Important notes
+    ///
+    /// The lock is opt-in for modifications of node properties: mutable indexing (`&mut graph[handle]`) and
+    /// [`Self::try_get_mut`] ignore it, so engine systems and scripts still update locked nodes. Use
+    /// [`Self::try_get_mut_checked`] to get an error for a locked node instead.
+    /// Nodes, that will be linked to a locked subtree later, won't be locked automatically, however it is
+    /// impossible to link a node to a locked node.
+    pub fn set_subtree_locked(&mut self, root: Handle<Node>, locked: bool) {
+        self.stack.clear();
+        self.stack.push(root);
+        while let Some(handle) = self.stack.pop() {
+            if let Some(node) = self.pool.try_borrow(handle) {
+                self.stack.extend_from_slice(node.children());
+                if locked {
+                    self.locked.insert(handle);
+                } else {
+                    self.locked.remove(&handle);
+                }
+            }
+        }
+    }
+
+    /// Returns true if the given node is locked, false - otherwise. See [`Self::set_subtree_locked`] for more info.
+    #[inline]
+    pub fn is_locked(&self, handle: Handle<Node>) -> bool {
+        self.locked.contains(&handle)
+    }
+
+    /// Returns a reference to named groups of nodes of the graph. See [`NodeGroups`] docs for more info.
+    #[inline]
+    pub fn groups(&self) -> &NodeGroups {
+        &self.groups
+    }
+
+    /// Returns a mutable reference to named groups of nodes of the graph. See [`NodeGroups`] docs for more info.
+    #[inline]
+    pub fn groups_mut(&mut self) -> &mut NodeGroups {
+        &mut self.groups
+    }
+
+    /// Adds the given node to a group with the given name, the group will be created if it does not exist.
+    /// Returns `true` if the node was added, `false` - if the handle is invalid or the node is already in
+    /// the group. The node will be automatically removed from the group when it is destroyed.
+    #[inline]
+    pub fn add_to_group(&mut self, handle: Handle<Node>, group: &str) -> bool {
+        self.is_valid_handle(handle) && self.groups.add(group, handle)
+    }
+
+    fn check_unlocked(&self, handle: Handle<Node>) -> Result<(), GraphError> {
+        if self.locked.contains(&handle) {
+            Err(GraphError::NodeLocked(handle))
+        } else {
+            Ok(())
+        }
+    }
+
     /// Links specified child with specified parent while keeping the
     /// child's global position and rotation.
     #[inline]
@@ -598,6 +1728,36 @@ impl Graph {
         })
     }
 
+    /// Searches for a node down the tree starting from the specified node using the specified closure. Unlike
+    /// [`Self::find`], it uses the second closure to decide whether to descend into children of a node or not, which
+    /// allows you to skip entire branches that cannot contain a desired node (for example - static level geometry).
+    /// The node itself is checked before the `descend` closure is called, so a node could be found even if its
+    /// subtree is pruned. Returns a tuple with a handle and a reference to the found node. If nothing is found,
+    /// it returns [`None`].
+    #[inline]
+    pub fn find_filtered<C, F>(
+        &self,
+        root_node: Handle<Node>,
+        cmp: &mut C,
+        descend: &mut F,
+    ) -> Option<(Handle<Node>, &Node)>
+    where
+        C: FnMut(&Node) -> bool,
+        F: FnMut(&Node) -> bool,
+    {
+        self.pool.try_borrow(root_node).and_then(|root| {
+            if cmp(root) {
+                Some((root_node, root))
+            } else if descend(root) {
+                root.children()
+                    .iter()
+                    .find_map(|c| self.find_filtered(*c, cmp, descend))
+            } else {
+                None
+            }
+        })
+    }
+
     /// Searches for a node down the tree starting from the specified node using the specified closure. Returns a tuple
     /// with a handle and a reference to the mapped value. If nothing is found, it returns [`None`].
     #[inline]
@@ -665,6 +1825,26 @@ impl Graph {
         self.find(root_node, &mut |node| node.name() == name)
     }
 
+    /// Searches for a node which name matches the specified pattern down the tree starting from the specified node.
+    /// Comparison is case-insensitive, the pattern may contain wildcards: `*` matches any sequence of characters
+    /// (including empty one) and `?` matches exactly one character. For example, `door_*` pattern matches `Door_01`,
+    /// `DOOR_Left` and `door_`. Returns a tuple with a handle and a reference to the found node. If nothing is found,
+    /// it returns [`None`].
+    #[inline]
+    pub fn find_by_name_matching(
+        &self,
+        root_node: Handle<Node>,
+        pattern: &str,
+    ) -> Option<(Handle<Node>, &Node)> {
+        let pattern = pattern.to_lowercase().chars().collect::<Vec<_>>();
+        self.find(root_node, &mut |node| {
+            wildcard_match(
+                &pattern,
+                &node.name().to_lowercase().chars().collect::<Vec<_>>(),
+            )
+        })
+    }
+
     /// Searches for a node with the specified name up the tree starting from the specified node. Returns a tuple with a
     /// handle and a reference to the found node. If nothing is found, it returns [`None`].
     #[inline]
@@ -1058,6 +2238,108 @@ impl Graph {
         instances
     }
 
+    /// Enables or disables compact serialization of resource instances. When enabled, descendant
+    /// nodes of resource instances that does not have any modified properties (and does not have
+    /// scripts) will not be saved at all, they will be re-created from respective resource on load.
+    /// Modified properties of every other instance node are saved as usual (only modified ones),
+    /// which means that instances are stored as a compact "patch" against their resources. This
+    /// dramatically reduces size of scenes with lots of instances and speeds up their loading.
+    ///
+    /// # Important notes
+    ///
+    /// Handles of the nodes that were not saved will change after loading. Any handles to such
+    /// nodes, that are stored in scripts or modified properties, will become invalid.
+    pub fn set_instance_patch_mode(&mut self, enabled: bool) {
+        self.instance_patch_mode = enabled;
+    }
+
+    /// Returns true if compact serialization of resource instances is enabled, false - otherwise.
+    /// See [`Self::set_instance_patch_mode`] for more info.
+    pub fn is_instance_patch_mode(&self) -> bool {
+        self.instance_patch_mode
+    }
+
+    // Searches for top-most nodes, that can be fully restored from their resources, in a sub-tree
+    // starting from the given node. Returns true if the whole sub-tree can be restored.
+    fn collect_restorable_instance_nodes(
+        &self,
+        handle: Handle<Node>,
+        instance_resource: Option<&ModelResource>,
+        restorable: &mut Vec<Handle<Node>>,
+    ) -> bool {
+        let node = &self.pool[handle];
+
+        let instance_resource = if node.is_resource_instance_root {
+            node.resource.as_ref()
+        } else {
+            instance_resource
+        };
+
+        let mut children_restorable = Vec::new();
+        let mut all_children_restorable = true;
+        for &child in node.children() {
+            if !self.collect_restorable_instance_nodes(
+                child,
+                instance_resource,
+                &mut children_restorable,
+            ) {
+                all_children_restorable = false;
+            }
+        }
+
+        if !node.is_resource_instance_root
+            && instance_resource.is_some()
+            && node.resource.as_ref() == instance_resource
+            && all_children_restorable
+            && is_restorable_from_resource(node)
+        {
+            restorable.push(handle);
+            true
+        } else {
+            restorable.extend(children_restorable);
+            false
+        }
+    }
+
+    // Temporarily removes every node, that can be fully restored from its resource, from the graph.
+    // Handles of the nodes stay the same, so the nodes can be put back using
+    // Self::put_back_restorable_instance_nodes.
+    fn take_restorable_instance_nodes(&mut self) -> RestorableInstanceNodes {
+        let mut restorable = Vec::new();
+        if self.pool.is_valid_handle(self.root) {
+            self.collect_restorable_instance_nodes(self.root, None, &mut restorable);
+        }
+
+        let mut taken = RestorableInstanceNodes::default();
+        for root in restorable {
+            let parent = self.pool[root].parent;
+            if let Some(parent_ref) = self.pool.try_borrow_mut(parent) {
+                if let Some(position) = parent_ref.children.iter().position(|c| *c == root) {
+                    parent_ref.children.remove(position);
+                    taken.links.push((parent, position, root));
+                }
+            }
+
+            self.stack.clear();
+            self.stack.push(root);
+            while let Some(handle) = self.stack.pop() {
+                self.stack.extend_from_slice(self.pool[handle].children());
+                taken.nodes.push((handle, self.pool.free(handle)));
+            }
+        }
+        taken
+    }
+
+    fn put_back_restorable_instance_nodes(&mut self, taken: RestorableInstanceNodes) {
+        for (handle, node) in taken.nodes {
+            let result = self.pool.spawn_at_handle(handle, node);
+            assert!(result.is_ok());
+        }
+        for (parent, position, child) in taken.links.into_iter().rev() {
+            self.pool[parent].children.insert(position, child);
+        }
+    }
+
     fn restore_dynamic_node_data(&mut self) {
         for (handle, node) in self.pool.pair_iter_mut() {
             node.self_handle = handle;
@@ -1131,9 +2413,12 @@ impl Graph {
             },
         );
 
+        if node.global_transform.get() != new_global_transform {
+            node.global_transform_changed.set(true);
+        }
         node.global_transform.set(new_global_transform);
         node.global_visibility
-            .set(parent_visibility && node.visibility());
+            .set((parent_visibility || node.ignore_parent_visibility()) && node.visibility());
         node.global_enabled.set(parent_enabled && node.is_enabled());
 
         for &child in node.children() {
@@ -1203,6 +2488,18 @@ impl Graph {
         );
     }
 
+    /// Returns an iterator over handles of the nodes whose global transform has changed during
+    /// the last [`Self::update`] call. It could be useful for systems that only interested in moved
+    /// objects (audio, networking, AI, etc.), so they don't need to cache and compare positions of
+    /// the nodes by themselves.
+    #[inline]
+    pub fn dirty_nodes_iter(&self) -> impl Iterator<Item = Handle<Node>> + '_ {
+        self.pool
+            .pair_iter()
+            .filter(|(_, node)| node.global_transform_changed.get())
+            .map(|(handle, _)| handle)
+    }
+
     /// Checks whether given node handle is valid or not.
     #[inline]
     pub fn is_valid_handle(&self, node_handle: Handle<Node>) -> bool {
@@ -1228,14 +2525,17 @@ impl Graph {
         handle: Handle<Node>,
         frame_size: Vector2<f32>,
         dt: f32,
-        delete_dead_nodes: bool,
+        switches: &GraphUpdateSwitches,
     ) {
+        let delete_dead_nodes = switches.delete_dead_nodes;
         if let Some((ticket, mut node)) = self.pool.try_take_reserve(handle) {
             node.transform_modified.set(false);
 
             let mut is_alive = node.is_alive();
 
             if node.is_globally_enabled() {
+                let last_time = switches.node_type_statistics.then(instant::Instant::now);
+
                 node.update(&mut UpdateContext {
                     frame_size,
                     dt,
@@ -1245,6 +2545,16 @@ impl Graph {
                     sound_context: &mut self.sound_context,
                 });
 
+                if let Some(last_time) = last_time {
+                    let statistics = self
+                        .performance_statistics
+                        .node_types
+                        .entry(node.type_name())
+                        .or_default();
+                    statistics.count += 1;
+                    statistics.update_time += instant::Instant::now() - last_time;
+                }
+
                 if delete_dead_nodes {
                     if let Some(lifetime) = node.lifetime.get_value_mut_silent().as_mut() {
                         *lifetime -= dt;
@@ -1258,7 +2568,7 @@ impl Graph {
             self.pool.put_back(ticket, node);
 
             if !is_alive && delete_dead_nodes {
-                self.remove_node(handle);
+                self.remove_node_internal(handle);
             }
         }
     }
@@ -1269,6 +2579,12 @@ impl Graph {
     ///
     /// Update switches allows you to disable update for parts of the update pipeline, it could be useful for editors
     /// where you need to have preview mode to update only specific set of nodes, etc.
+    ///
+    /// # Update Stages
+    ///
+    /// The update is performed in a fixed order: hierarchical data (global transforms, visibility, etc.) calculation,
+    /// synchronization of native physics and sound entities, physics step, update of every node. Custom systems
+    /// could be executed between these steps, see [`Self::add_update_system`] for more info.
     pub fn update(&mut self, frame_size: Vector2<f32>, dt: f32, switches: GraphUpdateSwitches) {
         self.sound_context.state().pause(switches.paused);
 
@@ -1276,19 +2592,41 @@ impl Graph {
             return;
         }
 
+        for node in self.pool.iter() {
+            node.global_transform_changed.set(false);
+        }
+
+        let ctx = UpdateSystemContext { frame_size, dt };
+
+        self.performance_statistics.update_systems_time = Duration::default();
+        self.performance_statistics.node_types.clear();
+
+        self.run_update_systems(UpdateStage::PreUpdate, &ctx);
+
         let last_time = instant::Instant::now();
         self.update_hierarchical_data();
         self.performance_statistics.hierarchical_properties_time =
             instant::Instant::now() - last_time;
 
+        self.run_update_systems(UpdateStage::PostHierarchy, &ctx);
+
         let last_time = instant::Instant::now();
         self.sync_native(&switches);
         self.performance_statistics.sync_time = instant::Instant::now() - last_time;
 
+        self.run_update_systems(UpdateStage::PostSync, &ctx);
+
+        // Native entities of removed nodes are kept alive until the simulation step, so the queries
+        // could not refer to removed entities.
+        self.physics.flush_removals();
+        self.physics2d.flush_removals();
+
         if switches.physics {
             self.physics.performance_statistics.reset();
             self.physics.update(dt);
             self.performance_statistics.physics = self.physics.performance_statistics.clone();
+            self.update_triggers();
+            self.update_broken_joints();
         }
 
         if switches.physics2d {
@@ -1297,25 +2635,131 @@ impl Graph {
             self.performance_statistics.physics2d = self.physics2d.performance_statistics.clone();
         }
 
+        // Scene queries use the state of the world after the simulation step for the rest of the frame.
+        self.physics.refresh_queries();
+        self.physics2d.refresh_queries();
+
         self.performance_statistics.sound_update_time =
             self.sound_context.state().full_render_duration();
 
+        self.run_update_systems(UpdateStage::PostPhysics, &ctx);
+
+        let last_time = instant::Instant::now();
         if let Some(overrides) = switches.node_overrides.as_ref() {
             for handle in overrides {
-                self.update_node(*handle, frame_size, dt, switches.delete_dead_nodes);
+                self.update_node(*handle, frame_size, dt, &switches);
             }
         } else {
             for i in 0..self.pool.get_capacity() {
-                self.update_node(
-                    self.pool.handle_from_index(i),
-                    frame_size,
-                    dt,
-                    switches.delete_dead_nodes,
-                );
+                self.update_node(self.pool.handle_from_index(i), frame_size, dt, &switches);
+            }
+        }
+        self.performance_statistics.nodes_update_time = instant::Instant::now() - last_time;
+
+        self.run_update_systems(UpdateStage::PostUpdate, &ctx);
+    }
+
+    // Marks joints that were broken during the last physics update.
+    fn update_broken_joints(&mut self) {
+        for event in self.physics.joint_break_events() {
+            if let Some(joint) = self
+                .pool
+                .try_borrow_mut(event.joint)
+                .and_then(|n| n.cast_mut::<scene::joint::Joint>())
+            {
+                joint.broken = true;
+            }
+        }
+    }
+
+    // Updates sets of entered, exited and inside colliders of sensor colliders using intersection events
+    // of the last physics update.
+    fn update_triggers(&mut self) {
+        for handle in self.active_triggers.drain(..) {
+            if let Some(collider) = self
+                .pool
+                .try_borrow_mut(handle)
+                .and_then(|n| n.cast_mut::<Collider>())
+            {
+                collider.entered.clear();
+                collider.exited.clear();
+            }
+        }
+
+        for event in self.physics.intersection_events() {
+            for (sensor, other) in [
+                (event.collider1, event.collider2),
+                (event.collider2, event.collider1),
+            ] {
+                if let Some(collider) = self
+                    .pool
+                    .try_borrow_mut(sensor)
+                    .and_then(|n| n.cast_mut::<Collider>())
+                {
+                    if !collider.is_sensor() {
+                        continue;
+                    }
+
+                    match event.kind {
+                        CollisionEventKind::Started => {
+                            collider.entered.insert(other);
+                            collider.inside.insert(other);
+                        }
+                        CollisionEventKind::Stopped => {
+                            collider.exited.insert(other);
+                            collider.inside.remove(&other);
+                        }
+                    }
+
+                    self.active_triggers.push(sensor);
+                }
             }
         }
     }
 
+    fn run_update_systems(&mut self, stage: UpdateStage, ctx: &UpdateSystemContext) {
+        if self.update_systems.is_empty() {
+            return;
+        }
+
+        // Take the systems out of the graph to be able to pass the graph to them. Systems could be
+        // added or removed while running, such changes will be merged back.
+        let last_time = instant::Instant::now();
+        let continuation = self.update_systems.make_empty_continuation();
+        let mut systems = std::mem::replace(&mut self.update_systems, continuation);
+        systems.run(stage, self, ctx);
+        let changes = std::mem::take(&mut self.update_systems);
+        systems.merge(changes);
+        self.update_systems = systems;
+        self.performance_statistics.update_systems_time += instant::Instant::now() - last_time;
+    }
+
+    /// Registers a new update system, that will be executed on every [`Self::update`] call after the given
+    /// stage. Systems of the same stage are executed in the order of registration. Returns an id of the
+    /// system, that could be used to remove it. See [`UpdateStage`] docs for more info.
+    ///
+    /// # Important notes
+    ///
+    /// Update systems are not serialized and are not copied when the graph is cloned. Systems won't be
+    /// executed if the graph is paused (see [`GraphUpdateSwitches::paused`]).
+    pub fn add_update_system<F>(&mut self, stage: UpdateStage, system: F) -> UpdateSystemId
+    where
+        F: FnMut(&mut Graph, &UpdateSystemContext) + Send + 'static,
+    {
+        self.update_systems.add(stage, Box::new(system))
+    }
+
+    /// Removes an update system with the given id. It is safe to call this method from update systems,
+    /// including the one that is being removed.
+    pub fn remove_update_system(&mut self, id: UpdateSystemId) {
+        self.update_systems.remove(id)
+    }
+
+    /// Returns total amount of registered update systems.
+    pub fn update_system_count(&self) -> usize {
+        self.update_systems.len()
+    }
+
     /// Returns capacity of internal pool. Can be used to iterate over all **potentially**
     /// available indices and try to convert them to handles.
     ///
@@ -1430,12 +2874,22 @@ impl Graph {
     pub fn take_reserve_sub_graph(&mut self, root: Handle<Node>) -> SubGraph {
         // Take out descendants first.
         let mut descendants = Vec::new();
-        let root_ref = &mut self[root];
+        let mut groups = Vec::new();
+        let mut locked = Vec::new();
+        let root_ref = &mut self.pool[root];
         let mut stack = root_ref.children().to_vec();
         let parent = root_ref.parent;
         while let Some(handle) = stack.pop() {
             stack.extend_from_slice(self[handle].children());
             descendants.push(self.take_reserve_internal(handle));
+            groups.push((handle, self.groups.remove_node(handle)));
+            if self.locked.remove(&handle) {
+                locked.push(handle);
+            }
+        }
+        groups.push((root, self.groups.remove_node(root)));
+        if self.locked.remove(&root) {
+            locked.push(root);
         }
 
         SubGraph {
@@ -1443,7 +2897,38 @@ impl Graph {
             root: self.take_reserve(root),
             descendants,
             parent,
+            physics_state: Default::default(),
+            groups,
+            locked,
+        }
+    }
+
+    /// Removes sub-graph starting from a given node and returns removed nodes. Unlike
+    /// [`Self::remove_node`], the nodes are not destroyed, and their handles stay reserved, so the
+    /// sub-graph could be put back later using [`Self::put_sub_graph_back`]. Native physics entities
+    /// of the nodes are removed, but their runtime state (velocities, forces, sleeping state, etc.)
+    /// is stored in the sub-graph and will be restored when the sub-graph is put back. This is
+    /// useful for undo/redo systems that must restore everything as it was.
+    #[inline]
+    pub fn remove_sub_graph(&mut self, root: Handle<Node>) -> SubGraph {
+        let handles = self.traverse_handle_iter(root).collect::<Vec<_>>();
+
+        let mut physics_state = Vec::new();
+        for &handle in handles.iter() {
+            if let Some(state) = self.native_physics_state(&self.pool[handle]) {
+                physics_state.push((handle, state));
+            }
         }
+
+        let mut sub_graph = self.take_reserve_sub_graph(root);
+        sub_graph.physics_state = physics_state;
+
+        for handle in handles {
+            self.event_broadcaster
+                .broadcast(GraphEvent::Removed(handle));
+        }
+
+        sub_graph
     }
 
     /// Puts previously extracted sub-graph into graph. Handles to nodes will become valid
@@ -1456,9 +2941,31 @@ impl Graph {
         }
 
         let (ticket, node) = sub_graph.root;
-        let root_handle = self.put_back(ticket, node);
+        let root_handle = self.put_back_internal(ticket, node);
+
+        // The sub-graph must be restored as it was, even if its parent is locked.
+        self.link_nodes_internal(root_handle, sub_graph.parent);
+
+        for (handle, names) in sub_graph.groups.iter() {
+            for name in names {
+                self.groups.add(name, *handle);
+            }
+        }
+        self.locked.extend(sub_graph.locked.iter().cloned());
+
+        if !sub_graph.physics_state.is_empty() {
+            // Native physics entities must be re-created first to be able to restore their state.
+            self.update_hierarchical_data_for_descendants(root_handle);
+            // Sync twice, because native joints can be created only when native rigid bodies of
+            // both ends of the joint are exist.
+            for _ in 0..2 {
+                self.sync_native(&Default::default());
+            }
 
-        self.link_nodes(root_handle, sub_graph.parent);
+            for (handle, state) in sub_graph.physics_state.iter() {
+                self.apply_native_physics_state(*handle, state);
+            }
+        }
 
         root_handle
     }
@@ -1469,59 +2976,319 @@ impl Graph {
         for (ticket, _) in sub_graph.descendants {
             self.pool.forget_ticket(ticket);
         }
-        let (ticket, _) = sub_graph.root;
-        self.pool.forget_ticket(ticket);
-    }
+        let (ticket, _) = sub_graph.root;
+        self.pool.forget_ticket(ticket);
+    }
+
+    /// Returns the number of nodes in the graph.
+    #[inline]
+    pub fn node_count(&self) -> u32 {
+        self.pool.alive_count()
+    }
+
+    /// Create a graph depth traversal iterator.
+    ///
+    /// # Notes
+    ///
+    /// This method allocates temporal array so it is not cheap! Should not be
+    /// used on each frame.
+    #[inline]
+    pub fn traverse_iter(&self, from: Handle<Node>) -> GraphTraverseIterator {
+        GraphTraverseIterator {
+            graph: self,
+            stack: vec![from],
+        }
+    }
+
+    /// Create a graph depth traversal iterator which will emit *handles* to nodes.
+    ///
+    /// # Notes
+    ///
+    /// This method allocates temporal array so it is not cheap! Should not be
+    /// used on each frame.
+    #[inline]
+    pub fn traverse_handle_iter(&self, from: Handle<Node>) -> GraphHandleTraverseIterator {
+        GraphHandleTraverseIterator {
+            graph: self,
+            stack: vec![from],
+        }
+    }
+
+    /// Returns depth of the given node in the hierarchy - the amount of its ancestors. The root node has depth of
+    /// zero, its children - one and so on. Returns [`None`] if the handle is invalid.
+    #[inline]
+    pub fn depth_of(&self, handle: Handle<Node>) -> Option<usize> {
+        let mut node = self.pool.try_borrow(handle)?;
+        let mut depth = 0;
+        while let Some(parent) = self.pool.try_borrow(node.parent) {
+            node = parent;
+            depth += 1;
+        }
+        Some(depth)
+    }
+
+    /// Returns handles of every node reachable from the root of the graph, sorted so that every parent goes
+    /// before its children (nodes are sorted by their depth in the hierarchy). The sorting is done in a single
+    /// linear pass without recursion. The array could be used to implement custom update passes that must
+    /// respect hierarchy order, for example constraint solvers or custom transform modifiers.
+    ///
+    /// # Notes
+    ///
+    /// This method allocates an array of handles so it is not cheap! Should not be used on each frame,
+    /// if the hierarchy does not change.
+    #[inline]
+    pub fn topological_sort(&self) -> Vec<Handle<Node>> {
+        let mut sorted = Vec::with_capacity(self.pool.alive_count() as usize);
+        if self.pool.is_valid_handle(self.root) {
+            sorted.push(self.root);
+        }
+        let mut i = 0;
+        while let Some(&handle) = sorted.get(i) {
+            sorted.extend_from_slice(self.pool[handle].children());
+            i += 1;
+        }
+        sorted
+    }
+
+    /// Creates deep copy of graph. Allows filtering while copying, returns copy and
+    /// old-to-new node mapping.
+    #[inline]
+    pub fn clone<F>(&self, root: Handle<Node>, filter: &mut F) -> (Self, NodeHandleMap)
+    where
+        F: FnMut(Handle<Node>, &Node) -> bool,
+    {
+        let mut copy = Self {
+            sound_context: self.sound_context.deep_clone(),
+            ..Default::default()
+        };
+        copy.reserve(self.pool.alive_count());
+
+        let (copy_root, old_new_map) = self.copy_node(root, &mut copy, filter);
+        assert_eq!(copy.root, copy_root);
+        copy.groups = self.groups.remap(&old_new_map);
+        (copy, old_new_map)
+    }
+
+    /// Creates deep copy of graph the same way as [`Self::clone`] does, but also preserves runtime
+    /// state of the physics - velocities, accumulated forces and activation (sleeping) state of rigid
+    /// bodies and accumulated impulses of joints. Unlike [`Self::clone`], native physics entities of
+    /// the copy are created immediately instead of the next update. It allows cloned scenes to continue
+    /// simulating (almost) identically to the original one, the only exception is contacts, they will
+    /// be re-calculated on the next update of the copy.
+    pub fn clone_with_physics_state<F>(
+        &self,
+        root: Handle<Node>,
+        filter: &mut F,
+    ) -> (Self, NodeHandleMap)
+    where
+        F: FnMut(Handle<Node>, &Node) -> bool,
+    {
+        let (mut copy, old_new_map) = self.clone(root, filter);
+
+        copy.physics.gravity = self.physics.gravity;
+        copy.physics.integration_parameters = self.physics.integration_parameters;
+        copy.physics2d.gravity = self.physics2d.gravity;
+        copy.physics2d.integration_parameters = self.physics2d.integration_parameters;
+
+        copy.update_hierarchical_data();
+        // Sync twice, because native joints can be created only when native rigid bodies of both
+        // ends of the joint are exist.
+        for _ in 0..2 {
+            copy.sync_native(&Default::default());
+        }
+
+        for (&original_handle, &copy_handle) in old_new_map.inner().iter() {
+            if let Some(state) = self
+                .pool
+                .try_borrow(original_handle)
+                .and_then(|original| self.native_physics_state(original))
+            {
+                copy.apply_native_physics_state(copy_handle, &state);
+            }
+        }
+
+        (copy, old_new_map)
+    }
+
+    /// Moves a sub-graph starting from the given node into a new scene. Unlike [`Self::clone`], the
+    /// nodes are moved as is (including their scripts), and the runtime state of the physics entities
+    /// (velocities, forces and activation state of rigid bodies, accumulated impulses of joints) is
+    /// preserved. The root of the sub-graph will be attached to the root of the new scene. Handles
+    /// to the moved nodes become invalid in this graph, cross-references between the moved nodes
+    /// (for example bodies of joints, or bones of skinned meshes) will be remapped to new handles.
+    ///
+    /// It could be useful to move some entities (for example a player or a vehicle) between streamed
+    /// levels without serialization round-trips.
+    ///
+    /// Returns an error if the given node, any of its descendants or its parent is locked, the graph is
+    /// left unchanged in this case. See [`Self::set_subtree_locked`] for more info.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given node is the root of the graph.
+    pub fn take_sub_graph_into_scene(&mut self, root: Handle<Node>) -> Result<Scene, GraphError> {
+        assert_ne!(root, self.root, "Cannot move the root of the graph!");
+
+        if !self.locked.is_empty() {
+            self.check_unlocked(self.pool[root].parent)?;
+            for handle in self.traverse_handle_iter(root) {
+                self.check_unlocked(handle)?;
+            }
+        }
+
+        let mut scene = Scene::new();
+        let dest = &mut scene.graph;
+        dest.physics.gravity = self.physics.gravity;
+        dest.physics.integration_parameters = self.physics.integration_parameters;
+        dest.physics2d.gravity = self.physics2d.gravity;
+        dest.physics2d.integration_parameters = self.physics2d.integration_parameters;
+
+        self.unlink_internal(root);
+
+        let mut old_new_map = NodeHandleMap::default();
+        let mut moved = Vec::new();
+        let mut physics_states = Vec::new();
+
+        self.stack.clear();
+        self.stack.push(root);
+        while let Some(handle) = self.stack.pop() {
+            self.stack.extend_from_slice(self.pool[handle].children());
+
+            let mut node = self.pool.free(handle);
+
+            // Native physics entities will be re-created in the new graph, remember their state first.
+            if let Some(state) = self.native_physics_state(&node) {
+                physics_states.push((handle, state));
+            }
+
+            node.on_removed_from_graph(self);
+            self.event_broadcaster
+                .broadcast(GraphEvent::Removed(handle));
+
+            let children = std::mem::take(&mut node.children);
+            node.parent = Handle::NONE;
+            let new_handle = dest.add_node(node);
+
+            // Group membership is moved together with the node.
+            for name in self.groups.remove_node(handle) {
+                dest.groups.add(&name, new_handle);
+            }
+
+            old_new_map.map.insert(handle, new_handle);
+            moved.push((new_handle, children));
+        }
+
+        // Restore hierarchy.
+        for (new_handle, children) in moved {
+            for child in children {
+                if let Some(&new_child) = old_new_map.map.get(&child) {
+                    dest.link_nodes(new_child, new_handle);
+                }
+            }
+        }
+
+        remap_handles(&old_new_map, dest);
+
+        dest.update_hierarchical_data();
+        // Sync twice, because native joints can be created only when native rigid bodies of both
+        // ends of the joint are exist.
+        for _ in 0..2 {
+            dest.sync_native(&Default::default());
+        }
+
+        for (handle, state) in physics_states {
+            if let Some(&new_handle) = old_new_map.map.get(&handle) {
+                dest.apply_native_physics_state(new_handle, &state);
+            }
+        }
 
-    /// Returns the number of nodes in the graph.
-    #[inline]
-    pub fn node_count(&self) -> u32 {
-        self.pool.alive_count()
+        Ok(scene)
     }
 
-    /// Create a graph depth traversal iterator.
-    ///
-    /// # Notes
-    ///
-    /// This method allocates temporal array so it is not cheap! Should not be
-    /// used on each frame.
-    #[inline]
-    pub fn traverse_iter(&self, from: Handle<Node>) -> GraphTraverseIterator {
-        GraphTraverseIterator {
-            graph: self,
-            stack: vec![from],
+    // Returns a copy of runtime state of native physics entities of the given node.
+    fn native_physics_state(&self, node: &Node) -> Option<NativePhysicsState> {
+        if let Some(rigid_body) = node.cast::<scene::rigidbody::RigidBody>() {
+            self.physics
+                .native_body(rigid_body.native.get())
+                .cloned()
+                .map(NativePhysicsState::RigidBody)
+        } else if let Some(joint) = node.cast::<scene::joint::Joint>() {
+            self.physics
+                .native_joint(joint.native.get())
+                .cloned()
+                .map(NativePhysicsState::Joint)
+        } else if let Some(rigid_body) = node.cast::<dim2::rigidbody::RigidBody>() {
+            self.physics2d
+                .native_body(rigid_body.native.get())
+                .cloned()
+                .map(NativePhysicsState::RigidBody2D)
+        } else if let Some(joint) = node.cast::<dim2::joint::Joint>() {
+            self.physics2d
+                .native_joint(joint.native.get())
+                .cloned()
+                .map(NativePhysicsState::Joint2D)
+        } else {
+            None
         }
     }
 
-    /// Create a graph depth traversal iterator which will emit *handles* to nodes.
-    ///
-    /// # Notes
-    ///
-    /// This method allocates temporal array so it is not cheap! Should not be
-    /// used on each frame.
-    #[inline]
-    pub fn traverse_handle_iter(&self, from: Handle<Node>) -> GraphHandleTraverseIterator {
-        GraphHandleTraverseIterator {
-            graph: self,
-            stack: vec![from],
+    // Applies previously copied runtime state to native physics entities of the given node.
+    fn apply_native_physics_state(&mut self, handle: Handle<Node>, state: &NativePhysicsState) {
+        let node = match self.pool.try_borrow(handle) {
+            Some(node) => node,
+            None => return,
+        };
+
+        match state {
+            NativePhysicsState::RigidBody(source) => {
+                if let Some(rigid_body) = node.cast::<scene::rigidbody::RigidBody>() {
+                    self.physics
+                        .copy_rigid_body_state(source, rigid_body.native.get());
+                }
+            }
+            NativePhysicsState::Joint(source) => {
+                if let Some(joint) = node.cast::<scene::joint::Joint>() {
+                    self.physics.copy_joint_state(source, joint.native.get());
+                }
+            }
+            NativePhysicsState::RigidBody2D(source) => {
+                if let Some(rigid_body) = node.cast::<dim2::rigidbody::RigidBody>() {
+                    self.physics2d
+                        .copy_rigid_body_state(source, rigid_body.native.get());
+                }
+            }
+            NativePhysicsState::Joint2D(source) => {
+                if let Some(joint) = node.cast::<dim2::joint::Joint>() {
+                    self.physics2d.copy_joint_state(source, joint.native.get());
+                }
+            }
         }
     }
 
-    /// Creates deep copy of graph. Allows filtering while copying, returns copy and
-    /// old-to-new node mapping.
+    /// Returns performance statistics of the last [`Self::update`] call.
     #[inline]
-    pub fn clone<F>(&self, root: Handle<Node>, filter: &mut F) -> (Self, NodeHandleMap)
+    pub fn performance_statistics(&self) -> &GraphPerformanceStatistics {
+        &self.performance_statistics
+    }
+
+    /// Extracts render-relevant state of the graph into a snapshot, that could be sent to another
+    /// thread. See [`extract::RenderSnapshot`] docs for more info.
+    pub fn extract_render_snapshot(&self) -> extract::RenderSnapshot {
+        extract::RenderSnapshot::extract(self)
+    }
+
+    /// Captures dynamic state of every node that passes the given filter. See [`GraphSnapshot`] docs
+    /// for more info.
+    pub fn capture_snapshot<F>(&self, flags: SnapshotFlags, filter: &mut F) -> GraphSnapshot
     where
         F: FnMut(Handle<Node>, &Node) -> bool,
     {
-        let mut copy = Self {
-            sound_context: self.sound_context.deep_clone(),
-            ..Default::default()
-        };
+        GraphSnapshot::capture(self, flags, filter)
+    }
 
-        let (copy_root, old_new_map) = self.copy_node(root, &mut copy, filter);
-        assert_eq!(copy.root, copy_root);
-        (copy, old_new_map)
+    /// Restores previously captured state of the nodes. See [`GraphSnapshot`] docs for more info.
+    pub fn restore_snapshot(&mut self, snapshot: &GraphSnapshot) {
+        snapshot.restore(self)
     }
 
     /// Returns local transformation matrix of a node without scale.
@@ -1619,6 +3386,94 @@ impl Graph {
         Vector3::new(m[0], m[5], m[10])
     }
 
+    // Calculates world transformation matrix of a node using local transforms of the node and its
+    // ancestors, unlike Base::global_transform it is always up-to-date.
+    fn calculate_global_transform(&self, node: Handle<Node>) -> Matrix4<f32> {
+        match self.pool.try_borrow(node) {
+            Some(node_ref) => {
+                self.calculate_global_transform(node_ref.parent())
+                    * node_ref.local_transform().matrix()
+            }
+            None => Matrix4::identity(),
+        }
+    }
+
+    /// Sets position of a node in world coordinates. The method calculates and assigns the local
+    /// position of the node that, given the current global transform of its parent, places the node
+    /// at the given position.
+    #[inline]
+    pub fn set_global_position(&mut self, node: Handle<Node>, position: Vector3<f32>) {
+        let parent_transform = self.calculate_global_transform(self[node].parent());
+        let local_position = parent_transform
+            .try_inverse()
+            .unwrap_or_else(Matrix4::identity)
+            .transform_point(&Point3::from(position))
+            .coords;
+
+        let transform = self[node].local_transform_mut();
+        // Rotation and scaling offsets and pivots may shift the origin of the node, take it into account.
+        let origin_offset = transform.matrix().position() - **transform.position();
+        transform.set_position(local_position - origin_offset);
+    }
+
+    /// Sets rotation of a node in world coordinates. The method calculates and assigns the local
+    /// rotation of the node that, given the current global rotation of its parent, results in the
+    /// given rotation. Local position of the node is left unchanged.
+    #[inline]
+    pub fn set_global_rotation(&mut self, node: Handle<Node>, rotation: UnitQuaternion<f32>) {
+        let parent = self[node].parent();
+        let parent_rotation = if parent.is_some() {
+            self.global_rotation(parent)
+        } else {
+            UnitQuaternion::identity()
+        };
+
+        let transform = self[node].local_transform_mut();
+        let local_rotation = transform.pre_rotation().inverse()
+            * parent_rotation.inverse()
+            * rotation
+            * transform.post_rotation().inverse();
+        transform.set_rotation(local_rotation);
+    }
+
+    /// Sets transformation of a node in world coordinates. The matrix is decomposed into position,
+    /// rotation and scale, then the method calculates and assigns the local transform of the node that,
+    /// given the current global transform of its parent, results in the given transform. Keep in mind
+    /// that the scale is calculated per axis, so it will be correct only if the axes of the node are
+    /// aligned with the axes of its parent or the parent has uniform scale.
+    #[inline]
+    pub fn set_global_transform(&mut self, node: Handle<Node>, transform: Matrix4<f32>) {
+        let basis = transform.basis();
+        let scale = Vector3::new(
+            basis.column(0).norm(),
+            basis.column(1).norm(),
+            basis.column(2).norm(),
+        );
+        let rotation = UnitQuaternion::from(Rotation3::from_matrix_eps(
+            &Matrix3::from_columns(&[
+                basis.column(0) / scale.x,
+                basis.column(1) / scale.y,
+                basis.column(2) / scale.z,
+            ]),
+            f32::EPSILON,
+            16,
+            Rotation3::identity(),
+        ));
+
+        let parent = self[node].parent();
+        let parent_scale = if parent.is_some() {
+            self.global_scale(parent)
+        } else {
+            Vector3::new(1.0, 1.0, 1.0)
+        };
+        self[node]
+            .local_transform_mut()
+            .set_scale(scale.component_div(&parent_scale));
+
+        self.set_global_rotation(node, rotation);
+        self.set_global_position(node, transform.position());
+    }
+
     /// Tries to borrow a node using the given handle, fetch its script and cast it to the specified type.
     #[inline]
     pub fn try_get_script_of<T>(&self, node: Handle<Node>) -> Option<&T>
@@ -1773,11 +3628,58 @@ impl Visit for Graph {
 
         let mut region = visitor.enter_region(name)?;
 
+        // Graphs without version are stored in the format prior to versioning (version 0).
+        let mut version = if region.is_reading() {
+            0u32
+        } else {
+            Self::VERSION
+        };
+        let _ = version.visit("Version", &mut region);
+
+        if version > Self::VERSION {
+            return Err(VisitError::User(format!(
+                "Graph version {} is not supported, the latest supported version is {}!",
+                version,
+                Self::VERSION
+            )));
+        }
+
         self.root.visit("Root", &mut region)?;
-        self.pool.visit("Pool", &mut region)?;
+        let _ = self
+            .instance_patch_mode
+            .visit("InstancePatchMode", &mut region);
+
+        // Nodes that can be fully restored from their resources are not saved in the patch mode,
+        // they will be re-created on load by Self::restore_integrity.
+        let taken = if !region.is_reading() && self.instance_patch_mode {
+            self.take_restorable_instance_nodes()
+        } else {
+            Default::default()
+        };
+        let result = self.pool.visit("Pool", &mut region);
+        self.put_back_restorable_instance_nodes(taken);
+        result?;
         self.sound_context.visit("SoundContext", &mut region)?;
         self.physics.visit("PhysicsWorld", &mut region)?;
         self.physics2d.visit("PhysicsWorld2D", &mut region)?;
+        let _ = self.groups.visit("Groups", &mut region);
+
+        if region.is_reading() && version < Self::VERSION {
+            if let Some(serialization_context) = region.blackboard.get::<SerializationContext>() {
+                let count = serialization_context
+                    .graph_migrations
+                    .migrate(self, version);
+
+                if count > 0 {
+                    Log::info(format!(
+                        "Graph was upgraded from version {} to {} using {} migrations.",
+                        version,
+                        Self::VERSION,
+                        count
+                    ));
+                }
+            }
+        }
 
         Ok(())
     }
@@ -1788,9 +3690,32 @@ mod test {
     use crate::scene::base::BaseBuilder;
     use crate::scene::pivot::PivotBuilder;
     use crate::{
-        core::pool::Handle,
-        scene::{graph::Graph, node::Node, pivot::Pivot},
+        core::{
+            algebra::{Matrix4, UnitQuaternion, Vector2, Vector3},
+            parking_lot::Mutex,
+            pool::Handle,
+            visitor::prelude::*,
+        },
+        engine::SerializationContext,
+        scene::{
+            graph::{
+                extract::{RenderSnapshot, SnapshotExchange},
+                snapshot::SnapshotFlags,
+                stage::UpdateStage,
+                sweep::OrphanAction,
+                Graph, GraphError, GraphUpdateSwitches,
+            },
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                MeshBuilder,
+            },
+            node::Node,
+            pivot::Pivot,
+            rigidbody::{RigidBody, RigidBodyBuilder},
+            transform::TransformBuilder,
+        },
     };
+    use std::sync::Arc;
 
     #[test]
     fn graph_init_test() {
@@ -1859,6 +3784,243 @@ mod test {
         assert_eq!(result.1, "A");
     }
 
+    #[test]
+    fn test_find_by_name_matching() {
+        let mut graph = Graph::new();
+        let door =
+            PivotBuilder::new(BaseBuilder::new().with_name("DOOR_Left_01")).build(&mut graph);
+        let window = PivotBuilder::new(BaseBuilder::new().with_name("Window")).build(&mut graph);
+        let root = graph.get_root();
+
+        assert_eq!(graph.find_by_name_matching(root, "door_*").unwrap().0, door);
+        assert_eq!(graph.find_by_name_matching(root, "*left*").unwrap().0, door);
+        assert_eq!(
+            graph.find_by_name_matching(root, "door_left_0?").unwrap().0,
+            door
+        );
+        assert_eq!(
+            graph.find_by_name_matching(root, "WINDOW").unwrap().0,
+            window
+        );
+        assert_eq!(graph.find_by_name_matching(root, "w*w").unwrap().0, window);
+        assert!(graph.find_by_name_matching(root, "door").is_none());
+        assert!(graph.find_by_name_matching(root, "window?").is_none());
+    }
+
+    #[test]
+    fn test_find_filtered() {
+        let mut graph = Graph::new();
+        let hidden = PivotBuilder::new(BaseBuilder::new().with_name("Target")).build(&mut graph);
+        let level = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_name("Level")
+                .with_children(&[hidden]),
+        )
+        .build(&mut graph);
+        let target = PivotBuilder::new(BaseBuilder::new().with_name("Target")).build(&mut graph);
+        let root = graph.get_root();
+
+        let mut visited = Vec::new();
+        let result = graph.find_filtered(
+            root,
+            &mut |node| {
+                visited.push(node.name().to_owned());
+                node.name() == "Target"
+            },
+            &mut |node| node.name() != "Level",
+        );
+        assert_eq!(result.unwrap().0, target);
+        // Level itself is checked, but its children are not.
+        assert!(visited.iter().any(|n| n == "Level"));
+        assert_eq!(visited.iter().filter(|n| *n == "Target").count(), 1);
+
+        assert_eq!(
+            graph
+                .find_filtered(root, &mut |node| node.name() == "Target", &mut |_| true)
+                .unwrap()
+                .0,
+            hidden
+        );
+        assert_eq!(
+            graph
+                .find_filtered(level, &mut |node| node.name() == "Level", &mut |_| false)
+                .unwrap()
+                .0,
+            level
+        );
+        assert!(graph
+            .find_filtered(root, &mut |node| node.name() == "Target", &mut |_| false)
+            .is_none());
+    }
+
+    #[test]
+    fn test_locked_subtree() {
+        let mut graph = Graph::new();
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let parent =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[child])).build(&mut graph);
+        let other = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        graph.set_subtree_locked(parent, true);
+        assert!(graph.is_locked(parent));
+        assert!(graph.is_locked(child));
+        assert!(!graph.is_locked(other));
+
+        assert_eq!(
+            graph.try_link_nodes(child, other),
+            Err(GraphError::NodeLocked(child))
+        );
+        assert_eq!(
+            graph.try_link_nodes(other, child),
+            Err(GraphError::NodeLocked(child))
+        );
+        assert_eq!(
+            graph.try_remove_node(parent),
+            Err(GraphError::NodeLocked(parent))
+        );
+        assert_eq!(
+            graph.try_get_mut_checked(child).err(),
+            Some(GraphError::NodeLocked(child))
+        );
+        assert!(graph.try_get_mut_checked(other).is_ok());
+        assert_eq!(graph[parent].children(), &[child]);
+
+        // The lock is opt-in for properties of the nodes, so scripts could still modify them.
+        graph[child].set_name("Modified");
+        assert_eq!(graph.try_get_mut(child).unwrap().name(), "Modified");
+
+        graph.set_subtree_locked(parent, false);
+        assert!(graph.try_get_mut_checked(child).is_ok());
+        assert!(graph.try_link_nodes(other, child).is_ok());
+        assert!(graph.try_remove_node(parent).is_ok());
+        assert!(!graph.is_valid_handle(other));
+        assert!(!graph.is_locked(child));
+        assert_eq!(
+            graph.try_get_mut_checked(other).err(),
+            Some(GraphError::InvalidHandle(other))
+        );
+    }
+
+    #[test]
+    fn test_locked_sub_graph_extraction() {
+        let mut graph = Graph::new();
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let parent =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[child])).build(&mut graph);
+        let container =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[parent])).build(&mut graph);
+        graph.set_subtree_locked(parent, true);
+
+        // Locked nodes cannot be moved to another scene.
+        assert_eq!(
+            graph.take_sub_graph_into_scene(container).err(),
+            Some(GraphError::NodeLocked(parent))
+        );
+        assert_eq!(graph[container].children(), &[parent]);
+
+        // Extracted nodes are unlocked until they're put back.
+        let sub_graph = graph.take_reserve_sub_graph(parent);
+        assert!(!graph.is_locked(parent));
+        assert!(!graph.is_locked(child));
+        graph.put_sub_graph_back(sub_graph);
+        assert!(graph.is_locked(parent));
+        assert!(graph.is_locked(child));
+        assert_eq!(graph[container].children(), &[parent]);
+
+        // Forgotten nodes must not leave stale locks.
+        let sub_graph = graph.take_reserve_sub_graph(parent);
+        graph.forget_sub_graph(sub_graph);
+        assert!(graph.locked.is_empty());
+
+        let scene = graph.take_sub_graph_into_scene(container).unwrap();
+        assert_eq!(scene.graph.node_count(), 2);
+    }
+
+    #[test]
+    fn test_node_groups() {
+        let mut graph = Graph::new();
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let parent =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[child])).build(&mut graph);
+
+        assert!(graph.add_to_group(parent, "Enemies"));
+        assert!(graph.add_to_group(child, "Enemies"));
+        assert!(graph.add_to_group(child, "Pickups"));
+        assert!(!graph.add_to_group(child, "Pickups"));
+        assert!(!graph.add_to_group(Handle::NONE, "Pickups"));
+        assert_eq!(graph.groups().members("Enemies").count(), 2);
+        assert_eq!(graph.groups().groups_of(child).count(), 2);
+
+        let sub_graph = graph.take_reserve_sub_graph(child);
+        assert!(!graph.groups().contains("Pickups", child));
+        graph.put_sub_graph_back(sub_graph);
+        assert!(graph.groups().contains("Pickups", child));
+
+        graph.remove_node(parent);
+        assert_eq!(graph.groups().members("Enemies").count(), 0);
+        assert!(!graph.groups().contains("Pickups", child));
+        assert!(graph.groups().has_group("Pickups"));
+    }
+
+    #[test]
+    fn test_global_transform_setters() {
+        let mut graph = Graph::new();
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let parent = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[child])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(1.0, 2.0, 3.0))
+                        .with_local_rotation(UnitQuaternion::from_axis_angle(
+                            &Vector3::y_axis(),
+                            std::f32::consts::FRAC_PI_2,
+                        ))
+                        .with_local_scale(Vector3::new(2.0, 2.0, 2.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+
+        let position = Vector3::new(-4.0, 5.0, 6.0);
+        let rotation = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 0.5);
+        graph.set_global_position(child, position);
+        graph.set_global_rotation(child, rotation);
+        graph.update_hierarchical_data();
+        assert!((graph[child].global_position() - position).norm() < 1.0e-5);
+        assert!(graph.global_rotation(child).angle_to(&rotation) < 1.0e-3);
+
+        let transform =
+            Matrix4::new_translation(&Vector3::new(1.0, 1.0, 1.0)) * Matrix4::new_scaling(3.0);
+        graph.set_global_transform(child, transform);
+        graph.update_hierarchical_data();
+        assert!((graph[child].global_transform() - transform).norm() < 1.0e-4);
+        assert_eq!(graph[parent].children(), &[child]);
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let mut graph = Graph::new();
+        let c = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let b = PivotBuilder::new(BaseBuilder::new().with_children(&[c])).build(&mut graph);
+        let a = PivotBuilder::new(BaseBuilder::new().with_children(&[b])).build(&mut graph);
+        let d = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let root = graph.get_root();
+
+        assert_eq!(graph.depth_of(root), Some(0));
+        assert_eq!(graph.depth_of(a), Some(1));
+        assert_eq!(graph.depth_of(c), Some(3));
+        assert_eq!(graph.depth_of(Handle::NONE), None);
+
+        let sorted = graph.topological_sort();
+        assert_eq!(sorted.len(), 5);
+        let position = |h| sorted.iter().position(|s| *s == h).unwrap();
+        assert_eq!(position(root), 0);
+        assert!(position(a) < position(b));
+        assert!(position(b) < position(c));
+        assert!(position(d) < position(b));
+    }
+
     #[test]
     fn test_change_root() {
         let mut graph = Graph::new();
@@ -1918,4 +4080,503 @@ mod test {
 
         assert!(graph[b].children.is_empty());
     }
+
+    #[test]
+    fn test_dirty_nodes_iter() {
+        let mut graph = Graph::new();
+
+        let b;
+        let a = PivotBuilder::new(BaseBuilder::new().with_children(&[{
+            b = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+            b
+        }]))
+        .build(&mut graph);
+        let c = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        graph.update(Default::default(), 1.0 / 60.0, Default::default());
+        graph.update(Default::default(), 1.0 / 60.0, Default::default());
+        assert_eq!(graph.dirty_nodes_iter().count(), 0);
+
+        // Moving a parent must mark its descendants as well.
+        graph[a]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 0.0, 0.0));
+        graph.update(Default::default(), 1.0 / 60.0, Default::default());
+
+        let dirty = graph.dirty_nodes_iter().collect::<Vec<_>>();
+        assert!(dirty.contains(&a));
+        assert!(dirty.contains(&b));
+        assert!(!dirty.contains(&c));
+
+        graph.update(Default::default(), 1.0 / 60.0, Default::default());
+        assert_eq!(graph.dirty_nodes_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut graph = Graph::new();
+
+        let a = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let b = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        graph[a]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 2.0, 3.0));
+
+        let snapshot = graph.capture_snapshot(SnapshotFlags::TRANSFORM, &mut |h, _| h == a);
+
+        graph[a]
+            .local_transform_mut()
+            .set_position(Vector3::new(4.0, 5.0, 6.0));
+        graph[b]
+            .local_transform_mut()
+            .set_position(Vector3::new(7.0, 8.0, 9.0));
+
+        graph.restore_snapshot(&snapshot);
+
+        assert_eq!(
+            **graph[a].local_transform().position(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+        // The node was not captured, so its state must remain the same.
+        assert_eq!(
+            **graph[b].local_transform().position(),
+            Vector3::new(7.0, 8.0, 9.0)
+        );
+    }
+
+    #[test]
+    fn test_graph_with_capacity() {
+        let mut graph = Graph::with_capacity(32);
+        assert!(graph.allocated_capacity() >= 32);
+        assert_eq!(graph.node_count(), 1);
+
+        graph.reserve(64);
+        assert!(graph.allocated_capacity() >= 65);
+        let handles = (0..64)
+            .map(|_| PivotBuilder::new(BaseBuilder::new()).build(&mut graph))
+            .collect::<Vec<_>>();
+        for (i, handle) in handles.iter().enumerate() {
+            assert_eq!(handle.index(), i as u32 + 1);
+        }
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut graph = Graph::new();
+        let garbage = (0..8)
+            .map(|_| PivotBuilder::new(BaseBuilder::new()).build(&mut graph))
+            .collect::<Vec<_>>();
+        let body = RigidBodyBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        graph.link_nodes(child, body);
+        graph.add_to_group(child, "Group");
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+        for handle in garbage {
+            graph.remove_node(handle);
+        }
+
+        let occupancy = graph.pool_occupancy();
+        assert_eq!(occupancy.records, 11);
+        assert_eq!(occupancy.alive, 3);
+        assert_eq!(occupancy.free, 8);
+        assert_eq!(occupancy.reserved, 0);
+        assert!(occupancy.fragmentation() > 0.7);
+
+        let map = graph.compact().unwrap();
+        assert_eq!(graph.pool_occupancy().records, 3);
+        assert_eq!(graph.pool_occupancy().fragmentation(), 0.0);
+
+        let new_body = map.inner()[&body];
+        let new_child = map.inner()[&child];
+        assert_eq!(graph[new_body].parent(), graph.get_root());
+        assert_eq!(graph[new_body].children(), &[new_child]);
+        assert_eq!(graph[new_child].parent(), new_body);
+        assert!(graph.groups().contains("Group", new_child));
+        let native = graph[new_body].cast::<RigidBody>().unwrap().native.get();
+        assert!(graph.physics.native_body(native).is_some());
+
+        let (ticket, node) = graph.take_reserve(new_child);
+        assert_eq!(graph.compact().err(), Some(GraphError::HasReservedNodes(1)));
+        graph.put_back(ticket, node);
+    }
+
+    #[test]
+    fn test_sweep_orphans() {
+        let mut graph = Graph::new();
+        let a = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let b = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let c = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        graph.link_nodes(c, b);
+
+        // Break the hierarchy.
+        let root = graph.get_root();
+        graph.pool[root].children.retain(|h| *h != a);
+        graph.pool[b].parent = Handle::new(100, 1);
+        graph.pool[root].children.retain(|h| *h != b);
+        graph.pool[c].children.push(Handle::new(100, 1));
+
+        let report = graph.sweep_orphans(OrphanAction::Report, 2);
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.orphans, vec![a]);
+        assert!(!report.pass_finished);
+
+        let report = graph.sweep_orphans(OrphanAction::Report, 100);
+        assert_eq!(report.orphans, vec![b]);
+        assert_eq!(report.dangling_children, 1);
+        assert!(report.pass_finished);
+
+        let report = graph.sweep_orphans(OrphanAction::LinkToRoot, 2);
+        assert_eq!(report.orphans, vec![a]);
+        assert_eq!(graph[a].parent(), root);
+
+        let report = graph.sweep_orphans(OrphanAction::Remove, 100);
+        assert_eq!(report.orphans, vec![b]);
+        assert!(!graph.is_valid_handle(b));
+        assert!(!graph.is_valid_handle(c));
+
+        assert!(graph.sweep_orphans(OrphanAction::Report, 100).is_clean());
+    }
+
+    #[test]
+    fn test_ignore_parent_visibility() {
+        let mut graph = Graph::new();
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let overridden = PivotBuilder::new(BaseBuilder::new().with_ignore_parent_visibility(true))
+            .build(&mut graph);
+        let grandchild = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let parent = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_visibility(false)
+                .with_children(&[child, overridden]),
+        )
+        .build(&mut graph);
+        graph.link_nodes(grandchild, overridden);
+
+        graph.update_hierarchical_data();
+        assert!(!graph[parent].global_visibility());
+        assert!(!graph[child].global_visibility());
+        assert!(graph[overridden].global_visibility());
+        assert!(graph[grandchild].global_visibility());
+
+        graph[overridden].set_visibility(false);
+        graph.update_hierarchical_data();
+        assert!(!graph[overridden].global_visibility());
+        assert!(!graph[grandchild].global_visibility());
+    }
+
+    #[test]
+    fn test_render_snapshot() {
+        fn assert_send<T: Send>() {}
+        assert_send::<RenderSnapshot>();
+
+        let mut graph = Graph::new();
+        let data = SurfaceSharedData::new(SurfaceData::make_cube(Matrix4::identity()));
+        let visible = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(data.clone()).build()])
+            .build(&mut graph);
+        MeshBuilder::new(BaseBuilder::new().with_visibility(false))
+            .with_surfaces(vec![SurfaceBuilder::new(data).build()])
+            .build(&mut graph);
+        graph.update_hierarchical_data();
+
+        let exchange = SnapshotExchange::default();
+        assert!(exchange.take_latest().is_none());
+        exchange.publish(&graph);
+        exchange.publish(&graph);
+
+        let snapshot = exchange.take_latest().unwrap();
+        assert_eq!(snapshot.frame, 2);
+        assert_eq!(snapshot.instances.len(), 1);
+        assert_eq!(snapshot.instances[0].handle, visible);
+        assert_eq!(snapshot.instances[0].surfaces.len(), 1);
+        assert!(exchange.take_latest().is_none());
+        exchange.recycle(snapshot);
+    }
+
+    #[test]
+    fn test_node_type_statistics() {
+        let mut graph = Graph::new();
+        for _ in 0..3 {
+            PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        }
+
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+        assert!(graph.performance_statistics().node_types.is_empty());
+
+        graph.update(
+            Vector2::new(100.0, 100.0),
+            1.0 / 60.0,
+            GraphUpdateSwitches {
+                node_type_statistics: true,
+                ..Default::default()
+            },
+        );
+        let statistics = graph.performance_statistics();
+        let pivots = statistics
+            .node_types
+            .iter()
+            .find(|(name, _)| name.ends_with("Pivot"))
+            .unwrap();
+        // Three pivots and the root.
+        assert_eq!(pivots.1.count, 4);
+        assert!(statistics.total() >= statistics.nodes_update_time);
+    }
+
+    #[test]
+    fn test_memory_usage() {
+        let mut graph = Graph::new();
+        let empty = graph.memory_usage();
+
+        let data = SurfaceSharedData::new(SurfaceData::make_cube(Matrix4::identity()));
+        for _ in 0..2 {
+            MeshBuilder::new(BaseBuilder::new())
+                .with_surfaces(vec![SurfaceBuilder::new(data.clone()).build()])
+                .build(&mut graph);
+        }
+
+        let usage = graph.memory_usage();
+        assert_eq!(usage.mesh_buffer_count, 1);
+        assert!(usage.mesh_buffer_bytes > 0);
+        assert!(usage.node_bytes > empty.node_bytes);
+        assert!(usage.total_bytes() > empty.total_bytes());
+    }
+
+    #[test]
+    fn test_update_systems() {
+        let mut graph = Graph::new();
+        let node = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        // Moved node must be already at its new position when physics entities are synchronized.
+        graph.add_update_system(UpdateStage::PreUpdate, move |graph, _| {
+            graph[node]
+                .local_transform_mut()
+                .set_position(Vector3::new(1.0, 2.0, 3.0));
+        });
+        let checker = graph.add_update_system(UpdateStage::PostHierarchy, move |graph, ctx| {
+            assert_eq!(ctx.dt, 0.5);
+            assert_eq!(graph[node].global_position(), Vector3::new(1.0, 2.0, 3.0));
+        });
+        // Self-removing system.
+        let id = Arc::new(Mutex::new(None));
+        let id_clone = id.clone();
+        let self_removing = graph.add_update_system(UpdateStage::PostUpdate, move |graph, _| {
+            graph.remove_update_system(id_clone.lock().unwrap());
+        });
+        *id.lock() = Some(self_removing);
+        assert_eq!(graph.update_system_count(), 3);
+
+        graph.update(Vector2::new(100.0, 100.0), 0.5, Default::default());
+        assert_eq!(graph.update_system_count(), 2);
+
+        graph.remove_update_system(checker);
+        assert_eq!(graph.update_system_count(), 1);
+    }
+
+    #[test]
+    fn test_remove_sub_graph() {
+        let mut graph = Graph::new();
+
+        let child = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[child]))
+            .with_lin_vel(Vector3::new(1.0, 2.0, 3.0))
+            .build(&mut graph);
+
+        graph.update_hierarchical_data();
+        graph.sync_native(&Default::default());
+
+        let sub_graph = graph.remove_sub_graph(body);
+        assert!(!graph.is_valid_handle(body));
+        assert!(!graph.is_valid_handle(child));
+        assert!(graph
+            .physics
+            .native_body(sub_graph.root.1.cast::<RigidBody>().unwrap().native.get())
+            .is_none());
+
+        let root = graph.put_sub_graph_back(sub_graph);
+        assert_eq!(root, body);
+        assert_eq!(graph[body].children(), &[child]);
+
+        let native = graph
+            .physics
+            .native_body(graph[body].cast::<RigidBody>().unwrap().native.get())
+            .unwrap();
+        assert_eq!(*native.linvel(), Vector3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn test_clone_with_physics_state() {
+        let mut graph = Graph::new();
+        let falling = RigidBodyBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let sleeping = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_lin_vel(Vector3::new(1.0, 0.0, 0.0))
+            .build(&mut graph);
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+        }
+        graph[sleeping].as_rigid_body_mut().sleep();
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+
+        let native_body = |graph: &Graph, handle: Handle<Node>| {
+            graph
+                .physics
+                .native_body(graph[handle].cast::<RigidBody>().unwrap().native.get())
+                .cloned()
+                .unwrap()
+        };
+
+        let (mut copy, map) = graph.clone_with_physics_state(graph.get_root(), &mut |_, _| true);
+        let copy_falling = map.inner()[&falling];
+        let copy_sleeping = map.inner()[&sleeping];
+
+        // Native entities must exist right away and have the same state as the original ones.
+        let original = native_body(&graph, falling);
+        let cloned = native_body(&copy, copy_falling);
+        assert_ne!(*original.linvel(), Vector3::default());
+        assert_eq!(original.linvel(), cloned.linvel());
+        assert!(native_body(&copy, copy_sleeping).is_sleeping());
+
+        // Both graphs must continue the simulation identically.
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+        copy.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+        assert_eq!(
+            graph[falling].global_position(),
+            copy[copy_falling].global_position()
+        );
+        assert!(copy[copy_sleeping].as_rigid_body().is_sleeping());
+    }
+
+    #[test]
+    fn test_take_sub_graph_into_scene() {
+        use crate::scene::{
+            joint::{Joint, JointBuilder},
+            rigidbody::RigidBodyType,
+        };
+
+        let mut graph = Graph::new();
+
+        let frame = RigidBodyBuilder::new(BaseBuilder::new().with_name("Frame"))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_name("Body")).build(&mut graph);
+        let joint = JointBuilder::new(BaseBuilder::new().with_name("Joint"))
+            .with_body1(frame)
+            .with_body2(body)
+            .build(&mut graph);
+        let container = PivotBuilder::new(BaseBuilder::new().with_children(&[frame, body, joint]))
+            .build(&mut graph);
+        let outside = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        graph.add_to_group(body, "Movable");
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+        }
+        let velocity = *graph
+            .physics
+            .native_body(graph[body].cast::<RigidBody>().unwrap().native.get())
+            .unwrap()
+            .linvel();
+        assert_ne!(velocity, Vector3::default());
+
+        let scene = graph.take_sub_graph_into_scene(container).unwrap();
+
+        for handle in [frame, body, joint, container] {
+            assert!(!graph.is_valid_handle(handle));
+        }
+        assert!(graph.is_valid_handle(outside));
+        assert_eq!(graph.groups().members("Movable").count(), 0);
+
+        let dest = &scene.graph;
+        let new_container = dest[dest.get_root()].children()[0];
+        let find = |name: &str| dest.find_by_name_from_root(name).unwrap().0;
+        let (new_frame, new_body, new_joint) = (find("Frame"), find("Body"), find("Joint"));
+        assert_eq!(
+            dest[new_container].children(),
+            &[new_frame, new_body, new_joint]
+        );
+        assert!(dest.groups().contains("Movable", new_body));
+
+        // Cross-references must be remapped to the new handles.
+        let new_joint_ref = dest[new_joint].cast::<Joint>().unwrap();
+        assert_eq!(new_joint_ref.body1(), new_frame);
+        assert_eq!(new_joint_ref.body2(), new_body);
+        assert!(dest
+            .physics
+            .native_joint(new_joint_ref.native.get())
+            .is_some());
+
+        // Runtime state of the native rigid body must be preserved.
+        let native = dest
+            .physics
+            .native_body(dest[new_body].cast::<RigidBody>().unwrap().native.get())
+            .unwrap();
+        assert_eq!(*native.linvel(), velocity);
+    }
+
+    #[test]
+    fn test_instance_patch_mode_save_load() {
+        use crate::{
+            asset::manager::ResourceManager,
+            resource::model::{Model, ModelResource, ModelResourceExtension},
+            scene::Scene,
+        };
+
+        let mut model = Model::default();
+        model.path = "prefab.rgs".into();
+        {
+            let prefab = &mut model.get_scene_mut().graph;
+            let a = PivotBuilder::new(BaseBuilder::new().with_name("A")).build(prefab);
+            let b = PivotBuilder::new(BaseBuilder::new().with_name("B")).build(prefab);
+            PivotBuilder::new(BaseBuilder::new().with_name("Pivot").with_children(&[a, b]))
+                .build(prefab);
+        }
+        let resource = ModelResource::new_ok(model);
+
+        let mut scene = Scene::new();
+        resource.instantiate(&mut scene);
+        let graph = &mut scene.graph;
+        let a = graph.find_by_name_from_root("A").unwrap().0;
+        let b = graph.find_by_name_from_root("B").unwrap().0;
+        graph[b]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 2.0, 3.0));
+        graph.set_instance_patch_mode(true);
+
+        let mut visitor = Visitor::new();
+        graph.visit("Graph", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        // Omitted nodes must be put back after saving.
+        assert_eq!(graph.find_by_name_from_root("A").unwrap().0, a);
+
+        let resource_manager = ResourceManager::new();
+        resource_manager
+            .state()
+            .built_in_resources
+            .insert("prefab.rgs".into(), resource.into_untyped());
+
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        visitor
+            .blackboard
+            .register(Arc::new(SerializationContext::new()));
+        visitor.blackboard.register(Arc::new(resource_manager));
+        let mut loaded = Graph::default();
+        loaded.visit("Graph", &mut visitor).unwrap();
+        assert!(loaded.is_instance_patch_mode());
+
+        // Unmodified instance node was not saved, the modified one was.
+        assert!(loaded.find_by_name_from_root("A").is_none());
+        let b = loaded.find_by_name_from_root("B").unwrap().0;
+
+        loaded.resolve();
+
+        // The omitted node must be restored from the resource at its original place.
+        let a = loaded.find_by_name_from_root("A").unwrap().0;
+        assert_eq!(loaded[a].parent(), loaded[b].parent());
+        assert_eq!(
+            **loaded[b].local_transform().position(),
+            Vector3::new(1.0, 2.0, 3.0)
+        );
+    }
 }
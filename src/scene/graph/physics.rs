@@ -7,11 +7,12 @@ use crate::{
             Vector2, Vector3,
         },
         arrayvec::ArrayVec,
-        instant,
+        hash_combine, instant,
         log::{Log, MessageKind},
-        math::Matrix4Ext,
+        math::aabb::AxisAlignedBoundingBox,
+        math::{get_barycentric_coords, Matrix4Ext},
         parking_lot::Mutex,
-        pool::Handle,
+        pool::{Handle, Pool},
         reflect::prelude::*,
         variable::VariableFlags,
         visitor::prelude::*,
@@ -19,31 +20,47 @@ use crate::{
     },
     scene::{
         self,
-        collider::{self, ColliderShape, GeometrySource},
+        collider::{self, ColliderShape},
         debug::SceneDrawingContext,
-        graph::{isometric_global_transform, NodePool},
-        joint::JointParams,
+        graph::{global_scale, isometric_global_transform, NodePool},
+        joint::{JointAxisMotion, JointAxisParams, JointParams, SpringJoint},
         mesh::{
             buffer::{VertexAttributeUsage, VertexReadTrait},
             Mesh,
         },
         node::{Node, NodeTrait},
-        rigidbody::ApplyAction,
+        rigidbody::{ApplyAction, RigidBodyChanges, RigidBodyMassProperties},
         terrain::Terrain,
+        transform::Transform,
+        vehicle::{Vehicle, Wheel},
+        water_volume::WaterVolume,
     },
     utils::raw_mesh::{RawMeshBuilder, RawVertex},
 };
+use bitflags::bitflags;
+use fxhash::{FxHashMap, FxHashSet};
 use rapier3d::{
+    control::{DynamicRayCastVehicleController, WheelTuning},
     dynamics::{
-        CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJointHandle, ImpulseJointSet,
-        IslandManager, JointAxesMask, MultibodyJointHandle, MultibodyJointSet, RigidBody,
-        RigidBodyActivation, RigidBodyBuilder, RigidBodyHandle, RigidBodySet, RigidBodyType,
+        CCDSolver, GenericJoint, GenericJointBuilder, ImpulseJoint, ImpulseJointHandle,
+        ImpulseJointSet, IslandManager, JointAxesMask, MassProperties, MultibodyJointHandle,
+        MultibodyJointSet, RigidBody, RigidBodyBuilder, RigidBodyHandle, RigidBodySet,
+        RigidBodyType,
     },
     geometry::{
-        BroadPhase, Collider, ColliderBuilder, ColliderHandle, ColliderSet, Cuboid,
-        InteractionGroups, NarrowPhase, Ray, SharedShape,
+        Aabb, Ball, BroadPhase, Collider, ColliderBuilder, ColliderHandle, ColliderSet,
+        CollisionEvent, Cuboid, HeightField, InteractionGroups, NarrowPhase, Ray, SharedShape,
+        SolverFlags,
+    },
+    parry::{
+        query::{self, PointQuery, TOIStatus},
+        shape::Shape,
+    },
+    pipeline::{
+        ActiveEvents, ActiveHooks, ContactModificationContext, DebugRenderMode,
+        DebugRenderPipeline, DebugRenderStyle, EventHandler, PairFilterContext, PhysicsPipeline,
+        QueryFilter, QueryPipeline,
     },
-    pipeline::{DebugRenderPipeline, EventHandler, PhysicsPipeline, QueryFilter, QueryPipeline},
     prelude::JointAxis,
 };
 use std::{
@@ -51,7 +68,7 @@ use std::{
     cmp::Ordering,
     fmt::{Debug, Formatter},
     hash::Hash,
-    sync::Arc,
+    sync::{mpsc::Sender, Arc, Weak},
     time::Duration,
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
@@ -163,7 +180,7 @@ pub struct PhysicsPerformanceStatistics {
     /// A time that was needed to perform a single simulation step.
     pub step_time: Duration,
 
-    /// A time that was needed to perform all ray casts.
+    /// A time that was needed to perform all ray and shape casts.
     pub total_ray_cast_time: Cell<Duration>,
 }
 
@@ -179,6 +196,106 @@ impl PhysicsPerformanceStatistics {
     }
 }
 
+/// Defines how strength of a radial impulse decreases with the distance from its center. See
+/// [`super::Graph::apply_radial_impulse`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ImpulseFalloff {
+    /// The impulse has the same strength in the whole radius.
+    Constant,
+    /// The strength decreases linearly from the center to the edge of the radius.
+    Linear,
+    /// The strength decreases quadratically from the center to the edge of the radius. Feels more
+    /// natural for explosions, because the strength drops faster near the center.
+    Quadratic,
+}
+
+impl ImpulseFalloff {
+    /// Returns strength factor in `[0; 1]` range at the given distance, that is normalized by the
+    /// radius of an impulse (`0.0` - center, `1.0` - edge).
+    pub fn factor(self, normalized_distance: f32) -> f32 {
+        let k = 1.0 - normalized_distance.clamp(0.0, 1.0);
+        match self {
+            ImpulseFalloff::Constant => 1.0,
+            ImpulseFalloff::Linear => k,
+            ImpulseFalloff::Quadratic => k * k,
+        }
+    }
+}
+
+/// A set of flags that defines which parts of the physics world are drawn by [`PhysicsWorld::draw`].
+/// Colliders are color-coded by the type of their rigid bodies: fixed - gray, dynamic - green,
+/// kinematic - orange, colliders without a rigid body - magenta.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct PhysicsDebugDrawFlags(u32);
+
+bitflags! {
+    impl PhysicsDebugDrawFlags: u32 {
+        /// Nothing.
+        const NONE = 0;
+        /// Local coordinate axes of rigid bodies.
+        const BODIES = 0b0000_0001;
+        /// Shapes of colliders.
+        const COLLIDERS = 0b0000_0010;
+        /// Anchors of joints and lines between them.
+        const JOINTS = 0b0000_0100;
+        /// Contact points (as lines between the points on both colliders) and contact normals.
+        const CONTACTS = 0b0000_1000;
+        /// Axis-aligned bounding boxes of colliders.
+        const AABBS = 0b0001_0000;
+        /// Colliders and joints of sleeping rigid bodies are drawn darker than the active ones.
+        const SLEEPING = 0b0010_0000;
+        /// Everything.
+        const ALL = Self::BODIES.bits()
+            | Self::COLLIDERS.bits()
+            | Self::JOINTS.bits()
+            | Self::CONTACTS.bits()
+            | Self::AABBS.bits()
+            | Self::SLEEPING.bits();
+    }
+}
+
+impl Default for PhysicsDebugDrawFlags {
+    fn default() -> Self {
+        Self::BODIES | Self::COLLIDERS | Self::JOINTS | Self::SLEEPING
+    }
+}
+
+impl PhysicsDebugDrawFlags {
+    fn render_mode(self) -> DebugRenderMode {
+        let mut mode = DebugRenderMode::empty();
+        mode.set(
+            DebugRenderMode::RIGID_BODY_AXES,
+            self.contains(Self::BODIES),
+        );
+        mode.set(
+            DebugRenderMode::COLLIDER_SHAPES,
+            self.contains(Self::COLLIDERS),
+        );
+        mode.set(DebugRenderMode::JOINTS, self.contains(Self::JOINTS));
+        mode.set(DebugRenderMode::CONTACTS, self.contains(Self::CONTACTS));
+        mode.set(DebugRenderMode::COLLIDER_AABBS, self.contains(Self::AABBS));
+        mode
+    }
+}
+
+// Colors of the debug drawing in HSLA format.
+fn debug_render_style() -> DebugRenderStyle {
+    DebugRenderStyle {
+        collider_dynamic_color: [120.0, 0.8, 0.5, 1.0],
+        collider_kinematic_color: [30.0, 1.0, 0.5, 1.0],
+        collider_fixed_color: [210.0, 0.1, 0.6, 1.0],
+        collider_parentless_color: [300.0, 0.8, 0.5, 1.0],
+        contact_depth_color: [0.0, 1.0, 0.5, 1.0],
+        contact_normal_color: [60.0, 1.0, 0.5, 1.0],
+        collider_aabb_color: [180.0, 0.8, 0.5, 1.0],
+        ..Default::default()
+    }
+}
+
+// Sleeping entities are drawn with lower saturation and lightness.
+const SLEEP_COLOR_MULTIPLIER: [f32; 4] = [1.0, 0.5, 0.5, 1.0];
+
 /// A ray intersection result.
 #[derive(Debug, Clone)]
 pub struct Intersection {
@@ -204,10 +321,46 @@ pub struct Intersection {
 
     /// Distance from the ray origin.
     pub toi: f32,
+
+    /// A triangle with which intersection was detected. It is set only for ray casts against triangle
+    /// mesh colliders. See [`crate::scene::graph::Graph::mesh_hit`] to find the triangle on a surface of
+    /// a source mesh and texture coordinates at the intersection position.
+    pub triangle: Option<TriangleHit>,
+}
+
+/// A triangle of a triangle mesh collider, that was hit by a ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangleHit {
+    /// Index of the triangle in the native triangle mesh. Unlike [`FeatureId::Face`], it is always
+    /// less than amount of triangles of the mesh regardless of the side from which the triangle was hit.
+    pub index: u32,
+
+    /// Barycentric coordinates of the intersection position in the triangle.
+    pub barycentric: Vector3<f32>,
+}
+
+/// A point on a surface of a mesh, that was hit by a ray. See [`crate::scene::graph::Graph::mesh_hit`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshHit {
+    /// A handle of the mesh node.
+    pub mesh: Handle<Node>,
+
+    /// Index of the surface of the mesh.
+    pub surface: usize,
+
+    /// Index of the triangle in the geometry buffer of the surface.
+    pub triangle: usize,
+
+    /// Barycentric coordinates of the intersection position in the triangle.
+    pub barycentric: Vector3<f32>,
+
+    /// Texture coordinates (first channel) at the intersection position. `None` if the surface does
+    /// not have texture coordinates.
+    pub uv: Option<Vector2<f32>>,
 }
 
 /// A set of options for the ray cast.
-pub struct RayCastOptions {
+pub struct RayCastOptions<'a> {
     /// A ray origin.
     pub ray_origin: Point3<f32>,
 
@@ -222,6 +375,36 @@ pub struct RayCastOptions {
 
     /// Whether to sort intersections from closest to farthest.
     pub sort_results: bool,
+
+    /// An optional filter, that is called for every collider node hit by the ray. If it returns `false`,
+    /// the collider is excluded from the results. It could be used, for example, to exclude colliders of
+    /// the shooter itself.
+    pub filter: Option<&'a dyn Fn(Handle<Node>) -> bool>,
+}
+
+/// A set of options for the shape cast.
+pub struct ShapeCastOptions {
+    /// Maximum time of impact. The shape travels `velocity * max_toi` at most, so with a normalized velocity
+    /// this is the maximum distance of cast.
+    pub max_toi: f32,
+
+    /// Groups to check.
+    pub groups: collider::InteractionGroups,
+
+    /// If `true`, colliders that are already intersecting the shape at its initial position will be reported
+    /// with zero time of impact. If `false`, such colliders will be reported only if the shape moves towards
+    /// them (deeper penetration).
+    pub stop_at_penetration: bool,
+}
+
+impl Default for ShapeCastOptions {
+    fn default() -> Self {
+        Self {
+            max_toi: f32::MAX,
+            groups: Default::default(),
+            stop_at_penetration: true,
+        }
+    }
 }
 
 /// A trait for ray cast results storage. It has two implementations: Vec and ArrayVec.
@@ -274,6 +457,371 @@ impl<const CAP: usize> QueryResultsStorage for ArrayVec<Intersection, CAP> {
     }
 }
 
+/// Defines whether a pair of colliders started or stopped touching each other.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CollisionEventKind {
+    /// Colliders started touching each other.
+    Started,
+    /// Colliders stopped touching each other.
+    Stopped,
+}
+
+/// An event that is produced when two non-sensor colliders start or stop touching each other. See
+/// [`PhysicsWorld::contact_events`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ContactEvent {
+    /// A handle of the first collider.
+    pub collider1: Handle<Node>,
+    /// A handle of the second collider.
+    pub collider2: Handle<Node>,
+    /// Kind of the event.
+    pub kind: CollisionEventKind,
+    /// `true` if the event was produced, because one of the colliders was removed (or disabled). The
+    /// handle of such collider could be invalid.
+    pub removed: bool,
+}
+
+/// An event that is produced when a sensor collider starts or stops intersecting with some other
+/// collider. See [`PhysicsWorld::intersection_events`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IntersectionEvent {
+    /// A handle of the first collider.
+    pub collider1: Handle<Node>,
+    /// A handle of the second collider.
+    pub collider2: Handle<Node>,
+    /// Kind of the event.
+    pub kind: CollisionEventKind,
+    /// `true` if the event was produced, because one of the colliders was removed (or disabled). The
+    /// handle of such collider could be invalid.
+    pub removed: bool,
+}
+
+/// An event that is produced when a joint breaks, because its break force or torque was exceeded. See
+/// [`PhysicsWorld::joint_break_events`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JointBreakEvent {
+    /// A handle of the joint node.
+    pub joint: Handle<Node>,
+    /// A force (in Newtons) that was applied by the joint at the moment of breaking.
+    pub force: f32,
+    /// A torque (in Newton-meters) that was applied by the joint at the moment of breaking.
+    pub torque: f32,
+}
+
+/// A pair of colliders, that is passed to [`PhysicsHooks`] methods.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ColliderPair {
+    /// A handle of the first collider node.
+    pub collider1: Handle<Node>,
+    /// A handle of the second collider node.
+    pub collider2: Handle<Node>,
+    /// A handle of the rigid body node of the first collider.
+    pub rigid_body1: Handle<Node>,
+    /// A handle of the rigid body node of the second collider.
+    pub rigid_body2: Handle<Node>,
+}
+
+impl ColliderPair {
+    fn from_native(
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        collider1: ColliderHandle,
+        collider2: ColliderHandle,
+    ) -> Self {
+        let collider_node = |handle: ColliderHandle| {
+            colliders
+                .get(handle)
+                .map(|c| Handle::decode_from_u128(c.user_data))
+                .unwrap_or_default()
+        };
+        let body_node = |handle: ColliderHandle| {
+            colliders
+                .get(handle)
+                .and_then(|c| c.parent())
+                .and_then(|b| bodies.get(b))
+                .map(|b| Handle::decode_from_u128(b.user_data))
+                .unwrap_or_default()
+        };
+        Self {
+            collider1: collider_node(collider1),
+            collider2: collider_node(collider2),
+            rigid_body1: body_node(collider1),
+            rigid_body2: body_node(collider2),
+        }
+    }
+
+    /// Returns `true` if the given collider is one of the colliders of the pair.
+    pub fn contains(&self, collider: Handle<Node>) -> bool {
+        self.collider1 == collider || self.collider2 == collider
+    }
+}
+
+/// Contacts between a pair of colliders, that could be modified before they're used by the solver.
+/// See [`PhysicsHooks::modify_contacts`].
+pub struct ContactModification<'a, 'b> {
+    /// The pair of colliders.
+    pub pair: ColliderPair,
+    native: &'a mut ContactModificationContext<'b>,
+}
+
+impl<'a, 'b> ContactModification<'a, 'b> {
+    /// Returns contact normal in world coordinates, it points from the first collider to the second.
+    pub fn normal(&self) -> Vector3<f32> {
+        *self.native.normal
+    }
+
+    /// Returns amount of contact points that will be used by the solver.
+    pub fn contact_count(&self) -> usize {
+        self.native.solver_contacts.len()
+    }
+
+    /// Removes every contact point, so the colliders pass through each other at this simulation step.
+    pub fn clear_contacts(&mut self) {
+        self.native.solver_contacts.clear();
+    }
+
+    /// Sets friction coefficient of every contact point.
+    pub fn set_friction(&mut self, friction: f32) {
+        for contact in self.native.solver_contacts.iter_mut() {
+            contact.friction = friction;
+        }
+    }
+
+    /// Sets restitution coefficient of every contact point.
+    pub fn set_restitution(&mut self, restitution: f32) {
+        for contact in self.native.solver_contacts.iter_mut() {
+            contact.restitution = restitution;
+        }
+    }
+
+    /// Sets velocity (in world coordinates) of the surface at every contact point. The solver will
+    /// try to reach this relative velocity between the colliders using friction, it could be used to
+    /// make conveyor belts.
+    pub fn set_tangent_velocity(&mut self, velocity: Vector3<f32>) {
+        for contact in self.native.solver_contacts.iter_mut() {
+            contact.tangent_velocity = velocity;
+        }
+    }
+
+    /// Returns custom data of the contact pair. It is preserved across simulation steps while the
+    /// colliders are in contact, and could be used to store some state of the pair.
+    pub fn user_data(&self) -> u32 {
+        *self.native.user_data
+    }
+
+    /// Sets custom data of the contact pair. See [`Self::user_data`].
+    pub fn set_user_data(&mut self, data: u32) {
+        *self.native.user_data = data;
+    }
+
+    /// Makes the given collider of the pair a one-way platform. Contacts are kept only if the other
+    /// collider hits the platform from the allowed side - contact normal must be within
+    /// `allowed_angle` (in radians) from `allowed_local_normal` (in local coordinates of the
+    /// platform). Contacts stay disabled until the colliders stop penetrating each other, so bodies
+    /// could pass through the platform from below and land on it. It does nothing if the platform
+    /// is not a part of the pair.
+    pub fn update_as_one_way_platform(
+        &mut self,
+        platform: Handle<Node>,
+        allowed_local_normal: Vector3<f32>,
+        allowed_angle: f32,
+    ) {
+        let allowed_local_n1 = if self.pair.collider1 == platform {
+            allowed_local_normal
+        } else if self.pair.collider2 == platform {
+            -allowed_local_normal
+        } else {
+            return;
+        };
+
+        self.native
+            .update_as_oneway_platform(&allowed_local_n1, allowed_angle);
+    }
+}
+
+/// Physics hooks allow you to filter and modify contacts between colliders using gameplay logic. It
+/// could be used for one-way platforms, conveyor belts, disabling collisions between a character and
+/// its own projectiles, etc. Hooks are called by the physics engine during simulation steps for every
+/// pair of colliders, that potentially interact. See [`PhysicsWorld::set_hooks`].
+///
+/// ## Performance
+///
+/// Hooks are called very often (many times per simulation step), so they should be as fast as
+/// possible.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         graph::physics::{ContactModification, PhysicsHooks},
+///         node::Node,
+///     },
+/// };
+///
+/// struct Hooks {
+///     conveyor: Handle<Node>,
+///     platform: Handle<Node>,
+/// }
+///
+/// impl PhysicsHooks for Hooks {
+///     fn modify_contacts(&self, contacts: &mut ContactModification) {
+///         if contacts.pair.contains(self.conveyor) {
+///             contacts.set_tangent_velocity(Vector3::new(1.0, 0.0, 0.0));
+///         }
+///         contacts.update_as_one_way_platform(self.platform, Vector3::y(), 0.5);
+///     }
+/// }
+/// ```
+pub trait PhysicsHooks: Send + Sync {
+    /// Returns `true` if contacts between the given colliders should be computed and resolved,
+    /// `false` - otherwise. Default implementation returns `true`.
+    fn filter_contact_pair(&self, _pair: &ColliderPair) -> bool {
+        true
+    }
+
+    /// Returns `true` if intersections between the given colliders (at least one of them is a sensor)
+    /// should be computed, `false` - otherwise. Default implementation returns `true`.
+    fn filter_intersection_pair(&self, _pair: &ColliderPair) -> bool {
+        true
+    }
+
+    /// Allows you to modify contacts between a pair of colliders before they're used by the solver.
+    /// Default implementation does nothing.
+    fn modify_contacts(&self, _contacts: &mut ContactModification) {}
+}
+
+// Native colliders call hooks only if they're explicitly enabled for them.
+fn active_hooks(has_hooks: bool) -> ActiveHooks {
+    if has_hooks {
+        ActiveHooks::all()
+    } else {
+        ActiveHooks::empty()
+    }
+}
+
+// Translates native hooks calls into user hooks calls.
+struct HooksAdapter<'a>(&'a dyn PhysicsHooks);
+
+impl<'a> rapier3d::pipeline::PhysicsHooks for HooksAdapter<'a> {
+    fn filter_contact_pair(&self, context: &PairFilterContext) -> Option<SolverFlags> {
+        let pair = ColliderPair::from_native(
+            context.bodies,
+            context.colliders,
+            context.collider1,
+            context.collider2,
+        );
+        if self.0.filter_contact_pair(&pair) {
+            Some(SolverFlags::COMPUTE_IMPULSES)
+        } else {
+            None
+        }
+    }
+
+    fn filter_intersection_pair(&self, context: &PairFilterContext) -> bool {
+        let pair = ColliderPair::from_native(
+            context.bodies,
+            context.colliders,
+            context.collider1,
+            context.collider2,
+        );
+        self.0.filter_intersection_pair(&pair)
+    }
+
+    fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+        let pair = ColliderPair::from_native(
+            context.bodies,
+            context.colliders,
+            context.collider1,
+            context.collider2,
+        );
+        self.0.modify_contacts(&mut ContactModification {
+            pair,
+            native: context,
+        });
+    }
+}
+
+/// An event that is produced when a native physics entity is created or removed for a scene node. It
+/// could be used to attach (and detach) custom per-body data at the right moment. Native handles could
+/// be used to access native entities directly. See [`PhysicsWorld::subscribe_native_events`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NativePhysicsEvent {
+    /// A native rigid body was created for a rigid body node.
+    RigidBodyCreated {
+        /// A handle of the rigid body node.
+        node: Handle<Node>,
+        /// A handle of the native rigid body.
+        native: RigidBodyHandle,
+    },
+    /// A native rigid body was removed. The handle of the node could be invalid at this moment.
+    RigidBodyRemoved {
+        /// A handle of the rigid body node.
+        node: Handle<Node>,
+        /// A handle of the native rigid body.
+        native: RigidBodyHandle,
+    },
+    /// A native collider was created for a collider node.
+    ColliderCreated {
+        /// A handle of the collider node.
+        node: Handle<Node>,
+        /// A handle of the native collider.
+        native: ColliderHandle,
+    },
+    /// A native collider was removed (explicitly or together with its rigid body). The handle of the
+    /// node could be invalid at this moment.
+    ColliderRemoved {
+        /// A handle of the collider node.
+        node: Handle<Node>,
+        /// A handle of the native collider.
+        native: ColliderHandle,
+    },
+    /// A native joint was created for a joint node.
+    JointCreated {
+        /// A handle of the joint node.
+        node: Handle<Node>,
+        /// A handle of the native joint.
+        native: ImpulseJointHandle,
+    },
+    /// A native joint was removed (explicitly or together with one of its rigid bodies). The handle
+    /// of the node could be invalid at this moment.
+    JointRemoved {
+        /// A handle of the joint node.
+        node: Handle<Node>,
+        /// A handle of the native joint.
+        native: ImpulseJointHandle,
+    },
+}
+
+// Collects raw events produced by the physics pipeline during simulation step.
+#[derive(Default)]
+struct EventCollector {
+    collision_events: Mutex<Vec<CollisionEvent>>,
+}
+
+impl EventHandler for EventCollector {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: CollisionEvent,
+        _contact_pair: Option<&rapier3d::geometry::ContactPair>,
+    ) {
+        self.collision_events.lock().push(event);
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: f32,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &rapier3d::geometry::ContactPair,
+        _total_force_magnitude: f32,
+    ) {
+    }
+}
+
 /// Data of the contact.
 pub struct ContactData {
     /// The contact point in the local-space of the first shape.
@@ -322,8 +870,8 @@ pub struct ContactPair {
 impl ContactPair {
     fn from_native(c: &rapier3d::geometry::ContactPair, physics: &PhysicsWorld) -> Option<Self> {
         Some(ContactPair {
-            collider1: Handle::decode_from_u128(physics.colliders.get(c.collider1)?.user_data),
-            collider2: Handle::decode_from_u128(physics.colliders.get(c.collider2)?.user_data),
+            collider1: Handle::decode_from_u128(physics.colliders.set.get(c.collider1)?.user_data),
+            collider2: Handle::decode_from_u128(physics.colliders.set.get(c.collider2)?.user_data),
             manifolds: c
                 .manifolds
                 .iter()
@@ -363,6 +911,55 @@ impl ContactPair {
     }
 }
 
+/// A point of contact between a collider and some other collider, see [`Contact`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContactPoint {
+    /// World-space position of the contact point on the surface of the collider.
+    pub position: Vector3<f32>,
+    /// World-space contact normal, that points from the collider towards the other collider.
+    pub normal: Vector3<f32>,
+    /// Distance between the colliders at the contact point. Negative values mean penetration.
+    pub distance: f32,
+    /// Magnitude of the impulse, that was applied along the normal by this contact at the last
+    /// simulation step.
+    pub impulse: f32,
+}
+
+/// A contact of a collider with some other collider from the point of view of the former. See
+/// [`super::Graph::contacts_of`] for more info.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contact {
+    /// Handle of the other collider.
+    pub other: Handle<Node>,
+    /// Handle of the rigid body of the other collider.
+    pub other_body: Handle<Node>,
+    /// Contact points between the colliders.
+    pub points: Vec<ContactPoint>,
+}
+
+/// A result of the time-of-impact query between two colliders, see [`super::Graph::time_of_impact`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeOfImpact {
+    /// Handle of the first collider.
+    pub collider1: Handle<Node>,
+    /// Handle of the second collider.
+    pub collider2: Handle<Node>,
+    /// Time (in seconds, if velocities are in m/s) after which the colliders will touch each other.
+    pub toi: f32,
+    /// World-space position of the contact point on the surface of the first collider at the time of
+    /// impact.
+    pub position1: Vector3<f32>,
+    /// World-space position of the contact point on the surface of the second collider at the time of
+    /// impact.
+    pub position2: Vector3<f32>,
+    /// World-space contact normal at the time of impact, that points from the first collider towards
+    /// the second one.
+    pub normal: Vector3<f32>,
+    /// `true` if the colliders are already intersecting. In this case the time of impact is zero and
+    /// contact points and the normal are undefined.
+    pub penetrating: bool,
+}
+
 /// Intersection info for pair of colliders.
 pub struct IntersectionPair {
     /// The first collider involved in the contact pair.
@@ -381,6 +978,63 @@ where
     map: BiDirHashMap<A, Handle<Node>>,
 }
 
+// Shifts mass properties of every collider of the given rigid body, so the center of mass of the body
+// matches the given one (in local coordinates of the body). Mass and angular inertia of the body
+// relative to its center of mass stay the same.
+fn apply_center_of_mass_override(
+    colliders: &mut ColliderSet,
+    body: &RigidBody,
+    center_of_mass: Vector3<f32>,
+) {
+    let mass_properties_of = |collider: &Collider| {
+        collider
+            .position_wrt_parent()
+            .filter(|_| collider.is_enabled())
+            .map(|position| {
+                (
+                    collider.shape().mass_properties(collider.density()),
+                    *position,
+                )
+            })
+    };
+
+    let mut total = MassProperties::zero();
+    for handle in body.colliders() {
+        if let Some((mass_properties, position)) =
+            colliders.get(*handle).and_then(mass_properties_of)
+        {
+            total += mass_properties.transform_by(&position);
+        }
+    }
+    if total.inv_mass == 0.0 {
+        return;
+    }
+
+    let offset = center_of_mass - total.local_com.coords;
+    for handle in body.colliders() {
+        let collider = match colliders.get(*handle) {
+            Some(collider) => collider,
+            None => continue,
+        };
+        let (mut desired, position) = match mass_properties_of(collider) {
+            Some(properties) => properties,
+            None => continue,
+        };
+        desired.local_com += position.rotation.inverse_transform_vector(&offset);
+
+        // `get_mut` is expensive, so mass properties are changed only if they're noticeably different.
+        // This also prevents accumulation of rounding errors of density.
+        let current = collider.mass_properties();
+        if (current.local_com - desired.local_com).norm() > 1.0e-5
+            || (current.mass() - desired.mass()).abs() > 1.0e-5 * desired.mass()
+        {
+            if let Some(collider) = colliders.get_mut(*handle) {
+                collider.set_mass_properties(desired);
+            }
+        }
+    }
+}
+
 fn convert_joint_params(
     params: scene::joint::JointParams,
     local_frame1: Isometry3<f32>,
@@ -391,6 +1045,13 @@ fn convert_joint_params(
         JointParams::FixedJoint(_) => JointAxesMask::LOCKED_FIXED_AXES,
         JointParams::PrismaticJoint(_) => JointAxesMask::LOCKED_PRISMATIC_AXES,
         JointParams::RevoluteJoint(_) => JointAxesMask::LOCKED_REVOLUTE_AXES,
+        // Spring forces are applied manually at each simulation step, see
+        // `PhysicsWorld::update_spring_joints`.
+        JointParams::SpringJoint(_) | JointParams::RopeJoint(_) => JointAxesMask::empty(),
+        JointParams::GenericJoint(ref v) => generic_joint_axes(v)
+            .iter()
+            .filter(|(_, _, params)| params.motion == JointAxisMotion::Locked)
+            .fold(JointAxesMask::empty(), |mask, (axis, _, _)| mask | *axis),
     };
 
     let mut joint = GenericJointBuilder::new(locked_axis)
@@ -424,24 +1085,76 @@ fn convert_joint_params(
             if v.limits_enabled {
                 joint.set_limits(JointAxis::X, [v.limits.start, v.limits.end]);
             }
+            set_joint_motor(&mut joint, JointAxis::X, &v.motor);
         }
         scene::joint::JointParams::RevoluteJoint(v) => {
             if v.limits_enabled {
                 joint.set_limits(JointAxis::AngX, [v.limits.start, v.limits.end]);
             }
+            set_joint_motor(&mut joint, JointAxis::AngX, &v.motor);
+        }
+        scene::joint::JointParams::SpringJoint(_) => {}
+        scene::joint::JointParams::RopeJoint(v) => {
+            // Linear axes are coupled, so the limit is applied to the distance between the anchors.
+            // The physics engine uses the length of the vector of per-axis limits as the maximum
+            // distance.
+            let limit = v.max_distance.max(0.0) / 3.0f32.sqrt();
+            joint.coupled_axes = JointAxesMask::LIN_AXES;
+            for axis in [JointAxis::X, JointAxis::Y, JointAxis::Z] {
+                joint.set_limits(axis, [0.0, limit]);
+            }
+        }
+        scene::joint::JointParams::GenericJoint(v) => {
+            for (_, axis, params) in generic_joint_axes(&v) {
+                if params.motion == JointAxisMotion::Locked {
+                    continue;
+                }
+                if params.motion == JointAxisMotion::Limited {
+                    joint.set_limits(axis, [params.limits.start, params.limits.end]);
+                }
+                set_joint_motor(&mut joint, axis, &params.motor);
+            }
         }
     }
 
     joint
 }
 
+fn generic_joint_axes(
+    joint: &scene::joint::GenericJoint,
+) -> [(JointAxesMask, JointAxis, &JointAxisParams); 6] {
+    [
+        (JointAxesMask::X, JointAxis::X, &joint.x),
+        (JointAxesMask::Y, JointAxis::Y, &joint.y),
+        (JointAxesMask::Z, JointAxis::Z, &joint.z),
+        (JointAxesMask::ANG_X, JointAxis::AngX, &joint.angular_x),
+        (JointAxesMask::ANG_Y, JointAxis::AngY, &joint.angular_y),
+        (JointAxesMask::ANG_Z, JointAxis::AngZ, &joint.angular_z),
+    ]
+}
+
+fn set_joint_motor(joint: &mut GenericJoint, axis: JointAxis, motor: &scene::joint::JointMotor) {
+    if motor.enabled {
+        joint
+            .set_motor(
+                axis,
+                motor.target_position,
+                motor.target_velocity,
+                motor.stiffness,
+                motor.damping,
+            )
+            .set_motor_max_force(axis, motor.max_force);
+    }
+}
+
 /// Creates new trimesh collider shape from given mesh node. It also bakes scale into
 /// vertices of trimesh because rapier does not support collider scaling yet.
 fn make_trimesh(
     owner_inv_transform: Matrix4<f32>,
     owner: Handle<Node>,
-    sources: &[GeometrySource],
+    trimesh: &collider::TrimeshShape,
     nodes: &NodePool,
+    cache: &RefCell<TrimeshCache>,
 ) -> SharedShape {
     let mut mesh_builder = RawMeshBuilder::new(0, 0);
 
@@ -452,7 +1165,30 @@ fn make_trimesh(
     // but we don't want to bake entire transform including root's transform.
     let root_inv_transform = owner_inv_transform;
 
-    for &source in sources {
+    // The key changes every time when the geometry of any source or its baked transform changes.
+    let mut key = hash_combine(
+        trimesh.weld_distance.to_bits() as u64,
+        trimesh
+            .triangle_budget
+            .map_or(u64::MAX, |budget| budget as u64),
+    );
+    for &source in trimesh.sources.iter() {
+        if let Some(mesh) = nodes.try_borrow(source.0).and_then(|n| n.cast::<Mesh>()) {
+            let global_transform = root_inv_transform * mesh.global_transform();
+            for value in global_transform.iter() {
+                key = hash_combine(key, value.to_bits() as u64);
+            }
+            for surface in mesh.surfaces() {
+                key = hash_combine(key, surface.data().lock().content_hash());
+            }
+        }
+    }
+
+    if let Some(shape) = cache.borrow().get(key) {
+        return shape;
+    }
+
+    for &source in trimesh.sources.iter() {
         if let Some(mesh) = nodes.try_borrow(source.0).and_then(|n| n.cast::<Mesh>()) {
             let global_transform = root_inv_transform * mesh.global_transform();
 
@@ -518,6 +1254,13 @@ fn make_trimesh(
         .map(|t| [t.0[0], t.0[1], t.0[2]])
         .collect::<Vec<_>>();
 
+    let (vertices, indices) = simplify_trimesh(
+        vertices,
+        indices,
+        trimesh.weld_distance,
+        trimesh.triangle_budget,
+    );
+
     if indices.is_empty() {
         Log::writeln(
             MessageKind::Warning,
@@ -529,7 +1272,156 @@ fn make_trimesh(
 
         SharedShape::trimesh(vec![Point3::new(0.0, 0.0, 0.0)], vec![[0, 0, 0]])
     } else {
-        SharedShape::trimesh(vertices, indices)
+        let shape = SharedShape::trimesh(vertices, indices);
+        cache.borrow_mut().insert(key, &shape);
+        shape
+    }
+}
+
+// Finds a triangle of a triangle mesh collider, that contains the given intersection position (in
+// world coordinates).
+fn triangle_hit(
+    collider: &Collider,
+    feature: rapier3d::geometry::FeatureId,
+    position: &Point3<f32>,
+) -> Option<TriangleHit> {
+    let trimesh = collider.shape().as_trimesh()?;
+
+    let mut index = match feature {
+        rapier3d::geometry::FeatureId::Face(index) => index,
+        _ => return None,
+    };
+    // Back faces have indices shifted by the amount of triangles.
+    let triangle_count = trimesh.num_triangles() as u32;
+    if index >= triangle_count {
+        index -= triangle_count;
+    }
+
+    let triangle = trimesh.triangle(index);
+    let local_position = collider.position().inverse_transform_point(position);
+    let (u, v, w) = get_barycentric_coords(
+        &local_position.coords,
+        &triangle.a.coords,
+        &triangle.b.coords,
+        &triangle.c.coords,
+    );
+
+    Some(TriangleHit {
+        index,
+        barycentric: Vector3::new(u, v, w),
+    })
+}
+
+// Merges vertices that fall in the same cell of a uniform grid with the given cell size and removes
+// triangles that became degenerate or duplicated.
+fn cluster_vertices(
+    vertices: &[Point3<f32>],
+    indices: &[[u32; 3]],
+    cell_size: f32,
+) -> (Vec<Point3<f32>>, Vec<[u32; 3]>) {
+    let mut cells = FxHashMap::default();
+    let mut clusters: Vec<(Vector3<f32>, u32)> = Vec::new();
+    let remap = vertices
+        .iter()
+        .map(|vertex| {
+            let cell = vertex.coords.map(|c| (c / cell_size).floor() as i64);
+            let index = *cells.entry(cell).or_insert_with(|| {
+                clusters.push((Vector3::default(), 0));
+                clusters.len() as u32 - 1
+            });
+            let cluster = &mut clusters[index as usize];
+            cluster.0 += vertex.coords;
+            cluster.1 += 1;
+            index
+        })
+        .collect::<Vec<_>>();
+
+    let mut unique_triangles = FxHashSet::default();
+    let indices = indices
+        .iter()
+        .map(|triangle| triangle.map(|i| remap[i as usize]))
+        .filter(|[a, b, c]| a != b && b != c && a != c)
+        .filter(|triangle| {
+            // Rotate the triangle so that its smallest index goes first, it keeps the winding and
+            // allows to detect duplicates.
+            let mut key = *triangle;
+            let first = (0..3).min_by_key(|&i| key[i]).unwrap_or_default();
+            key.rotate_left(first);
+            unique_triangles.insert(key)
+        })
+        .collect();
+
+    let vertices = clusters
+        .into_iter()
+        .map(|(sum, count)| Point3::from(sum.scale(1.0 / count as f32)))
+        .collect();
+
+    (vertices, indices)
+}
+
+// Welds close vertices and decimates the geometry until it fits the triangle budget using vertex
+// clustering. The grid gets coarser at each iteration, so the loss of details is spread uniformly.
+fn simplify_trimesh(
+    vertices: Vec<Point3<f32>>,
+    indices: Vec<[u32; 3]>,
+    weld_distance: f32,
+    triangle_budget: Option<u32>,
+) -> (Vec<Point3<f32>>, Vec<[u32; 3]>) {
+    let (vertices, indices) = if weld_distance > 0.0 {
+        cluster_vertices(&vertices, &indices, weld_distance)
+    } else {
+        (vertices, indices)
+    };
+
+    let budget = match triangle_budget {
+        Some(budget) if indices.len() > budget as usize => budget as usize,
+        _ => return (vertices, indices),
+    };
+
+    let points = vertices.iter().map(|v| v.coords).collect::<Vec<_>>();
+    let size = AxisAlignedBoundingBox::from_points(&points)
+        .half_extents()
+        .max()
+        * 2.0;
+    let mut cell_size = weld_distance.max(size / 1024.0);
+    let mut result = (vertices, indices);
+    for _ in 0..32 {
+        let (simplified_vertices, simplified_indices) =
+            cluster_vertices(&result.0, &result.1, cell_size);
+        if simplified_indices.is_empty() {
+            // Do not collapse the entire geometry.
+            break;
+        }
+        result = (simplified_vertices, simplified_indices);
+        if result.1.len() <= budget {
+            break;
+        }
+        cell_size *= 2.0;
+    }
+
+    result
+}
+
+// Native triangle meshes, keyed by contents of their geometry sources, baked transforms and
+// simplification options. It allows colliders with the same geometry to share native shapes and
+// skips expensive construction when a collider is re-created. An entry lives while there is at least
+// one collider that uses its shape.
+#[derive(Default)]
+pub(crate) struct TrimeshCache {
+    entries: FxHashMap<u64, Weak<dyn Shape>>,
+}
+
+impl TrimeshCache {
+    fn get(&self, key: u64) -> Option<SharedShape> {
+        self.entries
+            .get(&key)
+            .and_then(|shape| shape.upgrade())
+            .map(SharedShape)
+    }
+
+    fn insert(&mut self, key: u64, shape: &SharedShape) {
+        self.entries.retain(|_, shape| shape.strong_count() > 0);
+        self.entries.insert(key, Arc::downgrade(&shape.0));
     }
 }
 
@@ -610,39 +1502,43 @@ fn make_polyhedron_shape(owner_inv_transform: Matrix4<f32>, mesh: &Mesh) -> Shar
     SharedShape::convex_decomposition(&vertices, &indices)
 }
 
-/// Creates height field shape from given terrain.
-fn make_heightfield(terrain: &Terrain) -> SharedShape {
-    assert!(!terrain.chunks_ref().is_empty());
+// Copies heights of the given terrain chunk into combined column-major height data of a height
+// field with `nrows` rows.
+fn copy_chunk_heights(
+    terrain: &Terrain,
+    cx: usize,
+    cz: usize,
+    scale_y: f32,
+    nrows: u32,
+    data: &mut [f32],
+) {
+    let height_map_size = terrain.height_map_size();
+    let ox = cx as u32 * height_map_size.x;
+    let oz = cz as u32 * height_map_size.y;
+    let chunk = &terrain.chunks_ref()[cz * terrain.width_chunks().len() + cx];
+    let texture = chunk.heightmap().data_ref();
+    let height_map = texture.data_of_type::<f32>().unwrap();
+    for iy in 0..height_map_size.y {
+        for ix in 0..height_map_size.x {
+            let value = height_map[(iy * height_map_size.x + ix) as usize] * scale_y;
+            data[((ox + ix) * nrows + oz + iy) as usize] = value;
+        }
+    }
+}
 
-    // HACK: Temporary solution for https://github.com/FyroxEngine/Fyrox/issues/365
-    let scale = terrain.local_transform().scale();
+/// Creates height field shape from given terrain. It also bakes scale into the height field,
+/// because rapier does not support collider scaling yet.
+fn make_heightfield(terrain: &Terrain, scale: Vector3<f32>) -> SharedShape {
+    assert!(!terrain.chunks_ref().is_empty());
 
-    // Count rows and columns.
-    let height_map_size = terrain.height_map_size();
-    let nrows = height_map_size.y * terrain.length_chunks().len() as u32;
-    let ncols = height_map_size.x * terrain.width_chunks().len() as u32;
+    let (nrows, ncols) = heightfield_size(terrain);
 
     // Combine height map of each chunk into bigger one.
-    let mut ox = 0;
-    let mut oz = 0;
     let mut data = vec![0.0; (nrows * ncols) as usize];
     for cz in 0..terrain.length_chunks().len() {
         for cx in 0..terrain.width_chunks().len() {
-            let chunk = &terrain.chunks_ref()[cz * terrain.width_chunks().len() + cx];
-            let texture = chunk.heightmap().data_ref();
-            let height_map = texture.data_of_type::<f32>().unwrap();
-            for iy in 0..height_map_size.y {
-                for ix in 0..height_map_size.x {
-                    let value = height_map[(iy * height_map_size.x + ix) as usize] * scale.y;
-                    data[((ox + ix) * nrows + oz + iy) as usize] = value;
-                }
-            }
-
-            ox += height_map_size.x;
+            copy_chunk_heights(terrain, cx, cz, scale.y, nrows, &mut data);
         }
-
-        ox = 0;
-        oz += height_map_size.y;
     }
 
     SharedShape::heightfield(
@@ -651,44 +1547,140 @@ fn make_heightfield(terrain: &Terrain) -> SharedShape {
             Dyn(ncols as usize),
             data,
         )),
-        Vector3::new(
-            terrain.chunk_size().x * scale.x * terrain.width_chunks().len() as f32,
-            1.0,
-            terrain.chunk_size().y * scale.z * terrain.length_chunks().len() as f32,
-        ),
+        heightfield_scale(terrain, scale),
     )
 }
 
-// Converts descriptor in a shared shape.
-fn collider_shape_into_native_shape(
-    shape: &ColliderShape,
-    owner_inv_global_transform: Matrix4<f32>,
-    owner_collider: Handle<Node>,
-    pool: &NodePool,
-) -> Option<SharedShape> {
-    match shape {
-        ColliderShape::Ball(ball) => Some(SharedShape::ball(ball.radius)),
-
-        ColliderShape::Cylinder(cylinder) => {
-            Some(SharedShape::cylinder(cylinder.half_height, cylinder.radius))
+// Counts rows and columns of a height field made from the given terrain.
+fn heightfield_size(terrain: &Terrain) -> (u32, u32) {
+    let height_map_size = terrain.height_map_size();
+    let nrows = height_map_size.y * terrain.length_chunks().len() as u32;
+    let ncols = height_map_size.x * terrain.width_chunks().len() as u32;
+    (nrows, ncols)
+}
+
+fn heightfield_scale(terrain: &Terrain, scale: Vector3<f32>) -> Vector3<f32> {
+    Vector3::new(
+        terrain.chunk_size().x * scale.x * terrain.width_chunks().len() as f32,
+        1.0,
+        terrain.chunk_size().y * scale.z * terrain.length_chunks().len() as f32,
+    )
+}
+
+// Creates a copy of the given height field with the heights of changed chunks of the terrain
+// updated. Heights of other chunks are taken from the existing height field as is, so the cost of
+// the update is proportional to the amount of changed chunks.
+fn patch_heightfield(
+    heightfield: &HeightField,
+    terrain: &Terrain,
+    scale: Vector3<f32>,
+    changed_chunks: &[bool],
+) -> SharedShape {
+    let mut heights = heightfield.heights().clone();
+    let nrows = heights.nrows() as u32;
+    for cz in 0..terrain.length_chunks().len() {
+        for cx in 0..terrain.width_chunks().len() {
+            if changed_chunks[cz * terrain.width_chunks().len() + cx] {
+                copy_chunk_heights(terrain, cx, cz, scale.y, nrows, heights.as_mut_slice());
+            }
         }
-        ColliderShape::Cone(cone) => Some(SharedShape::cone(cone.half_height, cone.radius)),
-        ColliderShape::Cuboid(cuboid) => {
-            Some(SharedShape(Arc::new(Cuboid::new(cuboid.half_extents))))
+    }
+    SharedShape::heightfield(heights, *heightfield.scale())
+}
+
+fn is_same_heightfield_layout(
+    heightfield: &HeightField,
+    terrain: &Terrain,
+    scale: Vector3<f32>,
+) -> bool {
+    let (nrows, ncols) = heightfield_size(terrain);
+    heightfield.heights().nrows() == nrows as usize
+        && heightfield.heights().ncols() == ncols as usize
+        && *heightfield.scale() == heightfield_scale(terrain, scale)
+}
+
+// Collects hashes of height maps of the terrain, that is used as a geometry source of the given
+// height field shape. The hashes are used to detect terrain modifications.
+fn height_map_hashes(shape: &ColliderShape, pool: &NodePool) -> Vec<u64> {
+    match shape {
+        ColliderShape::Heightfield(heightfield) => pool
+            .try_borrow(heightfield.geometry_source.0)
+            .and_then(|n| n.cast::<Terrain>())
+            .map(|terrain| {
+                terrain
+                    .chunks_ref()
+                    .iter()
+                    .map(|chunk| chunk.heightmap().data_ref().data_hash())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+// Calculates combined global scale of the nodes, that are used as geometry sources for the given
+// shape. Such shapes have the scale baked in, so they must be re-created when it changes.
+fn geometry_sources_scale(shape: &ColliderShape, pool: &NodePool) -> Vector3<f32> {
+    match shape {
+        ColliderShape::Trimesh(trimesh) => trimesh
+            .sources
+            .iter()
+            .map(|source| global_scale(pool, source.0))
+            .sum(),
+        ColliderShape::Heightfield(heightfield) => {
+            global_scale(pool, heightfield.geometry_source.0)
         }
+        ColliderShape::Polyhedron(polyhedron) => global_scale(pool, polyhedron.geometry_source.0),
+        ColliderShape::Compound(compound) => compound
+            .children
+            .iter()
+            .map(|child| geometry_sources_scale(&child.shape, pool))
+            .sum(),
+        _ => Vector3::repeat(1.0),
+    }
+}
+
+// Converts descriptor in a shared shape. Dimensions of primitive shapes are multiplied by the given
+// scale, because rapier does not support collider scaling. Shapes with geometry sources (meshes and
+// terrains) already have the scale of their sources baked in, so the scale is ignored for them.
+// Non-uniform scale is approximated for round shapes (the largest scale component is used for their
+// radii).
+pub(crate) fn collider_shape_into_native_shape(
+    shape: &ColliderShape,
+    scale: Vector3<f32>,
+    owner_inv_global_transform: Matrix4<f32>,
+    owner_collider: Handle<Node>,
+    pool: &NodePool,
+    trimesh_cache: &RefCell<TrimeshCache>,
+) -> Option<SharedShape> {
+    let abs_scale = scale.abs();
+    match shape {
+        ColliderShape::Ball(ball) => Some(SharedShape::ball(ball.radius * abs_scale.max())),
+
+        ColliderShape::Cylinder(cylinder) => Some(SharedShape::cylinder(
+            cylinder.half_height * abs_scale.y,
+            cylinder.radius * abs_scale.x.max(abs_scale.z),
+        )),
+        ColliderShape::Cone(cone) => Some(SharedShape::cone(
+            cone.half_height * abs_scale.y,
+            cone.radius * abs_scale.x.max(abs_scale.z),
+        )),
+        ColliderShape::Cuboid(cuboid) => Some(SharedShape(Arc::new(Cuboid::new(
+            cuboid.half_extents.component_mul(&abs_scale),
+        )))),
         ColliderShape::Capsule(capsule) => Some(SharedShape::capsule(
-            Point3::from(capsule.begin),
-            Point3::from(capsule.end),
-            capsule.radius,
+            Point3::from(capsule.begin.component_mul(&scale)),
+            Point3::from(capsule.end.component_mul(&scale)),
+            capsule.radius * abs_scale.max(),
         )),
         ColliderShape::Segment(segment) => Some(SharedShape::segment(
-            Point3::from(segment.begin),
-            Point3::from(segment.end),
+            Point3::from(segment.begin.component_mul(&scale)),
+            Point3::from(segment.end.component_mul(&scale)),
         )),
         ColliderShape::Triangle(triangle) => Some(SharedShape::triangle(
-            Point3::from(triangle.a),
-            Point3::from(triangle.b),
-            Point3::from(triangle.c),
+            Point3::from(triangle.a.component_mul(&scale)),
+            Point3::from(triangle.b.component_mul(&scale)),
+            Point3::from(triangle.c.component_mul(&scale)),
         )),
         ColliderShape::Trimesh(trimesh) => {
             if trimesh.sources.is_empty() {
@@ -697,19 +1689,70 @@ fn collider_shape_into_native_shape(
                 Some(make_trimesh(
                     owner_inv_global_transform,
                     owner_collider,
-                    &trimesh.sources,
+                    trimesh,
                     pool,
+                    trimesh_cache,
                 ))
             }
         }
         ColliderShape::Heightfield(heightfield) => pool
             .try_borrow(heightfield.geometry_source.0)
             .and_then(|n| n.cast::<Terrain>())
-            .map(make_heightfield),
+            .map(|terrain| {
+                make_heightfield(terrain, global_scale(pool, heightfield.geometry_source.0))
+            }),
         ColliderShape::Polyhedron(polyhedron) => pool
             .try_borrow(polyhedron.geometry_source.0)
             .and_then(|n| n.cast::<Mesh>())
             .map(|mesh| make_polyhedron_shape(owner_inv_global_transform, mesh)),
+        ColliderShape::Compound(compound) => {
+            let mut shapes = Vec::with_capacity(compound.children.len());
+            for child in compound.children.iter() {
+                if matches!(
+                    child.shape,
+                    ColliderShape::Trimesh(_) | ColliderShape::Heightfield(_)
+                ) {
+                    Log::warn(format!(
+                        "{} shape cannot be a part of compound shape, it is ignored!",
+                        child.shape.as_ref()
+                    ));
+                    continue;
+                }
+
+                // Offsets of the children are scaled too, but the shapes of the children are scaled
+                // in their own frames, so non-uniform scale is exact only for non-rotated children.
+                let offset = Isometry3::from_parts(
+                    Translation3::from(child.position.component_mul(&scale)),
+                    child.rotation,
+                );
+                if let Some(shape) = collider_shape_into_native_shape(
+                    &child.shape,
+                    scale,
+                    owner_inv_global_transform,
+                    owner_collider,
+                    pool,
+                    trimesh_cache,
+                ) {
+                    // Nested compound shapes are not supported by the physics engine, flatten them.
+                    if let Some(nested) = shape.as_compound() {
+                        shapes.extend(
+                            nested
+                                .shapes()
+                                .iter()
+                                .map(|(delta, shape)| (offset * delta, shape.clone())),
+                        );
+                    } else {
+                        shapes.push((offset, shape));
+                    }
+                }
+            }
+
+            if shapes.is_empty() {
+                None
+            } else {
+                Some(SharedShape::compound(shapes))
+            }
+        }
     }
 }
 
@@ -884,8 +1927,33 @@ pub struct PhysicsWorld {
     pub integration_parameters: IntegrationParameters,
 
     /// Current gravity vector. Default is (0.0, -9.81, 0.0)
+    /// Use [`Self::set_gravity`] to change gravity at runtime, it wakes up sleeping rigid bodies.
     pub gravity: Vector3<f32>,
 
+    /// Fixed time step (in seconds) of the simulation. If set, frame time is accumulated and the
+    /// simulation is advanced in steps of this exact size as many times as needed to catch up with
+    /// the accumulated time. This makes the simulation independent of frame rate. If not set, the
+    /// simulation is advanced once per frame. Default is `None`.
+    ///
+    /// ## Important notes
+    ///
+    /// [`IntegrationParameters::dt`] is ignored when the fixed time step is set.
+    #[visit(optional)]
+    pub fixed_timestep: Option<f32>,
+
+    /// Maximum amount of fixed steps per frame. If a frame took more time than this amount of steps
+    /// could simulate, the rest of the time is discarded. This prevents the simulation from falling
+    /// behind more and more on slow machines. Default is 8.
+    #[visit(optional)]
+    pub max_fixed_steps: u32,
+
+    /// If `true`, transforms of dynamic (and velocity-based kinematic) rigid bodies are interpolated between two last physics
+    /// states using the amount of accumulated (not simulated yet) time. This smooths the motion when
+    /// the frame rate does not match [`Self::fixed_timestep`], at the cost of one step of latency.
+    /// Has no effect if fixed time step is not set. Default is `false`.
+    #[visit(optional)]
+    pub interpolate_transforms: bool,
+
     /// Performance statistics of a single simulation step.
     #[visit(skip)]
     #[reflect(hidden)]
@@ -919,7 +1987,7 @@ pub struct PhysicsWorld {
     // A container of colliders.
     #[visit(skip)]
     #[reflect(hidden)]
-    colliders: ColliderSet,
+    colliders: Container<ColliderSet, ColliderHandle>,
     // A container of impulse joints.
     #[visit(skip)]
     #[reflect(hidden)]
@@ -931,13 +1999,230 @@ pub struct PhysicsWorld {
     // Event handler collects info about contacts and proximity events.
     #[visit(skip)]
     #[reflect(hidden)]
-    event_handler: Box<dyn EventHandler>,
+    event_collector: EventCollector,
+    // Node handles of colliders removed since last simulation step. Removal events are produced
+    // after the colliders were removed, so their handles cannot be fetched from the collider map.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    removed_colliders: FxHashMap<ColliderHandle, Handle<Node>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    contact_events: Vec<ContactEvent>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    intersection_events: Vec<IntersectionEvent>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    joint_break_events: Vec<JointBreakEvent>,
+    // Break force and torque of every breakable native joint.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    breakable_joints: FxHashMap<ImpulseJointHandle, (Option<f32>, Option<f32>)>,
+    // Parameters of every native joint, that is used by a spring joint node.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    spring_joints: FxHashMap<ImpulseJointHandle, SpringJoint>,
+    // Overridden local center of mass of every native rigid body, that is used by a rigid body node
+    // with custom center of mass.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    center_of_mass_overrides: FxHashMap<RigidBodyHandle, Vector3<f32>>,
+    // Frame time that was not simulated yet, used only with fixed time step.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    accumulator: f32,
+    // Interpolation factor between previous and current physics states.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    interpolation_factor: f32,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    interpolation_states: FxHashMap<RigidBodyHandle, InterpolationState>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    vehicles: Pool<NativeVehicle>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    water_volumes: Pool<NativeWaterVolume>,
+    // Subscribers of the events about creation and removal of native entities.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    native_event_senders: Vec<Sender<NativePhysicsEvent>>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    hooks: Option<Box<dyn PhysicsHooks>>,
     #[visit(skip)]
     #[reflect(hidden)]
     query: RefCell<QueryPipeline>,
     #[visit(skip)]
     #[reflect(hidden)]
+    pending_removals: PendingRemovals,
+    // Results of ray casts against fixed colliders, see `cast_ray_cached`.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    static_ray_cast_cache: RefCell<StaticRayCastCache>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) trimesh_cache: RefCell<TrimeshCache>,
+    #[visit(skip)]
+    #[reflect(hidden)]
     debug_render_pipeline: Mutex<DebugRenderPipeline>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    debug_draw_flags: PhysicsDebugDrawFlags,
+}
+
+// Native rigid bodies and colliders, which removal is deferred until the next simulation step. The
+// query pipeline is rebuilt only once per frame, so the native entities are kept alive until then to
+// prevent the pipeline from referring to removed entities. Queries ignore such colliders.
+#[derive(Default)]
+struct PendingRemovals {
+    bodies: FxHashSet<RigidBodyHandle>,
+    colliders: Vec<ColliderHandle>,
+    // Contains the colliders of the removed bodies too.
+    removed_colliders: FxHashSet<ColliderHandle>,
+}
+
+/// Native representation of a [`Vehicle`] node.
+pub(crate) struct NativeVehicle {
+    pub(crate) controller: DynamicRayCastVehicleController,
+    // Handles of wheel nodes in the order of native wheels.
+    pub(crate) wheels: Vec<Handle<Node>>,
+    // Suspension lengths of the wheels at the last simulation step.
+    pub(crate) suspension_lengths: Vec<f32>,
+    // Friction slip cannot be changed after a native wheel was created, so the vehicle is re-created
+    // when it changes.
+    friction_slips: Vec<f32>,
+}
+
+/// Native representation of a [`WaterVolume`] node.
+pub(crate) struct NativeWaterVolume {
+    transform: Matrix4<f32>,
+    inv_transform: Matrix4<f32>,
+    half_extents: Vector3<f32>,
+    density: f32,
+    linear_drag: f32,
+    angular_drag: f32,
+    flow_velocity: Vector3<f32>,
+}
+
+// Returns `true` if the position of a body of the given type is defined by the simulation, so it must
+// be copied back to the scene node. Velocity-based kinematic bodies are moved by the physics engine
+// using their velocities.
+fn is_simulated_body_type(body_type: RigidBodyType) -> bool {
+    matches!(
+        body_type,
+        RigidBodyType::Dynamic | RigidBodyType::KinematicVelocityBased
+    )
+}
+
+// Physics state of a simulated rigid body, that is used for transform interpolation.
+struct InterpolationState {
+    // Position of the body before the last simulation step.
+    previous: Isometry3<f32>,
+    // Position that was passed to the scene node of the body.
+    interpolated: Isometry3<f32>,
+}
+
+/// Size of the grid that is used to quantize ray parameters in [`PhysicsWorld::cast_ray_cached`]. Rays
+/// whose origins, directions and lengths differ less than this value share the same cache entry.
+pub const RAY_CAST_CACHE_QUANTUM: f32 = 0.001;
+
+// Quantized parameters of a ray cast, that are used as a key of the static ray cast cache.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+struct RayCastCacheKey {
+    origin: [i32; 3],
+    direction: [i32; 3],
+    max_len: i32,
+    memberships: u32,
+    filter: u32,
+    sort_results: bool,
+}
+
+impl RayCastCacheKey {
+    fn new(opts: &RayCastOptions) -> Self {
+        let quantize = |v: f32| (v / RAY_CAST_CACHE_QUANTUM).round() as i32;
+        let direction = opts
+            .ray_direction
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default();
+        Self {
+            origin: [
+                quantize(opts.ray_origin.x),
+                quantize(opts.ray_origin.y),
+                quantize(opts.ray_origin.z),
+            ],
+            direction: [
+                quantize(direction.x),
+                quantize(direction.y),
+                quantize(direction.z),
+            ],
+            max_len: quantize(opts.max_len),
+            memberships: opts.groups.memberships.0,
+            filter: opts.groups.filter.0,
+            sort_results: opts.sort_results,
+        }
+    }
+}
+
+// Results of ray casts against fixed colliders. The cache is flushed every time when static geometry
+// of the world changes (a fixed collider is added, removed or modified, or a fixed body is moved).
+struct StaticRayCastCache {
+    entries: FxHashMap<RayCastCacheKey, Vec<Intersection>>,
+    capacity: usize,
+}
+
+impl Default for StaticRayCastCache {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+            capacity: 16384,
+        }
+    }
+}
+
+impl StaticRayCastCache {
+    fn invalidate(&mut self) {
+        self.entries.clear();
+    }
+}
+
+fn isometry_approx_eq(a: &Isometry3<f32>, b: &Isometry3<f32>) -> bool {
+    (a.translation.vector - b.translation.vector).norm() < 0.0001
+        && a.rotation.angle_to(&b.rotation) < 0.001
+}
+
+fn isometry_from_local_transform(transform: &Transform) -> Isometry3<f32> {
+    Isometry3 {
+        rotation: **transform.rotation(),
+        translation: Translation3 {
+            vector: **transform.position(),
+        },
+    }
+}
+
+// Searches for the closest ancestor rigid body of the given collider. There could be any number of
+// intermediate nodes between the collider and the rigid body, so the method also calculates relative
+// position of the collider in the local frame of the rigid body. Scale of the ancestors (including the
+// rigid body) is applied to the relative position.
+fn find_collider_parent_body<'a>(
+    nodes: &'a NodePool,
+    collider: &scene::collider::Collider,
+) -> Option<(&'a scene::rigidbody::RigidBody, Isometry3<f32>)> {
+    let mut position = isometry_from_local_transform(collider.local_transform());
+    let mut handle = collider.parent();
+    while let Some(node) = nodes.try_borrow(handle) {
+        position
+            .translation
+            .vector
+            .component_mul_assign(node.local_transform().scale());
+        if let Some(rigid_body) = node.cast::<scene::rigidbody::RigidBody>() {
+            return Some((rigid_body, position));
+        }
+        position = isometry_from_local_transform(node.local_transform()) * position;
+        handle = node.parent();
+    }
+    None
 }
 
 fn isometry_from_global_transform(transform: &Matrix4<f32>) -> Isometry3<f32> {
@@ -953,16 +2238,23 @@ fn isometry_from_global_transform(transform: &Matrix4<f32>) -> Isometry3<f32> {
 }
 
 fn calculate_local_frames(
-    joint: &dyn NodeTrait,
+    joint: &scene::joint::Joint,
     body1: &dyn NodeTrait,
     body2: &dyn NodeTrait,
 ) -> (Isometry3<f32>, Isometry3<f32>) {
     let joint_isometry = isometry_from_global_transform(&joint.global_transform());
 
-    (
-        isometry_from_global_transform(&body1.global_transform()).inverse() * joint_isometry,
-        isometry_from_global_transform(&body2.global_transform()).inverse() * joint_isometry,
-    )
+    let local_frame1 =
+        isometry_from_global_transform(&body1.global_transform()).inverse() * joint_isometry;
+    let mut local_frame2 =
+        isometry_from_global_transform(&body2.global_transform()).inverse() * joint_isometry;
+
+    // Distance joints connect the position of the joint with the origin of the second body.
+    if let JointParams::SpringJoint(_) | JointParams::RopeJoint(_) = joint.params() {
+        local_frame2.translation = Translation3::identity();
+    }
+
+    (local_frame1, local_frame2)
 }
 
 fn u32_to_group(v: u32) -> rapier3d::geometry::Group {
@@ -976,13 +2268,19 @@ impl PhysicsWorld {
             enabled: true,
             pipeline: PhysicsPipeline::new(),
             gravity: Vector3::new(0.0, -9.81, 0.0),
+            fixed_timestep: None,
+            max_fixed_steps: 8,
+            interpolate_transforms: false,
             integration_parameters: IntegrationParameters::default(),
             broad_phase: BroadPhase::new(),
             narrow_phase: NarrowPhase::new(),
             ccd_solver: CCDSolver::new(),
             islands: IslandManager::new(),
             bodies: RigidBodySet::new(),
-            colliders: ColliderSet::new(),
+            colliders: Container {
+                set: ColliderSet::new(),
+                map: Default::default(),
+            },
             joints: Container {
                 set: ImpulseJointSet::new(),
                 map: Default::default(),
@@ -991,80 +2289,601 @@ impl PhysicsWorld {
                 set: MultibodyJointSet::new(),
                 map: Default::default(),
             },
-            event_handler: Box::new(()),
+            event_collector: Default::default(),
+            removed_colliders: Default::default(),
+            contact_events: Default::default(),
+            intersection_events: Default::default(),
+            joint_break_events: Default::default(),
+            breakable_joints: Default::default(),
+            spring_joints: Default::default(),
+            center_of_mass_overrides: Default::default(),
+            accumulator: 0.0,
+            interpolation_factor: 1.0,
+            interpolation_states: Default::default(),
+            vehicles: Default::default(),
+            water_volumes: Default::default(),
+            native_event_senders: Default::default(),
+            hooks: None,
             query: RefCell::new(Default::default()),
+            pending_removals: Default::default(),
+            static_ray_cast_cache: Default::default(),
+            trimesh_cache: Default::default(),
             performance_statistics: Default::default(),
-            debug_render_pipeline: Default::default(),
+            debug_render_pipeline: Mutex::new(DebugRenderPipeline::new(
+                debug_render_style(),
+                Default::default(),
+            )),
+            debug_draw_flags: Default::default(),
         }
     }
 
     pub(super) fn update(&mut self, dt: f32) {
         let time = instant::Instant::now();
 
+        self.joint_break_events.clear();
+
         if self.enabled {
-            let integration_parameters = rapier3d::dynamics::IntegrationParameters {
-                dt: self.integration_parameters.dt.unwrap_or(dt),
-                min_ccd_dt: self.integration_parameters.min_ccd_dt,
-                erp: self.integration_parameters.erp,
-                damping_ratio: self.integration_parameters.damping_ratio,
-                joint_erp: self.integration_parameters.joint_erp,
-                joint_damping_ratio: self.integration_parameters.joint_damping_ratio,
-                allowed_linear_error: self.integration_parameters.allowed_linear_error,
-                max_penetration_correction: self.integration_parameters.max_penetration_correction,
-                prediction_distance: self.integration_parameters.prediction_distance,
-                max_velocity_iterations: self.integration_parameters.max_velocity_iterations
-                    as usize,
-                max_velocity_friction_iterations: self
-                    .integration_parameters
-                    .max_velocity_friction_iterations
-                    as usize,
-                max_stabilization_iterations: self
-                    .integration_parameters
-                    .max_stabilization_iterations
-                    as usize,
-                interleave_restitution_and_friction_resolution: self
-                    .integration_parameters
-                    .interleave_restitution_and_friction_resolution,
-                min_island_size: self.integration_parameters.min_island_size as usize,
-                max_ccd_substeps: self.integration_parameters.max_ccd_substeps as usize,
+            match self.fixed_timestep {
+                Some(fixed_timestep) if fixed_timestep > 0.0 => {
+                    self.accumulator += dt;
+
+                    // Kinematic bodies must reach their target positions (set by animations, scripts,
+                    // etc.) gradually during all the steps of the frame, otherwise they will move the
+                    // entire distance in the first step and stand still in the rest, pushing dynamic
+                    // bodies with wrong velocities.
+                    let total_steps = ((self.accumulator / fixed_timestep) as u32)
+                        .min(self.max_fixed_steps)
+                        .max(1);
+                    let kinematic_motions = self.kinematic_motions();
+
+                    let mut steps = 0;
+                    while self.accumulator >= fixed_timestep {
+                        if steps >= self.max_fixed_steps {
+                            // Discard the time that cannot be simulated in this frame.
+                            self.accumulator %= fixed_timestep;
+                            break;
+                        }
+
+                        if self.interpolate_transforms {
+                            self.save_interpolation_states();
+                        }
+                        let t = ((steps + 1) as f32 / total_steps as f32).min(1.0);
+                        for (handle, start, target) in kinematic_motions.iter() {
+                            if let Some(body) = self.bodies.get_mut(*handle) {
+                                body.set_next_kinematic_position(start.lerp_slerp(target, t));
+                            }
+                        }
+                        self.step(fixed_timestep);
+
+                        self.accumulator -= fixed_timestep;
+                        steps += 1;
+                    }
+
+                    self.interpolation_factor = (self.accumulator / fixed_timestep).min(1.0);
+                }
+                _ => {
+                    self.accumulator = 0.0;
+                    self.interpolation_factor = 1.0;
+                    self.step(self.integration_parameters.dt.unwrap_or(dt));
+                }
+            }
+
+            if !self.is_interpolating() {
+                self.interpolation_states.clear();
+            }
+        }
+
+        self.translate_events();
+
+        self.performance_statistics.step_time += instant::Instant::now() - time;
+    }
+
+    fn native_integration_parameters(&self, dt: f32) -> rapier3d::dynamics::IntegrationParameters {
+        rapier3d::dynamics::IntegrationParameters {
+            dt,
+            min_ccd_dt: self.integration_parameters.min_ccd_dt,
+            erp: self.integration_parameters.erp,
+            damping_ratio: self.integration_parameters.damping_ratio,
+            joint_erp: self.integration_parameters.joint_erp,
+            joint_damping_ratio: self.integration_parameters.joint_damping_ratio,
+            allowed_linear_error: self.integration_parameters.allowed_linear_error,
+            max_penetration_correction: self.integration_parameters.max_penetration_correction,
+            prediction_distance: self.integration_parameters.prediction_distance,
+            max_velocity_iterations: self.integration_parameters.max_velocity_iterations as usize,
+            max_velocity_friction_iterations: self
+                .integration_parameters
+                .max_velocity_friction_iterations
+                as usize,
+            max_stabilization_iterations: self.integration_parameters.max_stabilization_iterations
+                as usize,
+            interleave_restitution_and_friction_resolution: self
+                .integration_parameters
+                .interleave_restitution_and_friction_resolution,
+            min_island_size: self.integration_parameters.min_island_size as usize,
+            max_ccd_substeps: self.integration_parameters.max_ccd_substeps as usize,
+        }
+    }
+
+    fn step(&mut self, dt: f32) {
+        let integration_parameters = self.native_integration_parameters(dt);
+
+        self.update_vehicles(dt);
+        self.update_water_volumes(dt);
+        self.update_spring_joints(dt);
+        self.apply_center_of_mass_overrides();
+
+        let hooks_adapter = self.hooks.as_deref().map(HooksAdapter);
+        let hooks: &dyn rapier3d::pipeline::PhysicsHooks = match hooks_adapter.as_ref() {
+            Some(hooks_adapter) => hooks_adapter,
+            None => &(),
+        };
+
+        self.pipeline.step(
+            &self.gravity,
+            &integration_parameters,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders.set,
+            &mut self.joints.set,
+            &mut self.multibody_joints.set,
+            &mut self.ccd_solver,
+            // In Rapier 0.17 passing query pipeline here sometimes causing panic in numeric overflow,
+            // so we keep updating it manually.
+            None,
+            hooks,
+            &self.event_collector,
+        );
+
+        self.break_joints(dt);
+    }
+
+    // Removes every joint, which force or torque at the last simulation step exceeds its limits.
+    fn break_joints(&mut self, dt: f32) {
+        if self.breakable_joints.is_empty() || dt <= 0.0 {
+            return;
+        }
+
+        let mut broken_joints = Vec::new();
+        for (handle, (break_force, break_torque)) in self.breakable_joints.iter() {
+            let joint = match self.joints.set.get(*handle) {
+                Some(joint) => joint,
+                None => continue,
             };
 
-            self.pipeline.step(
-                &self.gravity,
-                &integration_parameters,
-                &mut self.islands,
-                &mut self.broad_phase,
-                &mut self.narrow_phase,
+            let force = joint.impulses.fixed_rows::<3>(0).norm() / dt;
+            let torque = joint.impulses.fixed_rows::<3>(3).norm() / dt;
+            if break_force.map_or(false, |limit| force > limit)
+                || break_torque.map_or(false, |limit| torque > limit)
+            {
+                broken_joints.push((*handle, force, torque));
+            }
+        }
+
+        for (handle, force, torque) in broken_joints {
+            if let Some(&joint) = self.joints.map.value_of(&handle) {
+                self.joint_break_events.push(JointBreakEvent {
+                    joint,
+                    force,
+                    torque,
+                });
+            }
+            self.remove_joint(handle);
+        }
+    }
+
+    // Keeps mass properties of colliders of every rigid body with overridden center of mass up to date,
+    // densities and shapes of the colliders could change at any time.
+    fn apply_center_of_mass_overrides(&mut self) {
+        for (handle, center_of_mass) in self.center_of_mass_overrides.iter() {
+            if let Some(body) = self.bodies.get(*handle) {
+                apply_center_of_mass_override(&mut self.colliders.set, body, *center_of_mass);
+            }
+        }
+    }
+
+    // Applies forces of every spring joint to its bodies. The physics engine does not support springs
+    // with non-zero rest length, so spring joints are native joints without restrictions and the
+    // force is calculated manually.
+    fn update_spring_joints(&mut self, dt: f32) {
+        for (handle, spring) in self.spring_joints.iter() {
+            let joint = match self.joints.set.get(*handle) {
+                Some(joint) => joint,
+                None => continue,
+            };
+            let (body1, body2) = match (self.bodies.get(joint.body1), self.bodies.get(joint.body2))
+            {
+                (Some(body1), Some(body2)) => (body1, body2),
+                _ => continue,
+            };
+
+            let anchor1 =
+                body1.position() * Point3::from(joint.data.local_frame1.translation.vector);
+            let anchor2 =
+                body2.position() * Point3::from(joint.data.local_frame2.translation.vector);
+            let delta = anchor2 - anchor1;
+            let distance = delta.norm();
+            let direction = match delta.try_normalize(f32::EPSILON) {
+                Some(direction) => direction,
+                None => continue,
+            };
+
+            let relative_velocity =
+                body2.velocity_at_point(&anchor2) - body1.velocity_at_point(&anchor1);
+            let force = spring.stiffness * (distance - spring.rest_length)
+                + spring.damping * relative_velocity.dot(&direction);
+            let impulse = direction * force * dt;
+            let (body1, body2) = (joint.body1, joint.body2);
+
+            self.bodies[body1].apply_impulse_at_point(impulse, anchor1, true);
+            self.bodies[body2].apply_impulse_at_point(-impulse, anchor2, true);
+        }
+    }
+
+    fn update_vehicles(&mut self, dt: f32) {
+        if self.vehicles.alive_count() == 0 {
+            return;
+        }
+
+        // Wheels are ray casts, so the query pipeline must be up-to-date.
+        let mut query = self.query.borrow_mut();
+        query.update(&self.bodies, &self.colliders.set);
+
+        for vehicle in self.vehicles.iter_mut() {
+            let chassis = vehicle.controller.chassis;
+            if self.bodies.get(chassis).is_none() {
+                continue;
+            }
+
+            vehicle.controller.update_vehicle(
+                dt,
                 &mut self.bodies,
-                &mut self.colliders,
-                &mut self.joints.set,
-                &mut self.multibody_joints.set,
-                &mut self.ccd_solver,
-                // In Rapier 0.17 passing query pipeline here sometimes causing panic in numeric overflow,
-                // so we keep updating it manually.
-                None,
-                &(),
-                &*self.event_handler,
+                &self.colliders.set,
+                &query,
+                QueryFilter::new().exclude_rigid_body(chassis),
             );
+
+            let chassis_position = self.bodies[chassis].position();
+            vehicle.suspension_lengths.clear();
+            for wheel in vehicle.controller.wheels() {
+                let hard_point = chassis_position * wheel.chassis_connection_point_cs;
+                let direction = chassis_position * wheel.direction_cs;
+                vehicle
+                    .suspension_lengths
+                    .push((wheel.center() - hard_point).dot(&direction));
+            }
         }
+    }
 
-        self.performance_statistics.step_time += instant::Instant::now() - time;
+    // Applies buoyancy and drag of every water volume to dynamic bodies. Submerged part of a collider
+    // is approximated by the intersection of its bounding box with the box of a volume.
+    fn update_water_volumes(&mut self, dt: f32) {
+        if self.water_volumes.alive_count() == 0 {
+            return;
+        }
+
+        for volume in self.water_volumes.iter() {
+            let volume_aabb =
+                AxisAlignedBoundingBox::from_min_max(-volume.half_extents, volume.half_extents);
+
+            // Submerged and total volume of the colliders of each body.
+            let mut submerged_bodies = FxHashMap::<RigidBodyHandle, (f32, f32)>::default();
+
+            for (_, collider) in self.colliders.set.iter() {
+                if collider.is_sensor() {
+                    continue;
+                }
+
+                let body_handle = match collider.parent() {
+                    Some(body_handle) => body_handle,
+                    None => continue,
+                };
+                let body = match self.bodies.get_mut(body_handle) {
+                    Some(body) if body.is_dynamic() => body,
+                    _ => continue,
+                };
+
+                let world_aabb = collider.compute_aabb();
+                let local_aabb = AxisAlignedBoundingBox::from_min_max(
+                    world_aabb.mins.coords,
+                    world_aabb.maxs.coords,
+                )
+                .transform(&volume.inv_transform);
+                let local_volume = local_aabb.volume();
+                if local_volume <= f32::EPSILON {
+                    continue;
+                }
+
+                let min = local_aabb.min.sup(&volume_aabb.min);
+                let max = local_aabb.max.inf(&volume_aabb.max);
+                let size = max - min;
+                if size.x <= 0.0 || size.y <= 0.0 || size.z <= 0.0 {
+                    continue;
+                }
+
+                let fraction = (size.x * size.y * size.z / local_volume).min(1.0);
+                let collider_volume = collider.shape().mass_properties(1.0).mass();
+                let submerged_volume = collider_volume * fraction;
+
+                let center = volume
+                    .transform
+                    .transform_point(&Point3::from((min + max).scale(0.5)));
+                let buoyancy = -self.gravity * volume.density * submerged_volume;
+                body.apply_impulse_at_point(buoyancy * dt, center, true);
+
+                let entry = submerged_bodies.entry(body_handle).or_default();
+                entry.0 += submerged_volume;
+                entry.1 += collider_volume;
+            }
+
+            for (body_handle, (submerged_volume, total_volume)) in submerged_bodies {
+                if total_volume <= f32::EPSILON {
+                    continue;
+                }
+
+                let fraction = submerged_volume / total_volume;
+                let body = &mut self.bodies[body_handle];
+
+                let linear_damping = (volume.linear_drag * fraction * dt).min(1.0);
+                let relative_velocity = body.linvel() - volume.flow_velocity;
+                body.apply_impulse(-relative_velocity * body.mass() * linear_damping, true);
+
+                let angular_damping = (volume.angular_drag * fraction * dt).min(1.0);
+                body.set_angvel(body.angvel() * (1.0 - angular_damping), true);
+            }
+        }
+    }
+
+    fn is_interpolating(&self) -> bool {
+        self.interpolate_transforms && self.fixed_timestep.map_or(false, |step| step > 0.0)
+    }
+
+    // Returns current and target positions of every kinematic position-based body, that should move
+    // at the next simulation step.
+    fn kinematic_motions(&self) -> Vec<(RigidBodyHandle, Isometry3<f32>, Isometry3<f32>)> {
+        self.bodies
+            .iter()
+            .filter(|(_, body)| {
+                body.body_type() == RigidBodyType::KinematicPositionBased
+                    && body.next_position() != body.position()
+            })
+            .map(|(handle, body)| (handle, *body.position(), *body.next_position()))
+            .collect()
+    }
+
+    fn save_interpolation_states(&mut self) {
+        for (handle, body) in self.bodies.iter() {
+            if is_simulated_body_type(body.body_type()) {
+                let position = *body.position();
+                let state = self
+                    .interpolation_states
+                    .entry(handle)
+                    .or_insert(InterpolationState {
+                        previous: position,
+                        interpolated: position,
+                    });
+                state.previous = position;
+            }
+        }
+    }
+
+    /// Returns interpolation factor between previous and current physics states, which is the
+    /// amount of accumulated (not simulated yet) time divided by the fixed time step. It is always
+    /// `1.0` if the fixed time step is not set.
+    pub fn interpolation_factor(&self) -> f32 {
+        self.interpolation_factor
+    }
+
+    fn translate_events(&mut self) {
+        self.contact_events.clear();
+        self.intersection_events.clear();
+
+        let colliders = &self.colliders;
+        let removed_colliders = &self.removed_colliders;
+        let node_handle = |native: ColliderHandle| {
+            colliders
+                .map
+                .value_of(&native)
+                .or_else(|| removed_colliders.get(&native))
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        for event in self.event_collector.collision_events.lock().drain(..) {
+            let collider1 = node_handle(event.collider1());
+            let collider2 = node_handle(event.collider2());
+            let kind = if event.started() {
+                CollisionEventKind::Started
+            } else {
+                CollisionEventKind::Stopped
+            };
+            if event.sensor() {
+                self.intersection_events.push(IntersectionEvent {
+                    collider1,
+                    collider2,
+                    kind,
+                    removed: event.removed(),
+                });
+            } else {
+                self.contact_events.push(ContactEvent {
+                    collider1,
+                    collider2,
+                    kind,
+                    removed: event.removed(),
+                });
+            }
+        }
+
+        self.removed_colliders.clear();
+    }
+
+    /// Returns a list of contact events (non-sensor colliders started or stopped touching each other)
+    /// produced during the last simulation step. Participants of the events are translated into
+    /// collider node handles.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::scene::graph::{physics::CollisionEventKind, Graph};
+    ///
+    /// fn print_contacts(graph: &Graph) {
+    ///     for event in graph.physics.contact_events() {
+    ///         if event.kind == CollisionEventKind::Started {
+    ///             println!("{} hit {}", graph[event.collider1].name(), graph[event.collider2].name());
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn contact_events(&self) -> &[ContactEvent] {
+        &self.contact_events
+    }
+
+    /// Returns a list of intersection events (a sensor collider started or stopped intersecting with
+    /// some other collider) produced during the last simulation step. Participants of the events are
+    /// translated into collider node handles.
+    pub fn intersection_events(&self) -> &[IntersectionEvent] {
+        &self.intersection_events
+    }
+
+    /// Returns a list of joints that were broken during the last update of the physics world, because
+    /// their break force or torque was exceeded. Broken joints are marked as broken right after the
+    /// physics update, see [`crate::scene::joint::Joint::is_broken`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use fyrox::scene::graph::Graph;
+    ///
+    /// fn print_broken_joints(graph: &Graph) {
+    ///     for event in graph.physics.joint_break_events() {
+    ///         println!("{} broke with {} N", graph[event.joint].name(), event.force);
+    ///     }
+    /// }
+    /// ```
+    pub fn joint_break_events(&self) -> &[JointBreakEvent] {
+        &self.joint_break_events
+    }
+
+    /// Sets new physics hooks, that will be used to filter and modify contacts between colliders. Pass
+    /// `None` to remove current hooks. Returns previous hooks. See [`PhysicsHooks`] docs for more info.
+    pub fn set_hooks(
+        &mut self,
+        hooks: Option<Box<dyn PhysicsHooks>>,
+    ) -> Option<Box<dyn PhysicsHooks>> {
+        let active_hooks = active_hooks(hooks.is_some());
+        for (_, collider) in self.colliders.set.iter_mut() {
+            collider.set_active_hooks(active_hooks);
+        }
+        std::mem::replace(&mut self.hooks, hooks)
+    }
+
+    /// Returns a reference to current physics hooks (if any).
+    pub fn hooks(&self) -> Option<&dyn PhysicsHooks> {
+        self.hooks.as_deref()
+    }
+
+    /// Adds new subscriber of the events about creation and removal of native physics entities.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use std::sync::mpsc::channel;
+    /// # use fyrox::{
+    /// #     core::algebra::Vector2,
+    /// #     scene::{
+    /// #         base::BaseBuilder,
+    /// #         graph::{physics::NativePhysicsEvent, Graph},
+    /// #         rigidbody::RigidBodyBuilder,
+    /// #     },
+    /// # };
+    /// let mut graph = Graph::new();
+    ///
+    /// let (tx, rx) = channel();
+    /// graph.physics.subscribe_native_events(tx);
+    ///
+    /// let body = RigidBodyBuilder::new(BaseBuilder::new()).build(&mut graph);
+    /// graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+    ///
+    /// match rx.try_recv() {
+    ///     Ok(NativePhysicsEvent::RigidBodyCreated { node, .. }) => assert_eq!(node, body),
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    pub fn subscribe_native_events(&mut self, sender: Sender<NativePhysicsEvent>) {
+        self.native_event_senders.push(sender);
+    }
+
+    fn broadcast_native_event(&mut self, event: NativePhysicsEvent) {
+        self.native_event_senders
+            .retain(|sender| sender.send(event).is_ok());
     }
 
     pub(super) fn add_body(&mut self, owner: Handle<Node>, mut body: RigidBody) -> RigidBodyHandle {
         body.user_data = owner.encode_to_u128();
-        self.bodies.insert(body)
+        let handle = self.bodies.insert(body);
+        self.broadcast_native_event(NativePhysicsEvent::RigidBodyCreated {
+            node: owner,
+            native: handle,
+        });
+        handle
     }
 
     pub(crate) fn remove_body(&mut self, handle: RigidBodyHandle) {
-        self.bodies.remove(
-            handle,
-            &mut self.islands,
-            &mut self.colliders,
-            &mut self.joints.set,
-            &mut self.multibody_joints.set,
-            true,
-        );
+        if self.pending_removals.bodies.contains(&handle) {
+            return;
+        }
+
+        let mut events = Vec::new();
+
+        // Attached colliders will be removed together with the body.
+        if let Some(body) = self.bodies.get(handle) {
+            if body.is_fixed() {
+                self.static_ray_cast_cache.get_mut().invalidate();
+            }
+
+            self.pending_removals.bodies.insert(handle);
+
+            for collider in body.colliders() {
+                self.pending_removals.removed_colliders.insert(*collider);
+                if let Some(owner) = self.colliders.map.remove_by_key(collider) {
+                    self.removed_colliders.insert(*collider, owner);
+                    events.push(NativePhysicsEvent::ColliderRemoved {
+                        node: owner,
+                        native: *collider,
+                    });
+                }
+            }
+
+            // The same applies to attached joints.
+            for (_, _, joint, _) in self.joints.set.attached_joints(handle) {
+                if let Some(owner) = self.joints.map.value_of(&joint) {
+                    events.push(NativePhysicsEvent::JointRemoved {
+                        node: *owner,
+                        native: joint,
+                    });
+                }
+            }
+
+            events.push(NativePhysicsEvent::RigidBodyRemoved {
+                node: Handle::decode_from_u128(body.user_data),
+                native: handle,
+            });
+        }
+
+        self.interpolation_states.remove(&handle);
+        self.center_of_mass_overrides.remove(&handle);
+
+        for event in events {
+            if let NativePhysicsEvent::JointRemoved { native, .. } = event {
+                // Joints do not participate in queries, so there is no need to defer their removal.
+                self.breakable_joints.remove(&native);
+                self.spring_joints.remove(&native);
+                self.joints.set.remove(native, false);
+                self.joints.map.remove_by_key(&native);
+            }
+            self.broadcast_native_event(event);
+        }
     }
 
     pub(super) fn add_collider(
@@ -1074,41 +2893,229 @@ impl PhysicsWorld {
         mut collider: Collider,
     ) -> ColliderHandle {
         collider.user_data = owner.encode_to_u128();
+        collider.set_active_hooks(active_hooks(self.hooks.is_some()));
+        if self
+            .bodies
+            .get(parent_body)
+            .map_or(false, |body| body.is_fixed())
+        {
+            self.static_ray_cast_cache.get_mut().invalidate();
+        }
+        let handle = self
+            .colliders
+            .set
+            .insert_with_parent(collider, parent_body, &mut self.bodies);
+        self.colliders.map.insert(handle, owner);
+        self.broadcast_native_event(NativePhysicsEvent::ColliderCreated {
+            node: owner,
+            native: handle,
+        });
+        handle
+    }
+
+    pub(crate) fn remove_collider(&mut self, handle: ColliderHandle) -> bool {
+        if !self.colliders.set.contains(handle) || self.is_removed_collider(handle) {
+            return false;
+        }
+
+        if self.is_static_collider(handle) {
+            self.static_ray_cast_cache.get_mut().invalidate();
+        }
+
+        self.pending_removals.colliders.push(handle);
+        self.pending_removals.removed_colliders.insert(handle);
+
+        let owner = self.colliders.map.remove_by_key(&handle);
+        assert!(owner.is_some());
+        let owner = owner.unwrap();
+        self.removed_colliders.insert(handle, owner);
+        self.broadcast_native_event(NativePhysicsEvent::ColliderRemoved {
+            node: owner,
+            native: handle,
+        });
+        true
+    }
+
+    // Returns `true` if the collider was removed, but its native representation is still alive until
+    // the next simulation step.
+    fn is_removed_collider(&self, handle: ColliderHandle) -> bool {
+        !self.pending_removals.removed_colliders.is_empty()
+            && self.pending_removals.removed_colliders.contains(&handle)
+    }
+
+    // Removes native rigid bodies and colliders, that were removed since the last simulation step.
+    pub(super) fn flush_removals(&mut self) {
+        for handle in self.pending_removals.colliders.drain(..) {
+            self.colliders
+                .set
+                .remove(handle, &mut self.islands, &mut self.bodies, false);
+        }
+
+        for handle in self.pending_removals.bodies.drain() {
+            self.bodies.remove(
+                handle,
+                &mut self.islands,
+                &mut self.colliders.set,
+                &mut self.joints.set,
+                &mut self.multibody_joints.set,
+                true,
+            );
+        }
+
+        self.pending_removals.removed_colliders.clear();
+    }
+
+    /// Rebuilds acceleration structure of scene queries (ray and shape casts, etc.). The structure is
+    /// rebuilt automatically once per frame after the physics step, so colliders that were created or
+    /// moved after that are not visible for queries until the next frame. Call this method after such
+    /// changes if the queries must take them into account in the same frame. Colliders of removed nodes
+    /// are ignored by queries immediately, there is no need to call this method after removal.
+    ///
+    /// Keep in mind that the method is expensive and must not be called before every query.
+    pub fn refresh_queries(&self) {
+        self.query
+            .borrow_mut()
+            .update(&self.bodies, &self.colliders.set);
+    }
+
+    pub(super) fn add_joint(
+        &mut self,
+        owner: Handle<Node>,
+        body1: RigidBodyHandle,
+        body2: RigidBodyHandle,
+        joint: GenericJoint,
+    ) -> ImpulseJointHandle {
+        let handle = self.joints.set.insert(body1, body2, joint, false);
+        self.joints.map.insert(handle, owner);
+        self.broadcast_native_event(NativePhysicsEvent::JointCreated {
+            node: owner,
+            native: handle,
+        });
+        handle
+    }
+
+    pub(crate) fn remove_joint(&mut self, handle: ImpulseJointHandle) {
+        self.breakable_joints.remove(&handle);
+        self.spring_joints.remove(&handle);
+        if self.joints.set.remove(handle, false).is_some() {
+            let owner = self.joints.map.remove_by_key(&handle);
+            assert!(owner.is_some());
+            self.broadcast_native_event(NativePhysicsEvent::JointRemoved {
+                node: owner.unwrap(),
+                native: handle,
+            });
+        }
+    }
+
+    pub(crate) fn remove_vehicle(&mut self, handle: Handle<NativeVehicle>) {
+        self.vehicles.try_free(handle);
+    }
+
+    pub(crate) fn vehicle(&self, handle: Handle<NativeVehicle>) -> Option<&NativeVehicle> {
+        self.vehicles.try_borrow(handle)
+    }
+
+    pub(crate) fn remove_water_volume(&mut self, handle: Handle<NativeWaterVolume>) {
+        self.water_volumes.try_free(handle);
+    }
+
+    // Checks whether the given collider is attached to a fixed rigid body.
+    fn is_static_collider(&self, handle: ColliderHandle) -> bool {
         self.colliders
-            .insert_with_parent(collider, parent_body, &mut self.bodies)
+            .set
+            .get(handle)
+            .and_then(|collider| collider.parent())
+            .and_then(|body| self.bodies.get(body))
+            .map_or(false, |body| body.is_fixed())
     }
 
-    pub(crate) fn remove_collider(&mut self, handle: ColliderHandle) -> bool {
+    pub(crate) fn native_body(&self, handle: RigidBodyHandle) -> Option<&RigidBody> {
+        self.bodies
+            .get(handle)
+            .filter(|_| !self.pending_removals.bodies.contains(&handle))
+    }
+
+    pub(crate) fn native_collider(&self, handle: ColliderHandle) -> Option<&Collider> {
         self.colliders
-            .remove(handle, &mut self.islands, &mut self.bodies, false)
-            .is_some()
+            .set
+            .get(handle)
+            .filter(|_| !self.is_removed_collider(handle))
     }
 
-    pub(super) fn add_joint(
-        &mut self,
-        owner: Handle<Node>,
-        body1: RigidBodyHandle,
-        body2: RigidBodyHandle,
-        joint: GenericJoint,
-    ) -> ImpulseJointHandle {
-        let handle = self.joints.set.insert(body1, body2, joint, false);
-        self.joints.map.insert(handle, owner);
-        handle
+    pub(crate) fn native_joint(&self, handle: ImpulseJointHandle) -> Option<&ImpulseJoint> {
+        self.joints.set.get(handle)
     }
 
-    pub(crate) fn remove_joint(&mut self, handle: ImpulseJointHandle) {
-        if self.joints.set.remove(handle, false).is_some() {
-            assert!(self.joints.map.remove_by_key(&handle).is_some());
+    // Copies runtime state (velocities, accumulated forces, activation state) of the given native
+    // rigid body (possibly from other physics world) to the rigid body with the given handle.
+    pub(crate) fn copy_rigid_body_state(&mut self, source: &RigidBody, dest: RigidBodyHandle) {
+        if let Some(dest) = self.bodies.get_mut(dest) {
+            dest.set_linvel(*source.linvel(), false);
+            dest.set_angvel(*source.angvel(), false);
+            dest.reset_forces(false);
+            dest.reset_torques(false);
+            dest.add_force(source.user_force(), false);
+            dest.add_torque(source.user_torque(), false);
+            *dest.activation_mut() = *source.activation();
+        }
+    }
+
+    // Copies accumulated impulses of the given native joint (possibly from other physics world) to
+    // the joint with the given handle.
+    pub(crate) fn copy_joint_state(&mut self, source: &ImpulseJoint, dest: ImpulseJointHandle) {
+        if let Some(dest) = self.joints.set.get_mut(dest) {
+            dest.impulses = source.impulses;
+        }
+    }
+
+    /// Sets new gravity vector of the physics world and wakes up every dynamic rigid body, so the
+    /// new gravity is applied to sleeping bodies as well. Returns previous gravity vector. Zero
+    /// vector could be used to create zero-G scenes, per-body gravity could be tweaked using
+    /// gravity scale of rigid bodies.
+    pub fn set_gravity(&mut self, gravity: Vector3<f32>) -> Vector3<f32> {
+        for (_, body) in self.bodies.iter_mut() {
+            if body.is_dynamic() {
+                body.wake_up(true);
+            }
         }
+        std::mem::replace(&mut self.gravity, gravity)
+    }
+
+    /// Returns current gravity vector of the physics world.
+    pub fn gravity(&self) -> Vector3<f32> {
+        self.gravity
+    }
+
+    /// Sets a set of flags that defines which parts of the physics world are drawn by [`Self::draw`].
+    /// Default flags are [`PhysicsDebugDrawFlags::BODIES`], [`PhysicsDebugDrawFlags::COLLIDERS`],
+    /// [`PhysicsDebugDrawFlags::JOINTS`] and [`PhysicsDebugDrawFlags::SLEEPING`].
+    pub fn set_debug_draw_flags(&mut self, flags: PhysicsDebugDrawFlags) -> PhysicsDebugDrawFlags {
+        std::mem::replace(&mut self.debug_draw_flags, flags)
+    }
+
+    /// Returns a set of flags that defines which parts of the physics world are drawn by [`Self::draw`].
+    pub fn debug_draw_flags(&self) -> PhysicsDebugDrawFlags {
+        self.debug_draw_flags
     }
 
     /// Draws physics world. Very useful for debugging, it allows you to see where are
-    /// rigid bodies, which colliders they have and so on.
+    /// rigid bodies, which colliders they have and so on. See [`Self::set_debug_draw_flags`] to
+    /// choose what will be drawn.
     pub fn draw(&self, context: &mut SceneDrawingContext) {
-        self.debug_render_pipeline.lock().render(
+        let mut pipeline = self.debug_render_pipeline.lock();
+        pipeline.mode = self.debug_draw_flags.render_mode();
+        pipeline.style.sleep_color_multiplier = if self
+            .debug_draw_flags
+            .contains(PhysicsDebugDrawFlags::SLEEPING)
+        {
+            SLEEP_COLOR_MULTIPLIER
+        } else {
+            [1.0; 4]
+        };
+        pipeline.render(
             context,
             &self.bodies,
-            &self.colliders,
+            &self.colliders.set,
             &self.joints.set,
             &self.multibody_joints.set,
             &self.narrow_phase,
@@ -1119,14 +3126,92 @@ impl PhysicsWorld {
     pub fn cast_ray<S: QueryResultsStorage>(&self, opts: RayCastOptions, query_buffer: &mut S) {
         let time = instant::Instant::now();
 
-        let mut query = self.query.borrow_mut();
+        self.cast_ray_internal(&opts, false, query_buffer);
+
+        self.performance_statistics.total_ray_cast_time.set(
+            self.performance_statistics.total_ray_cast_time.get()
+                + (instant::Instant::now() - time),
+        );
+    }
+
+    /// Casts a ray with given options against fixed colliders only (colliders of dynamic and kinematic
+    /// rigid bodies are ignored) and caches the results. Consecutive casts of the same ray are served
+    /// from the cache until static geometry of the world changes, which makes the method suitable for
+    /// systems that re-query the same set of rays for many frames (AI visibility grids, light probes
+    /// placement, etc.).
+    ///
+    /// ## Important notes
+    ///
+    /// - Ray parameters are quantized using [`RAY_CAST_CACHE_QUANTUM`], so rays that differ less than
+    /// the quantum are considered equal and share the results.
+    /// - Results of casts with [`RayCastOptions::filter`] are not cached, because the filter could return
+    /// different values for the same collider.
+    /// - The cache is flushed entirely when it reaches its capacity, see
+    /// [`Self::set_ray_cast_cache_capacity`].
+    pub fn cast_ray_cached<S: QueryResultsStorage>(
+        &self,
+        opts: RayCastOptions,
+        query_buffer: &mut S,
+    ) {
+        let time = instant::Instant::now();
+
+        if opts.filter.is_some() {
+            self.cast_ray_internal(&opts, true, query_buffer);
+        } else {
+            let key = RayCastCacheKey::new(&opts);
+            let mut cache = self.static_ray_cast_cache.borrow_mut();
+            if !cache.entries.contains_key(&key) {
+                if cache.entries.len() >= cache.capacity {
+                    cache.invalidate();
+                }
+                let mut intersections = Vec::new();
+                self.cast_ray_internal(&opts, true, &mut intersections);
+                cache.entries.insert(key, intersections);
+            }
+
+            query_buffer.clear();
+            for intersection in cache.entries[&key].iter() {
+                if !query_buffer.push(intersection.clone()) {
+                    break;
+                }
+            }
+        }
+
+        self.performance_statistics.total_ray_cast_time.set(
+            self.performance_statistics.total_ray_cast_time.get()
+                + (instant::Instant::now() - time),
+        );
+    }
+
+    /// Sets maximum amount of rays, whose results could be stored in the cache of
+    /// [`Self::cast_ray_cached`]. Default is 16384.
+    pub fn set_ray_cast_cache_capacity(&mut self, capacity: usize) {
+        let cache = self.static_ray_cast_cache.get_mut();
+        cache.capacity = capacity;
+        if cache.entries.len() > capacity {
+            cache.invalidate();
+        }
+    }
+
+    /// Returns maximum amount of rays, whose results could be stored in the cache of
+    /// [`Self::cast_ray_cached`].
+    pub fn ray_cast_cache_capacity(&self) -> usize {
+        self.static_ray_cast_cache.borrow().capacity
+    }
+
+    /// Removes every cached ray cast result, see [`Self::cast_ray_cached`]. There is no need to call
+    /// this method manually, the cache is flushed automatically when static geometry changes.
+    pub fn clear_ray_cast_cache(&self) {
+        self.static_ray_cast_cache.borrow_mut().invalidate();
+    }
 
-        // TODO: Ideally this must be called once per frame, but it seems to be impossible because
-        // a body can be deleted during the consecutive calls of this method which will most
-        // likely end up in panic because of invalid handle stored in internal acceleration
-        // structure. This could be fixed by delaying deleting of bodies/collider to the end
-        // of the frame.
-        query.update(&self.bodies, &self.colliders);
+    fn cast_ray_internal<S: QueryResultsStorage>(
+        &self,
+        opts: &RayCastOptions,
+        only_fixed: bool,
+        query_buffer: &mut S,
+    ) {
+        let query = self.query.borrow();
 
         query_buffer.clear();
         let ray = Ray::new(
@@ -1135,25 +3220,38 @@ impl PhysicsWorld {
                 .try_normalize(f32::EPSILON)
                 .unwrap_or_default(),
         );
+        let predicate = |handle: ColliderHandle, collider: &Collider| {
+            !self.is_removed_collider(handle)
+                && opts.filter.map_or(true, |filter| {
+                    filter(Handle::decode_from_u128(collider.user_data))
+                })
+        };
         query.intersections_with_ray(
             &self.bodies,
-            &self.colliders,
+            &self.colliders.set,
             &ray,
             opts.max_len,
             true,
-            QueryFilter::new().groups(InteractionGroups::new(
+            if only_fixed {
+                QueryFilter::only_fixed()
+            } else {
+                QueryFilter::new()
+            }
+            .groups(InteractionGroups::new(
                 u32_to_group(opts.groups.memberships.0),
                 u32_to_group(opts.groups.filter.0),
-            )),
+            ))
+            .predicate(&predicate),
             |handle, intersection| {
+                let collider = self.colliders.set.get(handle).unwrap();
+                let position = ray.point_at(intersection.toi);
                 query_buffer.push(Intersection {
-                    collider: Handle::decode_from_u128(
-                        self.colliders.get(handle).unwrap().user_data,
-                    ),
+                    collider: Handle::decode_from_u128(collider.user_data),
                     normal: intersection.normal,
-                    position: ray.point_at(intersection.toi),
+                    position,
                     feature: intersection.feature.into(),
                     toi: intersection.toi,
+                    triangle: triangle_hit(collider, intersection.feature, &position),
                 })
             },
         );
@@ -1168,6 +3266,181 @@ impl PhysicsWorld {
                 }
             })
         }
+    }
+
+    // Searches for dynamic rigid bodies with at least one collider in the given sphere. Returns a
+    // handle of each body node, distance from the center of the sphere to the closest collider of the
+    // body and world-space center of mass of the body.
+    pub(crate) fn dynamic_bodies_in_sphere(
+        &self,
+        center: Point3<f32>,
+        radius: f32,
+        groups: collider::InteractionGroups,
+    ) -> Vec<(Handle<Node>, f32, Vector3<f32>)> {
+        let query = self.query.borrow();
+
+        let predicate = |handle: ColliderHandle, _: &Collider| !self.is_removed_collider(handle);
+        let mut distances = FxHashMap::<RigidBodyHandle, f32>::default();
+        query.intersections_with_shape(
+            &self.bodies,
+            &self.colliders.set,
+            &Isometry3::translation(center.x, center.y, center.z),
+            &Ball::new(radius),
+            QueryFilter::only_dynamic()
+                .groups(InteractionGroups::new(
+                    u32_to_group(groups.memberships.0),
+                    u32_to_group(groups.filter.0),
+                ))
+                .predicate(&predicate),
+            |handle| {
+                if let Some(collider) = self.colliders.set.get(handle) {
+                    if let Some(body) = collider.parent() {
+                        let distance =
+                            collider
+                                .shape()
+                                .distance_to_point(collider.position(), &center, true);
+                        let closest = distances.entry(body).or_insert(f32::MAX);
+                        *closest = closest.min(distance);
+                    }
+                }
+                true
+            },
+        );
+
+        distances
+            .into_iter()
+            .filter_map(|(body, distance)| {
+                self.bodies.get(body).map(|body| {
+                    (
+                        Handle::decode_from_u128(body.user_data),
+                        distance,
+                        body.center_of_mass().coords,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    // Collects handles of every non-sensor collider node that overlaps the given shape into the storage.
+    // Stops at the first overlap if `first_only` is set.
+    pub(crate) fn overlapping_colliders(
+        &self,
+        shape: &SharedShape,
+        shape_position: &Isometry3<f32>,
+        groups: collider::InteractionGroups,
+        filter: Option<&dyn Fn(Handle<Node>) -> bool>,
+        first_only: bool,
+        colliders: &mut Vec<Handle<Node>>,
+    ) {
+        let query = self.query.borrow();
+
+        let predicate = |handle: ColliderHandle, collider: &Collider| {
+            !self.is_removed_collider(handle)
+                && filter.map_or(true, |filter| {
+                    filter(Handle::decode_from_u128(collider.user_data))
+                })
+        };
+        query.intersections_with_shape(
+            &self.bodies,
+            &self.colliders.set,
+            shape_position,
+            &*shape.0,
+            QueryFilter::new()
+                .exclude_sensors()
+                .groups(InteractionGroups::new(
+                    u32_to_group(groups.memberships.0),
+                    u32_to_group(groups.filter.0),
+                ))
+                .predicate(&predicate),
+            |handle| {
+                colliders.push(Handle::decode_from_u128(
+                    self.colliders.set.get(handle).unwrap().user_data,
+                ));
+                !first_only
+            },
+        );
+    }
+
+    // Collects handles of every collider node, which bounding box intersects the given region.
+    pub(crate) fn colliders_in_region(
+        &self,
+        region: &AxisAlignedBoundingBox,
+        colliders: &mut Vec<Handle<Node>>,
+    ) {
+        let query = self.query.borrow();
+        query.colliders_with_aabb_intersecting_aabb(
+            &Aabb::new(Point3::from(region.min), Point3::from(region.max)),
+            |handle| {
+                // The acceleration structure could contain removed colliders.
+                if let Some(collider) = self.colliders.set.get(*handle) {
+                    if !self.is_removed_collider(*handle) {
+                        colliders.push(Handle::decode_from_u128(collider.user_data));
+                    }
+                }
+                true
+            },
+        );
+    }
+
+    /// Casts a shape with the given initial position along the given velocity. Results are always sorted from
+    /// closest to farthest. Unlike ray cast, intersections contain positions and normals on the surface of
+    /// the hit colliders and [`FeatureId::Unknown`] feature.
+    pub(crate) fn cast_shape<S: QueryResultsStorage>(
+        &self,
+        shape: &SharedShape,
+        shape_position: &Isometry3<f32>,
+        velocity: &Vector3<f32>,
+        opts: ShapeCastOptions,
+        query_buffer: &mut S,
+    ) {
+        let time = instant::Instant::now();
+
+        let query = self.query.borrow();
+
+        query_buffer.clear();
+        let groups = InteractionGroups::new(
+            u32_to_group(opts.groups.memberships.0),
+            u32_to_group(opts.groups.filter.0),
+        );
+        // Rapier returns only the closest hit, so the cast is repeated excluding colliders that were
+        // already hit.
+        let mut excluded = Vec::new();
+        loop {
+            let predicate = |handle: ColliderHandle, _: &Collider| {
+                !excluded.contains(&handle) && !self.is_removed_collider(handle)
+            };
+            let hit = query.cast_shape(
+                &self.bodies,
+                &self.colliders.set,
+                shape_position,
+                velocity,
+                &*shape.0,
+                opts.max_toi,
+                opts.stop_at_penetration,
+                QueryFilter::new().groups(groups).predicate(&predicate),
+            );
+
+            let (handle, toi) = match hit {
+                Some(hit) => hit,
+                None => break,
+            };
+
+            // Witness points and normals are already in world space.
+            if !query_buffer.push(Intersection {
+                collider: Handle::decode_from_u128(
+                    self.colliders.set.get(handle).unwrap().user_data,
+                ),
+                normal: toi.normal1.into_inner(),
+                position: toi.witness1,
+                feature: FeatureId::Unknown,
+                toi: toi.toi,
+                triangle: None,
+            }) {
+                break;
+            }
+
+            excluded.push(handle);
+        }
 
         self.performance_statistics.total_ray_cast_time.set(
             self.performance_statistics.total_ray_cast_time.get()
@@ -1181,12 +3454,32 @@ impl PhysicsWorld {
         new_global_transform: &Matrix4<f32>,
     ) {
         if let Some(native) = self.bodies.get_mut(rigid_body.native.get()) {
-            native.set_position(
-                isometry_from_global_transform(new_global_transform),
-                // Do not wake up body, it is too expensive and must be done **only** by explicit
-                // `wake_up` call!
-                false,
-            );
+            let position = isometry_from_global_transform(new_global_transform);
+            if let Some(state) = self.interpolation_states.get_mut(&rigid_body.native.get()) {
+                if isometry_approx_eq(&state.interpolated, &position) {
+                    // The transform was changed by interpolation, not by the user.
+                    return;
+                }
+                // Prevent interpolation from the position before teleportation.
+                state.previous = position;
+                state.interpolated = position;
+            }
+            if native.is_fixed() {
+                self.static_ray_cast_cache.get_mut().invalidate();
+            }
+            if native.body_type() == RigidBodyType::KinematicPositionBased {
+                // Kinematic bodies must be moved smoothly, so the physics engine could calculate
+                // their velocities and push dynamic bodies correctly instead of teleporting
+                // through them.
+                native.set_next_kinematic_position(position);
+            } else {
+                native.set_position(
+                    position,
+                    // Do not wake up body, it is too expensive and must be done **only** by explicit
+                    // `wake_up` call!
+                    false,
+                );
+            }
         }
     }
 
@@ -1195,13 +3488,33 @@ impl PhysicsWorld {
         rigid_body: &mut scene::rigidbody::RigidBody,
         parent_transform: Matrix4<f32>,
     ) {
+        if let Some(native) = self.bodies.get(rigid_body.native.get()) {
+            let mass_properties = &native.mass_properties().local_mprops;
+            rigid_body.mass_properties = RigidBodyMassProperties {
+                mass: mass_properties.mass(),
+                local_center_of_mass: mass_properties.local_com.coords,
+                center_of_mass: native.center_of_mass().coords,
+                principal_inertia: mass_properties.principal_inertia(),
+                principal_inertia_local_frame: mass_properties.principal_inertia_local_frame,
+            };
+        }
+
         if self.enabled {
             if let Some(native) = self.bodies.get(rigid_body.native.get()) {
-                if native.body_type() == RigidBodyType::Dynamic {
+                if is_simulated_body_type(native.body_type()) {
+                    let mut position = *native.position();
+                    if let Some(state) = self.interpolation_states.get_mut(&rigid_body.native.get())
+                    {
+                        position = state
+                            .previous
+                            .lerp_slerp(&position, self.interpolation_factor);
+                        state.interpolated = position;
+                    }
+
                     let local_transform: Matrix4<f32> = parent_transform
                         .try_inverse()
                         .unwrap_or_else(Matrix4::identity)
-                        * native.position().to_homogeneous();
+                        * position.to_homogeneous();
 
                     let local_rotation = UnitQuaternion::from_matrix_eps(
                         &local_transform.basis(),
@@ -1251,9 +3564,12 @@ impl PhysicsWorld {
                 if let Some(native) = self.bodies.get_mut(rigid_body_node.native.get()) {
                     // Sync native rigid body's properties with scene node's in case if they
                     // were changed by user.
-                    rigid_body_node
+                    if rigid_body_node
                         .body_type
-                        .try_sync_model(|v| native.set_body_type(v.into(), false));
+                        .try_sync_model(|v| native.set_body_type(v.into(), false))
+                    {
+                        self.static_ray_cast_cache.get_mut().invalidate();
+                    }
                     rigid_body_node
                         .lin_vel
                         .try_sync_model(|v| native.set_linvel(v, false));
@@ -1263,31 +3579,78 @@ impl PhysicsWorld {
                     rigid_body_node
                         .mass
                         .try_sync_model(|v| native.set_additional_mass(v, true));
+                    let native_handle = rigid_body_node.native.get();
+                    rigid_body_node.local_center_of_mass.try_sync_model(|v| {
+                        if let Some(center_of_mass) = v {
+                            self.center_of_mass_overrides
+                                .insert(native_handle, center_of_mass);
+                        } else if self
+                            .center_of_mass_overrides
+                            .remove(&native_handle)
+                            .is_some()
+                        {
+                            // Restore mass properties calculated from densities of the colliders.
+                            for collider in native.colliders() {
+                                if let Some(collider) = self.colliders.set.get_mut(*collider) {
+                                    collider.set_density(collider.density());
+                                }
+                            }
+                        }
+                    });
                     rigid_body_node
                         .lin_damping
                         .try_sync_model(|v| native.set_linear_damping(v));
                     rigid_body_node
                         .ang_damping
                         .try_sync_model(|v| native.set_angular_damping(v));
-                    rigid_body_node
-                        .ccd_enabled
-                        .try_sync_model(|v| native.enable_ccd(v));
-                    rigid_body_node.can_sleep.try_sync_model(|v| {
+                    let changes = rigid_body_node.changes.replace(RigidBodyChanges::NONE);
+                    if rigid_body_node.ccd_enabled.try_sync_model(|_| ())
+                        || changes.contains(RigidBodyChanges::CCD)
+                    {
+                        native.enable_ccd(rigid_body_node.is_ccd_enabled());
+                    }
+                    // Sleep thresholds are used only if the body can sleep, so these properties
+                    // must be synced together.
+                    let can_sleep_changed = rigid_body_node.can_sleep.try_sync_model(|_| ());
+                    let linear_threshold_changed = rigid_body_node
+                        .sleep_linear_threshold
+                        .try_sync_model(|_| ());
+                    let angular_threshold_changed = rigid_body_node
+                        .sleep_angular_threshold
+                        .try_sync_model(|_| ());
+                    if can_sleep_changed || linear_threshold_changed || angular_threshold_changed {
                         let activation = native.activation_mut();
-                        if v {
-                            activation.linear_threshold =
-                                RigidBodyActivation::default_linear_threshold();
+                        if rigid_body_node.is_can_sleep() {
+                            activation.linear_threshold = rigid_body_node.sleep_linear_threshold();
                             activation.angular_threshold =
-                                RigidBodyActivation::default_angular_threshold();
+                                rigid_body_node.sleep_angular_threshold();
                         } else {
                             activation.sleeping = false;
                             activation.linear_threshold = -1.0;
                             activation.angular_threshold = -1.0;
                         };
-                    });
+                    }
                     rigid_body_node
-                        .translation_locked
-                        .try_sync_model(|v| native.lock_translations(v, false));
+                        .time_until_sleep
+                        .try_sync_model(|v| native.activation_mut().time_until_sleep = v);
+                    // Per-axis translation locks are combined with the full lock, so these
+                    // properties must be synced together.
+                    let translation_locked_changed =
+                        rigid_body_node.translation_locked.try_sync_model(|_| ());
+                    let x_translation_locked_changed =
+                        rigid_body_node.x_translation_locked.try_sync_model(|_| ());
+                    let y_translation_locked_changed =
+                        rigid_body_node.y_translation_locked.try_sync_model(|_| ());
+                    let z_translation_locked_changed =
+                        rigid_body_node.z_translation_locked.try_sync_model(|_| ());
+                    if translation_locked_changed
+                        || x_translation_locked_changed
+                        || y_translation_locked_changed
+                        || z_translation_locked_changed
+                    {
+                        let [x, y, z] = rigid_body_node.locked_translations();
+                        native.set_enabled_translations(!x, !y, !z, false);
+                    }
                     rigid_body_node.x_rotation_locked.try_sync_model(|v| {
                         native.set_enabled_rotations(
                             !v,
@@ -1317,7 +3680,7 @@ impl PhysicsWorld {
                         .try_sync_model(|v| native.set_dominance_group(v));
                     rigid_body_node
                         .gravity_scale
-                        .try_sync_model(|v| native.set_gravity_scale(v, false));
+                        .try_sync_model(|v| native.set_gravity_scale(v, true));
 
                     // We must reset any forces applied at previous update step, otherwise physics engine
                     // will keep pushing the rigid body infinitely.
@@ -1326,34 +3689,56 @@ impl PhysicsWorld {
                         native.reset_torques(false);
                     }
 
+                    // Forces and impulses must wake up the body, otherwise they're ignored by
+                    // sleeping bodies.
                     while let Some(action) = actions.pop_front() {
                         match action {
                             ApplyAction::Force(force) => {
-                                native.add_force(force, false);
+                                native.add_force(force, true);
                                 rigid_body_node.reset_forces.set(true);
                             }
                             ApplyAction::Torque(torque) => {
-                                native.add_torque(torque, false);
+                                native.add_torque(torque, true);
                                 rigid_body_node.reset_forces.set(true);
                             }
                             ApplyAction::ForceAtPoint { force, point } => {
-                                native.add_force_at_point(force, Point3::from(point), false);
+                                native.add_force_at_point(force, Point3::from(point), true);
                                 rigid_body_node.reset_forces.set(true);
                             }
-                            ApplyAction::Impulse(impulse) => native.apply_impulse(impulse, false),
+                            ApplyAction::Impulse(impulse) => native.apply_impulse(impulse, true),
                             ApplyAction::TorqueImpulse(impulse) => {
-                                native.apply_torque_impulse(impulse, false)
+                                native.apply_torque_impulse(impulse, true)
                             }
                             ApplyAction::ImpulseAtPoint { impulse, point } => {
-                                native.apply_impulse_at_point(impulse, Point3::from(point), false)
+                                native.apply_impulse_at_point(impulse, Point3::from(point), true)
                             }
                             ApplyAction::WakeUp => native.wake_up(true),
+                            ApplyAction::Sleep => {
+                                if rigid_body_node.is_can_sleep() {
+                                    native.sleep()
+                                }
+                            }
+                            ApplyAction::RecomputeMassProperties => {
+                                if let Some(center_of_mass) =
+                                    self.center_of_mass_overrides.get(&native_handle)
+                                {
+                                    apply_center_of_mass_override(
+                                        &mut self.colliders.set,
+                                        native,
+                                        *center_of_mass,
+                                    );
+                                }
+                                native
+                                    .recompute_mass_properties_from_colliders(&self.colliders.set);
+                            }
                         }
                     }
                 }
             }
         } else {
-            let mut builder = RigidBodyBuilder::new(rigid_body_node.body_type().into())
+            let [x_translation_locked, y_translation_locked, z_translation_locked] =
+                rigid_body_node.locked_translations();
+            let builder = RigidBodyBuilder::new(rigid_body_node.body_type().into())
                 .position(isometry_from_global_transform(
                     &rigid_body_node.global_transform(),
                 ))
@@ -1371,15 +3756,27 @@ impl PhysicsWorld {
                     !rigid_body_node.is_x_rotation_locked(),
                     !rigid_body_node.is_y_rotation_locked(),
                     !rigid_body_node.is_z_rotation_locked(),
+                )
+                .enabled_translations(
+                    !x_translation_locked,
+                    !y_translation_locked,
+                    !z_translation_locked,
                 );
 
-            if rigid_body_node.is_translation_locked() {
-                builder = builder.lock_translations();
+            let mut native = builder.build();
+            let activation = native.activation_mut();
+            if rigid_body_node.is_can_sleep() {
+                activation.linear_threshold = rigid_body_node.sleep_linear_threshold();
+                activation.angular_threshold = rigid_body_node.sleep_angular_threshold();
             }
+            activation.time_until_sleep = rigid_body_node.time_until_sleep();
 
-            rigid_body_node
-                .native
-                .set(self.add_body(handle, builder.build()));
+            let native_handle = self.add_body(handle, native);
+            rigid_body_node.native.set(native_handle);
+            if let Some(center_of_mass) = rigid_body_node.local_center_of_mass() {
+                self.center_of_mass_overrides
+                    .insert(native_handle, center_of_mass);
+            }
 
             Log::writeln(
                 MessageKind::Information,
@@ -1403,8 +3800,61 @@ impl PhysicsWorld {
             return;
         }
 
-        let anything_changed =
-            collider_node.transform_modified.get() || collider_node.needs_sync_model();
+        let parent_body = find_collider_parent_body(nodes, collider_node);
+
+        // The collider could be moved to another rigid body by re-linking any of its ancestors, in
+        // this case its native collider must be re-created.
+        if let Some(native) = self.colliders.set.get(collider_node.native.get()) {
+            if native.parent() != parent_body.map(|(body, _)| body.native.get()) {
+                self.remove_collider(collider_node.native.get());
+                collider_node.native.set(ColliderHandle::invalid());
+            }
+        }
+
+        // Position of the collider must be checked each frame, because transform of any
+        // intermediate node between the collider and its rigid body could change.
+        let position_changed = match (
+            parent_body,
+            self.colliders.set.get(collider_node.native.get()),
+        ) {
+            (Some((_, position)), Some(native)) => native.position_wrt_parent() != Some(&position),
+            _ => false,
+        };
+
+        let sources_scale = geometry_sources_scale(collider_node.shape(), nodes);
+        let sources_scale_changed = sources_scale != collider_node.sources_scale.get();
+
+        // Primitive shapes have the scale of the collider baked in, they must be re-created when it
+        // changes.
+        let shape_scale = global_scale(nodes, handle);
+        let shape_scale_changed = shape_scale != collider_node.shape_scale.get();
+
+        // Terrains could be edited at runtime, height fields must follow the changes.
+        let height_maps = height_map_hashes(collider_node.shape(), nodes);
+        let height_maps_changed = *collider_node.height_map_hashes.borrow() != height_maps;
+
+        // Physics material could be changed by any of its users, so its properties must be
+        // checked each frame.
+        let surface = collider_node.surface_properties();
+        let material_changed = match (
+            collider_node.physics_material(),
+            self.colliders.set.get(collider_node.native.get()),
+        ) {
+            (Some(_), Some(native)) => {
+                native.friction() != surface.friction
+                    || native.restitution() != surface.restitution
+                    || native.friction_combine_rule() != surface.friction_combine_rule.into()
+                    || native.restitution_combine_rule() != surface.restitution_combine_rule.into()
+            }
+            _ => false,
+        };
+
+        let anything_changed = position_changed
+            || collider_node.needs_sync_model()
+            || sources_scale_changed
+            || shape_scale_changed
+            || height_maps_changed
+            || material_changed;
 
         // Important notes!
         // 1) The collider node may lack backing native physics collider in case if it
@@ -1413,32 +3863,75 @@ impl PhysicsWorld {
         //    and a lot of other stuff, this is why we need `anything_changed` flag.
         if collider_node.native.get() != ColliderHandle::invalid() {
             if anything_changed {
-                if let Some(native) = self.colliders.get_mut(collider_node.native.get()) {
-                    if collider_node.transform_modified.get() {
-                        native.set_position_wrt_parent(Isometry3 {
-                            rotation: **collider_node.local_transform().rotation(),
-                            translation: Translation3 {
-                                vector: **collider_node.local_transform().position(),
-                            },
-                        });
+                if self.is_static_collider(collider_node.native.get()) {
+                    self.static_ray_cast_cache.get_mut().invalidate();
+                }
+
+                if let Some(native) = self.colliders.set.get_mut(collider_node.native.get()) {
+                    if let (true, Some((_, position))) = (position_changed, parent_body) {
+                        native.set_position_wrt_parent(position);
                     }
 
-                    collider_node.shape.try_sync_model(|v| {
+                    // Shapes with baked scale must be re-created when the scale of the collider or
+                    // the scale of their geometry sources changes.
+                    if collider_node.shape.try_sync_model(|_| ())
+                        || sources_scale_changed
+                        || shape_scale_changed
+                    {
                         let inv_global_transform = isometric_global_transform(nodes, handle)
                             .try_inverse()
                             .unwrap();
                         if let Some(shape) = collider_shape_into_native_shape(
-                            &v,
+                            collider_node.shape(),
+                            shape_scale,
                             inv_global_transform,
                             handle,
                             nodes,
+                            &self.trimesh_cache,
                         ) {
                             native.set_shape(shape);
                         }
-                    });
-                    collider_node
-                        .restitution
-                        .try_sync_model(|v| native.set_restitution(v));
+                        collider_node.sources_scale.set(sources_scale);
+                        collider_node.shape_scale.set(shape_scale);
+                        *collider_node.height_map_hashes.borrow_mut() = height_maps;
+                    } else if height_maps_changed {
+                        if let ColliderShape::Heightfield(heightfield) = collider_node.shape() {
+                            if let Some(terrain) = nodes
+                                .try_borrow(heightfield.geometry_source.0)
+                                .and_then(|n| n.cast::<Terrain>())
+                            {
+                                let old_hashes = collider_node.height_map_hashes.borrow();
+                                let new_shape = match native.shape().as_heightfield() {
+                                    // Only changed chunks are updated if the layout of the terrain
+                                    // is the same.
+                                    Some(native_heightfield)
+                                        if old_hashes.len() == height_maps.len()
+                                            && is_same_heightfield_layout(
+                                                native_heightfield,
+                                                terrain,
+                                                sources_scale,
+                                            ) =>
+                                    {
+                                        let changed_chunks = old_hashes
+                                            .iter()
+                                            .zip(height_maps.iter())
+                                            .map(|(old, new)| old != new)
+                                            .collect::<Vec<_>>();
+                                        patch_heightfield(
+                                            native_heightfield,
+                                            terrain,
+                                            sources_scale,
+                                            &changed_chunks,
+                                        )
+                                    }
+                                    _ => make_heightfield(terrain, sources_scale),
+                                };
+                                drop(old_hashes);
+                                native.set_shape(new_shape);
+                            }
+                        }
+                        *collider_node.height_map_hashes.borrow_mut() = height_maps;
+                    }
                     collider_node.collision_groups.try_sync_model(|v| {
                         native.set_collision_groups(InteractionGroups::new(
                             u32_to_group(v.memberships.0),
@@ -1451,24 +3944,32 @@ impl PhysicsWorld {
                             u32_to_group(v.filter.0),
                         ))
                     });
-                    collider_node
-                        .friction
-                        .try_sync_model(|v| native.set_friction(v));
                     collider_node
                         .is_sensor
                         .try_sync_model(|v| native.set_sensor(v));
+                    // Colliders without density use default density of the physics engine.
                     collider_node
-                        .friction_combine_rule
-                        .try_sync_model(|v| native.set_friction_combine_rule(v.into()));
-                    collider_node
-                        .restitution_combine_rule
-                        .try_sync_model(|v| native.set_restitution_combine_rule(v.into()));
+                        .density
+                        .try_sync_model(|v| native.set_density(v.unwrap_or(1.0)));
+                    // Surface properties could come either from the collider itself or from its
+                    // physics material, so they're applied together.
+                    let surface_changed = collider_node.friction.try_sync_model(|_| ())
+                        | collider_node.restitution.try_sync_model(|_| ())
+                        | collider_node.friction_combine_rule.try_sync_model(|_| ())
+                        | collider_node
+                            .restitution_combine_rule
+                            .try_sync_model(|_| ())
+                        | collider_node.physics_material.try_sync_model(|_| ());
+                    if surface_changed || material_changed {
+                        native.set_friction(surface.friction);
+                        native.set_restitution(surface.restitution);
+                        native.set_friction_combine_rule(surface.friction_combine_rule.into());
+                        native
+                            .set_restitution_combine_rule(surface.restitution_combine_rule.into());
+                    }
                 }
             }
-        } else if let Some(parent_body) = nodes
-            .try_borrow(collider_node.parent())
-            .and_then(|n| n.cast::<scene::rigidbody::RigidBody>())
-        {
+        } else if let Some((parent_body, position)) = parent_body {
             if parent_body.native.get() != RigidBodyHandle::invalid() {
                 let inv_global_transform = isometric_global_transform(nodes, handle)
                     .try_inverse()
@@ -1476,30 +3977,28 @@ impl PhysicsWorld {
                 let rigid_body_native = parent_body.native.get();
                 if let Some(shape) = collider_shape_into_native_shape(
                     collider_node.shape(),
+                    shape_scale,
                     inv_global_transform,
                     handle,
                     nodes,
+                    &self.trimesh_cache,
                 ) {
                     let mut builder = ColliderBuilder::new(shape)
-                        .position(Isometry3 {
-                            rotation: **collider_node.local_transform().rotation(),
-                            translation: Translation3 {
-                                vector: **collider_node.local_transform().position(),
-                            },
-                        })
-                        .friction(collider_node.friction())
-                        .restitution(collider_node.restitution())
+                        .position(position)
+                        .friction(surface.friction)
+                        .restitution(surface.restitution)
                         .collision_groups(InteractionGroups::new(
                             u32_to_group(collider_node.collision_groups().memberships.0),
                             u32_to_group(collider_node.collision_groups().filter.0),
                         ))
-                        .friction_combine_rule(collider_node.friction_combine_rule().into())
-                        .restitution_combine_rule(collider_node.restitution_combine_rule().into())
+                        .friction_combine_rule(surface.friction_combine_rule.into())
+                        .restitution_combine_rule(surface.restitution_combine_rule.into())
                         .solver_groups(InteractionGroups::new(
                             u32_to_group(collider_node.solver_groups().memberships.0),
                             u32_to_group(collider_node.solver_groups().filter.0),
                         ))
-                        .sensor(collider_node.is_sensor());
+                        .sensor(collider_node.is_sensor())
+                        .active_events(ActiveEvents::COLLISION_EVENTS);
 
                     if let Some(density) = collider_node.density() {
                         builder = builder.density(density);
@@ -1509,6 +4008,9 @@ impl PhysicsWorld {
                         self.add_collider(handle, rigid_body_native, builder.build());
 
                     collider_node.native.set(native_handle);
+                    collider_node.sources_scale.set(sources_scale);
+                    collider_node.shape_scale.set(shape_scale);
+                    *collider_node.height_map_hashes.borrow_mut() = height_maps;
 
                     Log::writeln(
                         MessageKind::Information,
@@ -1528,7 +4030,7 @@ impl PhysicsWorld {
         handle: Handle<Node>,
         joint: &scene::joint::Joint,
     ) {
-        if !joint.is_globally_enabled() {
+        if !joint.is_globally_enabled() || joint.is_broken() {
             self.remove_joint(joint.native.get());
             joint.native.set(ImpulseJointHandle(Default::default()));
             return;
@@ -1551,11 +4053,24 @@ impl PhysicsWorld {
                     native.body2 = rigid_body_node.native.get();
                 }
             });
+            let mut params_changed = false;
             joint.params.try_sync_model(|v| {
+                let contacts_enabled = native.data.contacts_enabled;
                 native.data =
                     // Preserve local frames.
-                    convert_joint_params(v, native.data.local_frame1, native.data.local_frame2)
+                    convert_joint_params(v, native.data.local_frame1, native.data.local_frame2);
+                native.data.set_contacts_enabled(contacts_enabled);
+                params_changed = true;
             });
+            if params_changed {
+                // Wake up the bodies, so new limits and motor settings will be applied to them
+                // even if they're sleeping.
+                for body in [native.body1, native.body2] {
+                    if let Some(body) = self.bodies.get_mut(body) {
+                        body.wake_up(true);
+                    }
+                }
+            }
             joint.contacts_enabled.try_sync_model(|v| {
                 native.data.set_contacts_enabled(v);
             });
@@ -1611,6 +4126,156 @@ impl PhysicsWorld {
                 );
             }
         }
+
+        let native = joint.native.get();
+        if self.joints.set.get(native).is_some() {
+            if joint.break_force().is_some() || joint.break_torque().is_some() {
+                self.breakable_joints
+                    .insert(native, (joint.break_force(), joint.break_torque()));
+            } else {
+                self.breakable_joints.remove(&native);
+            }
+
+            if let JointParams::SpringJoint(spring) = joint.params() {
+                self.spring_joints.insert(native, spring.clone());
+            } else {
+                self.spring_joints.remove(&native);
+            }
+        }
+    }
+
+    pub(crate) fn sync_to_vehicle_node(&mut self, nodes: &NodePool, vehicle: &Vehicle) {
+        let chassis = nodes
+            .try_borrow(vehicle.chassis())
+            .and_then(|n| n.cast::<scene::rigidbody::RigidBody>())
+            .filter(|b| self.bodies.get(b.native.get()).is_some());
+
+        let chassis = match chassis {
+            Some(chassis) if vehicle.is_globally_enabled() => chassis,
+            _ => {
+                self.remove_vehicle(vehicle.native.get());
+                vehicle.native.set(Handle::NONE);
+                return;
+            }
+        };
+
+        let wheels = vehicle
+            .children()
+            .iter()
+            .filter_map(|h| {
+                nodes
+                    .try_borrow(*h)
+                    .and_then(|n| n.cast::<Wheel>())
+                    .map(|w| (*h, w))
+            })
+            .collect::<Vec<_>>();
+
+        let chassis_native = chassis.native.get();
+        let need_rebuild = self
+            .vehicles
+            .try_borrow(vehicle.native.get())
+            .map_or(true, |native| {
+                native.controller.chassis != chassis_native
+                    || native.wheels.len() != wheels.len()
+                    || native
+                        .wheels
+                        .iter()
+                        .zip(wheels.iter())
+                        .any(|(a, (b, _))| a != b)
+                    || native
+                        .friction_slips
+                        .iter()
+                        .zip(wheels.iter())
+                        .any(|(a, (_, w))| *a != w.friction_slip())
+            });
+
+        if need_rebuild {
+            self.remove_vehicle(vehicle.native.get());
+
+            let mut controller = DynamicRayCastVehicleController::new(chassis_native);
+            // Y is up, Z is forward.
+            controller.index_up_axis = 1;
+            controller.index_forward_axis = 2;
+            for (_, wheel) in wheels.iter() {
+                controller.add_wheel(
+                    Default::default(),
+                    -Vector3::y(),
+                    -Vector3::x(),
+                    wheel.suspension_rest_length(),
+                    wheel.radius(),
+                    &WheelTuning {
+                        friction_slip: wheel.friction_slip(),
+                        ..Default::default()
+                    },
+                );
+            }
+
+            let handle = self.vehicles.spawn(NativeVehicle {
+                controller,
+                wheels: wheels.iter().map(|(h, _)| *h).collect(),
+                suspension_lengths: wheels
+                    .iter()
+                    .map(|(_, w)| w.suspension_rest_length())
+                    .collect(),
+                friction_slips: wheels.iter().map(|(_, w)| w.friction_slip()).collect(),
+            });
+            vehicle.native.set(handle);
+
+            Log::writeln(
+                MessageKind::Information,
+                format!("Native vehicle was created for node {}", vehicle.name()),
+            );
+        }
+
+        if let Some(native) = self.vehicles.try_borrow_mut(vehicle.native.get()) {
+            let chassis_isometry = isometry_from_global_transform(&chassis.global_transform());
+            for (native_wheel, (_, wheel)) in
+                native.controller.wheels_mut().iter_mut().zip(wheels.iter())
+            {
+                native_wheel.chassis_connection_point_cs = chassis_isometry
+                    .inverse_transform_point(&Point3::from(wheel.global_position()));
+                native_wheel.suspension_rest_length = wheel.suspension_rest_length();
+                native_wheel.max_suspension_travel = wheel.max_suspension_travel();
+                native_wheel.radius = wheel.radius();
+                native_wheel.suspension_stiffness = wheel.suspension_stiffness();
+                native_wheel.damping_compression = wheel.damping_compression();
+                native_wheel.damping_relaxation = wheel.damping_relaxation();
+                native_wheel.max_suspension_force = wheel.max_suspension_force();
+                native_wheel.engine_force = wheel.engine_force();
+                native_wheel.brake = wheel.brake();
+                native_wheel.steering = wheel.steering();
+            }
+        }
+    }
+
+    pub(crate) fn sync_to_water_volume_node(&mut self, volume: &WaterVolume) {
+        if !volume.is_globally_enabled() {
+            self.remove_water_volume(volume.native.get());
+            volume.native.set(Handle::NONE);
+            return;
+        }
+
+        let transform = volume.global_transform();
+        let native = NativeWaterVolume {
+            transform,
+            inv_transform: transform.try_inverse().unwrap_or_default(),
+            half_extents: volume.half_extents(),
+            density: volume.density(),
+            linear_drag: volume.linear_drag(),
+            angular_drag: volume.angular_drag(),
+            flow_velocity: volume.flow_velocity(),
+        };
+
+        if let Some(existing) = self.water_volumes.try_borrow_mut(volume.native.get()) {
+            *existing = native;
+        } else {
+            volume.native.set(self.water_volumes.spawn(native));
+
+            Log::writeln(
+                MessageKind::Information,
+                format!("Native water volume was created for node {}", volume.name()),
+            );
+        }
     }
 
     /// Intersections checks between regular colliders and sensor colliders
@@ -1621,10 +4286,10 @@ impl PhysicsWorld {
         self.narrow_phase.intersections_with(collider).map(
             |(collider1, collider2, intersecting)| IntersectionPair {
                 collider1: Handle::decode_from_u128(
-                    self.colliders.get(collider1).unwrap().user_data,
+                    self.colliders.set.get(collider1).unwrap().user_data,
                 ),
                 collider2: Handle::decode_from_u128(
-                    self.colliders.get(collider2).unwrap().user_data,
+                    self.colliders.set.get(collider2).unwrap().user_data,
                 ),
                 has_any_active_contact: intersecting,
             },
@@ -1643,12 +4308,180 @@ impl PhysicsWorld {
             .filter_map(|c| ContactPair::from_native(c, self))
     }
 
+    /// Returns current active contacts of the given collider with other colliders. See
+    /// [`super::Graph::contacts_of`] for more info.
+    pub fn contacts_of(&self, collider: Handle<Node>) -> Vec<Contact> {
+        let native = match self.colliders.map.key_of(&collider) {
+            Some(native) => *native,
+            None => return Vec::new(),
+        };
+
+        let mut contacts = Vec::new();
+        for pair in self.narrow_phase.contacts_with(native) {
+            if !pair.has_any_active_contact {
+                continue;
+            }
+
+            // Contact data is stored relative to the first collider of the pair.
+            let is_first = pair.collider1 == native;
+            let other_native = if is_first {
+                pair.collider2
+            } else {
+                pair.collider1
+            };
+            let (this, other) = match (
+                self.colliders.set.get(native),
+                self.colliders.set.get(other_native),
+            ) {
+                (Some(this), Some(other)) => (this, other),
+                _ => continue,
+            };
+
+            let mut points = Vec::new();
+            for manifold in pair.manifolds.iter() {
+                let normal = if is_first {
+                    manifold.data.normal
+                } else {
+                    -manifold.data.normal
+                };
+                for point in manifold.points.iter() {
+                    let local_position = if is_first {
+                        point.local_p1
+                    } else {
+                        point.local_p2
+                    };
+                    points.push(ContactPoint {
+                        position: this.position().transform_point(&local_position).coords,
+                        normal,
+                        distance: point.dist,
+                        impulse: point.data.impulse,
+                    });
+                }
+            }
+
+            contacts.push(Contact {
+                other: Handle::decode_from_u128(other.user_data),
+                other_body: other
+                    .parent()
+                    .and_then(|body| self.bodies.get(body))
+                    .map(|body| Handle::decode_from_u128(body.user_data))
+                    .unwrap_or_default(),
+                points,
+            });
+        }
+        contacts
+    }
+
+    /// Returns contact info for the given pair of colliders, it allows you to check whether two specific
+    /// colliders are touching each other (for example - if a foot is touching a specific platform) without
+    /// scanning every contact pair of the world. If at least one of the colliders is a sensor, the pair will
+    /// have no contact manifolds and [`ContactPair::has_any_active_contact`] will tell whether the colliders
+    /// are intersecting. Order of the colliders in the returned pair may differ from the order of the
+    /// arguments. Returns [`None`] if any of the handles does not have a backing native collider or the
+    /// colliders are too far from each other.
+    pub fn intersection_pair(
+        &self,
+        collider1: Handle<Node>,
+        collider2: Handle<Node>,
+    ) -> Option<ContactPair> {
+        let native1 = *self.colliders.map.key_of(&collider1)?;
+        let native2 = *self.colliders.map.key_of(&collider2)?;
+
+        if let Some(intersecting) = self.narrow_phase.intersection_pair(native1, native2) {
+            Some(ContactPair {
+                collider1,
+                collider2,
+                manifolds: Default::default(),
+                has_any_active_contact: intersecting,
+            })
+        } else {
+            self.narrow_phase
+                .contact_pair(native1, native2)
+                .and_then(|c| ContactPair::from_native(c, self))
+        }
+    }
+
+    /// Calculates the time after which two colliders moving with the given constant linear velocities will
+    /// touch each other. See [`super::Graph::time_of_impact`] for more info.
+    pub fn time_of_impact(
+        &self,
+        collider1: Handle<Node>,
+        velocity1: Vector3<f32>,
+        collider2: Handle<Node>,
+        velocity2: Vector3<f32>,
+        max_toi: f32,
+    ) -> Option<TimeOfImpact> {
+        let native1 = self.native_collider(*self.colliders.map.key_of(&collider1)?)?;
+        let native2 = self.native_collider(*self.colliders.map.key_of(&collider2)?)?;
+
+        let toi = query::time_of_impact(
+            native1.position(),
+            &velocity1,
+            native1.shape(),
+            native2.position(),
+            &velocity2,
+            native2.shape(),
+            max_toi,
+            true,
+        )
+        // Some pairs of shapes (for example - two triangle meshes) are not supported.
+        .ok()??;
+
+        // Witness points and normals are in local space of the colliders at their initial positions,
+        // the colliders are moved only by translation, so the points just have to be offset.
+        Some(TimeOfImpact {
+            collider1,
+            collider2,
+            toi: toi.toi,
+            position1: native1.position().transform_point(&toi.witness1).coords
+                + velocity1 * toi.toi,
+            position2: native2.position().transform_point(&toi.witness2).coords
+                + velocity2 * toi.toi,
+            normal: native1.position().transform_vector(&toi.normal1),
+            penetrating: toi.status == TOIStatus::Penetrating,
+        })
+    }
+
     /// Returns an iterator over all contact pairs generated in this frame.
     pub fn contacts(&self) -> impl Iterator<Item = ContactPair> + '_ {
         self.narrow_phase
             .contact_pairs()
             .filter_map(|c| ContactPair::from_native(c, self))
     }
+
+    /// Returns approximate amount of memory (in bytes) held by native physics entities - rigid bodies,
+    /// colliders (including geometry of triangle meshes, height fields and convex polyhedrons), joints
+    /// and contact pairs. Internal acceleration structures are not taken into account.
+    pub fn memory_usage(&self) -> usize {
+        self.bodies.len() * std::mem::size_of::<RigidBody>()
+            + self
+                .colliders
+                .set
+                .iter()
+                .map(|(_, c)| std::mem::size_of::<Collider>() + shape_size_bytes(c.shape()))
+                .sum::<usize>()
+            + self.joints.set.len() * std::mem::size_of::<ImpulseJoint>()
+            + self.narrow_phase.contact_pairs().count()
+                * std::mem::size_of::<rapier3d::geometry::ContactPair>()
+    }
+}
+
+fn shape_size_bytes(shape: &dyn rapier3d::geometry::Shape) -> usize {
+    if let Some(trimesh) = shape.as_trimesh() {
+        std::mem::size_of_val(trimesh.vertices()) + std::mem::size_of_val(trimesh.indices())
+    } else if let Some(heightfield) = shape.as_heightfield() {
+        heightfield.heights().len() * std::mem::size_of::<f32>()
+    } else if let Some(polyhedron) = shape.as_convex_polyhedron() {
+        std::mem::size_of_val(polyhedron.points())
+    } else if let Some(compound) = shape.as_compound() {
+        compound
+            .shapes()
+            .iter()
+            .map(|(_, shape)| shape_size_bytes(&**shape))
+            .sum()
+    } else {
+        0
+    }
 }
 
 impl Default for PhysicsWorld {
@@ -1662,3 +4495,164 @@ impl Debug for PhysicsWorld {
         write!(f, "PhysicsWorld")
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            collider::{BallShape, ColliderBuilder, ColliderShape},
+            debug::SceneDrawingContext,
+            graph::{physics::PhysicsDebugDrawFlags, Graph},
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_fixed_timestep_interpolation() {
+        let mut graph = Graph::new();
+        graph.physics.gravity = Vector3::default();
+        graph.physics.fixed_timestep = Some(0.1);
+        graph.physics.interpolate_transforms = true;
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_lin_vel(Vector3::x())
+            .with_can_sleep(false)
+            .build(&mut graph);
+
+        let position_x = |graph: &Graph| graph[body].local_transform().position().x;
+
+        // Not enough time was accumulated to make a step.
+        graph.update(Vector2::new(800.0, 600.0), 0.05, Default::default());
+        assert_eq!(position_x(&graph), 0.0);
+
+        // One full step, there is no accumulated time left, so the body must be rendered at its
+        // previous state.
+        graph.update(Vector2::new(800.0, 600.0), 0.05, Default::default());
+        assert_eq!(graph.physics.interpolation_factor(), 0.0);
+        assert!(position_x(&graph).abs() < 0.001);
+
+        // Half of a step is accumulated, the body must be in the middle between two last states.
+        graph.update(Vector2::new(800.0, 600.0), 0.15, Default::default());
+        assert!((graph.physics.interpolation_factor() - 0.5).abs() < 0.001);
+        assert!((position_x(&graph) - 0.15).abs() < 0.001);
+
+        // Interpolation must not be treated as teleportation of the body.
+        graph.update(Vector2::new(800.0, 600.0), 0.05, Default::default());
+        assert!((position_x(&graph) - 0.2).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_kinematic_motion_with_fixed_timestep() {
+        let mut graph = Graph::new();
+        graph.physics.fixed_timestep = Some(0.25);
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_body_type(RigidBodyType::KinematicPositionBased)
+            .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 0.0, Default::default());
+
+        // Four steps per frame, the body must move a quarter of the distance at each of them.
+        graph[body]
+            .local_transform_mut()
+            .set_position(Vector3::new(1.0, 0.0, 0.0));
+        graph.update(Vector2::new(800.0, 600.0), 1.0, Default::default());
+
+        let native = graph
+            .physics
+            .native_body(graph[body].as_rigid_body().native.get())
+            .unwrap();
+        assert!((native.translation().x - 1.0).abs() < 0.001);
+        assert!((native.linvel().x - 1.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_physics_debug_draw_flags() {
+        let mut graph = Graph::new();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider])).build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let mut line_count = |flags| {
+            graph.physics.set_debug_draw_flags(flags);
+            let mut context = SceneDrawingContext::default();
+            graph.physics.draw(&mut context);
+            context.lines.len()
+        };
+
+        assert_eq!(line_count(PhysicsDebugDrawFlags::NONE), 0);
+        let colliders = line_count(PhysicsDebugDrawFlags::COLLIDERS);
+        assert!(colliders > 0);
+        assert!(
+            line_count(PhysicsDebugDrawFlags::COLLIDERS | PhysicsDebugDrawFlags::AABBS) > colliders
+        );
+    }
+
+    #[test]
+    fn test_intersection_pair() {
+        let mut graph = Graph::new();
+
+        let mut add_box = |position: Vector3<f32>, body_type, sensor| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+                .with_sensor(sensor)
+                .build(&mut graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_children(&[collider])
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(position)
+                            .build(),
+                    ),
+            )
+            .with_body_type(body_type)
+            .build(&mut graph);
+            collider
+        };
+
+        let ground = add_box(Vector3::new(0.0, -0.5, 0.0), RigidBodyType::Static, false);
+        let crate_box = add_box(Vector3::new(0.0, 0.45, 0.0), RigidBodyType::Dynamic, false);
+        let sensor = add_box(Vector3::new(0.5, -0.5, 0.0), RigidBodyType::Static, true);
+        let far = add_box(Vector3::new(10.0, -0.5, 0.0), RigidBodyType::Static, false);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        // Solid colliders must have contact manifolds.
+        let pair = graph.physics.intersection_pair(ground, crate_box).unwrap();
+        assert!(pair.has_any_active_contact);
+        assert!(!pair.manifolds.is_empty());
+        assert!(
+            (pair.collider1, pair.collider2) == (ground, crate_box)
+                || (pair.collider1, pair.collider2) == (crate_box, ground)
+        );
+
+        // Sensors have no manifolds, only the intersection flag.
+        let pair = graph.physics.intersection_pair(sensor, ground).unwrap();
+        assert!(pair.has_any_active_contact);
+        assert!(pair.manifolds.is_empty());
+
+        assert!(graph.physics.intersection_pair(ground, far).is_none());
+        assert!(graph
+            .physics
+            .intersection_pair(ground, Handle::NONE)
+            .is_none());
+    }
+}
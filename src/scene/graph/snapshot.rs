@@ -0,0 +1,268 @@
+//! Graph snapshots allow you to capture dynamic state of scene nodes and restore it later. See
+//! [`GraphSnapshot`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Quaternion, UnitQuaternion, Vector2, Vector3, Vector4},
+        pool::Handle,
+    },
+    scene::{dim2, graph::Graph, node::Node, rigidbody::RigidBody},
+    script::Script,
+};
+use bitflags::bitflags;
+
+/// A set of flags that defines which state of scene nodes will be captured in a [`GraphSnapshot`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct SnapshotFlags(u8);
+
+bitflags! {
+    impl SnapshotFlags: u8 {
+        /// Nothing.
+        const NONE = 0;
+        /// Local position, rotation and scale of nodes.
+        const TRANSFORM = 0b0000_0001;
+        /// Linear and angular velocities of rigid bodies (both 2D and 3D).
+        const VELOCITY = 0b0000_0010;
+        /// Fields of scripts.
+        const SCRIPT = 0b0000_0100;
+        /// Everything.
+        const ALL = Self::TRANSFORM.bits() | Self::VELOCITY.bits() | Self::SCRIPT.bits();
+    }
+}
+
+impl Default for SnapshotFlags {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+const NO_BODY: u8 = 0;
+const BODY_3D: u8 = 1;
+const BODY_2D: u8 = 2;
+
+/// A snapshot of dynamic state of scene nodes (transforms, velocities, script fields). It can be
+/// captured at any time and restored later, which is useful for rewind mechanics and rollback
+/// netcode.
+///
+/// Transforms and velocities are packed in a compact binary buffer, scripts are stored as copies
+/// of respective script instances. Restoring a snapshot does not re-initialize scripts, only their
+/// fields are restored. The snapshot does not track creation or deletion of nodes - nodes that were
+/// deleted after the snapshot was captured are ignored on restore.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::graph::{snapshot::SnapshotFlags, Graph};
+///
+/// fn rewind(graph: &mut Graph) {
+///     // Capture transforms and velocities of every node.
+///     let snapshot = graph.capture_snapshot(
+///         SnapshotFlags::TRANSFORM | SnapshotFlags::VELOCITY,
+///         &mut |_, _| true,
+///     );
+///
+///     // Simulate for a while...
+///
+///     // And rewind everything back.
+///     graph.restore_snapshot(&snapshot);
+/// }
+/// ```
+#[derive(Default, Clone, Debug)]
+pub struct GraphSnapshot {
+    flags: SnapshotFlags,
+    data: Vec<u8>,
+    scripts: Vec<(Handle<Node>, Script)>,
+}
+
+fn write_f32(data: &mut Vec<u8>, value: f32) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_u32(data: &mut Vec<u8>, value: u32) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_end(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    fn read_bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(&self.data[self.position..(self.position + N)]);
+        self.position += N;
+        bytes
+    }
+
+    fn read_u8(&mut self) -> u8 {
+        self.read_bytes::<1>()[0]
+    }
+
+    fn read_u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.read_bytes())
+    }
+
+    fn read_f32(&mut self) -> f32 {
+        f32::from_le_bytes(self.read_bytes())
+    }
+
+    fn read_vector2(&mut self) -> Vector2<f32> {
+        Vector2::new(self.read_f32(), self.read_f32())
+    }
+
+    fn read_vector3(&mut self) -> Vector3<f32> {
+        Vector3::new(self.read_f32(), self.read_f32(), self.read_f32())
+    }
+}
+
+impl GraphSnapshot {
+    /// Captures state of every node of the graph that passes the given filter. `flags` defines which
+    /// parts of the state will be captured.
+    pub fn capture<F>(graph: &Graph, flags: SnapshotFlags, filter: &mut F) -> Self
+    where
+        F: FnMut(Handle<Node>, &Node) -> bool,
+    {
+        let mut data = Vec::new();
+        let mut scripts = Vec::new();
+
+        for (handle, node) in graph.pair_iter() {
+            if !filter(handle, node) {
+                continue;
+            }
+
+            write_u32(&mut data, handle.index());
+            write_u32(&mut data, handle.generation());
+
+            if flags.contains(SnapshotFlags::TRANSFORM) {
+                let transform = node.local_transform();
+                for v in transform.position().iter() {
+                    write_f32(&mut data, *v);
+                }
+                for v in transform.rotation().coords.iter() {
+                    write_f32(&mut data, *v);
+                }
+                for v in transform.scale().iter() {
+                    write_f32(&mut data, *v);
+                }
+            }
+
+            if flags.contains(SnapshotFlags::VELOCITY) {
+                if let Some(rigid_body) = node.cast::<RigidBody>() {
+                    data.push(BODY_3D);
+                    for v in rigid_body.lin_vel().iter() {
+                        write_f32(&mut data, *v);
+                    }
+                    for v in rigid_body.ang_vel().iter() {
+                        write_f32(&mut data, *v);
+                    }
+                } else if let Some(rigid_body) = node.cast::<dim2::rigidbody::RigidBody>() {
+                    data.push(BODY_2D);
+                    for v in rigid_body.lin_vel().iter() {
+                        write_f32(&mut data, *v);
+                    }
+                    write_f32(&mut data, rigid_body.ang_vel());
+                } else {
+                    data.push(NO_BODY);
+                }
+            }
+
+            if flags.contains(SnapshotFlags::SCRIPT) {
+                if let Some(script) = node.script() {
+                    scripts.push((handle, script.clone()));
+                }
+            }
+        }
+
+        Self {
+            flags,
+            data,
+            scripts,
+        }
+    }
+
+    /// Restores previously captured state of the nodes. Nodes that were deleted after the snapshot
+    /// was captured are ignored.
+    pub fn restore(&self, graph: &mut Graph) {
+        let mut reader = Reader {
+            data: &self.data,
+            position: 0,
+        };
+
+        while !reader.is_end() {
+            let index = reader.read_u32();
+            let generation = reader.read_u32();
+            let mut node = graph.try_get_mut(Handle::new(index, generation));
+
+            if self.flags.contains(SnapshotFlags::TRANSFORM) {
+                let position = reader.read_vector3();
+                let rotation =
+                    UnitQuaternion::new_unchecked(Quaternion::from_vector(Vector4::new(
+                        reader.read_f32(),
+                        reader.read_f32(),
+                        reader.read_f32(),
+                        reader.read_f32(),
+                    )));
+                let scale = reader.read_vector3();
+
+                if let Some(node) = node.as_mut() {
+                    node.local_transform_mut()
+                        .set_position(position)
+                        .set_rotation(rotation)
+                        .set_scale(scale);
+                }
+            }
+
+            if self.flags.contains(SnapshotFlags::VELOCITY) {
+                match reader.read_u8() {
+                    BODY_3D => {
+                        let lin_vel = reader.read_vector3();
+                        let ang_vel = reader.read_vector3();
+                        if let Some(rigid_body) =
+                            node.as_mut().and_then(|n| n.cast_mut::<RigidBody>())
+                        {
+                            rigid_body.set_lin_vel(lin_vel);
+                            rigid_body.set_ang_vel(ang_vel);
+                        }
+                    }
+                    BODY_2D => {
+                        let lin_vel = reader.read_vector2();
+                        let ang_vel = reader.read_f32();
+                        if let Some(rigid_body) = node
+                            .as_mut()
+                            .and_then(|n| n.cast_mut::<dim2::rigidbody::RigidBody>())
+                        {
+                            rigid_body.set_lin_vel(lin_vel);
+                            rigid_body.set_ang_vel(ang_vel);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        for (handle, captured_script) in self.scripts.iter() {
+            if let Some(script) = graph
+                .try_get_mut(*handle)
+                .and_then(|node| node.script_mut())
+            {
+                script.restore_instance_from(captured_script);
+            }
+        }
+    }
+
+    /// Returns flags that were used to capture the snapshot.
+    pub fn flags(&self) -> SnapshotFlags {
+        self.flags
+    }
+
+    /// Returns size of the compact binary buffer with transforms and velocities in bytes.
+    pub fn data_size(&self) -> usize {
+        self.data.len()
+    }
+}
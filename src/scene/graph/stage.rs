@@ -0,0 +1,121 @@
+//! Update stages allow you to run custom systems between the steps of [`Graph::update`]. See
+//! [`UpdateStage`] docs for more info.
+
+use crate::{core::algebra::Vector2, scene::graph::Graph};
+use std::fmt::{Debug, Formatter};
+
+/// A step of the graph update pipeline, after which a set of user-defined systems will be executed.
+/// The steps are executed in the order of declaration of the variants.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::graph::{stage::UpdateStage, Graph};
+///
+/// fn register_systems(graph: &mut Graph) {
+///     // Runs before physics entities are synchronized with the scene nodes, it could be used to
+///     // animate kinematic rigid bodies.
+///     graph.add_update_system(UpdateStage::PreUpdate, |graph, ctx| {
+///         // Move nodes here.
+///     });
+///
+///     // Runs after every node was updated, it could be used to apply custom constraints.
+///     graph.add_update_system(UpdateStage::PostUpdate, |graph, ctx| {
+///         // Apply constraints here.
+///     });
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UpdateStage {
+    /// At the very beginning of the update, before global transforms are calculated.
+    PreUpdate,
+    /// After global transforms and other hierarchical properties are calculated.
+    PostHierarchy,
+    /// After native physics and sound entities are synchronized with scene nodes.
+    PostSync,
+    /// After 3D and 2D physics steps.
+    PostPhysics,
+    /// After every node was updated (including scripts of animation players, particle systems, etc.).
+    PostUpdate,
+}
+
+/// A context of an update system.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UpdateSystemContext {
+    /// Size of the frame in pixels.
+    pub frame_size: Vector2<f32>,
+    /// Time passed since the last update, in seconds.
+    pub dt: f32,
+}
+
+/// A unique identifier of an update system. See [`Graph::add_update_system`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UpdateSystemId(u64);
+
+/// A boxed update system.
+pub type UpdateSystem = Box<dyn FnMut(&mut Graph, &UpdateSystemContext) + Send>;
+
+#[derive(Default)]
+pub(crate) struct UpdateSystems {
+    systems: Vec<(UpdateSystemId, UpdateStage, UpdateSystem)>,
+    // Systems that were removed while the container was taken out of the graph to run its systems.
+    pending_removals: Vec<UpdateSystemId>,
+    next_id: u64,
+}
+
+impl Debug for UpdateSystems {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UpdateSystems has {} systems.", self.systems.len())
+    }
+}
+
+impl UpdateSystems {
+    pub(crate) fn add(&mut self, stage: UpdateStage, system: UpdateSystem) -> UpdateSystemId {
+        let id = UpdateSystemId(self.next_id);
+        self.next_id += 1;
+        self.systems.push((id, stage, system));
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: UpdateSystemId) {
+        let count = self.systems.len();
+        self.systems.retain(|(i, _, _)| *i != id);
+        if self.systems.len() == count {
+            self.pending_removals.push(id);
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    // Creates an empty container, that continues numeration of the systems of this container.
+    pub(crate) fn make_empty_continuation(&self) -> Self {
+        Self {
+            next_id: self.next_id,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn run(&mut self, stage: UpdateStage, graph: &mut Graph, ctx: &UpdateSystemContext) {
+        for (_, system_stage, system) in self.systems.iter_mut() {
+            if *system_stage == stage {
+                (system)(graph, ctx)
+            }
+        }
+    }
+
+    // Merges systems that were added or removed while this container was taken out of the graph.
+    pub(crate) fn merge(&mut self, other: Self) {
+        self.next_id = self.next_id.max(other.next_id);
+        self.systems.extend(other.systems);
+        for id in other.pending_removals {
+            self.remove(id);
+        }
+        self.pending_removals.clear();
+    }
+}
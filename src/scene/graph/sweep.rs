@@ -0,0 +1,117 @@
+//! Integrity sweep allows you to find (and fix) nodes with broken hierarchy links. See
+//! [`Graph::sweep_orphans`] docs for more info.
+
+use crate::{
+    core::pool::Handle,
+    scene::{graph::Graph, node::Node},
+};
+
+/// Defines what to do with orphaned nodes found by [`Graph::sweep_orphans`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OrphanAction {
+    /// Only report orphaned nodes, the graph is left unchanged.
+    Report,
+    /// Attach orphaned nodes (with their descendants) to the root of the graph.
+    LinkToRoot,
+    /// Remove orphaned nodes (with their descendants) from the graph.
+    Remove,
+}
+
+/// A result of a single [`Graph::sweep_orphans`] call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OrphanSweepReport {
+    /// Amount of pool records checked during the call.
+    pub checked: u32,
+    /// Orphaned nodes found during the call. Handles of removed nodes are invalid.
+    pub orphans: Vec<Handle<Node>>,
+    /// Amount of invalid handles found in children lists of the checked nodes. They're removed from
+    /// the lists, unless the action is [`OrphanAction::Report`].
+    pub dangling_children: usize,
+    /// `true` if the sweep has reached the end of the pool and will start from the beginning on the
+    /// next call.
+    pub pass_finished: bool,
+}
+
+impl OrphanSweepReport {
+    /// Returns `true` if no issues were found, `false` - otherwise.
+    pub fn is_clean(&self) -> bool {
+        self.orphans.is_empty() && self.dangling_children == 0
+    }
+}
+
+pub(crate) fn sweep(graph: &mut Graph, action: OrphanAction, budget: u32) -> OrphanSweepReport {
+    let mut report = OrphanSweepReport::default();
+
+    let records = graph.pool.get_capacity();
+    if graph.orphan_sweep_cursor >= records {
+        graph.orphan_sweep_cursor = 0;
+    }
+
+    let start = graph.orphan_sweep_cursor;
+    let end = start.saturating_add(budget.max(1)).min(records);
+    for index in start..end {
+        report.checked += 1;
+
+        let handle = graph.pool.handle_from_index(index);
+        let (parent, dangling) = match graph.pool.try_borrow(handle) {
+            Some(node) => (
+                node.parent,
+                node.children
+                    .iter()
+                    .filter(|child| !graph.pool.is_valid_handle(**child))
+                    .count(),
+            ),
+            // Free or reserved record.
+            None => continue,
+        };
+
+        if dangling > 0 {
+            report.dangling_children += dangling;
+            if action != OrphanAction::Report {
+                prune_dangling_children(graph, handle);
+            }
+        }
+
+        if handle == graph.root || is_linked(graph, handle, parent) {
+            continue;
+        }
+
+        report.orphans.push(handle);
+
+        match action {
+            OrphanAction::Report => (),
+            OrphanAction::LinkToRoot => {
+                let root = graph.root;
+                graph.link_nodes_internal(handle, root);
+            }
+            OrphanAction::Remove => {
+                // Descendants could have dangling children too, they must be removed from the lists
+                // first to prevent the removal from touching invalid handles.
+                let mut stack = vec![handle];
+                while let Some(descendant) = stack.pop() {
+                    prune_dangling_children(graph, descendant);
+                    stack.extend_from_slice(graph.pool[descendant].children());
+                }
+                graph.remove_node_internal(handle);
+            }
+        }
+    }
+
+    graph.orphan_sweep_cursor = end;
+    report.pass_finished = end >= records;
+
+    report
+}
+
+fn is_linked(graph: &Graph, handle: Handle<Node>, parent: Handle<Node>) -> bool {
+    graph
+        .pool
+        .try_borrow(parent)
+        .map_or(false, |parent| parent.children.contains(&handle))
+}
+
+fn prune_dangling_children(graph: &mut Graph, handle: Handle<Node>) {
+    let mut children = std::mem::take(&mut graph.pool[handle].children);
+    children.retain(|child| graph.pool.is_valid_handle(*child));
+    graph.pool[handle].children = children;
+}
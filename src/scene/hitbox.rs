@@ -0,0 +1,440 @@
+//! Hitboxes are simplified collision shapes of a skinned mesh, that follow its animated pose. They're
+//! usually used for locational damage in shooters. See [`HitboxGenerator`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Isometry3, Point3, Translation3, UnitQuaternion, Vector3},
+        math::Matrix4Ext,
+        pool::Handle,
+    },
+    scene::{
+        base::BaseBuilder,
+        collider::{ColliderBuilder, ColliderShape, InteractionGroups},
+        graph::Graph,
+        mesh::{
+            buffer::{VertexAttributeUsage, VertexReadTrait},
+            Mesh,
+        },
+        node::Node,
+        rigidbody::{RigidBodyBuilder, RigidBodyType},
+    },
+};
+use fxhash::{FxHashMap, FxHashSet};
+
+/// A hitbox of a single bone.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Hitbox {
+    /// A handle of the bone.
+    pub bone: Handle<Node>,
+    /// A handle of the kinematic rigid body, that is attached to the bone.
+    pub body: Handle<Node>,
+    /// A handle of the capsule collider of the hitbox. Ray casts will return this handle.
+    pub collider: Handle<Node>,
+}
+
+/// Generates capsule hitboxes for the bones of a skinned mesh. Every capsule is fitted to the vertices,
+/// that are mostly influenced by its bone (according to skin weights). Each hitbox is a kinematic rigid
+/// body attached to its bone, so it follows the animated pose without any extra work.
+///
+/// By default, hitbox colliders are sensors, so they do not push other bodies, but they're still
+/// reported by ray casts. Use [`HitboxGenerator::with_collision_groups`] to be able to filter them in
+/// (or out of) ray casts.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{graph::Graph, hitbox::{Hitbox, HitboxGenerator}, node::Node},
+/// };
+///
+/// fn create_hitboxes(graph: &mut Graph, skinned_mesh: Handle<Node>) -> Vec<Hitbox> {
+///     HitboxGenerator::new(skinned_mesh)
+///         .with_weight_threshold(0.4)
+///         .generate(graph)
+/// }
+///
+/// // Locational damage.
+/// fn damage_multiplier(hitboxes: &[Hitbox], graph: &Graph, hit_collider: Handle<Node>) -> f32 {
+///     match hitboxes.iter().find(|h| h.collider == hit_collider) {
+///         Some(hitbox) if graph[hitbox.bone].name().contains("Head") => 4.0,
+///         Some(_) => 1.0,
+///         None => 0.0,
+///     }
+/// }
+/// ```
+pub struct HitboxGenerator {
+    mesh: Handle<Node>,
+    weight_threshold: f32,
+    radius_percentile: f32,
+    min_vertex_count: usize,
+    ignored_bones: FxHashSet<Handle<Node>>,
+    collision_groups: InteractionGroups,
+    solver_groups: InteractionGroups,
+    sensor: bool,
+}
+
+fn isometry_of(graph: &Graph, node: Handle<Node>) -> Isometry3<f32> {
+    let transform = graph[node].global_transform();
+    Isometry3::from_parts(
+        Translation3::new(transform[12], transform[13], transform[14]),
+        UnitQuaternion::from_matrix_eps(&transform.basis(), f32::EPSILON, 16, Default::default()),
+    )
+}
+
+impl HitboxGenerator {
+    /// Creates new hitbox generator for the given skinned mesh.
+    pub fn new(mesh: Handle<Node>) -> Self {
+        Self {
+            mesh,
+            weight_threshold: 0.5,
+            radius_percentile: 0.9,
+            min_vertex_count: 8,
+            ignored_bones: Default::default(),
+            collision_groups: Default::default(),
+            solver_groups: Default::default(),
+            sensor: true,
+        }
+    }
+
+    /// Sets minimal weight of a bone in a vertex, that is needed to take the vertex into account when
+    /// fitting the capsule of the bone. Default is 0.5.
+    pub fn with_weight_threshold(mut self, threshold: f32) -> Self {
+        self.weight_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets a fraction of vertices (`[0.0; 1.0]`), that must be inside of a capsule. Lower values give
+    /// tighter capsules, which ignore outliers (such as clothes or weapons). Default is 0.9.
+    pub fn with_radius_percentile(mut self, percentile: f32) -> Self {
+        self.radius_percentile = percentile.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets minimal amount of vertices, that is needed to create a hitbox for a bone. Default is 8.
+    pub fn with_min_vertex_count(mut self, count: usize) -> Self {
+        self.min_vertex_count = count.max(1);
+        self
+    }
+
+    /// Excludes the given bones from hitbox generation.
+    pub fn with_ignored_bones(mut self, bones: &[Handle<Node>]) -> Self {
+        self.ignored_bones.extend(bones.iter().cloned());
+        self
+    }
+
+    /// Sets collision groups of every hitbox collider.
+    pub fn with_collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// Sets solver groups of every hitbox collider.
+    pub fn with_solver_groups(mut self, groups: InteractionGroups) -> Self {
+        self.solver_groups = groups;
+        self
+    }
+
+    /// Sets whether hitbox colliders should be sensors or not. Default is `true`.
+    pub fn with_sensor(mut self, sensor: bool) -> Self {
+        self.sensor = sensor;
+        self
+    }
+
+    // Collects positions of vertices influenced by each bone, in the (unscaled) space of the bone.
+    fn collect_bone_points(&self, graph: &Graph) -> FxHashMap<Handle<Node>, Vec<Vector3<f32>>> {
+        let mut points = FxHashMap::<_, Vec<_>>::default();
+
+        let mesh = match graph.try_get(self.mesh).and_then(|n| n.cast::<Mesh>()) {
+            Some(mesh) => mesh,
+            None => return points,
+        };
+
+        for surface in mesh.surfaces() {
+            let bones = surface.bones();
+            // Maps bind pose positions to the current pose and then to the space of the bone.
+            let transforms = bones
+                .iter()
+                .map(|&bone| {
+                    let bone_ref = &graph[bone];
+                    (
+                        bone_ref.global_transform() * bone_ref.inv_bind_pose_transform(),
+                        isometry_of(graph, bone).inverse(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let data = surface.data();
+            let data = data.lock();
+            for view in data.vertex_buffer.iter() {
+                let (position, indices, weights) = match (
+                    view.read_3_f32(VertexAttributeUsage::Position),
+                    view.read_4_u8(VertexAttributeUsage::BoneIndices),
+                    view.read_4_f32(VertexAttributeUsage::BoneWeight),
+                ) {
+                    (Ok(position), Ok(indices), Ok(weights)) => (position, indices, weights),
+                    _ => break,
+                };
+
+                for (&index, &weight) in indices.iter().zip(weights.iter()) {
+                    if weight < self.weight_threshold || weight <= 0.0 {
+                        continue;
+                    }
+
+                    if let (Some(&bone), Some((skin, bone_space))) =
+                        (bones.get(index as usize), transforms.get(index as usize))
+                    {
+                        let world = skin.transform_point(&Point3::from(position));
+                        points
+                            .entry(bone)
+                            .or_default()
+                            .push(bone_space.transform_point(&world).coords);
+                    }
+                }
+            }
+        }
+
+        points
+    }
+
+    // Fits a capsule to the points in the space of the bone. Returns the ends of the segment and radius.
+    fn fit_capsule(
+        &self,
+        graph: &Graph,
+        bone: Handle<Node>,
+        child_bones: &[Handle<Node>],
+        points: &[Vector3<f32>],
+    ) -> (Vector3<f32>, Vector3<f32>, f32) {
+        let bone_space = isometry_of(graph, bone).inverse();
+
+        // Prefer direction to the child bones, it is the most stable axis. End bones use the direction to
+        // the center of their vertices.
+        let axis = if child_bones.is_empty() {
+            points.iter().sum::<Vector3<f32>>() / points.len() as f32
+        } else {
+            child_bones
+                .iter()
+                .map(|child| {
+                    bone_space
+                        .transform_point(&Point3::from(graph[*child].global_position()))
+                        .coords
+                })
+                .sum::<Vector3<f32>>()
+                / child_bones.len() as f32
+        }
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(Vector3::y);
+
+        let mut min = f32::MAX;
+        let mut max = f32::MIN;
+        let mut distances = Vec::with_capacity(points.len());
+        for point in points {
+            let t = point.dot(&axis);
+            min = min.min(t);
+            max = max.max(t);
+            distances.push((point - axis.scale(t)).norm());
+        }
+
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let index = ((distances.len() - 1) as f32 * self.radius_percentile).round() as usize;
+        let radius = distances[index].max(f32::EPSILON);
+
+        if max - min > 2.0 * radius {
+            (axis.scale(min + radius), axis.scale(max - radius), radius)
+        } else {
+            let center = axis.scale((min + max) * 0.5);
+            (center, center, radius)
+        }
+    }
+
+    /// Generates hitboxes and attaches them to the bones of the mesh. Bones without enough influenced
+    /// vertices are skipped. Returns a list of generated hitboxes.
+    pub fn generate(self, graph: &mut Graph) -> Vec<Hitbox> {
+        // Global transforms of the bones must be up-to-date.
+        graph.update_hierarchical_data();
+
+        let points = self.collect_bone_points(graph);
+        let bones = points.keys().cloned().collect::<FxHashSet<_>>();
+
+        // Sort the bones to get the same order of hitboxes on every generation.
+        let mut sorted_bones = bones.iter().cloned().collect::<Vec<_>>();
+        sorted_bones.sort_by_key(|bone| bone.index());
+
+        let mut hitboxes = Vec::new();
+        for bone in sorted_bones {
+            let bone_points = &points[&bone];
+            if self.ignored_bones.contains(&bone) || bone_points.len() < self.min_vertex_count {
+                continue;
+            }
+
+            let child_bones = graph[bone]
+                .children()
+                .iter()
+                .cloned()
+                .filter(|child| bones.contains(child))
+                .collect::<Vec<_>>();
+
+            let (begin, end, radius) = self.fit_capsule(graph, bone, &child_bones, bone_points);
+
+            // The capsule is fitted in world units, but the collider inherits the scale of the bone
+            // (for example, models are often imported with a scale), which is baked into its shape.
+            let scale = graph.global_scale(bone);
+            let (begin, end, radius) = (
+                begin.component_div(&scale),
+                end.component_div(&scale),
+                radius / scale.abs().max(),
+            );
+
+            let name = graph[bone].name().to_owned();
+
+            let collider = ColliderBuilder::new(
+                BaseBuilder::new().with_name(format!("{}HitboxCollider", name)),
+            )
+            .with_shape(ColliderShape::capsule(begin, end, radius))
+            .with_sensor(self.sensor)
+            .with_collision_groups(self.collision_groups)
+            .with_solver_groups(self.solver_groups)
+            .build(graph);
+
+            let body = RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_name(format!("{}Hitbox", name))
+                    .with_children(&[collider]),
+            )
+            .with_body_type(RigidBodyType::KinematicPositionBased)
+            .with_can_sleep(false)
+            .build(graph);
+
+            graph.link_nodes(body, bone);
+
+            hitboxes.push(Hitbox {
+                bone,
+                body,
+                collider,
+            });
+        }
+
+        hitboxes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Matrix4, Point3, UnitQuaternion, Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::ColliderShape,
+            graph::{physics::RayCastOptions, Graph},
+            hitbox::HitboxGenerator,
+            mesh::{
+                buffer::{TriangleBuffer, TriangleDefinition, VertexBuffer},
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                vertex::AnimatedVertex,
+                MeshBuilder,
+            },
+            pivot::PivotBuilder,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_hitbox_generator() {
+        let mut graph = Graph::new();
+
+        // Two bones of an "arm" along Y axis: Upper (0..1) and Lower (1..2).
+        let lower = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_name("Lower")
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 1.0, 0.0))
+                        .build(),
+                )
+                .with_inv_bind_pose_transform(Matrix4::new_translation(&Vector3::new(
+                    0.0, -1.0, 0.0,
+                ))),
+        )
+        .build(&mut graph);
+        let upper = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_name("Upper")
+                .with_children(&[lower]),
+        )
+        .build(&mut graph);
+
+        // A skinned cylinder of radius 0.2 around the bones.
+        let mut vertices = Vec::new();
+        for ring in 0..9 {
+            let y = ring as f32 * 0.25;
+            for i in 0..8 {
+                let angle = i as f32 * std::f32::consts::TAU / 8.0;
+                vertices.push(AnimatedVertex {
+                    position: Vector3::new(0.2 * angle.cos(), y, 0.2 * angle.sin()),
+                    tex_coord: Default::default(),
+                    normal: Vector3::new(angle.cos(), 0.0, angle.sin()),
+                    tangent: Default::default(),
+                    bone_weights: [1.0, 0.0, 0.0, 0.0],
+                    bone_indices: [if y < 1.0 { 0 } else { 1 }, 0, 0, 0],
+                });
+            }
+        }
+        let data = SurfaceData::new(
+            VertexBuffer::new(vertices.len(), vertices).unwrap(),
+            TriangleBuffer::new(vec![TriangleDefinition([0, 1, 2])]),
+            true,
+        );
+        let mesh = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(data))
+                .with_bones(vec![upper, lower])
+                .build()])
+            .build(&mut graph);
+
+        let hitboxes = HitboxGenerator::new(mesh).generate(&mut graph);
+        assert_eq!(hitboxes.len(), 2);
+        assert_eq!(hitboxes[0].bone, upper);
+        assert_eq!(hitboxes[1].bone, lower);
+        for hitbox in hitboxes.iter() {
+            assert_eq!(graph[hitbox.body].parent(), hitbox.bone);
+            let collider = graph[hitbox.collider].as_collider();
+            assert!(collider.is_sensor());
+            if let ColliderShape::Capsule(capsule) = collider.shape() {
+                assert!((capsule.radius - 0.2).abs() < 0.01);
+                assert!(capsule.begin.x.abs() < 0.001 && capsule.begin.z.abs() < 0.001);
+                assert!(capsule.end.y > capsule.begin.y);
+            } else {
+                panic!("Hitbox must be a capsule!");
+            }
+        }
+
+        // Hitboxes must follow the animated pose: rotate the arm, so it points along -X.
+        graph[upper]
+            .local_transform_mut()
+            .set_rotation(UnitQuaternion::from_axis_angle(
+                &Vector3::z_axis(),
+                std::f32::consts::FRAC_PI_2,
+            ));
+        for _ in 0..3 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let mut cast = |x: f32| {
+            let mut hits = Vec::new();
+            graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::new(x, 10.0, 0.0),
+                    ray_direction: -Vector3::y(),
+                    max_len: 20.0,
+                    groups: Default::default(),
+                    sort_results: true,
+                    filter: None,
+                },
+                &mut hits,
+            );
+            hits.first().map(|hit| hit.collider)
+        };
+        assert_eq!(cast(-0.5), Some(hitboxes[0].collider));
+        assert_eq!(cast(-1.5), Some(hitboxes[1].collider));
+        assert_eq!(cast(0.5), None);
+    }
+}
@@ -27,6 +27,69 @@ use std::{
 };
 use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
+/// Joint motor drives the free axis of a joint (linear for [`PrismaticJoint`], angular for
+/// [`RevoluteJoint`]) towards the target position and/or velocity. It works like a spring-damper:
+/// `stiffness` pulls the joint towards the target position, `damping` pulls it towards the target
+/// velocity. For example, a door could be closed by a motor with non-zero stiffness and zero target
+/// position, and a rotating turret could be driven by a motor with zero stiffness, non-zero damping
+/// and the desired target velocity.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct JointMotor {
+    /// Whether the motor is enabled or not. Default is `false`.
+    #[reflect(description = "Whether the motor is enabled or not.")]
+    pub enabled: bool,
+
+    /// Target position of the motor (in meters for linear axes, in radians for angular axes).
+    #[reflect(
+        description = "Target position of the motor (in meters for linear axes, in radians for angular axes)."
+    )]
+    pub target_position: f32,
+
+    /// Target velocity of the motor (in meters per second for linear axes, in radians per second for
+    /// angular axes).
+    #[reflect(
+        description = "Target velocity of the motor (in meters per second for linear axes, in radians per \
+        second for angular axes)."
+    )]
+    pub target_velocity: f32,
+
+    /// Stiffness of the motor, defines how strong the motor pulls the joint towards the target position.
+    #[reflect(
+        min_value = 0.0,
+        description = "Stiffness of the motor, defines how strong the motor pulls the joint towards \
+        the target position."
+    )]
+    pub stiffness: f32,
+
+    /// Damping of the motor, defines how strong the motor pulls the joint towards the target velocity.
+    #[reflect(
+        min_value = 0.0,
+        description = "Damping of the motor, defines how strong the motor pulls the joint towards \
+        the target velocity."
+    )]
+    pub damping: f32,
+
+    /// Maximum force (or torque for angular axes) the motor can apply. Default is [`f32::MAX`].
+    #[reflect(
+        min_value = 0.0,
+        description = "Maximum force (or torque for angular axes) the motor can apply."
+    )]
+    pub max_force: f32,
+}
+
+impl Default for JointMotor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_position: 0.0,
+            target_velocity: 0.0,
+            stiffness: 0.0,
+            damping: 0.0,
+            max_force: f32::MAX,
+        }
+    }
+}
+
 /// Ball joint locks any translational moves between two objects on the axis between objects, but
 /// allows rigid bodies to perform relative rotations. The real world example is a human shoulder,
 /// pendulum, etc.
@@ -96,6 +159,13 @@ pub struct PrismaticJoint {
     )]
     #[visit(optional)] // Backward compatibility
     pub limits: Range<f32>,
+
+    /// A motor that drives relative position of the attached bodies along local X axis of the joint.
+    #[reflect(
+        description = "A motor that drives relative position of the attached bodies along local X axis of the joint."
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub motor: JointMotor,
 }
 
 impl Default for PrismaticJoint {
@@ -103,6 +173,7 @@ impl Default for PrismaticJoint {
         Self {
             limits_enabled: false,
             limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
         }
     }
 }
@@ -123,6 +194,13 @@ pub struct RevoluteJoint {
     #[reflect(description = "Allowed angle range around local X axis of the joint (in radians).")]
     #[visit(optional)] // Backward compatibility
     pub limits: Range<f32>,
+
+    /// A motor that drives relative rotation of the attached bodies around local X axis of the joint.
+    #[reflect(
+        description = "A motor that drives relative rotation of the attached bodies around local X axis of the joint."
+    )]
+    #[visit(optional)] // Backward compatibility
+    pub motor: JointMotor,
 }
 
 impl Default for RevoluteJoint {
@@ -130,10 +208,147 @@ impl Default for RevoluteJoint {
         Self {
             limits_enabled: false,
             limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
+        }
+    }
+}
+
+/// Spring joint pulls two rigid bodies towards each other (or pushes them apart) to keep the distance
+/// between the anchors of the joint equal to the rest length. Relative rotation and the direction
+/// between the bodies are not restricted. The real world example is a bungee cord, a car suspension,
+/// etc. The first anchor of the joint is the position of the joint node (attached to the first body
+/// at the moment of binding), the second anchor is the origin of the second body.
+///
+/// # Notes
+///
+/// The spring force is applied at each simulation step, very high stiffness could make the simulation
+/// unstable. Use [`RopeJoint`] if the distance must never exceed some value.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct SpringJoint {
+    /// Distance between the anchors at which the spring does not apply any force (in meters).
+    #[reflect(
+        min_value = 0.0,
+        description = "Distance between the anchors at which the spring does not apply any force (in meters)."
+    )]
+    pub rest_length: f32,
+
+    /// Stiffness of the spring, defines the force (in newtons) per meter of the stretch or compression.
+    #[reflect(
+        min_value = 0.0,
+        description = "Stiffness of the spring, defines the force (in newtons) per meter of the stretch \
+        or compression."
+    )]
+    pub stiffness: f32,
+
+    /// Damping of the spring, defines how fast the oscillations of the spring fade out.
+    #[reflect(
+        min_value = 0.0,
+        description = "Damping of the spring, defines how fast the oscillations of the spring fade out."
+    )]
+    pub damping: f32,
+}
+
+impl Default for SpringJoint {
+    fn default() -> Self {
+        Self {
+            rest_length: 1.0,
+            stiffness: 100.0,
+            damping: 1.0,
+        }
+    }
+}
+
+/// Rope joint limits maximum distance between the anchors of the joint, the bodies are free to move
+/// closer to each other and to rotate. The real world example is a rope, a chain, etc. The anchors of
+/// the joint are defined in the same way as for [`SpringJoint`].
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct RopeJoint {
+    /// Maximum distance between the anchors of the joint (in meters).
+    #[reflect(
+        min_value = 0.0,
+        description = "Maximum distance between the anchors of the joint (in meters)."
+    )]
+    pub max_distance: f32,
+}
+
+impl Default for RopeJoint {
+    fn default() -> Self {
+        Self { max_distance: 1.0 }
+    }
+}
+
+/// Defines how a single degree of freedom of [`GenericJoint`] is restricted.
+#[derive(
+    Copy, Clone, Debug, Visit, PartialEq, Eq, Reflect, AsRefStr, EnumString, EnumVariantNames,
+)]
+pub enum JointAxisMotion {
+    /// Relative motion along (or around) the axis is not allowed.
+    Locked,
+    /// Relative motion along (or around) the axis is allowed within the limits.
+    Limited,
+    /// Relative motion along (or around) the axis is not restricted.
+    Free,
+}
+
+impl Default for JointAxisMotion {
+    fn default() -> Self {
+        Self::Locked
+    }
+}
+
+/// Parameters of a single degree of freedom of [`GenericJoint`].
+#[derive(Clone, Debug, Visit, PartialEq, Reflect)]
+pub struct JointAxisParams {
+    /// Defines how relative motion along (or around) the axis is restricted. Default is
+    /// [`JointAxisMotion::Locked`].
+    #[reflect(
+        description = "Defines how relative motion along (or around) the axis is restricted."
+    )]
+    pub motion: JointAxisMotion,
+
+    /// Allowed range of relative motion (in meters for linear axes, in radians for angular axes). It is
+    /// used only if the motion is [`JointAxisMotion::Limited`].
+    #[reflect(
+        description = "Allowed range of relative motion (in meters for linear axes, in radians for angular axes)."
+    )]
+    pub limits: Range<f32>,
+
+    /// A motor that drives relative motion along (or around) the axis. It has no effect on locked axes.
+    #[reflect(description = "A motor that drives relative motion along (or around) the axis.")]
+    pub motor: JointMotor,
+}
+
+impl Default for JointAxisParams {
+    fn default() -> Self {
+        Self {
+            motion: JointAxisMotion::Locked,
+            limits: -std::f32::consts::PI..std::f32::consts::PI,
+            motor: Default::default(),
         }
     }
 }
 
+/// Generic joint allows to configure each of six degrees of freedom (three linear and three angular
+/// axes in local frame of the joint) separately - every axis could be locked, limited or free, and
+/// could be driven by a motor. It could be used to build joints, that cannot be expressed by other
+/// joint types, for example a character joint with different limits for twist and swing or a
+/// sliding hinge. By default, every axis is locked.
+#[derive(Clone, Debug, Visit, PartialEq, Reflect, Default)]
+pub struct GenericJoint {
+    /// Parameters of relative motion along local X axis of the joint.
+    pub x: JointAxisParams,
+    /// Parameters of relative motion along local Y axis of the joint.
+    pub y: JointAxisParams,
+    /// Parameters of relative motion along local Z axis of the joint.
+    pub z: JointAxisParams,
+    /// Parameters of relative rotation around local X axis of the joint.
+    pub angular_x: JointAxisParams,
+    /// Parameters of relative rotation around local Y axis of the joint.
+    pub angular_y: JointAxisParams,
+    /// Parameters of relative rotation around local Z axis of the joint.
+    pub angular_z: JointAxisParams,
+}
+
 /// The exact kind of the joint.
 #[derive(Clone, Debug, PartialEq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames)]
 pub enum JointParams {
@@ -145,6 +360,12 @@ pub enum JointParams {
     PrismaticJoint(PrismaticJoint),
     /// See [`RevoluteJoint`] for more info.
     RevoluteJoint(RevoluteJoint),
+    /// See [`SpringJoint`] for more info.
+    SpringJoint(SpringJoint),
+    /// See [`RopeJoint`] for more info.
+    RopeJoint(RopeJoint),
+    /// See [`GenericJoint`] for more info.
+    GenericJoint(GenericJoint),
 }
 
 impl Default for JointParams {
@@ -176,6 +397,18 @@ pub struct Joint {
     #[visit(optional)] // Backward compatibility
     pub(crate) auto_rebind: InheritableVariable<bool>,
 
+    #[reflect(setter = "set_break_force")]
+    #[visit(optional)]
+    pub(crate) break_force: InheritableVariable<Option<f32>>,
+
+    #[reflect(setter = "set_break_torque")]
+    #[visit(optional)]
+    pub(crate) break_torque: InheritableVariable<Option<f32>>,
+
+    #[reflect(read_only)]
+    #[visit(optional)]
+    pub(crate) broken: bool,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) native: Cell<ImpulseJointHandle>,
@@ -194,6 +427,9 @@ impl Default for Joint {
             body2: Default::default(),
             contacts_enabled: InheritableVariable::new_modified(true),
             auto_rebind: true.into(),
+            break_force: Default::default(),
+            break_torque: Default::default(),
+            broken: false,
             native: Cell::new(ImpulseJointHandle::invalid()),
             need_rebind: Cell::new(true),
         }
@@ -224,6 +460,9 @@ impl Clone for Joint {
             contacts_enabled: self.contacts_enabled.clone(),
             // Do not copy. The copy will have its own native representation.
             auto_rebind: self.auto_rebind.clone(),
+            break_force: self.break_force.clone(),
+            break_torque: self.break_torque.clone(),
+            broken: self.broken,
             native: Cell::new(ImpulseJointHandle::invalid()),
             // Rebind will happen automatically.
             need_rebind: Cell::new(true),
@@ -296,6 +535,45 @@ impl Joint {
     pub fn is_auto_rebinding_enabled(&self) -> bool {
         *self.auto_rebind
     }
+
+    /// Sets a force (in Newtons) that breaks the joint when exceeded. `None` means that the force
+    /// could not break the joint. When the joint breaks, its native joint is removed, the joint is
+    /// marked as broken (see [`Self::is_broken`]) and [`crate::scene::graph::physics::JointBreakEvent`]
+    /// is produced.
+    pub fn set_break_force(&mut self, force: Option<f32>) -> Option<f32> {
+        self.break_force
+            .set_value_and_mark_modified(force.map(|f| f.max(0.0)))
+    }
+
+    /// Returns a force that breaks the joint.
+    pub fn break_force(&self) -> Option<f32> {
+        *self.break_force
+    }
+
+    /// Sets a torque (in Newton-meters) that breaks the joint when exceeded. `None` means that the
+    /// torque could not break the joint. See [`Self::set_break_force`] for more info.
+    pub fn set_break_torque(&mut self, torque: Option<f32>) -> Option<f32> {
+        self.break_torque
+            .set_value_and_mark_modified(torque.map(|t| t.max(0.0)))
+    }
+
+    /// Returns a torque that breaks the joint.
+    pub fn break_torque(&self) -> Option<f32> {
+        *self.break_torque
+    }
+
+    /// Returns `true` if the joint was broken by exceeding its break force or torque. Broken joints
+    /// do not connect their bodies anymore.
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
+    /// Restores a broken joint. The bodies will be connected again using their current relative
+    /// position.
+    pub fn repair(&mut self) {
+        self.broken = false;
+        self.need_rebind.set(true);
+    }
 }
 
 impl NodeTrait for Joint {
@@ -372,6 +650,8 @@ pub struct JointBuilder {
     body2: Handle<Node>,
     contacts_enabled: bool,
     auto_rebind: bool,
+    break_force: Option<f32>,
+    break_torque: Option<f32>,
 }
 
 impl JointBuilder {
@@ -384,6 +664,8 @@ impl JointBuilder {
             body2: Default::default(),
             contacts_enabled: true,
             auto_rebind: true,
+            break_force: None,
+            break_torque: None,
         }
     }
 
@@ -420,6 +702,18 @@ impl JointBuilder {
         self
     }
 
+    /// Sets desired force that breaks the joint. See [`Joint::set_break_force`] for more info.
+    pub fn with_break_force(mut self, force: Option<f32>) -> Self {
+        self.break_force = force;
+        self
+    }
+
+    /// Sets desired torque that breaks the joint. See [`Joint::set_break_torque`] for more info.
+    pub fn with_break_torque(mut self, torque: Option<f32>) -> Self {
+        self.break_torque = torque;
+        self
+    }
+
     /// Creates new Joint node, but does not add it to the graph.
     pub fn build_joint(self) -> Joint {
         Joint {
@@ -429,6 +723,9 @@ impl JointBuilder {
             body2: self.body2.into(),
             contacts_enabled: self.contacts_enabled.into(),
             auto_rebind: self.auto_rebind.into(),
+            break_force: self.break_force.into(),
+            break_torque: self.break_torque.into(),
+            broken: false,
             native: Cell::new(ImpulseJointHandle::invalid()),
             need_rebind: Cell::new(true),
         }
@@ -444,3 +741,209 @@ impl JointBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{BallShape, ColliderBuilder, ColliderShape},
+            graph::Graph,
+            joint::{
+                GenericJoint, JointAxisMotion, JointAxisParams, JointBuilder, JointMotor,
+                JointParams, PrismaticJoint, RopeJoint, SpringJoint,
+            },
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_joint_motor() {
+        let mut graph = Graph::new();
+        graph.physics.set_gravity(Vector3::default());
+
+        let frame = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .with_density(Some(1.0))
+            .build(&mut graph);
+        let slider =
+            RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider])).build(&mut graph);
+
+        JointBuilder::new(BaseBuilder::new())
+            .with_params(JointParams::PrismaticJoint(PrismaticJoint {
+                motor: JointMotor {
+                    enabled: true,
+                    target_position: 2.0,
+                    stiffness: 100.0,
+                    damping: 20.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }))
+            .with_body1(frame)
+            .with_body2(slider)
+            .build(&mut graph);
+
+        for _ in 0..300 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let position = graph[slider].global_position();
+        assert!((position.x - 2.0).abs() < 0.05);
+        assert!(position.y.abs() < 0.05 && position.z.abs() < 0.05);
+    }
+
+    #[test]
+    fn test_breakable_joint() {
+        let mut graph = Graph::new();
+
+        let frame = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        // ~525 kg, so the joint must hold ~5150 N.
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .with_density(Some(1000.0))
+            .build(&mut graph);
+        let body =
+            RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider])).build(&mut graph);
+
+        let joint = JointBuilder::new(BaseBuilder::new())
+            .with_params(JointParams::FixedJoint(Default::default()))
+            .with_body1(frame)
+            .with_body2(body)
+            .with_break_force(Some(10000.0))
+            .build(&mut graph);
+
+        for _ in 0..30 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+            assert!(graph.physics.joint_break_events().is_empty());
+        }
+        assert!(!graph[joint].as_joint().is_broken());
+        assert!(graph[body].global_position().y.abs() < 0.05);
+
+        graph[joint].as_joint_mut().set_break_force(Some(1000.0));
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let events = graph.physics.joint_break_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].joint, joint);
+        assert!(events[0].force > 1000.0);
+        assert!(graph[joint].as_joint().is_broken());
+
+        for _ in 0..30 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        assert!(graph[body].global_position().y < -1.0);
+    }
+
+    #[test]
+    fn test_additional_joint_types() {
+        let mut graph = Graph::new();
+
+        let frame = RigidBodyBuilder::new(BaseBuilder::new())
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let add_ball = |graph: &mut Graph, position: Vector3<f32>| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::ball(0.1))
+                .with_density(Some(1000.0))
+                .build(graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_children(&[collider])
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(position)
+                            .build(),
+                    ),
+            )
+            .build(graph)
+        };
+        let add_joint = |graph: &mut Graph, params, body| {
+            JointBuilder::new(BaseBuilder::new())
+                .with_params(params)
+                .with_body1(frame)
+                .with_body2(body)
+                .with_contacts_enabled(false)
+                .build(graph)
+        };
+
+        // Spring hangs below the anchor, stretched by the weight of the ball (~4.2 kg).
+        let spring_ball = add_ball(&mut graph, Vector3::new(10.0, -1.0, 0.0));
+        let spring = add_joint(
+            &mut graph,
+            JointParams::SpringJoint(SpringJoint {
+                rest_length: 1.0,
+                stiffness: 400.0,
+                damping: 20.0,
+            }),
+            spring_ball,
+        );
+        graph[spring]
+            .local_transform_mut()
+            .set_position(Vector3::new(10.0, 0.0, 0.0));
+
+        // Rope swings like a pendulum and never gets longer than its max distance.
+        let rope_ball = add_ball(&mut graph, Vector3::new(21.0, 0.0, 0.0));
+        let rope = add_joint(
+            &mut graph,
+            JointParams::RopeJoint(RopeJoint { max_distance: 1.0 }),
+            rope_ball,
+        );
+        graph[rope]
+            .local_transform_mut()
+            .set_position(Vector3::new(20.0, 0.0, 0.0));
+
+        // Generic joint allows only vertical motion.
+        let slider_ball = add_ball(&mut graph, Vector3::new(30.0, 0.0, 0.0));
+        let slider = add_joint(
+            &mut graph,
+            JointParams::GenericJoint(GenericJoint {
+                y: JointAxisParams {
+                    motion: JointAxisMotion::Limited,
+                    limits: -2.0..2.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            slider_ball,
+        );
+        graph[slider]
+            .local_transform_mut()
+            .set_position(Vector3::new(30.0, 0.0, 0.0));
+        graph[slider_ball]
+            .as_rigid_body_mut()
+            .set_lin_vel(Vector3::new(1.0, 0.0, 1.0));
+
+        let mut max_rope_length = 0.0f32;
+        let mut min_rope_height = 0.0f32;
+        for _ in 0..300 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+            let rope_position = graph[rope_ball].global_position();
+            max_rope_length =
+                max_rope_length.max((rope_position - Vector3::new(20.0, 0.0, 0.0)).norm());
+            min_rope_height = min_rope_height.min(rope_position.y);
+        }
+
+        let expected_stretch = 4.19 * 9.81 / 400.0;
+        let position = graph[spring_ball].global_position();
+        assert!((position.y + 1.0 + expected_stretch).abs() < 0.05);
+
+        assert!(min_rope_height < -0.9);
+        assert!(max_rope_length < 1.05);
+
+        let position = graph[slider_ball].global_position();
+        assert!((position.y + 2.0).abs() < 0.05);
+        assert!((position.x - 30.0).abs() < 0.01);
+        assert!(position.z.abs() < 0.01);
+    }
+}
@@ -27,6 +27,7 @@ use crate::{
     scene::base::{Base, BaseBuilder},
 };
 use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
 
 pub mod directional;
 pub mod point;
@@ -44,6 +45,44 @@ pub const DEFAULT_SCATTER_G: f32 = 0.03;
 /// significant value and you'll clearly see light volume with such settings.
 pub const DEFAULT_SCATTER_B: f32 = 0.03;
 
+/// Default illuminance (in lux) at which physically-based point and spot lights are considered
+/// to have no visible contribution. It is used to derive radius of such lights.
+pub const DEFAULT_CUTOFF_THRESHOLD: f32 = 0.05;
+
+/// Defines how intensity of point and spot lights is interpreted. Directional lights always use
+/// unitless intensity.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Reflect,
+    Visit,
+    PartialEq,
+    Eq,
+    Hash,
+    AsRefStr,
+    EnumString,
+    EnumVariantNames,
+)]
+#[repr(u32)]
+pub enum LightUnits {
+    /// Intensity is a unitless multiplier and the radius (or distance) of the light is set manually.
+    /// Attenuation reaches zero exactly at the radius. This is the mode that was used before
+    /// physical units were introduced.
+    #[default]
+    Legacy = 0,
+    /// Intensity is a luminous flux in lumens - the total amount of light emitted by the light
+    /// source. For spot lights the flux is concentrated in the cone, so narrowing the cone makes
+    /// the light brighter. Attenuation follows the inverse-square law and the radius is derived
+    /// from the cutoff threshold.
+    Lumens = 1,
+    /// Intensity is a luminous intensity in candela - the amount of light emitted in a single
+    /// direction. Attenuation follows the inverse-square law and the radius is derived from the
+    /// cutoff threshold.
+    Candela = 2,
+}
+
 /// Light scene node. It contains common properties of light such as color,
 /// scattering factor (per color channel) and other useful properties. Exact
 /// behavior defined by specific light kind.
@@ -67,6 +106,15 @@ pub struct BaseLight {
     #[reflect(min_value = 0.0, step = 0.1)]
     #[reflect(setter = "set_intensity")]
     intensity: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_units")]
+    units: InheritableVariable<LightUnits>,
+
+    #[visit(optional)]
+    #[reflect(min_value = 0.0001, step = 0.01)]
+    #[reflect(setter = "set_cutoff_threshold")]
+    cutoff_threshold: InheritableVariable<f32>,
 }
 
 impl Deref for BaseLight {
@@ -96,6 +144,8 @@ impl Default for BaseLight {
             )),
             scatter_enabled: InheritableVariable::new_modified(true),
             intensity: InheritableVariable::new_modified(1.0),
+            units: InheritableVariable::new_modified(LightUnits::Legacy),
+            cutoff_threshold: InheritableVariable::new_modified(DEFAULT_CUTOFF_THRESHOLD),
         }
     }
 }
@@ -148,7 +198,8 @@ impl BaseLight {
     ///
     /// Intensity is used for very bright light sources in HDR. For examples, sun
     /// can be represented as directional light source with very high intensity.
-    /// Other lights, however, will remain relatively dim.
+    /// Other lights, however, will remain relatively dim. Meaning of the value for point and spot
+    /// lights depends on current [`LightUnits`].
     pub fn set_intensity(&mut self, intensity: f32) -> f32 {
         self.intensity.set_value_and_mark_modified(intensity)
     }
@@ -158,6 +209,50 @@ impl BaseLight {
         *self.intensity
     }
 
+    /// Sets units in which intensity of the light is defined. See [`LightUnits`] docs for more
+    /// info. Has no effect on directional lights.
+    pub fn set_units(&mut self, units: LightUnits) -> LightUnits {
+        self.units.set_value_and_mark_modified(units)
+    }
+
+    /// Returns current units in which intensity of the light is defined.
+    pub fn units(&self) -> LightUnits {
+        *self.units
+    }
+
+    /// Returns `true` if the light uses physical units and inverse-square attenuation.
+    pub fn is_physically_based(&self) -> bool {
+        *self.units != LightUnits::Legacy
+    }
+
+    /// Sets illuminance (in lux) below which contribution of a physically-based light is
+    /// considered negligible. Lower values give larger radius of the light, which is more accurate
+    /// but more expensive to render. Default is [`DEFAULT_CUTOFF_THRESHOLD`].
+    pub fn set_cutoff_threshold(&mut self, threshold: f32) -> f32 {
+        self.cutoff_threshold
+            .set_value_and_mark_modified(threshold.max(f32::EPSILON))
+    }
+
+    /// Returns current cutoff threshold.
+    pub fn cutoff_threshold(&self) -> f32 {
+        *self.cutoff_threshold
+    }
+
+    /// Returns luminous intensity (in candela) of the light that emits its flux into the given
+    /// solid angle (in steradians). In legacy mode the intensity is returned as is.
+    pub fn luminous_intensity(&self, solid_angle: f32) -> f32 {
+        match *self.units {
+            LightUnits::Legacy | LightUnits::Candela => *self.intensity,
+            LightUnits::Lumens => *self.intensity / solid_angle.max(f32::EPSILON),
+        }
+    }
+
+    /// Returns distance at which illuminance of the light with the given luminous intensity falls
+    /// below the cutoff threshold.
+    pub fn cutoff_radius(&self, luminous_intensity: f32) -> f32 {
+        (luminous_intensity.abs() / self.cutoff_threshold.max(f32::EPSILON)).sqrt()
+    }
+
     /// Returns current scatter factor in linear color space.
     #[inline]
     pub fn scatter_linear(&self) -> Vector3<f32> {
@@ -186,6 +281,8 @@ pub struct BaseLightBuilder {
     scatter_factor: Vector3<f32>,
     scatter_enabled: bool,
     intensity: f32,
+    units: LightUnits,
+    cutoff_threshold: f32,
 }
 
 impl BaseLightBuilder {
@@ -201,6 +298,8 @@ impl BaseLightBuilder {
             scatter_factor: Vector3::new(DEFAULT_SCATTER_R, DEFAULT_SCATTER_G, DEFAULT_SCATTER_B),
             scatter_enabled: true,
             intensity: 1.0,
+            units: LightUnits::Legacy,
+            cutoff_threshold: DEFAULT_CUTOFF_THRESHOLD,
         }
     }
 
@@ -234,6 +333,18 @@ impl BaseLightBuilder {
         self
     }
 
+    /// Sets units in which light intensity is defined.
+    pub fn with_units(mut self, units: LightUnits) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Sets illuminance below which contribution of a physically-based light is negligible.
+    pub fn with_cutoff_threshold(mut self, threshold: f32) -> Self {
+        self.cutoff_threshold = threshold;
+        self
+    }
+
     /// Creates new instance of base light.
     pub fn build(self) -> BaseLight {
         BaseLight {
@@ -243,6 +354,8 @@ impl BaseLightBuilder {
             scatter: self.scatter_factor.into(),
             scatter_enabled: self.scatter_enabled.into(),
             intensity: self.intensity.into(),
+            units: self.units.into(),
+            cutoff_threshold: self.cutoff_threshold.into(),
         }
     }
 }
@@ -3,6 +3,11 @@
 //! parameter - radius at which intensity will be zero. Intensity of light will
 //! be calculated using inverse square root law.
 //!
+//! # Physical units
+//!
+//! When base light uses [`LightUnits::Lumens`](crate::scene::light::LightUnits::Lumens) or [`LightUnits::Candela`](crate::scene::light::LightUnits::Candela), the radius is
+//! ignored and derived from the cutoff threshold instead, see [`PointLight::effective_radius`].
+//!
 //! # Light scattering
 //!
 //! Point light support light scattering feature - it means that you'll see light
@@ -84,7 +89,8 @@ impl PointLight {
     }
 
     /// Sets radius of point light. This parameter also affects radius of spherical
-    /// light volume that is used in light scattering.
+    /// light volume that is used in light scattering. It is used only in [`LightUnits::Legacy`](crate::scene::light::LightUnits::Legacy)
+    /// mode.
     #[inline]
     pub fn set_radius(&mut self, radius: f32) -> f32 {
         self.radius.set_value_and_mark_modified(radius.abs())
@@ -96,6 +102,25 @@ impl PointLight {
         *self.radius
     }
 
+    /// Returns luminous intensity of the light. In physical units the flux is spread evenly over
+    /// the whole sphere. In legacy mode it is just the intensity of the light.
+    #[inline]
+    pub fn luminous_intensity(&self) -> f32 {
+        self.base_light
+            .luminous_intensity(4.0 * std::f32::consts::PI)
+    }
+
+    /// Returns radius that is actually used for rendering. It is the radius of the light in legacy
+    /// mode, otherwise it is derived from the cutoff threshold of the light.
+    #[inline]
+    pub fn effective_radius(&self) -> f32 {
+        if self.base_light.is_physically_based() {
+            self.base_light.cutoff_radius(self.luminous_intensity())
+        } else {
+            *self.radius
+        }
+    }
+
     /// Sets new shadow bias value. Bias will be used to offset fragment's depth before
     /// compare it with shadow map value, it is used to remove "shadow acne".
     pub fn set_shadow_bias(&mut self, bias: f32) -> f32 {
@@ -125,7 +150,12 @@ impl NodeTrait for PointLight {
     }
 
     fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
-        ctx.draw_wire_sphere(self.global_position(), self.radius(), 30, Color::GREEN);
+        ctx.draw_wire_sphere(
+            self.global_position(),
+            self.effective_radius(),
+            30,
+            Color::GREEN,
+        );
     }
 }
 
@@ -187,3 +217,40 @@ impl PointLightBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{
+        base::BaseBuilder,
+        light::{point::PointLightBuilder, BaseLightBuilder, LightUnits},
+    };
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_point_light_units() {
+        let light = |units| {
+            PointLightBuilder::new(
+                BaseLightBuilder::new(BaseBuilder::new())
+                    .with_intensity(100.0)
+                    .with_units(units)
+                    .with_cutoff_threshold(0.05),
+            )
+            .with_radius(7.0)
+            .build_point_light()
+        };
+
+        let legacy = light(LightUnits::Legacy);
+        assert_eq!(legacy.luminous_intensity(), 100.0);
+        assert_eq!(legacy.effective_radius(), 7.0);
+
+        let candela = light(LightUnits::Candela);
+        assert_eq!(candela.luminous_intensity(), 100.0);
+        assert!((candela.effective_radius() - (100.0f32 / 0.05).sqrt()).abs() < 1.0e-4);
+
+        // The flux is spread over the whole sphere.
+        let lumens = light(LightUnits::Lumens);
+        let intensity = 100.0 / (4.0 * PI);
+        assert!((lumens.luminous_intensity() - intensity).abs() < 1.0e-4);
+        assert!((lumens.effective_radius() - (intensity / 0.05).sqrt()).abs() < 1.0e-4);
+    }
+}
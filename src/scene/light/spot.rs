@@ -9,6 +9,11 @@
 //! how intensity of light changes over distance to point in world. Currently
 //! engine uses inverse square root law of distance attenuation.
 //!
+//! # Physical units
+//!
+//! When base light uses [`LightUnits::Lumens`](crate::scene::light::LightUnits::Lumens) or [`LightUnits::Candela`](crate::scene::light::LightUnits::Candela), the distance is
+//! ignored and derived from the cutoff threshold instead, see [`SpotLight::effective_distance`].
+//!
 //! # Light scattering
 //!
 //! Spot lights support light scattering feature - it means that you will see
@@ -157,7 +162,8 @@ impl SpotLight {
     }
 
     /// Sets maximum distance at which light intensity will be zero. Intensity
-    /// of light will be calculated using inverse square root law.
+    /// of light will be calculated using inverse square root law. It is used only in
+    /// [`LightUnits::Legacy`](crate::scene::light::LightUnits::Legacy) mode.
     #[inline]
     pub fn set_distance(&mut self, distance: f32) -> f32 {
         self.distance.set_value_and_mark_modified(distance.abs())
@@ -169,6 +175,25 @@ impl SpotLight {
         *self.distance
     }
 
+    /// Returns luminous intensity of the light. In physical units the flux is spread evenly over
+    /// the solid angle of the full cone. In legacy mode it is just the intensity of the light.
+    #[inline]
+    pub fn luminous_intensity(&self) -> f32 {
+        let solid_angle = 2.0 * std::f32::consts::PI * (1.0 - (self.full_cone_angle() * 0.5).cos());
+        self.base_light.luminous_intensity(solid_angle)
+    }
+
+    /// Returns distance that is actually used for rendering. It is the distance of the light in
+    /// legacy mode, otherwise it is derived from the cutoff threshold of the light.
+    #[inline]
+    pub fn effective_distance(&self) -> f32 {
+        if self.base_light.is_physically_based() {
+            self.base_light.cutoff_radius(self.luminous_intensity())
+        } else {
+            *self.distance
+        }
+    }
+
     /// Set cookie texture. Also called gobo this texture gets projected
     /// by the spot light.
     #[inline]
@@ -213,8 +238,8 @@ impl NodeTrait for SpotLight {
     fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
         ctx.draw_cone(
             16,
-            (self.full_cone_angle() * 0.5).tan() * self.distance(),
-            self.distance(),
+            (self.full_cone_angle() * 0.5).tan() * self.effective_distance(),
+            self.effective_distance(),
             Matrix4::new_translation(&self.global_position())
                 * UnitQuaternion::from_matrix_eps(
                     &self.global_transform().basis(),
@@ -223,7 +248,11 @@ impl NodeTrait for SpotLight {
                     UnitQuaternion::identity(),
                 )
                 .to_homogeneous()
-                * Matrix4::new_translation(&Vector3::new(0.0, -self.distance() * 0.5, 0.0)),
+                * Matrix4::new_translation(&Vector3::new(
+                    0.0,
+                    -self.effective_distance() * 0.5,
+                    0.0,
+                )),
             Color::GREEN,
             false,
         );
@@ -305,3 +334,50 @@ impl SpotLightBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::scene::{
+        base::BaseBuilder,
+        light::{spot::SpotLightBuilder, BaseLightBuilder, LightUnits},
+    };
+    use std::f32::consts::PI;
+
+    #[test]
+    fn test_spot_light_units() {
+        let light = |units, cone_angle: f32| {
+            SpotLightBuilder::new(
+                BaseLightBuilder::new(BaseBuilder::new())
+                    .with_intensity(100.0)
+                    .with_units(units)
+                    .with_cutoff_threshold(0.05),
+            )
+            .with_hotspot_cone_angle(cone_angle.to_radians())
+            .with_falloff_angle_delta(0.0)
+            .with_distance(7.0)
+            .build_spot_light()
+        };
+
+        let legacy = light(LightUnits::Legacy, 60.0);
+        assert_eq!(legacy.luminous_intensity(), 100.0);
+        assert_eq!(legacy.effective_distance(), 7.0);
+
+        // Intensity in candela does not depend on the cone.
+        for cone_angle in [30.0, 60.0] {
+            let candela = light(LightUnits::Candela, cone_angle);
+            assert_eq!(candela.luminous_intensity(), 100.0);
+            assert!((candela.effective_distance() - (100.0f32 / 0.05).sqrt()).abs() < 1.0e-4);
+        }
+
+        // The flux is concentrated in the cone, so narrow cones are brighter and reach farther.
+        let lumens = light(LightUnits::Lumens, 60.0);
+        let solid_angle = 2.0 * PI * (1.0 - 30.0f32.to_radians().cos());
+        let intensity = 100.0 / solid_angle;
+        assert!((lumens.luminous_intensity() - intensity).abs() < 1.0e-3);
+        assert!((lumens.effective_distance() - (intensity / 0.05).sqrt()).abs() < 1.0e-3);
+
+        let narrow = light(LightUnits::Lumens, 30.0);
+        assert!(narrow.luminous_intensity() > lumens.luminous_intensity());
+        assert!(narrow.effective_distance() > lumens.effective_distance());
+    }
+}
@@ -21,9 +21,11 @@ use crate::{
     scene::{
         base::{Base, BaseBuilder},
         debug::{Line, SceneDrawingContext},
-        graph::Graph,
+        graph::{Graph, NodePool},
         mesh::{
-            buffer::{VertexAttributeUsage, VertexReadTrait},
+            buffer::{
+                VertexAttributeDataType, VertexAttributeUsage, VertexBuffer, VertexReadTrait,
+            },
             surface::{BlendShape, Surface},
         },
         node::{Node, NodeTrait, UpdateContext},
@@ -148,6 +150,16 @@ pub struct Mesh {
     #[reflect(hidden)]
     #[visit(skip)]
     world_bounding_box: Cell<AxisAlignedBoundingBox>,
+
+    // Bounds of each surface in the space of every bone of the surface. Surfaces without bones
+    // have a single entry with their bounds in local coordinates of the mesh.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    bone_local_bounds: Vec<Vec<Option<AxisAlignedBoundingBox>>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    bone_local_bounds_dirty: Cell<bool>,
 }
 
 impl Default for Mesh {
@@ -161,6 +173,8 @@ impl Default for Mesh {
             render_path: InheritableVariable::new_modified(RenderPath::Deferred),
             decal_layer_index: InheritableVariable::new_modified(0),
             blend_shapes: Default::default(),
+            bone_local_bounds: Default::default(),
+            bone_local_bounds_dirty: Cell::new(true),
         }
     }
 }
@@ -185,9 +199,25 @@ impl TypeUuidProvider for Mesh {
     }
 }
 
+// Checks whether the buffer has an attribute with the given usage that is stored in the format
+// expected by the code that reads it.
+fn has_attribute(
+    buffer: &VertexBuffer,
+    usage: VertexAttributeUsage,
+    data_type: VertexAttributeDataType,
+    size: u8,
+) -> bool {
+    buffer
+        .layout()
+        .iter()
+        .any(|a| a.usage == usage && a.data_type == data_type && a.size >= size)
+}
+
 impl Mesh {
     /// Sets surfaces for the mesh.
     pub fn set_surfaces(&mut self, surfaces: Vec<Surface>) -> Vec<Surface> {
+        self.local_bounding_box_dirty.set(true);
+        self.bone_local_bounds_dirty.set(true);
         self.surfaces.set_value_and_mark_modified(surfaces)
     }
 
@@ -201,6 +231,7 @@ impl Mesh {
     #[inline]
     pub fn surfaces_mut(&mut self) -> &mut [Surface] {
         self.local_bounding_box_dirty.set(true);
+        self.bone_local_bounds_dirty.set(true);
         self.surfaces.get_value_mut_silent()
     }
 
@@ -209,6 +240,7 @@ impl Mesh {
     pub fn clear_surfaces(&mut self) {
         self.surfaces.get_value_mut_and_mark_modified().clear();
         self.local_bounding_box_dirty.set(true);
+        self.bone_local_bounds_dirty.set(true);
     }
 
     /// Adds new surface into mesh, can be used to procedurally generate meshes.
@@ -218,6 +250,7 @@ impl Mesh {
             .get_value_mut_and_mark_modified()
             .push(surface);
         self.local_bounding_box_dirty.set(true);
+        self.bone_local_bounds_dirty.set(true);
     }
 
     /// Returns a list of blend shapes.
@@ -298,6 +331,103 @@ impl Mesh {
         bounding_box
     }
 
+    // Calculates bounds of every skinned surface in the space of each of its bones. This allows
+    // us to get conservative world-space bounds for any pose of the skeleton by simply transforming
+    // each box by the current global transform of its bone.
+    fn calculate_bone_local_bounds(&mut self, nodes: &NodePool) {
+        self.bone_local_bounds.clear();
+
+        for surface in self.surfaces.iter() {
+            let data = surface.data();
+            let data = data.lock();
+
+            let has_positions = has_attribute(
+                &data.vertex_buffer,
+                VertexAttributeUsage::Position,
+                VertexAttributeDataType::F32,
+                3,
+            );
+
+            if surface.bones().is_empty() {
+                let mut bounds: Option<AxisAlignedBoundingBox> = None;
+                if !has_positions {
+                    self.bone_local_bounds.push(vec![bounds]);
+                    continue;
+                }
+                for view in data.vertex_buffer.iter() {
+                    if let Ok(position) = view.read_3_f32(VertexAttributeUsage::Position) {
+                        bounds
+                            .get_or_insert_with(|| AxisAlignedBoundingBox::from_point(position))
+                            .add_point(position);
+                    }
+                }
+                self.bone_local_bounds.push(vec![bounds]);
+                continue;
+            }
+
+            let mut bounds = vec![None; surface.bones().len()];
+
+            // Skip surfaces that cannot be skinned, they won't contribute to the final bounds.
+            if !has_positions
+                || !has_attribute(
+                    &data.vertex_buffer,
+                    VertexAttributeUsage::BoneIndices,
+                    VertexAttributeDataType::U8,
+                    4,
+                )
+                || !has_attribute(
+                    &data.vertex_buffer,
+                    VertexAttributeUsage::BoneWeight,
+                    VertexAttributeDataType::F32,
+                    4,
+                )
+            {
+                self.bone_local_bounds.push(bounds);
+                continue;
+            }
+
+            let inv_bind_poses = surface
+                .bones()
+                .iter()
+                .map(|&b| {
+                    nodes
+                        .try_borrow(b)
+                        .map(|bone| bone.inv_bind_pose_transform())
+                        .unwrap_or_else(Matrix4::identity)
+                })
+                .collect::<Vec<_>>();
+
+            for view in data.vertex_buffer.iter() {
+                let (position, bone_indices, bone_weights) = match (
+                    view.read_3_f32(VertexAttributeUsage::Position),
+                    view.read_4_u8(VertexAttributeUsage::BoneIndices),
+                    view.read_4_f32(VertexAttributeUsage::BoneWeight),
+                ) {
+                    (Ok(position), Ok(bone_indices), Ok(bone_weights)) => {
+                        (Point3::from(position), bone_indices, bone_weights)
+                    }
+                    _ => continue,
+                };
+                for (&bone_index, &weight) in bone_indices.iter().zip(bone_weights.iter()) {
+                    if weight > 0.0 {
+                        if let Some(bone_bounds) = bounds.get_mut(bone_index as usize) {
+                            let point = inv_bind_poses[bone_index as usize]
+                                .transform_point(&position)
+                                .coords;
+                            bone_bounds
+                                .get_or_insert_with(|| AxisAlignedBoundingBox::from_point(point))
+                                .add_point(point);
+                        }
+                    }
+                }
+            }
+
+            self.bone_local_bounds.push(bounds);
+        }
+
+        self.bone_local_bounds_dirty.set(false);
+    }
+
     /// Sets new decal layer index. It defines which decals will be applies to the mesh,
     /// for example iff a decal has index == 0 and a mesh has index == 0, then decals will
     /// be applied. This allows you to apply decals only on needed surfaces.
@@ -345,20 +475,39 @@ impl NodeTrait for Mesh {
 
     fn update(&mut self, context: &mut UpdateContext) {
         if self.surfaces.iter().any(|s| !s.bones.is_empty()) {
-            let mut world_aabb = self
-                .local_bounding_box()
-                .transform(&self.global_transform());
+            if self.bone_local_bounds_dirty.get()
+                || self.bone_local_bounds.len() != self.surfaces.len()
+            {
+                self.calculate_bone_local_bounds(context.nodes);
+            }
 
-            // Special case for skinned meshes.
-            for surface in self.surfaces.iter() {
-                for &bone in surface.bones() {
-                    if let Some(node) = context.nodes.try_borrow(bone) {
-                        world_aabb.add_point(node.global_position())
+            // Special case for skinned meshes - bounds of each bone are transformed using current
+            // pose of the skeleton, so the final box encloses the mesh in any pose.
+            let mut world_aabb: Option<AxisAlignedBoundingBox> = None;
+            let mut add_box = |bounds: AxisAlignedBoundingBox| match world_aabb.as_mut() {
+                Some(world_aabb) => world_aabb.add_box(bounds),
+                None => world_aabb = Some(bounds),
+            };
+            for (surface, surface_bounds) in self.surfaces.iter().zip(self.bone_local_bounds.iter())
+            {
+                if surface.bones().is_empty() {
+                    for bounds in surface_bounds.iter().flatten() {
+                        add_box(bounds.transform(&self.global_transform()));
+                    }
+                } else {
+                    for (&bone, bounds) in surface.bones().iter().zip(surface_bounds.iter()) {
+                        if let (Some(bounds), Some(node)) = (bounds, context.nodes.try_borrow(bone))
+                        {
+                            add_box(bounds.transform(&node.global_transform()));
+                        }
                     }
                 }
             }
 
-            self.world_bounding_box.set(world_aabb)
+            self.world_bounding_box.set(world_aabb.unwrap_or_else(|| {
+                self.local_bounding_box()
+                    .transform(&self.global_transform())
+            }))
         } else {
             self.world_bounding_box.set(
                 self.local_bounding_box()
@@ -536,6 +685,8 @@ impl MeshBuilder {
             render_path: self.render_path.into(),
             decal_layer_index: self.decal_layer_index.into(),
             world_bounding_box: Default::default(),
+            bone_local_bounds: Default::default(),
+            bone_local_bounds_dirty: Cell::new(true),
         })
     }
 
@@ -544,3 +695,202 @@ impl MeshBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Matrix4, UnitQuaternion, Vector2, Vector3},
+            math::{aabb::AxisAlignedBoundingBox, TriangleDefinition},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            graph::Graph,
+            mesh::{
+                buffer::{
+                    TriangleBuffer, VertexAttributeDataType, VertexAttributeDescriptor,
+                    VertexAttributeUsage, VertexBuffer, VertexTrait,
+                },
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                vertex::AnimatedVertex,
+                Mesh, MeshBuilder,
+            },
+            node::{Node, NodeTrait},
+            pivot::PivotBuilder,
+        },
+    };
+
+    // Skinned vertex that stores bone indices as floats instead of bytes.
+    #[derive(Copy, Clone)]
+    #[repr(C)]
+    struct FloatIndicesVertex {
+        position: Vector3<f32>,
+        bone_weights: [f32; 4],
+        bone_indices: [f32; 4],
+    }
+
+    impl VertexTrait for FloatIndicesVertex {
+        fn layout() -> &'static [VertexAttributeDescriptor] {
+            static LAYOUT: [VertexAttributeDescriptor; 3] = [
+                VertexAttributeDescriptor {
+                    usage: VertexAttributeUsage::Position,
+                    data_type: VertexAttributeDataType::F32,
+                    size: 3,
+                    divisor: 0,
+                    shader_location: 0,
+                },
+                VertexAttributeDescriptor {
+                    usage: VertexAttributeUsage::BoneWeight,
+                    data_type: VertexAttributeDataType::F32,
+                    size: 4,
+                    divisor: 0,
+                    shader_location: 1,
+                },
+                VertexAttributeDescriptor {
+                    usage: VertexAttributeUsage::BoneIndices,
+                    data_type: VertexAttributeDataType::F32,
+                    size: 4,
+                    divisor: 0,
+                    shader_location: 2,
+                },
+            ];
+            &LAYOUT
+        }
+    }
+
+    fn build_skinned_mesh(
+        graph: &mut Graph,
+        data: SurfaceData,
+        bone: Handle<Node>,
+    ) -> Handle<Node> {
+        MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(data))
+                .with_bones(vec![bone])
+                .build()])
+            .build(graph)
+    }
+
+    fn world_and_local_bounds(
+        graph: &Graph,
+        mesh: Handle<Node>,
+    ) -> (AxisAlignedBoundingBox, AxisAlignedBoundingBox) {
+        let mesh = graph[mesh].cast::<Mesh>().unwrap();
+        (mesh.world_bounding_box(), mesh.local_bounding_box())
+    }
+
+    #[test]
+    fn test_bone_bounds_skip_surfaces_without_skinning_attributes() {
+        let mut graph = Graph::new();
+        let bone = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+
+        // Static vertices have neither bone indices nor bone weights.
+        let no_attributes = build_skinned_mesh(
+            &mut graph,
+            SurfaceData::make_cube(Matrix4::identity()),
+            bone,
+        );
+
+        let vertices = vec![
+            FloatIndicesVertex {
+                position: Vector3::new(-1.0, 0.0, 0.0),
+                bone_weights: [1.0, 0.0, 0.0, 0.0],
+                bone_indices: [0.0; 4],
+            },
+            FloatIndicesVertex {
+                position: Vector3::new(1.0, 2.0, 0.0),
+                bone_weights: [1.0, 0.0, 0.0, 0.0],
+                bone_indices: [0.0; 4],
+            },
+            FloatIndicesVertex {
+                position: Vector3::new(0.0, 0.0, 3.0),
+                bone_weights: [1.0, 0.0, 0.0, 0.0],
+                bone_indices: [0.0; 4],
+            },
+        ];
+        let wrong_format = build_skinned_mesh(
+            &mut graph,
+            SurfaceData::new(
+                VertexBuffer::new(vertices.len(), vertices).unwrap(),
+                TriangleBuffer::new(vec![TriangleDefinition([0, 1, 2])]),
+                true,
+            ),
+            bone,
+        );
+
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+
+        // Such surfaces are skipped, so the world bounds fall back to the local bounds.
+        for mesh in [no_attributes, wrong_format] {
+            let (world, local) = world_and_local_bounds(&graph, mesh);
+            assert_eq!(world.min, local.min);
+            assert_eq!(world.max, local.max);
+        }
+    }
+
+    // A triangle fully bound to the given bone.
+    fn build_skinned_triangle(graph: &mut Graph, bone: Handle<Node>) -> Handle<Node> {
+        let vertices = [
+            Vector3::new(-1.0, 0.0, 0.0),
+            Vector3::new(1.0, 2.0, 0.0),
+            Vector3::new(0.0, 0.0, 3.0),
+        ]
+        .iter()
+        .map(|&position| AnimatedVertex {
+            position,
+            bone_weights: [1.0, 0.0, 0.0, 0.0],
+            bone_indices: [0; 4],
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+        build_skinned_mesh(
+            graph,
+            SurfaceData::new(
+                VertexBuffer::new(vertices.len(), vertices).unwrap(),
+                TriangleBuffer::new(vec![TriangleDefinition([0, 1, 2])]),
+                true,
+            ),
+            bone,
+        )
+    }
+
+    #[test]
+    fn test_bone_bounds_of_skinned_surface() {
+        let mut graph = Graph::new();
+        let bone = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let mesh = build_skinned_triangle(&mut graph, bone);
+
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+
+        let (world, _) = world_and_local_bounds(&graph, mesh);
+        assert_eq!(world.min, Vector3::new(-1.0, 0.0, 0.0));
+        assert_eq!(world.max, Vector3::new(1.0, 2.0, 3.0));
+    }
+
+    #[test]
+    fn test_bone_bounds_follow_bone() {
+        let mut graph = Graph::new();
+        let bone = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let mesh = build_skinned_triangle(&mut graph, bone);
+
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+
+        // Move and rotate the bone, the bounds must follow it without re-calculation of bone bounds.
+        graph[bone]
+            .local_transform_mut()
+            .set_position(Vector3::new(10.0, 0.0, 0.0))
+            .set_rotation(UnitQuaternion::from_axis_angle(
+                &Vector3::y_axis(),
+                std::f32::consts::FRAC_PI_2,
+            ));
+
+        graph.update(Vector2::new(100.0, 100.0), 1.0 / 60.0, Default::default());
+
+        let (world, local) = world_and_local_bounds(&graph, mesh);
+        assert!(world.min.metric_distance(&Vector3::new(10.0, 0.0, -1.0)) < 1.0e-5);
+        assert!(world.max.metric_distance(&Vector3::new(13.0, 2.0, 1.0)) < 1.0e-5);
+        // Mesh itself wasn't moved.
+        assert_eq!(local.min, Vector3::new(-1.0, 0.0, 0.0));
+        assert_eq!(local.max, Vector3::new(1.0, 2.0, 3.0));
+    }
+}
@@ -199,6 +199,7 @@ pub struct SurfaceData {
     // resource. Procedural data will be serialized.
     is_procedural: bool,
     pub(crate) cache_entry: AtomicIndex,
+    pub(crate) is_ready: bool,
 }
 
 impl SurfaceData {
@@ -216,9 +217,18 @@ impl SurfaceData {
             blend_shapes_container: None,
             is_procedural,
             cache_entry: AtomicIndex::unassigned(),
+            is_ready: false,
         }
     }
 
+    /// Returns `true` if the data is fully uploaded to GPU and can be rendered, `false` - otherwise. Large
+    /// surfaces could be uploaded in portions over multiple frames (see
+    /// [`crate::renderer::Renderer::set_geometry_upload_budget`]), such surfaces are not rendered until
+    /// the upload is finished.
+    pub fn is_ready(&self) -> bool {
+        self.is_ready
+    }
+
     /// Applies given transform for every spatial part of the data (vertex position, normal, tangent).
     pub fn transform_geometry(&mut self, transform: &Matrix4<f32>) -> Result<(), VertexFetchError> {
         // Discard scale by inverse and transpose given transform (M^-1)^T
@@ -260,6 +270,7 @@ impl SurfaceData {
             blend_shapes_container: Default::default(),
             is_procedural,
             cache_entry: AtomicIndex::unassigned(),
+            is_ready: false,
         }
     }
 
@@ -1253,6 +1264,14 @@ pub struct Surface {
     )]
     unique_material: InheritableVariable<bool>,
 
+    #[reflect(
+        min_value = 0.0,
+        step = 0.1,
+        description = "A multiplier of lightmap resolution (texels per unit) for the surface."
+    )]
+    #[reflect(setter = "set_lightmap_density")]
+    lightmap_density: InheritableVariable<f32>,
+
     // Temporal array for FBX conversion needs, it holds skinning data (weight + bone handle)
     // and will be used to fill actual bone indices and weight in vertices that will be
     // sent to GPU. The idea is very simple: GPU needs to know only indices of matrices of
@@ -1276,6 +1295,7 @@ impl Clone for Surface {
             },
             bones: self.bones.clone(),
             unique_material: self.unique_material.clone(),
+            lightmap_density: self.lightmap_density.clone(),
             vertex_weights: self.vertex_weights.clone(),
         }
     }
@@ -1290,6 +1310,7 @@ impl Visit for Surface {
         self.bones.visit("Bones", &mut region)?;
 
         let _ = self.unique_material.visit("UniqueMaterial", &mut region); // Backward compatibility.
+        let _ = self.lightmap_density.visit("LightmapDensity", &mut region); // Backward compatibility.
 
         Ok(())
     }
@@ -1303,6 +1324,7 @@ impl Default for Surface {
             vertex_weights: Default::default(),
             bones: Default::default(),
             unique_material: Default::default(),
+            lightmap_density: 1.0.into(),
         }
     }
 }
@@ -1367,6 +1389,19 @@ impl Surface {
     pub fn set_unique_material(&mut self, unique: bool) {
         self.unique_material.set_value_and_mark_modified(unique);
     }
+
+    /// Sets a multiplier of lightmap resolution for the surface. Lightmap generator multiplies its
+    /// texels-per-unit value by the density, so values above `1.0` give more detailed lightmaps for
+    /// important surfaces, and values below `1.0` save space on unimportant ones. Default is `1.0`.
+    pub fn set_lightmap_density(&mut self, density: f32) -> f32 {
+        self.lightmap_density
+            .set_value_and_mark_modified(density.max(0.0))
+    }
+
+    /// Returns a multiplier of lightmap resolution for the surface.
+    pub fn lightmap_density(&self) -> f32 {
+        *self.lightmap_density
+    }
 }
 
 /// Surface builder allows you to create surfaces in declarative manner.
@@ -1375,6 +1410,7 @@ pub struct SurfaceBuilder {
     material: Option<SharedMaterial>,
     bones: Vec<Handle<Node>>,
     unique_material: bool,
+    lightmap_density: f32,
 }
 
 impl SurfaceBuilder {
@@ -1385,6 +1421,7 @@ impl SurfaceBuilder {
             material: None,
             bones: Default::default(),
             unique_material: false,
+            lightmap_density: 1.0,
         }
     }
 
@@ -1406,6 +1443,13 @@ impl SurfaceBuilder {
         self
     }
 
+    /// Sets a multiplier of lightmap resolution for the surface. See [`Surface::set_lightmap_density`]
+    /// for more info.
+    pub fn with_lightmap_density(mut self, density: f32) -> Self {
+        self.lightmap_density = density;
+        self
+    }
+
     /// Creates new instance of surface.
     pub fn build(self) -> Surface {
         Surface {
@@ -1417,6 +1461,7 @@ impl SurfaceBuilder {
             vertex_weights: Default::default(),
             bones: self.bones.into(),
             unique_material: self.unique_material.into(),
+            lightmap_density: self.lightmap_density.max(0.0).into(),
         }
     }
 }
@@ -9,10 +9,12 @@ pub mod animation;
 pub mod base;
 pub mod camera;
 pub mod collider;
+pub mod component;
 pub mod debug;
 pub mod decal;
 pub mod dim2;
 pub mod graph;
+pub mod hitbox;
 pub mod joint;
 pub mod light;
 pub mod loader;
@@ -21,15 +23,19 @@ pub mod navmesh;
 pub mod node;
 pub mod particle_system;
 pub mod pivot;
+pub mod post_process_volume;
 pub mod ragdoll;
 pub mod rigidbody;
 pub mod sound;
 pub mod sprite;
 pub mod terrain;
 pub mod transform;
+pub mod user_data;
+pub mod vehicle;
+pub mod water_volume;
 
 use crate::{
-    asset::{self, manager::ResourceManager, untyped::UntypedResource},
+    asset::{self, manager::ResourceManager, untyped::UntypedResource, Resource, ResourceData},
     core::{
         algebra::Vector2,
         color::Color,
@@ -39,11 +45,12 @@ use crate::{
         reflect::prelude::*,
         sstorage::ImmutableString,
         visitor::{Visit, VisitError, VisitResult, Visitor},
+        TypeUuidProvider,
     },
     engine::SerializationContext,
     material::{shader::SamplerFallback, PropertyValue},
     renderer::framework::state::PolygonFillMode,
-    resource::texture::TextureResource,
+    resource::{model::Model, texture::TextureResource},
     scene::{
         base::BaseBuilder,
         camera::Camera,
@@ -228,7 +235,9 @@ impl Display for PerformanceStatistics {
             \tPhysics 2D: {:?}\n\
             \t\tSimulation: {:?}\n\
             \t\tRay cast: {:?}\n\
-            \tHierarchy: {:?}",
+            \tHierarchy: {:?}\n\
+            \tNodes: {:?}\n\
+            \tUpdate Systems: {:?}",
             self.graph.total(),
             self.graph.sync_time,
             self.graph.sound_update_time,
@@ -239,7 +248,19 @@ impl Display for PerformanceStatistics {
             self.graph.physics2d.step_time,
             self.graph.physics2d.total_ray_cast_time.get(),
             self.graph.hierarchical_properties_time,
-        )
+            self.graph.nodes_update_time,
+            self.graph.update_systems_time,
+        )?;
+
+        for (type_name, statistics) in self.graph.node_types.iter() {
+            write!(
+                f,
+                "\n\t\t{}: {} ({:?})",
+                type_name, statistics.count, statistics.update_time
+            )?;
+        }
+
+        Ok(())
     }
 }
 
@@ -476,6 +497,64 @@ impl Scene {
         collection
     }
 
+    /// Replaces every reference to the `old` resource in the scene with the `new` one. It could be used to
+    /// rebind scene nodes (meshes, sprites, terrains, particle systems, sounds, etc.) to another resource
+    /// after an asset was renamed or to swap assets of different quality tiers. Returns the number of
+    /// replaced references.
+    ///
+    /// It uses reflection to "scan" the contents of the scene, so if some fields are marked with
+    /// `#[reflect(hidden)]` attribute, then such fields will be ignored! Every property that contains
+    /// replaced resource is marked as modified, so it won't be overwritten by property inheritance and
+    /// will be synchronized with respective native objects (such as sound sources). Keep in mind that
+    /// materials are shared between nodes, which means that every user of a material will be affected.
+    ///
+    /// Model resources cannot be replaced this way, because it requires re-instantiation of the nodes.
+    pub fn replace_resource<T>(&mut self, old: &Resource<T>, new: &Resource<T>) -> usize
+    where
+        T: ResourceData + TypeUuidProvider,
+    {
+        if <T as TypeUuidProvider>::type_uuid() == <Model as TypeUuidProvider>::type_uuid() {
+            Log::err("Model resources cannot be replaced, instantiate the new model instead!");
+            return 0;
+        }
+
+        if old == new {
+            return 0;
+        }
+
+        let count = asset::replace_used_resources(
+            self,
+            &old.clone().into_untyped(),
+            &new.clone().into_untyped(),
+        );
+
+        if count > 0 {
+            // Cube maps of sky boxes are generated from their faces, so they must be re-created.
+            let new = new.clone().into_untyped();
+            for node in self.graph.linear_iter_mut() {
+                if let Some(skybox) = node.cast_mut::<Camera>().and_then(|c| c.skybox_mut()) {
+                    let faces = skybox.textures();
+                    let uses_new = faces
+                        .iter()
+                        .flatten()
+                        .any(|face| face.clone().into_untyped() == new);
+                    if uses_new && faces.iter().flatten().all(|face| face.is_ok()) {
+                        Log::verify(skybox.create_cubemap());
+                    }
+                }
+            }
+        }
+
+        Log::info(format!(
+            "{} references of {} resource were replaced with {}.",
+            count,
+            old.path().display(),
+            new.path().display()
+        ));
+
+        count
+    }
+
     /// Tries to set new lightmap to scene.
     pub fn set_lightmap(&mut self, lightmap: Lightmap) -> Result<Option<Lightmap>, &'static str> {
         // Assign textures to surfaces.
@@ -743,3 +822,76 @@ impl IndexMut<Handle<Scene>> for SceneContainer {
         &mut self.pool[index]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{algebra::Matrix4, sstorage::ImmutableString},
+        material::{shader::SamplerFallback, Material, PropertyValue, SharedMaterial},
+        resource::texture::{
+            TextureKind, TexturePixelKind, TextureResource, TextureResourceExtension,
+        },
+        scene::{
+            base::BaseBuilder,
+            mesh::{
+                surface::{SurfaceBuilder, SurfaceData, SurfaceSharedData},
+                Mesh, MeshBuilder,
+            },
+            Scene,
+        },
+    };
+
+    #[test]
+    fn test_replace_resource() {
+        let make_texture = || {
+            TextureResource::from_bytes(
+                TextureKind::Rectangle {
+                    width: 1,
+                    height: 1,
+                },
+                TexturePixelKind::R8,
+                vec![0],
+                false,
+            )
+            .unwrap()
+        };
+        let old = make_texture();
+        let new = make_texture();
+        let diffuse = ImmutableString::new("diffuseTexture");
+
+        let mut scene = Scene::new();
+        let mut meshes = Vec::new();
+        for _ in 0..2 {
+            let mut material = Material::standard();
+            material
+                .set_property(
+                    &diffuse,
+                    PropertyValue::Sampler {
+                        value: Some(old.clone()),
+                        fallback: SamplerFallback::White,
+                    },
+                )
+                .unwrap();
+            let data = SurfaceSharedData::new(SurfaceData::make_cube(Matrix4::identity()));
+            meshes.push(
+                MeshBuilder::new(BaseBuilder::new())
+                    .with_surfaces(vec![SurfaceBuilder::new(data)
+                        .with_material(SharedMaterial::new(material))
+                        .build()])
+                    .build(&mut scene.graph),
+            );
+        }
+
+        assert_eq!(scene.replace_resource(&old, &new), 2);
+        assert_eq!(scene.replace_resource(&old, &new), 0);
+
+        for mesh in meshes {
+            let mesh = scene.graph[mesh].cast::<Mesh>().unwrap();
+            let material = mesh.surfaces()[0].material().lock();
+            match material.property_ref(&diffuse) {
+                Some(PropertyValue::Sampler { value, .. }) => assert_eq!(value, &Some(new.clone())),
+                _ => panic!("diffuse texture is missing"),
+            }
+        }
+    }
+}
@@ -14,10 +14,13 @@ use crate::{
         node::{Node, NodeTrait},
         particle_system::ParticleSystem,
         pivot::Pivot,
+        post_process_volume::PostProcessVolume,
         ragdoll::Ragdoll,
         sound::{listener::Listener, Sound},
         sprite::Sprite,
         terrain::Terrain,
+        vehicle::{Vehicle, Wheel},
+        water_volume::WaterVolume,
     },
 };
 use fxhash::FxHashMap;
@@ -59,6 +62,10 @@ impl NodeConstructorContainer {
         container.add::<AnimationBlendingStateMachine>();
         container.add::<NavigationalMesh>();
         container.add::<Ragdoll>();
+        container.add::<Vehicle>();
+        container.add::<Wheel>();
+        container.add::<WaterVolume>();
+        container.add::<PostProcessVolume>();
 
         container
     }
@@ -29,10 +29,13 @@ use crate::{
         navmesh::NavigationalMesh,
         particle_system::ParticleSystem,
         pivot::Pivot,
+        post_process_volume::PostProcessVolume,
         ragdoll::Ragdoll,
         sound::{context::SoundContext, listener::Listener, Sound},
         sprite::Sprite,
         terrain::Terrain,
+        vehicle::{Vehicle, Wheel},
+        water_volume::WaterVolume,
         Scene,
     },
 };
@@ -400,6 +403,8 @@ impl Node {
     /// ```
     ///
     /// Some nodes could also provide access to inner components, check documentation of a node.
+    /// Components and other user data attached to the node (see
+    /// [`crate::scene::component::NodeComponent`]) are checked too.
     #[inline]
     pub fn query_component_ref<T>(&self) -> Option<&T>
     where
@@ -407,6 +412,7 @@ impl Node {
     {
         self.0
             .query_component_ref(TypeId::of::<T>())
+            .or_else(|| self.0.user_data_storage().query_ref(TypeId::of::<T>()))
             .and_then(|c| c.downcast_ref::<T>())
     }
 
@@ -428,14 +434,19 @@ impl Node {
     /// ```
     ///
     /// Some nodes could also provide access to inner components, check documentation of a node.
+    /// Components and other user data attached to the node (see
+    /// [`crate::scene::component::NodeComponent`]) are checked too.
     #[inline]
     pub fn query_component_mut<T>(&mut self) -> Option<&mut T>
     where
         T: 'static,
     {
-        self.0
-            .query_component_mut(TypeId::of::<T>())
-            .and_then(|c| c.downcast_mut::<T>())
+        let component = if self.0.query_component_ref(TypeId::of::<T>()).is_some() {
+            self.0.query_component_mut(TypeId::of::<T>())
+        } else {
+            self.0.user_data_storage_mut().query_mut(TypeId::of::<T>())
+        };
+        component.and_then(|c| c.downcast_mut::<T>())
     }
 
     pub(crate) fn mark_inheritable_variables_as_modified(&mut self) {
@@ -465,6 +476,10 @@ impl Node {
     define_is_as!(AnimationBlendingStateMachine => fn is_absm, fn as_absm, fn as_absm_mut);
     define_is_as!(AnimationPlayer => fn is_animation_player, fn as_animation_player, fn as_animation_player_mut);
     define_is_as!(Ragdoll => fn is_ragdoll, fn as_ragdoll, fn as_ragdoll_mut);
+    define_is_as!(Vehicle => fn is_vehicle, fn as_vehicle, fn as_vehicle_mut);
+    define_is_as!(Wheel => fn is_wheel, fn as_wheel, fn as_wheel_mut);
+    define_is_as!(WaterVolume => fn is_water_volume, fn as_water_volume, fn as_water_volume_mut);
+    define_is_as!(PostProcessVolume => fn is_post_process_volume, fn as_post_process_volume, fn as_post_process_volume_mut);
 }
 
 impl Visit for Node {
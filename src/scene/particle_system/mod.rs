@@ -61,6 +61,17 @@ impl ParticleSystemRng {
         }
     }
 
+    /// Creates new PRNG with a random seed.
+    pub fn new_random() -> Self {
+        Self::new(crate::rand::random())
+    }
+
+    /// Returns the seed of the PRNG.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.rng_seed
+    }
+
     /// Resets the state of PRNG.
     #[inline]
     pub fn reset(&mut self) {
@@ -206,6 +217,15 @@ pub struct ParticleSystem {
 
     #[visit(optional)]
     rng: ParticleSystemRng,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_fixed_seed")]
+    fixed_seed: InheritableVariable<bool>,
+
+    // Runtime-only multiplier of spawn rates of emitters, set by quality settings of the game.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    density: f32,
 }
 
 impl Deref for ParticleSystem {
@@ -271,6 +291,49 @@ impl ParticleSystem {
             .set_value_and_mark_modified(factor)
     }
 
+    /// Sets new seed of the pseudo-random numbers generator of the particle system. The state of
+    /// the generator is reset, so the particle system will produce the same sequence of particles
+    /// for the same seed.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = ParticleSystemRng::new(seed);
+    }
+
+    /// Returns current seed of the pseudo-random numbers generator of the particle system.
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Assigns a random seed to the pseudo-random numbers generator of the particle system.
+    pub fn randomize_seed(&mut self) {
+        self.rng = ParticleSystemRng::new_random();
+    }
+
+    /// Defines whether the seed is fixed or not. Every instance of a prefab with a particle system
+    /// gets a random seed on instantiation, so multiple copies of the same effect will look
+    /// different. Fixed seed is kept as is, which could be useful for effects that must look exactly
+    /// the same every time.
+    pub fn set_fixed_seed(&mut self, fixed: bool) -> bool {
+        self.fixed_seed.set_value_and_mark_modified(fixed)
+    }
+
+    /// Returns `true` if the seed is fixed, `false` - otherwise. See [`Self::set_fixed_seed`] for
+    /// more info.
+    pub fn is_seed_fixed(&self) -> bool {
+        *self.fixed_seed
+    }
+
+    /// Sets a multiplier of spawn rates of every emitter of the particle system. It allows you to
+    /// reduce the amount of particles on low-end hardware, without changing the emitters. The value
+    /// is clamped to `[0.0; 1.0]` range, default is `1.0`. The density is not serialized.
+    pub fn set_density(&mut self, density: f32) -> f32 {
+        std::mem::replace(&mut self.density, density.clamp(0.0, 1.0))
+    }
+
+    /// Returns current multiplier of spawn rates of emitters. See [`Self::set_density`] for more info.
+    pub fn density(&self) -> f32 {
+        self.density
+    }
+
     /// Replaces the particles in the particle system with pre-generated set. It could be useful
     /// to create procedural particle effects; when particles cannot be pre-made.
     pub fn set_particles(&mut self, particles: Vec<Particle>) {
@@ -398,8 +461,10 @@ impl ParticleSystem {
     }
 
     fn tick(&mut self, dt: f32) {
+        // Emitters spawn particles using the time they've been running, so slowing their time down
+        // reduces spawn rate proportionally.
         for emitter in self.emitters.get_value_mut_silent().iter_mut() {
-            emitter.tick(dt);
+            emitter.tick(dt * self.density);
         }
 
         for (i, emitter) in self.emitters.get_value_mut_silent().iter_mut().enumerate() {
@@ -508,6 +573,7 @@ pub struct ParticleSystemBuilder {
     soft_boundary_sharpness_factor: f32,
     is_playing: bool,
     rng: ParticleSystemRng,
+    fixed_seed: bool,
 }
 
 impl ParticleSystemBuilder {
@@ -523,6 +589,7 @@ impl ParticleSystemBuilder {
             soft_boundary_sharpness_factor: 2.5,
             is_playing: true,
             rng: ParticleSystemRng::default(),
+            fixed_seed: false,
         }
     }
 
@@ -581,6 +648,13 @@ impl ParticleSystemBuilder {
         self
     }
 
+    /// Sets whether the seed of the pseudo-random numbers generator is fixed or not. See
+    /// [`ParticleSystem::set_fixed_seed`] for more info.
+    pub fn with_fixed_seed(mut self, fixed: bool) -> Self {
+        self.fixed_seed = fixed;
+        self
+    }
+
     fn build_particle_system(self) -> ParticleSystem {
         ParticleSystem {
             base: self.base_builder.build_base(),
@@ -593,6 +667,8 @@ impl ParticleSystemBuilder {
             soft_boundary_sharpness_factor: self.soft_boundary_sharpness_factor.into(),
             is_playing: self.is_playing.into(),
             rng: self.rng,
+            fixed_seed: self.fixed_seed.into(),
+            density: 1.0,
         }
     }
 
@@ -606,3 +682,48 @@ impl ParticleSystemBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        resource::model::{Model, ModelResource, ModelResourceExtension},
+        scene::{
+            base::BaseBuilder,
+            particle_system::{ParticleSystemBuilder, ParticleSystemRng},
+            Scene,
+        },
+    };
+
+    #[test]
+    fn test_seed_randomized_per_instance() {
+        let mut model = Model::default();
+        {
+            let prefab = &mut model.get_scene_mut().graph;
+            ParticleSystemBuilder::new(BaseBuilder::new().with_name("Random"))
+                .with_rng(ParticleSystemRng::new(42))
+                .build(prefab);
+            ParticleSystemBuilder::new(BaseBuilder::new().with_name("Fixed"))
+                .with_rng(ParticleSystemRng::new(42))
+                .with_fixed_seed(true)
+                .build(prefab);
+        }
+        let resource = ModelResource::new_ok(model);
+
+        let mut scene = Scene::new();
+        let seeds = |scene: &mut Scene| {
+            let root = resource.instantiate(scene);
+            let seed_of = |name: &str| {
+                let handle = scene.graph.find_by_name(root, name).unwrap().0;
+                scene.graph[handle].as_particle_system().seed()
+            };
+            (seed_of("Random"), seed_of("Fixed"))
+        };
+        let (first_random, first_fixed) = seeds(&mut scene);
+        let (second_random, second_fixed) = seeds(&mut scene);
+
+        // Every copy of the effect gets its own seed, unless the seed is fixed.
+        assert_ne!(first_random, second_random);
+        assert_eq!(first_fixed, 42);
+        assert_eq!(second_fixed, 42);
+    }
+}
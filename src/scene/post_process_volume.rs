@@ -0,0 +1,574 @@
+//! Post-process volume overrides post-processing settings of a camera, when the camera is inside of the
+//! volume. See [`PostProcessVolume`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        color::Color,
+        math::{aabb::AxisAlignedBoundingBox, lerpf},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        camera::{Camera, ColorGradingLut, Exposure},
+        debug::SceneDrawingContext,
+        graph::Graph,
+        node::{Node, NodeTrait},
+    },
+};
+use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Shape of a post-process volume, defined in the local space of the volume node.
+#[derive(Visit, Reflect, Copy, Clone, PartialEq, Debug, AsRefStr, EnumString, EnumVariantNames)]
+pub enum VolumeShape {
+    /// A box with the given half extents.
+    Box {
+        /// Half extents of the box along each axis.
+        half_extents: Vector3<f32>,
+    },
+    /// A sphere with the given radius.
+    Sphere {
+        /// Radius of the sphere.
+        #[reflect(min_value = 0.0, step = 0.1)]
+        radius: f32,
+    },
+}
+
+impl Default for VolumeShape {
+    fn default() -> Self {
+        Self::Box {
+            half_extents: Vector3::new(0.5, 0.5, 0.5),
+        }
+    }
+}
+
+/// A set of post-processing settings, that are overridden by a volume. `None` means that the setting is
+/// not overridden and the value of the camera (or volumes with lower priority) is used.
+#[derive(Visit, Reflect, Clone, Default, PartialEq, Debug)]
+pub struct PostProcessOverrides {
+    /// Exposure of the frame. Exposures of the same kind are blended smoothly, otherwise the exposure is
+    /// switched when the weight of the volume is higher than 0.5.
+    pub exposure: Option<Exposure>,
+    /// Color grading look-up table.
+    pub color_grading_lut: Option<ColorGradingLut>,
+    /// Multiplier of the bloom (glow) intensity.
+    pub bloom_intensity: Option<f32>,
+}
+
+/// Final post-processing settings of a camera, with every post-process volume applied. See
+/// [`PostProcessSettings::evaluate`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct PostProcessSettings {
+    /// Exposure of the frame.
+    pub exposure: Exposure,
+    /// Color grading look-up table, `None` means that color grading is disabled.
+    pub color_grading_lut: Option<ColorGradingLut>,
+    /// Color grading look-up table, that is blended with [`Self::color_grading_lut`] using
+    /// [`Self::color_grading_blend`] factor. `None` means that the frame without color grading is blended.
+    pub blend_color_grading_lut: Option<ColorGradingLut>,
+    /// Blend factor between the color grading look-up tables in `[0; 1]` range.
+    pub color_grading_blend: f32,
+    /// Multiplier of the bloom (glow) intensity.
+    pub bloom_intensity: f32,
+}
+
+fn blend_exposure(a: Exposure, b: Exposure, t: f32) -> Exposure {
+    match (a, b) {
+        (Exposure::Manual(a), Exposure::Manual(b)) => Exposure::Manual(lerpf(a, b, t)),
+        (
+            Exposure::Auto {
+                key_value: a_key_value,
+                min_luminance: a_min_luminance,
+                max_luminance: a_max_luminance,
+            },
+            Exposure::Auto {
+                key_value: b_key_value,
+                min_luminance: b_min_luminance,
+                max_luminance: b_max_luminance,
+            },
+        ) => Exposure::Auto {
+            key_value: lerpf(a_key_value, b_key_value, t),
+            min_luminance: lerpf(a_min_luminance, b_min_luminance, t),
+            max_luminance: lerpf(a_max_luminance, b_max_luminance, t),
+        },
+        _ => {
+            if t >= 0.5 {
+                b
+            } else {
+                a
+            }
+        }
+    }
+}
+
+impl PostProcessSettings {
+    /// Creates post-processing settings using the settings of the given camera.
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self {
+            exposure: camera.exposure(),
+            color_grading_lut: if camera.color_grading_enabled() {
+                camera.color_grading_lut()
+            } else {
+                None
+            },
+            blend_color_grading_lut: None,
+            color_grading_blend: 0.0,
+            bloom_intensity: 1.0,
+        }
+    }
+
+    /// Calculates post-processing settings of the camera, by blending every enabled post-process volume
+    /// of the graph at the position of the camera. Volumes are applied in the order of their priority.
+    pub fn evaluate(graph: &Graph, camera: &Camera) -> Self {
+        let mut settings = Self::from_camera(camera);
+
+        let position = camera.global_position();
+        let mut volumes = graph
+            .linear_iter()
+            .filter_map(|node| node.cast::<PostProcessVolume>())
+            .filter(|volume| volume.is_globally_enabled())
+            .map(|volume| (volume, volume.weight_at(position)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect::<Vec<_>>();
+        volumes.sort_by_key(|(volume, _)| volume.priority());
+
+        for (volume, weight) in volumes {
+            settings.blend(volume.overrides(), weight);
+        }
+
+        settings
+    }
+
+    /// Blends the settings with the given overrides. Only two color grading look-up tables could be
+    /// blended at once, so when a third one is blended, the current blend collapses to the dominant one.
+    pub fn blend(&mut self, overrides: &PostProcessOverrides, weight: f32) {
+        let weight = weight.clamp(0.0, 1.0);
+        if weight <= 0.0 {
+            return;
+        }
+
+        if let Some(exposure) = overrides.exposure {
+            self.exposure = blend_exposure(self.exposure, exposure, weight);
+        }
+
+        if let Some(bloom_intensity) = overrides.bloom_intensity {
+            self.bloom_intensity = lerpf(self.bloom_intensity, bloom_intensity, weight);
+        }
+
+        if let Some(lut) = overrides.color_grading_lut.as_ref() {
+            self.blend_color_grading_lut(lut, weight);
+        }
+    }
+
+    fn blend_color_grading_lut(&mut self, lut: &ColorGradingLut, weight: f32) {
+        if weight >= 1.0 {
+            self.color_grading_lut = Some(lut.clone());
+            self.blend_color_grading_lut = None;
+            self.color_grading_blend = 0.0;
+        } else if self.blend_color_grading_lut.as_ref() == Some(lut) {
+            self.color_grading_blend = lerpf(self.color_grading_blend, 1.0, weight);
+        } else {
+            if self.color_grading_blend >= 0.5 {
+                self.color_grading_lut = self.blend_color_grading_lut.take();
+            }
+
+            if self.color_grading_lut.as_ref() == Some(lut) {
+                self.blend_color_grading_lut = None;
+                self.color_grading_blend = 0.0;
+            } else {
+                self.blend_color_grading_lut = Some(lut.clone());
+                self.color_grading_blend = weight;
+            }
+        }
+    }
+}
+
+/// Post-process volume is a box or a sphere, that overrides post-processing settings (exposure, color
+/// grading, bloom intensity) of a camera, when the camera is inside of it. It is a standard way of
+/// changing mood between different areas of a level, for example a cave could be darker and colder than
+/// an open space outside of it.
+///
+/// ## Blending
+///
+/// The influence of a volume fades out smoothly over its blend distance outside of its shape. Overlapping
+/// volumes are applied in the order of their priority (volumes with higher priority are applied last, so
+/// they have the final word), every volume is blended with the result of the previous ones using its
+/// weight. Settings of the camera are used as the base. See [`PostProcessSettings::evaluate`].
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         base::BaseBuilder,
+///         camera::Exposure,
+///         graph::Graph,
+///         node::Node,
+///         post_process_volume::{PostProcessOverrides, PostProcessVolumeBuilder, VolumeShape},
+///     },
+/// };
+///
+/// fn create_dark_cave_volume(graph: &mut Graph) -> Handle<Node> {
+///     PostProcessVolumeBuilder::new(BaseBuilder::new())
+///         .with_shape(VolumeShape::Box {
+///             half_extents: Vector3::new(10.0, 5.0, 20.0),
+///         })
+///         .with_blend_distance(3.0)
+///         .with_overrides(PostProcessOverrides {
+///             exposure: Some(Exposure::Manual(0.5)),
+///             bloom_intensity: Some(0.25),
+///             ..Default::default()
+///         })
+///         .build(graph)
+/// }
+/// ```
+#[derive(Visit, Reflect, Debug, Clone)]
+pub struct PostProcessVolume {
+    base: Base,
+
+    #[reflect(setter = "set_shape")]
+    shape: InheritableVariable<VolumeShape>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_blend_distance")]
+    blend_distance: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.05)]
+    #[reflect(setter = "set_weight")]
+    weight: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_priority")]
+    priority: InheritableVariable<i32>,
+
+    #[reflect(setter = "set_overrides")]
+    overrides: InheritableVariable<PostProcessOverrides>,
+}
+
+impl Default for PostProcessVolume {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            shape: Default::default(),
+            blend_distance: InheritableVariable::new_modified(1.0),
+            weight: InheritableVariable::new_modified(1.0),
+            priority: Default::default(),
+            overrides: Default::default(),
+        }
+    }
+}
+
+impl Deref for PostProcessVolume {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for PostProcessVolume {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for PostProcessVolume {
+    fn type_uuid() -> Uuid {
+        uuid!("6f1c3a0e-2b7d-4c55-9d0e-8a4f5b2c7e13")
+    }
+}
+
+impl PostProcessVolume {
+    /// Sets new shape of the volume.
+    pub fn set_shape(&mut self, shape: VolumeShape) -> VolumeShape {
+        self.shape.set_value_and_mark_modified(shape)
+    }
+
+    /// Returns current shape of the volume.
+    pub fn shape(&self) -> VolumeShape {
+        *self.shape
+    }
+
+    /// Sets a distance outside of the shape, over which the influence of the volume fades out.
+    pub fn set_blend_distance(&mut self, distance: f32) -> f32 {
+        self.blend_distance
+            .set_value_and_mark_modified(distance.max(0.0))
+    }
+
+    /// Returns current blend distance of the volume.
+    pub fn blend_distance(&self) -> f32 {
+        *self.blend_distance
+    }
+
+    /// Sets the maximum influence of the volume in `[0; 1]` range.
+    pub fn set_weight(&mut self, weight: f32) -> f32 {
+        self.weight
+            .set_value_and_mark_modified(weight.clamp(0.0, 1.0))
+    }
+
+    /// Returns the maximum influence of the volume.
+    pub fn weight(&self) -> f32 {
+        *self.weight
+    }
+
+    /// Sets priority of the volume. Volumes with higher priority are applied after the volumes with
+    /// lower priority.
+    pub fn set_priority(&mut self, priority: i32) -> i32 {
+        self.priority.set_value_and_mark_modified(priority)
+    }
+
+    /// Returns priority of the volume.
+    pub fn priority(&self) -> i32 {
+        *self.priority
+    }
+
+    /// Sets new overridden settings.
+    pub fn set_overrides(&mut self, overrides: PostProcessOverrides) -> PostProcessOverrides {
+        self.overrides.set_value_and_mark_modified(overrides)
+    }
+
+    /// Returns a reference to the overridden settings.
+    pub fn overrides(&self) -> &PostProcessOverrides {
+        &self.overrides
+    }
+
+    /// Returns a reference to the overridden settings.
+    pub fn overrides_mut(&mut self) -> &mut PostProcessOverrides {
+        self.overrides.get_value_mut_and_mark_modified()
+    }
+
+    /// Returns influence of the volume at the given point in world space. It is equal to the weight of
+    /// the volume inside of its shape and fades out to zero over the blend distance outside of it.
+    pub fn weight_at(&self, point: Vector3<f32>) -> f32 {
+        let transform = self.global_transform();
+        let local_point = transform
+            .try_inverse()
+            .unwrap_or_default()
+            .transform_point(&Point3::from(point))
+            .coords;
+
+        let closest = match *self.shape {
+            VolumeShape::Box { half_extents } => Vector3::new(
+                local_point.x.clamp(-half_extents.x, half_extents.x),
+                local_point.y.clamp(-half_extents.y, half_extents.y),
+                local_point.z.clamp(-half_extents.z, half_extents.z),
+            ),
+            VolumeShape::Sphere { radius } => {
+                let distance = local_point.norm();
+                if distance > radius {
+                    local_point.scale(radius / distance)
+                } else {
+                    local_point
+                }
+            }
+        };
+
+        if closest == local_point {
+            return *self.weight;
+        }
+
+        let blend_distance = *self.blend_distance;
+        if blend_distance <= f32::EPSILON {
+            return 0.0;
+        }
+
+        let distance = (transform.transform_point(&Point3::from(closest)).coords - point).norm();
+
+        *self.weight * (1.0 - (distance / blend_distance).min(1.0))
+    }
+}
+
+impl NodeTrait for PostProcessVolume {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        match *self.shape {
+            VolumeShape::Box { half_extents } => {
+                AxisAlignedBoundingBox::from_min_max(-half_extents, half_extents)
+            }
+            VolumeShape::Sphere { radius } => AxisAlignedBoundingBox::from_radius(radius),
+        }
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let color = Color::opaque(255, 127, 39);
+        match *self.shape {
+            VolumeShape::Box { .. } => {
+                ctx.draw_oob(&self.local_bounding_box(), self.global_transform(), color);
+            }
+            VolumeShape::Sphere { radius } => {
+                // Non-uniform scale is ignored, it is good enough for visualization.
+                let scale = self
+                    .global_transform()
+                    .transform_vector(&Vector3::x())
+                    .norm();
+                ctx.draw_wire_sphere(self.global_position(), radius * scale, 24, color);
+            }
+        }
+    }
+}
+
+/// Allows you to create post-process volumes in declarative manner.
+pub struct PostProcessVolumeBuilder {
+    base_builder: BaseBuilder,
+    shape: VolumeShape,
+    blend_distance: f32,
+    weight: f32,
+    priority: i32,
+    overrides: PostProcessOverrides,
+}
+
+impl PostProcessVolumeBuilder {
+    /// Creates new post-process volume builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            shape: Default::default(),
+            blend_distance: 1.0,
+            weight: 1.0,
+            priority: 0,
+            overrides: Default::default(),
+        }
+    }
+
+    /// Sets desired shape of the volume.
+    pub fn with_shape(mut self, shape: VolumeShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Sets desired blend distance of the volume.
+    pub fn with_blend_distance(mut self, distance: f32) -> Self {
+        self.blend_distance = distance.max(0.0);
+        self
+    }
+
+    /// Sets desired weight of the volume.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets desired priority of the volume.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets desired overridden settings.
+    pub fn with_overrides(mut self, overrides: PostProcessOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Creates new post-process volume node.
+    pub fn build_node(self) -> Node {
+        Node::new(PostProcessVolume {
+            base: self.base_builder.build_base(),
+            shape: self.shape.into(),
+            blend_distance: self.blend_distance.into(),
+            weight: self.weight.into(),
+            priority: self.priority.into(),
+            overrides: self.overrides.into(),
+        })
+    }
+
+    /// Creates new post-process volume node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::Vector3,
+        scene::{
+            base::BaseBuilder,
+            camera::{CameraBuilder, Exposure},
+            graph::Graph,
+            post_process_volume::{
+                PostProcessOverrides, PostProcessSettings, PostProcessVolumeBuilder, VolumeShape,
+            },
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_post_process_volume_blending() {
+        let mut graph = Graph::new();
+
+        let camera = CameraBuilder::new(BaseBuilder::new())
+            .with_exposure(Exposure::Manual(1.0))
+            .build(&mut graph);
+
+        let volume = PostProcessVolumeBuilder::new(BaseBuilder::new())
+            .with_shape(VolumeShape::Box {
+                half_extents: Vector3::new(1.0, 1.0, 1.0),
+            })
+            .with_blend_distance(1.0)
+            .with_overrides(PostProcessOverrides {
+                exposure: Some(Exposure::Manual(3.0)),
+                bloom_intensity: Some(0.0),
+                ..Default::default()
+            })
+            .build(&mut graph);
+
+        // Overrides the bloom of the first volume, but does not touch the exposure.
+        PostProcessVolumeBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, 0.0, 10.0))
+                    .build(),
+            ),
+        )
+        .with_shape(VolumeShape::Sphere { radius: 2.0 })
+        .with_priority(1)
+        .with_overrides(PostProcessOverrides {
+            bloom_intensity: Some(2.0),
+            ..Default::default()
+        })
+        .build(&mut graph);
+
+        let mut settings_at = |position: Vector3<f32>| {
+            graph[camera].local_transform_mut().set_position(position);
+            graph.update_hierarchical_data();
+            PostProcessSettings::evaluate(&graph, graph[camera].as_camera())
+        };
+
+        let inside = settings_at(Vector3::new(0.5, 0.0, 0.0));
+        assert_eq!(inside.exposure, Exposure::Manual(3.0));
+        assert_eq!(inside.bloom_intensity, 0.0);
+
+        let blended = settings_at(Vector3::new(1.5, 0.0, 0.0));
+        assert_eq!(blended.exposure, Exposure::Manual(2.0));
+        assert_eq!(blended.bloom_intensity, 0.5);
+
+        let outside = settings_at(Vector3::new(3.0, 0.0, 0.0));
+        assert_eq!(outside.exposure, Exposure::Manual(1.0));
+        assert_eq!(outside.bloom_intensity, 1.0);
+
+        let other = settings_at(Vector3::new(0.0, 0.0, 9.0));
+        assert_eq!(other.exposure, Exposure::Manual(1.0));
+        assert_eq!(other.bloom_intensity, 2.0);
+
+        graph[volume].set_enabled(false);
+        let disabled = settings_at(Vector3::new(0.5, 0.0, 0.0));
+        assert_eq!(disabled.exposure, Exposure::Manual(1.0));
+    }
+}
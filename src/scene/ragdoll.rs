@@ -14,11 +14,16 @@ use crate::{
     impl_query_component,
     scene::{
         base::{Base, BaseBuilder},
+        collider::{ColliderBuilder, ColliderShape, InteractionGroups},
         graph::Graph,
+        joint::{BallJoint, JointBuilder, JointParams, RevoluteJoint},
+        mesh::Mesh,
         node::{Node, NodeTrait, UpdateContext},
-        rigidbody::{RigidBody, RigidBodyType},
+        rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyType},
+        transform::TransformBuilder,
     },
 };
+use fxhash::{FxHashMap, FxHashSet};
 use std::{
     any::{type_name, Any, TypeId},
     ops::{Deref, DerefMut},
@@ -181,8 +186,49 @@ pub struct Ragdoll {
     character_rigid_body: InheritableVariable<Handle<Node>>,
     is_active: InheritableVariable<bool>,
     root_limb: InheritableVariable<Limb>,
+    #[visit(optional)]
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_blend_time")]
+    blend_time: InheritableVariable<f32>,
     #[reflect(hidden)]
     prev_enabled: bool,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    blend_weight: f32,
+    // Last physics-driven local pose of each bone, it is used to blend out of the rag doll.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    physics_poses: FxHashMap<Handle<Node>, (Vector3<f32>, UnitQuaternion<f32>)>,
+}
+
+fn decompose(transform: &Matrix4<f32>) -> (Vector3<f32>, UnitQuaternion<f32>) {
+    (
+        Vector3::new(transform[12], transform[13], transform[14]),
+        UnitQuaternion::from_matrix_eps(&transform.basis(), f32::EPSILON, 16, Default::default()),
+    )
+}
+
+// Sets local transform of the bone to a blend between its current (animated) pose and the given
+// pose. Pre- and post-rotations of the bone are kept, so the animation could continue to drive
+// the bone when the rag doll is deactivated.
+fn blend_bone_pose(
+    bone: &mut Node,
+    position: Vector3<f32>,
+    rotation: UnitQuaternion<f32>,
+    weight: f32,
+) {
+    let (animated_position, animated_rotation) = decompose(&bone.local_transform().matrix());
+    let position = animated_position.lerp(&position, weight);
+    let rotation = animated_rotation
+        .try_slerp(&rotation, weight, f32::EPSILON)
+        .unwrap_or(rotation);
+
+    let transform = bone.local_transform_mut();
+    let local_rotation =
+        transform.pre_rotation().inverse() * rotation * transform.post_rotation().inverse();
+    transform
+        .set_position(position)
+        .set_rotation(local_rotation);
 }
 
 impl Deref for Ragdoll {
@@ -221,6 +267,19 @@ impl NodeTrait for Ragdoll {
     }
 
     fn update(&mut self, ctx: &mut UpdateContext) {
+        let target_weight = if *self.is_active { 1.0 } else { 0.0 };
+        if *self.blend_time > 0.0 {
+            let step = ctx.dt / *self.blend_time;
+            self.blend_weight = if target_weight > self.blend_weight {
+                (self.blend_weight + step).min(target_weight)
+            } else {
+                (self.blend_weight - step).max(target_weight)
+            };
+        } else {
+            self.blend_weight = target_weight;
+        }
+        let blend_weight = self.blend_weight;
+
         // Get linear and angular velocities of the character rigid body and transfer it onto rag doll bodies when it is just activated.
         let mut new_lin_vel = None;
         let mut new_ang_vel = None;
@@ -236,13 +295,17 @@ impl NodeTrait for Ragdoll {
         }
         self.prev_enabled = *self.is_active;
 
+        let is_active = *self.is_active;
+        let self_transform_inverse = self.global_transform().try_inverse().unwrap_or_default();
+        let physics_poses = &mut self.physics_poses;
+
         self.root_limb.iterate_recursive(&mut |limb| {
             if let Some(limb_body) = ctx
                 .nodes
                 .try_borrow_mut(limb.physical_bone)
                 .and_then(|n| n.query_component_mut::<RigidBody>())
             {
-                if *self.is_active {
+                if is_active {
                     // Transfer linear and angular velocities to rag doll bodies.
                     if let Some(lin_vel) = new_lin_vel {
                         limb_body.set_lin_vel(lin_vel);
@@ -264,17 +327,9 @@ impl NodeTrait for Ragdoll {
                         .unwrap_or_else(Matrix4::identity)
                         * body_transform;
 
-                    ctx.nodes[limb.bone]
-                        .local_transform_mut()
-                        .set_position(Vector3::new(transform[12], transform[13], transform[14]))
-                        .set_pre_rotation(UnitQuaternion::identity())
-                        .set_post_rotation(UnitQuaternion::identity())
-                        .set_rotation(UnitQuaternion::from_matrix_eps(
-                            &transform.basis(),
-                            f32::EPSILON,
-                            16,
-                            Default::default(),
-                        ));
+                    let (position, rotation) = decompose(&transform);
+                    physics_poses.insert(limb.bone, (position, rotation));
+                    blend_bone_pose(&mut ctx.nodes[limb.bone], position, rotation, blend_weight);
 
                     // Calculate transform of the descendants explicitly, so the next bones in hierarchy will have new transform
                     // that can be used to calculate relative transform.
@@ -290,24 +345,32 @@ impl NodeTrait for Ragdoll {
                     limb_body.set_lin_vel(Default::default());
                     limb_body.set_ang_vel(Default::default());
 
-                    let self_transform_inverse =
-                        self.global_transform().try_inverse().unwrap_or_default();
+                    // Blend from the last physics-driven pose to the animated one.
+                    if blend_weight > 0.0 {
+                        if let Some((position, rotation)) = physics_poses.get(&limb.bone) {
+                            blend_bone_pose(
+                                &mut ctx.nodes[limb.bone],
+                                *position,
+                                *rotation,
+                                blend_weight,
+                            );
+                            Graph::update_hierarchical_data_recursively(
+                                ctx.nodes,
+                                ctx.sound_context,
+                                ctx.physics,
+                                ctx.physics2d,
+                                limb.bone,
+                            );
+                        }
+                    } else {
+                        physics_poses.remove(&limb.bone);
+                    }
 
                     // Sync transform of the physical body with respective bone.
                     if let Some(bone) = ctx.nodes.try_borrow(limb.bone) {
                         let relative_transform = self_transform_inverse * bone.global_transform();
 
-                        let position = Vector3::new(
-                            relative_transform[12],
-                            relative_transform[13],
-                            relative_transform[14],
-                        );
-                        let rotation = UnitQuaternion::from_matrix_eps(
-                            &relative_transform.basis(),
-                            f32::EPSILON,
-                            16,
-                            Default::default(),
-                        );
+                        let (position, rotation) = decompose(&relative_transform);
                         ctx.nodes[limb.physical_bone]
                             .local_transform_mut()
                             .set_position(position)
@@ -350,6 +413,23 @@ impl Ragdoll {
     pub fn set_root_limb(&mut self, root_limb: Limb) {
         self.root_limb.set_value_and_mark_modified(root_limb);
     }
+
+    /// Sets time (in seconds) of the transition between animation-driven and physics-driven poses
+    /// of the bones. Zero means instant switch. Default is zero.
+    pub fn set_blend_time(&mut self, time: f32) -> f32 {
+        self.blend_time.set_value_and_mark_modified(time.max(0.0))
+    }
+
+    /// Returns time (in seconds) of the transition between animation-driven and physics-driven poses.
+    pub fn blend_time(&self) -> f32 {
+        *self.blend_time
+    }
+
+    /// Returns current weight of physics-driven pose of the bones. Zero means that the bones are fully
+    /// driven by animation, one - the bones are fully driven by physics.
+    pub fn blend_weight(&self) -> f32 {
+        self.blend_weight
+    }
 }
 
 pub struct RagdollBuilder {
@@ -357,6 +437,7 @@ pub struct RagdollBuilder {
     character_rigid_body: Handle<Node>,
     is_active: bool,
     root_limb: Limb,
+    blend_time: f32,
 }
 
 impl RagdollBuilder {
@@ -366,6 +447,7 @@ impl RagdollBuilder {
             character_rigid_body: Default::default(),
             is_active: true,
             root_limb: Default::default(),
+            blend_time: 0.0,
         }
     }
 
@@ -384,15 +466,501 @@ impl RagdollBuilder {
         self
     }
 
+    /// Sets desired time of the transition between animation-driven and physics-driven poses.
+    pub fn with_blend_time(mut self, time: f32) -> Self {
+        self.blend_time = time;
+        self
+    }
+
     pub fn build(self, graph: &mut Graph) -> Handle<Node> {
         let ragdoll = Ragdoll {
             base: self.base_builder.build_base(),
             character_rigid_body: self.character_rigid_body.into(),
             is_active: self.is_active.into(),
             root_limb: self.root_limb.into(),
+            blend_time: self.blend_time.into(),
             prev_enabled: self.is_active,
+            blend_weight: if self.is_active { 1.0 } else { 0.0 },
+            physics_poses: Default::default(),
         };
 
         graph.add_node(Node::new(ragdoll))
     }
 }
+
+struct BoneSegment {
+    bone: Handle<Node>,
+    parent: Option<usize>,
+    begin: Vector3<f32>,
+    end: Vector3<f32>,
+    radius: f32,
+}
+
+impl BoneSegment {
+    fn length(&self) -> f32 {
+        (self.end - self.begin).norm()
+    }
+
+    fn volume(&self) -> f32 {
+        std::f32::consts::PI * self.radius * self.radius * (self.length() + 4.0 / 3.0 * self.radius)
+    }
+}
+
+/// Generates a rag doll for an arbitrary skeleton. Every bone, that has at least one child bone, gets a rigid
+/// body with a capsule collider, which goes from the bone to the center of its child bones. Bodies of adjacent
+/// bones are connected with ball joints (or revolute joints, see [`RagdollGenerator::with_hinge_bones`]). Leaf
+/// bones (usually end markers, such as `HeadTop_End`) only define the size of their parent capsules.
+///
+/// Generated rag doll is inactive, which means that its bodies follow the animated bones. Use
+/// [`Ragdoll::set_active`] to switch to physics-driven poses and [`Ragdoll::set_blend_time`] to make the
+/// transition smooth.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::pool::Handle,
+///     scene::{base::BaseBuilder, graph::Graph, node::Node, ragdoll::RagdollGenerator},
+/// };
+///
+/// fn create_ragdoll(graph: &mut Graph, skinned_mesh: Handle<Node>) -> Handle<Node> {
+///     RagdollGenerator::from_mesh(graph, skinned_mesh)
+///         .map(|generator| {
+///             generator
+///                 .with_total_mass(80.0)
+///                 .with_blend_time(0.3)
+///                 .generate(BaseBuilder::new().with_name("Ragdoll"), graph)
+///         })
+///         .unwrap_or_default()
+/// }
+/// ```
+pub struct RagdollGenerator {
+    root_bone: Handle<Node>,
+    bones: Option<FxHashSet<Handle<Node>>>,
+    ignored_bones: FxHashSet<Handle<Node>>,
+    hinge_bones: FxHashSet<Handle<Node>>,
+    total_mass: f32,
+    radius_ratio: f32,
+    min_bone_length: f32,
+    joint_limit: f32,
+    friction: f32,
+    use_ccd: bool,
+    can_sleep: bool,
+    collision_groups: InteractionGroups,
+    solver_groups: InteractionGroups,
+    character_rigid_body: Handle<Node>,
+    blend_time: f32,
+}
+
+impl RagdollGenerator {
+    /// Creates a generator for a skeleton with the given root bone. Every descendant of the root bone is
+    /// considered as a bone.
+    pub fn new(root_bone: Handle<Node>) -> Self {
+        Self {
+            root_bone,
+            bones: None,
+            ignored_bones: Default::default(),
+            hinge_bones: Default::default(),
+            total_mass: 70.0,
+            radius_ratio: 0.2,
+            min_bone_length: 0.05,
+            joint_limit: 45.0f32.to_radians(),
+            friction: 0.5,
+            use_ccd: false,
+            can_sleep: true,
+            collision_groups: Default::default(),
+            solver_groups: Default::default(),
+            character_rigid_body: Default::default(),
+            blend_time: 0.0,
+        }
+    }
+
+    /// Creates a generator for the skeleton of the given skinned mesh. Only the bones that are used by the
+    /// surfaces of the mesh (and their end markers) are considered. Returns `None` if the node is not a mesh
+    /// or the mesh has no bones.
+    pub fn from_mesh(graph: &Graph, mesh: Handle<Node>) -> Option<Self> {
+        let mesh = graph.try_get(mesh)?.cast::<Mesh>()?;
+
+        let mut bones = FxHashSet::default();
+        for surface in mesh.surfaces() {
+            bones.extend(surface.bones().iter().cloned());
+        }
+
+        // Root bone is the bone, which parent is not a bone.
+        let root_bone = bones
+            .iter()
+            .cloned()
+            .find(|bone| !bones.contains(&graph[*bone].parent()))?;
+
+        // End markers are not used by surfaces, but they define sizes of the capsules.
+        let ends = bones
+            .iter()
+            .flat_map(|bone| graph[*bone].children().iter().cloned())
+            .filter(|child| graph[*child].children().is_empty())
+            .collect::<Vec<_>>();
+        bones.extend(ends);
+
+        Some(Self {
+            bones: Some(bones),
+            ..Self::new(root_bone)
+        })
+    }
+
+    /// Excludes the given bones and their descendants from the simulation, they will follow the closest
+    /// simulated parent bone.
+    pub fn with_ignored_bones(mut self, bones: &[Handle<Node>]) -> Self {
+        self.ignored_bones.extend(bones.iter().cloned());
+        self
+    }
+
+    /// Sets a list of bones, that will be connected to their parent bones using revolute (hinge) joints
+    /// instead of ball joints. Rotation axis of a hinge is the local X axis of the bone. Usually it is used
+    /// for elbows and knees.
+    pub fn with_hinge_bones(mut self, bones: &[Handle<Node>]) -> Self {
+        self.hinge_bones.extend(bones.iter().cloned());
+        self
+    }
+
+    /// Sets total mass of the rag doll. It is distributed across the bodies proportionally to their volume.
+    pub fn with_total_mass(mut self, mass: f32) -> Self {
+        self.total_mass = mass;
+        self
+    }
+
+    /// Sets radius of the capsules relative to the length of their bones.
+    pub fn with_radius_ratio(mut self, ratio: f32) -> Self {
+        self.radius_ratio = ratio;
+        self
+    }
+
+    /// Sets minimal length of a bone, shorter bones (such as fingers) are not simulated along with their
+    /// descendants.
+    pub fn with_min_bone_length(mut self, length: f32) -> Self {
+        self.min_bone_length = length;
+        self
+    }
+
+    /// Sets maximum angle (in radians) of rotation of a bone relative to its parent around each axis.
+    pub fn with_joint_limit(mut self, angle: f32) -> Self {
+        self.joint_limit = angle;
+        self
+    }
+
+    /// Sets friction coefficient of every collider of the rag doll.
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    /// Sets whether the bodies of the rag doll should use continuous collision detection or not.
+    pub fn with_ccd_enabled(mut self, enabled: bool) -> Self {
+        self.use_ccd = enabled;
+        self
+    }
+
+    /// Sets whether the bodies of the rag doll can sleep or not.
+    pub fn with_can_sleep(mut self, can_sleep: bool) -> Self {
+        self.can_sleep = can_sleep;
+        self
+    }
+
+    /// Sets collision groups of every collider of the rag doll. It could be used to filter out collisions
+    /// between character capsule and any part of the rag doll.
+    pub fn with_collision_groups(mut self, groups: InteractionGroups) -> Self {
+        self.collision_groups = groups;
+        self
+    }
+
+    /// Sets solver groups of every collider of the rag doll.
+    pub fn with_solver_groups(mut self, groups: InteractionGroups) -> Self {
+        self.solver_groups = groups;
+        self
+    }
+
+    /// Sets a rigid body of the character, its velocity will be transferred to the rag doll on activation.
+    pub fn with_character_rigid_body(mut self, handle: Handle<Node>) -> Self {
+        self.character_rigid_body = handle;
+        self
+    }
+
+    /// Sets time of the transition between animation-driven and physics-driven poses.
+    pub fn with_blend_time(mut self, time: f32) -> Self {
+        self.blend_time = time;
+        self
+    }
+
+    fn is_bone(&self, graph: &Graph, handle: Handle<Node>) -> bool {
+        !self.ignored_bones.contains(&handle)
+            && graph.is_valid_handle(handle)
+            && self
+                .bones
+                .as_ref()
+                .map_or(true, |bones| bones.contains(&handle))
+    }
+
+    fn collect_segments(
+        &self,
+        graph: &Graph,
+        bone: Handle<Node>,
+        parent: Option<usize>,
+        segments: &mut Vec<BoneSegment>,
+    ) {
+        let child_bones = graph[bone]
+            .children()
+            .iter()
+            .cloned()
+            .filter(|child| self.is_bone(graph, *child))
+            .collect::<Vec<_>>();
+
+        if child_bones.is_empty() {
+            return;
+        }
+
+        let begin = graph[bone].global_position();
+        let end = child_bones
+            .iter()
+            .map(|child| graph[*child].global_position())
+            .sum::<Vector3<f32>>()
+            .scale(1.0 / child_bones.len() as f32);
+
+        let length = (end - begin).norm();
+        if length < self.min_bone_length {
+            return;
+        }
+
+        let index = segments.len();
+        segments.push(BoneSegment {
+            bone,
+            parent,
+            begin,
+            end,
+            radius: length * self.radius_ratio,
+        });
+
+        for child in child_bones {
+            self.collect_segments(graph, child, Some(index), segments);
+        }
+    }
+
+    fn make_body(
+        &self,
+        segment: &BoneSegment,
+        mass: f32,
+        ragdoll: Handle<Node>,
+        graph: &mut Graph,
+    ) -> Handle<Node> {
+        let length = segment.length();
+        let half_height = (length * 0.5 - segment.radius).max(0.0);
+        let center = length * 0.5;
+
+        let collider = ColliderBuilder::new(BaseBuilder::new().with_name("CapsuleCollider"))
+            .with_shape(ColliderShape::capsule(
+                Vector3::new(0.0, center - half_height, 0.0),
+                Vector3::new(0.0, center + half_height, 0.0),
+                segment.radius,
+            ))
+            .with_collision_groups(self.collision_groups)
+            .with_solver_groups(self.solver_groups)
+            .with_friction(self.friction)
+            .build(graph);
+
+        let rotation =
+            UnitQuaternion::rotation_between(&Vector3::y(), &(segment.end - segment.begin))
+                .unwrap_or_else(UnitQuaternion::identity);
+
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_name(format!("Ragdoll{}", graph[segment.bone].name()))
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(segment.begin)
+                        .with_local_rotation(rotation)
+                        .build(),
+                )
+                .with_children(&[collider]),
+        )
+        .with_mass(mass)
+        .with_can_sleep(self.can_sleep)
+        .with_ccd_enabled(self.use_ccd)
+        .with_body_type(RigidBodyType::KinematicPositionBased)
+        .build(graph);
+
+        graph.link_nodes(body, ragdoll);
+
+        body
+    }
+
+    fn make_joint(
+        &self,
+        bone: Handle<Node>,
+        body1: Handle<Node>,
+        body2: Handle<Node>,
+        ragdoll: Handle<Node>,
+        graph: &mut Graph,
+    ) -> Handle<Node> {
+        let limits = -self.joint_limit..self.joint_limit;
+        let params = if self.hinge_bones.contains(&bone) {
+            JointParams::RevoluteJoint(RevoluteJoint {
+                limits_enabled: true,
+                limits,
+                ..Default::default()
+            })
+        } else {
+            JointParams::BallJoint(BallJoint {
+                x_limits_enabled: true,
+                x_limits_angles: limits.clone(),
+                y_limits_enabled: true,
+                y_limits_angles: limits.clone(),
+                z_limits_enabled: true,
+                z_limits_angles: limits,
+            })
+        };
+
+        let bone_ref = &graph[bone];
+        let joint = JointBuilder::new(
+            BaseBuilder::new()
+                .with_name(format!("Ragdoll{}Joint", bone_ref.name()))
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(bone_ref.global_position())
+                        .with_local_rotation(decompose(&bone_ref.global_transform()).1)
+                        .build(),
+                ),
+        )
+        .with_params(params)
+        .with_body1(body1)
+        .with_body2(body2)
+        .with_auto_rebinding_enabled(false)
+        .with_contacts_enabled(false)
+        .build(graph);
+
+        graph.link_nodes(joint, ragdoll);
+
+        joint
+    }
+
+    /// Generates rigid bodies, colliders and joints for the skeleton and creates a new [`Ragdoll`] node, that
+    /// controls them. The rag doll node is added to the root of the graph. Returns a handle of the rag doll
+    /// node.
+    pub fn generate(self, base_builder: BaseBuilder, graph: &mut Graph) -> Handle<Node> {
+        // Global transforms of the bones must be up-to-date.
+        graph.update_hierarchical_data();
+
+        let mut segments = Vec::new();
+        if self.is_bone(graph, self.root_bone) {
+            self.collect_segments(graph, self.root_bone, None, &mut segments);
+        }
+
+        let ragdoll = RagdollBuilder::new(base_builder)
+            .with_active(false)
+            .with_character_rigid_body(self.character_rigid_body)
+            .with_blend_time(self.blend_time)
+            .build(graph);
+
+        let total_volume = segments.iter().map(|s| s.volume()).sum::<f32>();
+
+        let mut bodies = Vec::with_capacity(segments.len());
+        for segment in segments.iter() {
+            let mass = if total_volume > 0.0 {
+                self.total_mass * segment.volume() / total_volume
+            } else {
+                0.0
+            };
+            bodies.push(self.make_body(segment, mass, ragdoll, graph));
+        }
+
+        for (segment, body) in segments.iter().zip(bodies.iter()) {
+            if let Some(parent) = segment.parent {
+                self.make_joint(segment.bone, bodies[parent], *body, ragdoll, graph);
+            }
+        }
+
+        // Segments are stored in depth-first order, so children always come after their parents.
+        let mut limbs = segments
+            .iter()
+            .zip(bodies.iter())
+            .map(|(segment, body)| Limb {
+                bone: segment.bone,
+                physical_bone: *body,
+                children: Default::default(),
+            })
+            .collect::<Vec<_>>();
+        for index in (1..segments.len()).rev() {
+            if let Some(parent) = segments[index].parent {
+                let limb = limbs.remove(index);
+                limbs[parent].children.insert(0, limb);
+            }
+        }
+
+        if let Some(root_limb) = limbs.pop() {
+            graph[ragdoll].as_ragdoll_mut().set_root_limb(root_limb);
+        }
+
+        ragdoll
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder, graph::Graph, pivot::PivotBuilder, ragdoll::RagdollGenerator,
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_ragdoll_generator() {
+        let mut graph = Graph::new();
+
+        // Hips -> Spine -> Head -> HeadEnd chain with a tiny Finger bone.
+        let bone = |graph: &mut Graph, y: f32, children: &[_]| {
+            PivotBuilder::new(
+                BaseBuilder::new()
+                    .with_children(children)
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(0.0, y, 0.0))
+                            .build(),
+                    ),
+            )
+            .build(graph)
+        };
+        let head_end = bone(&mut graph, 0.3, &[]);
+        let head = bone(&mut graph, 0.5, &[head_end]);
+        let finger = bone(&mut graph, 0.01, &[]);
+        let spine = bone(&mut graph, 0.5, &[head, finger]);
+        let hips = bone(&mut graph, 1.0, &[spine]);
+
+        let ragdoll = RagdollGenerator::new(hips)
+            .with_total_mass(60.0)
+            .with_ignored_bones(&[finger])
+            .generate(BaseBuilder::new(), &mut graph);
+
+        let root_limb = graph[ragdoll].as_ragdoll().root_limb().clone();
+        assert_eq!(root_limb.bone, hips);
+        assert_eq!(root_limb.children.len(), 1);
+        assert_eq!(root_limb.children[0].bone, spine);
+        assert_eq!(root_limb.children[0].children[0].bone, head);
+        assert!(root_limb.children[0].children[0].children.is_empty());
+
+        let bodies = [
+            root_limb.physical_bone,
+            root_limb.children[0].physical_bone,
+            root_limb.children[0].children[0].physical_bone,
+        ];
+        let mass = bodies
+            .iter()
+            .map(|body| graph[*body].as_rigid_body().mass())
+            .sum::<f32>();
+        assert!((mass - 60.0).abs() < 0.001);
+        assert_eq!(graph.linear_iter().filter(|n| n.is_joint()).count(), 2);
+
+        // Inactive rag doll must follow the bones.
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        assert!((graph[bodies[2]].global_position() - Vector3::new(0.0, 2.0, 0.0)).norm() < 0.001);
+    }
+}
@@ -10,14 +10,14 @@
 //! [`RigidBody::set_can_sleep`] with `false` value.
 use crate::{
     core::{
-        algebra::{Matrix4, Vector3},
+        algebra::{Matrix4, UnitQuaternion, Vector3},
         log::Log,
         math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
         parking_lot::Mutex,
         pool::Handle,
         reflect::prelude::*,
         uuid::{uuid, Uuid},
-        variable::InheritableVariable,
+        variable::{InheritableVariable, VariableFlags},
         visitor::prelude::*,
         TypeUuidProvider,
     },
@@ -29,6 +29,7 @@ use crate::{
         Scene,
     },
 };
+use bitflags::bitflags;
 use rapier3d::{dynamics, prelude::RigidBodyHandle};
 use std::{
     cell::Cell,
@@ -49,10 +50,15 @@ pub enum RigidBodyType {
     /// Static rigid bodies cannot be affected by external forces.
     Static = 1,
     /// Kinematic rigid body cannot be affected by external forces, but can push other rigid bodies.
-    /// It also does not have any dynamic, you are able to control the position manually.
+    /// It also does not have any dynamic, you are able to control the position manually. Global
+    /// transform of the node (including the one set by animations) is used as the next kinematic
+    /// position of the body, so the body moves smoothly and pushes dynamic bodies on its way.
     KinematicPositionBased = 2,
     /// Kinematic rigid body cannot be affected by external forces, but can push other rigid bodies.
-    /// It also does not have any dynamic, you are able to control the position by changing velocity.
+    /// It also does not have any dynamic, you are able to control the position by changing velocity
+    /// (see [`RigidBody::set_lin_vel`] and [`RigidBody::set_ang_vel`]). The physics engine integrates
+    /// the velocities and moves the node, so such bodies are well suited for moving platforms, elevators,
+    /// conveyors, etc. - dynamic bodies standing on them are carried along by friction.
     KinematicVelocityBased = 3,
 }
 
@@ -118,6 +124,52 @@ pub(crate) enum ApplyAction {
         point: Vector3<f32>,
     },
     WakeUp,
+    Sleep,
+    RecomputeMassProperties,
+}
+
+/// Mass properties of a rigid body, that are calculated by the physics engine using densities and shapes of
+/// the colliders of the body and its additional mass (see [`RigidBody::set_mass`]). See
+/// [`RigidBody::mass_properties`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RigidBodyMassProperties {
+    /// Total mass of the body (in kg).
+    pub mass: f32,
+    /// Center of mass in local coordinates of the body.
+    pub local_center_of_mass: Vector3<f32>,
+    /// Center of mass in world coordinates.
+    pub center_of_mass: Vector3<f32>,
+    /// Principal angular inertia of the body (in kg·m²) relative to its center of mass.
+    pub principal_inertia: Vector3<f32>,
+    /// Rotation of the principal inertia axes in local coordinates of the body.
+    pub principal_inertia_local_frame: UnitQuaternion<f32>,
+}
+
+impl Default for RigidBodyMassProperties {
+    fn default() -> Self {
+        Self {
+            mass: 0.0,
+            local_center_of_mass: Default::default(),
+            center_of_mass: Default::default(),
+            principal_inertia: Default::default(),
+            principal_inertia_local_frame: UnitQuaternion::identity(),
+        }
+    }
+}
+
+/// A set of flags that defines which properties of a rigid body were changed and must be synced
+/// with the native rigid body on next physics update.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct RigidBodyChanges(u32);
+
+bitflags! {
+    impl RigidBodyChanges: u32 {
+        /// Nothing.
+        const NONE = 0;
+        /// Continuous collision detection flag (see [`RigidBody::set_ccd_enabled`]).
+        const CCD = 0b0000_0001;
+    }
 }
 
 /// Rigid body is a physics entity that responsible for the dynamics and kinematics of the solid.
@@ -127,6 +179,22 @@ pub(crate) enum ApplyAction {
 ///
 /// Rigid body that does not move for some time will go asleep. This means that the body will not
 /// move unless it is woken up by some other moving body. This feature allows to save CPU resources.
+/// Forces, torques and impulses applied via `apply_*` methods wake up the body automatically.
+///
+/// A body is considered as non-moving when its linear and angular velocities are below the sleep
+/// thresholds (see [`RigidBody::set_sleep_linear_threshold`] and
+/// [`RigidBody::set_sleep_angular_threshold`]) for [`RigidBody::time_until_sleep`] seconds. Use
+/// [`RigidBody::set_can_sleep`] to keep a body awake all the time (for example, a conveyor belt) and
+/// [`RigidBody::sleep`] to put a body to sleep explicitly (for example, far away from the player).
+///
+/// # Mass properties
+///
+/// Mass, center of mass and angular inertia of the body are calculated automatically using shapes and
+/// densities of its colliders (see [`crate::scene::collider::Collider::set_density`]) plus additional mass
+/// of the body (see [`RigidBody::set_mass`]). The result could be fetched using
+/// [`RigidBody::mass_properties`]. The center of mass could be overridden using
+/// [`RigidBody::set_local_center_of_mass`] - it is very useful for vehicles (lower center of mass makes a
+/// car more stable) and for balancing characters.
 #[derive(Visit, Reflect)]
 pub struct RigidBody {
     base: Base,
@@ -162,26 +230,63 @@ pub struct RigidBody {
     #[reflect(setter = "lock_translation")]
     pub(crate) translation_locked: InheritableVariable<bool>,
 
+    #[reflect(setter = "lock_x_translation")]
+    #[visit(optional)]
+    pub(crate) x_translation_locked: InheritableVariable<bool>,
+
+    #[reflect(setter = "lock_y_translation")]
+    #[visit(optional)]
+    pub(crate) y_translation_locked: InheritableVariable<bool>,
+
+    #[reflect(setter = "lock_z_translation")]
+    #[visit(optional)]
+    pub(crate) z_translation_locked: InheritableVariable<bool>,
+
     #[reflect(setter = "enable_ccd")]
     pub(crate) ccd_enabled: InheritableVariable<bool>,
 
     #[reflect(setter = "set_can_sleep")]
     pub(crate) can_sleep: InheritableVariable<bool>,
 
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_sleep_linear_threshold")]
+    #[visit(optional)]
+    pub(crate) sleep_linear_threshold: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_sleep_angular_threshold")]
+    #[visit(optional)]
+    pub(crate) sleep_angular_threshold: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_time_until_sleep")]
+    #[visit(optional)]
+    pub(crate) time_until_sleep: InheritableVariable<f32>,
+
     #[reflect(setter = "set_dominance")]
     pub(crate) dominance: InheritableVariable<i8>,
 
     #[reflect(setter = "set_gravity_scale")]
     pub(crate) gravity_scale: InheritableVariable<f32>,
 
+    #[reflect(setter = "set_local_center_of_mass")]
+    #[visit(optional)]
+    pub(crate) local_center_of_mass: InheritableVariable<Option<Vector3<f32>>>,
+
     #[visit(skip)]
     #[reflect(hidden)]
     pub(crate) sleeping: bool,
     #[visit(skip)]
     #[reflect(hidden)]
+    pub(crate) mass_properties: RigidBodyMassProperties,
+    #[visit(skip)]
+    #[reflect(hidden)]
     pub(crate) reset_forces: Cell<bool>,
     #[visit(skip)]
     #[reflect(hidden)]
+    pub(crate) changes: Cell<RigidBodyChanges>,
+    #[visit(skip)]
+    #[reflect(hidden)]
     pub(crate) native: Cell<RigidBodyHandle>,
     #[visit(skip)]
     #[reflect(hidden)]
@@ -209,13 +314,28 @@ impl Default for RigidBody {
             y_rotation_locked: Default::default(),
             z_rotation_locked: Default::default(),
             translation_locked: Default::default(),
+            x_translation_locked: Default::default(),
+            y_translation_locked: Default::default(),
+            z_translation_locked: Default::default(),
             ccd_enabled: Default::default(),
             can_sleep: InheritableVariable::new_modified(true),
+            sleep_linear_threshold: InheritableVariable::new_modified(
+                dynamics::RigidBodyActivation::default_linear_threshold(),
+            ),
+            sleep_angular_threshold: InheritableVariable::new_modified(
+                dynamics::RigidBodyActivation::default_angular_threshold(),
+            ),
+            time_until_sleep: InheritableVariable::new_modified(
+                dynamics::RigidBodyActivation::default_time_until_sleep(),
+            ),
             dominance: Default::default(),
             gravity_scale: InheritableVariable::new_modified(1.0),
+            local_center_of_mass: Default::default(),
+            mass_properties: Default::default(),
             native: Cell::new(RigidBodyHandle::invalid()),
             actions: Default::default(),
             reset_forces: Default::default(),
+            changes: Default::default(),
         }
     }
 }
@@ -249,14 +369,23 @@ impl Clone for RigidBody {
             y_rotation_locked: self.y_rotation_locked.clone(),
             z_rotation_locked: self.z_rotation_locked.clone(),
             translation_locked: self.translation_locked.clone(),
+            x_translation_locked: self.x_translation_locked.clone(),
+            y_translation_locked: self.y_translation_locked.clone(),
+            z_translation_locked: self.z_translation_locked.clone(),
             ccd_enabled: self.ccd_enabled.clone(),
             can_sleep: self.can_sleep.clone(),
+            sleep_linear_threshold: self.sleep_linear_threshold.clone(),
+            sleep_angular_threshold: self.sleep_angular_threshold.clone(),
+            time_until_sleep: self.time_until_sleep.clone(),
             dominance: self.dominance.clone(),
             gravity_scale: self.gravity_scale.clone(),
+            local_center_of_mass: self.local_center_of_mass.clone(),
+            mass_properties: self.mass_properties,
             // Do not copy. The copy will have its own native representation.
             native: Cell::new(RigidBodyHandle::invalid()),
             actions: Default::default(),
             reset_forces: self.reset_forces.clone(),
+            changes: self.changes.clone(),
         }
     }
 }
@@ -269,7 +398,8 @@ impl TypeUuidProvider for RigidBody {
 
 impl RigidBody {
     /// Sets new linear velocity of the rigid body. Changing this parameter will wake up the rigid
-    /// body!
+    /// body! For [`RigidBodyType::KinematicVelocityBased`] bodies this is the desired velocity, that
+    /// is kept until changed.
     pub fn set_lin_vel(&mut self, lin_vel: Vector3<f32>) -> Vector3<f32> {
         self.lin_vel.set_value_and_mark_modified(lin_vel)
     }
@@ -280,7 +410,8 @@ impl RigidBody {
     }
 
     /// Sets new angular velocity of the rigid body. Changing this parameter will wake up the rigid
-    /// body!
+    /// body! For [`RigidBodyType::KinematicVelocityBased`] bodies this is the desired velocity, that
+    /// is kept until changed.
     pub fn set_ang_vel(&mut self, ang_vel: Vector3<f32>) -> Vector3<f32> {
         self.ang_vel.set_value_and_mark_modified(ang_vel)
     }
@@ -296,11 +427,49 @@ impl RigidBody {
         self.mass.set_value_and_mark_modified(mass)
     }
 
-    /// Returns _additional_ mass of the rigid body.
+    /// Returns _additional_ mass of the rigid body. Use [`Self::mass_properties`] to get the total mass.
     pub fn mass(&self) -> f32 {
         *self.mass
     }
 
+    /// Overrides center of mass of the rigid body (in local coordinates of the body). `None` restores the
+    /// center of mass calculated from the colliders of the body. Total mass and angular inertia (relative
+    /// to the center of mass) of the body are not changed by the override.
+    ///
+    /// Keep in mind, that the override affects only colliders with non-zero mass, so it does nothing if
+    /// every collider of the body has zero density.
+    pub fn set_local_center_of_mass(
+        &mut self,
+        center_of_mass: Option<Vector3<f32>>,
+    ) -> Option<Vector3<f32>> {
+        self.local_center_of_mass
+            .set_value_and_mark_modified(center_of_mass)
+    }
+
+    /// Returns overridden center of mass of the rigid body, if any. Use [`Self::mass_properties`] to get
+    /// the actual center of mass.
+    pub fn local_center_of_mass(&self) -> Option<Vector3<f32>> {
+        *self.local_center_of_mass
+    }
+
+    /// Returns mass properties of the rigid body, that were calculated by the physics engine. The properties
+    /// are updated after each physics step, so changes of densities or shapes of colliders are reflected only
+    /// after the next update of the scene.
+    pub fn mass_properties(&self) -> &RigidBodyMassProperties {
+        &self.mass_properties
+    }
+
+    /// Forces the physics engine to recalculate mass properties of the rigid body using current densities and
+    /// shapes of its colliders, without waiting for the next simulation step. The physics engine does this
+    /// automatically at each simulation step when colliders are changed, so this method is needed only if
+    /// up-to-date mass properties (see [`Self::mass_properties`]) are required while the simulation is
+    /// paused. The properties are recalculated at the next scene update.
+    pub fn recompute_mass_properties(&mut self) {
+        self.actions
+            .get_mut()
+            .push_back(ApplyAction::RecomputeMassProperties)
+    }
+
     /// Sets angular damping of the rigid body. Angular damping will decrease angular velocity over
     /// time. Default is zero.
     pub fn set_ang_damping(&mut self, damping: f32) -> f32 {
@@ -370,6 +539,48 @@ impl RigidBody {
         *self.translation_locked
     }
 
+    /// Locks translation along X axis in world coordinates. It could be used to constrain the body
+    /// to a plane (for 2.5D games) or to a line (for objects moving along rails).
+    pub fn lock_x_translation(&mut self, state: bool) -> bool {
+        self.x_translation_locked.set_value_and_mark_modified(state)
+    }
+
+    /// Returns true if translation along X axis is locked, false - otherwise. Keep in mind that
+    /// translation along every axis is locked if [`Self::is_translation_locked`] is true.
+    pub fn is_x_translation_locked(&self) -> bool {
+        *self.x_translation_locked
+    }
+
+    /// Locks translation along Y axis in world coordinates.
+    pub fn lock_y_translation(&mut self, state: bool) -> bool {
+        self.y_translation_locked.set_value_and_mark_modified(state)
+    }
+
+    /// Returns true if translation along Y axis is locked, false - otherwise.
+    pub fn is_y_translation_locked(&self) -> bool {
+        *self.y_translation_locked
+    }
+
+    /// Locks translation along Z axis in world coordinates.
+    pub fn lock_z_translation(&mut self, state: bool) -> bool {
+        self.z_translation_locked.set_value_and_mark_modified(state)
+    }
+
+    /// Returns true if translation along Z axis is locked, false - otherwise.
+    pub fn is_z_translation_locked(&self) -> bool {
+        *self.z_translation_locked
+    }
+
+    // Returns whether translation along each axis is locked, taking both per-axis and full locks into
+    // account.
+    pub(crate) fn locked_translations(&self) -> [bool; 3] {
+        [
+            *self.translation_locked || *self.x_translation_locked,
+            *self.translation_locked || *self.y_translation_locked,
+            *self.translation_locked || *self.z_translation_locked,
+        ]
+    }
+
     /// Sets new body type. See [`RigidBodyType`] for more info.
     pub fn set_body_type(&mut self, body_type: RigidBodyType) -> RigidBodyType {
         self.body_type.set_value_and_mark_modified(body_type)
@@ -397,6 +608,21 @@ impl RigidBody {
         self.ccd_enabled.set_value_and_mark_modified(enable)
     }
 
+    /// Enables or disables continuous collision detection. Unlike [`Self::enable_ccd`], the flag is
+    /// synced with the native rigid body using [`RigidBodyChanges::CCD`], so the change is applied on
+    /// next physics update even if the variable itself was already synced. Enable it for fast moving
+    /// bodies (such as projectiles) to prevent them from tunneling through thin geometry.
+    pub fn set_ccd_enabled(&mut self, enabled: bool) -> bool {
+        self.changes.set(self.changes.get() | RigidBodyChanges::CCD);
+        self.ccd_enabled
+            .set_value_with_flags(enabled, VariableFlags::MODIFIED)
+    }
+
+    /// Returns a set of properties that were changed since last sync with the native rigid body.
+    pub fn changes(&self) -> RigidBodyChanges {
+        self.changes.get()
+    }
+
     /// Sets a gravity scale coefficient. Zero can be used to disable gravity.
     pub fn set_gravity_scale(&mut self, scale: f32) -> f32 {
         self.gravity_scale.set_value_and_mark_modified(scale)
@@ -483,6 +709,51 @@ impl RigidBody {
         self.actions.get_mut().push_back(ApplyAction::WakeUp)
     }
 
+    /// Puts rigid body to sleep, its velocities are reset to zero. The body will be woken up again
+    /// by any moving body that touches it, or by applied forces and impulses. The body is excluded
+    /// from the simulation at the next simulation step, [`Self::is_sleeping`] reflects this state
+    /// after the step. This does nothing if the body cannot sleep (see [`Self::set_can_sleep`]).
+    pub fn sleep(&mut self) {
+        self.actions.get_mut().push_back(ApplyAction::Sleep)
+    }
+
+    /// Sets linear velocity (in m/s) below which the rigid body is considered non-moving and could be
+    /// put to sleep. Default value is 0.4.
+    pub fn set_sleep_linear_threshold(&mut self, threshold: f32) -> f32 {
+        self.sleep_linear_threshold
+            .set_value_and_mark_modified(threshold.max(0.0))
+    }
+
+    /// Returns linear velocity below which the rigid body could be put to sleep.
+    pub fn sleep_linear_threshold(&self) -> f32 {
+        *self.sleep_linear_threshold
+    }
+
+    /// Sets angular velocity (in rad/s) below which the rigid body is considered non-moving and
+    /// could be put to sleep. Default value is 0.5.
+    pub fn set_sleep_angular_threshold(&mut self, threshold: f32) -> f32 {
+        self.sleep_angular_threshold
+            .set_value_and_mark_modified(threshold.max(0.0))
+    }
+
+    /// Returns angular velocity below which the rigid body could be put to sleep.
+    pub fn sleep_angular_threshold(&self) -> f32 {
+        *self.sleep_angular_threshold
+    }
+
+    /// Sets amount of time (in seconds) the rigid body must remain below the sleep thresholds to be
+    /// put to sleep. Default value is 2.0.
+    pub fn set_time_until_sleep(&mut self, time: f32) -> f32 {
+        self.time_until_sleep
+            .set_value_and_mark_modified(time.max(0.0))
+    }
+
+    /// Returns amount of time the rigid body must remain below the sleep thresholds to be put to
+    /// sleep.
+    pub fn time_until_sleep(&self) -> f32 {
+        *self.time_until_sleep
+    }
+
     pub(crate) fn need_sync_model(&self) -> bool {
         self.lin_vel.need_sync()
             || self.ang_vel.need_sync()
@@ -494,11 +765,19 @@ impl RigidBody {
             || self.y_rotation_locked.need_sync()
             || self.z_rotation_locked.need_sync()
             || self.translation_locked.need_sync()
+            || self.x_translation_locked.need_sync()
+            || self.y_translation_locked.need_sync()
+            || self.z_translation_locked.need_sync()
             || self.ccd_enabled.need_sync()
             || self.can_sleep.need_sync()
+            || self.sleep_linear_threshold.need_sync()
+            || self.sleep_angular_threshold.need_sync()
+            || self.time_until_sleep.need_sync()
             || self.dominance.need_sync()
             || self.gravity_scale.need_sync()
+            || self.local_center_of_mass.need_sync()
             || self.reset_forces.get()
+            || self.changes.get() != RigidBodyChanges::NONE
     }
 }
 
@@ -552,14 +831,21 @@ impl NodeTrait for RigidBody {
     }
 
     fn validate(&self, scene: &Scene) -> Result<(), String> {
-        for &child in self.children() {
-            if scene.graph.try_get_of_type::<Collider>(child).is_some() {
-                return Ok(());
+        // Colliders could be attached to the body through any number of intermediate nodes, but
+        // colliders of nested rigid bodies belong to those bodies.
+        let mut stack = self.children().to_vec();
+        while let Some(handle) = stack.pop() {
+            if let Some(node) = scene.graph.try_get(handle) {
+                if node.query_component_ref::<Collider>().is_some() {
+                    return Ok(());
+                } else if node.query_component_ref::<RigidBody>().is_none() {
+                    stack.extend_from_slice(node.children());
+                }
             }
         }
 
         Err("The 3D rigid body must have at least one 3D collider as a \
-        direct child node to work correctly!"
+        descendant node to work correctly!"
             .to_string())
     }
 }
@@ -578,10 +864,17 @@ pub struct RigidBodyBuilder {
     y_rotation_locked: bool,
     z_rotation_locked: bool,
     translation_locked: bool,
+    x_translation_locked: bool,
+    y_translation_locked: bool,
+    z_translation_locked: bool,
     ccd_enabled: bool,
     can_sleep: bool,
+    sleep_linear_threshold: f32,
+    sleep_angular_threshold: f32,
+    time_until_sleep: f32,
     dominance: i8,
     gravity_scale: f32,
+    local_center_of_mass: Option<Vector3<f32>>,
 }
 
 impl RigidBodyBuilder {
@@ -600,10 +893,17 @@ impl RigidBodyBuilder {
             y_rotation_locked: false,
             z_rotation_locked: false,
             translation_locked: false,
+            x_translation_locked: false,
+            y_translation_locked: false,
+            z_translation_locked: false,
             ccd_enabled: false,
             can_sleep: true,
+            sleep_linear_threshold: dynamics::RigidBodyActivation::default_linear_threshold(),
+            sleep_angular_threshold: dynamics::RigidBodyActivation::default_angular_threshold(),
+            time_until_sleep: dynamics::RigidBodyActivation::default_time_until_sleep(),
             dominance: 0,
             gravity_scale: 1.0,
+            local_center_of_mass: None,
         }
     }
 
@@ -619,6 +919,13 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets the desired center of mass of the body (in local coordinates of the body), that overrides the
+    /// center of mass calculated from the colliders. See [`RigidBody::set_local_center_of_mass`].
+    pub fn with_local_center_of_mass(mut self, center_of_mass: Option<Vector3<f32>>) -> Self {
+        self.local_center_of_mass = center_of_mass;
+        self
+    }
+
     /// Sets whether continuous collision detection should be enabled or not.
     pub fn with_ccd_enabled(mut self, enabled: bool) -> Self {
         self.ccd_enabled = enabled;
@@ -673,6 +980,24 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets whether the translation along X axis of the body should be locked or not.
+    pub fn with_x_translation_locked(mut self, x_translation_locked: bool) -> Self {
+        self.x_translation_locked = x_translation_locked;
+        self
+    }
+
+    /// Sets whether the translation along Y axis of the body should be locked or not.
+    pub fn with_y_translation_locked(mut self, y_translation_locked: bool) -> Self {
+        self.y_translation_locked = y_translation_locked;
+        self
+    }
+
+    /// Sets whether the translation along Z axis of the body should be locked or not.
+    pub fn with_z_translation_locked(mut self, z_translation_locked: bool) -> Self {
+        self.z_translation_locked = z_translation_locked;
+        self
+    }
+
     /// Locks or unlocks rotations of the rigid body.
     pub fn with_locked_rotations(mut self, locked: bool) -> Self {
         self.x_rotation_locked = locked;
@@ -693,6 +1018,25 @@ impl RigidBodyBuilder {
         self
     }
 
+    /// Sets desired linear velocity below which the body could be put to sleep.
+    pub fn with_sleep_linear_threshold(mut self, threshold: f32) -> Self {
+        self.sleep_linear_threshold = threshold.max(0.0);
+        self
+    }
+
+    /// Sets desired angular velocity below which the body could be put to sleep.
+    pub fn with_sleep_angular_threshold(mut self, threshold: f32) -> Self {
+        self.sleep_angular_threshold = threshold.max(0.0);
+        self
+    }
+
+    /// Sets desired amount of time the body must remain below the sleep thresholds to be put to
+    /// sleep.
+    pub fn with_time_until_sleep(mut self, time: f32) -> Self {
+        self.time_until_sleep = time.max(0.0);
+        self
+    }
+
     /// Sets desired dominance group.
     pub fn with_dominance(mut self, dominance: i8) -> Self {
         self.dominance = dominance;
@@ -720,13 +1064,22 @@ impl RigidBodyBuilder {
             y_rotation_locked: self.y_rotation_locked.into(),
             z_rotation_locked: self.z_rotation_locked.into(),
             translation_locked: self.translation_locked.into(),
+            x_translation_locked: self.x_translation_locked.into(),
+            y_translation_locked: self.y_translation_locked.into(),
+            z_translation_locked: self.z_translation_locked.into(),
             ccd_enabled: self.ccd_enabled.into(),
             can_sleep: self.can_sleep.into(),
+            sleep_linear_threshold: self.sleep_linear_threshold.into(),
+            sleep_angular_threshold: self.sleep_angular_threshold.into(),
+            time_until_sleep: self.time_until_sleep.into(),
             dominance: self.dominance.into(),
             gravity_scale: self.gravity_scale.into(),
+            local_center_of_mass: self.local_center_of_mass.into(),
+            mass_properties: Default::default(),
             native: Cell::new(RigidBodyHandle::invalid()),
             actions: Default::default(),
             reset_forces: Default::default(),
+            changes: Default::default(),
         }
     }
 
@@ -740,3 +1093,341 @@ impl RigidBodyBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{BallShape, ColliderBuilder, ColliderShape},
+            graph::Graph,
+            rigidbody::{RigidBody, RigidBodyBuilder, RigidBodyChanges, RigidBodyType},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_rigid_body_sleep_control() {
+        let mut graph = Graph::new();
+        graph.physics.gravity = Vector3::default();
+
+        let add_ball = |graph: &mut Graph, x: f32| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::ball(0.5))
+                .build(graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_children(&[collider])
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(x, 0.0, 0.0))
+                            .build(),
+                    ),
+            )
+            .with_lin_vel(Vector3::new(0.0, 0.0, 1.0))
+            .build(graph)
+        };
+
+        let ball = add_ball(&mut graph, -5.0);
+        let awake_ball = add_ball(&mut graph, 5.0);
+        let slow_ball = add_ball(&mut graph, 0.0);
+        graph[awake_ball].as_rigid_body_mut().set_can_sleep(false);
+        {
+            let slow_ball = graph[slow_ball].as_rigid_body_mut();
+            slow_ball.set_sleep_linear_threshold(2.0);
+            slow_ball.set_time_until_sleep(0.1);
+        }
+
+        let update = |graph: &mut Graph| {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default())
+        };
+
+        update(&mut graph);
+        assert!(!graph[ball].as_rigid_body().is_sleeping());
+
+        graph[ball].as_rigid_body_mut().sleep();
+        graph[awake_ball].as_rigid_body_mut().sleep();
+        for _ in 0..30 {
+            update(&mut graph);
+        }
+
+        assert!(graph[ball].as_rigid_body().is_sleeping());
+        assert_eq!(graph[ball].as_rigid_body().lin_vel(), Vector3::default());
+        // The body that cannot sleep must ignore sleep requests.
+        assert!(!graph[awake_ball].as_rigid_body().is_sleeping());
+        // The body is slower than its sleep threshold, so it must fall asleep on its own.
+        assert!(graph[slow_ball].as_rigid_body().is_sleeping());
+
+        graph[ball].as_rigid_body_mut().wake_up();
+        update(&mut graph);
+        assert!(!graph[ball].as_rigid_body().is_sleeping());
+    }
+
+    #[test]
+    fn test_rigid_body_mass_properties() {
+        let mut graph = Graph::new();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .with_density(Some(2.0))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_mass(0.0)
+            .with_gravity_scale(0.0)
+            .build(&mut graph);
+
+        let update = |graph: &mut Graph| {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        };
+        let mass_properties = |graph: &Graph| *graph[body].as_rigid_body().mass_properties();
+
+        update(&mut graph);
+        update(&mut graph);
+
+        let initial = mass_properties(&graph);
+        assert!((initial.mass - 2.0).abs() < 1.0e-4);
+        assert!(initial.local_center_of_mass.norm() < 1.0e-4);
+        // Inertia of a unit cube.
+        assert!((initial.principal_inertia - Vector3::repeat(2.0 / 6.0)).norm() < 1.0e-4);
+
+        // Overridden center of mass must not change mass and inertia of the body.
+        let center_of_mass = Vector3::new(0.0, -0.4, 0.1);
+        graph[body]
+            .as_rigid_body_mut()
+            .set_local_center_of_mass(Some(center_of_mass));
+        update(&mut graph);
+
+        let overridden = mass_properties(&graph);
+        assert!((overridden.mass - 2.0).abs() < 1.0e-4);
+        assert!((overridden.local_center_of_mass - center_of_mass).norm() < 1.0e-4);
+        assert!((overridden.principal_inertia - initial.principal_inertia).norm() < 1.0e-4);
+
+        // The override must survive changes of densities of the colliders.
+        graph[collider].as_collider_mut().set_density(Some(4.0));
+        update(&mut graph);
+
+        let denser = mass_properties(&graph);
+        assert!((denser.mass - 4.0).abs() < 1.0e-4);
+        assert!((denser.local_center_of_mass - center_of_mass).norm() < 1.0e-4);
+
+        graph[body]
+            .as_rigid_body_mut()
+            .set_local_center_of_mass(None);
+        update(&mut graph);
+
+        let restored = mass_properties(&graph);
+        assert!((restored.mass - 4.0).abs() < 1.0e-4);
+        assert!(restored.local_center_of_mass.norm() < 1.0e-4);
+
+        // Mass properties could be recalculated explicitly while the simulation is paused.
+        graph.physics.enabled = false;
+        graph[collider].as_collider_mut().set_density(Some(6.0));
+        graph[body].as_rigid_body_mut().recompute_mass_properties();
+        update(&mut graph);
+
+        assert!((mass_properties(&graph).mass - 6.0).abs() < 1.0e-4);
+    }
+
+    #[test]
+    fn test_ccd_prevents_tunneling() {
+        let mut graph = Graph::new();
+
+        let wall = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(5.0, 5.0, 0.05))
+            .build(&mut graph);
+        RigidBodyBuilder::new(BaseBuilder::new().with_children(&[wall]))
+            .with_body_type(RigidBodyType::Static)
+            .build(&mut graph);
+
+        let bullet_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.05))
+            .build(&mut graph);
+        let bullet = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[bullet_collider])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 0.0, -5.0))
+                        .build(),
+                ),
+        )
+        .with_gravity_scale(0.0)
+        .build(&mut graph);
+
+        for _ in 0..2 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        assert_eq!(
+            graph[bullet].as_rigid_body().changes(),
+            RigidBodyChanges::NONE
+        );
+
+        // Enable CCD at runtime, the flag must be synced with the native body.
+        let body = graph[bullet].as_rigid_body_mut();
+        body.set_ccd_enabled(true);
+        body.set_lin_vel(Vector3::new(0.0, 0.0, 600.0));
+        assert!(body.changes().contains(RigidBodyChanges::CCD));
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        let body = graph[bullet].as_rigid_body();
+        assert_eq!(body.changes(), RigidBodyChanges::NONE);
+        assert!(graph
+            .physics
+            .native_body(body.native.get())
+            .unwrap()
+            .is_ccd_enabled());
+
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        assert!(graph[bullet].as_rigid_body().is_ccd_enabled());
+        assert!(graph[bullet].global_position().z < 0.0);
+    }
+
+    #[test]
+    fn test_impulse_wakes_up_body() {
+        let mut graph = Graph::new();
+        graph.physics.set_gravity(Vector3::default());
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .build(&mut graph);
+        let body =
+            RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider])).build(&mut graph);
+
+        for _ in 0..200 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        assert!(graph[body].cast::<RigidBody>().unwrap().is_sleeping());
+
+        graph[body]
+            .cast_mut::<RigidBody>()
+            .unwrap()
+            .apply_impulse(Vector3::new(1.0, 0.0, 0.0));
+        for _ in 0..10 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        assert!(graph[body].global_position().x > 0.0);
+    }
+
+    #[test]
+    fn test_per_axis_translation_locking() {
+        let mut graph = Graph::new();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::Ball(BallShape { radius: 0.5 }))
+            .build(&mut graph);
+        let body = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_lin_vel(Vector3::new(1.0, 0.0, 0.0))
+            .with_y_translation_locked(true)
+            .with_z_translation_locked(true)
+            .with_can_sleep(false)
+            .build(&mut graph);
+
+        for _ in 0..30 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        // Gravity must not move the body along locked Y axis.
+        let position = graph[body].global_position();
+        assert!(position.x > 0.1);
+        assert_eq!(position.y, 0.0);
+        assert_eq!(position.z, 0.0);
+
+        // Unlocked axis must be free again.
+        graph[body]
+            .cast_mut::<RigidBody>()
+            .unwrap()
+            .lock_y_translation(false);
+        for _ in 0..30 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        let position = graph[body].global_position();
+        assert!(position.y < 0.0);
+        assert_eq!(position.z, 0.0);
+    }
+
+    #[test]
+    fn test_velocity_based_kinematic_body() {
+        let mut graph = Graph::new();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(1.0, 0.1, 1.0))
+            .build(&mut graph);
+        let platform = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_body_type(RigidBodyType::KinematicVelocityBased)
+            .with_lin_vel(Vector3::new(1.0, 0.0, 0.0))
+            .build(&mut graph);
+
+        for _ in 0..60 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        // The body must be moved by its velocity and must not be affected by gravity.
+        let position = graph[platform].global_position();
+        assert!((position.x - 1.0).abs() < 0.05);
+        assert_eq!(position.y, 0.0);
+
+        // Changing the desired velocity must change the direction of motion.
+        graph[platform]
+            .as_rigid_body_mut()
+            .set_lin_vel(Vector3::new(0.0, 0.0, -1.0));
+        for _ in 0..60 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+        let position = graph[platform].global_position();
+        assert!((position.x - 1.0).abs() < 0.05);
+        assert!((position.z + 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_position_based_kinematic_body() {
+        let mut graph = Graph::new();
+        graph.physics.gravity = Vector3::default();
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+            .build(&mut graph);
+        let pusher = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+            .with_body_type(RigidBodyType::KinematicPositionBased)
+            .build(&mut graph);
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::ball(0.5))
+            .build(&mut graph);
+        let ball = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[collider])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(2.0, 0.0, 0.0))
+                        .build(),
+                ),
+        )
+        .with_can_sleep(false)
+        .build(&mut graph);
+
+        graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+        for i in 1..=30 {
+            graph[pusher]
+                .local_transform_mut()
+                .set_position(Vector3::new(i as f32 * 0.1, 0.0, 0.0));
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+
+            // The body must not be teleported, its velocity must match the motion of the node.
+            let native = graph
+                .physics
+                .native_body(graph[pusher].as_rigid_body().native.get())
+                .unwrap();
+            assert!((native.translation().x - i as f32 * 0.1).abs() < 0.001);
+            assert!((native.linvel().x - 6.0).abs() < 0.01);
+        }
+
+        // The ball must be pushed away instead of being passed through.
+        assert!(graph[ball].global_position().x > 3.5);
+    }
+}
@@ -0,0 +1,350 @@
+//! Automatic estimation of reverberation parameters around the listener. See [`ReverbEstimator`] docs
+//! for more info.
+
+use crate::{
+    core::algebra::{Point3, Vector3},
+    scene::{
+        collider::InteractionGroups,
+        graph::{
+            physics::{Intersection, RayCastOptions},
+            Graph,
+        },
+        sound::{AudioBusGraph, Effect},
+    },
+};
+
+/// Properties of the environment around the listener, that were estimated by [`ReverbEstimator`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RoomEstimate {
+    /// Fraction of the rays that hit something, `0.0` - open space, `1.0` - fully enclosed room.
+    pub enclosure: f32,
+    /// Average (harmonic mean) distance from the listener to the surrounding surfaces (in meters).
+    pub mean_distance: f32,
+    /// Approximate volume of the room (in cubic meters).
+    pub volume: f32,
+    /// Effective absorption coefficient of the room, open directions absorb everything.
+    pub absorption: f32,
+    /// Estimated reverberation time (RT60, in seconds).
+    pub decay_time: f32,
+}
+
+impl Default for RoomEstimate {
+    fn default() -> Self {
+        Self {
+            enclosure: 0.0,
+            mean_distance: 0.0,
+            volume: 0.0,
+            absorption: 1.0,
+            decay_time: 0.0,
+        }
+    }
+}
+
+/// Reverb estimator casts a sparse set of rays from the listener against the physics world, estimates the
+/// size and absorption of the surrounding space and feeds the result into every [`Effect::Reverb`] of the
+/// target audio bus. It allows to have smooth indoor/outdoor acoustic transitions without manual tuning of
+/// reverb parameters for every place of a level.
+///
+/// The estimation is based on Eyring's formula (`RT60 = 0.161 * V / (-S * ln(1 - a))`) for a spherical
+/// room with the radius equal to the harmonic mean of distances to the hit surfaces (so a few grazing hits
+/// far away won't make the room huge). Rays that did not hit anything are considered as fully absorbing
+/// "open windows".
+///
+/// The estimator is optional and must be updated manually, usually once per frame after the scene update.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::{sound::acoustics::ReverbEstimator, Scene};
+///
+/// fn update_reverb(estimator: &mut ReverbEstimator, scene: &mut Scene, dt: f32) {
+///     estimator.update(&scene.graph, dt);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ReverbEstimator {
+    bus: String,
+    ray_count: usize,
+    max_distance: f32,
+    absorption: f32,
+    min_decay_time: f32,
+    max_decay_time: f32,
+    smoothing_time: f32,
+    groups: InteractionGroups,
+    estimate: RoomEstimate,
+    directions: Vec<Vector3<f32>>,
+    query_buffer: Vec<Intersection>,
+}
+
+impl Default for ReverbEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Evenly distributes the given amount of directions over the unit sphere.
+fn fibonacci_sphere(count: usize) -> Vec<Vector3<f32>> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    (0..count)
+        .map(|i| {
+            let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+            let r = (1.0 - y * y).max(0.0).sqrt();
+            let phi = golden_angle * i as f32;
+            Vector3::new(r * phi.cos(), y, r * phi.sin())
+        })
+        .collect()
+}
+
+impl ReverbEstimator {
+    /// Creates new reverb estimator, that drives reverb effects of the primary audio bus.
+    pub fn new() -> Self {
+        Self {
+            bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            ray_count: 32,
+            max_distance: 50.0,
+            absorption: 0.3,
+            min_decay_time: 0.1,
+            max_decay_time: 10.0,
+            smoothing_time: 0.5,
+            groups: Default::default(),
+            estimate: Default::default(),
+            directions: fibonacci_sphere(32),
+            query_buffer: Default::default(),
+        }
+    }
+
+    /// Sets the name of the audio bus, which reverb effects will be driven by the estimator.
+    pub fn set_bus<S: AsRef<str>>(&mut self, name: S) {
+        self.bus = name.as_ref().to_owned();
+    }
+
+    /// Returns the name of the audio bus, which reverb effects are driven by the estimator.
+    pub fn bus(&self) -> &str {
+        &self.bus
+    }
+
+    /// Sets the amount of rays, that will be cast every update. More rays give more precise (and more
+    /// stable) result, but are slower. Default is 32.
+    pub fn set_ray_count(&mut self, count: usize) {
+        self.ray_count = count.max(1);
+        self.directions = fibonacci_sphere(self.ray_count);
+    }
+
+    /// Returns the amount of rays, that are cast every update.
+    pub fn ray_count(&self) -> usize {
+        self.ray_count
+    }
+
+    /// Sets maximum length of the rays. Surfaces that are further will be considered as open space.
+    pub fn set_max_distance(&mut self, distance: f32) {
+        self.max_distance = distance.max(0.0);
+    }
+
+    /// Returns maximum length of the rays.
+    pub fn max_distance(&self) -> f32 {
+        self.max_distance
+    }
+
+    /// Sets average absorption coefficient (`[0.0; 1.0]`) of the surfaces. Hard surfaces (concrete,
+    /// tiles) have low absorption, soft ones (carpets, curtains) - high. Default is 0.3.
+    pub fn set_absorption(&mut self, absorption: f32) {
+        self.absorption = absorption.clamp(0.01, 1.0);
+    }
+
+    /// Returns average absorption coefficient of the surfaces.
+    pub fn absorption(&self) -> f32 {
+        self.absorption
+    }
+
+    /// Sets the range of decay times, that could be set to reverb effects.
+    pub fn set_decay_time_range(&mut self, min: f32, max: f32) {
+        self.min_decay_time = min.max(0.0);
+        self.max_decay_time = max.max(self.min_decay_time);
+    }
+
+    /// Returns the range of decay times, that could be set to reverb effects.
+    pub fn decay_time_range(&self) -> (f32, f32) {
+        (self.min_decay_time, self.max_decay_time)
+    }
+
+    /// Sets time (in seconds) that is needed for reverb parameters to (mostly) adapt to a new
+    /// environment. Zero means instant transitions. Default is 0.5 seconds.
+    pub fn set_smoothing_time(&mut self, time: f32) {
+        self.smoothing_time = time.max(0.0);
+    }
+
+    /// Returns transition time of reverb parameters.
+    pub fn smoothing_time(&self) -> f32 {
+        self.smoothing_time
+    }
+
+    /// Sets collision groups, that will be used for ray casting. It could be used to filter out the
+    /// colliders of the player.
+    pub fn set_groups(&mut self, groups: InteractionGroups) {
+        self.groups = groups;
+    }
+
+    /// Returns collision groups, that are used for ray casting.
+    pub fn groups(&self) -> InteractionGroups {
+        self.groups
+    }
+
+    /// Returns current (smoothed) estimate of the environment around the listener.
+    pub fn estimate(&self) -> RoomEstimate {
+        self.estimate
+    }
+
+    /// Estimates the environment at the given position without any smoothing.
+    pub fn estimate_at(&mut self, graph: &Graph, position: Vector3<f32>) -> RoomEstimate {
+        let mut hits = 0;
+        let mut inv_distance_sum = 0.0;
+        for direction in self.directions.iter() {
+            graph.physics.cast_ray(
+                RayCastOptions {
+                    ray_origin: Point3::from(position),
+                    ray_direction: *direction,
+                    max_len: self.max_distance,
+                    groups: self.groups,
+                    sort_results: true,
+                    filter: None,
+                },
+                &mut self.query_buffer,
+            );
+
+            if let Some(first) = self.query_buffer.first() {
+                hits += 1;
+                inv_distance_sum += 1.0 / first.toi.max(0.01);
+            }
+        }
+
+        let enclosure = hits as f32 / self.directions.len() as f32;
+        let mean_distance = if hits > 0 {
+            hits as f32 / inv_distance_sum
+        } else {
+            self.max_distance
+        };
+        let absorption = enclosure * self.absorption + (1.0 - enclosure);
+
+        // Eyring's formula for a sphere, V / S = r / 3. Fully absorbing environment has no reverberation.
+        let decay_time = if absorption < 1.0 {
+            0.161 * mean_distance / (-3.0 * (1.0 - absorption).ln())
+        } else {
+            0.0
+        };
+
+        RoomEstimate {
+            enclosure,
+            mean_distance,
+            volume: 4.0 / 3.0 * std::f32::consts::PI * mean_distance.powi(3),
+            absorption,
+            decay_time: decay_time.clamp(self.min_decay_time, self.max_decay_time),
+        }
+    }
+
+    /// Estimates the environment around the listener and applies the result to reverb effects of the
+    /// target audio bus.
+    pub fn update(&mut self, graph: &Graph, dt: f32) {
+        let listener_position = graph.sound_context.native.state().listener().position();
+
+        let target = self.estimate_at(graph, listener_position);
+
+        let k = if self.smoothing_time > 0.0 {
+            1.0 - (-dt / self.smoothing_time).exp()
+        } else {
+            1.0
+        };
+        let current = &mut self.estimate;
+        current.enclosure += (target.enclosure - current.enclosure) * k;
+        current.mean_distance += (target.mean_distance - current.mean_distance) * k;
+        current.volume += (target.volume - current.volume) * k;
+        current.absorption += (target.absorption - current.absorption) * k;
+        current.decay_time += (target.decay_time - current.decay_time) * k;
+
+        let decay_time = current.decay_time;
+        let mut state = graph.sound_context.state();
+        for bus in state.bus_graph_mut().buses_iter_mut() {
+            if bus.name() == self.bus {
+                for effect in bus.effects_mut() {
+                    if let Effect::Reverb(reverb) = effect {
+                        reverb.set_decay_time(decay_time);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            collider::test::create_cuboid,
+            graph::Graph,
+            rigidbody::RigidBodyType,
+            sound::{acoustics::ReverbEstimator, reverb::Reverb, Effect},
+        },
+    };
+
+    #[test]
+    fn test_reverb_estimation() {
+        let mut graph = Graph::new();
+
+        // Ground only.
+        create_cuboid(
+            &mut graph,
+            Vector3::new(0.0, -2.5, 0.0),
+            Vector3::new(100.0, 0.5, 100.0),
+            RigidBodyType::Static,
+            false,
+        );
+        for _ in 0..2 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let mut estimator = ReverbEstimator::new();
+        let outdoor = estimator.estimate_at(&graph, Default::default());
+        assert!(outdoor.enclosure > 0.4 && outdoor.enclosure < 0.6);
+
+        // Close the room.
+        for (position, half_extents) in [
+            (Vector3::new(0.0, 2.5, 0.0), Vector3::new(3.0, 0.5, 3.0)),
+            (Vector3::new(2.5, 0.0, 0.0), Vector3::new(0.5, 3.0, 3.0)),
+            (Vector3::new(-2.5, 0.0, 0.0), Vector3::new(0.5, 3.0, 3.0)),
+            (Vector3::new(0.0, 0.0, 2.5), Vector3::new(3.0, 3.0, 0.5)),
+            (Vector3::new(0.0, 0.0, -2.5), Vector3::new(3.0, 3.0, 0.5)),
+        ] {
+            create_cuboid(
+                &mut graph,
+                position,
+                half_extents,
+                RigidBodyType::Static,
+                false,
+            );
+        }
+        for _ in 0..2 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let indoor = estimator.estimate_at(&graph, Default::default());
+        assert_eq!(indoor.enclosure, 1.0);
+        assert!(indoor.mean_distance >= 2.0 && indoor.mean_distance < 3.5);
+        assert!(indoor.decay_time > outdoor.decay_time);
+
+        graph
+            .sound_context
+            .state()
+            .bus_graph_mut()
+            .primary_bus_mut()
+            .add_effect(Effect::Reverb(Reverb::new()));
+        estimator.set_smoothing_time(0.0);
+        estimator.update(&graph, 1.0 / 60.0);
+        let state = graph.sound_context.state();
+        match state.bus_graph_ref().primary_bus_ref().effect(0) {
+            Some(Effect::Reverb(reverb)) => {
+                assert_eq!(reverb.decay_time(), estimator.estimate().decay_time)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::{
     core::{
+        algebra::Vector3,
         log::{Log, MessageKind},
         pool::Handle,
         visitor::prelude::*,
@@ -12,6 +13,7 @@ use fxhash::FxHashSet;
 use fyrox_sound::{
     bus::AudioBusGraph,
     context::DistanceModel,
+    error::SoundError,
     renderer::Renderer,
     source::{SoundSource, SoundSourceBuilder, Status},
 };
@@ -60,6 +62,38 @@ impl<'a> SoundContextGuard<'a> {
         self.guard.distance_model()
     }
 
+    /// Sets new scale of Doppler effect. Zero disables the effect. See
+    /// [`fyrox_sound::context::State::set_doppler_scale`] for more info.
+    pub fn set_doppler_scale(&mut self, scale: f32) {
+        self.guard.set_doppler_scale(scale);
+    }
+
+    /// Returns current scale of Doppler effect.
+    pub fn doppler_scale(&self) -> f32 {
+        self.guard.doppler_scale()
+    }
+
+    /// Sets speed of sound (in units per second) that is used to calculate Doppler effect.
+    pub fn set_speed_of_sound(&mut self, speed: f32) {
+        self.guard.set_speed_of_sound(speed);
+    }
+
+    /// Returns speed of sound (in units per second).
+    pub fn speed_of_sound(&self) -> f32 {
+        self.guard.speed_of_sound()
+    }
+
+    /// Sets maximum amount of simultaneously playing sounds. See
+    /// [`fyrox_sound::context::State::set_max_voices`] for more info.
+    pub fn set_max_voices(&mut self, max_voices: Option<u32>) {
+        self.guard.set_max_voices(max_voices);
+    }
+
+    /// Returns maximum amount of simultaneously playing sounds.
+    pub fn max_voices(&self) -> Option<u32> {
+        self.guard.max_voices()
+    }
+
     /// Normalizes given frequency using context's sampling rate. Normalized frequency then can be used
     /// to create filters.
     pub fn normalize_frequency(&self, f: f32) -> f32 {
@@ -95,6 +129,28 @@ impl<'a> SoundContextGuard<'a> {
     pub fn destroy_sound_sources(&mut self) {
         self.guard.sources_mut().clear();
     }
+
+    /// Stops every sound with the given tag (see [`crate::scene::base::Base::tag`]). Returns the last error,
+    /// that has occurred while stopping the sounds (if any).
+    pub fn stop_all_with_tag(&mut self, tag: &str) -> Result<(), SoundError> {
+        self.guard.stop_all_with_tag(tag)
+    }
+
+    /// Pauses every playing sound with the given tag (see [`crate::scene::base::Base::tag`]).
+    pub fn pause_all_with_tag(&mut self, tag: &str) {
+        self.guard.pause_all_with_tag(tag)
+    }
+
+    /// Resumes every paused sound with the given tag (see [`crate::scene::base::Base::tag`]).
+    pub fn resume_all_with_tag(&mut self, tag: &str) {
+        self.guard.resume_all_with_tag(tag)
+    }
+
+    /// Sets new gain for every sound with the given tag (see [`crate::scene::base::Base::tag`]). Gain of the
+    /// sound nodes stays the same, it will be applied again if it is changed.
+    pub fn set_gain_for_tag(&mut self, tag: &str, gain: f32) {
+        self.guard.set_gain_for_tag(tag, gain)
+    }
 }
 
 impl Default for SoundContext {
@@ -142,10 +198,18 @@ impl SoundContext {
         }
     }
 
+    pub(crate) fn set_sound_velocity(&mut self, sound: &Sound, velocity: Vector3<f32>) {
+        if let Some(source) = self.native.state().try_get_source_mut(sound.native.get()) {
+            source.set_velocity(velocity);
+        }
+    }
+
     pub(crate) fn sync_with_sound(&self, sound: &mut Sound) {
         if let Some(source) = self.native.state().try_get_source_mut(sound.native.get()) {
-            // Sync back.
-            sound.status.set_value_silent(source.status());
+            // Sync back. Virtualized sounds are paused internally, but must keep their status.
+            if !sound.virtualized.get() {
+                sound.status.set_value_silent(source.status());
+            }
             sound
                 .playback_time
                 .set_value_silent(source.playback_time().as_secs_f32());
@@ -163,11 +227,13 @@ impl SoundContext {
         {
             self.remove_sound(sound.native.get(), &sound.name);
             sound.native.set(Default::default());
+            sound.virtualized.set(false);
             return;
         }
 
         if sound.native.get().is_some() {
             let mut state = self.native.state();
+            let listener_position = state.listener().position();
             let source = state.source_mut(sound.native.get());
             sound.buffer.try_sync_model(|v| {
                 Log::verify(source.set_buffer(v));
@@ -178,6 +244,12 @@ impl SoundContext {
             sound.rolloff_factor.try_sync_model(|v| {
                 source.set_rolloff_factor(v);
             });
+            sound.distance_curve.try_sync_model(|v| {
+                source.set_distance_curve(v);
+            });
+            sound.max_distance_cutoff.try_sync_model(|v| {
+                source.set_max_distance_cutoff(v);
+            });
             sound.radius.try_sync_model(|v| {
                 source.set_radius(v);
             });
@@ -190,6 +262,12 @@ impl SoundContext {
             sound.looping.try_sync_model(|v| {
                 source.set_looping(v);
             });
+            sound.loop_start.try_sync_model(|v| {
+                source.set_loop_start(v);
+            });
+            sound.loop_end.try_sync_model(|v| {
+                source.set_loop_end(v);
+            });
             sound.panning.try_sync_model(|v| {
                 source.set_panning(v);
             });
@@ -199,6 +277,20 @@ impl SoundContext {
             sound
                 .spatial_blend
                 .try_sync_model(|v| source.set_spatial_blend(v));
+            // Must be synced before status, so the delay is applied to the sound that was started
+            // at the same frame.
+            sound.start_delay.try_sync_model(|v| {
+                source.set_start_delay(v);
+            });
+            sound.min_retrigger_interval.try_sync_model(|v| {
+                source.set_min_retrigger_interval(v);
+            });
+            sound.priority.try_sync_model(|v| {
+                source.set_priority(v);
+            });
+            sound.max_buffer_voices.try_sync_model(|v| {
+                source.set_max_buffer_voices(v);
+            });
             sound.status.try_sync_model(|v| match v {
                 Status::Stopped => {
                     Log::verify(source.stop());
@@ -213,11 +305,43 @@ impl SoundContext {
             sound.audio_bus.try_sync_model(|audio_bus| {
                 source.set_bus(audio_bus);
             });
+            sound.send_bus.try_sync_model(|send_bus| {
+                source.set_send_bus(send_bus);
+            });
+            sound.send_level.try_sync_model(|v| {
+                source.set_send_level(v);
+            });
+            sound.low_pass_filter.try_sync_model(|v| {
+                source.set_low_pass_filter(v);
+            });
+            sound.high_pass_filter.try_sync_model(|v| {
+                source.set_high_pass_filter(v);
+            });
+            if source.tag() != sound.tag() {
+                source.set_tag(sound.tag());
+            }
+
+            let is_audible = sound.audible_radius().map_or(true, |radius| {
+                sound.global_position().metric_distance(&listener_position) <= radius
+            });
+            if is_audible {
+                if sound.virtualized.get() {
+                    sound.virtualized.set(false);
+                    if sound.status() == Status::Playing {
+                        source.play();
+                    }
+                }
+            } else if source.status() == Status::Playing {
+                source.pause();
+                sound.virtualized.set(true);
+            }
         } else {
             match SoundSourceBuilder::new()
                 .with_gain(sound.gain())
                 .with_opt_buffer(sound.buffer())
                 .with_looping(sound.is_looping())
+                .with_loop_start(sound.loop_start())
+                .with_loop_end(sound.loop_end())
                 .with_panning(sound.panning())
                 .with_pitch(sound.pitch())
                 .with_status(sound.status())
@@ -226,7 +350,18 @@ impl SoundContext {
                 .with_radius(sound.radius())
                 .with_max_distance(sound.max_distance())
                 .with_bus(sound.audio_bus())
+                .with_send_bus(sound.send_bus())
+                .with_send_level(sound.send_level())
+                .with_low_pass_filter(sound.low_pass_filter().cloned())
+                .with_high_pass_filter(sound.high_pass_filter().cloned())
+                .with_tag(sound.tag())
                 .with_rolloff_factor(sound.rolloff_factor())
+                .with_distance_curve(sound.distance_curve().cloned())
+                .with_max_distance_cutoff(sound.is_max_distance_cutoff())
+                .with_start_delay(sound.start_delay())
+                .with_min_retrigger_interval(sound.min_retrigger_interval())
+                .with_priority(sound.priority())
+                .with_max_buffer_voices(sound.max_buffer_voices())
                 .build()
             {
                 Ok(source) => {
@@ -2,7 +2,8 @@
 
 use crate::{
     core::{
-        algebra::Matrix4,
+        algebra::{Matrix4, Vector3},
+        curve::Curve,
         math::{aabb::AxisAlignedBoundingBox, m4x4_approx_eq},
         pool::Handle,
         reflect::prelude::*,
@@ -38,13 +39,17 @@ pub use fyrox_sound::{
 
 use crate::scene::Scene;
 use fyrox_resource::ResourceStateRef;
-use fyrox_sound::source::SoundSource;
+use fyrox_sound::{
+    effects::filter::{HighPassFilterEffect, LowPassFilterEffect},
+    source::SoundSource,
+};
 use std::{
     cell::Cell,
     ops::{Deref, DerefMut},
     time::Duration,
 };
 
+pub mod acoustics;
 pub mod context;
 pub mod listener;
 
@@ -73,6 +78,20 @@ pub struct Sound {
     #[reflect(setter = "set_looping")]
     looping: InheritableVariable<bool>,
 
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_loop_start",
+        description = "Start of the loop region in samples per channel."
+    )]
+    loop_start: InheritableVariable<u32>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_loop_end",
+        description = "End of the loop region in samples per channel. None means the end of the buffer."
+    )]
+    loop_end: InheritableVariable<Option<u32>>,
+
     #[reflect(min_value = 0.0, step = 0.05)]
     #[reflect(setter = "set_pitch")]
     pitch: InheritableVariable<f64>,
@@ -89,6 +108,21 @@ pub struct Sound {
     #[reflect(setter = "set_rolloff_factor")]
     rolloff_factor: InheritableVariable<f32>,
 
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_distance_curve",
+        description = "Custom curve that maps distance to the listener to distance gain. \
+        Overrides distance model of the sound context."
+    )]
+    distance_curve: InheritableVariable<Option<Curve>>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_max_distance_cutoff",
+        description = "Makes the sound silent if the listener is further than max distance."
+    )]
+    max_distance_cutoff: InheritableVariable<bool>,
+
     #[visit(optional)]
     #[reflect(setter = "set_playback_time", min_value = 0.0)]
     playback_time: InheritableVariable<f32>,
@@ -102,9 +136,76 @@ pub struct Sound {
     )]
     audio_bus: InheritableVariable<String>,
 
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_audible_radius",
+        description = "Maximum distance to the listener at which the sound is still audible. The sound \
+        will be virtualized (paused) when the listener is further away and resumed when it comes back."
+    )]
+    audible_radius: InheritableVariable<Option<f32>>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_start_delay", min_value = 0.0, step = 0.01)]
+    start_delay: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_min_retrigger_interval", min_value = 0.0, step = 0.01)]
+    min_retrigger_interval: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_priority",
+        description = "Priority of the sound. When voice limits are exceeded, sounds with lower \
+        priority are stopped first."
+    )]
+    priority: InheritableVariable<i32>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_max_buffer_voices",
+        description = "Maximum amount of simultaneously playing sounds with the same sound buffer. \
+        The least important sounds are stopped when the limit is exceeded."
+    )]
+    max_buffer_voices: InheritableVariable<Option<u32>>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_send_bus",
+        description = "A name of an audio bus (usually with a reverb effect) to which a part of the \
+        sound's signal will be sent. Empty name disables the send."
+    )]
+    send_bus: InheritableVariable<String>,
+
+    #[visit(optional)]
+    #[reflect(
+        setter = "set_send_level",
+        min_value = 0.0,
+        max_value = 1.0,
+        step = 0.05
+    )]
+    send_level: InheritableVariable<f32>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_low_pass_filter")]
+    low_pass_filter: InheritableVariable<Option<LowPassFilterEffect>>,
+
+    #[visit(optional)]
+    #[reflect(setter = "set_high_pass_filter")]
+    high_pass_filter: InheritableVariable<Option<HighPassFilterEffect>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub(crate) virtualized: Cell<bool>,
+
     #[reflect(hidden)]
     #[visit(skip)]
     pub(crate) native: Cell<Handle<SoundSource>>,
+
+    // Global position at previous update, it is used to calculate velocity of the sound for
+    // Doppler effect.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    prev_position: Option<Vector3<f32>>,
 }
 
 impl Deref for Sound {
@@ -131,14 +232,29 @@ impl Default for Sound {
             panning: InheritableVariable::new_modified(0.0),
             status: InheritableVariable::new_modified(Status::Stopped),
             looping: InheritableVariable::new_modified(false),
+            loop_start: InheritableVariable::new_modified(0),
+            loop_end: InheritableVariable::new_modified(None),
             pitch: InheritableVariable::new_modified(1.0),
             radius: InheritableVariable::new_modified(10.0),
             max_distance: InheritableVariable::new_modified(f32::MAX),
             rolloff_factor: InheritableVariable::new_modified(1.0),
+            distance_curve: InheritableVariable::new_modified(None),
+            max_distance_cutoff: InheritableVariable::new_modified(false),
             playback_time: Default::default(),
             spatial_blend: InheritableVariable::new_modified(1.0),
             audio_bus: InheritableVariable::new_modified(AudioBusGraph::PRIMARY_BUS.to_string()),
+            audible_radius: InheritableVariable::new_modified(None),
+            start_delay: InheritableVariable::new_modified(0.0),
+            min_retrigger_interval: InheritableVariable::new_modified(0.0),
+            priority: InheritableVariable::new_modified(0),
+            max_buffer_voices: InheritableVariable::new_modified(None),
+            send_bus: InheritableVariable::new_modified(Default::default()),
+            send_level: InheritableVariable::new_modified(0.0),
+            low_pass_filter: InheritableVariable::new_modified(None),
+            high_pass_filter: InheritableVariable::new_modified(None),
+            virtualized: Default::default(),
             native: Default::default(),
+            prev_position: None,
         }
     }
 }
@@ -153,15 +269,30 @@ impl Clone for Sound {
             panning: self.panning.clone(),
             status: self.status.clone(),
             looping: self.looping.clone(),
+            loop_start: self.loop_start.clone(),
+            loop_end: self.loop_end.clone(),
             pitch: self.pitch.clone(),
             radius: self.radius.clone(),
             max_distance: self.max_distance.clone(),
             rolloff_factor: self.rolloff_factor.clone(),
+            distance_curve: self.distance_curve.clone(),
+            max_distance_cutoff: self.max_distance_cutoff.clone(),
             playback_time: self.playback_time.clone(),
             spatial_blend: self.spatial_blend.clone(),
             audio_bus: self.audio_bus.clone(),
+            audible_radius: self.audible_radius.clone(),
+            start_delay: self.start_delay.clone(),
+            min_retrigger_interval: self.min_retrigger_interval.clone(),
+            priority: self.priority.clone(),
+            max_buffer_voices: self.max_buffer_voices.clone(),
+            send_bus: self.send_bus.clone(),
+            send_level: self.send_level.clone(),
+            low_pass_filter: self.low_pass_filter.clone(),
+            high_pass_filter: self.high_pass_filter.clone(),
+            virtualized: Default::default(),
             // Do not copy. The copy will have its own native representation.
             native: Default::default(),
+            prev_position: None,
         }
     }
 }
@@ -281,6 +412,30 @@ impl Sound {
         *self.looping
     }
 
+    /// Sets start of the loop region (in samples per channel from the beginning of the buffer). When
+    /// a looping sound reaches the end of the loop region, it continues playing from its start
+    /// instead of the beginning of the buffer. It is useful for music with an intro, that should be
+    /// played only once.
+    pub fn set_loop_start(&mut self, start: u32) -> u32 {
+        self.loop_start.set_value_and_mark_modified(start)
+    }
+
+    /// Returns start of the loop region (in samples per channel).
+    pub fn loop_start(&self) -> u32 {
+        *self.loop_start
+    }
+
+    /// Sets end of the loop region (in samples per channel from the beginning of the buffer). See
+    /// [`Self::set_loop_start`] for more info. `None` means the end of the buffer.
+    pub fn set_loop_end(&mut self, end: Option<u32>) -> Option<u32> {
+        self.loop_end.set_value_and_mark_modified(end)
+    }
+
+    /// Returns end of the loop region (in samples per channel).
+    pub fn loop_end(&self) -> Option<u32> {
+        *self.loop_end
+    }
+
     /// Sets sound pitch. Defines "tone" of sounds. Default value is 1.0
     pub fn set_pitch(&mut self, pitch: f64) -> f64 {
         self.pitch.set_value_and_mark_modified(pitch.abs())
@@ -342,6 +497,29 @@ impl Sound {
         *self.max_distance
     }
 
+    /// Sets custom distance attenuation curve, that maps distance between the sound and the listener
+    /// to distance gain. The curve overrides distance model of the sound context for this sound.
+    /// `None` disables the curve.
+    pub fn set_distance_curve(&mut self, curve: Option<Curve>) -> Option<Curve> {
+        self.distance_curve.set_value_and_mark_modified(curve)
+    }
+
+    /// Returns custom distance attenuation curve, if any.
+    pub fn distance_curve(&self) -> Option<&Curve> {
+        self.distance_curve.as_ref()
+    }
+
+    /// Enables or disables max distance cutoff. When enabled, the sound becomes silent if the
+    /// listener is further than max distance (see [`Self::set_max_distance`]).
+    pub fn set_max_distance_cutoff(&mut self, cutoff: bool) -> bool {
+        self.max_distance_cutoff.set_value_and_mark_modified(cutoff)
+    }
+
+    /// Returns `true` if max distance cutoff is enabled, `false` - otherwise.
+    pub fn is_max_distance_cutoff(&self) -> bool {
+        *self.max_distance_cutoff
+    }
+
     /// Sets new audio bus name to which the sound will be attached.
     pub fn set_audio_bus(&mut self, name: String) {
         self.audio_bus.set_value_and_mark_modified(name);
@@ -351,6 +529,138 @@ impl Sound {
     pub fn audio_bus(&self) -> &str {
         &self.audio_bus
     }
+
+    /// Sets maximum distance between the sound and the listener at which the sound is still audible.
+    /// If the listener is further away, the sound will be virtualized - its native source will be
+    /// paused automatically on graph update and resumed as soon as the listener comes back in range.
+    /// Status of the sound node remains unchanged while it is virtualized. `None` disables the
+    /// virtualization (default).
+    ///
+    /// It is useful for large amounts of ambient sounds (such as sounds of particle systems, rivers,
+    /// etc.) spread across a level, only few of which are audible at the same time.
+    pub fn set_audible_radius(&mut self, radius: Option<f32>) -> Option<f32> {
+        self.audible_radius.set_value_and_mark_modified(radius)
+    }
+
+    /// Returns audible radius of the sound. See [`Self::set_audible_radius`] for more info.
+    pub fn audible_radius(&self) -> Option<f32> {
+        *self.audible_radius
+    }
+
+    /// Sets a delay (in seconds) between the moment when the sound is started (from stopped state)
+    /// and the moment when it becomes audible. Small random delays could be used to "humanize"
+    /// layered one-shot sounds, that are started at the same time. The delay is not applied when the
+    /// sound is resumed after pause or when it loops.
+    pub fn set_start_delay(&mut self, delay: f32) -> f32 {
+        self.start_delay.set_value_and_mark_modified(delay.max(0.0))
+    }
+
+    /// Returns start delay (in seconds) of the sound.
+    pub fn start_delay(&self) -> f32 {
+        *self.start_delay
+    }
+
+    /// Sets minimum interval (in seconds) between starts of sounds with the same sound buffer. If
+    /// the sound is started earlier than the interval has passed since the last start of any sound
+    /// with the same buffer, it will be stopped right away. This prevents phasing and doubled volume
+    /// of rapidly repeated one-shot sounds (UI clicks, overlapping pickups, etc.). Zero disables the
+    /// protection.
+    pub fn set_min_retrigger_interval(&mut self, interval: f32) -> f32 {
+        self.min_retrigger_interval
+            .set_value_and_mark_modified(interval.max(0.0))
+    }
+
+    /// Returns minimum retrigger interval (in seconds) of the sound.
+    pub fn min_retrigger_interval(&self) -> f32 {
+        *self.min_retrigger_interval
+    }
+
+    /// Sets priority of the sound. When the amount of playing sounds exceeds voice limits (see
+    /// [`super::context::SoundContextGuard::set_max_voices`] and [`Self::set_max_buffer_voices`]), sounds
+    /// with lower priority are stopped first. Sounds with the same priority are stopped from the
+    /// quietest one.
+    pub fn set_priority(&mut self, priority: i32) -> i32 {
+        self.priority.set_value_and_mark_modified(priority)
+    }
+
+    /// Returns priority of the sound.
+    pub fn priority(&self) -> i32 {
+        *self.priority
+    }
+
+    /// Sets maximum amount of simultaneously playing sounds with the same sound buffer. It is
+    /// useful to prevent rapidly repeated sounds (weapon shots, footsteps, etc.) from overloading
+    /// the mixer. `None` means no limit.
+    pub fn set_max_buffer_voices(&mut self, max_voices: Option<u32>) -> Option<u32> {
+        self.max_buffer_voices
+            .set_value_and_mark_modified(max_voices)
+    }
+
+    /// Returns maximum amount of simultaneously playing sounds with the same sound buffer.
+    pub fn max_buffer_voices(&self) -> Option<u32> {
+        *self.max_buffer_voices
+    }
+
+    /// Sets new name of the send bus. Besides its own audio bus (see [`Self::set_audio_bus`]), the
+    /// sound also feeds a part of its signal (defined by [`Self::set_send_level`]) to the send bus.
+    /// Usually the send bus has a reverb effect, this way distant or indoor sounds could have more
+    /// reverberation than the others. Empty name disables the send (default).
+    pub fn set_send_bus(&mut self, name: String) -> String {
+        self.send_bus.set_value_and_mark_modified(name)
+    }
+
+    /// Returns the name of the send bus.
+    pub fn send_bus(&self) -> &str {
+        &self.send_bus
+    }
+
+    /// Sets amount of the signal (in `[0; 1]` range) that will be sent to the send bus (see
+    /// [`Self::set_send_bus`]).
+    pub fn set_send_level(&mut self, level: f32) -> f32 {
+        self.send_level
+            .set_value_and_mark_modified(level.clamp(0.0, 1.0))
+    }
+
+    /// Returns send level of the sound.
+    pub fn send_level(&self) -> f32 {
+        *self.send_level
+    }
+
+    /// Sets new low-pass filter of the sound, the filter muffles high frequencies of the sound. It
+    /// could be used to simulate occlusion, sounds heard underwater, etc. `None` disables the filter
+    /// (default).
+    pub fn set_low_pass_filter(
+        &mut self,
+        filter: Option<LowPassFilterEffect>,
+    ) -> Option<LowPassFilterEffect> {
+        self.low_pass_filter.set_value_and_mark_modified(filter)
+    }
+
+    /// Returns a reference to the low-pass filter of the sound, if any.
+    pub fn low_pass_filter(&self) -> Option<&LowPassFilterEffect> {
+        self.low_pass_filter.as_ref()
+    }
+
+    /// Sets new high-pass filter of the sound, the filter removes low frequencies of the sound. It
+    /// could be used to simulate radio voices, small speakers, etc. `None` disables the filter
+    /// (default).
+    pub fn set_high_pass_filter(
+        &mut self,
+        filter: Option<HighPassFilterEffect>,
+    ) -> Option<HighPassFilterEffect> {
+        self.high_pass_filter.set_value_and_mark_modified(filter)
+    }
+
+    /// Returns a reference to the high-pass filter of the sound, if any.
+    pub fn high_pass_filter(&self) -> Option<&HighPassFilterEffect> {
+        self.high_pass_filter.as_ref()
+    }
+
+    /// Returns `true` if the sound is virtualized (paused because the listener is out of its audible
+    /// radius), `false` - otherwise.
+    pub fn is_virtualized(&self) -> bool {
+        self.virtualized.get()
+    }
 }
 
 impl NodeTrait for Sound {
@@ -399,6 +709,11 @@ impl NodeTrait for Sound {
     }
 
     fn update(&mut self, context: &mut UpdateContext) {
+        let position = self.global_position();
+        let velocity = calculate_velocity(self.prev_position, position, context.dt);
+        self.prev_position = Some(position);
+        context.sound_context.set_sound_velocity(self, velocity);
+
         context.sound_context.sync_with_sound(self);
     }
 
@@ -425,6 +740,18 @@ impl NodeTrait for Sound {
     }
 }
 
+// Calculates velocity from position delta, it is used for Doppler effect of sounds and listeners.
+pub(crate) fn calculate_velocity(
+    prev_position: Option<Vector3<f32>>,
+    position: Vector3<f32>,
+    dt: f32,
+) -> Vector3<f32> {
+    match prev_position {
+        Some(prev_position) if dt > 0.0 => (position - prev_position).scale(1.0 / dt),
+        _ => Vector3::default(),
+    }
+}
+
 /// Sound builder, allows you to create a new [`Sound`] instance.
 pub struct SoundBuilder {
     base_builder: BaseBuilder,
@@ -434,13 +761,26 @@ pub struct SoundBuilder {
     panning: f32,
     status: Status,
     looping: bool,
+    loop_start: u32,
+    loop_end: Option<u32>,
     pitch: f64,
     radius: f32,
     max_distance: f32,
     rolloff_factor: f32,
+    distance_curve: Option<Curve>,
+    max_distance_cutoff: bool,
     playback_time: Duration,
     spatial_blend: f32,
     audio_bus: String,
+    audible_radius: Option<f32>,
+    start_delay: f32,
+    min_retrigger_interval: f32,
+    priority: i32,
+    max_buffer_voices: Option<u32>,
+    send_bus: String,
+    send_level: f32,
+    low_pass_filter: Option<LowPassFilterEffect>,
+    high_pass_filter: Option<HighPassFilterEffect>,
 }
 
 impl SoundBuilder {
@@ -454,13 +794,26 @@ impl SoundBuilder {
             panning: 0.0,
             status: Status::Stopped,
             looping: false,
+            loop_start: 0,
+            loop_end: None,
             pitch: 1.0,
             radius: 10.0,
             max_distance: f32::MAX,
             rolloff_factor: 1.0,
+            distance_curve: None,
+            max_distance_cutoff: false,
             spatial_blend: 1.0,
             playback_time: Default::default(),
             audio_bus: AudioBusGraph::PRIMARY_BUS.to_string(),
+            audible_radius: None,
+            start_delay: 0.0,
+            min_retrigger_interval: 0.0,
+            priority: 0,
+            max_buffer_voices: None,
+            send_bus: Default::default(),
+            send_level: 0.0,
+            low_pass_filter: None,
+            high_pass_filter: None,
         }
     }
 
@@ -494,6 +847,16 @@ impl SoundBuilder {
         fn with_looping(looping: bool)
     );
 
+    define_with!(
+        /// Sets desired loop start. See [`Sound::set_loop_start`] for more info.
+        fn with_loop_start(loop_start: u32)
+    );
+
+    define_with!(
+        /// Sets desired loop end. See [`Sound::set_loop_end`] for more info.
+        fn with_loop_end(loop_end: Option<u32>)
+    );
+
     define_with!(
         /// Sets desired pitch. See [`Sound::set_pitch`] for more info.
         fn with_pitch(pitch: f64)
@@ -514,6 +877,16 @@ impl SoundBuilder {
         fn with_rolloff_factor(rolloff_factor: f32)
     );
 
+    define_with!(
+        /// Sets desired distance curve. See [`Sound::set_distance_curve`] for more info.
+        fn with_distance_curve(distance_curve: Option<Curve>)
+    );
+
+    define_with!(
+        /// Sets desired max distance cutoff. See [`Sound::set_max_distance_cutoff`] for more info.
+        fn with_max_distance_cutoff(max_distance_cutoff: bool)
+    );
+
     define_with!(
         /// Sets desired spatial blend factor. See [`Sound::set_spatial_blend`] for more info.
         fn with_spatial_blend_factor(spatial_blend: f32)
@@ -529,6 +902,53 @@ impl SoundBuilder {
         fn with_audio_bus(audio_bus: String)
     );
 
+    define_with!(
+        /// Sets desired audible radius. See [`Sound::set_audible_radius`] for more info.
+        fn with_audible_radius(audible_radius: Option<f32>)
+    );
+
+    define_with!(
+        /// Sets desired start delay. See [`Sound::set_start_delay`] for more info.
+        fn with_start_delay(start_delay: f32)
+    );
+
+    define_with!(
+        /// Sets desired minimum retrigger interval. See [`Sound::set_min_retrigger_interval`] for
+        /// more info.
+        fn with_min_retrigger_interval(min_retrigger_interval: f32)
+    );
+
+    define_with!(
+        /// Sets desired priority. See [`Sound::set_priority`] for more info.
+        fn with_priority(priority: i32)
+    );
+
+    define_with!(
+        /// Sets desired maximum amount of voices with the same buffer. See
+        /// [`Sound::set_max_buffer_voices`] for more info.
+        fn with_max_buffer_voices(max_buffer_voices: Option<u32>)
+    );
+
+    define_with!(
+        /// Sets desired send bus. See [`Sound::set_send_bus`] for more info.
+        fn with_send_bus(send_bus: String)
+    );
+
+    define_with!(
+        /// Sets desired send level. See [`Sound::set_send_level`] for more info.
+        fn with_send_level(send_level: f32)
+    );
+
+    define_with!(
+        /// Sets desired low-pass filter. See [`Sound::set_low_pass_filter`] for more info.
+        fn with_low_pass_filter(low_pass_filter: Option<LowPassFilterEffect>)
+    );
+
+    define_with!(
+        /// Sets desired high-pass filter. See [`Sound::set_high_pass_filter`] for more info.
+        fn with_high_pass_filter(high_pass_filter: Option<HighPassFilterEffect>)
+    );
+
     /// Creates a new [`Sound`] node.
     #[must_use]
     pub fn build_sound(self) -> Sound {
@@ -540,14 +960,29 @@ impl SoundBuilder {
             panning: self.panning.into(),
             status: self.status.into(),
             looping: self.looping.into(),
+            loop_start: self.loop_start.into(),
+            loop_end: self.loop_end.into(),
             pitch: self.pitch.into(),
             radius: self.radius.into(),
             max_distance: self.max_distance.into(),
             rolloff_factor: self.rolloff_factor.into(),
+            distance_curve: self.distance_curve.into(),
+            max_distance_cutoff: self.max_distance_cutoff.into(),
             playback_time: self.playback_time.as_secs_f32().into(),
             spatial_blend: self.spatial_blend.into(),
             audio_bus: self.audio_bus.into(),
+            audible_radius: self.audible_radius.into(),
+            start_delay: self.start_delay.into(),
+            min_retrigger_interval: self.min_retrigger_interval.into(),
+            priority: self.priority.into(),
+            max_buffer_voices: self.max_buffer_voices.into(),
+            send_bus: self.send_bus.into(),
+            send_level: self.send_level.into(),
+            low_pass_filter: self.low_pass_filter.into(),
+            high_pass_filter: self.high_pass_filter.into(),
+            virtualized: Default::default(),
             native: Default::default(),
+            prev_position: None,
         }
     }
 
@@ -562,3 +997,67 @@ impl SoundBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            graph::Graph,
+            sound::{listener::ListenerBuilder, Sound, SoundBuilder, Status},
+            transform::TransformBuilder,
+        },
+    };
+
+    #[test]
+    fn test_audible_radius_virtualization() {
+        let mut graph = Graph::new();
+        let listener = ListenerBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let sound = SoundBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(10.0, 0.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_audible_radius(Some(5.0))
+        .with_status(Status::Playing)
+        .build(&mut graph);
+
+        let update = |graph: &mut Graph| {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+            let sound_ref = graph[sound].cast::<Sound>().unwrap();
+            let native_status = graph
+                .sound_context
+                .state()
+                .source(sound_ref.native.get())
+                .status();
+            (
+                sound_ref.is_virtualized(),
+                sound_ref.status(),
+                native_status,
+            )
+        };
+
+        // The first update creates the native source, the second one virtualizes it, because the
+        // listener is out of the audible radius.
+        update(&mut graph);
+        assert_eq!(update(&mut graph), (true, Status::Playing, Status::Paused));
+
+        // The sound must be resumed as soon as the listener comes back.
+        graph[listener]
+            .local_transform_mut()
+            .set_position(Vector3::new(8.0, 0.0, 0.0));
+        assert_eq!(
+            update(&mut graph),
+            (false, Status::Playing, Status::Playing)
+        );
+
+        // And virtualized again when it leaves.
+        graph[listener]
+            .local_transform_mut()
+            .set_position(Vector3::new(-8.0, 0.0, 0.0));
+        assert_eq!(update(&mut graph), (true, Status::Playing, Status::Paused));
+    }
+}
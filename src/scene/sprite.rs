@@ -4,6 +4,7 @@
 
 use crate::{
     core::{
+        algebra::{Vector2, Vector4},
         color::Color,
         math::aabb::AxisAlignedBoundingBox,
         pool::Handle,
@@ -21,6 +22,101 @@ use crate::{
     },
 };
 use std::ops::{Deref, DerefMut};
+use strum_macros::{AsRefStr, EnumString, EnumVariantNames};
+
+/// Tiling options of a sprite, see [`SpriteUvMode::Tile`].
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct SpriteTiling {
+    /// Amount of repetitions of the texture along each axis. Could be fractional.
+    #[reflect(min_value = 0.0, step = 0.1)]
+    pub repeat: Vector2<f32>,
+}
+
+impl Default for SpriteTiling {
+    fn default() -> Self {
+        Self {
+            repeat: Vector2::new(1.0, 1.0),
+        }
+    }
+}
+
+/// Margins of a 9-slice sprite, see [`SpriteUvMode::NineSlice`]. Each margin is defined in texture
+/// coordinates (`[0; 1]` range) and measured from the respective side of the sprite.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct NineSliceMargins {
+    /// Width of the left column of the texture.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub left_margin_uv: f32,
+    /// Width of the right column of the texture.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub right_margin_uv: f32,
+    /// Height of the top row of the texture.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub top_margin_uv: f32,
+    /// Height of the bottom row of the texture.
+    #[reflect(min_value = 0.0, max_value = 1.0, step = 0.01)]
+    pub bottom_margin_uv: f32,
+}
+
+impl Default for NineSliceMargins {
+    fn default() -> Self {
+        Self {
+            left_margin_uv: 0.25,
+            right_margin_uv: 0.25,
+            top_margin_uv: 0.25,
+            bottom_margin_uv: 0.25,
+        }
+    }
+}
+
+/// Defines how a texture is mapped onto a sprite.
+#[derive(Clone, Debug, PartialEq, Visit, Reflect, AsRefStr, EnumString, EnumVariantNames)]
+pub enum SpriteUvMode {
+    /// The texture is stretched over the entire sprite.
+    Stretch,
+    /// The texture is repeated the given amount of times along each axis. Repetition does not depend
+    /// on wrap mode of the texture.
+    Tile(SpriteTiling),
+    /// The texture is split into 9 parts using the given margins: corners keep their size, edges are
+    /// stretched along one axis and the center is stretched along both axes. Size of the borders is
+    /// defined as if the texture were stretched over a square sprite of the same [`Sprite::size`], so
+    /// for a square sprite this mode is the same as [`SpriteUvMode::Stretch`]. Use
+    /// [`Sprite::set_size_scale`] to make the sprite rectangular. It is useful for world-space panels,
+    /// frames, and so on.
+    NineSlice(NineSliceMargins),
+}
+
+impl Default for SpriteUvMode {
+    fn default() -> Self {
+        Self::Stretch
+    }
+}
+
+impl SpriteUvMode {
+    /// Returns an index of the mode, that is used in sprite shader.
+    pub(crate) fn shader_index(&self) -> i32 {
+        match self {
+            SpriteUvMode::Stretch => 0,
+            SpriteUvMode::Tile(_) => 1,
+            SpriteUvMode::NineSlice(_) => 2,
+        }
+    }
+
+    /// Packs parameters of the mode in a vector, that is used in sprite shader. Tiling mode uses `xy`
+    /// components, 9-slice mode uses all of them (left, right, top, bottom).
+    pub(crate) fn shader_params(&self) -> Vector4<f32> {
+        match self {
+            SpriteUvMode::Stretch => Vector4::default(),
+            SpriteUvMode::Tile(tiling) => Vector4::new(tiling.repeat.x, tiling.repeat.y, 0.0, 0.0),
+            SpriteUvMode::NineSlice(margins) => Vector4::new(
+                margins.left_margin_uv,
+                margins.right_margin_uv,
+                margins.top_margin_uv,
+                margins.bottom_margin_uv,
+            ),
+        }
+    }
+}
 
 /// Sprite is billboard which always faces towards camera. It can be used as a "model" for bullets, and so on.
 ///
@@ -78,6 +174,14 @@ pub struct Sprite {
 
     #[reflect(setter = "set_rotation")]
     rotation: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_size_scale")]
+    #[visit(optional)]
+    size_scale: InheritableVariable<Vector2<f32>>,
+
+    #[reflect(setter = "set_uv_mode")]
+    #[visit(optional)]
+    uv_mode: InheritableVariable<SpriteUvMode>,
 }
 
 impl Deref for Sprite {
@@ -140,6 +244,27 @@ impl Sprite {
         *self.rotation
     }
 
+    /// Sets per-axis scale of the sprite size, it allows you to make rectangular sprites. Actual half-width
+    /// of the sprite is `size * size_scale.x` and half-height is `size * size_scale.y`. Default is (1.0, 1.0).
+    pub fn set_size_scale(&mut self, size_scale: Vector2<f32>) -> Vector2<f32> {
+        self.size_scale.set_value_and_mark_modified(size_scale)
+    }
+
+    /// Returns current per-axis scale of the sprite size.
+    pub fn size_scale(&self) -> Vector2<f32> {
+        *self.size_scale
+    }
+
+    /// Sets new texture mapping mode of the sprite. Default is [`SpriteUvMode::Stretch`].
+    pub fn set_uv_mode(&mut self, uv_mode: SpriteUvMode) -> SpriteUvMode {
+        self.uv_mode.set_value_and_mark_modified(uv_mode)
+    }
+
+    /// Returns current texture mapping mode of the sprite.
+    pub fn uv_mode(&self) -> &SpriteUvMode {
+        &self.uv_mode
+    }
+
     /// Sets new texture for sprite. Default is None.
     pub fn set_texture(&mut self, texture: Option<TextureResource>) -> Option<TextureResource> {
         self.texture.set_value_and_mark_modified(texture)
@@ -160,7 +285,9 @@ impl NodeTrait for Sprite {
     crate::impl_query_component!();
 
     fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
-        AxisAlignedBoundingBox::from_radius(*self.size)
+        AxisAlignedBoundingBox::from_radius(
+            *self.size * self.size_scale.x.abs().max(self.size_scale.y.abs()),
+        )
     }
 
     fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
@@ -180,6 +307,8 @@ pub struct SpriteBuilder {
     color: Color,
     size: f32,
     rotation: f32,
+    size_scale: Vector2<f32>,
+    uv_mode: SpriteUvMode,
 }
 
 impl SpriteBuilder {
@@ -191,6 +320,8 @@ impl SpriteBuilder {
             color: Color::WHITE,
             size: 0.2,
             rotation: 0.0,
+            size_scale: Vector2::new(1.0, 1.0),
+            uv_mode: SpriteUvMode::Stretch,
         }
     }
 
@@ -224,6 +355,18 @@ impl SpriteBuilder {
         self
     }
 
+    /// Sets desired per-axis scale of the size.
+    pub fn with_size_scale(mut self, size_scale: Vector2<f32>) -> Self {
+        self.size_scale = size_scale;
+        self
+    }
+
+    /// Sets desired texture mapping mode.
+    pub fn with_uv_mode(mut self, uv_mode: SpriteUvMode) -> Self {
+        self.uv_mode = uv_mode;
+        self
+    }
+
     fn build_sprite(self) -> Sprite {
         Sprite {
             base: self.base_builder.build_base(),
@@ -231,6 +374,8 @@ impl SpriteBuilder {
             color: self.color.into(),
             size: self.size.into(),
             rotation: self.rotation.into(),
+            size_scale: self.size_scale.into(),
+            uv_mode: self.uv_mode.into(),
         }
     }
 
@@ -244,3 +389,62 @@ impl SpriteBuilder {
         graph.add_node(self.build_node())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3, Vector4},
+            visitor::{Visit, Visitor},
+        },
+        scene::{
+            base::BaseBuilder,
+            node::NodeTrait,
+            sprite::{NineSliceMargins, Sprite, SpriteBuilder, SpriteTiling, SpriteUvMode},
+        },
+    };
+
+    #[test]
+    fn test_sprite_uv_modes() {
+        let margins = NineSliceMargins {
+            left_margin_uv: 0.1,
+            right_margin_uv: 0.2,
+            top_margin_uv: 0.3,
+            bottom_margin_uv: 0.4,
+        };
+        let mut sprite = SpriteBuilder::new(BaseBuilder::new())
+            .with_size(2.0)
+            .with_size_scale(Vector2::new(3.0, -1.0))
+            .with_uv_mode(SpriteUvMode::NineSlice(margins.clone()))
+            .build_sprite();
+
+        assert_eq!(sprite.uv_mode().shader_index(), 2);
+        assert_eq!(
+            sprite.uv_mode().shader_params(),
+            Vector4::new(0.1, 0.2, 0.3, 0.4)
+        );
+        // Bounds must enclose the sprite in any orientation.
+        assert_eq!(sprite.local_bounding_box().max, Vector3::new(6.0, 6.0, 6.0));
+
+        sprite.set_uv_mode(SpriteUvMode::Tile(SpriteTiling {
+            repeat: Vector2::new(4.0, 2.0),
+        }));
+        assert_eq!(sprite.uv_mode().shader_index(), 1);
+        assert_eq!(
+            sprite.uv_mode().shader_params(),
+            Vector4::new(4.0, 2.0, 0.0, 0.0)
+        );
+        assert_eq!(SpriteUvMode::default().shader_index(), 0);
+
+        // The new properties must survive serialization.
+        let mut visitor = Visitor::new();
+        sprite.visit("Sprite", &mut visitor).unwrap();
+        let data = visitor.save_binary_to_vec().unwrap();
+
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        let mut loaded = Sprite::default();
+        loaded.visit("Sprite", &mut visitor).unwrap();
+        assert_eq!(loaded.size_scale(), Vector2::new(3.0, -1.0));
+        assert_eq!(loaded.uv_mode(), sprite.uv_mode());
+    }
+}
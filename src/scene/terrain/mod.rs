@@ -69,6 +69,11 @@ pub struct Layer {
     /// Name of the node uv offsets property in the material.
     #[visit(optional)]
     pub node_uv_offsets_property_name: String,
+
+    /// Surface type of the layer (for example - `"Grass"`, `"Dirt"`, `"Rock"`). It can be used to
+    /// select footstep sounds, impact effects, etc. See [`Terrain::surface_type_at`] for more info.
+    #[visit(optional)]
+    pub surface_type: String,
 }
 
 impl Default for Layer {
@@ -78,6 +83,7 @@ impl Default for Layer {
             mask_property_name: "maskTexture".to_string(),
             height_map_property_name: "heightMapTexture".to_string(),
             node_uv_offsets_property_name: "nodeUvOffsets".to_string(),
+            surface_type: Default::default(),
         }
     }
 }
@@ -999,6 +1005,59 @@ impl Terrain {
         project(self.global_transform(), p)
     }
 
+    /// Returns an index of the most visible layer at the given point in world coordinates. Layers
+    /// are blended one on top of another, so every layer hides the layers below it proportionally
+    /// to its mask value. Returns `None` if the point is outside of the terrain or the terrain has
+    /// no layers.
+    pub fn dominant_layer_at(&self, p: Vector3<f32>) -> Option<usize> {
+        let position = self.project(p)?;
+
+        let chunk = self.chunks.iter().find(|chunk| {
+            let min = chunk.local_position();
+            let max = min + chunk.physical_size;
+            position.x >= min.x && position.y >= min.y && position.x <= max.x && position.y <= max.y
+        })?;
+
+        let k = (position - chunk.local_position()).component_div(&chunk.physical_size);
+
+        let mut dominant_layer = None;
+        let mut max_weight = 0.0;
+        let mut visibility = 1.0;
+        // Go from the topmost layer to the bottom one.
+        for (layer_index, mask) in chunk.layer_masks.iter().enumerate().rev() {
+            let mask_value = if let ResourceStateRef::Ok(mask) = mask.state().get() {
+                if let TextureKind::Rectangle { width, height } = mask.kind() {
+                    let x = (k.x * (width - 1) as f32).round() as usize;
+                    let z = (k.y * (height - 1) as f32).round() as usize;
+                    mask.data()
+                        .get(z * width as usize + x)
+                        .map_or(0.0, |pixel| *pixel as f32 / 255.0)
+                } else {
+                    0.0
+                }
+            } else {
+                0.0
+            };
+
+            let weight = mask_value * visibility;
+            if weight > max_weight {
+                max_weight = weight;
+                dominant_layer = Some(layer_index);
+            }
+            visibility *= 1.0 - mask_value;
+        }
+
+        dominant_layer
+    }
+
+    /// Returns surface type of the most visible layer at the given point in world coordinates. See
+    /// [`Self::dominant_layer_at`] for more info.
+    pub fn surface_type_at(&self, p: Vector3<f32>) -> Option<&str> {
+        self.dominant_layer_at(p)
+            .and_then(|layer_index| self.layers.get(layer_index))
+            .map(|layer| layer.surface_type.as_str())
+    }
+
     /// Applies the given function to each pixel of the height map.
     pub fn for_each_height_map_pixel<F>(&mut self, mut func: F)
     where
@@ -0,0 +1,446 @@
+//! Typed user data storage allows you to attach arbitrary data to scene nodes. See [`UserDataStorage`]
+//! docs for more info.
+
+use crate::{
+    core::{
+        log::Log,
+        parking_lot::Mutex,
+        uuid::Uuid,
+        visitor::{Visit, VisitError, VisitResult, Visitor},
+        TypeUuidProvider,
+    },
+    engine::SerializationContext,
+};
+use fxhash::FxHashMap;
+use std::{
+    any::{Any, TypeId},
+    collections::BTreeMap,
+    fmt::{Debug, Formatter},
+};
+
+/// A piece of data that can be attached to a scene node. It is implemented automatically for every
+/// type that is `Clone + Send + Debug + 'static`.
+pub trait UserData: Any + Send + Debug {
+    /// Creates a boxed copy of the data.
+    fn clone_box(&self) -> Box<dyn UserData>;
+
+    /// Casts self as `Any`.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Casts self as `Any`.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Casts self as `Any`.
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T> UserData for T
+where
+    T: Clone + Send + Debug + 'static,
+{
+    fn clone_box(&self) -> Box<dyn UserData> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+type VisitFn = fn(&mut dyn UserData, &str, &mut Visitor) -> VisitResult;
+
+fn visit_as<T: Visit + 'static>(
+    data: &mut dyn UserData,
+    name: &str,
+    visitor: &mut Visitor,
+) -> VisitResult {
+    data.as_any_mut()
+        .downcast_mut::<T>()
+        .expect("Type of user data must match its visit function!")
+        .visit(name, visitor)
+}
+
+// Type UUID and visit function of serializable user data.
+#[derive(Copy, Clone)]
+struct Serializer {
+    type_uuid: Uuid,
+    visit: VisitFn,
+}
+
+struct Entry {
+    data: Box<dyn UserData>,
+    serializer: Option<Serializer>,
+}
+
+impl Clone for Entry {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone_box(),
+            serializer: self.serializer,
+        }
+    }
+}
+
+/// A type map, that holds at most one instance of each type of user data. Every scene node has its own
+/// storage (see [`crate::scene::base::Base::user_data_storage`]), the data is cloned together with
+/// the node.
+/// It allows you to attach gameplay data to nodes directly, instead of maintaining external
+/// `HashMap<Handle<Node>, T>` tables, which must be kept in sync with the graph manually.
+///
+/// # Serialization
+///
+/// User data is not serialized by default. To make it serializable, the data must implement [`Visit`]
+/// and [`TypeUuidProvider`], be inserted using [`Self::insert_serializable`], and its type must be
+/// registered in [`SerializationContext::user_data_constructors`], so it could be created on loading.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::scene::node::Node;
+///
+/// #[derive(Clone, Debug)]
+/// struct Health(f32);
+///
+/// fn damage(node: &mut Node, amount: f32) {
+///     if let Some(health) = node.user_data_mut::<Health>() {
+///         health.0 -= amount;
+///     } else {
+///         node.user_data_storage_mut().insert(Health(100.0 - amount));
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct UserDataStorage {
+    entries: FxHashMap<TypeId, Entry>,
+}
+
+impl Debug for UserDataStorage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.entries.values().map(|e| &e.data))
+            .finish()
+    }
+}
+
+impl UserDataStorage {
+    /// Adds the data to the storage, replacing previous data of the same type (if any). The data will
+    /// not be serialized.
+    pub fn insert<T: UserData>(&mut self, data: T) -> Option<T> {
+        self.insert_entry(Box::new(data), None)
+    }
+
+    /// Adds the data to the storage, replacing previous data of the same type (if any). The data will
+    /// be serialized together with its node, make sure to register its type in
+    /// [`SerializationContext::user_data_constructors`], otherwise it will be skipped on loading.
+    pub fn insert_serializable<T>(&mut self, data: T) -> Option<T>
+    where
+        T: UserData + Visit + TypeUuidProvider,
+    {
+        self.insert_entry(
+            Box::new(data),
+            Some(Serializer {
+                type_uuid: T::type_uuid(),
+                visit: visit_as::<T>,
+            }),
+        )
+    }
+
+    fn insert_entry<T: UserData>(
+        &mut self,
+        data: Box<dyn UserData>,
+        serializer: Option<Serializer>,
+    ) -> Option<T> {
+        self.entries
+            .insert(TypeId::of::<T>(), Entry { data, serializer })
+            .and_then(|old| old.data.into_any().downcast().ok())
+            .map(|data| *data)
+    }
+
+    /// Returns a reference to the data of the given type, if any.
+    pub fn get<T: UserData>(&self) -> Option<&T> {
+        self.entries
+            .get(&TypeId::of::<T>())
+            .and_then(|e| e.data.as_any().downcast_ref())
+    }
+
+    /// Returns a reference to the data of the given type, if any.
+    pub fn get_mut<T: UserData>(&mut self) -> Option<&mut T> {
+        self.entries
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|e| e.data.as_any_mut().downcast_mut())
+    }
+
+    /// Returns a reference to the data of the given type, if any. Unlike [`Self::get`], it could be
+    /// used when the type is known only at runtime.
+    pub fn query_ref(&self, type_id: TypeId) -> Option<&dyn Any> {
+        self.entries.get(&type_id).map(|e| e.data.as_any())
+    }
+
+    /// Returns a reference to the data of the given type, if any. Unlike [`Self::get_mut`], it could
+    /// be used when the type is known only at runtime.
+    pub fn query_mut(&mut self, type_id: TypeId) -> Option<&mut dyn Any> {
+        self.entries.get_mut(&type_id).map(|e| e.data.as_any_mut())
+    }
+
+    /// Returns `true` if the storage has the data of the given type, `false` - otherwise.
+    pub fn contains<T: UserData>(&self) -> bool {
+        self.entries.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Removes the data of the given type and returns it, if any.
+    pub fn remove<T: UserData>(&mut self) -> Option<T> {
+        self.entries
+            .remove(&TypeId::of::<T>())
+            .and_then(|e| e.data.into_any().downcast().ok())
+            .map(|data| *data)
+    }
+
+    /// Returns amount of pieces of data in the storage.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the storage is empty, `false` - otherwise.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every piece of data from the storage.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl Visit for UserDataStorage {
+    fn visit(&mut self, name: &str, visitor: &mut Visitor) -> VisitResult {
+        let mut region = visitor.enter_region(name)?;
+
+        let mut count = self
+            .entries
+            .values()
+            .filter(|e| e.serializer.is_some())
+            .count() as u32;
+        count.visit("Count", &mut region)?;
+
+        if region.is_reading() {
+            self.entries.clear();
+
+            if count == 0 {
+                return Ok(());
+            }
+
+            // Keep a shared reference to the context, because the region is borrowed mutably below.
+            let serialization_context = region
+                .blackboard
+                .inner()
+                .get(&TypeId::of::<SerializationContext>())
+                .cloned()
+                .ok_or_else(|| {
+                    VisitError::User(
+                        "Visitor blackboard must contain serialization context!".to_string(),
+                    )
+                })?;
+            let serialization_context = serialization_context
+                .downcast_ref::<SerializationContext>()
+                .ok_or(VisitError::TypeMismatch)?;
+
+            for i in 0..count {
+                let mut item = region.enter_region(&format!("Item{}", i))?;
+
+                let mut type_uuid = Uuid::default();
+                type_uuid.visit("TypeUuid", &mut item)?;
+
+                match serialization_context
+                    .user_data_constructors
+                    .try_create(&type_uuid)
+                {
+                    Some((type_id, mut entry)) => {
+                        if let Some(serializer) = entry.serializer {
+                            (serializer.visit)(&mut *entry.data, "Data", &mut item)?;
+                        }
+                        self.entries.insert(type_id, entry);
+                    }
+                    None => Log::warn(format!(
+                        "There is no user data constructor for {} type, the data is skipped!",
+                        type_uuid
+                    )),
+                }
+            }
+        } else {
+            for (i, entry) in self
+                .entries
+                .values_mut()
+                .filter(|e| e.serializer.is_some())
+                .enumerate()
+            {
+                let mut item = region.enter_region(&format!("Item{}", i))?;
+
+                let serializer = entry.serializer.unwrap();
+                let mut type_uuid = serializer.type_uuid;
+                type_uuid.visit("TypeUuid", &mut item)?;
+                (serializer.visit)(&mut *entry.data, "Data", &mut item)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct UserDataConstructor {
+    type_id: TypeId,
+    constructor: Box<dyn Fn() -> Entry + Send>,
+}
+
+/// A container of constructors of serializable user data. See [`UserDataStorage`] docs for more info.
+#[derive(Default)]
+pub struct UserDataConstructorContainer {
+    map: Mutex<BTreeMap<Uuid, UserDataConstructor>>,
+}
+
+impl UserDataConstructorContainer {
+    /// Creates new empty container.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new type of serializable user data.
+    ///
+    /// # Panic
+    ///
+    /// The method will panic if there is already a constructor for given type uuid.
+    pub fn add<T>(&self) -> &Self
+    where
+        T: UserData + Visit + TypeUuidProvider + Default,
+    {
+        let old = self.map.lock().insert(
+            T::type_uuid(),
+            UserDataConstructor {
+                type_id: TypeId::of::<T>(),
+                constructor: Box::new(|| Entry {
+                    data: Box::new(T::default()),
+                    serializer: Some(Serializer {
+                        type_uuid: T::type_uuid(),
+                        visit: visit_as::<T>,
+                    }),
+                }),
+            },
+        );
+
+        assert!(old.is_none());
+
+        self
+    }
+
+    /// Unregisters type constructor.
+    pub fn remove(&self, type_uuid: Uuid) {
+        self.map.lock().remove(&type_uuid);
+    }
+
+    /// Returns `true` if there is a constructor for the given type uuid, `false` - otherwise.
+    pub fn contains(&self, type_uuid: &Uuid) -> bool {
+        self.map.lock().contains_key(type_uuid)
+    }
+
+    fn try_create(&self, type_uuid: &Uuid) -> Option<(TypeId, Entry)> {
+        self.map
+            .lock()
+            .get(type_uuid)
+            .map(|c| (c.type_id, (c.constructor)()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            uuid::{uuid, Uuid},
+            visitor::prelude::*,
+            TypeUuidProvider,
+        },
+        engine::SerializationContext,
+        scene::{
+            base::{Base, BaseBuilder},
+            graph::Graph,
+            pivot::PivotBuilder,
+        },
+    };
+    use std::sync::Arc;
+
+    #[derive(Clone, Debug, Default, PartialEq, Visit)]
+    struct Health {
+        value: f32,
+    }
+
+    impl TypeUuidProvider for Health {
+        fn type_uuid() -> Uuid {
+            uuid!("9e1a5c3e-4f7d-4a3b-9d2a-6b1f0c8e7d21")
+        }
+    }
+
+    fn save(base: &mut Base, serialization_context: &Arc<SerializationContext>) -> Vec<u8> {
+        let mut visitor = Visitor::new();
+        visitor.blackboard.register(serialization_context.clone());
+        base.visit("Base", &mut visitor).unwrap();
+        visitor.save_binary_to_vec().unwrap()
+    }
+
+    #[test]
+    fn test_user_data() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Transient(u32);
+
+        let mut graph = Graph::new();
+        let node = PivotBuilder::new(BaseBuilder::new()).build(&mut graph);
+        let storage = graph[node].user_data_storage_mut();
+        assert!(storage
+            .insert_serializable(Health { value: 100.0 })
+            .is_none());
+        assert!(storage.insert(Transient(1)).is_none());
+        assert_eq!(storage.insert(Transient(2)), Some(Transient(1)));
+        graph[node].user_data_mut::<Health>().unwrap().value -= 10.0;
+
+        let (copy, map) = graph.clone(graph.get_root(), &mut |_, _| true);
+        let copy_node = &copy[map.inner()[&node]];
+        assert_eq!(copy_node.user_data(), Some(&Health { value: 90.0 }));
+        assert_eq!(copy_node.user_data(), Some(&Transient(2)));
+
+        // Only serializable data must be saved.
+        let serialization_context = Arc::new(SerializationContext::new());
+        serialization_context.user_data_constructors.add::<Health>();
+
+        let data = save(&mut (**copy_node).clone(), &serialization_context);
+
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        visitor.blackboard.register(serialization_context);
+        let mut loaded = Base::default();
+        loaded.visit("Base", &mut visitor).unwrap();
+        assert_eq!(loaded.user_data(), Some(&Health { value: 90.0 }));
+        assert_eq!(loaded.user_data::<Transient>(), None);
+    }
+
+    #[test]
+    fn test_user_data_without_serialization_context() {
+        let mut base = Base::default();
+        base.user_data_storage_mut()
+            .insert_serializable(Health { value: 100.0 });
+        let serialization_context = Arc::new(SerializationContext::new());
+        let data = save(&mut base, &serialization_context);
+
+        // Loading must fail gracefully instead of panicking.
+        let mut visitor = Visitor::load_from_memory(data).unwrap();
+        let mut region = visitor.enter_region("Base").unwrap();
+        let mut storage = base.user_data_storage().clone();
+        assert!(matches!(
+            storage.visit("UserData", &mut region),
+            Err(VisitError::User(_))
+        ));
+    }
+}
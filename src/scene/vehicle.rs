@@ -0,0 +1,809 @@
+//! Raycast vehicle is a simple and robust model of a wheeled vehicle. See [`Vehicle`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{Matrix4, UnitQuaternion, Vector3},
+        color::Color,
+        log::Log,
+        math::{aabb::AxisAlignedBoundingBox, Matrix4Ext},
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::SceneDrawingContext,
+        graph::{physics::NativeVehicle, Graph},
+        node::{Node, NodeTrait, SyncContext, UpdateContext},
+        rigidbody::RigidBody,
+        Scene,
+    },
+};
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+
+/// Wheel of a [`Vehicle`]. Position of the wheel node defines the point where the suspension is attached
+/// to the chassis. The suspension goes down along the up axis (Y) of the chassis and the wheel rotates
+/// around the right axis (X) of the chassis, so wheel nodes should not be rotated relative to the chassis.
+///
+/// Wheel node itself is never moved by the simulation, use [`Wheel::set_visual`] to assign a node (usually
+/// a wheel mesh) which transform will be synchronized with the simulated wheel.
+#[derive(Visit, Reflect, Debug, Clone)]
+pub struct Wheel {
+    base: Base,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_radius")]
+    radius: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_suspension_rest_length")]
+    suspension_rest_length: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.05)]
+    #[reflect(setter = "set_max_suspension_travel")]
+    max_suspension_travel: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_suspension_stiffness")]
+    suspension_stiffness: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.01)]
+    #[reflect(setter = "set_damping_compression")]
+    damping_compression: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.01)]
+    #[reflect(setter = "set_damping_relaxation")]
+    damping_relaxation: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_max_suspension_force")]
+    max_suspension_force: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_friction_slip")]
+    friction_slip: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_engine_force")]
+    engine_force: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_brake")]
+    brake: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_steering")]
+    steering: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_visual")]
+    visual: InheritableVariable<Handle<Node>>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    suspension_length: f32,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    rotation: f32,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    suspension_force: f32,
+}
+
+impl Default for Wheel {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            radius: InheritableVariable::new_modified(0.35),
+            suspension_rest_length: InheritableVariable::new_modified(0.3),
+            max_suspension_travel: InheritableVariable::new_modified(0.2),
+            suspension_stiffness: InheritableVariable::new_modified(20.0),
+            damping_compression: InheritableVariable::new_modified(0.83),
+            damping_relaxation: InheritableVariable::new_modified(0.88),
+            max_suspension_force: InheritableVariable::new_modified(6000.0),
+            friction_slip: InheritableVariable::new_modified(10.5),
+            engine_force: Default::default(),
+            brake: Default::default(),
+            steering: Default::default(),
+            visual: Default::default(),
+            suspension_length: 0.3,
+            rotation: 0.0,
+            suspension_force: 0.0,
+        }
+    }
+}
+
+impl Deref for Wheel {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Wheel {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Wheel {
+    fn type_uuid() -> Uuid {
+        uuid!("0a3a5e0b-6a5c-4b0e-9a8a-3f6b1e6f2d51")
+    }
+}
+
+impl Wheel {
+    /// Sets radius of the wheel.
+    pub fn set_radius(&mut self, radius: f32) -> f32 {
+        self.radius.set_value_and_mark_modified(radius.abs())
+    }
+
+    /// Returns radius of the wheel.
+    pub fn radius(&self) -> f32 {
+        *self.radius
+    }
+
+    /// Sets length of the suspension spring at rest.
+    pub fn set_suspension_rest_length(&mut self, length: f32) -> f32 {
+        self.suspension_rest_length
+            .set_value_and_mark_modified(length.abs())
+    }
+
+    /// Returns length of the suspension spring at rest.
+    pub fn suspension_rest_length(&self) -> f32 {
+        *self.suspension_rest_length
+    }
+
+    /// Sets maximum distance the suspension can travel from its rest length in both directions.
+    pub fn set_max_suspension_travel(&mut self, travel: f32) -> f32 {
+        self.max_suspension_travel
+            .set_value_and_mark_modified(travel.abs())
+    }
+
+    /// Returns maximum distance the suspension can travel from its rest length in both directions.
+    pub fn max_suspension_travel(&self) -> f32 {
+        *self.max_suspension_travel
+    }
+
+    /// Sets stiffness of the suspension spring. The stiffness is relative to the mass of the chassis,
+    /// so the same value works for light and heavy vehicles. Increase it if the suspension does not
+    /// push the vehicle strong enough.
+    pub fn set_suspension_stiffness(&mut self, stiffness: f32) -> f32 {
+        self.suspension_stiffness
+            .set_value_and_mark_modified(stiffness)
+    }
+
+    /// Returns stiffness of the suspension spring.
+    pub fn suspension_stiffness(&self) -> f32 {
+        *self.suspension_stiffness
+    }
+
+    /// Sets damping of the suspension when it is being compressed.
+    pub fn set_damping_compression(&mut self, damping: f32) -> f32 {
+        self.damping_compression
+            .set_value_and_mark_modified(damping)
+    }
+
+    /// Returns damping of the suspension when it is being compressed.
+    pub fn damping_compression(&self) -> f32 {
+        *self.damping_compression
+    }
+
+    /// Sets damping of the suspension when it is being released. Increase it if the suspension
+    /// overshoots.
+    pub fn set_damping_relaxation(&mut self, damping: f32) -> f32 {
+        self.damping_relaxation.set_value_and_mark_modified(damping)
+    }
+
+    /// Returns damping of the suspension when it is being released.
+    pub fn damping_relaxation(&self) -> f32 {
+        *self.damping_relaxation
+    }
+
+    /// Sets maximum force the suspension can apply to the chassis.
+    pub fn set_max_suspension_force(&mut self, force: f32) -> f32 {
+        self.max_suspension_force.set_value_and_mark_modified(force)
+    }
+
+    /// Returns maximum force the suspension can apply to the chassis.
+    pub fn max_suspension_force(&self) -> f32 {
+        *self.max_suspension_force
+    }
+
+    /// Sets amount of traction of the tire. Larger values make braking and acceleration more
+    /// instantaneous, but too high values may flip the vehicle.
+    ///
+    /// ## Performance
+    ///
+    /// Changing this value re-creates native representation of the whole vehicle, so it should not be
+    /// changed every frame.
+    pub fn set_friction_slip(&mut self, friction_slip: f32) -> f32 {
+        self.friction_slip
+            .set_value_and_mark_modified(friction_slip)
+    }
+
+    /// Returns amount of traction of the tire.
+    pub fn friction_slip(&self) -> f32 {
+        *self.friction_slip
+    }
+
+    /// Sets forward force applied by the wheel to the chassis. Negative values move the vehicle
+    /// backwards.
+    pub fn set_engine_force(&mut self, force: f32) -> f32 {
+        self.engine_force.set_value_and_mark_modified(force)
+    }
+
+    /// Returns forward force applied by the wheel to the chassis.
+    pub fn engine_force(&self) -> f32 {
+        *self.engine_force
+    }
+
+    /// Sets maximum braking impulse of the wheel.
+    pub fn set_brake(&mut self, brake: f32) -> f32 {
+        self.brake.set_value_and_mark_modified(brake.abs())
+    }
+
+    /// Returns maximum braking impulse of the wheel.
+    pub fn brake(&self) -> f32 {
+        *self.brake
+    }
+
+    /// Sets steering angle (in radians) of the wheel. Positive values turn the wheel to the left.
+    pub fn set_steering(&mut self, steering: f32) -> f32 {
+        self.steering.set_value_and_mark_modified(steering)
+    }
+
+    /// Returns steering angle (in radians) of the wheel.
+    pub fn steering(&self) -> f32 {
+        *self.steering
+    }
+
+    /// Sets a node which transform will be synchronized with the simulated wheel (suspension offset,
+    /// steering and rotation of the wheel on its axle). Usually it is a wheel mesh.
+    pub fn set_visual(&mut self, visual: Handle<Node>) -> Handle<Node> {
+        self.visual.set_value_and_mark_modified(visual)
+    }
+
+    /// Returns a handle of the node which transform is synchronized with the simulated wheel.
+    pub fn visual(&self) -> Handle<Node> {
+        *self.visual
+    }
+
+    /// Returns current length of the suspension. It is equal to the rest length, if the wheel is in the
+    /// air.
+    pub fn suspension_length(&self) -> f32 {
+        self.suspension_length
+    }
+
+    /// Returns current rotation angle (in radians) of the wheel on its axle.
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Returns current force applied by the suspension to the chassis.
+    pub fn suspension_force(&self) -> f32 {
+        self.suspension_force
+    }
+
+    /// Returns `true` if the wheel touches the ground, `false` - otherwise.
+    pub fn is_in_contact(&self) -> bool {
+        self.suspension_force > 0.0
+    }
+
+    /// Returns transform of the simulated wheel relative to the wheel node.
+    pub fn local_wheel_transform(&self) -> Matrix4<f32> {
+        Matrix4::new_translation(&Vector3::new(0.0, -self.suspension_length, 0.0))
+            * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), *self.steering).to_homogeneous()
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.rotation).to_homogeneous()
+    }
+}
+
+impl NodeTrait for Wheel {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        let position = self.global_position();
+        let center = position - self.up_vector().normalize() * self.suspension_length;
+        ctx.add_line(crate::scene::debug::Line {
+            begin: position,
+            end: center,
+            color: Color::GREEN,
+        });
+        ctx.draw_wire_sphere(center, *self.radius, 16, Color::GREEN);
+    }
+}
+
+/// Allows you to build a wheel in declarative manner.
+pub struct WheelBuilder {
+    base_builder: BaseBuilder,
+    radius: f32,
+    suspension_rest_length: f32,
+    max_suspension_travel: f32,
+    suspension_stiffness: f32,
+    damping_compression: f32,
+    damping_relaxation: f32,
+    max_suspension_force: f32,
+    friction_slip: f32,
+    visual: Handle<Node>,
+}
+
+impl WheelBuilder {
+    /// Creates new wheel builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        let wheel = Wheel::default();
+        Self {
+            base_builder,
+            radius: *wheel.radius,
+            suspension_rest_length: *wheel.suspension_rest_length,
+            max_suspension_travel: *wheel.max_suspension_travel,
+            suspension_stiffness: *wheel.suspension_stiffness,
+            damping_compression: *wheel.damping_compression,
+            damping_relaxation: *wheel.damping_relaxation,
+            max_suspension_force: *wheel.max_suspension_force,
+            friction_slip: *wheel.friction_slip,
+            visual: Default::default(),
+        }
+    }
+
+    /// Sets desired radius of the wheel.
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Sets desired rest length of the suspension.
+    pub fn with_suspension_rest_length(mut self, length: f32) -> Self {
+        self.suspension_rest_length = length;
+        self
+    }
+
+    /// Sets desired maximum travel of the suspension.
+    pub fn with_max_suspension_travel(mut self, travel: f32) -> Self {
+        self.max_suspension_travel = travel;
+        self
+    }
+
+    /// Sets desired stiffness of the suspension.
+    pub fn with_suspension_stiffness(mut self, stiffness: f32) -> Self {
+        self.suspension_stiffness = stiffness;
+        self
+    }
+
+    /// Sets desired damping of the suspension when it is being compressed.
+    pub fn with_damping_compression(mut self, damping: f32) -> Self {
+        self.damping_compression = damping;
+        self
+    }
+
+    /// Sets desired damping of the suspension when it is being released.
+    pub fn with_damping_relaxation(mut self, damping: f32) -> Self {
+        self.damping_relaxation = damping;
+        self
+    }
+
+    /// Sets desired maximum force of the suspension.
+    pub fn with_max_suspension_force(mut self, force: f32) -> Self {
+        self.max_suspension_force = force;
+        self
+    }
+
+    /// Sets desired traction of the tire.
+    pub fn with_friction_slip(mut self, friction_slip: f32) -> Self {
+        self.friction_slip = friction_slip;
+        self
+    }
+
+    /// Sets desired node which transform will be synchronized with the simulated wheel.
+    pub fn with_visual(mut self, visual: Handle<Node>) -> Self {
+        self.visual = visual;
+        self
+    }
+
+    /// Creates new wheel instance.
+    pub fn build_wheel(self) -> Wheel {
+        Wheel {
+            base: self.base_builder.build_base(),
+            radius: self.radius.into(),
+            suspension_rest_length: self.suspension_rest_length.into(),
+            max_suspension_travel: self.max_suspension_travel.into(),
+            suspension_stiffness: self.suspension_stiffness.into(),
+            damping_compression: self.damping_compression.into(),
+            damping_relaxation: self.damping_relaxation.into(),
+            max_suspension_force: self.max_suspension_force.into(),
+            friction_slip: self.friction_slip.into(),
+            visual: self.visual.into(),
+            suspension_length: self.suspension_rest_length,
+            ..Default::default()
+        }
+    }
+
+    /// Creates new wheel node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_wheel())
+    }
+
+    /// Creates new wheel node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+/// Raycast vehicle is a model of a wheeled vehicle, where each wheel is represented by a ray cast down
+/// from the chassis instead of a real rigid body. Such model is much more stable and cheaper than a set
+/// of rigid bodies connected with joints. Each wheel has a suspension (spring and damper), engine force,
+/// brake, steering and a simple friction model.
+///
+/// The vehicle moves a dynamic rigid body (the chassis), its colliders define the shape of the vehicle.
+/// Wheels of the vehicle are [`Wheel`] nodes which are direct children of the vehicle node, the vehicle
+/// node itself is usually a child of the chassis. Forward direction of the vehicle is the look axis (Z)
+/// of the chassis, up direction is the up axis (Y) of the chassis.
+///
+/// The vehicle is simulated right before every step of the physics world, so it works correctly with
+/// fixed time step as well.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{
+///         base::BaseBuilder,
+///         collider::{ColliderBuilder, ColliderShape},
+///         graph::Graph,
+///         node::Node,
+///         rigidbody::RigidBodyBuilder,
+///         transform::TransformBuilder,
+///         vehicle::{VehicleBuilder, WheelBuilder},
+///     },
+/// };
+///
+/// fn create_car(graph: &mut Graph) -> Handle<Node> {
+///     let mut wheels = Vec::new();
+///     for (x, z) in [(-0.8, 1.2), (0.8, 1.2), (-0.8, -1.2), (0.8, -1.2)] {
+///         wheels.push(
+///             WheelBuilder::new(
+///                 BaseBuilder::new().with_local_transform(
+///                     TransformBuilder::new()
+///                         .with_local_position(Vector3::new(x, -0.2, z))
+///                         .build(),
+///                 ),
+///             )
+///             .with_radius(0.35)
+///             .build(graph),
+///         );
+///     }
+///
+///     let collider = ColliderBuilder::new(BaseBuilder::new())
+///         .with_shape(ColliderShape::cuboid(0.9, 0.3, 1.8))
+///         .build(graph);
+///
+///     let chassis = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[collider]))
+///         .build(graph);
+///
+///     let vehicle = VehicleBuilder::new(BaseBuilder::new().with_children(&wheels))
+///         .with_chassis(chassis)
+///         .build(graph);
+///     graph.link_nodes(vehicle, chassis);
+///
+///     chassis
+/// }
+/// ```
+///
+/// Use [`Wheel::set_engine_force`], [`Wheel::set_brake`] and [`Wheel::set_steering`] to control the
+/// vehicle.
+#[derive(Visit, Reflect, Debug)]
+pub struct Vehicle {
+    base: Base,
+
+    #[reflect(setter = "set_chassis")]
+    chassis: InheritableVariable<Handle<Node>>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    speed: f32,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native: Cell<Handle<NativeVehicle>>,
+}
+
+impl Default for Vehicle {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            chassis: Default::default(),
+            speed: 0.0,
+            native: Cell::new(Handle::NONE),
+        }
+    }
+}
+
+impl Clone for Vehicle {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            chassis: self.chassis.clone(),
+            speed: self.speed,
+            // Do not copy. The copy will have its own native representation.
+            native: Cell::new(Handle::NONE),
+        }
+    }
+}
+
+impl Deref for Vehicle {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for Vehicle {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for Vehicle {
+    fn type_uuid() -> Uuid {
+        uuid!("c4b52e1d-8f5e-4c0b-bf3c-2d8e5a7a9e14")
+    }
+}
+
+impl Vehicle {
+    /// Sets a handle of the rigid body that will be used as chassis of the vehicle.
+    pub fn set_chassis(&mut self, chassis: Handle<Node>) -> Handle<Node> {
+        self.chassis.set_value_and_mark_modified(chassis)
+    }
+
+    /// Returns a handle of the rigid body that is used as chassis of the vehicle.
+    pub fn chassis(&self) -> Handle<Node> {
+        *self.chassis
+    }
+
+    /// Returns current forward speed of the vehicle. It is negative, when the vehicle moves backwards.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+}
+
+impl NodeTrait for Vehicle {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.local_bounding_box()
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.base.world_bounding_box()
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn on_removed_from_graph(&mut self, graph: &mut Graph) {
+        graph.physics.remove_vehicle(self.native.get());
+        self.native.set(Handle::NONE);
+
+        Log::info(format!(
+            "Native vehicle was removed for node: {}",
+            self.name()
+        ));
+    }
+
+    fn sync_native(&self, _self_handle: Handle<Node>, context: &mut SyncContext) {
+        context.physics.sync_to_vehicle_node(context.nodes, self);
+    }
+
+    fn update(&mut self, context: &mut UpdateContext) {
+        let native = match context.physics.vehicle(self.native.get()) {
+            Some(native) => native,
+            None => return,
+        };
+
+        self.speed = native.controller.current_vehicle_speed;
+
+        for ((wheel_handle, native_wheel), suspension_length) in native
+            .wheels
+            .iter()
+            .zip(native.controller.wheels())
+            .zip(native.suspension_lengths.iter())
+        {
+            let wheel = match context
+                .nodes
+                .try_borrow_mut(*wheel_handle)
+                .and_then(|n| n.cast_mut::<Wheel>())
+            {
+                Some(wheel) => wheel,
+                None => continue,
+            };
+
+            wheel.suspension_length = *suspension_length;
+            wheel.rotation = native_wheel.rotation;
+            wheel.suspension_force = native_wheel.wheel_suspension_force;
+
+            let visual = *wheel.visual;
+            let wheel_transform = wheel.global_transform() * wheel.local_wheel_transform();
+
+            // Sync transform of the visual node with the simulated wheel.
+            let parent = match context.nodes.try_borrow(visual) {
+                Some(visual) => visual.parent(),
+                None => continue,
+            };
+            let transform = context
+                .nodes
+                .try_borrow(parent)
+                .and_then(|p| p.global_transform().try_inverse())
+                .unwrap_or_else(Matrix4::identity)
+                * wheel_transform;
+
+            context.nodes[visual]
+                .local_transform_mut()
+                .set_position(Vector3::new(transform[12], transform[13], transform[14]))
+                .set_rotation(UnitQuaternion::from_matrix_eps(
+                    &transform.basis(),
+                    f32::EPSILON,
+                    16,
+                    Default::default(),
+                ));
+        }
+    }
+
+    fn validate(&self, scene: &Scene) -> Result<(), String> {
+        match scene.graph.try_get(self.chassis()) {
+            Some(chassis) if chassis.query_component_ref::<RigidBody>().is_some() => Ok(()),
+            _ => Err("Chassis of the vehicle must be a rigid body!".to_string()),
+        }
+    }
+}
+
+/// Allows you to build a vehicle in declarative manner.
+pub struct VehicleBuilder {
+    base_builder: BaseBuilder,
+    chassis: Handle<Node>,
+}
+
+impl VehicleBuilder {
+    /// Creates new vehicle builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            chassis: Default::default(),
+        }
+    }
+
+    /// Sets desired chassis of the vehicle. It must be a handle of a rigid body.
+    pub fn with_chassis(mut self, chassis: Handle<Node>) -> Self {
+        self.chassis = chassis;
+        self
+    }
+
+    /// Creates new vehicle instance.
+    pub fn build_vehicle(self) -> Vehicle {
+        Vehicle {
+            base: self.base_builder.build_base(),
+            chassis: self.chassis.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates new vehicle node.
+    pub fn build_node(self) -> Node {
+        Node::new(self.build_vehicle())
+    }
+
+    /// Creates new vehicle node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            rigidbody::{RigidBodyBuilder, RigidBodyType},
+            transform::TransformBuilder,
+            vehicle::{VehicleBuilder, Wheel, WheelBuilder},
+        },
+    };
+
+    #[test]
+    fn test_raycast_vehicle() {
+        let mut graph = Graph::new();
+
+        let ground_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(100.0, 0.5, 100.0))
+            .build(&mut graph);
+        RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[ground_collider])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, -0.5, 0.0))
+                        .build(),
+                ),
+        )
+        .with_body_type(RigidBodyType::Static)
+        .build(&mut graph);
+
+        let mut wheels = Vec::new();
+        for (x, z) in [(-0.8, 1.2), (0.8, 1.2), (-0.8, -1.2), (0.8, -1.2)] {
+            wheels.push(
+                WheelBuilder::new(
+                    BaseBuilder::new().with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(x, -0.2, z))
+                            .build(),
+                    ),
+                )
+                .build(&mut graph),
+            );
+        }
+        let vehicle =
+            VehicleBuilder::new(BaseBuilder::new().with_children(&wheels)).build(&mut graph);
+
+        let chassis_collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(0.9, 0.3, 1.8))
+            .build(&mut graph);
+        let chassis = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[chassis_collider, vehicle])
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(Vector3::new(0.0, 1.0, 0.0))
+                        .build(),
+                ),
+        )
+        .build(&mut graph);
+        graph[vehicle].as_vehicle_mut().set_chassis(chassis);
+
+        // Let the vehicle settle on its suspension.
+        for _ in 0..180 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        let position = graph[chassis].global_position();
+        // Chassis must hang on the wheels, not lie on the ground.
+        assert!(position.y > 0.6, "{}", position.y);
+        assert!(position.z.abs() < 0.01);
+        for wheel in wheels.iter() {
+            assert!(graph[*wheel].as_wheel().is_in_contact());
+        }
+
+        for wheel in wheels.iter() {
+            graph[*wheel].as_wheel_mut().set_engine_force(1000.0);
+        }
+        for _ in 0..60 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        assert!(graph[chassis].global_position().z > 0.5);
+        assert!(graph[vehicle].as_vehicle().speed() > 0.0);
+        let wheel: &Wheel = graph[wheels[0]].as_wheel();
+        assert!(wheel.rotation() > 0.0);
+    }
+}
@@ -0,0 +1,393 @@
+//! Water volume applies buoyancy and drag forces to rigid bodies inside of it. See [`WaterVolume`] docs
+//! for more info.
+
+use crate::{
+    core::{
+        algebra::{Point3, Vector3},
+        color::Color,
+        log::Log,
+        math::aabb::AxisAlignedBoundingBox,
+        pool::Handle,
+        reflect::prelude::*,
+        uuid::{uuid, Uuid},
+        variable::InheritableVariable,
+        visitor::prelude::*,
+        TypeUuidProvider,
+    },
+    scene::{
+        base::{Base, BaseBuilder},
+        debug::SceneDrawingContext,
+        graph::{physics::NativeWaterVolume, Graph},
+        node::{Node, NodeTrait, SyncContext},
+    },
+};
+use std::{
+    cell::Cell,
+    ops::{Deref, DerefMut},
+};
+
+/// Water volume is a box filled with a fluid. Every dynamic rigid body, that overlaps the box, receives
+/// buoyancy and drag forces at each simulation step of the physics world. It could be used for floating
+/// objects (boats, barrels, etc.) and for swimming characters.
+///
+/// ## Buoyancy
+///
+/// Buoyancy force is equal to the weight of the fluid displaced by the submerged part of a collider, it is
+/// applied at the center of the submerged part, so floating objects tend to rotate into a stable position.
+/// Whether a body floats or sinks is defined by its density (mass of the body divided by the volume of its
+/// colliders) - bodies with density lower than the density of the fluid float, the other ones sink. The
+/// simplest way to control it is to set the density of colliders of a body (see
+/// [`crate::scene::collider::Collider::set_density`]). Submerged part of a collider is approximated using its bounding box,
+/// which is accurate enough for gameplay purposes.
+///
+/// ## Drag
+///
+/// Linear and angular drag slow down bodies inside of the fluid proportionally to their submerged part.
+/// Linear drag acts relative to the flow velocity of the fluid, so a non-zero flow velocity could be used
+/// to make rivers that carry objects.
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{
+///     core::{algebra::Vector3, pool::Handle},
+///     scene::{base::BaseBuilder, graph::Graph, node::Node, water_volume::WaterVolumeBuilder},
+/// };
+///
+/// fn create_pool(graph: &mut Graph) -> Handle<Node> {
+///     WaterVolumeBuilder::new(BaseBuilder::new())
+///         .with_half_extents(Vector3::new(10.0, 2.0, 5.0))
+///         .with_density(1000.0)
+///         .with_linear_drag(1.5)
+///         .build(graph)
+/// }
+/// ```
+#[derive(Visit, Reflect, Debug)]
+pub struct WaterVolume {
+    base: Base,
+
+    #[reflect(setter = "set_half_extents")]
+    half_extents: InheritableVariable<Vector3<f32>>,
+
+    #[reflect(min_value = 0.0, step = 10.0)]
+    #[reflect(setter = "set_density")]
+    density: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_linear_drag")]
+    linear_drag: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0, step = 0.1)]
+    #[reflect(setter = "set_angular_drag")]
+    angular_drag: InheritableVariable<f32>,
+
+    #[reflect(setter = "set_flow_velocity")]
+    flow_velocity: InheritableVariable<Vector3<f32>>,
+
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub(crate) native: Cell<Handle<NativeWaterVolume>>,
+}
+
+impl Default for WaterVolume {
+    fn default() -> Self {
+        Self {
+            base: Default::default(),
+            half_extents: InheritableVariable::new_modified(Vector3::new(0.5, 0.5, 0.5)),
+            density: InheritableVariable::new_modified(1000.0),
+            linear_drag: InheritableVariable::new_modified(1.0),
+            angular_drag: InheritableVariable::new_modified(1.0),
+            flow_velocity: Default::default(),
+            native: Cell::new(Handle::NONE),
+        }
+    }
+}
+
+impl Clone for WaterVolume {
+    fn clone(&self) -> Self {
+        Self {
+            base: self.base.clone(),
+            half_extents: self.half_extents.clone(),
+            density: self.density.clone(),
+            linear_drag: self.linear_drag.clone(),
+            angular_drag: self.angular_drag.clone(),
+            flow_velocity: self.flow_velocity.clone(),
+            // Do not copy. The copy will have its own native representation.
+            native: Cell::new(Handle::NONE),
+        }
+    }
+}
+
+impl Deref for WaterVolume {
+    type Target = Base;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+
+impl DerefMut for WaterVolume {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.base
+    }
+}
+
+impl TypeUuidProvider for WaterVolume {
+    fn type_uuid() -> Uuid {
+        uuid!("0d6a8f42-71c3-4e9b-a5d2-3b8e6c1f7a90")
+    }
+}
+
+impl WaterVolume {
+    /// Sets new half extents of the box of the volume, in local coordinates of the node.
+    pub fn set_half_extents(&mut self, half_extents: Vector3<f32>) -> Vector3<f32> {
+        self.half_extents
+            .set_value_and_mark_modified(half_extents.sup(&Vector3::default()))
+    }
+
+    /// Returns current half extents of the box of the volume.
+    pub fn half_extents(&self) -> Vector3<f32> {
+        *self.half_extents
+    }
+
+    /// Sets density of the fluid in kg/m³. Default value is 1000.0 which is the density of water.
+    pub fn set_density(&mut self, density: f32) -> f32 {
+        self.density.set_value_and_mark_modified(density.max(0.0))
+    }
+
+    /// Returns density of the fluid in kg/m³.
+    pub fn density(&self) -> f32 {
+        *self.density
+    }
+
+    /// Sets linear drag coefficient of the fluid. It defines how fast the velocity of a fully submerged
+    /// body approaches the flow velocity (in 1/s).
+    pub fn set_linear_drag(&mut self, drag: f32) -> f32 {
+        self.linear_drag.set_value_and_mark_modified(drag.max(0.0))
+    }
+
+    /// Returns linear drag coefficient of the fluid.
+    pub fn linear_drag(&self) -> f32 {
+        *self.linear_drag
+    }
+
+    /// Sets angular drag coefficient of the fluid. It defines how fast the angular velocity of a fully
+    /// submerged body fades out (in 1/s).
+    pub fn set_angular_drag(&mut self, drag: f32) -> f32 {
+        self.angular_drag.set_value_and_mark_modified(drag.max(0.0))
+    }
+
+    /// Returns angular drag coefficient of the fluid.
+    pub fn angular_drag(&self) -> f32 {
+        *self.angular_drag
+    }
+
+    /// Sets flow velocity of the fluid in world coordinates.
+    pub fn set_flow_velocity(&mut self, velocity: Vector3<f32>) -> Vector3<f32> {
+        self.flow_velocity.set_value_and_mark_modified(velocity)
+    }
+
+    /// Returns flow velocity of the fluid in world coordinates.
+    pub fn flow_velocity(&self) -> Vector3<f32> {
+        *self.flow_velocity
+    }
+
+    /// Returns depth of the given point (in world coordinates) under the surface of the fluid, or `None`
+    /// if the point is outside of the volume. The surface is the top face (along local Y axis) of the
+    /// box. It could be used to check whether a character should swim or whether its head is under water.
+    pub fn depth_at(&self, point: Vector3<f32>) -> Option<f32> {
+        let transform = self.global_transform();
+        let local_point = transform
+            .try_inverse()?
+            .transform_point(&Point3::from(point))
+            .coords;
+        let half_extents = *self.half_extents;
+
+        if local_point.x.abs() > half_extents.x
+            || local_point.y.abs() > half_extents.y
+            || local_point.z.abs() > half_extents.z
+        {
+            return None;
+        }
+
+        let up = transform.transform_vector(&Vector3::y());
+        Some((half_extents.y - local_point.y) * up.norm())
+    }
+}
+
+impl NodeTrait for WaterVolume {
+    crate::impl_query_component!();
+
+    fn local_bounding_box(&self) -> AxisAlignedBoundingBox {
+        AxisAlignedBoundingBox::from_min_max(-*self.half_extents, *self.half_extents)
+    }
+
+    fn world_bounding_box(&self) -> AxisAlignedBoundingBox {
+        self.local_bounding_box()
+            .transform(&self.global_transform())
+    }
+
+    fn id(&self) -> Uuid {
+        Self::type_uuid()
+    }
+
+    fn on_removed_from_graph(&mut self, graph: &mut Graph) {
+        graph.physics.remove_water_volume(self.native.get());
+        self.native.set(Handle::NONE);
+
+        Log::info(format!(
+            "Native water volume was removed for node: {}",
+            self.name()
+        ));
+    }
+
+    fn sync_native(&self, _self_handle: Handle<Node>, context: &mut SyncContext) {
+        context.physics.sync_to_water_volume_node(self);
+    }
+
+    fn debug_draw(&self, ctx: &mut SceneDrawingContext) {
+        ctx.draw_oob(
+            &self.local_bounding_box(),
+            self.global_transform(),
+            Color::opaque(0, 120, 255),
+        );
+    }
+}
+
+/// Allows you to create water volumes in declarative manner.
+pub struct WaterVolumeBuilder {
+    base_builder: BaseBuilder,
+    half_extents: Vector3<f32>,
+    density: f32,
+    linear_drag: f32,
+    angular_drag: f32,
+    flow_velocity: Vector3<f32>,
+}
+
+impl WaterVolumeBuilder {
+    /// Creates new water volume builder.
+    pub fn new(base_builder: BaseBuilder) -> Self {
+        Self {
+            base_builder,
+            half_extents: Vector3::new(0.5, 0.5, 0.5),
+            density: 1000.0,
+            linear_drag: 1.0,
+            angular_drag: 1.0,
+            flow_velocity: Default::default(),
+        }
+    }
+
+    /// Sets desired half extents of the box of the volume.
+    pub fn with_half_extents(mut self, half_extents: Vector3<f32>) -> Self {
+        self.half_extents = half_extents.sup(&Vector3::default());
+        self
+    }
+
+    /// Sets desired density of the fluid.
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density.max(0.0);
+        self
+    }
+
+    /// Sets desired linear drag coefficient of the fluid.
+    pub fn with_linear_drag(mut self, drag: f32) -> Self {
+        self.linear_drag = drag.max(0.0);
+        self
+    }
+
+    /// Sets desired angular drag coefficient of the fluid.
+    pub fn with_angular_drag(mut self, drag: f32) -> Self {
+        self.angular_drag = drag.max(0.0);
+        self
+    }
+
+    /// Sets desired flow velocity of the fluid.
+    pub fn with_flow_velocity(mut self, velocity: Vector3<f32>) -> Self {
+        self.flow_velocity = velocity;
+        self
+    }
+
+    /// Creates new water volume node.
+    pub fn build_node(self) -> Node {
+        Node::new(WaterVolume {
+            base: self.base_builder.build_base(),
+            half_extents: self.half_extents.into(),
+            density: self.density.into(),
+            linear_drag: self.linear_drag.into(),
+            angular_drag: self.angular_drag.into(),
+            flow_velocity: self.flow_velocity.into(),
+            native: Cell::new(Handle::NONE),
+        })
+    }
+
+    /// Creates new water volume node and adds it to the graph.
+    pub fn build(self, graph: &mut Graph) -> Handle<Node> {
+        graph.add_node(self.build_node())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::algebra::{Vector2, Vector3},
+        scene::{
+            base::BaseBuilder,
+            collider::{ColliderBuilder, ColliderShape},
+            graph::Graph,
+            rigidbody::RigidBodyBuilder,
+            transform::TransformBuilder,
+            water_volume::WaterVolumeBuilder,
+        },
+    };
+
+    #[test]
+    fn test_water_volume_buoyancy() {
+        let mut graph = Graph::new();
+
+        // The surface of the water is at Y = 0.
+        let water = WaterVolumeBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(0.0, -2.0, 0.0))
+                    .build(),
+            ),
+        )
+        .with_half_extents(Vector3::new(10.0, 2.0, 10.0))
+        .with_linear_drag(3.0)
+        .build(&mut graph);
+
+        let add_box = |graph: &mut Graph, x: f32, density: f32| {
+            let collider = ColliderBuilder::new(BaseBuilder::new())
+                .with_shape(ColliderShape::cuboid(0.5, 0.5, 0.5))
+                .with_density(Some(density))
+                .build(graph);
+            RigidBodyBuilder::new(
+                BaseBuilder::new()
+                    .with_children(&[collider])
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(Vector3::new(x, 1.0, 0.0))
+                            .build(),
+                    ),
+            )
+            .with_locked_rotations(true)
+            .build(graph)
+        };
+
+        let light = add_box(&mut graph, -5.0, 500.0);
+        let heavy = add_box(&mut graph, 5.0, 2000.0);
+
+        for _ in 0..300 {
+            graph.update(Vector2::new(800.0, 600.0), 1.0 / 60.0, Default::default());
+        }
+
+        // Half of the light box must be under water.
+        let light_position = graph[light].global_position();
+        assert!(light_position.y.abs() < 0.1, "{}", light_position.y);
+        assert!(graph[heavy].global_position().y < -1.0);
+
+        let water = graph[water].as_water_volume();
+        assert_eq!(water.depth_at(Vector3::new(0.0, -0.5, 0.0)), Some(0.5));
+        assert_eq!(water.depth_at(Vector3::new(0.0, 0.5, 0.0)), None);
+    }
+}
@@ -568,6 +568,12 @@ impl Script {
         }
     }
 
+    // Replaces the script instance with a copy of the instance of the given script, initialization
+    // flags remain unchanged.
+    pub(crate) fn restore_instance_from(&mut self, other: &Script) {
+        self.instance = other.instance.clone_box();
+    }
+
     /// Performs downcasting to a particular type.
     #[inline]
     pub fn cast<T: ScriptTrait>(&self) -> Option<&T> {
@@ -0,0 +1,455 @@
+//! Free-fly debug camera, that can temporarily take over rendering of a scene from any camera. It
+//! is useful to inspect scenes in any build of a game without writing a camera controller. See
+//! [`DebugCamera`] docs for more info.
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3},
+        pool::Handle,
+    },
+    event::{DeviceEvent, ElementState, Event, MouseButton, MouseScrollDelta, WindowEvent},
+    keyboard::KeyCode,
+    scene::{
+        base::BaseBuilder,
+        camera::{Camera, CameraBuilder},
+        node::Node,
+        transform::TransformBuilder,
+        Scene,
+    },
+};
+
+/// Free-fly debug camera controller. When enabled, it creates its own camera at the location of the
+/// currently active camera of a scene and disables every other camera of the scene, so the scene is
+/// rendered from the debug camera. When disabled, it removes its camera and enables the cameras
+/// back. The camera ignores physics, so it flies through any geometry.
+///
+/// Default controls:
+///
+/// - `F8` - toggle the camera.
+/// - `W`/`S`/`A`/`D` - move forward/backward/left/right.
+/// - `E`/`Q` - move up/down.
+/// - Hold right mouse button and move the mouse - look around.
+/// - Hold `Shift` - move faster, hold `Ctrl` - move slower.
+/// - Mouse wheel - change movement speed.
+///
+/// Gamepads could be used as well, feed their stick values using [`DebugCamera::set_analog_input`].
+///
+/// The engine has its own instance of the camera (see [`crate::engine::Engine::debug_camera`]),
+/// which is updated automatically when [`crate::engine::Engine::debug_camera_scene`] is set. Games
+/// that do not use the engine's executor could drive their own instance:
+///
+/// ## Example
+///
+/// ```rust
+/// use fyrox::{event::Event, scene::Scene, utils::debug_camera::DebugCamera};
+///
+/// struct Game {
+///     debug_camera: DebugCamera,
+/// }
+///
+/// impl Game {
+///     fn on_os_event(&mut self, event: &Event<()>) {
+///         self.debug_camera.process_event(event);
+///     }
+///
+///     fn update(&mut self, scene: &mut Scene, dt: f32) {
+///         self.debug_camera.update(scene, dt);
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DebugCamera {
+    /// A key that toggles the camera.
+    pub toggle_key: KeyCode,
+    /// Movement speed in meters per second.
+    pub speed: f32,
+    /// Speed multiplier that is used when `Shift` is held.
+    pub fast_speed_multiplier: f32,
+    /// Speed multiplier that is used when `Ctrl` is held.
+    pub slow_speed_multiplier: f32,
+    /// Mouse sensitivity in radians per pixel.
+    pub mouse_sensitivity: f32,
+    /// Look speed (in radians per second) for analog input.
+    pub analog_look_speed: f32,
+    camera: Handle<Node>,
+    disabled_cameras: Vec<Handle<Node>>,
+    toggle_requested: bool,
+    yaw: f32,
+    pitch: f32,
+    move_forward: bool,
+    move_backward: bool,
+    move_left: bool,
+    move_right: bool,
+    move_up: bool,
+    move_down: bool,
+    fast: bool,
+    slow: bool,
+    rotating: bool,
+    mouse_delta: Vector2<f32>,
+    analog_movement: Vector3<f32>,
+    analog_look: Vector2<f32>,
+}
+
+impl Default for DebugCamera {
+    fn default() -> Self {
+        Self {
+            toggle_key: KeyCode::F8,
+            speed: 10.0,
+            fast_speed_multiplier: 4.0,
+            slow_speed_multiplier: 0.25,
+            mouse_sensitivity: 0.005,
+            analog_look_speed: 2.0,
+            camera: Handle::NONE,
+            disabled_cameras: Default::default(),
+            toggle_requested: false,
+            yaw: 0.0,
+            pitch: 0.0,
+            move_forward: false,
+            move_backward: false,
+            move_left: false,
+            move_right: false,
+            move_up: false,
+            move_down: false,
+            fast: false,
+            slow: false,
+            rotating: false,
+            mouse_delta: Default::default(),
+            analog_movement: Default::default(),
+            analog_look: Default::default(),
+        }
+    }
+}
+
+impl DebugCamera {
+    /// Creates new debug camera controller. The camera is disabled by default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the debug camera is currently rendering the scene.
+    pub fn is_enabled(&self) -> bool {
+        self.camera.is_some()
+    }
+
+    /// Returns a handle of the camera node of the debug camera. The handle is [`Handle::NONE`] if
+    /// the debug camera is disabled.
+    pub fn camera(&self) -> Handle<Node> {
+        self.camera
+    }
+
+    /// Sets analog input, that is used together with the keyboard and the mouse. `movement` is a
+    /// desired movement direction in local coordinates of the camera (x - right, y - up,
+    /// z - forward), `look` is a desired rotation speed (x - yaw, y - pitch). Every component
+    /// should be in `[-1; 1]` range, for example values of gamepad sticks.
+    pub fn set_analog_input(&mut self, movement: Vector3<f32>, look: Vector2<f32>) {
+        self.analog_movement = movement;
+        self.analog_look = look;
+    }
+
+    /// Processes the given OS event, it must be called for every event.
+    pub fn process_event(&mut self, event: &Event<()>) {
+        match event {
+            Event::WindowEvent { event, .. } => match event {
+                WindowEvent::KeyboardInput { event, .. } => {
+                    let pressed = event.state == ElementState::Pressed;
+                    match event.physical_key {
+                        key if key == self.toggle_key => {
+                            if pressed && !event.repeat {
+                                self.toggle_requested = !self.toggle_requested;
+                            }
+                        }
+                        KeyCode::KeyW => self.move_forward = pressed,
+                        KeyCode::KeyS => self.move_backward = pressed,
+                        KeyCode::KeyA => self.move_left = pressed,
+                        KeyCode::KeyD => self.move_right = pressed,
+                        KeyCode::KeyE => self.move_up = pressed,
+                        KeyCode::KeyQ => self.move_down = pressed,
+                        KeyCode::ShiftLeft | KeyCode::ShiftRight => self.fast = pressed,
+                        KeyCode::ControlLeft | KeyCode::ControlRight => self.slow = pressed,
+                        _ => (),
+                    }
+                }
+                WindowEvent::MouseInput {
+                    state,
+                    button: MouseButton::Right,
+                    ..
+                } => {
+                    self.rotating = *state == ElementState::Pressed;
+                }
+                WindowEvent::MouseWheel { delta, .. } if self.is_enabled() => {
+                    let amount = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => *y,
+                        MouseScrollDelta::PixelDelta(position) => position.y.signum() as f32,
+                    };
+                    self.speed = (self.speed * 1.2f32.powf(amount)).clamp(0.1, 1000.0);
+                }
+                _ => (),
+            },
+            Event::DeviceEvent {
+                event: DeviceEvent::MouseMotion { delta },
+                ..
+            } => {
+                if self.rotating {
+                    self.mouse_delta += Vector2::new(delta.0 as f32, delta.1 as f32);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Toggles the debug camera, the change will be applied at the next [`Self::update`].
+    pub fn toggle(&mut self) {
+        self.toggle_requested = !self.toggle_requested;
+    }
+
+    /// Enables the debug camera in the given scene. It does nothing if the camera is already
+    /// enabled.
+    pub fn enable(&mut self, scene: &mut Scene) {
+        if self.is_enabled() {
+            return;
+        }
+
+        let mut builder = CameraBuilder::new(BaseBuilder::new().with_name("DebugCamera"));
+        let mut position = Vector3::default();
+        let mut look = Vector3::z();
+
+        // Take the settings of the active camera, so the picture stays the same.
+        let active_camera = scene
+            .graph
+            .pair_iter()
+            .find(|(_, node)| node.cast::<Camera>().map_or(false, |c| c.is_enabled()))
+            .map(|(handle, _)| handle);
+        if let Some(camera) = active_camera.and_then(|h| scene.graph[h].cast::<Camera>()) {
+            position = camera.global_position();
+            look = camera.look_vector();
+            builder = builder
+                .with_projection(camera.projection_value())
+                .with_viewport(camera.viewport())
+                .with_exposure(camera.exposure())
+                .with_color_grading_enabled(camera.color_grading_enabled())
+                .with_dithering_enabled(camera.dithering_enabled());
+            if let Some(skybox) = camera.skybox_ref() {
+                builder = builder.with_skybox(skybox.clone());
+            }
+            if let Some(environment) = camera.environment_map() {
+                builder = builder.with_environment(environment);
+            }
+            if let Some(lut) = camera.color_grading_lut() {
+                builder = builder.with_color_grading_lut(lut);
+            }
+        }
+
+        let look = look.try_normalize(f32::EPSILON).unwrap_or_else(Vector3::z);
+        self.yaw = look.x.atan2(look.z);
+        self.pitch = (-look.y).clamp(-1.0, 1.0).asin();
+
+        self.disabled_cameras.clear();
+        for (handle, node) in scene.graph.pair_iter_mut() {
+            if let Some(camera) = node.cast_mut::<Camera>() {
+                if camera.is_enabled() {
+                    camera.set_enabled(false);
+                    self.disabled_cameras.push(handle);
+                }
+            }
+        }
+
+        self.camera = builder.build(&mut scene.graph);
+        scene.graph[self.camera].set_local_transform(
+            TransformBuilder::new()
+                .with_local_position(position)
+                .with_local_rotation(self.rotation())
+                .build(),
+        );
+        self.toggle_requested = true;
+    }
+
+    /// Disables the debug camera in the given scene and enables the cameras that were active before.
+    /// It does nothing if the camera is already disabled.
+    pub fn disable(&mut self, scene: &mut Scene) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        if scene.graph.is_valid_handle(self.camera) {
+            scene.graph.remove_node(self.camera);
+        }
+        self.camera = Handle::NONE;
+
+        for handle in self.disabled_cameras.drain(..) {
+            if let Some(camera) = scene.graph.try_get_mut_of_type::<Camera>(handle) {
+                camera.set_enabled(true);
+            }
+        }
+        self.toggle_requested = false;
+    }
+
+    fn rotation(&self) -> UnitQuaternion<f32> {
+        UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.yaw)
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), self.pitch)
+    }
+
+    /// Applies pending toggle requests and moves the debug camera (if enabled). Must be called
+    /// every frame.
+    pub fn update(&mut self, scene: &mut Scene, dt: f32) {
+        if self.toggle_requested != self.is_enabled() {
+            if self.toggle_requested {
+                self.enable(scene);
+            } else {
+                self.disable(scene);
+            }
+        }
+
+        // The camera could be removed by someone else.
+        if self.is_enabled() && !scene.graph.is_valid_handle(self.camera) {
+            self.disable(scene);
+        }
+
+        let mouse_delta = std::mem::take(&mut self.mouse_delta);
+
+        if !self.is_enabled() {
+            return;
+        }
+
+        let look = mouse_delta.scale(self.mouse_sensitivity)
+            + self.analog_look.scale(self.analog_look_speed * dt);
+        self.yaw -= look.x;
+        self.pitch = (self.pitch + look.y).clamp(
+            -std::f32::consts::FRAC_PI_2 + 0.01,
+            std::f32::consts::FRAC_PI_2 - 0.01,
+        );
+
+        let mut direction = self.analog_movement;
+        let mut add = |condition: bool, v: Vector3<f32>| {
+            if condition {
+                direction += v;
+            }
+        };
+        add(self.move_forward, Vector3::z());
+        add(self.move_backward, -Vector3::z());
+        add(self.move_left, Vector3::x());
+        add(self.move_right, -Vector3::x());
+        add(self.move_up, Vector3::y());
+        add(self.move_down, -Vector3::y());
+
+        let mut speed = self.speed;
+        if self.fast {
+            speed *= self.fast_speed_multiplier;
+        }
+        if self.slow {
+            speed *= self.slow_speed_multiplier;
+        }
+
+        let rotation = self.rotation();
+        let velocity = rotation
+            .transform_vector(&direction.cap_magnitude(1.0))
+            .scale(speed * dt);
+
+        let transform = scene.graph[self.camera].local_transform_mut();
+        transform.set_rotation(rotation).offset(velocity);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{UnitQuaternion, Vector3},
+            pool::Handle,
+        },
+        scene::{
+            base::BaseBuilder,
+            camera::{Camera, CameraBuilder},
+            node::Node,
+            transform::TransformBuilder,
+            Scene,
+        },
+        utils::debug_camera::DebugCamera,
+    };
+
+    fn assert_vec_eq(a: Vector3<f32>, b: Vector3<f32>) {
+        assert!((a - b).norm() < 1e-4, "{:?} != {:?}", a, b);
+    }
+
+    fn is_camera_enabled(scene: &Scene, handle: Handle<Node>) -> bool {
+        scene.graph[handle].cast::<Camera>().unwrap().is_enabled()
+    }
+
+    // Creates a scene with two active cameras and one inactive camera. The first camera is placed
+    // at (1, 2, 3) and looks along the X axis.
+    fn make_scene() -> (Scene, [Handle<Node>; 3]) {
+        let mut scene = Scene::new();
+        let first = CameraBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_position(Vector3::new(1.0, 2.0, 3.0))
+                    .with_local_rotation(UnitQuaternion::from_axis_angle(
+                        &Vector3::y_axis(),
+                        std::f32::consts::FRAC_PI_2,
+                    ))
+                    .build(),
+            ),
+        )
+        .build(&mut scene.graph);
+        let second = CameraBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+        let inactive = CameraBuilder::new(BaseBuilder::new())
+            .enabled(false)
+            .build(&mut scene.graph);
+        scene.graph.update_hierarchical_data();
+        (scene, [first, second, inactive])
+    }
+
+    #[test]
+    fn test_debug_camera_enable_disable() {
+        let (mut scene, [first, second, inactive]) = make_scene();
+
+        let mut debug_camera = DebugCamera::new();
+        debug_camera.enable(&mut scene);
+        scene.graph.update_hierarchical_data();
+
+        // The debug camera must take the place of the active camera and be the only active one.
+        assert!(debug_camera.is_enabled());
+        let camera = &scene.graph[debug_camera.camera()];
+        assert_vec_eq(camera.global_position(), Vector3::new(1.0, 2.0, 3.0));
+        assert_vec_eq(camera.look_vector().normalize(), Vector3::x());
+        assert!(is_camera_enabled(&scene, debug_camera.camera()));
+        assert!(!is_camera_enabled(&scene, first));
+        assert!(!is_camera_enabled(&scene, second));
+        assert!(!is_camera_enabled(&scene, inactive));
+
+        let camera = debug_camera.camera();
+        debug_camera.disable(&mut scene);
+
+        // Only the cameras that were active before must be enabled back.
+        assert!(!debug_camera.is_enabled());
+        assert!(!scene.graph.is_valid_handle(camera));
+        assert!(is_camera_enabled(&scene, first));
+        assert!(is_camera_enabled(&scene, second));
+        assert!(!is_camera_enabled(&scene, inactive));
+    }
+
+    #[test]
+    fn test_debug_camera_movement() {
+        let (mut scene, _) = make_scene();
+
+        let mut debug_camera = DebugCamera::new();
+        debug_camera.toggle();
+        debug_camera.update(&mut scene, 0.0);
+        assert!(debug_camera.is_enabled());
+
+        // Full speed forward, the camera must move along its look direction.
+        debug_camera.set_analog_input(Vector3::z(), Default::default());
+        debug_camera.update(&mut scene, 0.5);
+        scene.graph.update_hierarchical_data();
+
+        let camera = &scene.graph[debug_camera.camera()];
+        assert_vec_eq(
+            camera.global_position(),
+            Vector3::new(1.0 + debug_camera.speed * 0.5, 2.0, 3.0),
+        );
+        assert_vec_eq(camera.look_vector().normalize(), Vector3::x());
+
+        debug_camera.toggle();
+        debug_camera.update(&mut scene, 0.0);
+        assert!(!debug_camera.is_enabled());
+    }
+}
@@ -10,6 +10,7 @@
 #![forbid(unsafe_code)]
 
 use crate::{
+    asset::ResourceStateRef,
     asset::{
         manager::{ResourceManager, ResourceRegistrationError},
         ResourceData,
@@ -24,7 +25,7 @@ use crate::{
         sstorage::ImmutableString,
         visitor::prelude::*,
     },
-    material::PropertyValue,
+    material::{Material, PropertyValue},
     resource::texture::{Texture, TextureKind, TexturePixelKind, TextureResource},
     scene::{
         light::{directional::DirectionalLight, point::PointLight, spot::SpotLight},
@@ -95,6 +96,9 @@ struct Instance {
     source_data: SurfaceSharedData,
     data: Option<InstanceData>,
     transform: Matrix4<f32>,
+    density: f32,
+    /// Linear HDR radiance of the surface, zero for non-emissive surfaces.
+    emission: Vector3<f32>,
 }
 
 impl Instance {
@@ -381,6 +385,8 @@ impl LightmapInputData {
                         transform: global_transform,
                         // Calculated down below.
                         data: None,
+                        density: surface.lightmap_density(),
+                        emission: material_emission(&material),
                     });
                 }
             }
@@ -396,11 +402,13 @@ impl LightmapInputData {
 
 impl Lightmap {
     /// Generates lightmap for given scene. This method **automatically** generates secondary
-    /// texture coordinates! This method is blocking, however internally it uses massive parallelism
-    /// to use all available CPU power efficiently.
+    /// texture coordinates for surfaces that don't have them, existing secondary texture coordinates
+    /// are kept as is (see [`uvgen::generate_uvs_if_missing`]). This method is blocking, however
+    /// internally it uses massive parallelism to use all available CPU power efficiently.
     ///
     /// `texels_per_unit` defines resolution of lightmap, the higher value is, the more quality
-    /// lightmap will be generated, but also it will be slow to generate.
+    /// lightmap will be generated, but also it will be slow to generate. It is multiplied by
+    /// lightmap density of each surface (see [`crate::scene::mesh::surface::Surface::set_lightmap_density`]).
     /// `progress_indicator` allows you to get info about current progress.
     /// `cancellation_token` allows you to stop generation in any time.
     pub fn new(
@@ -425,7 +433,7 @@ impl Lightmap {
                     Err(LightmapGenerationError::Cancelled)
                 } else {
                     let mut data = data.lock();
-                    let patch = uvgen::generate_uvs(&mut data, uv_spacing)?;
+                    let patch = uvgen::generate_uvs_if_missing(&mut data, uv_spacing)?;
                     progress_indicator.advance_progress();
                     Ok((patch.data_id, patch))
                 }
@@ -498,15 +506,23 @@ impl Lightmap {
             })
             .collect::<Result<(), LightmapGenerationError>>()?;
 
+        let emitters = collect_emitters(&instances);
+
         progress_indicator.set_stage(ProgressStage::CalculatingLight, instances.len() as u32);
 
         let mut map: FxHashMap<Handle<Node>, Vec<LightmapEntry>> = FxHashMap::default();
-        for instance in instances.iter() {
+        for (instance_index, instance) in instances.iter().enumerate() {
             if cancellation_token.is_cancelled() {
                 return Err(LightmapGenerationError::Cancelled);
             }
 
-            let lightmap = generate_lightmap(instance, &instances, &lights, texels_per_unit);
+            let lightmap = generate_lightmap(
+                instance_index,
+                &instances,
+                &lights,
+                &emitters,
+                texels_per_unit,
+            );
             map.entry(instance.owner).or_default().push(LightmapEntry {
                 texture: Some(TextureResource::new_ok(lightmap)),
                 lights: lights.iter().map(|light| light.handle()).collect(),
@@ -621,9 +637,115 @@ impl LightDefinition {
     }
 }
 
+/// Emissive triangle is a small area light source. Emissive surfaces (neon signs, screens, etc.) are
+/// split into triangles, each triangle emits light from its front side.
+struct EmissiveTriangle {
+    /// Index of an instance to which the triangle belongs.
+    instance_index: usize,
+    /// World-space center of the triangle.
+    center: Vector3<f32>,
+    /// World-space normal of the triangle.
+    normal: Vector3<f32>,
+    /// Area of the triangle.
+    area: f32,
+    /// Linear HDR radiance of the triangle.
+    radiance: Vector3<f32>,
+}
+
+/// Calculates average color of the texture. Only 8-bit color formats are supported, any other
+/// format is treated as white.
+fn average_texture_color(texture: &Texture) -> Vector3<f32> {
+    let (stride, swizzle) = match texture.pixel_kind() {
+        TexturePixelKind::RGB8 => (3, [0, 1, 2]),
+        TexturePixelKind::RGBA8 => (4, [0, 1, 2]),
+        TexturePixelKind::BGR8 => (3, [2, 1, 0]),
+        TexturePixelKind::BGRA8 => (4, [2, 1, 0]),
+        _ => return Vector3::repeat(1.0),
+    };
+
+    let mut sum = Vector3::<f32>::default();
+    let mut count = 0usize;
+    for pixel in texture.mip_level_data(0).chunks_exact(stride) {
+        sum += Vector3::new(
+            pixel[swizzle[0]] as f32,
+            pixel[swizzle[1]] as f32,
+            pixel[swizzle[2]] as f32,
+        );
+        count += 1;
+    }
+
+    if count > 0 {
+        sum.scale(1.0 / (255.0 * count as f32))
+    } else {
+        Vector3::default()
+    }
+}
+
+/// Calculates emitted radiance of a surface with the given material. It mimics the standard shader:
+/// `emissionIntensity * emissionStrength * emissionTexture`, where the texture is replaced with its
+/// average color. Materials without emission texture do not emit light.
+fn material_emission(material: &Material) -> Vector3<f32> {
+    let texture = match material
+        .property_ref(&ImmutableString::new("emissionTexture"))
+        .and_then(|v| v.as_sampler())
+    {
+        Some(texture) => texture,
+        None => return Vector3::default(),
+    };
+
+    let texture_color = match texture.state().get() {
+        ResourceStateRef::Ok(texture) => average_texture_color(texture),
+        _ => return Vector3::default(),
+    };
+
+    let strength = match material.property_ref(&ImmutableString::new("emissionStrength")) {
+        Some(PropertyValue::Vector3(strength)) => *strength,
+        _ => Vector3::repeat(1.0),
+    };
+
+    let intensity = match material.property_ref(&ImmutableString::new("emissionIntensity")) {
+        Some(PropertyValue::Float(intensity)) => *intensity,
+        _ => 1.0,
+    };
+
+    strength.component_mul(&texture_color).scale(intensity)
+}
+
+/// Collects emissive triangles of every emissive instance.
+fn collect_emitters(instances: &[Instance]) -> Vec<EmissiveTriangle> {
+    let mut emitters = Vec::new();
+    for (instance_index, instance) in instances.iter().enumerate() {
+        if instance.emission.max() <= 0.0 {
+            continue;
+        }
+
+        let data = instance.data();
+        for triangle in data.triangles.iter() {
+            let a = data.vertices[triangle[0] as usize].world_position;
+            let b = data.vertices[triangle[1] as usize].world_position;
+            let c = data.vertices[triangle[2] as usize].world_position;
+
+            let normal = match (b - a).cross(&(c - a)).try_normalize(f32::EPSILON) {
+                Some(normal) => normal,
+                // Degenerated triangle.
+                None => continue,
+            };
+
+            emitters.push(EmissiveTriangle {
+                instance_index,
+                center: (a + b + c).scale(1.0 / 3.0),
+                normal,
+                area: math::triangle_area(a, b, c),
+                radiance: instance.emission,
+            });
+        }
+    }
+    emitters
+}
+
 /// Computes total area of triangles in surface data and returns size of square
 /// in which triangles can fit.
-fn estimate_size(data: &InstanceData, texels_per_unit: u32) -> u32 {
+fn estimate_size(data: &InstanceData, texels_per_unit: f32) -> u32 {
     let mut area = 0.0;
     for triangle in data.triangles.iter() {
         let a = data.vertices[triangle[0] as usize].world_position;
@@ -631,7 +753,8 @@ fn estimate_size(data: &InstanceData, texels_per_unit: u32) -> u32 {
         let c = data.vertices[triangle[2] as usize].world_position;
         area += math::triangle_area(a, b, c);
     }
-    area.sqrt().ceil() as u32 * texels_per_unit
+    // Keep at least one texel, otherwise zero density would produce an empty texture.
+    ((area.sqrt().ceil() * texels_per_unit).ceil() as u32).max(1)
 }
 
 /// Calculates distance attenuation for a point using given distance to the point and
@@ -746,6 +869,36 @@ impl Grid {
     }
 }
 
+/// Checks whether there is any geometry between the given points.
+fn is_occluded(from: Vector3<f32>, to: Vector3<f32>, instances: &[Instance]) -> bool {
+    let mut query_buffer = ArrayVec::<Handle<OctreeNode>, 64>::new();
+    let shadow_bias = 0.01;
+    let ray = Ray::from_two_points(from, to);
+    for instance in instances {
+        let data = instance.data();
+        data.octree.ray_query_static(&ray, &mut query_buffer);
+        for &node in query_buffer.iter() {
+            match data.octree.node(node) {
+                OctreeNode::Leaf { indices, .. } => {
+                    for &triangle_index in indices {
+                        let triangle = &data.triangles[triangle_index as usize];
+                        let va = data.vertices[triangle[0] as usize].world_position;
+                        let vb = data.vertices[triangle[1] as usize].world_position;
+                        let vc = data.vertices[triangle[2] as usize].world_position;
+                        if let Some(pt) = ray.triangle_intersection_point(&[va, vb, vc]) {
+                            if ray.origin.metric_distance(&pt) + shadow_bias < ray.dir.norm() {
+                                return true;
+                            }
+                        }
+                    }
+                }
+                OctreeNode::Branch { .. } => unreachable!(),
+            }
+        }
+    }
+    false
+}
+
 /// https://en.wikipedia.org/wiki/Lambert%27s_cosine_law
 fn lambertian(light_vec: Vector3<f32>, normal: Vector3<f32>) -> f32 {
     normal.dot(&light_vec).max(0.0)
@@ -764,15 +917,20 @@ fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
 /// This method is has linear complexity - the more complex mesh you pass, the more
 /// time it will take. Required time increases drastically if you enable shadows and
 /// global illumination (TODO), because in this case your data will be raytraced.
+/// Emissive triangles are raytraced too, so highly tessellated emissive surfaces will
+/// slow down generation significantly.
 fn generate_lightmap(
-    instance: &Instance,
-    other_instances: &[Instance],
+    instance_index: usize,
+    instances: &[Instance],
     lights: &[LightDefinition],
+    emitters: &[EmissiveTriangle],
     texels_per_unit: u32,
 ) -> Texture {
+    let instance = &instances[instance_index];
+
     // We have to re-generate new set of world-space vertices because UV generator
     // may add new vertices on seams.
-    let atlas_size = estimate_size(instance.data(), texels_per_unit);
+    let atlas_size = estimate_size(instance.data(), texels_per_unit as f32 * instance.density);
     let scale = 1.0 / atlas_size as f32;
     let grid = Grid::new(instance.data(), (atlas_size / 32).max(4) as usize);
 
@@ -821,48 +979,43 @@ fn generate_lightmap(
                         }
                     };
                     // Shadows
-                    if attenuation >= 0.01 {
-                        let mut query_buffer = ArrayVec::<Handle<OctreeNode>, 64>::new();
-                        let shadow_bias = 0.01;
-                        let ray = Ray::from_two_points(light_position, world_position);
-                        'outer_loop: for other_instance in other_instances {
-                            other_instance
-                                .data()
-                                .octree
-                                .ray_query_static(&ray, &mut query_buffer);
-                            for &node in query_buffer.iter() {
-                                match other_instance.data().octree.node(node) {
-                                    OctreeNode::Leaf { indices, .. } => {
-                                        let other_data = other_instance.data();
-                                        for &triangle_index in indices {
-                                            let triangle =
-                                                &other_data.triangles[triangle_index as usize];
-                                            let va = other_data.vertices[triangle[0] as usize]
-                                                .world_position;
-                                            let vb = other_data.vertices[triangle[1] as usize]
-                                                .world_position;
-                                            let vc = other_data.vertices[triangle[2] as usize]
-                                                .world_position;
-                                            if let Some(pt) =
-                                                ray.triangle_intersection_point(&[va, vb, vc])
-                                            {
-                                                if ray.origin.metric_distance(&pt) + shadow_bias
-                                                    < ray.dir.norm()
-                                                {
-                                                    attenuation = 0.0;
-                                                    break 'outer_loop;
-                                                }
-                                            }
-                                        }
-                                    }
-                                    OctreeNode::Branch { .. } => unreachable!(),
-                                }
-                            }
-                        }
+                    if attenuation >= 0.01 && is_occluded(light_position, world_position, instances)
+                    {
+                        attenuation = 0.0;
                     }
                     pixel_color += light_color.scale(attenuation);
                 }
 
+                // Emissive surfaces act as area lights. Surface does not light itself, its own
+                // emission is added by the shader.
+                for emitter in emitters {
+                    if emitter.instance_index == instance_index {
+                        continue;
+                    }
+
+                    let d = emitter.center - world_position;
+                    let sqr_distance = d.norm_squared();
+                    let light_vec = match d.try_normalize(f32::EPSILON) {
+                        Some(light_vec) => light_vec,
+                        None => continue,
+                    };
+                    let emitter_cos = -emitter.normal.dot(&light_vec);
+                    if emitter_cos <= 0.0 {
+                        continue;
+                    }
+                    // Area is added to the denominator to prevent singularity near the emitter.
+                    let attenuation =
+                        lambertian(light_vec, world_normal) * emitter_cos * emitter.area
+                            / (std::f32::consts::PI * sqr_distance + emitter.area);
+                    if attenuation * emitter.radiance.max() >= 0.001 {
+                        // Start slightly above the emitter, so the ray won't hit the emitter itself.
+                        let origin = emitter.center + emitter.normal.scale(0.01);
+                        if !is_occluded(origin, world_position, instances) {
+                            pixel_color += emitter.radiance.scale(attenuation);
+                        }
+                    }
+                }
+
                 *pixel = Vector4::new(
                     (pixel_color.x.clamp(0.0, 1.0) * 255.0) as u8,
                     (pixel_color.y.clamp(0.0, 1.0) * 255.0) as u8,
@@ -974,7 +1127,12 @@ fn generate_lightmap(
 #[cfg(test)]
 mod test {
     use crate::{
-        core::algebra::{Matrix4, Vector3},
+        core::{
+            algebra::{Matrix4, UnitQuaternion, Vector3},
+            sstorage::ImmutableString,
+        },
+        material::{Material, PropertyValue, SharedMaterial},
+        resource::texture::{Texture, TextureKind, TexturePixelKind, TextureResource},
         scene::{
             base::BaseBuilder,
             light::{point::PointLightBuilder, BaseLightBuilder},
@@ -986,9 +1144,32 @@ mod test {
             transform::TransformBuilder,
             Scene,
         },
-        utils::lightmap::{Lightmap, LightmapInputData},
+        utils::{
+            lightmap::{Lightmap, LightmapInputData},
+            uvgen,
+        },
     };
 
+    #[test]
+    fn test_generate_uvs_if_missing() {
+        let mut data = SurfaceData::make_cube(Matrix4::identity());
+        assert!(!uvgen::has_lightmap_uvs(&data));
+
+        let patch = uvgen::generate_uvs_if_missing(&mut data, 0.005).unwrap();
+        assert!(uvgen::has_lightmap_uvs(&data));
+        assert_eq!(
+            patch.second_tex_coords.len(),
+            data.vertex_buffer.vertex_count() as usize
+        );
+
+        // Existing coordinates must be kept.
+        let vertex_count = data.vertex_buffer.vertex_count();
+        let second_patch = uvgen::generate_uvs_if_missing(&mut data, 0.005).unwrap();
+        assert_eq!(data.vertex_buffer.vertex_count(), vertex_count);
+        assert_eq!(second_patch.second_tex_coords, patch.second_tex_coords);
+        assert!(second_patch.additional_vertices.is_empty());
+    }
+
     #[test]
     fn test_generate_lightmap() {
         let mut scene = Scene::new();
@@ -1037,4 +1218,74 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_emissive_surface_lights_surroundings() {
+        let mut scene = Scene::new();
+
+        let floor = MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_quad(
+                    &(UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 90.0f32.to_radians())
+                        .to_homogeneous()
+                        * Matrix4::new_scaling(4.0)),
+                ),
+            ))
+            .build()])
+            .build(&mut scene.graph);
+
+        let white = Texture::from_bytes(
+            TextureKind::Rectangle {
+                width: 1,
+                height: 1,
+            },
+            TexturePixelKind::RGBA8,
+            vec![255, 255, 255, 255],
+            false,
+        )
+        .unwrap();
+
+        let mut material = Material::standard();
+        material
+            .set_property(
+                &ImmutableString::new("emissionTexture"),
+                PropertyValue::Sampler {
+                    value: Some(TextureResource::new_ok(white)),
+                    fallback: Default::default(),
+                },
+            )
+            .unwrap();
+        material
+            .set_property(
+                &ImmutableString::new("emissionIntensity"),
+                PropertyValue::Float(4.0),
+            )
+            .unwrap();
+
+        MeshBuilder::new(BaseBuilder::new())
+            .with_surfaces(vec![SurfaceBuilder::new(SurfaceSharedData::new(
+                SurfaceData::make_cube(
+                    Matrix4::new_translation(&Vector3::new(0.0, 1.0, 0.0))
+                        * Matrix4::new_scaling(0.5),
+                ),
+            ))
+            .with_material(SharedMaterial::new(material))
+            .build()])
+            .build(&mut scene.graph);
+
+        // There are no lights in the scene, so the floor could be lit only by the emissive cube.
+        let data = LightmapInputData::from_scene(
+            &scene,
+            |_, _| true,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap();
+
+        let lightmap =
+            Lightmap::new(data, 16, 0.005, Default::default(), Default::default()).unwrap();
+
+        let texture = lightmap.map[&floor][0].texture.as_ref().unwrap().data_ref();
+        assert!(texture.data().iter().any(|&v| v > 0));
+    }
 }
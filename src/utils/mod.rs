@@ -5,9 +5,11 @@
 pub mod astar;
 pub mod behavior;
 pub mod component;
+pub mod debug_camera;
 pub mod lightmap;
 pub mod navmesh;
 pub mod raw_mesh;
+pub mod thumbnail;
 pub mod uvgen;
 
 use crate::{
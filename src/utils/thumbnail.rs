@@ -0,0 +1,277 @@
+//! Thumbnail (preview) generation for scenes and prefabs. It is useful for asset browsers and for
+//! any tooling, that needs to show an image of a model without adding it to the game scene.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use fyrox::{
+//!     asset::manager::ResourceManager,
+//!     renderer::Renderer,
+//!     resource::{model::Model, texture::TextureResource},
+//!     utils::thumbnail::{render_thumbnail, ThumbnailSettings},
+//! };
+//!
+//! async fn make_thumbnail(
+//!     resource_manager: &ResourceManager,
+//!     renderer: &mut Renderer,
+//! ) -> Option<TextureResource> {
+//!     let model = resource_manager
+//!         .request::<Model, _>("data/models/barrel.rgs")
+//!         .await
+//!         .ok()?;
+//!     render_thumbnail(renderer, &model, &ThumbnailSettings::default()).ok()
+//! }
+//! ```
+
+use crate::{
+    core::{
+        algebra::{UnitQuaternion, Vector2, Vector3},
+        color::Color,
+        math::aabb::AxisAlignedBoundingBox,
+    },
+    renderer::{framework::error::FrameworkError, Renderer},
+    resource::{
+        model::{ModelResource, ModelResourceExtension},
+        texture::{TextureKind, TexturePixelKind, TextureResource, TextureResourceExtension},
+    },
+    scene::{
+        base::BaseBuilder,
+        camera::{Camera, CameraBuilder, Projection},
+        graph::GraphUpdateSwitches,
+        light::{directional::DirectionalLightBuilder, BaseLightBuilder},
+        transform::TransformBuilder,
+        Scene,
+    },
+};
+
+/// A set of parameters, that defines how a thumbnail will look like.
+#[derive(Clone, Debug)]
+pub struct ThumbnailSettings {
+    /// Width of the thumbnail in pixels.
+    pub width: u32,
+    /// Height of the thumbnail in pixels.
+    pub height: u32,
+    /// Direction in which the camera looks at the model.
+    pub view_direction: Vector3<f32>,
+    /// Ambient lighting color of the temporary scene.
+    pub ambient_lighting_color: Color,
+    /// Adds a directional light, that shines along the view direction, when set. Could be turned
+    /// off for models that have their own lights.
+    pub add_light: bool,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        Self {
+            width: 256,
+            height: 256,
+            view_direction: Vector3::new(-1.0, -1.0, -1.0),
+            ambient_lighting_color: Color::opaque(100, 100, 100),
+            add_light: true,
+        }
+    }
+}
+
+/// Instantiates the given scene or prefab in a temporary scene, frames its combined bounding box
+/// with a temporary camera and renders one frame offscreen. Cameras of the model are disabled, so
+/// only the temporary camera is used. The model resource must be fully loaded. Returns a procedural
+/// RGBA8 texture with the thumbnail, it could be shown in the UI or saved to a file.
+pub fn render_thumbnail(
+    renderer: &mut Renderer,
+    model: &ModelResource,
+    settings: &ThumbnailSettings,
+) -> Result<TextureResource, FrameworkError> {
+    let width = settings.width.max(1);
+    let height = settings.height.max(1);
+    let frame_size = Vector2::new(width as f32, height as f32);
+    let switches = GraphUpdateSwitches {
+        physics: false,
+        physics2d: false,
+        ..Default::default()
+    };
+
+    let mut scene = Scene::new();
+    scene.render_target = Some(TextureResource::new_render_target(width, height));
+    scene.ambient_lighting_color = settings.ambient_lighting_color;
+
+    let root = model.instantiate(&mut scene);
+
+    for node in scene.graph.linear_iter_mut() {
+        if let Some(camera) = node.cast_mut::<Camera>() {
+            camera.set_enabled(false);
+        }
+    }
+
+    scene.graph.update_hierarchical_data();
+    let aabb = scene
+        .graph
+        .aabb_of_descendants(root)
+        .filter(|aabb| !aabb.is_invalid_or_degenerate())
+        .unwrap_or_else(|| AxisAlignedBoundingBox::from_radius(1.0));
+
+    let direction = view_direction(settings.view_direction);
+    let rotation = view_rotation(direction);
+
+    let camera = CameraBuilder::new(
+        BaseBuilder::new().with_local_transform(
+            TransformBuilder::new()
+                .with_local_rotation(rotation)
+                .build(),
+        ),
+    )
+    .build(&mut scene.graph);
+
+    if settings.add_light {
+        DirectionalLightBuilder::new(BaseLightBuilder::new(
+            BaseBuilder::new().with_local_transform(
+                TransformBuilder::new()
+                    .with_local_rotation(rotation)
+                    .build(),
+            ),
+        ))
+        .build(&mut scene.graph);
+    }
+
+    scene.graph.update_hierarchical_data();
+
+    // The camera uses perspective projection, so only its position has to be changed.
+    let camera = scene.graph[camera].as_camera_mut();
+    if let Projection::Perspective(perspective) = camera.projection_value() {
+        camera.local_transform_mut().set_position(fit_position(
+            &aabb,
+            direction,
+            perspective.fov,
+            frame_size.x / frame_size.y,
+        ));
+    }
+
+    scene.graph.update(frame_size, 0.0, switches);
+
+    let pixels = renderer.render_scene_to_pixels(&scene)?;
+
+    TextureResource::from_bytes(
+        TextureKind::Rectangle { width, height },
+        TexturePixelKind::RGBA8,
+        flip_rows(&pixels, width as usize),
+        false,
+    )
+    .ok_or_else(|| FrameworkError::Custom("Invalid thumbnail data!".to_string()))
+}
+
+fn view_direction(direction: Vector3<f32>) -> Vector3<f32> {
+    direction
+        .try_normalize(f32::EPSILON)
+        .unwrap_or_else(|| -Vector3::z())
+}
+
+fn view_rotation(direction: Vector3<f32>) -> UnitQuaternion<f32> {
+    // Up vector must not be collinear with the view direction.
+    let up = if direction.y.abs() > 0.999 {
+        Vector3::z()
+    } else {
+        Vector3::y()
+    };
+    UnitQuaternion::face_towards(&direction, &up)
+}
+
+// Returns a position of a perspective camera looking along the given (normalized) direction, at
+// which the whole bounding box is visible. The bounding sphere of the box is fitted into the
+// narrowest of the vertical and horizontal fields of view, so the corners of the box are never
+// clipped regardless of the view direction and the aspect ratio.
+fn fit_position(
+    aabb: &AxisAlignedBoundingBox,
+    direction: Vector3<f32>,
+    vertical_fov: f32,
+    aspect_ratio: f32,
+) -> Vector3<f32> {
+    let horizontal_fov = 2.0 * ((vertical_fov * 0.5).tan() * aspect_ratio).atan();
+    let fov = vertical_fov.min(horizontal_fov);
+    let radius = aabb.half_extents().norm();
+    aabb.center() - direction.scale(radius / (fov * 0.5).sin())
+}
+
+// OpenGL stores rows of images from bottom to top, the texture needs them from top to bottom.
+fn flip_rows(pixels: &[u8], width: usize) -> Vec<u8> {
+    pixels
+        .chunks_exact(width * 4)
+        .rev()
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            algebra::{Vector2, Vector3},
+            math::aabb::AxisAlignedBoundingBox,
+        },
+        scene::{
+            base::BaseBuilder,
+            camera::{CameraBuilder, PerspectiveProjection, Projection},
+            graph::Graph,
+            transform::TransformBuilder,
+        },
+        utils::thumbnail::{fit_position, flip_rows, view_direction, view_rotation},
+    };
+
+    #[test]
+    fn test_flip_rows() {
+        // 2x3 image, each pixel is filled with the index of its row.
+        let pixels = [
+            0u8, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2,
+        ];
+        let flipped = flip_rows(&pixels, 2);
+        assert_eq!(&flipped[0..8], &[2; 8]);
+        assert_eq!(&flipped[8..16], &[1; 8]);
+        assert_eq!(&flipped[16..24], &[0; 8]);
+    }
+
+    #[test]
+    fn test_framing() {
+        let aabb = AxisAlignedBoundingBox::from_min_max(
+            Vector3::new(-1.0, 0.0, -3.0),
+            Vector3::new(2.0, 1.0, 1.0),
+        );
+        let fov = PerspectiveProjection::default().fov;
+
+        for (view, frame_size) in [
+            (Vector3::new(-1.0, -1.0, -1.0), Vector2::new(256.0, 256.0)),
+            (Vector3::new(0.0, -1.0, 0.0), Vector2::new(400.0, 100.0)),
+            (Vector3::new(1.0, 0.0, 0.0), Vector2::new(100.0, 400.0)),
+            (Vector3::default(), Vector2::new(256.0, 128.0)),
+        ] {
+            let direction = view_direction(view);
+            assert!((direction.norm() - 1.0).abs() < 1.0e-5);
+
+            let mut graph = Graph::new();
+            let camera = CameraBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_rotation(view_rotation(direction))
+                        .with_local_position(fit_position(
+                            &aabb,
+                            direction,
+                            fov,
+                            frame_size.x / frame_size.y,
+                        ))
+                        .build(),
+                ),
+            )
+            .with_projection(Projection::Perspective(PerspectiveProjection {
+                fov,
+                ..Default::default()
+            }))
+            .build(&mut graph);
+            graph.update(frame_size, 0.0, Default::default());
+
+            let camera = graph[camera].as_camera();
+            assert!(camera.look_vector().normalize().dot(&direction) > 0.999);
+            let frustum = camera.frustum();
+            for corner in aabb.corners() {
+                assert!(frustum.is_contains_point(corner));
+            }
+        }
+    }
+}
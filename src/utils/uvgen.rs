@@ -442,6 +442,66 @@ pub fn generate_uvs(
     Ok(patch)
 }
 
+/// Default spacing between UV islands (in UV units) of generated UV maps.
+pub const DEFAULT_UV_SPACING: f32 = 0.005;
+
+/// Returns `true` if the surface data has usable secondary texture coordinates (the ones used for
+/// lightmaps), `false` - otherwise. Texture coordinates are considered usable if there is at least
+/// one triangle with non-degenerate secondary UVs, so vertex formats that have the attribute but
+/// never fill it (legacy scenes, for example) are treated as having no secondary UVs.
+pub fn has_lightmap_uvs(data: &SurfaceData) -> bool {
+    if !data
+        .vertex_buffer
+        .has_attribute(VertexAttributeUsage::TexCoord1)
+    {
+        return false;
+    }
+
+    let tex_coord = |index: u32| {
+        data.vertex_buffer
+            .get(index as usize)
+            .and_then(|view| view.read_2_f32(VertexAttributeUsage::TexCoord1).ok())
+            .unwrap_or_default()
+    };
+
+    data.geometry_buffer.iter().any(|triangle| {
+        let a = tex_coord(triangle[0]);
+        let b = tex_coord(triangle[1]);
+        let c = tex_coord(triangle[2]);
+        (b - a).perp(&(c - a)).abs() > f32::EPSILON
+    })
+}
+
+/// Creates a patch from current secondary texture coordinates and topology of the surface data,
+/// without generating anything.
+pub fn make_patch(data: &SurfaceData) -> Result<SurfaceDataPatch, VertexFetchError> {
+    Ok(SurfaceDataPatch {
+        data_id: data.content_hash(),
+        triangles: data.geometry_buffer.triangles_ref().to_vec(),
+        second_tex_coords: data
+            .vertex_buffer
+            .iter()
+            .map(|view| view.read_2_f32(VertexAttributeUsage::TexCoord1))
+            .collect::<Result<Vec<_>, _>>()?,
+        additional_vertices: Default::default(),
+    })
+}
+
+/// Generates UV map for given surface data only if it does not have usable secondary texture
+/// coordinates (see [`has_lightmap_uvs`]), otherwise the existing coordinates are kept as is. In both
+/// cases returns a patch with actual secondary texture coordinates of the data. It allows you to use
+/// hand-made lightmap UVs where they're available and automatically unwrap everything else.
+pub fn generate_uvs_if_missing(
+    data: &mut SurfaceData,
+    spacing: f32,
+) -> Result<SurfaceDataPatch, VertexFetchError> {
+    if has_lightmap_uvs(data) {
+        make_patch(data)
+    } else {
+        generate_uvs(data, spacing)
+    }
+}
+
 /// Generates UVs for a specified mesh.
 pub fn generate_uvs_mesh(
     mesh: &Mesh,